@@ -24,7 +24,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Create a dummy TAO instance for demo
     use tao_database::infrastructure::tao_core::tao_core::TaoCore;
-    let query_router = Arc::new(TaoQueryRouter::new());
+    let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
     let association_registry = Arc::new(AssociationRegistry::new());
     let tao_core = Arc::new(TaoCore::new(query_router, association_registry));
     let tao: Arc<dyn TaoOperations> = Arc::new(Tao::minimal(tao_core));
@@ -2,34 +2,54 @@
 // Provides endpoints for creating users, relationships, and visualizing the graph
 
 use axum::{
-    extract::Path,
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
     middleware,
     response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
-use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use tao_database::domains::user::EntUser;
 use tao_database::framework::entity::ent_trait::Entity;
 use tao_database::{
     error::{AppError, AppResult},
+    framework::schema::ent_schema::EntitySchemaDescription,
     infrastructure::{
         association_registry::AssociationRegistry,
-        database::database::{DatabaseInterface, PostgresDatabase},
-        middleware::{viewer_context_middleware, HasTaoOperations, Vc},
-        query_router::{QueryRouterConfig, TaoQueryRouter},
+        cache::access_tracker::{AccessTracker, HotObject},
+        cache::data_source::track_data_source,
+        cache::stale_read::{mark_stale_read, track_stale_reads},
+        database::database::{ConsistencyIssue, DatabaseInterface, PostgresDatabase},
+        database::dsn::redact_dsn,
+        init_logging,
+        middleware::{
+            idempotency_middleware, query_plan_middleware, rate_limit_middleware,
+            read_consistency_middleware, request_deadline_middleware, request_timeout_from_env,
+            stale_read_middleware, viewer_context_middleware, CorsConfig, HasTaoOperations,
+            IdempotencyStore, RateLimitConfig, RateLimiter, RouteRateLimit, Vc,
+        },
+        monitoring::health::{DatabaseProbe, HealthProbe, QueryRouterProbe, WalProbe},
+        monitoring::monitoring::{HealthStatus, MetricsCollector, ServiceStatus},
+        query_router::{QueryRouterConfig, RebalanceMove, ShardReport, TaoQueryRouter},
         shard_topology::{ShardHealth, ShardInfo},
+        storage::write_ahead_log::WalStatus,
+        tao_core::cursor::Cursor,
         tao_core::tao::Tao,
-        tao_core::tao_core::{create_tao_association, current_time_millis, TaoId, TaoOperations},
+        tao_core::tao_core::{
+            create_tao_association, current_time_millis, ListLimits, TaoAssocQuery, TaoId,
+            TaoOperations,
+        },
+        tao_core::tao_decorators::{MaintenanceMode, WalDecorator, WalReplaySummary},
     },
+    schemas::create_schema_registry,
 };
 
 // Import new graph models
@@ -69,17 +89,46 @@ struct RelationshipResponse {
     created_at: i64,
 }
 
+/// Body of a successful response from the user, relationship, graph and seed endpoints.
+/// Their errors are `AppError`s and use the shared error envelope.
 #[derive(Serialize, Deserialize)]
 struct ApiResponse<T> {
     success: bool,
     data: Option<T>,
-    error: Option<String>,
 }
 
 // Application state (empty as Tao is global)
 #[derive(Clone)]
 struct AppState {
     tao: Arc<dyn TaoOperations>,
+    query_router: Arc<TaoQueryRouter>,
+    /// Present when TAO was built with a write-ahead log
+    wal: Option<Arc<WalDecorator>>,
+    /// Freezes writes through `tao` while set
+    maintenance: Arc<MaintenanceMode>,
+    /// Present when TAO's cache samples object reads
+    access_tracker: Option<Arc<AccessTracker>>,
+    /// Registered entity schemas, described once at startup
+    schemas: Arc<Vec<EntitySchemaDescription>>,
+    /// Association types clients may create edges of
+    association_registry: Arc<AssociationRegistry>,
+    /// Page sizes of every list endpoint
+    list_limits: ListLimits,
+    /// Keeps the last health report and each component's error rate
+    metrics: Arc<MetricsCollector>,
+    /// Subsystems checked by `GET /health/detailed`
+    health_probes: Arc<Vec<Arc<dyn HealthProbe>>>,
+}
+
+/// Response header of list endpoints carrying the limit the page was read with
+const APPLIED_LIMIT_HEADER: &str = "x-tao-applied-limit";
+/// Response header naming where an entity was read from: primary, replica or cache
+const DATA_SOURCE_HEADER: &str = "x-data-source";
+
+/// Query parameters of list endpoints without cursors
+#[derive(Debug, Deserialize)]
+struct ListParams {
+    limit: Option<u32>,
 }
 
 impl HasTaoOperations for AppState {
@@ -92,62 +141,64 @@ impl HasTaoOperations for AppState {
 async fn create_user(
     vc: Vc,
     Json(request): Json<CreateUserRequest>,
-) -> impl IntoResponse {
+) -> AppResult<(StatusCode, Json<ApiResponse<UserResponse>>)> {
     info!("Creating user: {}", request.name);
 
     // Use Meta's authentic pattern with clean ViewerContext extractor
-    let result = EntUser::create(vc)
+    let user = EntUser::create(vc)
         .username(request.name.to_lowercase().replace(" ", "_"))
         .email(request.email.clone())
         .full_name(request.name.clone())
         .bio(request.bio.unwrap_or("".to_string()))
         .is_verified(true)
         .savex()
-        .await;
+        .await
+        .inspect_err(|e| warn!("Failed to create user: {}", e))?;
 
-    match result {
-        Ok(user) => {
-            info!(
-                "Created user: {} (ID: {})",
-                user.full_name.as_deref().unwrap_or("Unknown"), // Handle Option<String> for logging
-                user.id
-            );
-            let response = ApiResponse {
-                success: true,
-                data: Some(UserResponse {
-                    id: user.id,
-                    username: user.username,
-                    email: user.email,
-                    full_name: user.full_name,
-                    bio: user.bio,
-                    is_verified: user.is_verified,
-                    location: user.location,
-                }),
-                error: None,
-            };
-            (StatusCode::CREATED, Json(response))
-        }
-        Err(e) => {
-            warn!("Failed to create user: {}", e);
-            let response = ApiResponse::<UserResponse> {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to create user: {}", e)),
-            };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(response))
-        }
-    }
+    info!(
+        "Created user: {} (ID: {})",
+        user.full_name.as_deref().unwrap_or("Unknown"), // Handle Option<String> for logging
+        user.id
+    );
+    let response = ApiResponse {
+        success: true,
+        data: Some(UserResponse {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            full_name: user.full_name,
+            bio: user.bio,
+            is_verified: user.is_verified,
+            location: user.location,
+        }),
+    };
+    Ok((StatusCode::CREATED, Json(response)))
 }
 
 async fn create_relationship(
     vc: Vc,
+    State(state): State<AppState>,
     Json(request): Json<CreateRelationshipRequest>
-) -> impl IntoResponse {
+) -> AppResult<(StatusCode, Json<ApiResponse<RelationshipResponse>>)> {
     info!(
         "Creating relationship: {} -> {} ({})",
         request.from_user_id, request.to_user_id, request.relationship_type
     );
 
+    // A typo would otherwise create a phantom edge type the typed API never reads
+    let registry = &state.association_registry;
+    if !registry.is_known_type(&request.relationship_type).await {
+        warn!(
+            "Rejected unknown relationship type: {}",
+            request.relationship_type
+        );
+        return Err(AppError::BadRequest(format!(
+            "Unknown relationship_type '{}'; valid types: {}",
+            request.relationship_type,
+            registry.association_types().await.join(", ")
+        )));
+    }
+
     let association = create_tao_association(
         request.from_user_id,
         request.relationship_type.clone(),
@@ -157,123 +208,432 @@ async fn create_relationship(
 
     // Use TAO from ViewerContext (Meta's pattern) - no Arc cloning needed!
     let tao = &vc.tao;
-    match tao.assoc_add(association.clone()).await {
-        Ok(_) => {
-            let response = ApiResponse {
-                success: true,
-                data: Some(RelationshipResponse {
-                    id1: request.from_user_id,
-                    id2: request.to_user_id,
-                    relationship_type: request.relationship_type,
-                    created_at: association.time,
-                }),
-                error: None,
-            };
-            (StatusCode::CREATED, Json(response))
-        }
-        Err(e) => {
-            warn!("Failed to create relationship: {}", e);
-            let response = ApiResponse::<RelationshipResponse> {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to create relationship: {}", e)),
-            };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(response))
-        }
-    }
+    tao.assoc_add(association.clone())
+        .await
+        .inspect_err(|e| warn!("Failed to create relationship: {}", e))?;
+    let response = ApiResponse {
+        success: true,
+        data: Some(RelationshipResponse {
+            id1: request.from_user_id,
+            id2: request.to_user_id,
+            relationship_type: request.relationship_type,
+            created_at: association.time,
+        }),
+    };
+    Ok((StatusCode::CREATED, Json(response)))
 }
 
 async fn get_user(
     vc: Vc,
     Path(user_id): Path<TaoId>
-) -> impl IntoResponse {
-    match EntUser::gen_nullable(vc, Some(user_id)).await {
-        Ok(Some(user)) => {
-            let response = ApiResponse {
-                success: true,
-                data: Some(UserResponse {
-                    id: user.id,
-                    username: user.username,
-                    email: user.email,
-                    full_name: user.full_name,
-                    bio: user.bio,
-                    is_verified: user.is_verified,
-                    location: user.location,
-                }),
-                error: None,
-            };
-            (StatusCode::OK, Json(response))
-        }
-        Ok(None) => {
-            let response = ApiResponse::<UserResponse> {
-                success: false,
-                data: None,
-                error: Some("User not found".to_string()),
-            };
-            (StatusCode::NOT_FOUND, Json(response))
-        }
-        Err(e) => {
-            warn!("Failed to get user {}: {}", user_id, e);
-            let response = ApiResponse::<UserResponse> {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to get user: {}", e)),
-            };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(response))
-        }
+) -> AppResult<Json<ApiResponse<UserResponse>>> {
+    let user = EntUser::gen_nullable(vc, Some(user_id))
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+    let response = ApiResponse {
+        success: true,
+        data: Some(UserResponse {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            full_name: user.full_name,
+            bio: user.bio,
+            is_verified: user.is_verified,
+            location: user.location,
+        }),
+    };
+    Ok(Json(response))
+}
+
+async fn get_all_users(
+    vc: Vc,
+    State(state): State<AppState>,
+    Query(params): Query<ListParams>,
+) -> AppResult<(
+    [(&'static str, String); 1],
+    Json<ApiResponse<Vec<UserResponse>>>,
+)> {
+    let limit = state.list_limits.apply(params.limit);
+    let headers = [(APPLIED_LIMIT_HEADER, limit.to_string())];
+    let users = vc
+        .tao
+        .get_all_objects_of_type(EntUser::ENTITY_TYPE.to_string(), Some(limit))
+        .await
+        .and_then(|objects| {
+            objects
+                .iter()
+                .take(limit as usize)
+                .map(EntUser::from_stored_object)
+                .collect::<AppResult<Vec<_>>>()
+        });
+    let user_objs = users.inspect_err(|e| warn!("Failed to get all users: {}", e))?;
+    let mut users = Vec::new();
+    for user in user_objs {
+        users.push(UserResponse {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            full_name: user.full_name,
+            bio: user.bio,
+            is_verified: user.is_verified,
+            location: user.location,
+        });
     }
+
+    let response = ApiResponse {
+        success: true,
+        data: Some(users),
+    };
+    Ok((headers, Json(response)))
 }
 
-async fn get_all_users(vc: Vc) -> impl IntoResponse {
-    match EntUser::gen_all(vc).await {
-        Ok(user_objs) => {
-            let mut users = Vec::new();
-            for user in user_objs {
-                users.push(UserResponse {
-                    id: user.id,
-                    username: user.username,
-                    email: user.email,
-                    full_name: user.full_name,
-                    bio: user.bio,
-                    is_verified: user.is_verified,
-                    location: user.location,
-                });
-            }
+#[derive(Debug, Serialize)]
+struct AssocTypeCount {
+    atype: String,
+    count: u64,
+}
 
-            let response = ApiResponse {
-                success: true,
-                data: Some(users),
-                error: None,
-            };
-            (StatusCode::OK, Json(response))
+#[derive(Debug, Serialize)]
+struct EntityInspection {
+    id: TaoId,
+    otype: String,
+    created_time: i64,
+    updated_time: i64,
+    version: u64,
+    /// Served from expired cache because storage was unavailable
+    stale: bool,
+    /// Association types stored with this entity as id1
+    associations: Vec<AssocTypeCount>,
+}
+
+/// GET /api/entities/{id} - type, timestamps and edge counts of any entity, for admin tooling
+///
+/// `x-data-source` says whether the object came from the primary, a replica or the cache,
+/// so together with `updated_time` and `stale` clients can tell how fresh it is.
+async fn inspect_entity_handler(
+    vc: Vc,
+    Path(id): Path<TaoId>,
+) -> AppResult<(HeaderMap, Json<EntityInspection>)> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    let ((object, stale), source) = track_data_source(track_stale_reads(vc.tao.obj_get(id))).await;
+    if stale {
+        // The inner scope hides it from `stale_read_middleware`
+        mark_stale_read();
+    }
+    let object = object?.ok_or_else(|| AppError::NotFound(format!("Entity {} not found", id)))?;
+    let associations = vc
+        .tao
+        .list_assoc_types(id)
+        .await?
+        .into_iter()
+        .map(|(atype, count)| AssocTypeCount { atype, count })
+        .collect();
+
+    let mut headers = HeaderMap::new();
+    if let Some(source) = source {
+        headers.insert(
+            DATA_SOURCE_HEADER,
+            HeaderValue::from_static(source.as_str()),
+        );
+    }
+    Ok((
+        headers,
+        Json(EntityInspection {
+            id: object.id,
+            otype: object.otype,
+            created_time: object.created_time,
+            updated_time: object.updated_time,
+            version: object.version,
+            stale,
+            associations,
+        }),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct AssocPageParams {
+    limit: Option<u32>,
+    /// `next_cursor` of the previous page
+    cursor: Option<String>,
+    /// Edges to skip instead of a cursor. Unstable: an edge added between two fetches
+    /// shifts the rest of the list, so an edge is returned twice or skipped.
+    offset: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AssocEdge {
+    id2: TaoId,
+    time: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AssocPage {
+    associations: Vec<AssocEdge>,
+    /// Pass back as `cursor` for the next page; absent on the last page
+    next_cursor: Option<String>,
+}
+
+/// GET /api/entities/{id}/assocs/{atype}?limit=50&cursor=...
+///
+/// Newest edges first. Pages follow `next_cursor`, which resumes after the last
+/// `(time, id2)` returned, so edges added between fetches never repeat or skip one.
+async fn assoc_page_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+    Path((id, atype)): Path<(TaoId, String)>,
+    Query(params): Query<AssocPageParams>,
+) -> AppResult<([(&'static str, String); 1], Json<AssocPage>)> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    if params.cursor.is_some() && params.offset.is_some() {
+        return Err(AppError::Validation(
+            "Pass either cursor or offset, not both".to_string(),
+        ));
+    }
+    let after = params
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            Cursor::decode(cursor)?
+                .keyset()
+                .ok_or_else(|| AppError::Validation("Invalid pagination cursor".to_string()))
+        })
+        .transpose()?;
+    let limit = state.list_limits.apply(params.limit);
+
+    let page = vc
+        .tao
+        .assoc_get_page(TaoAssocQuery {
+            id1: id,
+            atype,
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: Some(limit),
+            offset: params.offset,
+            after,
+            include_total: false,
+        })
+        .await?;
+
+    Ok((
+        [(APPLIED_LIMIT_HEADER, limit.to_string())],
+        Json(AssocPage {
+            associations: page
+                .associations
+                .iter()
+                .map(|assoc| AssocEdge {
+                    id2: assoc.id2,
+                    time: assoc.time,
+                })
+                .collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.encode()),
+        }),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct EntityFullParams {
+    /// Comma-separated edge names of the entity's type, e.g. `friends,posts,groups`
+    edges: Option<String>,
+    /// Edges per list
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EdgeList {
+    /// Every edge of this type, not only the ones on the page
+    total: u64,
+    #[serde(flatten)]
+    page: AssocPage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntityFull {
+    id: TaoId,
+    otype: String,
+    created_time: i64,
+    updated_time: i64,
+    version: u64,
+    /// Keyed by edge name, one entry per requested edge
+    edges: std::collections::BTreeMap<String, EdgeList>,
+}
+
+/// GET /api/v1/tao/viewer/entity/{id}/full?edges=friends,posts,groups&limit=10
+///
+/// An entity with the first page of each requested edge list and its total, e.g. for a
+/// profile page. The object, the edge lists and the counts are read concurrently, the
+/// lists in one multi-type query and the counts in one batch. Anonymous viewers get the
+/// totals but not who is on the other end of the edges.
+async fn entity_full_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+    Path(id): Path<TaoId>,
+    Query(params): Query<EntityFullParams>,
+) -> AppResult<([(&'static str, String); 1], Json<EntityFull>)> {
+    let mut atypes: Vec<String> = Vec::new();
+    for edge in params.edges.as_deref().unwrap_or("").split(',') {
+        let edge = edge.trim();
+        if !edge.is_empty() && !atypes.iter().any(|atype| atype == edge) {
+            atypes.push(edge.to_string());
         }
-        Err(e) => {
-            warn!("Failed to get all users: {}", e);
-            let response = ApiResponse::<Vec<UserResponse>> {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to get users: {}", e)),
+    }
+    let limit = state.list_limits.apply(params.limit);
+
+    let (object, mut lists, counts) = futures::try_join!(
+        vc.tao.obj_get(id),
+        vc.tao.assoc_get_multi_type(id, atypes.clone(), Some(limit)),
+        vc.tao
+            .assoc_count_many(atypes.iter().map(|atype| (id, atype.clone())).collect()),
+    )?;
+    let object = object.ok_or_else(|| AppError::NotFound(format!("Entity {} not found", id)))?;
+
+    // Only the entity's own edges, so the query can't be used to read arbitrary atypes
+    let schema = state
+        .schemas
+        .iter()
+        .find(|schema| schema.entity_type == object.otype);
+    if let Some(unknown) = atypes.iter().find(|atype| {
+        !schema.is_some_and(|schema| schema.edges.iter().any(|edge| &edge.name == *atype))
+    }) {
+        return Err(AppError::Validation(format!(
+            "{} has no edge named {}",
+            object.otype, unknown
+        )));
+    }
+
+    let show_edges = vc.is_authenticated();
+    let edges = atypes
+        .into_iter()
+        .map(|atype| {
+            let assocs = lists.remove(&atype).unwrap_or_default();
+            let total = counts.get(&(id, atype.clone())).copied().unwrap_or(0);
+            let page = if show_edges {
+                let last = assocs.last();
+                AssocPage {
+                    next_cursor: Cursor::next_page(
+                        None,
+                        Some(limit),
+                        assocs.len(),
+                        last.map(|assoc| assoc.id2),
+                        last.map(|assoc| assoc.time),
+                    )
+                    .map(|cursor| cursor.encode()),
+                    associations: assocs
+                        .iter()
+                        .map(|assoc| AssocEdge {
+                            id2: assoc.id2,
+                            time: assoc.time,
+                        })
+                        .collect(),
+                }
+            } else {
+                AssocPage {
+                    associations: Vec::new(),
+                    next_cursor: None,
+                }
             };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(response))
+            (atype, EdgeList { total, page })
+        })
+        .collect();
+
+    Ok((
+        [(APPLIED_LIMIT_HEADER, limit.to_string())],
+        Json(EntityFull {
+            id: object.id,
+            otype: object.otype,
+            created_time: object.created_time,
+            updated_time: object.updated_time,
+            version: object.version,
+            edges,
+        }),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteEntityParams {
+    /// Expected object type, e.g. `ent_post`
+    #[serde(rename = "type")]
+    otype: Option<String>,
+}
+
+/// DELETE /api/entities/{id}?type=ent_post
+///
+/// Passing `type` is the recommended form: the object is only deleted when it has that
+/// type, so a stale or mistyped id cannot remove an unrelated entity. Without it the id
+/// alone decides. Either way a missing object (or a type mismatch) is a 404.
+///
+/// Admins can delete any entity; anyone else only one they own through an owner edge.
+async fn delete_entity_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+    Path(id): Path<TaoId>,
+    Query(params): Query<DeleteEntityParams>,
+) -> AppResult<StatusCode> {
+    if !vc.is_authenticated() {
+        return Err(AppError::Unauthorized(
+            "Authentication required to delete entities".to_string(),
+        ));
+    }
+    if !vc.is_admin() && !viewer_owns_entity(&vc, &state, id).await? {
+        return Err(AppError::Forbidden(
+            "Only the entity's owner or an admin can delete it".to_string(),
+        ));
+    }
+
+    let deleted = match &params.otype {
+        Some(otype) => vc.tao.obj_delete_by_type(id, otype.clone()).await?,
+        None => vc.tao.obj_delete(id).await?,
+    };
+    if !deleted {
+        return Err(AppError::NotFound(match params.otype {
+            Some(otype) => format!("No {} with id {}", otype, id),
+            None => format!("Entity {} not found", id),
+        }));
+    }
+
+    info!("Deleted entity {} (type check: {:?})", id, params.otype);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Whether one of the owner edges declared by `id`'s schema points at the viewer.
+/// A missing entity is a 404 rather than a 403.
+async fn viewer_owns_entity(vc: &Vc, state: &AppState, id: TaoId) -> AppResult<bool> {
+    let object = vc
+        .tao
+        .obj_get(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Entity {} not found", id)))?;
+    let Some(user_id) = vc.user_id else {
+        return Ok(false);
+    };
+    let owner_edges: Vec<String> = state
+        .schemas
+        .iter()
+        .filter(|schema| schema.entity_type == object.otype)
+        .flat_map(|schema| &schema.edges)
+        .filter(|edge| edge.owner)
+        .map(|edge| edge.name.clone())
+        .collect();
+    for atype in owner_edges {
+        if vc.tao.assoc_exists(id, atype, user_id).await? {
+            return Ok(true);
         }
     }
+    Ok(false)
 }
 
-async fn get_graph_data(vc: Vc) -> impl IntoResponse {
+async fn get_graph_data(vc: Vc) -> AppResult<Json<ApiResponse<GraphData>>> {
     info!("Fetching graph data.");
 
-    let users = match EntUser::gen_all(vc).await {
-        Ok(users) => users,
-        Err(e) => {
-            warn!("Failed to get all users for graph data: {}", e);
-            let response = ApiResponse::<GraphData> {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to get graph data: {}", e)),
-            };
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(response));
-        }
-    };
+    let users = EntUser::gen_all(vc)
+        .await
+        .inspect_err(|e| warn!("Failed to get all users for graph data: {}", e))?;
 
     let mut graph_nodes = Vec::with_capacity(users.len());
     let mut graph_edges = Vec::new();
@@ -353,9 +713,8 @@ async fn get_graph_data(vc: Vc) -> impl IntoResponse {
             nodes: graph_nodes,
             edges: graph_edges,
         }),
-        error: None,
     };
-    (StatusCode::OK, Json(response))
+    Ok(Json(response))
 }
 
 async fn health_check() -> impl IntoResponse {
@@ -366,7 +725,184 @@ async fn health_check() -> impl IntoResponse {
     }))
 }
 
-async fn seed_data_handler(vc: Vc) -> impl IntoResponse {
+/// GET /health/detailed: status, probe time and error rate of every subsystem.
+/// Answers 503 when a critical component (the databases or the query router) is unhealthy.
+async fn detailed_health_handler(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<HealthStatus>) {
+    let health = state
+        .metrics
+        .perform_health_check(&state.health_probes)
+        .await;
+    let status = if health.overall_status == ServiceStatus::Unhealthy {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(health))
+}
+
+/// GET /api/v1/tao/schema: every entity type with its fields and edges
+async fn schema_handler(State(state): State<AppState>) -> Json<Vec<EntitySchemaDescription>> {
+    Json(state.schemas.as_ref().clone())
+}
+
+/// GET /api/v1/tao/admin/shards: every shard with its host, region, replicas and pool
+async fn shard_report_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<ShardReport>>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    Ok(Json(state.query_router.shard_report().await))
+}
+
+/// GET /api/v1/tao/admin/shards/rebalance: suggested moves from hot to cold shards.
+/// Only a report for operators; nothing is migrated.
+async fn rebalance_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<RebalanceMove>>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    Ok(Json(state.query_router.rebalance_recommendations().await?))
+}
+
+/// WAL admin endpoints need an admin viewer and a TAO built with a WAL
+fn admin_wal(vc: &Vc, state: &AppState) -> AppResult<Arc<WalDecorator>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    state.wal.clone().ok_or_else(|| {
+        AppError::ServiceUnavailable("Write-ahead log is not enabled on this server".to_string())
+    })
+}
+
+async fn wal_replay_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+) -> AppResult<Json<WalReplaySummary>> {
+    let wal = admin_wal(&vc, &state)?;
+    let summary = wal.process_pending_transactions().await?;
+    info!("WAL replay triggered by admin: {:?}", summary);
+    Ok(Json(summary))
+}
+
+async fn wal_status_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+) -> AppResult<Json<WalStatus>> {
+    let wal = admin_wal(&vc, &state)?;
+    Ok(Json(wal.status().await))
+}
+
+/// Hot objects reported when the request does not ask for a number
+const DEFAULT_HOT_OBJECTS: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct HotObjectsParams {
+    limit: Option<usize>,
+}
+
+/// GET /api/v1/tao/admin/cache/hot-objects?limit=20: the most read objects among sampled
+/// reads, for sizing the cache and choosing TTLs
+async fn hot_objects_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+    Query(params): Query<HotObjectsParams>,
+) -> AppResult<Json<Vec<HotObject>>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    let tracker = state.access_tracker.ok_or_else(|| {
+        AppError::ServiceUnavailable("Access tracking is not enabled on this server".to_string())
+    })?;
+    let limit = params.limit.unwrap_or(DEFAULT_HOT_OBJECTS);
+    Ok(Json(tracker.hot_objects(limit)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct MaintenanceStatus {
+    enabled: bool,
+}
+
+/// GET /api/v1/tao/admin/maintenance
+async fn maintenance_status_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+) -> AppResult<Json<MaintenanceStatus>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    Ok(Json(MaintenanceStatus {
+        enabled: state.maintenance.is_enabled(),
+    }))
+}
+
+/// PUT /api/v1/tao/admin/maintenance with `{"enabled": true}`
+///
+/// While enabled every TAO write is rejected with 503 and reads keep working.
+async fn set_maintenance_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+    Json(request): Json<MaintenanceStatus>,
+) -> AppResult<Json<MaintenanceStatus>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+    state.maintenance.set_enabled(request.enabled);
+    warn!("Maintenance mode set to {} by admin", request.enabled);
+    Ok(Json(request))
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsistencyParams {
+    /// Recount mismatched association counts instead of only reporting them
+    #[serde(default)]
+    repair: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ShardConsistencyReport {
+    shard_id: u16,
+    issues: Vec<ConsistencyIssue>,
+}
+
+/// POST /api/v1/tao/admin/consistency?repair=true
+///
+/// Audits every shard's association counts and orphaned edges. This scans whole tables,
+/// so run it off-peak.
+async fn consistency_audit_handler(
+    vc: Vc,
+    State(state): State<AppState>,
+    Query(params): Query<ConsistencyParams>,
+) -> AppResult<Json<Vec<ShardConsistencyReport>>> {
+    if !vc.is_admin() {
+        return Err(AppError::Forbidden("Admin permission required".to_string()));
+    }
+
+    let mut shards = state.query_router.get_all_shards().await;
+    shards.sort_unstable();
+    let mut reports = Vec::with_capacity(shards.len());
+    for shard_id in shards {
+        let database = state.query_router.get_database_for_shard(shard_id).await?;
+        let issues = database.audit_consistency(params.repair).await?;
+        if !issues.is_empty() {
+            warn!(
+                "Consistency audit found {} issues on shard {} (repair: {})",
+                issues.len(),
+                shard_id,
+                params.repair
+            );
+        }
+        reports.push(ShardConsistencyReport { shard_id, issues });
+    }
+    Ok(Json(reports))
+}
+
+async fn seed_data_handler(vc: Vc) -> AppResult<Json<ApiResponse<String>>> {
     info!("Seeding sample data...");
 
     // Create sample users using EntUserBuilder
@@ -431,20 +967,11 @@ async fn seed_data_handler(vc: Vc) -> impl IntoResponse {
             .bio(bio.unwrap_or("").to_string())
             .is_verified(is_verified);
 
-        match user_builder.savex().await {
-            Ok(user) => {
-                users.push(user);
-            }
-            Err(e) => {
-                println!("Failed to create EntUser {}: {}", name, e);
-                let response = ApiResponse::<String> {
-                    success: false,
-                    data: None,
-                    error: Some(format!("Failed to create user {}: {}", name, e)),
-                };
-                return (StatusCode::INTERNAL_SERVER_ERROR, Json(response));
-            }
-        }
+        let user = user_builder
+            .savex()
+            .await
+            .inspect_err(|e| println!("Failed to create EntUser {}: {}", name, e))?;
+        users.push(user);
     }
 
     // Create sample relationships using Ent-specific methods
@@ -498,13 +1025,58 @@ async fn seed_data_handler(vc: Vc) -> impl IntoResponse {
             "Successfully seeded {} users with relationships",
             users.len()
         )),
-        error: None,
     };
-    (StatusCode::OK, Json(response))
+    Ok(Json(response))
+}
+
+/// How long a create endpoint remembers an `Idempotency-Key`
+const IDEMPOTENCY_KEY_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Rate-limit tokens spent by a read, a create, and a bulk or full-scan call
+const READ_COST: u32 = 1;
+const WRITE_COST: u32 = 5;
+const BULK_COST: u32 = 50;
+
+fn rate_limit(limiter: &Arc<RateLimiter>, cost: u32) -> RouteRateLimit {
+    RouteRateLimit::new(limiter.clone(), cost)
+}
+
+/// Create endpoints, which replay the original response to a retried `Idempotency-Key`.
+/// A replayed retry is rate limited like the original call.
+fn create_routes(idempotency: IdempotencyStore, limiter: &Arc<RateLimiter>) -> Router<AppState> {
+    Router::new()
+        .route("/api/users", post(create_user))
+        .route("/api/relationships", post(create_relationship))
+        .route_layer(middleware::from_fn_with_state(
+            idempotency,
+            idempotency_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            rate_limit(limiter, WRITE_COST),
+            rate_limit_middleware,
+        ))
+}
+
+/// Endpoints that read or write the whole graph
+fn bulk_routes(limiter: &Arc<RateLimiter>) -> Router<AppState> {
+    Router::new()
+        .route("/api/graph", get(get_graph_data))
+        .route("/api/seed", post(seed_data_handler))
+        .route("/api/v1/tao/admin/wal/replay", post(wal_replay_handler))
+        .route("/api/v1/tao/admin/shards/rebalance", get(rebalance_handler))
+        .route(
+            "/api/v1/tao/admin/consistency",
+            post(consistency_audit_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            rate_limit(limiter, BULK_COST),
+            rate_limit_middleware,
+        ))
 }
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
+    init_logging()?;
     info!("🚀 Starting TAO Web Server...");
 
     // Initialize databases for sharding
@@ -517,19 +1089,25 @@ async fn main() -> AppResult<()> {
     let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
 
     for (i, url) in shard_urls.iter().enumerate() {
-        info!("Initializing shard {} at {}", i + 1, url);
-        let pool = PgPoolOptions::new()
-            .max_connections(10) // Example value, adjust as needed
-            .connect(url)
+        info!("Initializing shard {} at {}", i + 1, redact_dsn(url));
+        let pool_options = PgPoolOptions::new().max_connections(10); // Example value, adjust as needed
+        let database = async {
+            let connect_options: PgConnectOptions = url.parse()?;
+            PostgresDatabase::connect(
+                pool_options,
+                connect_options,
+                query_router.statement_timeouts(),
+            )
             .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!(
-                    "Failed to connect to database for shard {}: {}",
-                    i + 1,
-                    e
-                ))
-            })?;
-        let database = PostgresDatabase::new(pool);
+        }
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to connect to database for shard {}: {}",
+                i + 1,
+                e
+            ))
+        })?;
         database.initialize().await?; // Initialize tables for this specific shard
         let db_interface: Arc<dyn DatabaseInterface> = Arc::new(database);
 
@@ -548,7 +1126,10 @@ async fn main() -> AppResult<()> {
     println!("✅ All shards configured");
 
     // Create TAO with WAL
-    let association_registry = Arc::new(AssociationRegistry::new());
+    let schema_registry = create_schema_registry();
+    let association_registry = Arc::new(AssociationRegistry::from_schema_registry(
+        &schema_registry,
+    )?);
 
     // Setup WAL
     // let wal_config = WalConfig::default();
@@ -559,38 +1140,104 @@ async fn main() -> AppResult<()> {
     // let metrics = initialize_metrics_default().await?;
 
     // Create TaoCore instance
+    let list_limits = ListLimits::from_env()?;
     let tao_core = Arc::new(
         tao_database::infrastructure::tao_core::tao_core::TaoCore::new(
             query_router.clone(),
             association_registry.clone(),
-        ),
+        )
+        .with_object_history(schema_registry.history_otypes())
+        .with_list_limits(list_limits),
     );
 
     // Initialize TAO with all components
-    let tao = Arc::new(Tao::minimal(tao_core));
+    let maintenance = Arc::new(MaintenanceMode::new());
+    let tao = Arc::new(
+        Tao::minimal(tao_core)
+            .with_maintenance_mode(maintenance.clone())
+            .with_deadline(),
+    );
     println!("✅ TAO initialized with production features");
 
+    // The minimal TAO has no cache layer to probe
+    let wal = tao.wal_decorator();
+    let mut health_probes: Vec<Arc<dyn HealthProbe>> = vec![
+        Arc::new(DatabaseProbe::new(query_router.clone())),
+        Arc::new(QueryRouterProbe::new(query_router.clone())),
+    ];
+    if let Some(wal) = &wal {
+        health_probes.push(Arc::new(WalProbe::new(wal.wal().clone())));
+    }
+
     // Application state - inject TAO instead of using global state
     let app_state = AppState { 
-        tao: tao as Arc<dyn TaoOperations> 
+        wal,
+        tao: tao as Arc<dyn TaoOperations>,
+        query_router: query_router.clone(),
+        maintenance,
+        // The minimal TAO has no cache layer to sample reads in
+        access_tracker: None,
+        schemas: Arc::new(schema_registry.describe()),
+        association_registry,
+        list_limits,
+        metrics: Arc::new(MetricsCollector::new()),
+        health_probes: Arc::new(health_probes),
     };
 
+    // Fail startup on a bad allow-list rather than serving with the wrong policy
+    let cors_config = CorsConfig::from_env()?;
+    if cors_config.dev_mode {
+        warn!("CORS_DEV_MODE is set: allowing requests from any origin");
+    }
+    let cors_layer = cors_config.build_layer()?;
+    let limiter = Arc::new(RateLimiter::new(RateLimitConfig::from_env()?));
+    let request_timeout = request_timeout_from_env()?;
+
     let app = Router::new()
-        .route("/api/health", get(health_check))
-        .route("/api/users", get(get_all_users).post(create_user))
+        .route("/api/users", get(get_all_users))
         .route("/api/users/{id}", get(get_user))
-        .route("/api/relationships", post(create_relationship))
-        .route("/api/graph", get(get_graph_data))
-        .route("/api/seed", post(seed_data_handler))
-        .layer(middleware::from_fn_with_state(app_state.clone(), viewer_context_middleware::<AppState>))
-        .layer(
-            ServiceBuilder::new().layer(
-                CorsLayer::new()
-                    .allow_origin(Any)
-                    .allow_methods(Any)
-                    .allow_headers(Any),
-            ),
+        .route(
+            "/api/entities/{id}",
+            get(inspect_entity_handler).delete(delete_entity_handler),
+        )
+        .route("/api/entities/{id}/assocs/{atype}", get(assoc_page_handler))
+        .route(
+            "/api/v1/tao/viewer/entity/{id}/full",
+            get(entity_full_handler),
+        )
+        .route("/api/v1/tao/schema", get(schema_handler))
+        .route("/api/v1/tao/admin/shards", get(shard_report_handler))
+        .route("/api/v1/tao/admin/wal/status", get(wal_status_handler))
+        .route(
+            "/api/v1/tao/admin/cache/hot-objects",
+            get(hot_objects_handler),
         )
+        .route(
+            "/api/v1/tao/admin/maintenance",
+            get(maintenance_status_handler).put(set_maintenance_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            rate_limit(&limiter, READ_COST),
+            rate_limit_middleware,
+        ))
+        .merge(create_routes(
+            IdempotencyStore::new(query_router.clone(), IDEMPOTENCY_KEY_TTL),
+            &limiter,
+        ))
+        .merge(bulk_routes(&limiter))
+        .route("/api/health", get(health_check))
+        .route("/health/detailed", get(detailed_health_handler))
+        // Inside the viewer context layer: only admins get query plans
+        .layer(middleware::from_fn(query_plan_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), viewer_context_middleware::<AppState>))
+        .layer(middleware::from_fn(stale_read_middleware))
+        .layer(middleware::from_fn(read_consistency_middleware))
+        // Outside the layers above: it may move the request to a task of its own
+        .layer(middleware::from_fn_with_state(
+            request_timeout,
+            request_deadline_middleware,
+        ))
+        .layer(ServiceBuilder::new().layer(cors_layer))
         .with_state(app_state);
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
@@ -600,7 +1247,1069 @@ async fn main() -> AppResult<()> {
     info!("📊 Graph visualization available at http://{}", addr);
 
     let listener = TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // Peer addresses let anonymous callers be rate limited by ip
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_database::infrastructure::{
+        cache::cache_layer::{CacheConfig, TaoMultiTierCache},
+        database::sqlite_database::SqliteDatabase,
+        monitoring::health::CacheProbe,
+        storage::write_ahead_log::{TaoOperation, TaoWriteAheadLog, WalConfig},
+        tao_core::tao_core::{TaoAssociation, TaoCore},
+        tao_core::tao_decorators::{BaseTao, CacheDecorator},
+        viewer::viewer::ViewerContext,
+        TaoIdGenerator,
+    };
+
+    async fn wal_app_state(wal_dir: &str) -> (AppState, Arc<TaoWriteAheadLog>) {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        query_router.add_shard(shard_info, database).await.unwrap();
+
+        let association_registry = Arc::new(AssociationRegistry::new());
+        let tao_core = Arc::new(TaoCore::new(
+            query_router.clone(),
+            association_registry.clone(),
+        ));
+        let wal = Arc::new(TaoWriteAheadLog::new(WalConfig::default(), wal_dir).await.unwrap());
+        let wal_decorator = Arc::new(WalDecorator::new(
+            Arc::new(BaseTao::new(tao_core.clone())),
+            wal.clone(),
+        ));
+
+        let health_probes: Vec<Arc<dyn HealthProbe>> = vec![
+            Arc::new(DatabaseProbe::new(query_router.clone())),
+            Arc::new(QueryRouterProbe::new(query_router.clone())),
+            Arc::new(CacheProbe::new(Arc::new(TaoMultiTierCache::new(
+                CacheConfig::default(),
+            )))),
+            Arc::new(WalProbe::new(wal.clone())),
+        ];
+
+        let maintenance = Arc::new(MaintenanceMode::new());
+        let state = AppState {
+            tao: Arc::new(Tao::minimal(tao_core).with_maintenance_mode(maintenance.clone())),
+            query_router,
+            wal: Some(wal_decorator),
+            maintenance,
+            access_tracker: Some(Arc::new(AccessTracker::new(1.0))),
+            schemas: Arc::new(create_schema_registry().describe()),
+            association_registry,
+            list_limits: ListLimits::default(),
+            metrics: Arc::new(MetricsCollector::new()),
+            health_probes: Arc::new(health_probes),
+        };
+        (state, wal)
+    }
+
+    fn admin_vc(state: &AppState) -> Vc {
+        Vc::new(Arc::new(ViewerContext::system("test".to_string(), state.tao.clone())))
+    }
+
+    #[tokio::test]
+    async fn test_wal_replay_endpoint_recovers_failed_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+
+        let id = TaoIdGenerator::new(0).next_id();
+        let txn_id = wal
+            .log_operations(vec![TaoOperation::InsertObject {
+                object_id: id,
+                object_type: "user".to_string(),
+                data: vec![1],
+            }])
+            .await
+            .unwrap();
+        wal.mark_transaction_failed(txn_id, "shard unavailable".to_string())
+            .await
+            .unwrap();
+
+        let Json(status) = wal_status_handler(admin_vc(&state), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(status.retry_queued, 1);
+
+        let Json(summary) = wal_replay_handler(admin_vc(&state), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            WalReplaySummary {
+                pending_found: 1,
+                retried: 1,
+                committed: 1,
+                failed: 0,
+                dead_lettered: 0,
+            }
+        );
+        assert!(state.tao.obj_get(id).await.unwrap().is_some());
+
+        let Json(status) = wal_status_handler(admin_vc(&state), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(status, WalStatus::default());
+    }
+
+    #[tokio::test]
+    async fn test_wal_endpoints_require_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let anonymous = Vc::new(Arc::new(ViewerContext::anonymous(
+            "test".to_string(),
+            state.tao.clone(),
+        )));
+
+        let result = wal_replay_handler(anonymous, State(state)).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_shard_report_requires_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+
+        let Json(report) = shard_report_handler(admin_vc(&state), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(report.len(), 1);
+
+        let anonymous = Vc::new(Arc::new(ViewerContext::anonymous(
+            "test".to_string(),
+            state.tao.clone(),
+        )));
+        let response = shard_report_handler(anonymous, State(state))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_hot_objects_endpoint_lists_most_read_and_requires_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let tracker = state.access_tracker.clone().unwrap();
+        for (id, reads) in [(7, 30), (8, 10), (9, 1)] {
+            for _ in 0..reads {
+                tracker.record(id);
+            }
+        }
+
+        let Json(hot) = hot_objects_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Query(HotObjectsParams { limit: Some(2) }),
+        )
+        .await
+        .unwrap();
+        let ids: Vec<TaoId> = hot.iter().map(|object| object.id).collect();
+        assert_eq!(ids, vec![7, 8]);
+
+        let anonymous = Vc::new(Arc::new(ViewerContext::anonymous(
+            "test".to_string(),
+            state.tao.clone(),
+        )));
+        let result = hot_objects_handler(
+            anonymous,
+            State(state),
+            Query(HotObjectsParams { limit: None }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_consistency_endpoint_reports_orphans_and_requires_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let generator = TaoIdGenerator::new(0);
+        let (id1, id2) = (generator.next_id(), generator.next_id());
+        state
+            .tao
+            .assoc_add(create_tao_association(id1, "friends".to_string(), id2, None))
+            .await
+            .unwrap();
+
+        let anonymous = Vc::new(Arc::new(ViewerContext::anonymous(
+            "test".to_string(),
+            state.tao.clone(),
+        )));
+        let result = consistency_audit_handler(
+            anonymous,
+            State(state.clone()),
+            Query(ConsistencyParams { repair: false }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+        let Json(reports) = consistency_audit_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Query(ConsistencyParams { repair: true }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports[0].issues,
+            vec![ConsistencyIssue::OrphanedAssociation {
+                id1,
+                atype: "friends".to_string(),
+                id2,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_mode_rejects_writes_but_serves_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let id = TaoIdGenerator::new(0).next_id();
+        state
+            .tao
+            .create_object(id, "ent_post".to_string(), vec![1])
+            .await
+            .unwrap();
+
+        let Json(status) = set_maintenance_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Json(MaintenanceStatus { enabled: true }),
+        )
+        .await
+        .unwrap();
+        assert!(status.enabled);
+
+        let write = state.tao.obj_update(id, vec![2]).await;
+        assert!(matches!(write, Err(AppError::ServiceUnavailable(_))));
+        let read = state.tao.obj_get(id).await.unwrap().unwrap();
+        assert_eq!(read.data, vec![1]);
+
+        let Json(status) = set_maintenance_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Json(MaintenanceStatus { enabled: false }),
+        )
+        .await
+        .unwrap();
+        assert!(!status.enabled);
+        let Json(status) = maintenance_status_handler(admin_vc(&state), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(status, MaintenanceStatus { enabled: false });
+        state.tao.obj_update(id, vec![2]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_entity_checks_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let id = TaoIdGenerator::new(0).next_id();
+        state
+            .tao
+            .create_object(id, "ent_post".to_string(), vec![1])
+            .await
+            .unwrap();
+
+        let params = |otype: &str| {
+            Query(DeleteEntityParams {
+                otype: Some(otype.to_string()),
+            })
+        };
+
+        let result = delete_entity_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Path(id),
+            params("ent_user"),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        assert!(state.tao.obj_exists(id).await.unwrap());
+
+        let status = delete_entity_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Path(id),
+            params("ent_post"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(!state.tao.obj_exists(id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_entity_requires_owner_or_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let ids = TaoIdGenerator::new(0);
+        let (post, author, stranger) = (ids.next_id(), ids.next_id(), ids.next_id());
+        state
+            .tao
+            .create_object(post, "ent_post".to_string(), vec![1])
+            .await
+            .unwrap();
+        let authored = create_tao_association(post, "author".to_string(), author, None);
+        state.tao.assoc_add(authored).await.unwrap();
+        let user_vc = |user_id: TaoId| {
+            Vc::new(Arc::new(ViewerContext::authenticated_user(
+                user_id,
+                format!("user{}", user_id),
+                "test".to_string(),
+                state.tao.clone(),
+            )))
+        };
+        let typed = || {
+            Query(DeleteEntityParams {
+                otype: Some("ent_post".to_string()),
+            })
+        };
+
+        let response =
+            delete_entity_handler(user_vc(stranger), State(state.clone()), Path(post), typed())
+                .await
+                .into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(state.tao.obj_exists(post).await.unwrap());
+
+        let status =
+            delete_entity_handler(user_vc(author), State(state.clone()), Path(post), typed())
+                .await
+                .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(!state.tao.obj_exists(post).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_inspect_entity_lists_assoc_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let id = TaoIdGenerator::new(0).next_id();
+        state
+            .tao
+            .create_object(id, "ent_post".to_string(), vec![1])
+            .await
+            .unwrap();
+        for (atype, id2) in [("liked_by", 1), ("liked_by", 2), ("tagged", 3)] {
+            state
+                .tao
+                .assoc_add(create_tao_association(id, atype.to_string(), id2, None))
+                .await
+                .unwrap();
+        }
+
+        let (_, Json(inspection)) = inspect_entity_handler(admin_vc(&state), Path(id))
+            .await
+            .unwrap();
+        assert_eq!(inspection.otype, "ent_post");
+        let associations: Vec<(&str, u64)> = inspection
+            .associations
+            .iter()
+            .map(|assoc| (assoc.atype.as_str(), assoc.count))
+            .collect();
+        assert_eq!(associations, vec![("liked_by", 2), ("tagged", 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_inspect_entity_reports_whether_it_was_read_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        state.tao = Arc::new(CacheDecorator::new(state.wal.clone().unwrap(), cache, true));
+        let id = TaoIdGenerator::new(0).next_id();
+        state
+            .tao
+            .create_object(id, "ent_post".to_string(), vec![1])
+            .await
+            .unwrap();
+
+        let inspect = || inspect_entity_handler(admin_vc(&state), Path(id));
+        let (headers, Json(first)) = inspect().await.unwrap();
+        assert_eq!(headers[DATA_SOURCE_HEADER], "primary");
+        assert!(!first.stale);
+
+        // The first read filled the cache
+        let (headers, Json(second)) = inspect().await.unwrap();
+        assert_eq!(headers[DATA_SOURCE_HEADER], "cache");
+        assert_eq!(second.updated_time, first.updated_time);
+        assert!(!second.stale);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_endpoint_pages_by_cursor_across_inserts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let generator = TaoIdGenerator::new(0);
+        let post = generator.next_id();
+        let like = |id2: TaoId, time: i64| TaoAssociation {
+            id1: post,
+            atype: "liked_by".to_string(),
+            id2,
+            time,
+            data: None,
+            subtype: None,
+        };
+        // Same-millisecond likes, as under load
+        let likers: Vec<TaoId> = (0..5).map(|_| generator.next_id()).collect();
+        for &liker in &likers {
+            state.tao.assoc_add(like(liker, 1_000)).await.unwrap();
+        }
+
+        let fetch = |cursor: Option<String>| {
+            assoc_page_handler(
+                admin_vc(&state),
+                State(state.clone()),
+                Path((post, "liked_by".to_string())),
+                Query(AssocPageParams {
+                    limit: Some(2),
+                    cursor,
+                    offset: None,
+                }),
+            )
+        };
+        let (_, Json(first)) = fetch(None).await.unwrap();
+        let mut seen: Vec<TaoId> = first.associations.iter().map(|edge| edge.id2).collect();
+
+        state
+            .tao
+            .assoc_add(like(generator.next_id(), 2_000))
+            .await
+            .unwrap();
+
+        let mut cursor = first.next_cursor;
+        while cursor.is_some() {
+            let (_, Json(page)) = fetch(cursor).await.unwrap();
+            seen.extend(page.associations.iter().map(|edge| edge.id2));
+            cursor = page.next_cursor;
+        }
+        let mut expected = likers;
+        expected.reverse();
+        assert_eq!(seen, expected);
+
+        let both = assoc_page_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Path((post, "liked_by".to_string())),
+            Query(AssocPageParams {
+                limit: None,
+                cursor: Some("abc".to_string()),
+                offset: Some(2),
+            }),
+        )
+        .await;
+        assert!(matches!(both, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_assoc_endpoint_applies_list_limits_and_reports_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        state.list_limits = ListLimits { default: 2, max: 3 };
+        let generator = TaoIdGenerator::new(0);
+        let post = generator.next_id();
+        for time in 0..5 {
+            state
+                .tao
+                .assoc_add(TaoAssociation {
+                    id1: post,
+                    atype: "liked_by".to_string(),
+                    id2: generator.next_id(),
+                    time,
+                    data: None,
+                    subtype: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let fetch = |limit: Option<u32>| {
+            assoc_page_handler(
+                admin_vc(&state),
+                State(state.clone()),
+                Path((post, "liked_by".to_string())),
+                Query(AssocPageParams {
+                    limit,
+                    cursor: None,
+                    offset: None,
+                }),
+            )
+        };
+        // Default applied, clamped to max, within bounds
+        for (requested, applied) in [(None, 2), (Some(50), 3), (Some(1), 1)] {
+            let ([(header, value)], Json(page)) = fetch(requested).await.unwrap();
+            assert_eq!(header, APPLIED_LIMIT_HEADER);
+            assert_eq!(value, applied.to_string());
+            assert_eq!(page.associations.len(), applied);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entity_full_returns_requested_edge_lists_with_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let generator = TaoIdGenerator::new(0);
+        let user = generator.next_id();
+        state
+            .tao
+            .create_object(user, "ent_user".to_string(), vec![1])
+            .await
+            .unwrap();
+        let friends: Vec<TaoId> = (0..3).map(|_| generator.next_id()).collect();
+        for (i, &friend) in friends.iter().enumerate() {
+            state
+                .tao
+                .assoc_add(TaoAssociation {
+                    id1: user,
+                    atype: "friends".to_string(),
+                    id2: friend,
+                    time: 1_000 + i as i64,
+                    data: None,
+                    subtype: None,
+                })
+                .await
+                .unwrap();
+        }
+        let post = generator.next_id();
+        state
+            .tao
+            .assoc_add(create_tao_association(
+                user,
+                "posts".to_string(),
+                post,
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let params = |edges: &str| {
+            Query(EntityFullParams {
+                edges: Some(edges.to_string()),
+                limit: Some(2),
+            })
+        };
+        let (_, Json(full)) = entity_full_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Path(user),
+            params("friends,posts,groups"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(full.id, user);
+        assert_eq!(full.otype, "ent_user");
+        assert_eq!(
+            full.edges.keys().collect::<Vec<_>>(),
+            vec!["friends", "groups", "posts"]
+        );
+
+        let friend_list = &full.edges["friends"];
+        assert_eq!(friend_list.total, 3);
+        let page: Vec<TaoId> = friend_list
+            .page
+            .associations
+            .iter()
+            .map(|e| e.id2)
+            .collect();
+        assert_eq!(page, vec![friends[2], friends[1]]);
+        assert!(friend_list.page.next_cursor.is_some());
+        assert_eq!(full.edges["posts"].total, 1);
+        assert_eq!(full.edges["posts"].page.associations[0].id2, post);
+        assert!(full.edges["posts"].page.next_cursor.is_none());
+        assert_eq!(full.edges["groups"].total, 0);
+
+        // Anonymous viewers see how many, not who
+        let anonymous = Vc::new(Arc::new(ViewerContext::anonymous(
+            "test".to_string(),
+            state.tao.clone(),
+        )));
+        let (_, Json(public)) = entity_full_handler(
+            anonymous,
+            State(state.clone()),
+            Path(user),
+            params("friends"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(public.edges["friends"].total, 3);
+        assert!(public.edges["friends"].page.associations.is_empty());
+
+        let unknown = entity_full_handler(
+            admin_vc(&state),
+            State(state.clone()),
+            Path(user),
+            params("liked_by"),
+        )
+        .await;
+        assert!(matches!(unknown, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_errors_use_the_envelope_with_code_and_request_id() {
+        use axum::body::{to_bytes, Body};
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let app = Router::new()
+            .route("/api/entities/{id}", get(inspect_entity_handler))
+            .route("/api/entities/{id}/assocs/{atype}", get(assoc_page_handler))
+            .route("/api/users/{id}", get(get_user))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                viewer_context_middleware::<AppState>,
+            ))
+            .with_state(state.clone());
+
+        let get = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .header("authorization", "System internal")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let error_body = |body: &[u8]| {
+            let body: serde_json::Value = serde_json::from_slice(body).unwrap();
+            body["error"].clone()
+        };
+
+        let missing = app.clone().oneshot(get("/api/entities/42")).await.unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let error = error_body(&to_bytes(missing.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(error["code"], "not_found");
+        assert_eq!(error["message"], "Entity 42 not found");
+        assert!(error["request_id"].as_str().unwrap().starts_with("req-"));
+
+        let invalid = app
+            .clone()
+            .oneshot(get("/api/entities/42/assocs/liked_by?cursor=abc&offset=2"))
+            .await
+            .unwrap();
+        assert_eq!(invalid.status(), StatusCode::BAD_REQUEST);
+        let error = error_body(&to_bytes(invalid.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(error["code"], "validation_error");
+        assert_eq!(error["message"], "Pass either cursor or offset, not both");
+        assert!(error["request_id"].is_string());
+
+        // The user endpoints answer in the same envelope
+        let no_user = app.clone().oneshot(get("/api/users/42")).await.unwrap();
+        assert_eq!(no_user.status(), StatusCode::NOT_FOUND);
+        let error = error_body(&to_bytes(no_user.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(error["code"], "not_found");
+        assert!(error["request_id"].is_string());
+
+        let post = state.tao.generate_id(None).await.unwrap();
+        state
+            .tao
+            .create_object(post, "ent_post".to_string(), Vec::new())
+            .await
+            .unwrap();
+        let not_a_user = app
+            .oneshot(get(&format!("/api/users/{}", post)))
+            .await
+            .unwrap();
+        assert_eq!(not_a_user.status(), StatusCode::BAD_REQUEST);
+        let error = error_body(&to_bytes(not_a_user.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(error["code"], "type_mismatch");
+    }
+
+    #[tokio::test]
+    async fn test_create_user_replays_idempotency_key() {
+        use axum::body::{to_bytes, Body};
+        use axum::http::Request;
+        use tao_database::infrastructure::middleware::{
+            IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAY_HEADER,
+        };
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let store = IdempotencyStore::new(state.query_router.clone(), IDEMPOTENCY_KEY_TTL);
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig::default()));
+        let app = Router::new()
+            .merge(create_routes(store, &limiter))
+            .route("/api/users", get(get_all_users))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                viewer_context_middleware::<AppState>,
+            ))
+            .with_state(state.clone());
+
+        let create = |key: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header("content-type", "application/json")
+                .header(IDEMPOTENCY_KEY_HEADER, key)
+                .body(Body::from(
+                    r#"{"name": "Ada Lovelace", "email": "ada@example.com", "bio": null}"#,
+                ))
+                .unwrap()
+        };
+        let created_id = |body: &[u8]| {
+            let response: ApiResponse<UserResponse> = serde_json::from_slice(body).unwrap();
+            response.data.unwrap().id
+        };
+
+        let first = app.clone().oneshot(create("key-1")).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let first_id = created_id(&to_bytes(first.into_body(), usize::MAX).await.unwrap());
+
+        let retry = app.clone().oneshot(create("key-1")).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers().get(IDEMPOTENT_REPLAY_HEADER).unwrap(), "true");
+        let retry_id = created_id(&to_bytes(retry.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(retry_id, first_id);
+
+        assert!(state.tao.obj_exists(first_id).await.unwrap());
+        let users = state
+            .tao
+            .estimate_count_of_type("ent_user".to_string())
+            .await
+            .unwrap();
+        assert_eq!(users, 1);
+
+        // Reads on the same path are untouched by the create-only layer
+        let list = app
+            .oneshot(Request::builder().uri("/api/users").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(list.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_creates_with_one_idempotency_key_create_once() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tao_database::infrastructure::middleware::IDEMPOTENCY_KEY_HEADER;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let store = IdempotencyStore::new(state.query_router.clone(), IDEMPOTENCY_KEY_TTL);
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig::default()));
+        let app = Router::new()
+            .merge(create_routes(store, &limiter))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                viewer_context_middleware::<AppState>,
+            ))
+            .with_state(state.clone());
+
+        let create = || {
+            Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header("content-type", "application/json")
+                .header(IDEMPOTENCY_KEY_HEADER, "key-1")
+                .body(Body::from(
+                    r#"{"name": "Ada Lovelace", "email": "ada@example.com", "bio": null}"#,
+                ))
+                .unwrap()
+        };
+        let first = tokio::spawn(app.clone().oneshot(create()));
+        let second = tokio::spawn(app.clone().oneshot(create()));
+        let mut statuses = vec![
+            first.await.unwrap().unwrap().status(),
+            second.await.unwrap().unwrap().status(),
+        ];
+        statuses.sort();
+
+        // The loser either saw the key still claimed or got the winner's response replayed
+        assert_eq!(statuses[0], StatusCode::CREATED);
+        assert!(
+            matches!(statuses[1], StatusCode::CREATED | StatusCode::CONFLICT),
+            "got {:?}",
+            statuses
+        );
+        let users = state
+            .tao
+            .estimate_count_of_type("ent_user".to_string())
+            .await
+            .unwrap();
+        assert_eq!(users, 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_relationship_rejects_unknown_type() {
+        use axum::body::to_bytes;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let ids = TaoIdGenerator::new(0);
+        let (alice, bob) = (ids.next_id(), ids.next_id());
+        let request = |relationship_type: &str| {
+            Json(CreateRelationshipRequest {
+                from_user_id: alice,
+                to_user_id: bob,
+                relationship_type: relationship_type.to_string(),
+            })
+        };
+
+        let response =
+            create_relationship(admin_vc(&state), State(state.clone()), request("freinds"))
+                .await
+                .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "bad_request");
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.contains("freinds"));
+        assert!(message.contains("friends"));
+        assert!(!state
+            .tao
+            .assoc_exists(alice, "freinds".to_string(), bob)
+            .await
+            .unwrap());
+
+        let response =
+            create_relationship(admin_vc(&state), State(state.clone()), request("friends"))
+                .await
+                .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_routes_return_429_with_retry_after() {
+        use axum::body::Body;
+        use axum::extract::ConnectInfo;
+        use axum::http::{header, Request};
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let store = IdempotencyStore::new(state.query_router.clone(), IDEMPOTENCY_KEY_TTL);
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            capacity: 2 * WRITE_COST,
+            refill_per_second: 0.1,
+            trusted_proxies: vec!["10.0.0.1".parse().unwrap()],
+        }));
+        let app = Router::new()
+            .route("/api/users", get(get_all_users))
+            .route_layer(middleware::from_fn_with_state(
+                rate_limit(&limiter, READ_COST),
+                rate_limit_middleware,
+            ))
+            .merge(create_routes(store, &limiter))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                viewer_context_middleware::<AppState>,
+            ))
+            .with_state(state);
+
+        let request = |method: &str, peer: &str, forwarded_for: Option<&str>| {
+            let mut builder = Request::builder()
+                .method(method)
+                .uri("/api/users")
+                .header("content-type", "application/json");
+            if let Some(forwarded_for) = forwarded_for {
+                builder = builder.header("x-forwarded-for", forwarded_for);
+            }
+            let mut request = builder
+                .body(Body::from(
+                    r#"{"name": "Ada Lovelace", "email": "ada@example.com", "bio": null}"#,
+                ))
+                .unwrap();
+            let peer: SocketAddr = format!("{}:4000", peer).parse().unwrap();
+            request.extensions_mut().insert(ConnectInfo(peer));
+            request
+        };
+
+        // Creates cost more than reads: a full bucket covers two of them
+        for _ in 0..2 {
+            let created = app
+                .clone()
+                .oneshot(request("POST", "1.1.1.1", None))
+                .await
+                .unwrap();
+            assert_eq!(created.status(), StatusCode::CREATED);
+        }
+        let limited = app
+            .clone()
+            .oneshot(request("GET", "1.1.1.1", None))
+            .await
+            .unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = limited.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(retry_after, 10);
+
+        // Another client, and one reaching us through the trusted proxy, are unaffected
+        let other = app
+            .clone()
+            .oneshot(request("GET", "2.2.2.2", None))
+            .await
+            .unwrap();
+        assert_eq!(other.status(), StatusCode::OK);
+        let proxied = app
+            .clone()
+            .oneshot(request("GET", "10.0.0.1", Some("3.3.3.3")))
+            .await
+            .unwrap();
+        assert_eq!(proxied.status(), StatusCode::OK);
+
+        // A forwarded-for header from an untrusted peer doesn't escape the limit
+        let spoofed = app
+            .oneshot(request("GET", "1.1.1.1", Some("4.4.4.4")))
+            .await
+            .unwrap();
+        assert_eq!(spoofed.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_scatter_gather_consumes_more_quota_than_a_point_read() {
+        use axum::body::Body;
+        use axum::extract::ConnectInfo;
+        use axum::http::Request;
+        use tower::ServiceExt;
+        use tao_database::infrastructure::middleware::COST_UNITS_HEADER;
+        use tao_database::infrastructure::tao_core::request_context::{
+            POINT_READ_UNITS, SCATTER_UNITS_PER_SHARD,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+        let user_id = state.tao.generate_id(None).await.unwrap();
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            capacity: 100,
+            refill_per_second: 0.001,
+            trusted_proxies: Vec::new(),
+        }));
+        let app = Router::new()
+            .route("/api/users", get(get_all_users))
+            .route("/api/users/{id}", get(get_user))
+            .route_layer(middleware::from_fn_with_state(
+                rate_limit(&limiter, READ_COST),
+                rate_limit_middleware,
+            ))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                viewer_context_middleware::<AppState>,
+            ))
+            .with_state(state);
+
+        let get_units = |uri: String, peer: &'static str| {
+            let app = app.clone();
+            async move {
+                let mut request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                let peer: SocketAddr = format!("{}:4000", peer).parse().unwrap();
+                request.extensions_mut().insert(ConnectInfo(peer));
+                let response = app.oneshot(request).await.unwrap();
+                response.headers()[COST_UNITS_HEADER]
+                    .to_str()
+                    .unwrap()
+                    .parse::<u64>()
+                    .unwrap()
+            }
+        };
+
+        let point = get_units(format!("/api/users/{}", user_id), "1.1.1.1").await;
+        let scatter = get_units("/api/users".to_string(), "2.2.2.2").await;
+        assert_eq!(point, POINT_READ_UNITS);
+        assert!(scatter >= SCATTER_UNITS_PER_SHARD, "{}", scatter);
+
+        let point_left = limiter.remaining("ip:1.1.1.1");
+        let scatter_left = limiter.remaining("ip:2.2.2.2");
+        assert!(point_left > scatter_left);
+        assert!(100.0 - scatter_left >= SCATTER_UNITS_PER_SHARD as f64 - 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_detailed_health_reports_a_failing_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+
+        let (status, Json(health)) = detailed_health_handler(State(state.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(health.overall_status, ServiceStatus::Healthy);
+        for component in ["database", "query_router", "cache", "wal"] {
+            assert_eq!(health.services[component].status, ServiceStatus::Healthy);
+        }
+
+        // Stub a failing database by closing the shard's connection pool
+        let database = state.query_router.get_database_for_shard(0).await.unwrap();
+        database
+            .as_any()
+            .downcast_ref::<SqliteDatabase>()
+            .unwrap()
+            .close()
+            .await;
+
+        let (status, Json(health)) = detailed_health_handler(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health.overall_status, ServiceStatus::Unhealthy);
+        assert_eq!(health.database_status, ServiceStatus::Unhealthy);
+        let database = &health.services["database"];
+        assert!(database.details.contains("shard 0"), "{}", database.details);
+        assert_eq!(database.error_rate, 0.5);
+        // The other components are unaffected
+        assert_eq!(health.query_router_status, ServiceStatus::Healthy);
+        assert_eq!(health.wal_status, ServiceStatus::Healthy);
+        assert_eq!(health.health_check_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_schema_endpoint_describes_user_fields_and_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _wal) = wal_app_state(dir.path().to_str().unwrap()).await;
+
+        let Json(schemas) = schema_handler(State(state)).await;
+        let user = schemas
+            .iter()
+            .find(|schema| schema.entity_type == "ent_user")
+            .expect("ent_user should be described");
+
+        let field_names: Vec<&str> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            field_names,
+            vec![
+                "username",
+                "email",
+                "created_time",
+                "full_name",
+                "bio",
+                "profile_picture_url",
+                "last_active_time",
+                "is_verified",
+                "location",
+                "privacy_settings"
+            ]
+        );
+        let field = |name: &str| user.fields.iter().find(|f| f.name == name).unwrap();
+        assert!(field("username").required && field("username").unique);
+        assert!(!field("bio").required);
+        assert!(!field("is_verified").required);
+        assert!(field("is_verified").default.is_some());
+
+        let edge = |name: &str| user.edges.iter().find(|e| e.name == name).unwrap();
+        assert_eq!(edge("friends").target_type, "ent_user");
+        assert_eq!(edge("liked_posts").target_type, "ent_post");
+        assert_eq!(edge("liked_posts").inverse_name.as_deref(), Some("liked_by"));
+        assert_eq!(edge("groups").payload[0].name, "role");
+        assert_eq!(user.edges.len(), 8);
+    }
+}
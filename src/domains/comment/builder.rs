@@ -3,12 +3,12 @@
 // Regenerate with: cargo run --bin entc generate
 
 use crate::framework::entity::ent_trait::Entity;
-use crate::framework::builder::ent_builder::EntBuilder;
+use crate::framework::builder::ent_builder::{EntBuilder, OwnerEdge};
 use crate::framework::builder::has_tao::HasTao;
 use crate::infrastructure::viewer::viewer::ViewerContext;
 use crate::infrastructure::tao_core::tao_core::{TaoEntityBuilder, TaoOperations};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
-use crate::error::{AppResult, AppError};
+use crate::error::{AppResult, AppError, ValidationErrors};
 use super::entity::EntComment;
 use std::sync::Arc;
 
@@ -19,6 +19,7 @@ pub struct EntCommentBuilderState {
     content: Option<String>,
     created_time: Option<i64>,
     pub(crate) tao: Option<Arc<dyn TaoOperations>>,
+    pub(crate) viewer_id: Option<i64>,
 }
 
 impl EntCommentBuilderState {
@@ -53,20 +54,28 @@ impl EntCommentBuilderState {
 impl EntBuilder for EntComment {
     type BuilderState = EntCommentBuilderState;
 
-    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String> {
+    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors> {
         let current_time = current_time_millis();
 
+        let mut missing = ValidationErrors::new();
+        if state.author_id.is_none() {
+            missing.push("author_id", "required", "author_id is required");
+        }
+        if state.post_id.is_none() {
+            missing.push("post_id", "required", "post_id is required");
+        }
+        if state.content.is_none() {
+            missing.push("content", "required", "content is required");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
         Ok(EntComment {
             id,
-            author_id: state.author_id.ok_or_else(|| 
-                "Required field 'author_id' not provided".to_string()
-            )?,
-            post_id: state.post_id.ok_or_else(|| 
-                "Required field 'post_id' not provided".to_string()
-            )?,
-            content: state.content.ok_or_else(|| 
-                "Required field 'content' not provided".to_string()
-            )?,
+            author_id: state.author_id.unwrap_or_default(),
+            post_id: state.post_id.unwrap_or_default(),
+            content: state.content.unwrap_or_default(),
             created_time: current_time,
         })
     }
@@ -74,6 +83,10 @@ impl EntBuilder for EntComment {
     fn entity_type() -> &'static str {
         "ent_comment"
     }
+
+    fn owner_edge() -> Option<OwnerEdge> {
+        Some(OwnerEdge { atype: "author", inverse: Some("comments") })
+    }
 }
 
 impl HasTao for EntCommentBuilderState {
@@ -84,14 +97,27 @@ impl HasTao for EntCommentBuilderState {
     fn set_tao(&mut self, tao: Arc<dyn TaoOperations>) {
         self.tao = Some(tao);
     }
+
+    fn get_viewer_id(&self) -> Option<i64> {
+        self.viewer_id
+    }
+
+    fn set_viewer_id(&mut self, viewer_id: Option<i64>) {
+        self.viewer_id = viewer_id;
+    }
 }
 
 impl EntComment {
     /// Create a new entity builder state (Meta's pattern: EntUser::create(vc))
-    pub fn create(vc: Arc<ViewerContext>) -> EntCommentBuilderState {
+    pub fn create<V>(vc: V) -> EntCommentBuilderState 
+    where 
+        V: Into<Arc<ViewerContext>>,
+    {
+        let vc = vc.into();
         let mut builder = EntCommentBuilderState::default();
         // Extract TAO from viewer context following Meta's pattern
         builder.set_tao(Arc::clone(&vc.tao));
+        builder.set_viewer_id(vc.user_id);
         builder
     }
 }
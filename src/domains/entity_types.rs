@@ -0,0 +1,14 @@
+// Generated entity type registrations
+// DO NOT EDIT
+
+use crate::framework::entity::entity_registry::EntityTypeRegistry;
+
+/// Register a deserializer for every generated entity type
+pub fn register_entity_types(registry: &mut EntityTypeRegistry) {
+    registry.register::<crate::domains::comment::EntComment>();
+    registry.register::<crate::domains::event::EntEvent>();
+    registry.register::<crate::domains::group::EntGroup>();
+    registry.register::<crate::domains::page::EntPage>();
+    registry.register::<crate::domains::post::EntPost>();
+    registry.register::<crate::domains::user::EntUser>();
+}
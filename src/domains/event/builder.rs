@@ -8,7 +8,7 @@ use crate::framework::builder::has_tao::HasTao;
 use crate::infrastructure::viewer::viewer::ViewerContext;
 use crate::infrastructure::tao_core::tao_core::{TaoEntityBuilder, TaoOperations};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
-use crate::error::{AppResult, AppError};
+use crate::error::{AppResult, AppError, ValidationErrors};
 use super::entity::EntEvent;
 use std::sync::Arc;
 
@@ -19,6 +19,7 @@ pub struct EntEventBuilderState {
     event_time: Option<i64>,
     created_time: Option<i64>,
     pub(crate) tao: Option<Arc<dyn TaoOperations>>,
+    pub(crate) viewer_id: Option<i64>,
 }
 
 impl EntEventBuilderState {
@@ -53,18 +54,25 @@ impl EntEventBuilderState {
 impl EntBuilder for EntEvent {
     type BuilderState = EntEventBuilderState;
 
-    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String> {
+    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors> {
         let current_time = current_time_millis();
 
+        let mut missing = ValidationErrors::new();
+        if state.name.is_none() {
+            missing.push("name", "required", "name is required");
+        }
+        if state.event_time.is_none() {
+            missing.push("event_time", "required", "event_time is required");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
         Ok(EntEvent {
             id,
-            name: state.name.ok_or_else(|| 
-                "Required field 'name' not provided".to_string()
-            )?,
+            name: state.name.unwrap_or_default(),
             description: state.description,
-            event_time: state.event_time.ok_or_else(|| 
-                "Required field 'event_time' not provided".to_string()
-            )?,
+            event_time: state.event_time.unwrap_or_default(),
             created_time: current_time,
         })
     }
@@ -82,14 +90,27 @@ impl HasTao for EntEventBuilderState {
     fn set_tao(&mut self, tao: Arc<dyn TaoOperations>) {
         self.tao = Some(tao);
     }
+
+    fn get_viewer_id(&self) -> Option<i64> {
+        self.viewer_id
+    }
+
+    fn set_viewer_id(&mut self, viewer_id: Option<i64>) {
+        self.viewer_id = viewer_id;
+    }
 }
 
 impl EntEvent {
     /// Create a new entity builder state (Meta's pattern: EntUser::create(vc))
-    pub fn create(vc: Arc<ViewerContext>) -> EntEventBuilderState {
+    pub fn create<V>(vc: V) -> EntEventBuilderState 
+    where 
+        V: Into<Arc<ViewerContext>>,
+    {
+        let vc = vc.into();
         let mut builder = EntEventBuilderState::default();
         // Extract TAO from viewer context following Meta's pattern
         builder.set_tao(Arc::clone(&vc.tao));
+        builder.set_viewer_id(vc.user_id);
         builder
     }
 }
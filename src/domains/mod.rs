@@ -7,3 +7,5 @@ pub mod user;
 pub mod comment;
 pub mod group;
 pub mod event;
+
+pub mod entity_types;
@@ -8,7 +8,7 @@ use crate::framework::builder::has_tao::HasTao;
 use crate::infrastructure::viewer::viewer::ViewerContext;
 use crate::infrastructure::tao_core::tao_core::{TaoEntityBuilder, TaoOperations};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
-use crate::error::{AppResult, AppError};
+use crate::error::{AppResult, AppError, ValidationErrors};
 use super::entity::EntPage;
 use std::sync::Arc;
 
@@ -18,6 +18,7 @@ pub struct EntPageBuilderState {
     description: Option<String>,
     created_time: Option<i64>,
     pub(crate) tao: Option<Arc<dyn TaoOperations>>,
+    pub(crate) viewer_id: Option<i64>,
 }
 
 impl EntPageBuilderState {
@@ -47,14 +48,20 @@ impl EntPageBuilderState {
 impl EntBuilder for EntPage {
     type BuilderState = EntPageBuilderState;
 
-    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String> {
+    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors> {
         let current_time = current_time_millis();
 
+        let mut missing = ValidationErrors::new();
+        if state.name.is_none() {
+            missing.push("name", "required", "name is required");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
         Ok(EntPage {
             id,
-            name: state.name.ok_or_else(|| 
-                "Required field 'name' not provided".to_string()
-            )?,
+            name: state.name.unwrap_or_default(),
             description: state.description,
             created_time: current_time,
         })
@@ -73,14 +80,27 @@ impl HasTao for EntPageBuilderState {
     fn set_tao(&mut self, tao: Arc<dyn TaoOperations>) {
         self.tao = Some(tao);
     }
+
+    fn get_viewer_id(&self) -> Option<i64> {
+        self.viewer_id
+    }
+
+    fn set_viewer_id(&mut self, viewer_id: Option<i64>) {
+        self.viewer_id = viewer_id;
+    }
 }
 
 impl EntPage {
     /// Create a new entity builder state (Meta's pattern: EntUser::create(vc))
-    pub fn create(vc: Arc<ViewerContext>) -> EntPageBuilderState {
+    pub fn create<V>(vc: V) -> EntPageBuilderState 
+    where 
+        V: Into<Arc<ViewerContext>>,
+    {
+        let vc = vc.into();
         let mut builder = EntPageBuilderState::default();
         // Extract TAO from viewer context following Meta's pattern
         builder.set_tao(Arc::clone(&vc.tao));
+        builder.set_viewer_id(vc.user_id);
         builder
     }
 }
@@ -4,7 +4,7 @@
 
 use std::sync::Arc;
 use crate::framework::entity::ent_trait::Entity;
-use crate::error::AppResult;
+use crate::error::{AppResult, ValidationErrors};
 use super::entity::EntPage;
 use crate::infrastructure::tao_core::tao_core::{TaoOperations, TaoObject};
 use crate::infrastructure::tao_core::tao::Tao;
@@ -22,12 +22,12 @@ impl Entity for EntPage {
         self.id
     }
 
-    fn validate(&self) -> AppResult<Vec<String>> {
-        let mut errors = Vec::new();
+    fn validate(&self) -> AppResult<ValidationErrors> {
+        let mut errors = ValidationErrors::new();
         
         // Validate name (required)
         if self.name.trim().is_empty() {
-            errors.push("name cannot be empty".to_string());
+            errors.push("name", "required", "name cannot be empty");
         }
         
         
@@ -45,7 +45,7 @@ impl EntPage {
         let mut cursor = Cursor::new(&tao_obj.data);
         let mut protocol = TCompactInputProtocol::new(&mut cursor);
         let mut entity = EntPage::read_from_in_protocol(&mut protocol)
-            .map_err(|e| crate::error::AppError::SerializationError(e.to_string()))?;
+            .map_err(|e| crate::error::AppError::serialization(&tao_obj.otype, tao_obj.id, e))?;
         
         Ok(Some(entity))
     }
@@ -3,12 +3,12 @@
 // Regenerate with: cargo run --bin entc generate
 
 use crate::framework::entity::ent_trait::Entity;
-use crate::framework::builder::ent_builder::EntBuilder;
+use crate::framework::builder::ent_builder::{EntBuilder, OwnerEdge};
 use crate::framework::builder::has_tao::HasTao;
 use crate::infrastructure::viewer::viewer::ViewerContext;
 use crate::infrastructure::tao_core::tao_core::{TaoEntityBuilder, TaoOperations};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
-use crate::error::{AppResult, AppError};
+use crate::error::{AppResult, AppError, ValidationErrors};
 use super::entity::EntPost;
 use std::sync::Arc;
 
@@ -27,6 +27,7 @@ pub struct EntPostBuilderState {
     tags: Option<String>,
     mentions: Option<String>,
     pub(crate) tao: Option<Arc<dyn TaoOperations>>,
+    pub(crate) viewer_id: Option<i64>,
 }
 
 impl EntPostBuilderState {
@@ -101,33 +102,32 @@ impl EntPostBuilderState {
 impl EntBuilder for EntPost {
     type BuilderState = EntPostBuilderState;
 
-    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String> {
+    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors> {
         let current_time = current_time_millis();
 
+        let mut missing = ValidationErrors::new();
+        if state.author_id.is_none() {
+            missing.push("author_id", "required", "author_id is required");
+        }
+        if state.content.is_none() {
+            missing.push("content", "required", "content is required");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
         Ok(EntPost {
             id,
-            author_id: state.author_id.ok_or_else(|| 
-                "Required field 'author_id' not provided".to_string()
-            )?,
-            content: state.content.ok_or_else(|| 
-                "Required field 'content' not provided".to_string()
-            )?,
+            author_id: state.author_id.unwrap_or_default(),
+            content: state.content.unwrap_or_default(),
             media_url: state.media_url,
             created_time: current_time,
             updated_time: Some(current_time),
-            post_type: state.post_type.ok_or_else(|| 
-                "Required field 'post_type' not provided".to_string()
-            )?,
-            visibility: state.visibility,
-            like_count: state.like_count.ok_or_else(|| 
-                "Required field 'like_count' not provided".to_string()
-            )?,
-            comment_count: state.comment_count.ok_or_else(|| 
-                "Required field 'comment_count' not provided".to_string()
-            )?,
-            share_count: state.share_count.ok_or_else(|| 
-                "Required field 'share_count' not provided".to_string()
-            )?,
+            post_type: state.post_type.unwrap_or_else(|| "text".to_string()),
+            visibility: state.visibility.or_else(|| Some("public".to_string())),
+            like_count: state.like_count.unwrap_or(0),
+            comment_count: state.comment_count.unwrap_or(0),
+            share_count: state.share_count.unwrap_or(0),
             tags: state.tags,
             mentions: state.mentions,
         })
@@ -136,6 +136,10 @@ impl EntBuilder for EntPost {
     fn entity_type() -> &'static str {
         "ent_post"
     }
+
+    fn owner_edge() -> Option<OwnerEdge> {
+        Some(OwnerEdge { atype: "author", inverse: Some("posts") })
+    }
 }
 
 impl HasTao for EntPostBuilderState {
@@ -146,14 +150,27 @@ impl HasTao for EntPostBuilderState {
     fn set_tao(&mut self, tao: Arc<dyn TaoOperations>) {
         self.tao = Some(tao);
     }
+
+    fn get_viewer_id(&self) -> Option<i64> {
+        self.viewer_id
+    }
+
+    fn set_viewer_id(&mut self, viewer_id: Option<i64>) {
+        self.viewer_id = viewer_id;
+    }
 }
 
 impl EntPost {
     /// Create a new entity builder state (Meta's pattern: EntUser::create(vc))
-    pub fn create(vc: Arc<ViewerContext>) -> EntPostBuilderState {
+    pub fn create<V>(vc: V) -> EntPostBuilderState 
+    where 
+        V: Into<Arc<ViewerContext>>,
+    {
+        let vc = vc.into();
         let mut builder = EntPostBuilderState::default();
         // Extract TAO from viewer context following Meta's pattern
         builder.set_tao(Arc::clone(&vc.tao));
+        builder.set_viewer_id(vc.user_id);
         builder
     }
 }
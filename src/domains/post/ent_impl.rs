@@ -4,7 +4,7 @@
 
 use std::sync::Arc;
 use crate::framework::entity::ent_trait::Entity;
-use crate::error::AppResult;
+use crate::error::{AppResult, ValidationErrors};
 use super::entity::EntPost;
 use crate::infrastructure::tao_core::tao_core::{TaoOperations, TaoObject};
 use crate::infrastructure::tao_core::tao::Tao;
@@ -25,33 +25,48 @@ impl Entity for EntPost {
         self.id
     }
 
-    fn validate(&self) -> AppResult<Vec<String>> {
-        let mut errors = Vec::new();
+    fn validate(&self) -> AppResult<ValidationErrors> {
+        let mut errors = ValidationErrors::new();
         
         
         // Validate content (required)
         if self.content.trim().is_empty() {
-            errors.push("content cannot be empty".to_string());
+            errors.push("content", "required", "content cannot be empty");
         }
         // Validate content min length
         if self.content.len() < 1 {
-            errors.push("content must be at least 1 characters".to_string());
+            errors.push("content", "min_length", "content must be at least 1 characters");
         }
         // Validate content max length
         if self.content.len() > 10000 {
-            errors.push("content cannot exceed 10000 characters".to_string());
+            errors.push("content", "max_length", "content cannot exceed 10000 characters");
         }
         
         
         
         // Validate post type (required)
         if self.post_type.trim().is_empty() {
-            errors.push("post type cannot be empty".to_string());
+            errors.push("post_type", "required", "post type cannot be empty");
         }
         
         
+        // Validate like count range
+        let val = self.like_count;
+        if !(0.0..=2147483647.0).contains(&(val as f64)) {
+            errors.push("like_count", "range", "like count must be between 0 and 2147483647");
+        }
         
+        // Validate comment count range
+        let val = self.comment_count;
+        if !(0.0..=2147483647.0).contains(&(val as f64)) {
+            errors.push("comment_count", "range", "comment count must be between 0 and 2147483647");
+        }
         
+        // Validate share count range
+        let val = self.share_count;
+        if !(0.0..=2147483647.0).contains(&(val as f64)) {
+            errors.push("share_count", "range", "share count must be between 0 and 2147483647");
+        }
         
         
         
@@ -69,7 +84,7 @@ impl EntPost {
         let mut cursor = Cursor::new(&tao_obj.data);
         let mut protocol = TCompactInputProtocol::new(&mut cursor);
         let mut entity = EntPost::read_from_in_protocol(&mut protocol)
-            .map_err(|e| crate::error::AppError::SerializationError(e.to_string()))?;
+            .map_err(|e| crate::error::AppError::serialization(&tao_obj.otype, tao_obj.id, e))?;
         
         Ok(Some(entity))
     }
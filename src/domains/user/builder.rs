@@ -8,7 +8,7 @@ use crate::framework::builder::has_tao::HasTao;
 use crate::infrastructure::viewer::viewer::ViewerContext;
 use crate::infrastructure::tao_core::tao_core::{TaoEntityBuilder, TaoOperations};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
-use crate::error::{AppResult, AppError};
+use crate::error::{AppResult, AppError, ValidationErrors};
 use super::entity::EntUser;
 use std::sync::Arc;
 
@@ -25,6 +25,7 @@ pub struct EntUserBuilderState {
     location: Option<String>,
     privacy_settings: Option<String>,
     pub(crate) tao: Option<Arc<dyn TaoOperations>>,
+    pub(crate) viewer_id: Option<i64>,
 }
 
 impl EntUserBuilderState {
@@ -89,25 +90,30 @@ impl EntUserBuilderState {
 impl EntBuilder for EntUser {
     type BuilderState = EntUserBuilderState;
 
-    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String> {
+    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors> {
         let current_time = current_time_millis();
 
+        let mut missing = ValidationErrors::new();
+        if state.username.is_none() {
+            missing.push("username", "required", "username is required");
+        }
+        if state.email.is_none() {
+            missing.push("email", "required", "email is required");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
         Ok(EntUser {
             id,
-            username: state.username.ok_or_else(|| 
-                "Required field 'username' not provided".to_string()
-            )?,
-            email: state.email.ok_or_else(|| 
-                "Required field 'email' not provided".to_string()
-            )?,
+            username: state.username.unwrap_or_default(),
+            email: state.email.unwrap_or_default(),
             created_time: current_time,
             full_name: state.full_name,
             bio: state.bio,
             profile_picture_url: state.profile_picture_url,
             last_active_time: state.last_active_time,
-            is_verified: state.is_verified.ok_or_else(|| 
-                "Required field 'is_verified' not provided".to_string()
-            )?,
+            is_verified: state.is_verified.unwrap_or(false),
             location: state.location,
             privacy_settings: state.privacy_settings,
         })
@@ -126,6 +132,14 @@ impl HasTao for EntUserBuilderState {
     fn set_tao(&mut self, tao: Arc<dyn TaoOperations>) {
         self.tao = Some(tao);
     }
+
+    fn get_viewer_id(&self) -> Option<i64> {
+        self.viewer_id
+    }
+
+    fn set_viewer_id(&mut self, viewer_id: Option<i64>) {
+        self.viewer_id = viewer_id;
+    }
 }
 
 impl EntUser {
@@ -138,6 +152,7 @@ impl EntUser {
         let mut builder = EntUserBuilderState::default();
         // Extract TAO from viewer context following Meta's pattern
         builder.set_tao(Arc::clone(&vc.tao));
+        builder.set_viewer_id(vc.user_id);
         builder
     }
 }
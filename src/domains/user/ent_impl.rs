@@ -4,7 +4,7 @@
 
 use std::sync::Arc;
 use crate::framework::entity::ent_trait::Entity;
-use crate::error::AppResult;
+use crate::error::{AppResult, ValidationErrors};
 use super::entity::EntUser;
 use crate::infrastructure::tao_core::tao_core::{TaoOperations, TaoObject};
 use crate::infrastructure::tao_core::tao::Tao;
@@ -12,6 +12,7 @@ use thrift::protocol::{TCompactInputProtocol, TSerializable};
 use crate::infrastructure::global_tao::get_global_tao;
 use std::io::Cursor;
 use regex;
+use crate::framework::entity::edge_payload::EdgePayload;
 use crate::domains::post::EntPost;
 use crate::domains::group::EntGroup;
 use crate::domains::page::EntPage;
@@ -24,48 +25,48 @@ impl Entity for EntUser {
         self.id
     }
 
-    fn validate(&self) -> AppResult<Vec<String>> {
-        let mut errors = Vec::new();
+    fn validate(&self) -> AppResult<ValidationErrors> {
+        let mut errors = ValidationErrors::new();
         
         // Validate username (required)
         if self.username.trim().is_empty() {
-            errors.push("username cannot be empty".to_string());
+            errors.push("username", "required", "username cannot be empty");
         }
         // Validate username min length
         if self.username.len() < 3 {
-            errors.push("username must be at least 3 characters".to_string());
+            errors.push("username", "min_length", "username must be at least 3 characters");
         }
         // Validate username max length
         if self.username.len() > 30 {
-            errors.push("username cannot exceed 30 characters".to_string());
+            errors.push("username", "max_length", "username cannot exceed 30 characters");
         }
         // Validate username pattern
         let username_regex = regex::Regex::new(r"^[a-zA-Z0-9_]+$").unwrap();
         if !username_regex.is_match(&self.username) {
-            errors.push("username format is invalid".to_string());
+            errors.push("username", "pattern", "username format is invalid");
         }
         
         // Validate email (required)
         if self.email.trim().is_empty() {
-            errors.push("email cannot be empty".to_string());
+            errors.push("email", "required", "email cannot be empty");
         }
         // Validate email pattern
         let email_regex = regex::Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap();
         if !email_regex.is_match(&self.email) {
-            errors.push("email format is invalid".to_string());
+            errors.push("email", "pattern", "email format is invalid");
         }
         
         // Validate full name max length
         if let Some(ref val) = self.full_name {
             if val.len() > 100 {
-                errors.push("full name cannot exceed 100 characters".to_string());
+                errors.push("full_name", "max_length", "full name cannot exceed 100 characters");
             }
         }
         
         // Validate bio max length
         if let Some(ref val) = self.bio {
             if val.len() > 500 {
-                errors.push("bio cannot exceed 500 characters".to_string());
+                errors.push("bio", "max_length", "bio cannot exceed 500 characters");
             }
         }
         
@@ -88,7 +89,7 @@ impl EntUser {
         let mut cursor = Cursor::new(&tao_obj.data);
         let mut protocol = TCompactInputProtocol::new(&mut cursor);
         let mut entity = EntUser::read_from_in_protocol(&mut protocol)
-            .map_err(|e| crate::error::AppError::SerializationError(e.to_string()))?;
+            .map_err(|e| crate::error::AppError::serialization(&tao_obj.otype, tao_obj.id, e))?;
         
         Ok(Some(entity))
     }
@@ -300,7 +301,7 @@ impl EntUser {
     }
     
     /// Add group association via TAO
-    pub async fn add_group(&self, target_id: i64) -> AppResult<()> {
+    pub async fn add_group(&self, target_id: i64, payload: EntUserGroupsPayload) -> AppResult<()> {
         let tao = get_global_tao()?.clone();
         // Fetch the EntGroup to ensure it exists before creating an association
         let _group = EntGroup::from_tao_object(
@@ -308,7 +309,7 @@ impl EntUser {
                 .ok_or_else(|| crate::error::AppError::NotFound(format!("EntGroup with id {} not found", target_id)))?
         ).await?;
 
-        let assoc = crate::infrastructure::tao_core::tao_core::create_tao_association(self.id(), "groups".to_string(), target_id, None);
+        let assoc = crate::infrastructure::tao_core::tao_core::create_tao_association(self.id(), "groups".to_string(), target_id, Some(payload.to_data()));
         tao.assoc_add(assoc).await?;
         Ok(())
     }
@@ -409,3 +410,55 @@ impl EntUser {
     
 }
 
+/// Payload of the groups edge, stored as the association's data
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EntUserGroupsPayload {
+    pub role: String,
+}
+
+impl EntUserGroupsPayload {
+    /// Create a payload from its required fields; the rest take their schema defaults
+    pub fn new(role: String) -> Self {
+        Self {
+            role,
+        }
+    }
+
+    /// Start building a payload field by field
+    pub fn builder() -> EntUserGroupsPayloadBuilder {
+        EntUserGroupsPayloadBuilder::default()
+    }
+}
+
+impl EdgePayload for EntUserGroupsPayload {
+    const ATYPE: &'static str = "groups";
+}
+
+/// Builder for `EntUserGroupsPayload`
+#[derive(Debug, Default)]
+pub struct EntUserGroupsPayloadBuilder {
+    role: Option<String>,
+}
+
+impl EntUserGroupsPayloadBuilder {
+    pub fn role(mut self, role: String) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// Build the payload, reporting every unset required field with the `required` code
+    pub fn build(self) -> Result<EntUserGroupsPayload, ValidationErrors> {
+        let mut missing = ValidationErrors::new();
+        if self.role.is_none() {
+            missing.push("role", "required", "role is required");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        Ok(EntUserGroupsPayload {
+            role: self.role.unwrap_or_default(),
+        })
+    }
+}
+
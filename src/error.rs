@@ -3,9 +3,81 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::fmt;
 
+use crate::infrastructure::middleware::viewer_context_middleware::current_request_id;
+
+/// One invalid field: `field` is the field's schema name, `code` a stable identifier
+/// clients can switch on (e.g. `required`, `max_length`) and `message` is for display
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+    pub code: String,
+}
+
+/// Every invalid field found while validating one entity, in the order they were checked
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidationErrors(pub Vec<FieldError>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.0.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+            code: code.to_string(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, FieldError> {
+        self.0.iter()
+    }
+
+    /// Messages grouped by field, the shape returned to HTTP clients
+    pub fn by_field(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut fields: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for error in &self.0 {
+            fields
+                .entry(error.field.as_str())
+                .or_default()
+                .push(error.message.as_str());
+        }
+        fields
+    }
+
+    /// `Err(AppError::ValidationFailed)` if any field is invalid
+    pub fn into_result(self) -> AppResult<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ValidationFailed(self))
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<&str> = self.iter().map(|error| error.message.as_str()).collect();
+        write!(f, "{}", messages.join(", "))
+    }
+}
+
 #[derive(Debug)]
 pub enum AppError {
     Database(anyhow::Error),
@@ -14,8 +86,17 @@ pub enum AppError {
     BadRequest(String),
     Internal(String),
     Validation(String),
+    /// Field-level validation failures, returned to clients per field
+    ValidationFailed(ValidationErrors),
     SerializationError(String),
     DeserializationError(String),
+    /// A stored object whose bytes could not be read as its type, e.g. because they
+    /// were written under an older schema
+    Serialization {
+        otype: String,
+        id: i64,
+        source: thrift::Error,
+    },
     TaoError(String),
     ShardError(String),
     TimeoutError(String),
@@ -29,6 +110,15 @@ pub enum AppError {
     Forbidden(String),
     TooManyRequests(String),
     ServiceUnavailable(String),
+    /// The request clashes with one still in flight, e.g. a retry reusing its idempotency key
+    Conflict(String),
+    /// An incremental sync cursor is older than the retained changes; refetch everything
+    ResyncRequired(String),
+    /// A typed read found the id, but stored as another otype; usually a caller bug
+    TypeMismatch {
+        expected: String,
+        actual: String,
+    },
 }
 
 impl fmt::Display for AppError {
@@ -40,8 +130,14 @@ impl fmt::Display for AppError {
             AppError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
             AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
             AppError::Validation(msg) => write!(f, "Validation error: {}", msg),
+            AppError::ValidationFailed(errors) => write!(f, "Validation failed: {}", errors),
             AppError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
             AppError::DeserializationError(msg) => write!(f, "Deserialization error: {}", msg),
+            AppError::Serialization { otype, id, source } => write!(
+                f,
+                "Stored {} {} could not be deserialized (corrupt, or written under an older {} schema): {}",
+                otype, id, otype, source
+            ),
             AppError::TaoError(msg) => write!(f, "TAO error: {}", msg),
             AppError::ShardError(msg) => write!(f, "Shard error: {}", msg),
             AppError::TimeoutError(msg) => write!(f, "Timeout error: {}", msg),
@@ -54,10 +150,62 @@ impl fmt::Display for AppError {
             AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             AppError::TooManyRequests(msg) => write!(f, "Too many requests: {}", msg),
             AppError::ServiceUnavailable(msg) => write!(f, "Service unavailable: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            AppError::ResyncRequired(msg) => write!(f, "Resync required: {}", msg),
+            AppError::TypeMismatch { expected, actual } => {
+                write!(f, "Type mismatch: expected {}, found {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl AppError {
+    /// Failure to deserialize the stored object `id` as `otype`, logged with both so
+    /// corrupt or outdated rows can be found
+    pub fn serialization(otype: &str, id: i64, source: thrift::Error) -> Self {
+        tracing::error!("Failed to deserialize stored {} {}: {}", otype, id, source);
+        AppError::Serialization {
+            otype: otype.to_string(),
+            id,
+            source,
+        }
+    }
+
+    /// Stable machine-readable identifier of the error kind, returned as `error.code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) | AppError::DatabaseError(_) => "database_error",
+            AppError::NotFound(_) => "not_found",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Internal(_) => "internal_error",
+            AppError::Validation(_) => "validation_error",
+            AppError::ValidationFailed(_) => "validation_failed",
+            AppError::SerializationError(_) => "serialization_error",
+            AppError::DeserializationError(_) => "deserialization_error",
+            AppError::Serialization { .. } => "corrupt_object",
+            AppError::TaoError(_) => "tao_error",
+            AppError::ShardError(_) => "shard_unavailable",
+            AppError::TimeoutError(_) => "timeout",
+            AppError::ConfigurationError(_) => "configuration_error",
+            AppError::IdGenerationError(_) => "id_generation_error",
+            AppError::StorageError(_) => "storage_error",
+            AppError::TransactionError(_) => "transaction_error",
+            AppError::Thrift(_) => "thrift_error",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::TooManyRequests(_) => "too_many_requests",
+            AppError::ServiceUnavailable(_) => "service_unavailable",
+            AppError::Conflict(_) => "conflict",
+            AppError::ResyncRequired(_) => "resync_required",
+            AppError::TypeMismatch { .. } => "type_mismatch",
         }
     }
 }
 
+/// Every error is returned as
+/// `{"error": {"code": "not_found", "message": "...", "request_id": "req-..."}}`.
+/// `request_id` is null for responses produced outside `viewer_context_middleware`, and
+/// `validation_failed` errors also carry `fields` and `errors` with the per-field details.
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match &self {
@@ -85,8 +233,16 @@ impl IntoResponse for AppError {
                 )
             }
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::ValidationFailed(errors) => (
+                StatusCode::BAD_REQUEST,
+                format!("Validation failed: {}", errors),
+            ),
             AppError::SerializationError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             AppError::DeserializationError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
+            AppError::Serialization { otype, id, .. } => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Stored {} {} could not be read", otype, id),
+            ),
             AppError::TaoError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             AppError::ShardError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
             AppError::TimeoutError(msg) => (StatusCode::REQUEST_TIMEOUT, msg.clone()),
@@ -105,14 +261,25 @@ impl IntoResponse for AppError {
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
             AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
             AppError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::ResyncRequired(msg) => (StatusCode::GONE, msg.clone()),
+            AppError::TypeMismatch { expected, actual } => (
+                StatusCode::BAD_REQUEST,
+                format!("Expected a {}, found a {}", expected, actual),
+            ),
         };
 
-        let body = Json(json!({
-            "error": error_message,
-            "status": status.as_u16()
-        }));
+        let mut error = json!({
+            "code": self.code(),
+            "message": error_message,
+            "request_id": current_request_id(),
+        });
+        if let AppError::ValidationFailed(errors) = &self {
+            error["fields"] = json!(errors.by_field());
+            error["errors"] = json!(errors);
+        }
 
-        (status, body).into_response()
+        (status, Json(json!({ "error": error }))).into_response()
     }
 }
 
@@ -129,3 +296,54 @@ impl From<thrift::Error> for AppError {
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::post::EntPost;
+    use crate::framework::entity::ent_trait::Entity;
+
+    #[tokio::test]
+    async fn test_multi_field_validation_failure_reports_each_field() {
+        let post = EntPost::new(
+            1,
+            2,
+            "   ".to_string(),
+            None,
+            1_000,
+            None,
+            "text".to_string(),
+            None,
+            -1,
+            0,
+            -5,
+            None,
+            None,
+        );
+
+        let err = post.validate().unwrap().into_result().unwrap_err();
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "validation_failed");
+        assert_eq!(
+            body["error"]["fields"],
+            json!({
+                "content": ["content cannot be empty"],
+                "like_count": ["like count must be between 0 and 2147483647"],
+                "share_count": ["share count must be between 0 and 2147483647"],
+            })
+        );
+        let codes: Vec<&str> = body["error"]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["code"].as_str().unwrap())
+            .collect();
+        assert_eq!(codes, vec!["required", "range", "range"]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::ValidationErrors;
 use crate::framework::entity::ent_trait::Entity;
 use crate::framework::builder::has_tao::HasTao;
 
@@ -9,8 +10,22 @@ pub trait EntBuilder: Entity + Sized + Send + Sync {
 
     /// Build the entity with a given ID and builder state.
     /// This method is called by TAO after ID generation.
-    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String>;
+    /// Every unset required field is reported, each with the `required` code.
+    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors>;
 
     /// Returns the type name of the entity.
     fn entity_type() -> &'static str;
+
+    /// Edge recording who created the entity, if the schema declares an owner edge.
+    fn owner_edge() -> Option<OwnerEdge> {
+        None
+    }
+}
+
+/// Authorship edge added by TAO when an entity is created by a logged-in viewer.
+/// `atype` points from the entity to the viewer; `inverse`, if set, points back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwnerEdge {
+    pub atype: &'static str,
+    pub inverse: Option<&'static str>,
 }
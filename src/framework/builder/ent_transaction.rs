@@ -0,0 +1,231 @@
+// Ent Transaction - create entities and the edges around them as one atomic write
+// Ids are generated up front on one shard so a single database transaction covers the batch
+
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::{AppError, AppResult};
+use crate::framework::builder::ent_builder::EntBuilder;
+use crate::framework::builder::has_tao::HasTao;
+use crate::framework::ent_hooks;
+use crate::infrastructure::tao_core::tao_core::{
+    current_time_millis, TaoAssociation, TaoId, TaoOperations, TaoWriteBatch,
+};
+
+/// Collects entities and associations and writes them with `TaoOperations::write_batch`.
+/// The first entity is placed on its viewer's shard and every later one next to it, so
+/// entities, their owner edges and edges added from them are co-located. Nothing is
+/// written before `commit`; dropping the transaction discards it.
+pub struct EntTransaction {
+    tao: Arc<dyn TaoOperations>,
+    batch: TaoWriteBatch,
+    /// First id handed out, owner of every later one
+    anchor: Option<TaoId>,
+    /// After-create hooks of the staged entities, run once the batch has committed
+    after_create: Vec<BoxFuture<'static, ()>>,
+}
+
+impl fmt::Debug for EntTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntTransaction")
+            .field("batch", &self.batch)
+            .field("anchor", &self.anchor)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EntTransaction {
+    pub fn new(tao: Arc<dyn TaoOperations>) -> Self {
+        Self {
+            tao,
+            batch: TaoWriteBatch::default(),
+            anchor: None,
+            after_create: Vec::new(),
+        }
+    }
+
+    /// Build, hook and validate an entity and stage it with its owner edges. Its id is
+    /// generated now, so the returned entity can be used to add edges to it.
+    pub async fn create_entity<E: EntBuilder>(
+        &mut self,
+        mut state: E::BuilderState,
+    ) -> AppResult<E> {
+        state.set_tao(Arc::clone(&self.tao));
+        let viewer_id = state.get_viewer_id();
+        let id = self.tao.generate_id(self.anchor.or(viewer_id)).await?;
+        self.anchor.get_or_insert(id);
+
+        let mut entity = E::build(state, id).map_err(AppError::ValidationFailed)?;
+        ent_hooks::run_before_create(&mut entity).await?;
+        entity.validate()?.into_result()?;
+
+        let data = entity.serialize_to_bytes()?;
+        self.batch
+            .objects
+            .push((id, <E as EntBuilder>::entity_type().to_string(), data));
+
+        if let (Some(owner_edge), Some(viewer_id)) = (E::owner_edge(), viewer_id) {
+            let time = current_time_millis();
+            self.batch.associations.push(TaoAssociation {
+                id1: id,
+                atype: owner_edge.atype.to_string(),
+                id2: viewer_id,
+                time,
+                data: None,
+                subtype: None,
+            });
+            if let Some(inverse) = owner_edge.inverse {
+                self.batch.associations.push(TaoAssociation {
+                    id1: viewer_id,
+                    atype: inverse.to_string(),
+                    id2: id,
+                    time,
+                    data: None,
+                    subtype: None,
+                });
+            }
+        }
+
+        let created = entity.clone();
+        self.after_create.push(Box::pin(async move {
+            ent_hooks::run_after_create(&created).await
+        }));
+        Ok(entity)
+    }
+
+    /// Stage an association. Its id1 must be on the transaction's shard.
+    pub fn add_assoc(&mut self, assoc: TaoAssociation) -> &mut Self {
+        self.batch.associations.push(assoc);
+        self
+    }
+
+    /// Write everything staged in one database transaction. If any part fails, or the
+    /// batch spans shards, nothing is written.
+    pub async fn commit(self) -> AppResult<()> {
+        self.tao.write_batch(self.batch).await?;
+        for after_create in self.after_create {
+            after_create.await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::post::builder::EntPostBuilderState;
+    use crate::domains::post::EntPost;
+    use crate::framework::entity::ent_trait::Entity;
+    use crate::infrastructure::association_registry::AssociationRegistry;
+    use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+    use crate::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
+    use crate::infrastructure::shard_topology::{ShardHealth, ShardInfo};
+    use crate::infrastructure::tao_core::tao_core::{TaoCore, TaoEntityBuilder};
+
+    async fn sqlite_tao(shards: u16) -> (Arc<TaoQueryRouter>, Arc<dyn TaoOperations>) {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        for shard_id in 0..shards {
+            let shard_info = ShardInfo {
+                shard_id,
+                connection_string: "sqlite::memory:".to_string(),
+                region: "local".to_string(),
+                health: ShardHealth::Healthy,
+                replicas: vec![],
+                last_health_check: current_time_millis(),
+                load_factor: 0.0,
+            };
+            let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+            query_router.add_shard(shard_info, database).await.unwrap();
+        }
+        let tao = Arc::new(TaoCore::new(
+            query_router.clone(),
+            Arc::new(AssociationRegistry::new()),
+        ));
+        (query_router, tao)
+    }
+
+    fn mention(post_id: TaoId, user_id: TaoId) -> TaoAssociation {
+        TaoAssociation {
+            id1: post_id,
+            atype: "mentions".to_string(),
+            id2: user_id,
+            time: current_time_millis(),
+            data: None,
+            subtype: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_edge_rolls_back_the_whole_transaction() {
+        let (_, tao) = sqlite_tao(1).await;
+        let mut txn = tao.transaction();
+        let post = txn
+            .create_entity::<EntPost>(
+                EntPostBuilderState::default()
+                    .author_id(1)
+                    .content("hello @bob".to_string()),
+            )
+            .await
+            .unwrap();
+        txn.add_assoc(mention(post.id(), 2));
+        txn.commit().await.unwrap();
+        assert!(tao.obj_exists(post.id()).await.unwrap());
+        assert!(tao
+            .assoc_exists(post.id(), "mentions".to_string(), 2)
+            .await
+            .unwrap());
+
+        // The database rejects the mention edge after the post has been written
+        tao.execute_query(
+            "CREATE TRIGGER reject_mentions BEFORE INSERT ON tao_associations \
+             WHEN NEW.atype = 'mentions' BEGIN SELECT RAISE(ABORT, 'mention rejected'); END"
+                .to_string(),
+        )
+        .await
+        .unwrap();
+
+        let mut txn = tao.transaction();
+        let post = txn
+            .create_entity::<EntPost>(
+                EntPostBuilderState::default()
+                    .author_id(1)
+                    .content("hello again @bob".to_string()),
+            )
+            .await
+            .unwrap();
+        txn.add_assoc(mention(post.id(), 2));
+        let result = txn.commit().await;
+        assert!(matches!(result, Err(AppError::DatabaseError(_))));
+        assert!(!tao.obj_exists(post.id()).await.unwrap());
+        assert!(!tao
+            .assoc_exists(post.id(), "mentions".to_string(), 2)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_cross_shard_edges_are_rejected_before_writing() {
+        let (query_router, tao) = sqlite_tao(2).await;
+        let mut txn = EntTransaction::new(tao.clone());
+        let post = txn
+            .create_entity::<EntPost>(
+                EntPostBuilderState::default()
+                    .author_id(1)
+                    .content("hello".to_string()),
+            )
+            .await
+            .unwrap();
+
+        // An edge stored on an object that lives on the other shard
+        let other_shard = 1 - query_router.get_shard_for_object(post.id());
+        let elsewhere = query_router
+            .generate_tao_id_on_shard(other_shard)
+            .await
+            .unwrap();
+        txn.add_assoc(mention(elsewhere, 2));
+        let result = txn.commit().await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert!(!tao.obj_exists(post.id()).await.unwrap());
+    }
+}
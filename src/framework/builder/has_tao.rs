@@ -4,4 +4,11 @@ use crate::infrastructure::tao_core::tao_core::TaoOperations;
 pub trait HasTao: Send + Sync {
     fn get_tao(&self) -> Option<Arc<dyn TaoOperations>>;
     fn set_tao(&mut self, tao: Arc<dyn TaoOperations>);
+
+    /// User id of the viewer creating the entity, used for its owner edge
+    fn get_viewer_id(&self) -> Option<i64> {
+        None
+    }
+
+    fn set_viewer_id(&mut self, _viewer_id: Option<i64>) {}
 }
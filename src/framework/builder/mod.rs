@@ -1,3 +1,4 @@
 pub mod ent_builder;
+pub mod ent_transaction;
 pub mod has_tao;
 pub mod ergonomic_builder;
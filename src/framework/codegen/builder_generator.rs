@@ -1,16 +1,16 @@
 // Unified Builder pattern generator - implements EntBuilder directly on entities
 use super::utils;
-use crate::framework::schema::ent_schema::{EntityType, FieldDefinition, SchemaRegistry};
+use crate::framework::schema::ent_schema::{
+    EdgeDefinition, EdgeType, EntityType, FieldDefault, FieldDefinition, SchemaRegistry,
+};
 
 pub struct BuilderGenerator<'a> {
-    _registry: &'a SchemaRegistry,
+    registry: &'a SchemaRegistry,
 }
 
 impl<'a> BuilderGenerator<'a> {
     pub fn new(registry: &'a SchemaRegistry) -> Self {
-        Self {
-            _registry: registry,
-        }
+        Self { registry }
     }
 
     /// Generate builder state struct and EntBuilder implementation for entity
@@ -33,7 +33,8 @@ impl<'a> BuilderGenerator<'a> {
         ));
 
         // Generate imports
-        builder_content.push_str(&self.generate_imports(&struct_name));
+        let owner_edge = self.owner_edge(entity_type);
+        builder_content.push_str(&self.generate_imports(&struct_name, owner_edge.is_some()));
 
         // Generate builder state struct
         builder_content.push_str(&self.generate_builder_state_struct(&state_name, fields)?);
@@ -68,20 +69,25 @@ impl<'a> BuilderGenerator<'a> {
     }
 
     /// Generate necessary imports for builder
-    fn generate_imports(&self, struct_name: &str) -> String {
+    fn generate_imports(&self, struct_name: &str, has_owner_edge: bool) -> String {
+        let ent_builder_import = if has_owner_edge {
+            "{EntBuilder, OwnerEdge}"
+        } else {
+            "EntBuilder"
+        };
         format!(
             r#"use crate::framework::entity::ent_trait::Entity;
-use crate::framework::builder::ent_builder::EntBuilder;
+use crate::framework::builder::ent_builder::{};
 use crate::framework::builder::has_tao::HasTao;
 use crate::infrastructure::viewer::viewer::ViewerContext;
 use crate::infrastructure::tao_core::tao_core::{{TaoEntityBuilder, TaoOperations}};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
-use crate::error::{{AppResult, AppError}};
+use crate::error::{{AppResult, AppError, ValidationErrors}};
 use super::entity::{};
 use std::sync::Arc;
 
 "#,
-            struct_name
+            ent_builder_import, struct_name
         )
     }
 
@@ -104,6 +110,7 @@ use std::sync::Arc;
         }
         state_struct.push_str("    pub(crate) tao: Option<Arc<dyn TaoOperations>>,
 ");
+        state_struct.push_str("    pub(crate) viewer_id: Option<i64>,\n");
         state_struct.push_str("}\n\n");
         Ok(state_struct)
     }
@@ -180,10 +187,28 @@ use std::sync::Arc;
 
         // Generate build method
         impl_block.push_str(
-            "    fn build(state: Self::BuilderState, id: i64) -> Result<Self, String> {\n",
+            "    fn build(state: Self::BuilderState, id: i64) -> Result<Self, ValidationErrors> {\n",
         );
         impl_block.push_str("        let current_time = current_time_millis();\n\n");
 
+        // Report every unset required field at once rather than failing on the first
+        let required: Vec<&FieldDefinition> = fields
+            .iter()
+            .filter(|field| Self::is_required_input(field))
+            .collect();
+        if !required.is_empty() {
+            impl_block.push_str("        let mut missing = ValidationErrors::new();\n");
+            for field in &required {
+                impl_block.push_str(&format!(
+                    "        if state.{}.is_none() {{\n            missing.push(\"{}\", \"required\", \"{} is required\");\n        }}\n",
+                    field.name, field.name, field.name
+                ));
+            }
+            impl_block.push_str("        if !missing.is_empty() {\n");
+            impl_block.push_str("            return Err(missing);\n");
+            impl_block.push_str("        }\n\n");
+        }
+
         impl_block.push_str(&format!("        Ok({} {{\n", struct_name));
         impl_block.push_str("            id,\n");
 
@@ -205,23 +230,37 @@ use std::sync::Arc;
                     }
                 }
                 _ => {
-                    if field.optional {
-                        impl_block.push_str(&format!(
-                            "            {}: state.{},\n",
-                            field.name, field.name
-                        ));
-                    } else {
-                        impl_block.push_str(&format!(
-                            "            {}: state.{}.ok_or_else(|| 
-",
-                            field.name, field.name
-                        ));
-                        impl_block.push_str(&format!(
-                            "                \"Required field '{}' not provided\".to_string()\n",
-                            field.name
-                        ));
-                        impl_block.push_str("            )?,\n");
-                    }
+                    // Literal defaults are cheap; anything else is only built when needed
+                    let lazy = matches!(
+                        field.default,
+                        Some(FieldDefault::String(_)) | Some(FieldDefault::Function(_))
+                    );
+                    let value = match (&field.default, field.optional) {
+                        (Some(default), true) if lazy => format!(
+                            "state.{}.or_else(|| Some({}))",
+                            field.name,
+                            Self::default_expr(default)
+                        ),
+                        (Some(default), true) => format!(
+                            "state.{}.or(Some({}))",
+                            field.name,
+                            Self::default_expr(default)
+                        ),
+                        (Some(default), false) if lazy => format!(
+                            "state.{}.unwrap_or_else(|| {})",
+                            field.name,
+                            Self::default_expr(default)
+                        ),
+                        (Some(default), false) => format!(
+                            "state.{}.unwrap_or({})",
+                            field.name,
+                            Self::default_expr(default)
+                        ),
+                        (None, true) => format!("state.{}", field.name),
+                        // Presence was checked above
+                        (None, false) => format!("state.{}.unwrap_or_default()", field.name),
+                    };
+                    impl_block.push_str(&format!("            {}: {},\n", field.name, value));
                 }
             }
         }
@@ -235,10 +274,57 @@ use std::sync::Arc;
         impl_block.push_str(&format!("        \"{}\"\n", entity_type_str));
         impl_block.push_str("    }\n");
 
+        // Generate owner_edge method when the schema marks an authorship edge
+        if let Some(edge) = self.owner_edge(entity_type) {
+            // A `From` edge names the inverse stored on the owner; a `To` edge only has one if bidirectional
+            let inverse = match (&edge.edge_type, &edge.inverse_name) {
+                (EdgeType::From, Some(name)) => format!("Some(\"{}\")", name),
+                (EdgeType::To, Some(name)) if edge.bidirectional => format!("Some(\"{}\")", name),
+                _ => "None".to_string(),
+            };
+            impl_block.push_str("\n    fn owner_edge() -> Option<OwnerEdge> {\n");
+            impl_block.push_str(&format!(
+                "        Some(OwnerEdge {{ atype: \"{}\", inverse: {} }})\n",
+                edge.name, inverse
+            ));
+            impl_block.push_str("    }\n");
+        }
+
         impl_block.push_str("}\n\n");
         Ok(impl_block)
     }
 
+    /// The schema edge marked with `owner_edge()`, if any
+    fn owner_edge(&self, entity_type: &EntityType) -> Option<&'a EdgeDefinition> {
+        self.registry
+            .get_edges(entity_type)
+            .and_then(|edges| edges.iter().find(|edge| edge.owner))
+    }
+
+    /// Whether build() must fail when the caller leaves this field unset.
+    /// Timestamps are filled in by build() itself.
+    fn is_required_input(field: &FieldDefinition) -> bool {
+        !field.optional
+            && field.default.is_none()
+            && !matches!(
+                field.name.as_str(),
+                "id" | "created_time" | "updated_time" | "time_updated"
+            )
+    }
+
+    /// Rust expression producing a schema-declared default
+    pub(super) fn default_expr(default: &FieldDefault) -> String {
+        match default {
+            FieldDefault::String(value) => format!("{:?}.to_string()", value),
+            FieldDefault::Int(value) => value.to_string(),
+            FieldDefault::Int64(value) => value.to_string(),
+            FieldDefault::Float(value) => format!("{:?}", value),
+            FieldDefault::Bool(value) => value.to_string(),
+            FieldDefault::Function(name) if name == "now" => "current_time".to_string(),
+            FieldDefault::Function(name) => format!("{}()", name),
+        }
+    }
+
     /// Generate create() static method for entity
     fn generate_entity_create_method(
         &self,
@@ -256,6 +342,7 @@ use std::sync::Arc;
         create_method.push_str(&format!("        let mut builder = {}::default();\n", state_name));
         create_method.push_str("        // Extract TAO from viewer context following Meta's pattern\n");
         create_method.push_str("        builder.set_tao(Arc::clone(&vc.tao));\n");
+        create_method.push_str("        builder.set_viewer_id(vc.user_id);\n");
         create_method.push_str("        builder\n");
         create_method.push_str("    }\n");
 
@@ -272,8 +359,58 @@ use std::sync::Arc;
         impl_block.push_str("    }\n\n");
         impl_block.push_str("    fn set_tao(&mut self, tao: Arc<dyn TaoOperations>) {\n");
         impl_block.push_str("        self.tao = Some(tao);\n");
+        impl_block.push_str("    }\n\n");
+        impl_block.push_str("    fn get_viewer_id(&self) -> Option<i64> {\n");
+        impl_block.push_str("        self.viewer_id\n");
+        impl_block.push_str("    }\n\n");
+        impl_block.push_str("    fn set_viewer_id(&mut self, viewer_id: Option<i64>) {\n");
+        impl_block.push_str("        self.viewer_id = viewer_id;\n");
         impl_block.push_str("    }\n");
         impl_block.push_str("}\n\n");
         Ok(impl_block)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::domains::post::{EntPost, EntPostBuilderState};
+    use crate::framework::builder::ent_builder::EntBuilder;
+
+    #[test]
+    fn test_generated_build_lists_missing_required_fields() {
+        let err = EntPost::build(EntPostBuilderState::default(), 1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "author_id is required, content is required"
+        );
+        assert!(err.iter().all(|error| error.code == "required"));
+
+        let state = EntPostBuilderState::default().content("hello".to_string());
+        let err = EntPost::build(state, 1).unwrap_err();
+        assert_eq!(err.by_field().keys().copied().collect::<Vec<_>>(), vec!["author_id"]);
+    }
+
+    #[test]
+    fn test_generated_build_applies_schema_defaults() {
+        let state = EntPostBuilderState::default()
+            .author_id(7)
+            .content("hello".to_string());
+        let post = EntPost::build(state, 1).unwrap();
+
+        assert_eq!(post.post_type, "text");
+        assert_eq!(post.visibility.as_deref(), Some("public"));
+        assert_eq!(post.like_count, 0);
+        // Optional fields without a declared default stay unset
+        assert_eq!(post.media_url, None);
+
+        // Explicit values win over defaults
+        let state = EntPostBuilderState::default()
+            .author_id(7)
+            .content("hello".to_string())
+            .visibility("friends".to_string())
+            .like_count(3);
+        let post = EntPost::build(state, 1).unwrap();
+        assert_eq!(post.visibility.as_deref(), Some("friends"));
+        assert_eq!(post.like_count, 3);
+    }
+}
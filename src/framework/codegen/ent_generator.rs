@@ -1,7 +1,8 @@
 // Ent trait implementation generator
+use super::builder_generator::BuilderGenerator;
 use super::utils;
 use crate::framework::schema::ent_schema::{
-    EdgeDefinition, EntityType, FieldDefinition, SchemaRegistry,
+    EdgeDefinition, EntityType, FieldDefault, FieldDefinition, SchemaRegistry,
 };
 
 pub struct EntGenerator<'a> {
@@ -52,6 +53,9 @@ impl<'a> EntGenerator<'a> {
         // Close the impl block
         ent_content.push_str("}\n\n");
 
+        // Generate typed payloads for edges that declare one
+        ent_content.push_str(&self.generate_payload_structs(&struct_name, edges));
+
         // Write to file
         std::fs::write(&ent_impl_path, ent_content)
             .map_err(|e| format!("Failed to write ent_impl file {}: {}", ent_impl_path, e))?;
@@ -63,7 +67,7 @@ impl<'a> EntGenerator<'a> {
     fn generate_imports(&self, struct_name: &str, edges: &[EdgeDefinition]) -> String {
         let mut imports = String::from("use std::sync::Arc;\n");
         imports.push_str("use crate::framework::entity::ent_trait::Entity;\n");
-        imports.push_str("use crate::error::AppResult;\n");
+        imports.push_str("use crate::error::{AppResult, ValidationErrors};\n");
         imports.push_str(&format!("use super::entity::{};\n", struct_name));
         imports.push_str(
             "use crate::infrastructure::tao_core::tao_core::{TaoOperations, TaoObject};\n",
@@ -73,6 +77,9 @@ impl<'a> EntGenerator<'a> {
         imports.push_str("use crate::infrastructure::global_tao::get_global_tao;\n");
         imports.push_str("use std::io::Cursor;\n");
         imports.push_str("use regex;\n");
+        if edges.iter().any(|edge| !edge.payload.is_empty()) {
+            imports.push_str("use crate::framework::entity::edge_payload::EdgePayload;\n");
+        }
 
         // Add cross-entity imports for edge traversal, excluding current entity to avoid duplicates
         let current_entity_type = self.entity_type_from_struct_name(struct_name);
@@ -137,8 +144,8 @@ impl<'a> EntGenerator<'a> {
         impl_block.push_str("    fn id(&self) -> i64 {\n");
         impl_block.push_str("        self.id\n");
         impl_block.push_str("    }\n\n");
-        impl_block.push_str("    fn validate(&self) -> AppResult<Vec<String>> {\n");
-        impl_block.push_str("        let mut errors = Vec::new();\n");
+        impl_block.push_str("    fn validate(&self) -> AppResult<ValidationErrors> {\n");
+        impl_block.push_str("        let mut errors = ValidationErrors::new();\n");
         impl_block.push_str("        \n");
 
         // Generate comprehensive validation based on schema
@@ -162,8 +169,8 @@ impl<'a> EntGenerator<'a> {
                             field.name
                         ));
                         impl_block.push_str(&format!(
-                            "            errors.push(\"{} cannot be empty\".to_string());\n",
-                            field_display
+                            "            errors.push(\"{}\", \"required\", \"{} cannot be empty\");\n",
+                            field.name, field_display
                         ));
                         impl_block.push_str("        }\n");
                     }
@@ -189,7 +196,7 @@ impl<'a> EntGenerator<'a> {
                             ));
                             impl_block
                                 .push_str(&format!("            if val.len() < {} {{\n", min));
-                            impl_block.push_str(&format!("                errors.push(\"{} must be at least {} characters\".to_string());\n", field_display, min));
+                            impl_block.push_str(&format!("                errors.push(\"{}\", \"min_length\", \"{} must be at least {} characters\");\n", field.name, field_display, min));
                             impl_block.push_str("            }\n");
                             impl_block.push_str("        }\n");
                         } else {
@@ -201,7 +208,7 @@ impl<'a> EntGenerator<'a> {
                                 "        if self.{}.len() < {} {{\n",
                                 field.name, min
                             ));
-                            impl_block.push_str(&format!("            errors.push(\"{} must be at least {} characters\".to_string());\n", field_display, min));
+                            impl_block.push_str(&format!("            errors.push(\"{}\", \"min_length\", \"{} must be at least {} characters\");\n", field.name, field_display, min));
                             impl_block.push_str("        }\n");
                         }
                     }
@@ -217,7 +224,7 @@ impl<'a> EntGenerator<'a> {
                             ));
                             impl_block
                                 .push_str(&format!("            if val.len() > {} {{\n", max));
-                            impl_block.push_str(&format!("                errors.push(\"{} cannot exceed {} characters\".to_string());\n", field_display, max));
+                            impl_block.push_str(&format!("                errors.push(\"{}\", \"max_length\", \"{} cannot exceed {} characters\");\n", field.name, field_display, max));
                             impl_block.push_str("            }\n");
                             impl_block.push_str("        }\n");
                         } else {
@@ -229,7 +236,7 @@ impl<'a> EntGenerator<'a> {
                                 "        if self.{}.len() > {} {{\n",
                                 field.name, max
                             ));
-                            impl_block.push_str(&format!("            errors.push(\"{} cannot exceed {} characters\".to_string());\n", field_display, max));
+                            impl_block.push_str(&format!("            errors.push(\"{}\", \"max_length\", \"{} cannot exceed {} characters\");\n", field.name, field_display, max));
                             impl_block.push_str("        }\n");
                         }
                     }
@@ -250,7 +257,7 @@ impl<'a> EntGenerator<'a> {
                                 "            if !{}_regex.is_match(val) {{\n",
                                 field.name
                             ));
-                            impl_block.push_str(&format!("                errors.push(\"{} format is invalid\".to_string());\n", field_display));
+                            impl_block.push_str(&format!("                errors.push(\"{}\", \"pattern\", \"{} format is invalid\");\n", field.name, field_display));
                             impl_block.push_str("            }\n");
                             impl_block.push_str("        }\n");
                         } else {
@@ -259,13 +266,52 @@ impl<'a> EntGenerator<'a> {
                                 field.name, field.name
                             ));
                             impl_block.push_str(&format!(
-                                "            errors.push(\"{} format is invalid\".to_string());\n",
-                                field_display
+                                "            errors.push(\"{}\", \"pattern\", \"{} format is invalid\");\n",
+                                field.name, field_display
+                            ));
+                            impl_block.push_str("        }\n");
+                        }
+                    }
+                    crate::framework::schema::ent_schema::FieldValidator::Range(min, max) => {
+                        impl_block
+                            .push_str(&format!("        // Validate {} range\n", field_display));
+                        let check = format!("!({:?}..={:?}).contains(&(val as f64))", min, max);
+                        let message = format!(
+                            "errors.push(\"{}\", \"range\", \"{} must be between {} and {}\");",
+                            field.name, field_display, min, max
+                        );
+                        if field.optional {
+                            impl_block.push_str(&format!(
+                                "        if let Some(val) = self.{} {{\n",
+                                field.name
                             ));
+                            impl_block.push_str(&format!("            if {} {{\n", check));
+                            impl_block.push_str(&format!("                {}\n", message));
+                            impl_block.push_str("            }\n");
+                            impl_block.push_str("        }\n");
+                        } else {
+                            impl_block
+                                .push_str(&format!("        let val = self.{};\n", field.name));
+                            impl_block.push_str(&format!("        if {} {{\n", check));
+                            impl_block.push_str(&format!("            {}\n", message));
                             impl_block.push_str("        }\n");
                         }
                     }
-                    _ => {} // Handle other validators as needed
+                    crate::framework::schema::ent_schema::FieldValidator::Custom(name) => {
+                        impl_block.push_str(&format!(
+                            "        // Validate {} with custom validator '{}'\n",
+                            field_display, name
+                        ));
+                        impl_block.push_str(&format!(
+                            "        if let Some(error) = crate::framework::ent_hooks::run_custom_validator(\"{}\", \"{}\", &serde_json::json!(self.{})) {{\n",
+                            name, field_display, field.name
+                        ));
+                        impl_block.push_str(&format!(
+                            "            errors.push(\"{}\", \"{}\", error);\n",
+                            field.name, name
+                        ));
+                        impl_block.push_str("        }\n");
+                    }
                 }
             }
 
@@ -301,7 +347,7 @@ impl<'a> EntGenerator<'a> {
             "        let mut entity = {}::read_from_in_protocol(&mut protocol)\n",
             struct_name
         ));
-        method_block.push_str("            .map_err(|e| crate::error::AppError::SerializationError(e.to_string()))?;\n");
+        method_block.push_str("            .map_err(|e| crate::error::AppError::serialization(&tao_obj.otype, tao_obj.id, e))?;\n");
         method_block.push_str("        \n");
         method_block.push_str("        Ok(Some(entity))\n");
         method_block.push_str("    }\n\n");
@@ -389,9 +435,20 @@ impl<'a> EntGenerator<'a> {
                         "    /// Add {} association via TAO\n",
                         edge.name.trim_end_matches('s').replace('_', " ")
                     ));
+                    let (payload_param, payload_data) = if edge.payload.is_empty() {
+                        (String::new(), "None")
+                    } else {
+                        (
+                            format!(
+                                ", payload: {}",
+                                Self::payload_struct_name(struct_name, edge)
+                            ),
+                            "Some(payload.to_data())",
+                        )
+                    };
                     edge_methods.push_str(&format!(
-                        "    pub async fn {}(&self, target_id: i64) -> AppResult<()> {{\n",
-                        add_method
+                        "    pub async fn {}(&self, target_id: i64{}) -> AppResult<()> {{\n",
+                        add_method, payload_param
                     )); // Removed tao parameter
                     edge_methods.push_str("        let tao = get_global_tao()?.clone();\n"); // Get global tao instance
                     edge_methods.push_str(&format!("        // Fetch the {} to ensure it exists before creating an association\n", return_type));
@@ -404,7 +461,7 @@ impl<'a> EntGenerator<'a> {
                     edge_methods.push_str(&format!("                .ok_or_else(|| crate::error::AppError::NotFound(format!(\"{} with id {{}} not found\", target_id)))?\n", return_type));
                     edge_methods.push_str("        ).await?;\n");
                     edge_methods.push('\n');
-                    edge_methods.push_str(&format!("        let assoc = crate::infrastructure::tao_core::tao_core::create_tao_association(self.id(), \"{}\".to_string(), target_id, None);\n", edge.name));
+                    edge_methods.push_str(&format!("        let assoc = crate::infrastructure::tao_core::tao_core::create_tao_association(self.id(), \"{}\".to_string(), target_id, {});\n", edge.name, payload_data));
                     edge_methods.push_str("        tao.assoc_add(assoc).await?;\n");
                     edge_methods.push_str("        Ok(())\n");
                     edge_methods.push_str("    }\n");
@@ -429,4 +486,258 @@ impl<'a> EntGenerator<'a> {
         }
         Ok(edge_methods)
     }
+
+    /// Name of the payload struct for `edge`, e.g. `EntUserGroupsPayload`
+    fn payload_struct_name(struct_name: &str, edge: &EdgeDefinition) -> String {
+        format!(
+            "{}{}Payload",
+            struct_name,
+            utils::snake_to_pascal(&edge.name)
+        )
+    }
+
+    /// Generate a serde struct per edge payload, with a constructor taking the required
+    /// fields, a builder, and an `EdgePayload` impl for JSON conversion to and from
+    /// association data
+    fn generate_payload_structs(&self, struct_name: &str, edges: &[EdgeDefinition]) -> String {
+        let mut payloads = String::new();
+        for edge in edges.iter().filter(|edge| !edge.payload.is_empty()) {
+            let payload_name = Self::payload_struct_name(struct_name, edge);
+            let builder_name = format!("{}Builder", payload_name);
+            let required: Vec<&FieldDefinition> = edge
+                .payload
+                .iter()
+                .filter(|field| !field.optional && field.default.is_none())
+                .collect();
+
+            payloads.push_str(&format!(
+                "/// Payload of the {} edge, stored as the association's data\n",
+                edge.name
+            ));
+            payloads.push_str(
+                "#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]\n",
+            );
+            payloads.push_str(&format!("pub struct {} {{\n", payload_name));
+            for field in &edge.payload {
+                payloads.push_str(&format!(
+                    "    pub {}: {},\n",
+                    field.name,
+                    utils::field_type_to_rust(&field.field_type, field.optional)
+                ));
+            }
+            payloads.push_str("}\n\n");
+
+            payloads.push_str(&format!("impl {} {{\n", payload_name));
+            payloads.push_str(
+                "    /// Create a payload from its required fields; the rest take their schema defaults\n",
+            );
+            let params: Vec<String> = required
+                .iter()
+                .map(|field| {
+                    format!(
+                        "{}: {}",
+                        field.name,
+                        utils::field_type_to_rust(&field.field_type, false)
+                    )
+                })
+                .collect();
+            payloads.push_str(&format!(
+                "    pub fn new({}) -> Self {{\n",
+                params.join(", ")
+            ));
+            payloads.push_str("        Self {\n");
+            for field in &edge.payload {
+                match Self::payload_default_expr(field) {
+                    Some(value) => {
+                        payloads.push_str(&format!("            {}: {},\n", field.name, value))
+                    }
+                    None => payloads.push_str(&format!("            {},\n", field.name)),
+                }
+            }
+            payloads.push_str("        }\n");
+            payloads.push_str("    }\n\n");
+
+            payloads.push_str("    /// Start building a payload field by field\n");
+            payloads.push_str(&format!("    pub fn builder() -> {} {{\n", builder_name));
+            payloads.push_str(&format!("        {}::default()\n", builder_name));
+            payloads.push_str("    }\n");
+            payloads.push_str("}\n\n");
+
+            payloads.push_str(&format!("impl EdgePayload for {} {{\n", payload_name));
+            payloads.push_str(&format!(
+                "    const ATYPE: &'static str = \"{}\";\n",
+                edge.name
+            ));
+            payloads.push_str("}\n\n");
+
+            payloads.push_str(&format!("/// Builder for `{}`\n", payload_name));
+            payloads.push_str("#[derive(Debug, Default)]\n");
+            payloads.push_str(&format!("pub struct {} {{\n", builder_name));
+            for field in &edge.payload {
+                payloads.push_str(&format!(
+                    "    {}: {},\n",
+                    field.name,
+                    utils::field_type_to_rust(&field.field_type, true)
+                ));
+            }
+            payloads.push_str("}\n\n");
+
+            payloads.push_str(&format!("impl {} {{\n", builder_name));
+            for field in &edge.payload {
+                payloads.push_str(&format!(
+                    "    pub fn {}(mut self, {}: {}) -> Self {{\n",
+                    field.name,
+                    field.name,
+                    utils::field_type_to_rust(&field.field_type, false)
+                ));
+                payloads.push_str(&format!(
+                    "        self.{} = Some({});\n",
+                    field.name, field.name
+                ));
+                payloads.push_str("        self\n");
+                payloads.push_str("    }\n\n");
+            }
+            payloads.push_str(
+                "    /// Build the payload, reporting every unset required field with the `required` code\n",
+            );
+            payloads.push_str(&format!(
+                "    pub fn build(self) -> Result<{}, ValidationErrors> {{\n",
+                payload_name
+            ));
+            if !required.is_empty() {
+                payloads.push_str("        let mut missing = ValidationErrors::new();\n");
+                for field in &required {
+                    payloads.push_str(&format!("        if self.{}.is_none() {{\n", field.name));
+                    payloads.push_str(&format!(
+                        "            missing.push(\"{}\", \"required\", \"{} is required\");\n",
+                        field.name, field.name
+                    ));
+                    payloads.push_str("        }\n");
+                }
+                payloads.push_str("        if !missing.is_empty() {\n");
+                payloads.push_str("            return Err(missing);\n");
+                payloads.push_str("        }\n\n");
+            }
+            payloads.push_str(&format!("        Ok({} {{\n", payload_name));
+            for field in &edge.payload {
+                let value = match Self::payload_default_expr(field) {
+                    _ if field.optional => format!("self.{}", field.name),
+                    Some(default) => format!("self.{}.unwrap_or_else(|| {})", field.name, default),
+                    None => format!("self.{}.unwrap_or_default()", field.name),
+                };
+                payloads.push_str(&format!("            {}: {},\n", field.name, value));
+            }
+            payloads.push_str("        })\n");
+            payloads.push_str("    }\n");
+            payloads.push_str("}\n\n");
+        }
+        payloads
+    }
+
+    /// Value a payload field takes when not given, or `None` for a required field
+    fn payload_default_expr(field: &FieldDefinition) -> Option<String> {
+        match &field.default {
+            _ if field.optional => Some("None".to_string()),
+            None => None,
+            Some(FieldDefault::Function(name)) if name == "now" => {
+                Some("crate::infrastructure::tao_core::tao_core::current_time_millis()".to_string())
+            }
+            Some(default) => Some(BuilderGenerator::default_expr(default)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::post::{EntPost, EntPostBuilderState};
+    use crate::framework::builder::ent_builder::EntBuilder;
+    use crate::framework::entity::ent_trait::Entity;
+    use crate::framework::schema::ent_schema::FieldType;
+
+    #[test]
+    fn test_generated_validate_reports_each_violation() {
+        let state = EntPostBuilderState::default()
+            .author_id(7)
+            .content("x".repeat(10_001))
+            .like_count(-1);
+        let post = EntPost::build(state, 1).unwrap();
+
+        let errors = post.validate().unwrap();
+        let reported: Vec<(&str, &str, &str)> = errors
+            .iter()
+            .map(|error| {
+                (
+                    error.field.as_str(),
+                    error.code.as_str(),
+                    error.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "content",
+                    "max_length",
+                    "content cannot exceed 10000 characters"
+                ),
+                (
+                    "like_count",
+                    "range",
+                    "like count must be between 0 and 2147483647"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_range_and_custom_validators_are_generated() {
+        let registry = SchemaRegistry::new();
+        let generator = EntGenerator::new(&registry);
+        let fields = vec![
+            FieldDefinition::new("age", FieldType::Int)
+                .optional()
+                .range(0..=120),
+            FieldDefinition::new("bio", FieldType::String).custom("no_links"),
+        ];
+
+        let code = generator
+            .generate_ent_trait_impl(&EntityType::EntUser, "EntUser", &fields)
+            .unwrap();
+        assert!(code.contains("if let Some(val) = self.age {"));
+        assert!(code.contains("age must be between 0 and 120"));
+        assert!(code
+            .contains("run_custom_validator(\"no_links\", \"bio\", &serde_json::json!(self.bio))"));
+    }
+
+    #[test]
+    fn test_payload_struct_is_generated_for_edges_declaring_one() {
+        let registry = SchemaRegistry::new();
+        let generator = EntGenerator::new(&registry);
+        let edges = vec![
+            EdgeDefinition::to("groups", EntityType::EntGroup).payload(vec![
+                FieldDefinition::new("role", FieldType::String),
+                FieldDefinition::new("muted", FieldType::Bool)
+                    .default_value(FieldDefault::Bool(false)),
+                FieldDefinition::new("note", FieldType::String).optional(),
+            ]),
+            EdgeDefinition::to("friends", EntityType::EntUser),
+        ];
+
+        let code = generator.generate_payload_structs("EntUser", &edges);
+        assert!(code.contains("pub struct EntUserGroupsPayload {"));
+        assert!(code.contains("pub note: Option<String>,"));
+        assert!(code.contains("pub fn new(role: String) -> Self {"));
+        assert!(code.contains("muted: false,"));
+        assert!(code.contains("note: None,"));
+        assert!(!code.contains("EntUserFriendsPayload"));
+
+        assert!(code.contains("impl EdgePayload for EntUserGroupsPayload {"));
+        assert!(code.contains("const ATYPE: &'static str = \"groups\";"));
+        assert!(code.contains("pub fn builder() -> EntUserGroupsPayloadBuilder {"));
+        assert!(code.contains("missing.push(\"role\", \"required\", \"role is required\");"));
+        assert!(code.contains("muted: self.muted.unwrap_or_else(|| false),"));
+        assert!(code.contains("note: self.note,"));
+    }
 }
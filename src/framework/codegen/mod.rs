@@ -80,6 +80,10 @@ impl CodeGenerator {
         }
         println!("✅ Generated Ent implementations");
 
+        // Step 10: Register every entity's deserializer for type-erased loading
+        self.generate_entity_type_registrations(&schemas)?;
+        println!("✅ Generated entity type registrations");
+
         println!("🎉 Modular codegen pipeline completed successfully!");
 
         Ok(())
@@ -281,6 +285,7 @@ impl CodeGenerator {
         for domain_name in domain_names {
             domains_mod.push_str(&format!("pub mod {};\n", domain_name));
         }
+        domains_mod.push_str("\npub mod entity_types;\n");
 
         std::fs::write("src/domains/mod.rs", domains_mod)
             .map_err(|e| format!("Failed to write domains/mod.rs: {}", e))?;
@@ -298,4 +303,60 @@ impl CodeGenerator {
         }
         Ok(())
     }
+
+    /// Generate src/domains/entity_types.rs, registering every entity with the
+    /// `EntityTypeRegistry`
+    fn generate_entity_type_registrations(
+        &self,
+        schemas: &HashMap<EntityType, (Vec<FieldDefinition>, Vec<EdgeDefinition>)>,
+    ) -> Result<(), String> {
+        std::fs::write(
+            "src/domains/entity_types.rs",
+            entity_type_registrations_content(schemas.keys()),
+        )
+        .map_err(|e| format!("Failed to write domains/entity_types.rs: {}", e))
+    }
+}
+
+/// Contents of the generated entity type registration module, sorted by otype
+fn entity_type_registrations_content<'a>(
+    entity_types: impl Iterator<Item = &'a EntityType>,
+) -> String {
+    let mut entity_types: Vec<_> = entity_types.collect();
+    entity_types.sort_by_key(|entity_type| entity_type.as_str());
+
+    let mut content = String::from("// Generated entity type registrations\n// DO NOT EDIT\n\n");
+    content.push_str("use crate::framework::entity::entity_registry::EntityTypeRegistry;\n\n");
+    content.push_str("/// Register a deserializer for every generated entity type\n");
+    content.push_str("pub fn register_entity_types(registry: &mut EntityTypeRegistry) {\n");
+    for entity_type in entity_types {
+        content.push_str(&format!(
+            "    registry.register::<crate::domains::{}::{}>();\n",
+            utils::entity_domain_name(entity_type),
+            utils::entity_struct_name(entity_type)
+        ));
+    }
+    content.push_str("}\n");
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_type_registrations_match_checked_in_module() {
+        let entity_types = [
+            EntityType::EntUser,
+            EntityType::EntPost,
+            EntityType::EntComment,
+            EntityType::EntGroup,
+            EntityType::EntPage,
+            EntityType::EntEvent,
+        ];
+        assert_eq!(
+            entity_type_registrations_content(entity_types.iter()),
+            include_str!("../../domains/entity_types.rs")
+        );
+    }
 }
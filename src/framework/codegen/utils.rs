@@ -108,6 +108,19 @@ pub fn snake_to_camel(s: &str) -> String {
     result
 }
 
+/// Convert snake_case to PascalCase (e.g., "liked_posts" -> "LikedPosts")
+pub fn snake_to_pascal(s: &str) -> String {
+    s.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            }
+        })
+        .collect()
+}
+
 /// Generate file header comment
 pub fn generate_file_header(file_type: &str, entity_type: &EntityType) -> String {
     format!(
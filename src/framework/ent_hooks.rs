@@ -3,11 +3,14 @@
 
 use crate::{
     error::{AppError, AppResult},
-    framework::schema::ent_schema::EntityType,
+    framework::{entity::ent_trait::Entity, schema::ent_schema::EntityType},
 };
 use async_trait::async_trait;
 use serde_json::Value;
+use std::any::Any;
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use tracing::warn;
 
 /// Hook context containing mutation information
 #[derive(Debug, Clone)]
@@ -89,6 +92,130 @@ impl HookRegistry {
     }
 }
 
+/// Named field validator referenced from schemas via `FieldValidator::Custom`.
+/// Receives the field name and its value, and returns an error message on failure.
+pub type CustomFieldValidator = fn(field: &str, value: &Value) -> Option<String>;
+
+fn custom_validators() -> &'static RwLock<HashMap<String, CustomFieldValidator>> {
+    static VALIDATORS: OnceLock<RwLock<HashMap<String, CustomFieldValidator>>> = OnceLock::new();
+    VALIDATORS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register a custom validator under `name`, replacing any previous one
+pub fn register_custom_validator(name: &str, validator: CustomFieldValidator) {
+    custom_validators()
+        .write()
+        .unwrap()
+        .insert(name.to_string(), validator);
+}
+
+/// Run the custom validator `name` against a field value.
+/// Called from generated `validate()`; an unregistered name is reported as an error
+/// so a typo in a schema cannot silently disable validation.
+pub fn run_custom_validator(name: &str, field: &str, value: &Value) -> Option<String> {
+    match custom_validators().read().unwrap().get(name) {
+        Some(validator) => validator(field, value),
+        None => Some(format!("{} uses unknown validator '{}'", field, name)),
+    }
+}
+
+/// Typed lifecycle callbacks for one entity type, invoked by the entity write helpers
+/// (`create_entity`, `Entity::update`, `Entity::delete`).
+/// Before-hooks run ahead of validation and may mutate the entity or abort the write
+/// by returning an error. After-hooks run once the write has committed; their errors
+/// are logged rather than returned, since the write cannot be undone.
+#[async_trait]
+pub trait EntHooks<E: Entity>: Send + Sync {
+    /// Hook name for logging
+    fn name(&self) -> &str;
+
+    async fn before_create(&self, _entity: &mut E) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn after_create(&self, _entity: &E) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn before_update(&self, _entity: &mut E) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn after_delete(&self, _entity_id: i64) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+/// Per entity type, holds a `Vec<Arc<dyn EntHooks<E>>>` for the matching `E`
+fn ent_hooks_registry() -> &'static RwLock<HashMap<&'static str, Box<dyn Any + Send + Sync>>> {
+    static HOOKS: OnceLock<RwLock<HashMap<&'static str, Box<dyn Any + Send + Sync>>>> =
+        OnceLock::new();
+    HOOKS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register lifecycle hooks for `E`. Hooks run in registration order.
+pub fn register_ent_hooks<E: Entity>(hooks: Arc<dyn EntHooks<E>>) {
+    ent_hooks_registry()
+        .write()
+        .unwrap()
+        .entry(E::ENTITY_TYPE)
+        .or_insert_with(|| Box::new(Vec::<Arc<dyn EntHooks<E>>>::new()))
+        .downcast_mut::<Vec<Arc<dyn EntHooks<E>>>>()
+        .expect("hooks registered under a mismatched entity type")
+        .push(hooks);
+}
+
+/// Snapshot of the hooks registered for `E`, so no lock is held across hook awaits
+fn ent_hooks_for<E: Entity>() -> Vec<Arc<dyn EntHooks<E>>> {
+    ent_hooks_registry()
+        .read()
+        .unwrap()
+        .get(E::ENTITY_TYPE)
+        .and_then(|hooks| hooks.downcast_ref::<Vec<Arc<dyn EntHooks<E>>>>())
+        .cloned()
+        .unwrap_or_default()
+}
+
+pub async fn run_before_create<E: Entity>(entity: &mut E) -> AppResult<()> {
+    for hook in ent_hooks_for::<E>() {
+        hook.before_create(entity).await?;
+    }
+    Ok(())
+}
+
+pub async fn run_after_create<E: Entity>(entity: &E) {
+    for hook in ent_hooks_for::<E>() {
+        if let Err(e) = hook.after_create(entity).await {
+            warn!(
+                "after_create hook '{}' failed for {}: {}",
+                hook.name(),
+                E::ENTITY_TYPE,
+                e
+            );
+        }
+    }
+}
+
+pub async fn run_before_update<E: Entity>(entity: &mut E) -> AppResult<()> {
+    for hook in ent_hooks_for::<E>() {
+        hook.before_update(entity).await?;
+    }
+    Ok(())
+}
+
+pub async fn run_after_delete<E: Entity>(entity_id: i64) {
+    for hook in ent_hooks_for::<E>() {
+        if let Err(e) = hook.after_delete(entity_id).await {
+            warn!(
+                "after_delete hook '{}' failed for {}: {}",
+                hook.name(),
+                E::ENTITY_TYPE,
+                e
+            );
+        }
+    }
+}
+
 /// Built-in hooks for common patterns
 
 /// Timestamp hook - automatically sets created/updated timestamps
@@ -285,3 +412,118 @@ pub fn create_default_hook_registry() -> HookRegistry {
 
     registry
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::post::{EntPost, EntPostBuilderState};
+    use crate::infrastructure::{
+        association_registry::AssociationRegistry,
+        database::sqlite_database::SqliteDatabase,
+        query_router::{QueryRouterConfig, TaoQueryRouter},
+        shard_topology::{ShardHealth, ShardInfo},
+        tao_core::{
+            tao::Tao,
+            tao_core::{current_time_millis, TaoCore, TaoEntityBuilder, TaoOperations},
+        },
+    };
+    use std::sync::Mutex;
+
+    async fn sqlite_tao() -> Arc<dyn TaoOperations> {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        query_router.add_shard(shard_info, database).await.unwrap();
+        let tao_core = Arc::new(TaoCore::new(
+            query_router,
+            Arc::new(AssociationRegistry::new()),
+        ));
+        Arc::new(Tao::minimal(tao_core))
+    }
+
+    /// Rejects posts mentioning "forbidden" and records every post it saw committed
+    struct ModerationHooks {
+        created: Mutex<Vec<i64>>,
+    }
+
+    #[async_trait]
+    impl EntHooks<EntPost> for ModerationHooks {
+        fn name(&self) -> &str {
+            "moderation"
+        }
+
+        async fn before_create(&self, post: &mut EntPost) -> AppResult<()> {
+            if post.content.contains("forbidden") {
+                return Err(AppError::Validation(
+                    "post rejected by moderation".to_string(),
+                ));
+            }
+            Ok(())
+        }
+
+        async fn after_create(&self, post: &EntPost) -> AppResult<()> {
+            self.created.lock().unwrap().push(post.id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ent_hooks_wrap_create() {
+        let hooks = Arc::new(ModerationHooks {
+            created: Mutex::new(Vec::new()),
+        });
+        register_ent_hooks::<EntPost>(hooks.clone());
+        let tao = sqlite_tao().await;
+
+        let post = tao
+            .create_entity::<EntPost>(
+                EntPostBuilderState::default()
+                    .author_id(1)
+                    .content("hello".to_string()),
+            )
+            .await
+            .unwrap();
+        assert!(hooks.created.lock().unwrap().contains(&post.id));
+        assert!(tao.obj_exists(post.id).await.unwrap());
+
+        let rejected = tao
+            .create_entity::<EntPost>(
+                EntPostBuilderState::default()
+                    .author_id(1)
+                    .content("something forbidden".to_string()),
+            )
+            .await;
+        assert!(matches!(rejected, Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_custom_validator_registry() {
+        register_custom_validator("no_links", |field, value| {
+            value
+                .as_str()
+                .filter(|text| text.contains("http"))
+                .map(|_| format!("{} cannot contain links", field))
+        });
+
+        assert_eq!(
+            run_custom_validator("no_links", "bio", &Value::from("hello")),
+            None
+        );
+        assert_eq!(
+            run_custom_validator("no_links", "bio", &Value::from("see http://x")),
+            Some("bio cannot contain links".to_string())
+        );
+        assert_eq!(
+            run_custom_validator("missing", "bio", &Value::from("hello")),
+            Some("bio uses unknown validator 'missing'".to_string())
+        );
+    }
+}
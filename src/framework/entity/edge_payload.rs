@@ -0,0 +1,21 @@
+use crate::error::{AppError, AppResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Typed `data` of an association type that declares a payload (see `EdgeDefinition::payload`).
+/// Codegen implements it for each edge's payload struct; the data is stored as a JSON object
+/// so that `assoc_add` can check it against the payload fields.
+pub trait EdgePayload: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// The association type this payload belongs to
+    const ATYPE: &'static str;
+
+    /// Encode as association data
+    fn to_data(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("edge payload serializes to JSON")
+    }
+
+    /// Decode from association data
+    fn from_data(data: &[u8]) -> AppResult<Self> {
+        serde_json::from_slice(data).map_err(|e| AppError::SerializationError(e.to_string()))
+    }
+}
@@ -1,8 +1,9 @@
 // Entity Trait - Simplified Meta's Entity Framework Interface
 // Single trait that provides both entity identity and common CRUD operations
 
-use crate::error::AppResult;
-use crate::infrastructure::tao_core::tao_core::TaoOperations;
+use crate::error::{AppResult, ValidationErrors};
+use crate::framework::ent_hooks;
+use crate::infrastructure::tao_core::tao_core::{TaoObject, TaoOperations};
 use async_trait::async_trait;
 use std::sync::Arc;
 use thrift::protocol::TSerializable;
@@ -10,7 +11,7 @@ use thrift::protocol::TSerializable;
 /// Entity trait that all generated entities implement
 /// Provides both entity identity and common CRUD operations templated for all entity types
 #[async_trait]
-pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
+pub trait Entity: Send + Sync + Clone + Sized + TSerializable + 'static {
     /// Entity type name for TAO operations (entity-specific)
     const ENTITY_TYPE: &'static str;
 
@@ -18,7 +19,8 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
     fn id(&self) -> i64;
 
     /// Validate entity according to schema constraints (entity-specific implementation)
-    fn validate(&self) -> AppResult<Vec<String>>;
+    /// Returns every violation found, keyed by field; empty when the entity is valid
+    fn validate(&self) -> AppResult<ValidationErrors>;
 
     // --- Common CRUD Operations (templated for all entities) ---
 
@@ -49,8 +51,22 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
             .map_err(|e| crate::error::AppError::DeserializationError(e.to_string()))
     }
 
+    /// Deserialize the entity stored in `obj`. A failure names the object's id and type,
+    /// as it usually means the row predates a schema change or is corrupt.
+    fn from_stored_object(obj: &TaoObject) -> AppResult<Self> {
+        use std::io::Cursor;
+        use thrift::protocol::TCompactInputProtocol;
+
+        let mut cursor = Cursor::new(&obj.data);
+        let mut protocol = TCompactInputProtocol::new(&mut cursor);
+
+        Self::read_from_in_protocol(&mut protocol)
+            .map_err(|e| crate::error::AppError::serialization(&obj.otype, obj.id, e))
+    }
+
     /// Load entity with nullable ID - returns None if not found (TYPE-SAFE)
-    /// Only returns entities of the correct type, ensuring EntUser::gen_nullable(post_id) returns None
+    /// An id stored as another type is an error rather than None, so
+    /// EntUser::gen_nullable(post_id) fails with `AppError::TypeMismatch`
     /// Meta's pattern: EntUser::genNullable(vc, entity_id)
     async fn gen_nullable<V>(vc: V, entity_id: Option<i64>) -> AppResult<Option<Self>> 
     where 
@@ -61,17 +77,17 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
             Some(id) => {
                 // Extract TAO from viewer context (Meta's pattern)
                 let tao_ops = &vc.tao;
-                // Use type-aware query to ensure we only get entities of the correct type
-                let objects = tao_ops
-                    .get_by_id_and_type(vec![id], Self::ENTITY_TYPE.to_string())
-                    .await?;
-
-                if let Some(obj) = objects.into_iter().next() {
-                    // TaoObject.data is now a Vec<u8>, not Option<Vec<u8>>
-                    let entity = Self::deserialize_from_bytes(&obj.data)?;
-                    Ok(Some(entity))
-                } else {
-                    Ok(None) // No entity of this type with this ID
+                // One read serves both outcomes: the stored otype tells a missing id
+                // from one that belongs to another entity type
+                match tao_ops.obj_get(id).await? {
+                    Some(obj) if obj.otype == Self::ENTITY_TYPE => {
+                        Ok(Some(Self::from_stored_object(&obj)?))
+                    }
+                    Some(obj) => Err(crate::error::AppError::TypeMismatch {
+                        expected: Self::ENTITY_TYPE.to_string(),
+                        actual: obj.otype,
+                    }),
+                    None => Ok(None), // No entity with this ID
                 }
             }
             None => Ok(None),
@@ -94,8 +110,7 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
             .await?;
 
         if let Some(obj) = objects.into_iter().next() {
-            // TaoObject.data is now a Vec<u8>, not Option<Vec<u8>>
-            Self::deserialize_from_bytes(&obj.data)
+            Self::from_stored_object(&obj)
         } else {
             Err(crate::error::AppError::Validation(format!(
                 "Entity {} of type {} not found",
@@ -108,13 +123,9 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
     /// Update existing entity (TYPE-SAFE)
     /// Only updates entities of the correct type, ensuring type safety
     async fn update(&mut self, tao: &Arc<dyn TaoOperations>) -> AppResult<()> {
-        let validation_errors = self.validate()?;
-        if !validation_errors.is_empty() {
-            return Err(crate::error::AppError::Validation(format!(
-                "Validation failed: {}",
-                validation_errors.join(", ")
-            )));
-        }
+        ent_hooks::run_before_update(self).await?;
+
+        self.validate()?.into_result()?;
 
         let data = self.serialize_to_bytes()?;
 
@@ -144,8 +155,13 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
         // Extract TAO from viewer context (Meta's pattern)
         let tao_ops = &vc.tao;
         // Use type-aware delete to ensure we only delete entities of the correct type
-        tao_ops.obj_delete_by_type(entity_id, Self::ENTITY_TYPE.to_string())
-            .await
+        let deleted = tao_ops
+            .obj_delete_by_type(entity_id, Self::ENTITY_TYPE.to_string())
+            .await?;
+        if deleted {
+            ent_hooks::run_after_delete::<Self>(entity_id).await;
+        }
+        Ok(deleted)
     }
 
     /// Check if entity exists (TYPE-SAFE)
@@ -195,8 +211,7 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
         let mut results = Vec::with_capacity(entity_ids.len());
         for id in entity_ids {
             if let Some(obj) = object_map.get(&id) {
-                // TaoObject.data is now a Vec<u8>, not Option<Vec<u8>>
-                let entity = Self::deserialize_from_bytes(&obj.data)?;
+                let entity = Self::from_stored_object(obj)?;
                 results.push(Some(entity));
             } else {
                 results.push(None); // No entity of this type with this ID
@@ -221,7 +236,7 @@ pub trait Entity: Send + Sync + Clone + Sized + TSerializable {
 
         objects
             .into_iter()
-            .map(|obj| Self::deserialize_from_bytes(&obj.data))
+            .map(|obj| Self::from_stored_object(&obj))
             .collect()
     }
 
@@ -0,0 +1,217 @@
+// Entity Type Registry - deserialize a TaoObject without knowing its type at compile time
+// Generic tooling (graph export, replication apply, admin tools) only has the otype string
+
+use crate::error::{AppError, AppResult};
+use crate::framework::entity::ent_trait::Entity;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Type-erased view of a generated entity
+pub trait ErasedEntity: Send + Sync + fmt::Debug {
+    /// The otype this entity is stored under
+    fn otype(&self) -> &'static str;
+
+    /// Object id of the entity
+    fn object_id(&self) -> i64;
+
+    /// Serialize back to the bytes stored in `TaoObject.data`
+    fn to_bytes(&self) -> AppResult<Vec<u8>>;
+
+    /// Access the concrete entity, for `downcast_ref`
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Entity + fmt::Debug> ErasedEntity for T {
+    fn otype(&self) -> &'static str {
+        T::ENTITY_TYPE
+    }
+
+    fn object_id(&self) -> i64 {
+        self.id()
+    }
+
+    fn to_bytes(&self) -> AppResult<Vec<u8>> {
+        self.serialize_to_bytes()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl dyn ErasedEntity {
+    /// The concrete entity, if it is a `T`
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+}
+
+/// Turns stored object bytes into an erased entity
+pub type EntityDeserializer = fn(&[u8]) -> AppResult<Box<dyn ErasedEntity>>;
+
+fn deserialize_erased<T: Entity + fmt::Debug>(data: &[u8]) -> AppResult<Box<dyn ErasedEntity>> {
+    Ok(Box::new(T::deserialize_from_bytes(data)?))
+}
+
+/// Maps otype strings to the deserializer of the entity stored under them
+#[derive(Debug, Clone, Default)]
+pub struct EntityTypeRegistry {
+    deserializers: HashMap<&'static str, EntityDeserializer>,
+}
+
+impl EntityTypeRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry holding every entity type produced by codegen
+    pub fn with_generated_entities() -> Self {
+        let mut registry = Self::new();
+        crate::domains::entity_types::register_entity_types(&mut registry);
+        registry
+    }
+
+    /// Register `T` under its `ENTITY_TYPE`
+    pub fn register<T: Entity + fmt::Debug>(&mut self) {
+        self.register_deserializer(T::ENTITY_TYPE, deserialize_erased::<T>);
+    }
+
+    /// Register a custom deserializer for `otype`, replacing any existing one
+    pub fn register_deserializer(&mut self, otype: &'static str, deserializer: EntityDeserializer) {
+        self.deserializers.insert(otype, deserializer);
+    }
+
+    pub fn contains(&self, otype: &str) -> bool {
+        self.deserializers.contains_key(otype)
+    }
+
+    /// Registered otypes, sorted
+    pub fn otypes(&self) -> Vec<&'static str> {
+        let mut otypes: Vec<_> = self.deserializers.keys().copied().collect();
+        otypes.sort_unstable();
+        otypes
+    }
+
+    /// Deserialize whatever entity `bytes` holds, given the otype it was stored under
+    pub fn deserialize(&self, otype: &str, bytes: &[u8]) -> AppResult<Box<dyn ErasedEntity>> {
+        let deserializer = self.deserializers.get(otype).ok_or_else(|| {
+            AppError::DeserializationError(format!("Unknown entity type: {}", otype))
+        })?;
+        deserializer(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::comment::EntComment;
+    use crate::domains::event::EntEvent;
+    use crate::domains::group::EntGroup;
+    use crate::domains::page::EntPage;
+    use crate::domains::post::EntPost;
+    use crate::domains::user::EntUser;
+
+    fn round_trip<T: Entity + fmt::Debug + PartialEq>(registry: &EntityTypeRegistry, entity: T) {
+        let bytes = entity.serialize_to_bytes().unwrap();
+        let erased = registry.deserialize(T::ENTITY_TYPE, &bytes).unwrap();
+
+        assert_eq!(erased.otype(), T::ENTITY_TYPE);
+        assert_eq!(erased.object_id(), entity.id());
+        assert_eq!(erased.to_bytes().unwrap(), bytes);
+        assert_eq!(erased.downcast_ref::<T>(), Some(&entity));
+    }
+
+    #[test]
+    fn test_generated_entities_round_trip_through_erased_path() {
+        let registry = EntityTypeRegistry::with_generated_entities();
+        assert_eq!(
+            registry.otypes(),
+            vec![
+                "ent_comment",
+                "ent_event",
+                "ent_group",
+                "ent_page",
+                "ent_post",
+                "ent_user"
+            ]
+        );
+
+        round_trip(
+            &registry,
+            EntUser::new(
+                1,
+                "alice".to_string(),
+                "alice@example.com".to_string(),
+                1_000,
+                "Alice".to_string(),
+                None,
+                None,
+                None,
+                true,
+                None,
+                None,
+            ),
+        );
+        round_trip(
+            &registry,
+            EntPost::new(
+                2,
+                1,
+                "hello".to_string(),
+                None,
+                1_000,
+                None,
+                "text".to_string(),
+                "public".to_string(),
+                3,
+                0,
+                0,
+                None,
+                None,
+            ),
+        );
+        round_trip(
+            &registry,
+            EntComment::new(3, 1, 2, "nice".to_string(), 1_000),
+        );
+        round_trip(
+            &registry,
+            EntGroup::new(4, "rustaceans".to_string(), None, 1_000),
+        );
+        round_trip(
+            &registry,
+            EntPage::new(5, "tao".to_string(), "docs".to_string(), 1_000),
+        );
+        round_trip(
+            &registry,
+            EntEvent::new(6, "launch".to_string(), None, 2_000, 1_000),
+        );
+    }
+
+    #[test]
+    fn test_unknown_otype_is_an_error() {
+        let registry = EntityTypeRegistry::with_generated_entities();
+        let user = EntUser::new(
+            1,
+            "alice".to_string(),
+            "alice@example.com".to_string(),
+            1_000,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+        );
+        let bytes = user.serialize_to_bytes().unwrap();
+
+        assert!(matches!(
+            registry.deserialize("ent_widget", &bytes),
+            Err(AppError::DeserializationError(_))
+        ));
+        assert!(!EntityTypeRegistry::new().contains(EntUser::ENTITY_TYPE));
+    }
+}
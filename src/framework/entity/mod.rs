@@ -1,2 +1,4 @@
 pub mod ent_trait;
 pub mod associations;
+pub mod edge_payload;
+pub mod entity_registry;
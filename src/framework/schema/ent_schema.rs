@@ -2,7 +2,7 @@
 // Provides declarative schema definition with automatic code generation
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum EntityType {
@@ -81,6 +81,55 @@ pub trait EntSchema: Send + Sync {
     {
         Vec::new()
     }
+
+    /// How `obj_get` results of this entity are cached
+    fn cache_policy() -> CachePolicy
+    where
+        Self: Sized,
+    {
+        CachePolicy::Default
+    }
+
+    /// Keep every value `obj_update` overwrites, for auditing and rollback. Off by
+    /// default, since the history grows with every update.
+    fn keep_history() -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
+
+    /// Whether writes of this entity are logged to the WAL (see `Durability`)
+    fn durability() -> Durability
+    where
+        Self: Sized,
+    {
+        Durability::Durable
+    }
+}
+
+/// Whether writes of an entity or edge type are logged to the write-ahead log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Durability {
+    /// Logged to the WAL after the database write, so a failed write can be replayed
+    #[default]
+    Durable,
+    /// Written to the database only, for low-value data like presence or view counts.
+    /// Nothing replays these writes: one that fails, or is lost to a crash between the
+    /// database write and the acknowledgement, is gone.
+    BestEffort,
+}
+
+/// Object caching policy of an entity type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CachePolicy {
+    /// Cache with the cache's own TTLs
+    #[default]
+    Default,
+    /// Never cache; every read goes to the store
+    Never,
+    /// Cache in both tiers for this many milliseconds
+    Ttl(u64),
 }
 
 /// Field definition - equivalent to Meta's field package
@@ -141,6 +190,32 @@ impl FieldDefinition {
         self.validators.push(validator);
         self
     }
+
+    /// Require at least `min` characters
+    pub fn min_len(self, min: usize) -> Self {
+        self.validate(FieldValidator::MinLength(min))
+    }
+
+    /// Allow at most `max` characters
+    pub fn max_len(self, max: usize) -> Self {
+        self.validate(FieldValidator::MaxLength(max))
+    }
+
+    /// Require the value to match a regex
+    pub fn matches(self, pattern: &str) -> Self {
+        self.validate(FieldValidator::Pattern(pattern.to_string()))
+    }
+
+    /// Require a numeric value within an inclusive range, e.g. `.range(0..=120)`
+    pub fn range<T: Into<f64>>(self, range: std::ops::RangeInclusive<T>) -> Self {
+        let (min, max) = range.into_inner();
+        self.validate(FieldValidator::Range(min.into(), max.into()))
+    }
+
+    /// Run a validator registered with `ent_hooks::register_custom_validator`
+    pub fn custom(self, name: &str) -> Self {
+        self.validate(FieldValidator::Custom(name.to_string()))
+    }
 }
 
 /// Field types supported by Ent
@@ -191,6 +266,16 @@ pub struct EdgeDefinition {
     pub immutable: bool,
     pub bidirectional: bool,
     pub inverse_name: Option<String>,
+    /// Set by `symmetric()`: the edge is its own inverse and is always stored both ways
+    pub symmetric: bool,
+    /// Recorded automatically from the creating viewer (see `owner_edge()`)
+    pub owner: bool,
+    /// Set by `high_fanout()`: number of buckets the inverse edges are spread across
+    pub fanout_buckets: Option<u32>,
+    /// Fields of the edge's `data`, stored as a JSON object (see `payload()`)
+    pub payload: Vec<FieldDefinition>,
+    /// Set to `BestEffort` by `best_effort()`: writes of the edge skip the WAL
+    pub durability: Durability,
     pub storage_key: Option<String>,
     pub annotations: Vec<AnnotationDefinition>,
     pub constraints: Vec<EdgeConstraint>,
@@ -209,6 +294,11 @@ impl EdgeDefinition {
             immutable: false,
             bidirectional: false,
             inverse_name: None,
+            symmetric: false,
+            owner: false,
+            fanout_buckets: None,
+            payload: Vec::new(),
+            durability: Durability::Durable,
             storage_key: None,
             annotations: Vec::new(),
             constraints: Vec::new(),
@@ -227,6 +317,11 @@ impl EdgeDefinition {
             immutable: false,
             bidirectional: false,
             inverse_name: Some(inverse_edge.to_string()),
+            symmetric: false,
+            owner: false,
+            fanout_buckets: None,
+            payload: Vec::new(),
+            durability: Durability::Durable,
             storage_key: None,
             annotations: Vec::new(),
             constraints: Vec::new(),
@@ -259,6 +354,157 @@ impl EdgeDefinition {
         self.inverse_name = Some(name.to_string());
         self
     }
+
+    /// Mark edge as symmetric, like friendship: A -> B implies B -> A. The edge is its own
+    /// inverse and must point at its own entity type. TAO adds and deletes both directions
+    /// together, and `assoc_exists` accepts either.
+    pub fn symmetric(mut self) -> Self {
+        self.symmetric = true;
+        self.bidirectional = true;
+        self.inverse_name = Some(self.name.clone());
+        self
+    }
+
+    /// Mark edge as pointing at the entity's creator.
+    /// Creating the entity as a logged-in viewer adds this edge (and its inverse)
+    /// from the new entity to the viewer's user id.
+    pub fn owner_edge(mut self) -> Self {
+        self.owner = true;
+        self
+    }
+
+    /// Mark edge as high fan-out (e.g. following a celebrity), so its inverse can grow
+    /// to millions of edges on one target. TAO then writes the inverse itself, spread
+    /// over `buckets` buckets (`id1 % buckets`) placed on different shards, instead of
+    /// one list on the target's shard. Read the inverse with `get_in_neighbor_ids`.
+    ///
+    /// Tradeoff: no single shard or count row takes every write, but reading the inverse
+    /// costs one query per bucket, and buckets are placed by position in the shard list,
+    /// so adding or removing shards strands existing bucket edges until they are moved.
+    pub fn high_fanout(mut self, buckets: u32) -> Self {
+        self.fanout_buckets = Some(buckets.max(1));
+        self
+    }
+
+    /// Declare the fields carried in the edge's `data`, stored as a JSON object.
+    /// `assoc_add` rejects data that does not match them, and codegen emits a typed
+    /// payload struct the generated `add_*` method takes.
+    pub fn payload(mut self, fields: Vec<FieldDefinition>) -> Self {
+        self.payload = fields;
+        self
+    }
+
+    /// Mark edge as best effort: its writes skip the WAL. A write lost to a crash
+    /// between the database write and the acknowledgement is not replayed.
+    pub fn best_effort(mut self) -> Self {
+        self.durability = Durability::BestEffort;
+        self
+    }
+}
+
+/// Check an edge's `data` against its payload fields, returning one message per violation.
+/// Missing data is treated as an empty object; keys the schema does not declare are rejected.
+pub fn validate_payload(fields: &[FieldDefinition], data: Option<&[u8]>) -> Vec<String> {
+    let object = match data.map(serde_json::from_slice::<serde_json::Value>) {
+        None => serde_json::Map::new(),
+        Some(Ok(serde_json::Value::Object(object))) => object,
+        Some(_) => return vec!["payload must be a JSON object".to_string()],
+    };
+
+    let mut errors = Vec::new();
+    for key in object.keys() {
+        if !fields.iter().any(|field| field.name == *key) {
+            errors.push(format!("unknown field '{}'", key));
+        }
+    }
+    for field in fields {
+        let field_display = field.name.replace('_', " ");
+        match object.get(&field.name) {
+            None | Some(serde_json::Value::Null) => {
+                if !field.optional && field.default.is_none() {
+                    errors.push(format!("{} is required", field_display));
+                }
+            }
+            Some(value) => field.check_value(&field_display, value, &mut errors),
+        }
+    }
+    errors
+}
+
+impl FieldDefinition {
+    /// Check a JSON value against this field's type and validators
+    fn check_value(
+        &self,
+        field_display: &str,
+        value: &serde_json::Value,
+        errors: &mut Vec<String>,
+    ) {
+        let type_ok = match &self.field_type {
+            FieldType::String | FieldType::UUID | FieldType::JSON => value.is_string(),
+            FieldType::Int => value.as_i64().is_some_and(|v| i32::try_from(v).is_ok()),
+            FieldType::Int64 | FieldType::Time => value.is_i64(),
+            FieldType::Float => value.is_number(),
+            FieldType::Bool => value.is_boolean(),
+            FieldType::Bytes => value.is_array(),
+            FieldType::Enum(variants) => value
+                .as_str()
+                .is_some_and(|v| variants.iter().any(|variant| variant == v)),
+        };
+        if !type_ok {
+            errors.push(match &self.field_type {
+                FieldType::Enum(variants) => {
+                    format!("{} must be one of {}", field_display, variants.join(", "))
+                }
+                field_type => format!("{} must be of type {:?}", field_display, field_type),
+            });
+            return;
+        }
+
+        for validator in &self.validators {
+            match validator {
+                FieldValidator::MinLength(min) => {
+                    if value.as_str().is_some_and(|v| v.len() < *min) {
+                        errors.push(format!(
+                            "{} must be at least {} characters",
+                            field_display, min
+                        ));
+                    }
+                }
+                FieldValidator::MaxLength(max) => {
+                    if value.as_str().is_some_and(|v| v.len() > *max) {
+                        errors.push(format!(
+                            "{} cannot exceed {} characters",
+                            field_display, max
+                        ));
+                    }
+                }
+                FieldValidator::Pattern(pattern) => {
+                    let matches = regex::Regex::new(pattern)
+                        .map(|re| value.as_str().is_some_and(|v| re.is_match(v)));
+                    if !matches.unwrap_or(false) {
+                        errors.push(format!("{} format is invalid", field_display));
+                    }
+                }
+                FieldValidator::Range(min, max) => {
+                    if value.as_f64().is_some_and(|v| !(*min..=*max).contains(&v)) {
+                        errors.push(format!(
+                            "{} must be between {} and {}",
+                            field_display, min, max
+                        ));
+                    }
+                }
+                FieldValidator::Custom(name) => {
+                    if let Some(error) = crate::framework::ent_hooks::run_custom_validator(
+                        name,
+                        field_display,
+                        value,
+                    ) {
+                        errors.push(error);
+                    }
+                }
+            }
+        }
+    }
 }
 
 /// Edge types - direction of relationship
@@ -350,11 +596,78 @@ pub struct AnnotationDefinition {
     pub value: String,
 }
 
+/// A field as reported by `SchemaRegistry::describe`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldDescription {
+    pub name: String,
+    pub field_type: FieldType,
+    /// Must be supplied on create: not optional and without a default
+    pub required: bool,
+    pub default: Option<FieldDefault>,
+    pub unique: bool,
+    pub immutable: bool,
+}
+
+impl From<&FieldDefinition> for FieldDescription {
+    fn from(field: &FieldDefinition) -> Self {
+        Self {
+            name: field.name.clone(),
+            field_type: field.field_type.clone(),
+            required: !field.optional && field.default.is_none(),
+            default: field.default.clone(),
+            unique: field.unique,
+            immutable: field.immutable,
+        }
+    }
+}
+
+/// An edge as reported by `SchemaRegistry::describe`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeDescription {
+    pub name: String,
+    /// Otype of the entity the edge points to
+    pub target_type: String,
+    pub edge_type: EdgeType,
+    pub cardinality: EdgeCardinality,
+    pub inverse_name: Option<String>,
+    pub symmetric: bool,
+    /// Set by `owner_edge()`: the edge points at the entity's creator
+    pub owner: bool,
+    pub payload: Vec<FieldDescription>,
+}
+
+impl From<&EdgeDefinition> for EdgeDescription {
+    fn from(edge: &EdgeDefinition) -> Self {
+        Self {
+            name: edge.name.clone(),
+            target_type: edge.target_entity.as_str().to_string(),
+            edge_type: edge.edge_type.clone(),
+            cardinality: edge.cardinality.clone(),
+            inverse_name: edge.inverse_name.clone(),
+            symmetric: edge.symmetric,
+            owner: edge.owner,
+            payload: edge.payload.iter().map(FieldDescription::from).collect(),
+        }
+    }
+}
+
+/// One registered entity type with its fields and edges, in declaration order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySchemaDescription {
+    /// Otype the entity is stored under, e.g. `ent_user`
+    pub entity_type: String,
+    pub fields: Vec<FieldDescription>,
+    pub edges: Vec<EdgeDescription>,
+}
+
 /// Schema registry - holds all defined schemas
 #[derive(Default)]
 pub struct SchemaRegistry {
     field_definitions: HashMap<EntityType, Vec<FieldDefinition>>,
     edge_definitions: HashMap<EntityType, Vec<EdgeDefinition>>,
+    cache_policies: HashMap<EntityType, CachePolicy>,
+    history_types: HashSet<EntityType>,
+    best_effort_types: HashSet<EntityType>,
 }
 
 impl SchemaRegistry {
@@ -369,7 +682,15 @@ impl SchemaRegistry {
         let edges = T::edges();
 
         self.field_definitions.insert(entity_type.clone(), fields);
-        self.edge_definitions.insert(entity_type, edges);
+        self.edge_definitions.insert(entity_type.clone(), edges);
+        self.cache_policies
+            .insert(entity_type.clone(), T::cache_policy());
+        if T::keep_history() {
+            self.history_types.insert(entity_type.clone());
+        }
+        if T::durability() == Durability::BestEffort {
+            self.best_effort_types.insert(entity_type);
+        }
     }
 
     /// Get field definitions for an entity
@@ -382,6 +703,34 @@ impl SchemaRegistry {
         self.edge_definitions.get(entity_type)
     }
 
+    /// Caching policy of an entity; `Default` for unregistered ones
+    pub fn get_cache_policy(&self, entity_type: &EntityType) -> CachePolicy {
+        self.cache_policies
+            .get(entity_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Otypes whose schema keeps object history, sorted
+    pub fn history_otypes(&self) -> Vec<&'static str> {
+        let mut otypes: Vec<_> = self
+            .history_types
+            .iter()
+            .map(|entity_type| entity_type.as_str())
+            .collect();
+        otypes.sort_unstable();
+        otypes
+    }
+
+    /// Durability of an entity's writes; `Durable` for unregistered ones
+    pub fn get_durability(&self, entity_type: &EntityType) -> Durability {
+        if self.best_effort_types.contains(entity_type) {
+            Durability::BestEffort
+        } else {
+            Durability::Durable
+        }
+    }
+
     /// Get all registered entity types
     pub fn get_entity_types(&self) -> Vec<&EntityType> {
         self.field_definitions.keys().collect()
@@ -397,6 +746,25 @@ impl SchemaRegistry {
         Some((fields, edges))
     }
 
+    /// Describe every registered entity type, sorted by otype
+    pub fn describe(&self) -> Vec<EntitySchemaDescription> {
+        let mut descriptions: Vec<_> = self
+            .field_definitions
+            .iter()
+            .map(|(entity_type, fields)| EntitySchemaDescription {
+                entity_type: entity_type.as_str().to_string(),
+                fields: fields.iter().map(FieldDescription::from).collect(),
+                edges: self
+                    .edge_definitions
+                    .get(entity_type)
+                    .map(|edges| edges.iter().map(EdgeDescription::from).collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        descriptions.sort_by(|a, b| a.entity_type.cmp(&b.entity_type));
+        descriptions
+    }
+
     /// Validate schema consistency
     pub fn validate(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
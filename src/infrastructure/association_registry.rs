@@ -1,20 +1,42 @@
 //! A registry for managing association types and their inverse relationships.
 //!
 //! This module provides a centralized place to define and retrieve inverse
-//! association types, ensuring consistency across the TAO system. In a
-//! production system, this mapping could be loaded from a configuration file
-//! or a schema definition.
+//! association types, ensuring consistency across the TAO system. At startup
+//! the mapping is derived from the registered schemas' edge definitions with
+//! `AssociationRegistry::from_schema_registry`.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::error::{AppError, AppResult};
+use crate::framework::schema::ent_schema::{FieldDefinition, SchemaRegistry};
+
+/// One association type as seen from its source entity type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssociationEndpoints {
+    pub source_type: String,
+    pub atype: String,
+    pub target_type: String,
+    /// Association type stored on the target pointing back, if any
+    pub inverse_atype: Option<String>,
+}
+
 /// Manages the mapping of association types to their inverse types.
 #[derive(Debug, Clone)]
 pub struct AssociationRegistry {
     /// A map where the key is an association type and the value is its inverse.
     /// For symmetric associations (e.g., "friends"), the inverse is itself.
     inverse_map: Arc<RwLock<HashMap<String, String>>>,
+    /// Endpoints keyed by (source entity type, association type). Only populated
+    /// when the registry is loaded from schemas.
+    endpoints: Arc<RwLock<HashMap<(String, String), AssociationEndpoints>>>,
+    /// High fan-out association types and how many buckets their inverse is spread over
+    fanout_buckets: Arc<RwLock<HashMap<String, u32>>>,
+    /// Fields the `data` of each association type must match, for types declaring a payload
+    payload_schemas: Arc<RwLock<HashMap<String, Vec<FieldDefinition>>>>,
+    /// Association types TAO stores in both directions
+    symmetric: Arc<RwLock<HashSet<String>>>,
 }
 
 impl AssociationRegistry {
@@ -36,7 +58,163 @@ impl AssociationRegistry {
 
         AssociationRegistry {
             inverse_map: Arc::new(RwLock::new(map)),
+            endpoints: Arc::new(RwLock::new(HashMap::new())),
+            fanout_buckets: Arc::new(RwLock::new(HashMap::new())),
+            payload_schemas: Arc::new(RwLock::new(HashMap::new())),
+            symmetric: Arc::new(RwLock::new(HashSet::new())),
+        }
+    }
+
+    /// Builds the registry from every registered schema's edge definitions.
+    ///
+    /// An edge naming an inverse is paired with the target's edge of that name; when the
+    /// target declares no such edge the inverse is implied. Errors if an edge targets an
+    /// unregistered entity type, or if the two sides of a pair disagree about each other.
+    ///
+    /// Edge names reused across entity types with different inverses (e.g. `followers` on
+    /// both users and pages) are left out of the flat inverse map and can only be resolved
+    /// with `get_association`.
+    pub fn from_schema_registry(schemas: &SchemaRegistry) -> AppResult<Self> {
+        let mut entity_types = schemas.get_entity_types();
+        entity_types.sort_by_key(|entity_type| entity_type.as_str());
+
+        let mut endpoints: HashMap<(String, String), AssociationEndpoints> = HashMap::new();
+        let mut declared = Vec::new();
+        let mut fanout_buckets = HashMap::new();
+        let mut payload_schemas = HashMap::new();
+        let mut symmetric = HashSet::new();
+        for entity_type in &entity_types {
+            for edge in schemas.get_edges(entity_type).into_iter().flatten() {
+                if schemas.get_fields(&edge.target_entity).is_none() {
+                    return Err(AppError::ConfigurationError(format!(
+                        "Edge '{}' on {} points to unregistered entity type {}",
+                        edge.name, entity_type, edge.target_entity
+                    )));
+                }
+                let endpoint = AssociationEndpoints {
+                    source_type: entity_type.as_str().to_string(),
+                    atype: edge.name.clone(),
+                    target_type: edge.target_entity.as_str().to_string(),
+                    inverse_atype: edge.inverse_name.clone(),
+                };
+                let key = (endpoint.source_type.clone(), endpoint.atype.clone());
+                if endpoints.insert(key, endpoint.clone()).is_some() {
+                    return Err(AppError::ConfigurationError(format!(
+                        "Edge '{}' is defined more than once on {}",
+                        edge.name, entity_type
+                    )));
+                }
+                if let Some(buckets) = edge.fanout_buckets {
+                    fanout_buckets.insert(edge.name.clone(), buckets);
+                }
+                if edge.symmetric {
+                    if edge.target_entity != **entity_type || edge.fanout_buckets.is_some() {
+                        return Err(AppError::ConfigurationError(format!(
+                            "Symmetric edge '{}' on {} must point to {} and cannot be high fan-out",
+                            edge.name, entity_type, entity_type
+                        )));
+                    }
+                    symmetric.insert(edge.name.clone());
+                }
+                // Payloads are looked up by association type alone
+                if !edge.payload.is_empty()
+                    && payload_schemas
+                        .insert(edge.name.clone(), edge.payload.clone())
+                        .is_some()
+                {
+                    return Err(AppError::ConfigurationError(format!(
+                        "Payload of edge '{}' is declared on more than one entity type",
+                        edge.name
+                    )));
+                }
+                declared.push(endpoint);
+            }
+        }
+
+        for endpoint in &declared {
+            let Some(inverse) = &endpoint.inverse_atype else {
+                continue;
+            };
+            let key = (endpoint.target_type.clone(), inverse.clone());
+            match endpoints.get_mut(&key) {
+                Some(counterpart) => {
+                    if counterpart.target_type != endpoint.source_type {
+                        return Err(AppError::ConfigurationError(format!(
+                            "Edge '{}' on {} names inverse '{}' on {}, which points to {}",
+                            endpoint.atype,
+                            endpoint.source_type,
+                            inverse,
+                            endpoint.target_type,
+                            counterpart.target_type
+                        )));
+                    }
+                    match &counterpart.inverse_atype {
+                        Some(back) if *back != endpoint.atype => {
+                            return Err(AppError::ConfigurationError(format!(
+                                "Edge '{}' on {} names inverse '{}', but '{}' on {} names inverse '{}'",
+                                endpoint.atype,
+                                endpoint.source_type,
+                                inverse,
+                                inverse,
+                                endpoint.target_type,
+                                back
+                            )));
+                        }
+                        Some(_) => {}
+                        None => counterpart.inverse_atype = Some(endpoint.atype.clone()),
+                    }
+                }
+                None => {
+                    endpoints.insert(
+                        key,
+                        AssociationEndpoints {
+                            source_type: endpoint.target_type.clone(),
+                            atype: inverse.clone(),
+                            target_type: endpoint.source_type.clone(),
+                            inverse_atype: Some(endpoint.atype.clone()),
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut inverse_map = HashMap::new();
+        let mut ambiguous = HashSet::new();
+        for endpoint in endpoints.values() {
+            let Some(inverse) = &endpoint.inverse_atype else {
+                continue;
+            };
+            if ambiguous.contains(&endpoint.atype) {
+                continue;
+            }
+            match inverse_map.get(&endpoint.atype) {
+                Some(existing) if existing != inverse => {
+                    inverse_map.remove(&endpoint.atype);
+                    ambiguous.insert(endpoint.atype.clone());
+                }
+                _ => {
+                    inverse_map.insert(endpoint.atype.clone(), inverse.clone());
+                }
+            }
+        }
+
+        // TAO writes the inverse of a high fan-out edge itself, so it must know which it is
+        for atype in fanout_buckets.keys() {
+            if !inverse_map.contains_key(atype) {
+                return Err(AppError::ConfigurationError(format!(
+                    "High fan-out edge '{}' needs an inverse that is unambiguous across entity types",
+                    atype
+                )));
+            }
         }
+
+        Ok(AssociationRegistry {
+            inverse_map: Arc::new(RwLock::new(inverse_map)),
+            endpoints: Arc::new(RwLock::new(endpoints)),
+            fanout_buckets: Arc::new(RwLock::new(fanout_buckets)),
+            payload_schemas: Arc::new(RwLock::new(payload_schemas)),
+            symmetric: Arc::new(RwLock::new(symmetric)),
+        })
     }
 
     /// Retrieves the inverse association type for a given association type.
@@ -47,6 +225,79 @@ impl AssociationRegistry {
         map.get(atype).cloned()
     }
 
+    /// Every association type the registry knows of, sorted
+    pub async fn association_types(&self) -> Vec<String> {
+        let mut atypes: HashSet<String> = self.inverse_map.read().await.keys().cloned().collect();
+        atypes.extend(
+            self.endpoints
+                .read()
+                .await
+                .keys()
+                .map(|(_, atype)| atype.clone()),
+        );
+        let mut atypes: Vec<String> = atypes.into_iter().collect();
+        atypes.sort();
+        atypes
+    }
+
+    /// Whether `atype` is a registered association type, i.e. one the typed API can read
+    pub async fn is_known_type(&self, atype: &str) -> bool {
+        self.inverse_map.read().await.contains_key(atype)
+            || self
+                .endpoints
+                .read()
+                .await
+                .keys()
+                .any(|(_, known)| known == atype)
+    }
+
+    /// Retrieves the endpoints of `atype` stored on objects of `source_type`.
+    pub async fn get_association(
+        &self,
+        source_type: &str,
+        atype: &str,
+    ) -> Option<AssociationEndpoints> {
+        let endpoints = self.endpoints.read().await;
+        endpoints
+            .get(&(source_type.to_string(), atype.to_string()))
+            .cloned()
+    }
+
+    /// Number of buckets the inverse of `atype` is spread over, if `atype` is high fan-out
+    pub async fn get_fanout_buckets(&self, atype: &str) -> Option<u32> {
+        self.fanout_buckets.read().await.get(atype).copied()
+    }
+
+    /// Marks `atype` as high fan-out, with its inverse spread over `buckets` buckets.
+    /// `atype` must have an inverse registered.
+    pub async fn register_high_fanout(&self, atype: String, buckets: u32) {
+        let mut map = self.fanout_buckets.write().await;
+        map.insert(atype, buckets.max(1));
+    }
+
+    /// Whether `atype` is symmetric, i.e. stored in both directions
+    pub async fn is_symmetric(&self, atype: &str) -> bool {
+        self.symmetric.read().await.contains(atype)
+    }
+
+    /// Marks `atype` as symmetric and makes it its own inverse
+    pub async fn register_symmetric(&self, atype: String) {
+        self.register_inverse_association(atype.clone(), atype.clone())
+            .await;
+        self.symmetric.write().await.insert(atype);
+    }
+
+    /// Fields the `data` of `atype` must match, if `atype` declares a payload
+    pub async fn get_payload_schema(&self, atype: &str) -> Option<Vec<FieldDefinition>> {
+        self.payload_schemas.read().await.get(atype).cloned()
+    }
+
+    /// Requires the `data` of `atype` to match `fields` from now on
+    pub async fn register_payload_schema(&self, atype: String, fields: Vec<FieldDefinition>) {
+        let mut map = self.payload_schemas.write().await;
+        map.insert(atype, fields);
+    }
+
     /// Adds or updates an inverse association mapping.
     pub async fn register_inverse_association(&self, atype: String, inverse_atype: String) {
         let mut map = self.inverse_map.write().await;
@@ -59,3 +310,105 @@ impl Default for AssociationRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::schema::ent_schema::{
+        EdgeDefinition, EntSchema, EntityType, FieldDefinition,
+    };
+    use crate::schemas::create_schema_registry;
+
+    #[tokio::test]
+    async fn test_registry_from_schemas_has_inverse_pairs() {
+        let registry =
+            AssociationRegistry::from_schema_registry(&create_schema_registry()).unwrap();
+
+        for (atype, inverse) in [
+            ("friends", "friends"),
+            ("following", "followers"),
+            ("liked_posts", "liked_by"),
+            ("liked_by", "liked_posts"),
+            ("attending_events", "attendees"),
+        ] {
+            assert_eq!(
+                registry
+                    .get_inverse_association_type(atype)
+                    .await
+                    .as_deref(),
+                Some(inverse),
+                "inverse of {}",
+                atype
+            );
+        }
+        // `followers` means different things on users and pages
+        assert_eq!(
+            registry.get_inverse_association_type("followers").await,
+            None
+        );
+        let page_followers = registry
+            .get_association("ent_page", "followers")
+            .await
+            .unwrap();
+        assert_eq!(page_followers.target_type, "ent_user");
+        assert_eq!(
+            page_followers.inverse_atype.as_deref(),
+            Some("followed_pages")
+        );
+
+        // Users declare no `comments` edge, so it is implied by the comment's `author`
+        let comments = registry
+            .get_association("ent_user", "comments")
+            .await
+            .unwrap();
+        assert_eq!(comments.target_type, "ent_comment");
+        assert_eq!(comments.inverse_atype.as_deref(), Some("author"));
+
+        // Only the side declaring a payload validates its data
+        let payload = registry.get_payload_schema("groups").await.unwrap();
+        assert_eq!(payload[0].name, "role");
+        assert!(registry.get_payload_schema("members").await.is_none());
+    }
+
+    struct LeftSchema;
+    struct RightSchema;
+
+    impl EntSchema for LeftSchema {
+        fn entity_type() -> EntityType {
+            EntityType::EntUser
+        }
+        fn fields() -> Vec<FieldDefinition> {
+            Vec::new()
+        }
+        fn edges() -> Vec<EdgeDefinition> {
+            vec![EdgeDefinition::to("pins", EntityType::EntPage).inverse("pinned_by")]
+        }
+    }
+
+    impl EntSchema for RightSchema {
+        fn entity_type() -> EntityType {
+            EntityType::EntPage
+        }
+        fn fields() -> Vec<FieldDefinition> {
+            Vec::new()
+        }
+        fn edges() -> Vec<EdgeDefinition> {
+            vec![EdgeDefinition::from(
+                "pinned_by",
+                EntityType::EntUser,
+                "bookmarks",
+            )]
+        }
+    }
+
+    #[test]
+    fn test_registry_rejects_mismatched_inverses() {
+        let mut schemas = SchemaRegistry::new();
+        schemas.register::<LeftSchema>();
+        schemas.register::<RightSchema>();
+        assert!(matches!(
+            AssociationRegistry::from_schema_registry(&schemas),
+            Err(AppError::ConfigurationError(_))
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+// Access Tracker - samples object reads into a fixed-size frequency sketch
+// Used to see which objects are read most, e.g. when sizing the cache or picking TTLs
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::infrastructure::monitoring::monitoring::sample_evenly;
+use crate::infrastructure::tao_core::tao_core::TaoId;
+
+/// Counters per sketch row; each row costs 4 bytes per counter
+const SKETCH_WIDTH: usize = 4096;
+/// Sketch rows, each hashing ids with its own seed
+const SKETCH_DEPTH: usize = 4;
+/// Ids kept as hot object candidates, the most `hot_objects` can return
+const MAX_HOT_CANDIDATES: usize = 64;
+/// Sampled reads after which every count is halved, so old reads fade out
+const AGING_PERIOD: u64 = 10 * SKETCH_WIDTH as u64;
+
+const ROW_SEEDS: [u64; SKETCH_DEPTH] = [
+    0x9E37_79B9_7F4A_7C15,
+    0xC2B2_AE3D_27D4_EB4F,
+    0x1656_67B1_9E37_79F9,
+    0x27D4_EB2F_1656_67C5,
+];
+
+/// An object and how many of its reads the tracker sampled, aged by `AGING_PERIOD`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HotObject {
+    pub id: TaoId,
+    pub sampled_reads: u32,
+}
+
+/// Count-min sketch of sampled object reads, with a small set of the most read ids.
+/// Memory is fixed at construction; recording a read allocates nothing, and only the
+/// periodic aging waits on a lock.
+#[derive(Debug)]
+pub struct AccessTracker {
+    sample_rate: f64,
+    sample_counter: AtomicU64,
+    sampled: AtomicU64,
+    sketch: Vec<AtomicU32>,
+    /// Best known ids with their estimated counts, at most `MAX_HOT_CANDIDATES`
+    candidates: Mutex<Vec<(TaoId, u32)>>,
+}
+
+impl AccessTracker {
+    /// Sample roughly `sample_rate` of reads, e.g. the `trace_sample_rate` of the
+    /// metrics `SamplingConfig`
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            sample_counter: AtomicU64::new(0),
+            sampled: AtomicU64::new(0),
+            sketch: (0..SKETCH_WIDTH * SKETCH_DEPTH)
+                .map(|_| AtomicU32::new(0))
+                .collect(),
+            candidates: Mutex::new(Vec::with_capacity(MAX_HOT_CANDIDATES)),
+        }
+    }
+
+    /// Note a read of `id`, if it is sampled
+    pub fn record(&self, id: TaoId) {
+        if !sample_evenly(&self.sample_counter, self.sample_rate) {
+            return;
+        }
+
+        let mut estimate = u32::MAX;
+        for row in 0..SKETCH_DEPTH {
+            let count = self.sketch[Self::slot(row, id)].fetch_add(1, Ordering::Relaxed);
+            estimate = estimate.min(count.saturating_add(1));
+        }
+
+        // A contended candidate list just misses this sample; the sketch still has it
+        if let Ok(mut candidates) = self.candidates.try_lock() {
+            if let Some(candidate) = candidates.iter_mut().find(|(known, _)| *known == id) {
+                candidate.1 = estimate;
+            } else if candidates.len() < MAX_HOT_CANDIDATES {
+                candidates.push((id, estimate));
+            } else if let Some(coldest) = candidates.iter_mut().min_by_key(|(_, count)| *count) {
+                if coldest.1 < estimate {
+                    *coldest = (id, estimate);
+                }
+            }
+        }
+
+        if (self.sampled.fetch_add(1, Ordering::Relaxed) + 1).is_multiple_of(AGING_PERIOD) {
+            self.age();
+        }
+    }
+
+    /// Estimated sampled reads of `id`. Never an undercount, though other ids hashing to
+    /// the same counters can inflate it.
+    pub fn estimate(&self, id: TaoId) -> u32 {
+        (0..SKETCH_DEPTH)
+            .map(|row| self.sketch[Self::slot(row, id)].load(Ordering::Relaxed))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Up to `top_n` of the most read objects, most read first
+    pub fn hot_objects(&self, top_n: usize) -> Vec<HotObject> {
+        let ids: Vec<TaoId> = match self.candidates.lock() {
+            Ok(candidates) => candidates.iter().map(|(id, _)| *id).collect(),
+            Err(_) => return Vec::new(),
+        };
+        let mut hot: Vec<HotObject> = ids
+            .into_iter()
+            .map(|id| HotObject {
+                id,
+                sampled_reads: self.estimate(id),
+            })
+            .filter(|object| object.sampled_reads > 0)
+            .collect();
+        hot.sort_by(|a, b| b.sampled_reads.cmp(&a.sampled_reads).then(a.id.cmp(&b.id)));
+        hot.truncate(top_n);
+        hot
+    }
+
+    /// Halve every count
+    fn age(&self) {
+        for counter in &self.sketch {
+            let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count / 2)
+            });
+        }
+        if let Ok(mut candidates) = self.candidates.lock() {
+            for candidate in candidates.iter_mut() {
+                candidate.1 /= 2;
+            }
+        }
+    }
+
+    fn slot(row: usize, id: TaoId) -> usize {
+        // splitmix64 finalizer, seeded per row
+        let mut x = (id as u64) ^ ROW_SEEDS[row];
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        row * SKETCH_WIDTH + (x as usize % SKETCH_WIDTH)
+    }
+}
@@ -39,6 +39,11 @@ impl CacheEntry {
         self.inserted_at.elapsed() > self.ttl
     }
 
+    /// Whether the entry is past its TTL plus the allowed staleness window
+    pub fn is_past_stale_window(&self, max_stale_age: Duration) -> bool {
+        self.inserted_at.elapsed() > self.ttl + max_stale_age
+    }
+
     pub fn access(&mut self) {
         self.access_count += 1;
         self.last_accessed = Instant::now();
@@ -50,7 +55,7 @@ pub struct TaoMultiTierCache {
     /// L1 Cache: Local in-memory cache (fastest)
     l1_cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
     /// L2 Cache: Distributed cache (Redis/Memcached)
-    l2_cache: Option<Arc<dyn DistributedCache + Send + Sync>>,
+    l2_cache: Option<Arc<dyn CacheBackend + Send + Sync>>,
     /// Cache configuration
     config: CacheConfig,
     /// Cache metrics for monitoring
@@ -72,9 +77,16 @@ pub struct CacheConfig {
     pub l1_max_entries: usize,
     pub l1_default_ttl: Duration,
     pub l2_default_ttl: Duration,
+    /// TTL for association lists in both tiers; `None` uses the tier defaults.
+    /// Lets slow-changing lists (e.g. friends) outlive object entries
+    pub assoc_ttl: Option<Duration>,
     pub enable_write_through: bool,
     pub enable_read_through: bool,
     pub invalidation_enabled: bool,
+    /// Serve expired L1 entries when the backing store is unavailable (opt-in)
+    pub serve_stale_on_unavailable: bool,
+    /// How long past its TTL an entry may still be served as stale
+    pub max_stale_age: Duration,
 }
 
 impl Default for CacheConfig {
@@ -83,9 +95,12 @@ impl Default for CacheConfig {
             l1_max_entries: 10_000,
             l1_default_ttl: Duration::from_secs(300), // 5 minutes
             l2_default_ttl: Duration::from_secs(3600), // 1 hour
+            assoc_ttl: None,
             enable_write_through: true,
             enable_read_through: true,
             invalidation_enabled: true,
+            serve_stale_on_unavailable: false,
+            max_stale_age: Duration::from_secs(3600), // 1 hour
         }
     }
 }
@@ -143,11 +158,15 @@ impl TaoMultiTierCache {
         }
     }
 
-    pub fn with_l2_cache(mut self, l2_cache: Arc<dyn DistributedCache + Send + Sync>) -> Self {
+    pub fn with_l2_cache(mut self, l2_cache: Arc<dyn CacheBackend + Send + Sync>) -> Self {
         self.l2_cache = Some(l2_cache);
         self
     }
 
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
     /// Get object with multi-tier cache lookup
     #[instrument(skip(self))]
     pub async fn get_object(&self, object_id: TaoId) -> AppResult<Option<TaoObject>> {
@@ -159,7 +178,7 @@ impl TaoMultiTierCache {
                 info!("L1 cache hit for object {}", object_id);
                 self.record_l1_hit().await;
                 return Ok(Some(self.deserialize_object(&entry.data)?));
-            } else {
+            } else if !self.config.serve_stale_on_unavailable {
                 // Remove expired entry
                 self.invalidate_l1(&cache_key).await;
             }
@@ -188,19 +207,43 @@ impl TaoMultiTierCache {
     /// Cache object with write-through to both layers
     #[instrument(skip(self, object))]
     pub async fn put_object(&self, object_id: TaoId, object: &TaoObject) -> AppResult<()> {
+        self.write_object(
+            object_id,
+            object,
+            self.config.l1_default_ttl,
+            self.config.l2_default_ttl,
+        )
+        .await
+    }
+
+    /// Cache object in both layers for `ttl` instead of the tier defaults
+    #[instrument(skip(self, object))]
+    pub async fn put_object_with_ttl(
+        &self,
+        object_id: TaoId,
+        object: &TaoObject,
+        ttl: Duration,
+    ) -> AppResult<()> {
+        self.write_object(object_id, object, ttl, ttl).await
+    }
+
+    async fn write_object(
+        &self,
+        object_id: TaoId,
+        object: &TaoObject,
+        l1_ttl: Duration,
+        l2_ttl: Duration,
+    ) -> AppResult<()> {
         let cache_key = format!("obj:{}", object_id);
         let data = self.serialize_object(object)?;
 
         // Write to L1 cache
-        self.put_l1(&cache_key, data.clone(), self.config.l1_default_ttl)
-            .await;
+        self.put_l1(&cache_key, data.clone(), l1_ttl).await;
 
         // Write through to L2 cache if enabled
         if self.config.enable_write_through {
             if let Some(ref l2_cache) = self.l2_cache {
-                l2_cache
-                    .put(&cache_key, data, self.config.l2_default_ttl)
-                    .await?;
+                l2_cache.put(&cache_key, data, l2_ttl).await?;
                 self.record_write_through().await;
             }
         }
@@ -213,13 +256,16 @@ impl TaoMultiTierCache {
     #[instrument(skip(self))]
     pub async fn invalidate_object(&self, object_id: TaoId) -> AppResult<()> {
         let cache_key = format!("obj:{}", object_id);
+        let counts_key = format!("counts:{}", object_id);
 
         // Invalidate L1
         self.invalidate_l1(&cache_key).await;
+        self.invalidate_l1(&counts_key).await;
 
         // Invalidate L2
         if let Some(ref l2_cache) = self.l2_cache {
             l2_cache.delete(&cache_key).await?;
+            l2_cache.delete(&counts_key).await?;
         }
 
         self.record_invalidation().await;
@@ -227,6 +273,36 @@ impl TaoMultiTierCache {
         Ok(())
     }
 
+    /// Invalidate the cached association list for (id1, atype) from all cache layers
+    #[instrument(skip(self))]
+    pub async fn invalidate_associations(&self, id1: TaoId, atype: &str) -> AppResult<()> {
+        let cache_key = format!("assoc:{}:{}", id1, atype);
+        self.invalidate_l1(&cache_key).await;
+        if let Some(ref l2_cache) = self.l2_cache {
+            l2_cache.delete(&cache_key).await?;
+        }
+        self.record_invalidation().await;
+        Ok(())
+    }
+
+    /// Drop this node's L1 copy of an object after another node changed it.
+    /// L2 is shared, so the writing node has already cleared it there.
+    pub async fn evict_local_object(&self, object_id: TaoId) {
+        self.invalidate_l1(&format!("obj:{}", object_id)).await;
+        self.invalidate_l1(&format!("counts:{}", object_id)).await;
+    }
+
+    /// Drop this node's L1 copy of an association list after another node changed it
+    pub async fn evict_local_associations(&self, id1: TaoId, atype: &str) {
+        self.invalidate_l1(&format!("assoc:{}:{}", id1, atype))
+            .await;
+    }
+
+    /// Drop every L1 entry, e.g. after missing invalidations from other nodes
+    pub async fn clear_local(&self) {
+        self.l1_cache.write().await.clear();
+    }
+
     /// Cache associations with pagination support
     #[instrument(skip(self, associations))]
     pub async fn put_associations(
@@ -238,13 +314,17 @@ impl TaoMultiTierCache {
         let cache_key = format!("assoc:{}:{}", id1, atype);
         let data = self.serialize_associations(associations)?;
 
-        self.put_l1(&cache_key, data.clone(), self.config.l1_default_ttl)
-            .await;
+        self.put_l1(
+            &cache_key,
+            data.clone(),
+            self.assoc_ttl(self.config.l1_default_ttl),
+        )
+        .await;
 
         if self.config.enable_write_through {
             if let Some(ref l2_cache) = self.l2_cache {
                 l2_cache
-                    .put(&cache_key, data, self.config.l2_default_ttl)
+                    .put(&cache_key, data, self.assoc_ttl(self.config.l2_default_ttl))
                     .await?;
                 self.record_write_through().await;
             }
@@ -267,7 +347,7 @@ impl TaoMultiTierCache {
             if !entry.is_expired() {
                 self.record_l1_hit().await;
                 return Ok(Some(self.deserialize_associations(&entry.data)?));
-            } else {
+            } else if !self.config.serve_stale_on_unavailable {
                 self.invalidate_l1(&cache_key).await;
             }
         }
@@ -275,12 +355,86 @@ impl TaoMultiTierCache {
         self.record_l1_miss().await;
 
         // Try L2
+        if let Some(ref l2_cache) = self.l2_cache {
+            if let Some(data) = l2_cache.get(&cache_key).await? {
+                self.record_l2_hit().await;
+                self.put_l1(
+                    &cache_key,
+                    data.clone(),
+                    self.assoc_ttl(self.config.l1_default_ttl),
+                )
+                .await;
+                return Ok(Some(self.deserialize_associations(&data)?));
+            }
+        }
+
+        self.record_l2_miss().await;
+        Ok(None)
+    }
+
+    /// Cache association counts for `id1`, merged into any counts already cached for it.
+    /// All of an object's counts share one key, so `invalidate_object` drops them with it.
+    #[instrument(skip(self, counts))]
+    pub async fn put_assoc_counts(
+        &self,
+        id1: TaoId,
+        counts: &HashMap<String, u64>,
+    ) -> AppResult<()> {
+        let cache_key = format!("counts:{}", id1);
+        let mut merged = self.get_assoc_counts(id1).await?.unwrap_or_default();
+        merged.extend(counts.iter().map(|(atype, count)| (atype.clone(), *count)));
+        let data = bincode::serialize(&merged).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize association counts: {}", e))
+        })?;
+
+        self.put_l1(&cache_key, data.clone(), self.config.l1_default_ttl)
+            .await;
+
+        if self.config.enable_write_through {
+            if let Some(ref l2_cache) = self.l2_cache {
+                l2_cache
+                    .put(&cache_key, data, self.config.l2_default_ttl)
+                    .await?;
+                self.record_write_through().await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a cached association count
+    #[instrument(skip(self))]
+    pub async fn get_assoc_count(&self, id1: TaoId, atype: &str) -> AppResult<Option<u64>> {
+        Ok(self
+            .get_assoc_counts(id1)
+            .await?
+            .and_then(|counts| counts.get(atype).copied()))
+    }
+
+    async fn get_assoc_counts(&self, id1: TaoId) -> AppResult<Option<HashMap<String, u64>>> {
+        let cache_key = format!("counts:{}", id1);
+        let deserialize = |data: &[u8]| {
+            bincode::deserialize(data).map_err(|e| {
+                AppError::Internal(format!("Failed to deserialize association counts: {}", e))
+            })
+        };
+
+        if let Some(entry) = self.get_from_l1(&cache_key).await {
+            if !entry.is_expired() {
+                self.record_l1_hit().await;
+                return Ok(Some(deserialize(&entry.data)?));
+            }
+            self.invalidate_l1(&cache_key).await;
+        }
+
+        self.record_l1_miss().await;
+
         if let Some(ref l2_cache) = self.l2_cache {
             if let Some(data) = l2_cache.get(&cache_key).await? {
                 self.record_l2_hit().await;
                 self.put_l1(&cache_key, data.clone(), self.config.l1_default_ttl)
                     .await;
-                return Ok(Some(self.deserialize_associations(&data)?));
+                return Ok(Some(deserialize(&data)?));
             }
         }
 
@@ -288,7 +442,41 @@ impl TaoMultiTierCache {
         Ok(None)
     }
 
+    /// Get the last-known object from L1, ignoring its TTL.
+    /// Only used as a fallback when the backing store is unavailable.
+    #[instrument(skip(self))]
+    pub async fn get_stale_object(&self, object_id: TaoId) -> AppResult<Option<TaoObject>> {
+        let cache_key = format!("obj:{}", object_id);
+        match self.get_stale_from_l1(&cache_key).await {
+            Some(entry) => Ok(Some(self.deserialize_object(&entry.data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the last-known associations from L1, ignoring their TTL.
+    /// Only used as a fallback when the backing store is unavailable.
+    #[instrument(skip(self))]
+    pub async fn get_stale_associations(
+        &self,
+        id1: TaoId,
+        atype: &str,
+    ) -> AppResult<Option<Vec<TaoAssociation>>> {
+        let cache_key = format!("assoc:{}:{}", id1, atype);
+        match self.get_stale_from_l1(&cache_key).await {
+            Some(entry) => Ok(Some(self.deserialize_associations(&entry.data)?)),
+            None => Ok(None),
+        }
+    }
+
     /// L1 cache operations
+    async fn get_stale_from_l1(&self, key: &str) -> Option<CacheEntry> {
+        let entry = self.get_from_l1(key).await?;
+        if entry.is_past_stale_window(self.config.max_stale_age) {
+            return None;
+        }
+        Some(entry)
+    }
+
     async fn get_from_l1(&self, key: &str) -> Option<CacheEntry> {
         let mut cache = self.l1_cache.write().await;
         if let Some(entry) = cache.get_mut(key) {
@@ -311,6 +499,10 @@ impl TaoMultiTierCache {
         cache.insert(key.to_string(), entry);
     }
 
+    fn assoc_ttl(&self, tier_default: Duration) -> Duration {
+        self.config.assoc_ttl.unwrap_or(tier_default)
+    }
+
     async fn invalidate_l1(&self, key: &str) {
         let mut cache = self.l1_cache.write().await;
         cache.remove(key);
@@ -377,9 +569,18 @@ impl TaoMultiTierCache {
     /// Background cleanup for expired entries
     pub async fn cleanup_expired(&self) {
         let mut cache = self.l1_cache.write().await;
+        let serve_stale = self.config.serve_stale_on_unavailable;
+        let max_stale_age = self.config.max_stale_age;
         let expired_keys: Vec<String> = cache
             .iter()
-            .filter(|(_, entry)| entry.is_expired())
+            .filter(|(_, entry)| {
+                if serve_stale {
+                    // Keep expired entries around as stale fallbacks until the window closes
+                    entry.is_past_stale_window(max_stale_age)
+                } else {
+                    entry.is_expired()
+                }
+            })
             .map(|(key, _)| key.clone())
             .collect();
 
@@ -389,9 +590,10 @@ impl TaoMultiTierCache {
     }
 }
 
-/// Distributed cache interface (Redis/Memcached abstraction)
+/// L2 cache backend (Redis/Memcached abstraction). Shared by every node, so a
+/// `delete` here is seen cluster-wide.
 #[async_trait::async_trait]
-pub trait DistributedCache {
+pub trait CacheBackend {
     async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>>;
     async fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> AppResult<()>;
     async fn delete(&self, key: &str) -> AppResult<()>;
@@ -401,51 +603,218 @@ pub trait DistributedCache {
     async fn mget(&self, keys: &[String]) -> AppResult<Vec<Option<Vec<u8>>>>;
     async fn mset(&self, items: &[(String, Vec<u8>)], ttl: Duration) -> AppResult<()>;
 
-    /// Cache invalidation
+    /// Delete every key matching a glob pattern where `*` matches any run of characters
     async fn invalidate_pattern(&self, pattern: &str) -> AppResult<u64>;
 }
 
-/// Redis implementation of distributed cache
-pub struct RedisDistributedCache {
-    // Redis client would go here
-    // client: redis::Client,
+/// Process-local backend, for single-node deployments and tests
+#[derive(Debug, Default)]
+pub struct InMemoryCacheBackend {
+    entries: RwLock<HashMap<String, (Vec<u8>, Instant)>>,
+}
+
+impl InMemoryCacheBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
 }
 
 #[async_trait::async_trait]
-impl DistributedCache for RedisDistributedCache {
-    async fn get(&self, _key: &str) -> AppResult<Option<Vec<u8>>> {
-        // Redis GET implementation
-        Ok(None)
+impl CacheBackend for InMemoryCacheBackend {
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        let entries = self.entries.read().await;
+        Ok(entries
+            .get(key)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(value, _)| value.clone()))
     }
 
-    async fn put(&self, _key: &str, _value: Vec<u8>, _ttl: Duration) -> AppResult<()> {
-        // Redis SET with TTL implementation
+    async fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> AppResult<()> {
+        let mut entries = self.entries.write().await;
+        entries.insert(key.to_string(), (value, Instant::now() + ttl));
         Ok(())
     }
 
-    async fn delete(&self, _key: &str) -> AppResult<()> {
-        // Redis DEL implementation
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        self.entries.write().await.remove(key);
         Ok(())
     }
 
-    async fn exists(&self, _key: &str) -> AppResult<bool> {
-        // Redis EXISTS implementation
-        Ok(false)
+    async fn exists(&self, key: &str) -> AppResult<bool> {
+        Ok(self.get(key).await?.is_some())
     }
 
-    async fn mget(&self, _keys: &[String]) -> AppResult<Vec<Option<Vec<u8>>>> {
-        // Redis MGET implementation
-        Ok(vec![])
+    async fn mget(&self, keys: &[String]) -> AppResult<Vec<Option<Vec<u8>>>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
     }
 
-    async fn mset(&self, _items: &[(String, Vec<u8>)], _ttl: Duration) -> AppResult<()> {
-        // Redis MSET with TTL implementation
+    async fn mset(&self, items: &[(String, Vec<u8>)], ttl: Duration) -> AppResult<()> {
+        let mut entries = self.entries.write().await;
+        let expires_at = Instant::now() + ttl;
+        for (key, value) in items {
+            entries.insert(key.clone(), (value.clone(), expires_at));
+        }
         Ok(())
     }
 
-    async fn invalidate_pattern(&self, _pattern: &str) -> AppResult<u64> {
-        // Redis pattern-based deletion
-        Ok(0)
+    async fn invalidate_pattern(&self, pattern: &str) -> AppResult<u64> {
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|key, _| !glob_matches(pattern, key));
+        Ok((before - entries.len()) as u64)
+    }
+}
+
+/// Match `key` against a pattern whose only wildcard is `*`, as Redis `SCAN MATCH` does
+fn glob_matches(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard at all
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Redis-backed L2, enabled with the `redis` feature. Keys are namespaced by `prefix`
+/// so several deployments can share one Redis.
+#[cfg(feature = "redis")]
+pub struct RedisCacheBackend {
+    connection: redis::aio::ConnectionManager,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisCacheBackend {
+    /// Connect to `url` (e.g. `redis://127.0.0.1:6379`)
+    pub async fn connect(url: &str, prefix: &str) -> AppResult<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| AppError::ConfigurationError(format!("Invalid Redis URL: {}", e)))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("Redis unavailable: {}", e)))?;
+        Ok(Self {
+            connection,
+            prefix: prefix.to_string(),
+        })
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[cfg(feature = "redis")]
+fn redis_error(e: redis::RedisError) -> AppError {
+    AppError::ServiceUnavailable(format!("Redis error: {}", e))
+}
+
+#[cfg(feature = "redis")]
+#[async_trait::async_trait]
+impl CacheBackend for RedisCacheBackend {
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        redis::cmd("GET")
+            .arg(self.key(key))
+            .query_async(&mut self.connection.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> AppResult<()> {
+        redis::cmd("SET")
+            .arg(self.key(key))
+            .arg(value)
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut self.connection.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        redis::cmd("DEL")
+            .arg(self.key(key))
+            .query_async(&mut self.connection.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn exists(&self, key: &str) -> AppResult<bool> {
+        redis::cmd("EXISTS")
+            .arg(self.key(key))
+            .query_async(&mut self.connection.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn mget(&self, keys: &[String]) -> AppResult<Vec<Option<Vec<u8>>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let keys: Vec<String> = keys.iter().map(|key| self.key(key)).collect();
+        redis::cmd("MGET")
+            .arg(keys)
+            .query_async(&mut self.connection.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn mset(&self, items: &[(String, Vec<u8>)], ttl: Duration) -> AppResult<()> {
+        let mut pipe = redis::pipe();
+        for (key, value) in items {
+            pipe.cmd("SET")
+                .arg(self.key(key))
+                .arg(value.as_slice())
+                .arg("PX")
+                .arg(ttl.as_millis().max(1) as u64)
+                .ignore();
+        }
+        pipe.query_async(&mut self.connection.clone())
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn invalidate_pattern(&self, pattern: &str) -> AppResult<u64> {
+        let mut connection = self.connection.clone();
+        let mut cursor: u64 = 0;
+        let mut deleted = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(self.key(pattern))
+                .arg("COUNT")
+                .arg(500)
+                .query_async(&mut connection)
+                .await
+                .map_err(redis_error)?;
+            if !keys.is_empty() {
+                let removed: u64 = redis::cmd("DEL")
+                    .arg(keys)
+                    .query_async(&mut connection)
+                    .await
+                    .map_err(redis_error)?;
+                deleted += removed;
+            }
+            if next == 0 {
+                return Ok(deleted);
+            }
+            cursor = next;
+        }
     }
 }
 
@@ -555,3 +924,121 @@ pub async fn initialize_cache_default() -> AppResult<Arc<TaoMultiTierCache>> {
     info!("✅ Multi-tier cache initialized with default configuration");
     Ok(Arc::new(cache))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Semantics every `CacheBackend` must share, so backends are interchangeable as L2
+    async fn assert_backend_semantics(backend: &dyn CacheBackend) {
+        let ttl = Duration::from_secs(60);
+        assert_eq!(backend.get("obj:1").await.unwrap(), None);
+
+        backend.put("obj:1", vec![1], ttl).await.unwrap();
+        backend.put("obj:1", vec![2], ttl).await.unwrap();
+        assert_eq!(backend.get("obj:1").await.unwrap(), Some(vec![2]));
+        assert!(backend.exists("obj:1").await.unwrap());
+
+        backend
+            .mset(
+                &[
+                    ("obj:2".to_string(), vec![3]),
+                    ("assoc:1:likes".to_string(), vec![4]),
+                ],
+                ttl,
+            )
+            .await
+            .unwrap();
+        let keys = ["obj:2".to_string(), "obj:9".to_string()];
+        assert_eq!(
+            backend.mget(&keys).await.unwrap(),
+            vec![Some(vec![3]), None]
+        );
+
+        backend.delete("obj:1").await.unwrap();
+        assert_eq!(backend.get("obj:1").await.unwrap(), None);
+        assert!(!backend.exists("obj:1").await.unwrap());
+
+        assert_eq!(backend.invalidate_pattern("obj:*").await.unwrap(), 1);
+        assert_eq!(backend.get("obj:2").await.unwrap(), None);
+        assert_eq!(backend.get("assoc:1:likes").await.unwrap(), Some(vec![4]));
+
+        backend
+            .put("obj:3", vec![5], Duration::from_millis(1))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(backend.get("obj:3").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_semantics() {
+        assert_backend_semantics(&InMemoryCacheBackend::new()).await;
+
+        assert!(glob_matches("assoc:*:likes", "assoc:12:likes"));
+        assert!(!glob_matches("assoc:*:likes", "assoc:12:liked_by"));
+        assert!(glob_matches("obj:1", "obj:1"));
+        assert!(!glob_matches("obj:1", "obj:12"));
+    }
+
+    #[tokio::test]
+    async fn test_shared_l2_sees_other_nodes_writes_and_invalidations() {
+        let l2 = Arc::new(InMemoryCacheBackend::new());
+        let node_a = TaoMultiTierCache::new(CacheConfig::default()).with_l2_cache(l2.clone());
+        let node_b = TaoMultiTierCache::new(CacheConfig::default()).with_l2_cache(l2);
+        let object = TaoObject {
+            id: 7,
+            otype: "ent_user".to_string(),
+            data: vec![1, 2, 3],
+            created_time: 1,
+            updated_time: 1,
+            version: 1,
+        };
+
+        node_a.put_object(7, &object).await.unwrap();
+        node_a.invalidate_object(7).await.unwrap();
+        assert!(node_b.get_object(7).await.unwrap().is_none());
+
+        node_a.put_object(7, &object).await.unwrap();
+        assert_eq!(
+            node_b.get_object(7).await.unwrap().unwrap().data,
+            vec![1, 2, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assoc_ttl_expires_independently_of_objects() {
+        let cache = TaoMultiTierCache::new(CacheConfig {
+            l1_default_ttl: Duration::from_secs(60),
+            assoc_ttl: Some(Duration::from_millis(20)),
+            ..CacheConfig::default()
+        });
+        let object = TaoObject {
+            id: 7,
+            otype: "ent_user".to_string(),
+            data: vec![1],
+            created_time: 1,
+            updated_time: 1,
+            version: 1,
+        };
+        let friend = TaoAssociation {
+            id1: 7,
+            atype: "friend".to_string(),
+            id2: 8,
+            time: 1,
+            data: None,
+            subtype: None,
+        };
+
+        cache.put_object(7, &object).await.unwrap();
+        cache
+            .put_associations(7, "friend", &[friend])
+            .await
+            .unwrap();
+        assert!(cache.get_associations(7, "friend").await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(cache.get_associations(7, "friend").await.unwrap().is_none());
+        assert!(cache.get_object(7).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,40 @@
+// Cache Policies - per-otype object caching rules declared by the schemas
+// Consulted by the cache decorator after a read, once the object's otype is known
+
+use std::collections::HashMap;
+
+use crate::framework::schema::ent_schema::{CachePolicy, SchemaRegistry};
+
+/// Maps otypes to their `CachePolicy`. Otypes without an entry use `CachePolicy::Default`.
+#[derive(Debug, Clone, Default)]
+pub struct CachePolicies {
+    policies: HashMap<String, CachePolicy>,
+}
+
+impl CachePolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policies of every registered schema that declares a non-default one
+    pub fn from_schema_registry(schemas: &SchemaRegistry) -> Self {
+        let mut policies = Self::new();
+        for entity_type in schemas.get_entity_types() {
+            policies.set(entity_type.as_str(), schemas.get_cache_policy(entity_type));
+        }
+        policies
+    }
+
+    pub fn set(&mut self, otype: impl Into<String>, policy: CachePolicy) {
+        let otype = otype.into();
+        if policy == CachePolicy::Default {
+            self.policies.remove(&otype);
+        } else {
+            self.policies.insert(otype, policy);
+        }
+    }
+
+    pub fn policy_for(&self, otype: &str) -> CachePolicy {
+        self.policies.get(otype).copied().unwrap_or_default()
+    }
+}
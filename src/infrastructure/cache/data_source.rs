@@ -0,0 +1,75 @@
+// Data Source Tracking - Request-scoped record of where reads were served from
+// The cache and replica selection record their part; the HTTP layer reports it to clients
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::task_local;
+
+/// Where a read was served from, ordered from most to least fresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataSource {
+    /// The shard's primary database
+    Primary,
+    /// A read replica, up to its replication lag behind the primary
+    Replica,
+    /// A cached copy, up to its TTL behind the database
+    Cache,
+}
+
+impl DataSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Primary => "primary",
+            Self::Replica => "replica",
+            Self::Cache => "cache",
+        }
+    }
+}
+
+task_local! {
+    static DATA_SOURCE: Arc<Mutex<Option<DataSource>>>;
+}
+
+/// Run `f` with data source tracking enabled. Returns the output of `f` and the least
+/// fresh source any read inside it was served from, or `None` if nothing was read.
+pub async fn track_data_source<F>(f: F) -> (F::Output, Option<DataSource>)
+where
+    F: Future,
+{
+    let source = Arc::new(Mutex::new(None));
+    let output = DATA_SOURCE.scope(source.clone(), f).await;
+    let source = *source.lock().unwrap_or_else(|e| e.into_inner());
+    (output, source)
+}
+
+/// Record that the current read was served from `source`.
+/// A no-op outside of `track_data_source`.
+pub fn record_data_source(source: DataSource) {
+    let _ = DATA_SOURCE.try_with(|recorded| {
+        let mut recorded = recorded.lock().unwrap_or_else(|e| e.into_inner());
+        *recorded = (*recorded).max(Some(source));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_least_fresh_source_wins() {
+        let ((), source) = track_data_source(async {}).await;
+        assert_eq!(source, None);
+
+        let ((), source) = track_data_source(async {
+            record_data_source(DataSource::Cache);
+            record_data_source(DataSource::Primary);
+        })
+        .await;
+        assert_eq!(source, Some(DataSource::Cache));
+
+        // Outside of a tracking scope this must not panic
+        record_data_source(DataSource::Replica);
+    }
+}
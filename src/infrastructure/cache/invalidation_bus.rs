@@ -0,0 +1,187 @@
+// Cache Invalidation Bus - Broadcasts cache invalidations so every node evicts its L1 copy
+// In-process broadcast for single-process setups and tests; Redis pub/sub behind the `redis` feature
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::error::AppResult;
+use crate::infrastructure::cache::cache_layer::TaoMultiTierCache;
+use crate::infrastructure::tao_core::tao_core::{AssocType, TaoId};
+
+/// Messages buffered per subscriber before it starts lagging
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// What a write made stale
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheInvalidation {
+    Object(TaoId),
+    Associations { id1: TaoId, atype: AssocType },
+}
+
+/// An invalidation tagged with the node that published it, so that node can skip its own
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidationMessage {
+    pub origin: String,
+    pub invalidation: CacheInvalidation,
+}
+
+#[async_trait]
+pub trait InvalidationBus: Send + Sync + std::fmt::Debug {
+    async fn publish(&self, message: InvalidationMessage) -> AppResult<()>;
+    /// Messages published by every node from now on, this node's own included
+    fn subscribe(&self) -> broadcast::Receiver<InvalidationMessage>;
+}
+
+/// Bus shared by the caches of one process
+#[derive(Debug)]
+pub struct InProcessInvalidationBus {
+    sender: broadcast::Sender<InvalidationMessage>,
+}
+
+impl InProcessInvalidationBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl Default for InProcessInvalidationBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl InvalidationBus for InProcessInvalidationBus {
+    async fn publish(&self, message: InvalidationMessage) -> AppResult<()> {
+        // No subscribers is not an error: nothing can be stale
+        let _ = self.sender.send(message);
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<InvalidationMessage> {
+        self.sender.subscribe()
+    }
+}
+
+/// Evict L1 entries named by other nodes' invalidations until the bus closes.
+/// A subscriber that falls behind has missed invalidations, so it drops its whole L1.
+pub fn spawn_invalidation_listener(
+    cache: Arc<TaoMultiTierCache>,
+    bus: &dyn InvalidationBus,
+    node_id: String,
+) -> JoinHandle<()> {
+    let mut receiver = bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) if message.origin == node_id => {}
+                Ok(message) => match message.invalidation {
+                    CacheInvalidation::Object(id) => cache.evict_local_object(id).await,
+                    CacheInvalidation::Associations { id1, atype } => {
+                        cache.evict_local_associations(id1, &atype).await
+                    }
+                },
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!(
+                        "Cache node {} missed {} invalidations, clearing L1",
+                        node_id, missed
+                    );
+                    cache.clear_local().await;
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    })
+}
+
+/// Redis pub/sub bus. Each node publishes to `channel` and forwards what it receives
+/// into a local broadcast channel for its listener.
+#[cfg(feature = "redis")]
+pub struct RedisInvalidationBus {
+    connection: redis::aio::ConnectionManager,
+    channel: String,
+    sender: broadcast::Sender<InvalidationMessage>,
+}
+
+#[cfg(feature = "redis")]
+impl RedisInvalidationBus {
+    pub async fn connect(url: &str, channel: &str) -> AppResult<Self> {
+        use crate::error::AppError;
+        use futures::StreamExt;
+
+        let client = redis::Client::open(url)
+            .map_err(|e| AppError::ConfigurationError(format!("Invalid Redis URL: {}", e)))?;
+        let unavailable = |e: redis::RedisError| {
+            AppError::ServiceUnavailable(format!("Redis unavailable: {}", e))
+        };
+        let connection = redis::aio::ConnectionManager::new(client.clone())
+            .await
+            .map_err(unavailable)?;
+        let mut pubsub = client.get_async_pubsub().await.map_err(unavailable)?;
+        pubsub.subscribe(channel).await.map_err(unavailable)?;
+
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let forward = sender.clone();
+        tokio::spawn(async move {
+            let mut messages = pubsub.into_on_message();
+            while let Some(msg) = messages.next().await {
+                let payload: Vec<u8> = match msg.get_payload() {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        warn!("Unreadable cache invalidation payload: {}", e);
+                        continue;
+                    }
+                };
+                match bincode::deserialize::<InvalidationMessage>(&payload) {
+                    Ok(message) => {
+                        let _ = forward.send(message);
+                    }
+                    Err(e) => warn!("Malformed cache invalidation message: {}", e),
+                }
+            }
+            warn!("Redis invalidation subscription ended");
+        });
+
+        Ok(Self {
+            connection,
+            channel: channel.to_string(),
+            sender,
+        })
+    }
+}
+
+#[cfg(feature = "redis")]
+impl std::fmt::Debug for RedisInvalidationBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisInvalidationBus")
+            .field("channel", &self.channel)
+            .finish()
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl InvalidationBus for RedisInvalidationBus {
+    async fn publish(&self, message: InvalidationMessage) -> AppResult<()> {
+        use crate::error::AppError;
+
+        let payload = bincode::serialize(&message).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize cache invalidation: {}", e))
+        })?;
+        redis::cmd("PUBLISH")
+            .arg(&self.channel)
+            .arg(payload)
+            .query_async::<()>(&mut self.connection.clone())
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("Redis error: {}", e)))
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<InvalidationMessage> {
+        self.sender.subscribe()
+    }
+}
@@ -1,2 +1,8 @@
+pub mod access_tracker;
 pub mod cache;
 pub mod cache_layer;
+pub mod cache_policy;
+pub mod data_source;
+pub mod invalidation_bus;
+pub mod read_consistency;
+pub mod stale_read;
@@ -0,0 +1,47 @@
+// Read Consistency - Request-scoped choice between cached and fresh reads
+// Lets callers (and the HTTP layer) bypass the cache without changing TaoOperations signatures
+
+use std::future::Future;
+use tokio::task_local;
+
+/// How reads inside a scope may be served
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadConsistency {
+    /// Cache hits (and degraded stale reads) are acceptable
+    #[default]
+    Cached,
+    /// Skip the cache lookup and replicas, read from the primary and repopulate the cache
+    Fresh,
+}
+
+task_local! {
+    static READ_CONSISTENCY: ReadConsistency;
+}
+
+/// Run `f` with every read inside it served at the given consistency
+pub async fn with_read_consistency<F>(consistency: ReadConsistency, f: F) -> F::Output
+where
+    F: Future,
+{
+    READ_CONSISTENCY.scope(consistency, f).await
+}
+
+/// Consistency requested by the enclosing scope; `Cached` outside of one
+pub fn current_read_consistency() -> ReadConsistency {
+    READ_CONSISTENCY.try_with(|c| *c).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_consistency_scoped_to_future() {
+        assert_eq!(current_read_consistency(), ReadConsistency::Cached);
+        let inside =
+            with_read_consistency(ReadConsistency::Fresh, async { current_read_consistency() })
+                .await;
+        assert_eq!(inside, ReadConsistency::Fresh);
+        assert_eq!(current_read_consistency(), ReadConsistency::Cached);
+    }
+}
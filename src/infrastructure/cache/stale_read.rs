@@ -0,0 +1,45 @@
+// Stale Read Tracking - Request-scoped flag raised when a read was served from expired cache
+// Lets the HTTP layer tell clients that a response is degraded without changing TaoOperations
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::task_local;
+
+task_local! {
+    static STALE_READ: Arc<AtomicBool>;
+}
+
+/// Run `f` with stale-read tracking enabled.
+/// Returns the output of `f` and whether any read inside it was served stale.
+pub async fn track_stale_reads<F>(f: F) -> (F::Output, bool)
+where
+    F: Future,
+{
+    let flag = Arc::new(AtomicBool::new(false));
+    let output = STALE_READ.scope(flag.clone(), f).await;
+    (output, flag.load(Ordering::Relaxed))
+}
+
+/// Record that the current read was served from stale cache.
+/// A no-op outside of `track_stale_reads`.
+pub fn mark_stale_read() {
+    let _ = STALE_READ.try_with(|flag| flag.store(true, Ordering::Relaxed));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stale_flag_scoped_to_future() {
+        let ((), stale) = track_stale_reads(async {}).await;
+        assert!(!stale);
+
+        let ((), stale) = track_stale_reads(async { mark_stale_read() }).await;
+        assert!(stale);
+
+        // Outside of a tracking scope this must not panic
+        mark_stale_read();
+    }
+}
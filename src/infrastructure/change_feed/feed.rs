@@ -0,0 +1,122 @@
+// Change Feed - Object and association change notifications for downstream systems
+// Search indexers, cache warmers, etc. subscribe instead of polling TAO
+
+use async_trait::async_trait;
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::error::AppResult;
+use crate::infrastructure::tao_core::tao_core::{AssocType, TaoId, TaoTime, TaoType};
+
+/// Default number of events buffered per subscriber before slow subscribers start lagging
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+/// A successful write to TAO
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    ObjectCreated {
+        id: TaoId,
+        otype: TaoType,
+    },
+    ObjectUpdated {
+        id: TaoId,
+    },
+    ObjectDeleted {
+        id: TaoId,
+    },
+    AssocAdded {
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+        time: TaoTime,
+    },
+    AssocDeleted {
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+    },
+    AssocsCleared {
+        id1: TaoId,
+        atype: AssocType,
+        count: u64,
+    },
+    AssocMoved {
+        id1: TaoId,
+        atype: AssocType,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    },
+    /// Edges between two objects removed in either direction, counted per type
+    AssocsDeletedBetween {
+        id1: TaoId,
+        id2: TaoId,
+        removed: HashMap<AssocType, u64>,
+    },
+}
+
+/// Destination for change events
+#[async_trait]
+pub trait ChangeFeed: Send + Sync + std::fmt::Debug {
+    /// Publish an event. Called after the write has succeeded, so a failure here
+    /// is reported but never undoes the write.
+    async fn publish(&self, event: ChangeEvent) -> AppResult<()>;
+}
+
+/// In-process change feed backed by a tokio broadcast channel.
+/// Every subscriber sees every event published after it subscribed, in order.
+#[derive(Debug)]
+pub struct BroadcastChangeFeed {
+    sender: broadcast::Sender<ChangeEvent>,
+}
+
+impl Default for BroadcastChangeFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHANNEL_CAPACITY)
+    }
+}
+
+impl BroadcastChangeFeed {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Subscribe to the raw channel
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribe as a stream. Events dropped because the subscriber fell behind
+    /// are logged and skipped; the stream ends when the feed is dropped.
+    pub fn stream(&self) -> impl Stream<Item = ChangeEvent> + Send + 'static {
+        stream::unfold(self.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Change feed subscriber lagged, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+#[async_trait]
+impl ChangeFeed for BroadcastChangeFeed {
+    async fn publish(&self, event: ChangeEvent) -> AppResult<()> {
+        // No subscribers is not an error - nobody is listening yet
+        let _ = self.sender.send(event);
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+// Webhook Change Sink - Delivers change events to external services over HTTP
+// Each endpoint gets its own ordered delivery queue with retry/backoff and a dead-letter list
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::{Request, StatusCode};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper_util::rt::TokioExecutor;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, warn};
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::change_feed::feed::{ChangeEvent, ChangeFeed};
+use crate::infrastructure::tao_core::tao_core::current_time_millis;
+
+/// Header carrying the HMAC-SHA256 signature of the request body, as `sha256=<hex>`
+pub const SIGNATURE_HEADER: &str = "x-tao-signature";
+
+type HmacSha256 = Hmac<Sha256>;
+type HttpClient = Client<HttpConnector, Full<Bytes>>;
+
+/// A single webhook receiver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpointConfig {
+    /// `http://` URL events are POSTed to
+    pub url: String,
+    /// Shared secret used to sign payloads for this endpoint
+    pub secret: String,
+}
+
+/// Configuration for webhook delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub endpoints: Vec<WebhookEndpointConfig>,
+    /// Delivery attempts per event before it is dead-lettered
+    pub max_attempts: u32,
+    /// Base delay for exponential backoff (ms)
+    pub base_retry_delay_ms: u64,
+    /// Maximum retry delay (ms)
+    pub max_retry_delay_ms: u64,
+    /// Per-request timeout (ms)
+    pub request_timeout_ms: u64,
+    /// Events buffered per endpoint; publishing to a full queue dead-letters the event
+    pub queue_capacity: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            max_attempts: 5,
+            base_retry_delay_ms: 100,
+            max_retry_delay_ms: 30_000, // 30 seconds
+            request_timeout_ms: 5_000,
+            queue_capacity: 10_000,
+        }
+    }
+}
+
+/// An event that could not be delivered to an endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub endpoint: String,
+    pub event: ChangeEvent,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: i64,
+}
+
+/// Signature header value for `body` signed with `secret`
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Check a signature header produced by `sign_payload`, in constant time
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(hex) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    if hex.len() % 2 != 0 {
+        return false;
+    }
+    let Ok(expected) = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+    else {
+        return false;
+    };
+
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Change feed that POSTs every event as JSON to the configured endpoints.
+/// `publish` only enqueues, so writes never wait on a receiver.
+#[derive(Debug)]
+pub struct WebhookChangeSink {
+    queues: Vec<(String, mpsc::Sender<ChangeEvent>)>,
+    dead_letters: Arc<Mutex<Vec<DeadLetter>>>,
+}
+
+impl WebhookChangeSink {
+    /// Create the sink and start one delivery worker per endpoint
+    pub fn new(config: WebhookConfig) -> Self {
+        let client: HttpClient = Client::builder(TokioExecutor::new()).build_http();
+        let dead_letters = Arc::new(Mutex::new(Vec::new()));
+
+        let queues = config
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+                tokio::spawn(Self::deliver_loop(
+                    endpoint.clone(),
+                    config.clone(),
+                    client.clone(),
+                    receiver,
+                    Arc::clone(&dead_letters),
+                ));
+                (endpoint.url.clone(), sender)
+            })
+            .collect();
+
+        Self {
+            queues,
+            dead_letters,
+        }
+    }
+
+    /// Events that permanently failed delivery
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.lock_dead_letters().clone()
+    }
+
+    /// Remove and return dead-lettered events, e.g. to replay them
+    pub fn drain_dead_letters(&self) -> Vec<DeadLetter> {
+        std::mem::take(&mut *self.lock_dead_letters())
+    }
+
+    fn lock_dead_letters(&self) -> std::sync::MutexGuard<'_, Vec<DeadLetter>> {
+        self.dead_letters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    async fn deliver_loop(
+        endpoint: WebhookEndpointConfig,
+        config: WebhookConfig,
+        client: HttpClient,
+        mut receiver: mpsc::Receiver<ChangeEvent>,
+        dead_letters: Arc<Mutex<Vec<DeadLetter>>>,
+    ) {
+        while let Some(event) = receiver.recv().await {
+            let (attempts, result) =
+                Self::deliver_with_retry(&endpoint, &config, &client, &event).await;
+            if let Err(e) = result {
+                error!(
+                    "Dead-lettering change event for {} after {} attempts: {}",
+                    endpoint.url, attempts, e
+                );
+                dead_letters
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .push(DeadLetter {
+                        endpoint: endpoint.url.clone(),
+                        event,
+                        attempts,
+                        last_error: e.to_string(),
+                        failed_at: current_time_millis(),
+                    });
+            }
+        }
+    }
+
+    /// Returns the number of attempts made and the final outcome
+    async fn deliver_with_retry(
+        endpoint: &WebhookEndpointConfig,
+        config: &WebhookConfig,
+        client: &HttpClient,
+        event: &ChangeEvent,
+    ) -> (u32, AppResult<()>) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => Bytes::from(body),
+            Err(e) => {
+                return (
+                    0,
+                    Err(AppError::SerializationError(format!(
+                        "Failed to serialize change event: {}",
+                        e
+                    ))),
+                )
+            }
+        };
+        let signature = sign_payload(&endpoint.secret, &body);
+        let max_attempts = config.max_attempts.max(1);
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = Self::post(endpoint, config, client, body.clone(), &signature).await;
+
+            match result {
+                Ok(()) => {
+                    debug!("Delivered change event to {}", endpoint.url);
+                    return (attempt, Ok(()));
+                }
+                Err((e, retryable)) => {
+                    if !retryable || attempt >= max_attempts {
+                        return (attempt, Err(e));
+                    }
+                    let delay = Self::retry_delay(config, attempt);
+                    warn!(
+                        "Webhook delivery to {} failed (attempt {}), retrying in {:?}: {}",
+                        endpoint.url, attempt, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Exponential backoff: base * 2^(attempt-1), capped at the configured maximum
+    fn retry_delay(config: &WebhookConfig, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = config.base_retry_delay_ms.saturating_mul(1 << exponent);
+        Duration::from_millis(delay.min(config.max_retry_delay_ms))
+    }
+
+    /// POST once. Errors carry whether a retry could help: client errors other than
+    /// 408/429 mean the receiver rejected the event itself and are not retried.
+    async fn post(
+        endpoint: &WebhookEndpointConfig,
+        config: &WebhookConfig,
+        client: &HttpClient,
+        body: Bytes,
+        signature: &str,
+    ) -> Result<(), (AppError, bool)> {
+        let request = Request::post(endpoint.url.as_str())
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(Full::new(body))
+            .map_err(|e| {
+                (
+                    AppError::Validation(format!("Invalid webhook URL {}: {}", endpoint.url, e)),
+                    false,
+                )
+            })?;
+
+        let timeout = Duration::from_millis(config.request_timeout_ms);
+        let response = match tokio::time::timeout(timeout, client.request(request)).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => {
+                return Err((
+                    AppError::ServiceUnavailable(format!("Webhook request failed: {}", e)),
+                    true,
+                ))
+            }
+            Err(_) => {
+                return Err((
+                    AppError::ServiceUnavailable(format!(
+                        "Webhook request timed out after {:?}",
+                        timeout
+                    )),
+                    true,
+                ))
+            }
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retryable = !status.is_client_error()
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS;
+        Err((
+            AppError::ServiceUnavailable(format!("Webhook receiver returned {}", status)),
+            retryable,
+        ))
+    }
+}
+
+#[async_trait]
+impl ChangeFeed for WebhookChangeSink {
+    async fn publish(&self, event: ChangeEvent) -> AppResult<()> {
+        for (url, queue) in &self.queues {
+            if let Err(e) = queue.try_send(event.clone()) {
+                let reason = match e {
+                    mpsc::error::TrySendError::Full(_) => "delivery queue full",
+                    mpsc::error::TrySendError::Closed(_) => "delivery worker stopped",
+                };
+                warn!("Dead-lettering change event for {}: {}", url, reason);
+                self.lock_dead_letters().push(DeadLetter {
+                    endpoint: url.clone(),
+                    event: event.clone(),
+                    attempts: 0,
+                    last_error: reason.to_string(),
+                    failed_at: current_time_millis(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::association_registry::AssociationRegistry;
+    use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+    use crate::infrastructure::id_generator::TaoIdGenerator;
+    use crate::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
+    use crate::infrastructure::shard_topology::{ShardHealth, ShardInfo};
+    use crate::infrastructure::tao_core::tao_core::{TaoCore, TaoOperations};
+    use crate::infrastructure::tao_core::tao_decorators::{BaseTao, ChangeFeedDecorator};
+    use axum::{http::HeaderMap, routing::post, Router};
+
+    async fn sqlite_base_tao() -> Arc<BaseTao> {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        query_router.add_shard(shard_info, database).await.unwrap();
+
+        let tao_core = Arc::new(TaoCore::new(
+            query_router,
+            Arc::new(AssociationRegistry::new()),
+        ));
+        Arc::new(BaseTao::new(tao_core))
+    }
+
+    /// Local receiver: `/ok` records requests, `/fail` always returns 500
+    async fn start_receiver() -> (String, mpsc::UnboundedReceiver<(HeaderMap, Bytes)>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route(
+                "/ok",
+                post(move |headers: HeaderMap, body: Bytes| {
+                    let sender = sender.clone();
+                    async move {
+                        let _ = sender.send((headers, body));
+                        StatusCode::OK
+                    }
+                }),
+            )
+            .route(
+                "/fail",
+                post(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), receiver)
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign_payload("secret", b"payload");
+        assert!(verify_signature("secret", b"payload", &signature));
+        assert!(!verify_signature("other", b"payload", &signature));
+        assert!(!verify_signature("secret", b"tampered", &signature));
+        assert!(!verify_signature("secret", b"payload", "sha256=zz"));
+    }
+
+    #[tokio::test]
+    async fn test_delivers_signed_events_and_dead_letters_failures() {
+        let (base_url, mut received) = start_receiver().await;
+        let sink = Arc::new(WebhookChangeSink::new(WebhookConfig {
+            endpoints: vec![
+                WebhookEndpointConfig {
+                    url: format!("{}/ok", base_url),
+                    secret: "ok-secret".to_string(),
+                },
+                WebhookEndpointConfig {
+                    url: format!("{}/fail", base_url),
+                    secret: "fail-secret".to_string(),
+                },
+            ],
+            max_attempts: 3,
+            base_retry_delay_ms: 5,
+            max_retry_delay_ms: 20,
+            ..WebhookConfig::default()
+        }));
+        let tao = ChangeFeedDecorator::new(sqlite_base_tao().await, sink.clone());
+
+        let id = TaoIdGenerator::new(0).next_id();
+        tao.create_object(id, "user".to_string(), vec![1])
+            .await
+            .unwrap();
+
+        let (headers, body) = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify_signature("ok-secret", &body, signature));
+        let event: ChangeEvent = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            event,
+            ChangeEvent::ObjectCreated {
+                id,
+                otype: "user".to_string()
+            }
+        );
+
+        // The failing endpoint exhausts its retries and the event is dead-lettered
+        let dead_letters = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let dead_letters = sink.dead_letters();
+                if !dead_letters.is_empty() {
+                    return dead_letters;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert!(dead_letters[0].endpoint.ends_with("/fail"));
+        assert_eq!(dead_letters[0].attempts, 3);
+        assert_eq!(dead_letters[0].event, event);
+    }
+}
@@ -0,0 +1,895 @@
+// Database Conformance - One suite of DatabaseInterface checks run against every backend
+// SQLite serves development and tests while Postgres serves production, so both must agree
+
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::infrastructure::database::database::{
+    AssocQuery, Association, AssociationType, ConsistencyIssue, DatabaseInterface,
+    IdempotencyRecord, ObjectId, ObjectQuery, PostgresDatabase, Timestamp,
+};
+use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+use crate::infrastructure::tao_core::cursor::Cursor;
+use crate::infrastructure::tao_core::tao_core::current_time_millis;
+
+/// Run every check against `db`, which must start out empty. Times are taken from the
+/// clock since Postgres only holds rows in partitions around the present.
+pub async fn check_conformance(db: &dyn DatabaseInterface) {
+    let now = current_time_millis();
+    check_objects(db).await;
+    check_object_history(db).await;
+    check_associations(db, now).await;
+    check_association_queries(db, now).await;
+    check_association_counts(db, now).await;
+    check_attributes_and_unique_keys(db).await;
+    check_idempotency_records(db, now).await;
+    check_association_changes(db).await;
+    check_transactions(db, now).await;
+    check_shard_scans(db).await;
+}
+
+fn edge(id1: ObjectId, atype: &str, id2: ObjectId, time: Timestamp) -> Association {
+    Association {
+        id1,
+        atype: atype.to_string(),
+        id2,
+        time,
+        data: Some(format!("{}-{}", id1, id2).into_bytes()),
+        subtype: None,
+    }
+}
+
+fn assoc_query(id1: ObjectId, atype: &str) -> AssocQuery {
+    AssocQuery {
+        id1,
+        atype: atype.to_string(),
+        id2_set: None,
+        low_id2: None,
+        high_id2: None,
+        high_time: None,
+        low_time: None,
+        limit: None,
+        offset: None,
+        after: None,
+        include_total: false,
+        dedup: true,
+    }
+}
+
+fn object_query(ids: Vec<ObjectId>) -> ObjectQuery {
+    ObjectQuery {
+        ids,
+        otype: None,
+        created_after: None,
+        created_before: None,
+        limit: None,
+        offset: None,
+    }
+}
+
+fn id2s(associations: &[Association]) -> Vec<ObjectId> {
+    associations.iter().map(|assoc| assoc.id2).collect()
+}
+
+async fn check_objects(db: &dyn DatabaseInterface) {
+    db.create_object(1, "ent_user".to_string(), b"alice".to_vec())
+        .await
+        .unwrap();
+    db.create_object(2, "ent_user".to_string(), b"bob".to_vec())
+        .await
+        .unwrap();
+    db.create_object(3, "ent_post".to_string(), b"hello".to_vec())
+        .await
+        .unwrap();
+
+    let alice = db.get_object(1).await.unwrap().unwrap();
+    assert_eq!(
+        (
+            alice.id,
+            alice.otype.as_str(),
+            alice.data.as_slice(),
+            alice.version
+        ),
+        (1, "ent_user", b"alice".as_slice(), 1)
+    );
+    assert!(alice.created_time > 0 && alice.updated_time >= alice.created_time);
+    assert!(db.get_object(99).await.unwrap().is_none());
+    assert!(db.object_exists(1).await.unwrap());
+    assert!(!db.object_exists(99).await.unwrap());
+
+    db.update_object(1, b"alice v2".to_vec()).await.unwrap();
+    let alice = db.get_object(1).await.unwrap().unwrap();
+    assert_eq!(
+        (alice.data.as_slice(), alice.version),
+        (b"alice v2".as_slice(), 2)
+    );
+    let missing = db.update_object(99, Vec::new()).await;
+    assert!(
+        matches!(missing, Err(AppError::NotFound(_))),
+        "{:?}",
+        missing
+    );
+
+    let users = db
+        .get_objects(ObjectQuery {
+            otype: Some("ent_user".to_string()),
+            ..object_query(vec![])
+        })
+        .await
+        .unwrap();
+    let mut ids: Vec<ObjectId> = users.objects.iter().map(|object| object.id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+    let picked = db.get_objects(object_query(vec![3, 1, 99])).await.unwrap();
+    let mut ids: Vec<ObjectId> = picked.objects.iter().map(|object| object.id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 3]);
+    let page = db
+        .get_objects(ObjectQuery {
+            limit: Some(1),
+            offset: Some(1),
+            ..object_query(vec![])
+        })
+        .await
+        .unwrap();
+    let ids: Vec<ObjectId> = page.objects.iter().map(|object| object.id).collect();
+    assert_eq!(ids, vec![2]);
+    let later = db
+        .get_objects(ObjectQuery {
+            created_after: Some(alice.created_time + 3_600_000),
+            ..object_query(vec![])
+        })
+        .await
+        .unwrap();
+    assert!(later.objects.is_empty());
+
+    assert_eq!(
+        db.get_object_type_count("ent_user".to_string())
+            .await
+            .unwrap(),
+        2
+    );
+    assert!(db.delete_object(2).await.unwrap());
+    assert!(!db.delete_object(2).await.unwrap());
+    assert!(!db.object_exists(2).await.unwrap());
+    assert_eq!(
+        db.get_object_type_count("ent_user".to_string())
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        db.get_object_type_count("ent_page".to_string())
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+async fn check_object_history(db: &dyn DatabaseInterface) {
+    db.create_object(10, "ent_page".to_string(), b"v1".to_vec())
+        .await
+        .unwrap();
+    db.update_object_with_history(10, b"v2".to_vec(), Some(1))
+        .await
+        .unwrap();
+    db.update_object_with_history(10, b"v3".to_vec(), None)
+        .await
+        .unwrap();
+
+    let current = db.get_object(10).await.unwrap().unwrap();
+    assert_eq!(
+        (current.data.as_slice(), current.version),
+        (b"v3".as_slice(), 3)
+    );
+    let history = db.get_object_history(10, 10).await.unwrap();
+    let versions: Vec<(u64, &[u8], Option<ObjectId>)> = history
+        .iter()
+        .map(|version| (version.version, version.data.as_slice(), version.changed_by))
+        .collect();
+    assert_eq!(
+        versions,
+        vec![(2, b"v2".as_slice(), None), (1, b"v1".as_slice(), Some(1))]
+    );
+    assert_eq!(db.get_object_history(10, 1).await.unwrap().len(), 1);
+    let first = db.get_object_version(10, 1).await.unwrap().unwrap();
+    assert_eq!(first.data, b"v1".to_vec());
+    assert!(db.get_object_version(10, 3).await.unwrap().is_none());
+
+    let missing = db.update_object_with_history(99, Vec::new(), None).await;
+    assert!(
+        matches!(missing, Err(AppError::NotFound(_))),
+        "{:?}",
+        missing
+    );
+}
+
+async fn check_associations(db: &dyn DatabaseInterface, now: Timestamp) {
+    db.create_association(edge(1, "likes", 3, now))
+        .await
+        .unwrap();
+    let stored = db
+        .get_association(1, "likes".to_string(), 3)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (
+            stored.id1,
+            stored.atype.as_str(),
+            stored.id2,
+            stored.time,
+            stored.data
+        ),
+        (1, "likes", 3, now, Some(b"1-3".to_vec()))
+    );
+    assert!(db
+        .association_exists(1, "likes".to_string(), 3)
+        .await
+        .unwrap());
+    assert!(!db
+        .association_exists(3, "likes".to_string(), 1)
+        .await
+        .unwrap());
+    assert!(db
+        .get_association(1, "likes".to_string(), 4)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(db
+        .touch_association(1, "likes".to_string(), 3, now + 5)
+        .await
+        .unwrap());
+    assert!(!db
+        .touch_association(1, "likes".to_string(), 4, now + 5)
+        .await
+        .unwrap());
+    let touched = db
+        .get_association(1, "likes".to_string(), 3)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (touched.time, touched.data),
+        (now + 5, Some(b"1-3".to_vec()))
+    );
+    assert_eq!(
+        db.get_association_count(1, "likes".to_string())
+            .await
+            .unwrap(),
+        1
+    );
+
+    assert!(db
+        .delete_association(1, "likes".to_string(), 3)
+        .await
+        .unwrap());
+    assert!(!db
+        .delete_association(1, "likes".to_string(), 3)
+        .await
+        .unwrap());
+    assert_eq!(
+        db.get_association_count(1, "likes".to_string())
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+async fn check_association_queries(db: &dyn DatabaseInterface, now: Timestamp) {
+    // 100 follows 101..=105, one second apart; 105 is the newest
+    for id2 in 101..=105 {
+        db.create_association(edge(100, "follows", id2, now + (id2 - 100) * 1000))
+            .await
+            .unwrap();
+    }
+    db.create_association(edge(100, "likes", 101, now))
+        .await
+        .unwrap();
+    db.create_association(edge(101, "follows", 100, now))
+        .await
+        .unwrap();
+
+    let all = db
+        .get_associations(assoc_query(100, "follows"))
+        .await
+        .unwrap();
+    assert_eq!(id2s(&all.associations), vec![105, 104, 103, 102, 101]);
+    assert_eq!(all.total_count, None);
+
+    let page = db
+        .get_associations(AssocQuery {
+            limit: Some(2),
+            include_total: true,
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&page.associations), vec![105, 104]);
+    assert_eq!(page.total_count, Some(5));
+    assert_eq!(
+        page.next_cursor,
+        Some(Cursor {
+            offset: 2,
+            last_id: Some(104),
+            last_time: Some(now + 4000),
+        })
+    );
+    let offset = db
+        .get_associations(AssocQuery {
+            limit: Some(2),
+            offset: Some(2),
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&offset.associations), vec![103, 102]);
+    let after = db
+        .get_associations(AssocQuery {
+            limit: Some(2),
+            after: Some((now + 4000, 104)),
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&after.associations), vec![103, 102]);
+    let last = db
+        .get_associations(AssocQuery {
+            limit: Some(2),
+            offset: Some(4),
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&last.associations), vec![101]);
+    assert_eq!(last.next_cursor, None);
+
+    let filtered = db
+        .get_associations(AssocQuery {
+            id2_set: Some(vec![101, 103, 999]),
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&filtered.associations), vec![103, 101]);
+    let id2_range = db
+        .get_associations(AssocQuery {
+            low_id2: Some(102),
+            high_id2: Some(104),
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&id2_range.associations), vec![104, 103, 102]);
+    let time_range = db
+        .get_associations(AssocQuery {
+            low_time: Some(now + 2000),
+            high_time: Some(now + 3000),
+            ..assoc_query(100, "follows")
+        })
+        .await
+        .unwrap();
+    assert_eq!(id2s(&time_range.associations), vec![103, 102]);
+
+    let since = db
+        .get_associations_since(100, "follows".to_string(), now + 2000, 2)
+        .await
+        .unwrap();
+    assert_eq!(id2s(&since), vec![103, 104]);
+
+    let multi = db
+        .get_associations_multi_type(
+            100,
+            vec![
+                "follows".to_string(),
+                "likes".to_string(),
+                "blocks".to_string(),
+            ],
+            Some(2),
+        )
+        .await
+        .unwrap();
+    let mut by_type: HashMap<AssociationType, Vec<ObjectId>> = HashMap::new();
+    for assoc in &multi {
+        by_type
+            .entry(assoc.atype.clone())
+            .or_default()
+            .push(assoc.id2);
+    }
+    assert_eq!(
+        by_type,
+        HashMap::from([
+            ("follows".to_string(), vec![105, 104]),
+            ("likes".to_string(), vec![101]),
+        ])
+    );
+
+    let mut from_object: Vec<(AssociationType, ObjectId)> = db
+        .get_associations_from_object(100)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|assoc| (assoc.atype, assoc.id2))
+        .collect();
+    from_object.sort();
+    assert_eq!(from_object.len(), 6);
+    assert_eq!(from_object[0], ("follows".to_string(), 101));
+    assert_eq!(from_object[5], ("likes".to_string(), 101));
+
+    let between: Vec<(AssociationType, ObjectId)> = db
+        .get_associations_between(100, 101)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|assoc| (assoc.atype, assoc.id2))
+        .collect();
+    assert_eq!(
+        between,
+        vec![("follows".to_string(), 101), ("likes".to_string(), 101)]
+    );
+
+    assert_eq!(
+        db.count_associations(100, "follows".to_string())
+            .await
+            .unwrap(),
+        5
+    );
+    let counts = db
+        .count_associations_many(vec![
+            (100, "follows".to_string()),
+            (100, "likes".to_string()),
+            (100, "blocks".to_string()),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(
+        counts,
+        HashMap::from([
+            ((100, "follows".to_string()), 5),
+            ((100, "likes".to_string()), 1),
+        ])
+    );
+
+    let mut deleted = db
+        .delete_associations_of_type(100, "follows".to_string())
+        .await
+        .unwrap();
+    deleted.sort();
+    assert_eq!(deleted, vec![101, 102, 103, 104, 105]);
+    assert_eq!(
+        db.get_association_count(100, "follows".to_string())
+            .await
+            .unwrap(),
+        0
+    );
+    assert!(db
+        .delete_associations_of_type(100, "follows".to_string())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+async fn check_association_counts(db: &dyn DatabaseInterface, now: Timestamp) {
+    db.create_object(200, "ent_user".to_string(), Vec::new())
+        .await
+        .unwrap();
+    db.create_association(edge(200, "follows", 201, now))
+        .await
+        .unwrap();
+    db.create_association(edge(200, "follows", 202, now))
+        .await
+        .unwrap();
+    db.create_association(edge(200, "blocks", 203, now))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        db.get_association_counts_for_object(200).await.unwrap(),
+        vec![("blocks".to_string(), 1), ("follows".to_string(), 2)]
+    );
+    db.update_association_count(200, "follows".to_string(), 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_association_count(200, "follows".to_string())
+            .await
+            .unwrap(),
+        5
+    );
+    assert_eq!(
+        db.get_association_count(200, "missing".to_string())
+            .await
+            .unwrap(),
+        0
+    );
+
+    for (id2, subtype) in [(204, Some("love")), (205, Some("love")), (206, None)] {
+        db.create_association(Association {
+            subtype: subtype.map(str::to_string),
+            ..edge(200, "reacts", id2, now)
+        })
+        .await
+        .unwrap();
+    }
+    let by_subtype = db
+        .count_associations_by_subtype(200, "reacts".to_string())
+        .await
+        .unwrap();
+    assert_eq!(by_subtype.len(), 2);
+    assert_eq!(by_subtype[&Some("love".to_string())], 2);
+    assert_eq!(by_subtype[&None], 1);
+    let reaction = db
+        .get_association(200, "reacts".to_string(), 204)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(reaction.subtype.as_deref(), Some("love"));
+
+    // A drifted count and an edge whose source object is gone
+    let issues = db.audit_consistency(false).await.unwrap();
+    assert!(issues.contains(&ConsistencyIssue::CountMismatch {
+        id1: 200,
+        atype: "follows".to_string(),
+        stored: 5,
+        actual: 2,
+        repaired: false,
+    }));
+    assert!(issues.contains(&ConsistencyIssue::OrphanedAssociation {
+        id1: 101,
+        atype: "follows".to_string(),
+        id2: 100,
+    }));
+    assert_eq!(
+        db.recount_association(200, "follows".to_string())
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        db.get_association_count(200, "follows".to_string())
+            .await
+            .unwrap(),
+        2
+    );
+
+    db.update_association_count(200, "blocks".to_string(), 1)
+        .await
+        .unwrap();
+    let issues = db.audit_consistency(true).await.unwrap();
+    assert!(issues.contains(&ConsistencyIssue::CountMismatch {
+        id1: 200,
+        atype: "blocks".to_string(),
+        stored: 2,
+        actual: 1,
+        repaired: true,
+    }));
+    assert_eq!(
+        db.get_association_count(200, "blocks".to_string())
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+async fn check_attributes_and_unique_keys(db: &dyn DatabaseInterface) {
+    db.create_object(300, "ent_user".to_string(), Vec::new())
+        .await
+        .unwrap();
+    db.set_object_attribute(300, "theme".to_string(), b"dark".to_vec())
+        .await
+        .unwrap();
+    db.set_object_attribute(300, "theme".to_string(), b"light".to_vec())
+        .await
+        .unwrap();
+    db.set_object_attribute(300, "lang".to_string(), b"en".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_object_attribute(300, "theme".to_string())
+            .await
+            .unwrap(),
+        Some(b"light".to_vec())
+    );
+    assert_eq!(
+        db.get_object_attribute(300, "missing".to_string())
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        db.get_object_attributes(300).await.unwrap(),
+        HashMap::from([
+            ("theme".to_string(), b"light".to_vec()),
+            ("lang".to_string(), b"en".to_vec()),
+        ])
+    );
+
+    let key = || {
+        (
+            "ent_user".to_string(),
+            "email".to_string(),
+            "a@b.c".to_string(),
+        )
+    };
+    let mut tx = db.begin_transaction().await.unwrap();
+    let (otype, field, value) = key();
+    assert!(db
+        .claim_unique_key_tx(&mut tx, otype, field, value, 300)
+        .await
+        .unwrap());
+    let (otype, field, value) = key();
+    assert!(!db
+        .claim_unique_key_tx(&mut tx, otype, field, value, 301)
+        .await
+        .unwrap());
+    tx.commit().await.unwrap();
+    let (otype, field, value) = key();
+    assert_eq!(
+        db.get_unique_key(otype, field, value).await.unwrap(),
+        Some(300)
+    );
+
+    // Deleting the object takes its attributes and keys with it
+    assert!(db.delete_object(300).await.unwrap());
+    assert!(db.get_object_attributes(300).await.unwrap().is_empty());
+    let (otype, field, value) = key();
+    assert_eq!(db.get_unique_key(otype, field, value).await.unwrap(), None);
+}
+
+async fn check_idempotency_records(db: &dyn DatabaseInterface, now: Timestamp) {
+    let record = |key: &str, status, expires_at| IdempotencyRecord {
+        key: key.to_string(),
+        status,
+        body: b"{}".to_vec(),
+        expires_at,
+    };
+    db.put_idempotency_record(record("live", 201, now + 60_000))
+        .await
+        .unwrap();
+    db.put_idempotency_record(record("live", 200, now + 60_000))
+        .await
+        .unwrap();
+    db.put_idempotency_record(record("expired", 201, now - 1))
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_idempotency_record("live").await.unwrap(),
+        Some(record("live", 200, now + 60_000))
+    );
+    assert_eq!(db.get_idempotency_record("expired").await.unwrap(), None);
+    assert_eq!(db.get_idempotency_record("missing").await.unwrap(), None);
+
+    // A claim holds the key until the request finishes; expired records can be reclaimed
+    let pending = IdempotencyRecord {
+        key: "claimed".to_string(),
+        status: IdempotencyRecord::PENDING_STATUS,
+        body: Vec::new(),
+        expires_at: now + 60_000,
+    };
+    assert_eq!(
+        db.claim_idempotency_key("claimed", now + 60_000)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        db.claim_idempotency_key("claimed", now + 60_000)
+            .await
+            .unwrap(),
+        Some(pending)
+    );
+    assert_eq!(
+        db.claim_idempotency_key("live", now + 60_000)
+            .await
+            .unwrap(),
+        Some(record("live", 200, now + 60_000))
+    );
+    assert_eq!(
+        db.claim_idempotency_key("expired", now + 60_000)
+            .await
+            .unwrap(),
+        None
+    );
+    db.delete_idempotency_record("claimed").await.unwrap();
+    assert_eq!(
+        db.claim_idempotency_key("claimed", now + 60_000)
+            .await
+            .unwrap(),
+        None
+    );
+    // Postgres keeps idempotency keys across runs, so leave the key free for the next one
+    db.delete_idempotency_record("claimed").await.unwrap();
+}
+
+async fn check_association_changes(db: &dyn DatabaseInterface) {
+    let empty = db
+        .get_association_changes(400, "friends".to_string(), 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        (empty.changes.len(), empty.latest_seq, empty.oldest_seq),
+        (0, 0, 0)
+    );
+
+    let seq = db
+        .record_association_changes(400, "friends".to_string(), vec![(401, true), (402, true)])
+        .await
+        .unwrap();
+    assert_eq!(seq, 2);
+    let seq = db
+        .record_association_changes(400, "friends".to_string(), vec![(401, false)])
+        .await
+        .unwrap();
+    assert_eq!(seq, 3);
+
+    let log = db
+        .get_association_changes(400, "friends".to_string(), 1)
+        .await
+        .unwrap();
+    let changes: Vec<(u64, ObjectId, bool)> = log
+        .changes
+        .iter()
+        .map(|change| (change.seq, change.id2, change.added))
+        .collect();
+    assert_eq!(changes, vec![(2, 402, true), (3, 401, false)]);
+    assert_eq!((log.latest_seq, log.oldest_seq), (3, 1));
+}
+
+async fn check_transactions(db: &dyn DatabaseInterface, now: Timestamp) {
+    // Rolled back: nothing is left behind
+    let mut tx = db.begin_transaction().await.unwrap();
+    db.create_object_tx(&mut tx, 500, "ent_post".to_string(), b"draft".to_vec())
+        .await
+        .unwrap();
+    db.create_association_tx(&mut tx, edge(500, "tagged", 501, now))
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+    assert!(!db.object_exists(500).await.unwrap());
+    assert!(!db
+        .association_exists(500, "tagged".to_string(), 501)
+        .await
+        .unwrap());
+    assert_eq!(
+        db.get_association_count(500, "tagged".to_string())
+            .await
+            .unwrap(),
+        0
+    );
+
+    let mut tx = db.begin_transaction().await.unwrap();
+    db.create_object_tx(&mut tx, 500, "ent_post".to_string(), b"final".to_vec())
+        .await
+        .unwrap();
+    db.create_association_tx(&mut tx, edge(500, "tagged", 501, now))
+        .await
+        .unwrap();
+    db.create_association_tx(&mut tx, edge(500, "tagged", 502, now))
+        .await
+        .unwrap();
+    assert!(db
+        .delete_association_tx(&mut tx, 500, "tagged".to_string(), 502)
+        .await
+        .unwrap());
+    assert!(!db
+        .delete_association_tx(&mut tx, 500, "tagged".to_string(), 503)
+        .await
+        .unwrap());
+    db.update_association_count_tx(&mut tx, 500, "viewed".to_string(), 7)
+        .await
+        .unwrap();
+    let seq = db
+        .record_association_changes_tx(&mut tx, 500, "tagged".to_string(), vec![(501, true)])
+        .await
+        .unwrap();
+    assert_eq!(seq, 1);
+    tx.commit().await.unwrap();
+
+    let post = db.get_object(500).await.unwrap().unwrap();
+    assert_eq!(
+        (post.otype.as_str(), post.data.as_slice()),
+        ("ent_post", b"final".as_slice())
+    );
+    let tagged = db
+        .get_associations(assoc_query(500, "tagged"))
+        .await
+        .unwrap();
+    assert_eq!(id2s(&tagged.associations), vec![501]);
+    assert_eq!(
+        db.get_association_count(500, "tagged".to_string())
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        db.get_association_count(500, "viewed".to_string())
+            .await
+            .unwrap(),
+        7
+    );
+
+    // Rolled back: the deleted edges and their count come back
+    let mut tx = db.begin_transaction().await.unwrap();
+    assert_eq!(
+        db.delete_associations_of_type_tx(&mut tx, 500, "tagged".to_string())
+            .await
+            .unwrap(),
+        vec![501]
+    );
+    tx.rollback().await.unwrap();
+    assert!(db
+        .association_exists(500, "tagged".to_string(), 501)
+        .await
+        .unwrap());
+    assert_eq!(
+        db.get_association_count(500, "tagged".to_string())
+            .await
+            .unwrap(),
+        1
+    );
+
+    let mut tx = db.begin_transaction().await.unwrap();
+    assert!(db.delete_object_tx(&mut tx, 500).await.unwrap());
+    assert!(!db.delete_object_tx(&mut tx, 500).await.unwrap());
+    tx.commit().await.unwrap();
+    assert!(!db.object_exists(500).await.unwrap());
+}
+
+async fn check_shard_scans(db: &dyn DatabaseInterface) {
+    assert!(db.write_high_watermark().await.unwrap() > 0);
+
+    let mut object_ids: Vec<ObjectId> = db
+        .get_all_objects_from_shard()
+        .await
+        .unwrap()
+        .iter()
+        .map(|object| object.id)
+        .collect();
+    object_ids.sort();
+    assert_eq!(object_ids, vec![1, 3, 10, 200]);
+
+    let mut edges: Vec<(ObjectId, AssociationType, ObjectId)> = db
+        .get_all_associations_from_shard()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|assoc| (assoc.id1, assoc.atype, assoc.id2))
+        .collect();
+    edges.sort();
+    assert_eq!(
+        edges,
+        vec![
+            (100, "likes".to_string(), 101),
+            (101, "follows".to_string(), 100),
+            (200, "blocks".to_string(), 203),
+            (200, "follows".to_string(), 201),
+            (200, "follows".to_string(), 202),
+            (200, "reacts".to_string(), 204),
+            (200, "reacts".to_string(), 205),
+            (200, "reacts".to_string(), 206),
+            (500, "tagged".to_string(), 501),
+        ]
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_conforms() {
+        let db = SqliteDatabase::new_in_memory().await.unwrap();
+        check_conformance(&db).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_postgres_conforms() {
+        // Recreates the TAO tables
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let db = PostgresDatabase::new(sqlx::PgPool::connect(&url).await.unwrap());
+        db.initialize().await.unwrap();
+        check_conformance(&db).await;
+    }
+}
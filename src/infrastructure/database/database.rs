@@ -2,12 +2,23 @@
 // This layer handles direct SQL queries for objects, associations, and indexes
 
 use crate::error::{AppError, AppResult};
+use crate::infrastructure::id_generator::IdGenerator;
+use crate::infrastructure::monitoring::monitoring::MetricsCollector;
+use crate::infrastructure::shard_topology::ShardId;
+use crate::infrastructure::tao_core::cursor::Cursor;
 use async_trait::async_trait;
+use futures::future::BoxFuture;
+use rand::Rng;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-use sqlx::postgres::{PgPool, Postgres};
+use sqlx::postgres::{
+    PgArguments, PgConnectOptions, PgConnection, PgPool, PgPoolOptions, PgRow, Postgres,
+};
 use sqlx::sqlite::Sqlite;
-use sqlx::{Column, Row, Transaction, ValueRef}; // Added Sqlite for generic DatabaseTransaction
+use sqlx::{Column, QueryBuilder, Row, Transaction, ValueRef}; // Added Sqlite for generic DatabaseTransaction
 
 // Generic database types - framework agnostic
 pub type ObjectId = i64;
@@ -34,6 +45,8 @@ pub struct Association {
     pub id2: ObjectId,
     pub time: Timestamp,
     pub data: Option<Vec<u8>>,
+    /// Dimension within `atype`, e.g. the kind of a reaction; `None` for plain edges
+    pub subtype: Option<String>,
 }
 
 /// Association query parameters - framework agnostic
@@ -42,17 +55,35 @@ pub struct AssocQuery {
     pub id1: ObjectId,
     pub atype: AssociationType,
     pub id2_set: Option<Vec<ObjectId>>,
+    /// Inclusive lower/upper bounds on id2
+    pub low_id2: Option<ObjectId>,
+    pub high_id2: Option<ObjectId>,
     pub high_time: Option<Timestamp>,
     pub low_time: Option<Timestamp>,
     pub limit: Option<u32>,
+    /// Rows to skip. Unstable under concurrent writes: an edge inserted between two
+    /// page fetches shifts every later row, so a row is returned twice or skipped.
     pub offset: Option<u64>,
+    /// Keyset position: only rows after this `(time_created, id2)` in the
+    /// `time_created DESC, id2 DESC` order. Stable under concurrent writes.
+    pub after: Option<(Timestamp, ObjectId)>,
+    /// Also return the total number of associations for (id1, atype)
+    pub include_total: bool,
+    /// Return each (id1, atype, id2) edge once, as its most recent row. Postgres keys
+    /// associations by time as well, so re-adding an edge at another time leaves two rows.
+    pub dedup: bool,
 }
 
 /// Object query parameters - framework agnostic
 #[derive(Debug, Clone)]
 pub struct ObjectQuery {
+    /// Empty means no id filter
     pub ids: Vec<ObjectId>,
     pub otype: Option<ObjectType>,
+    /// Inclusive lower bound on `time_created`
+    pub created_after: Option<Timestamp>,
+    /// Inclusive upper bound on `time_created`
+    pub created_before: Option<Timestamp>,
     pub limit: Option<u32>,
     pub offset: Option<u64>,
 }
@@ -61,38 +92,150 @@ pub struct ObjectQuery {
 #[derive(Debug, Clone)]
 pub struct AssocQueryResult {
     pub associations: Vec<Association>,
-    pub next_cursor: Option<String>,
+    pub next_cursor: Option<Cursor>,
+    /// Total associations for (id1, atype), from the count table; set when `include_total` was requested
+    pub total_count: Option<u64>,
 }
 
 /// Object query result with pagination - framework agnostic
 #[derive(Debug, Clone)]
 pub struct ObjectQueryResult {
     pub objects: Vec<Object>,
-    pub next_cursor: Option<String>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// A value replaced by `update_object_with_history`: `data` as of `version`, last
+/// written at `updated_time`. `changed_by` is the user whose update replaced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectVersion {
+    pub id: ObjectId,
+    pub version: u64,
+    pub data: Vec<u8>,
+    pub updated_time: Timestamp,
+    pub changed_by: Option<ObjectId>,
+}
+
+/// Stored response for an HTTP `Idempotency-Key`, replayed to retries until `expires_at`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub expires_at: Timestamp,
+}
+
+impl IdempotencyRecord {
+    /// Status of a record claimed by a request that has not finished yet
+    pub const PENDING_STATUS: u16 = 0;
+
+    pub fn is_pending(&self) -> bool {
+        self.status == Self::PENDING_STATUS
+    }
+}
+
+/// One entry of an association change log: `id2` was added to or removed from the edges
+/// of `(id1, atype)`, bringing them to version `seq`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssociationChange {
+    pub seq: u64,
+    pub id2: ObjectId,
+    pub added: bool,
+    pub changed_time: Timestamp,
+}
+
+/// The logged changes of one `(id1, atype)` after some sequence number
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssociationChangeLog {
+    /// Oldest first
+    pub changes: Vec<AssociationChange>,
+    /// Sequence number of the latest change; 0 if there never was one
+    pub latest_seq: u64,
+    /// Oldest sequence number still logged; 0 if none is
+    pub oldest_seq: u64,
+}
+
+/// Changes logged per `(id1, atype)`; older ones are dropped as new ones come in
+pub const ASSOC_CHANGE_LOG_RETENTION: u64 = 1000;
+
+/// A disagreement between a shard's denormalized state and its actual rows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConsistencyIssue {
+    /// `association_counts` differs from the number of stored edges
+    CountMismatch {
+        id1: ObjectId,
+        atype: AssociationType,
+        stored: i64,
+        actual: i64,
+        repaired: bool,
+    },
+    /// An edge whose `id1` object no longer exists on the shard
+    OrphanedAssociation {
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+    },
 }
 
 /// Unified transaction wrapper for database operations
-pub enum DatabaseTransaction {
+pub struct DatabaseTransaction {
+    tx: TransactionKind,
+    /// Shard the transaction runs on, with the generator that routes ids to shards
+    shard: Option<(ShardId, Arc<dyn IdGenerator>)>,
+}
+
+enum TransactionKind {
     Postgres(Transaction<'static, Postgres>),
     Sqlite(Transaction<'static, Sqlite>),
 }
 
 impl DatabaseTransaction {
     pub fn new_postgres(tx: Transaction<'static, Postgres>) -> Self {
-        Self::Postgres(tx)
+        Self {
+            tx: TransactionKind::Postgres(tx),
+            shard: None,
+        }
     }
 
     pub fn new_sqlite(tx: Transaction<'static, Sqlite>) -> Self {
-        Self::Sqlite(tx)
+        Self {
+            tx: TransactionKind::Sqlite(tx),
+            shard: None,
+        }
+    }
+
+    /// Bind the transaction to `shard_id`, so that `check_shard` rejects writes for ids
+    /// that `id_generator` routes to any other shard
+    pub fn bind_to_shard(mut self, shard_id: ShardId, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.shard = Some((shard_id, id_generator));
+        self
+    }
+
+    /// Every `*_tx` write checks its ids with this before touching the database. A write
+    /// for an object of another shard would land where nothing ever reads it, so it is an
+    /// error in the caller rather than in the request. Unbound transactions accept any id.
+    pub fn check_shard(&self, id: ObjectId) -> AppResult<()> {
+        match &self.shard {
+            Some((shard_id, id_generator)) if id_generator.shard_of(id) != *shard_id => {
+                Err(AppError::Internal(format!(
+                    "cross-shard write in single-shard transaction: object {} is on shard {}, \
+                     the transaction on shard {}",
+                    id,
+                    id_generator.shard_of(id),
+                    shard_id
+                )))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Commit the transaction
     pub async fn commit(self) -> AppResult<()> {
-        match self {
-            DatabaseTransaction::Postgres(tx) => tx.commit().await.map_err(|e| {
+        match self.tx {
+            TransactionKind::Postgres(tx) => tx.commit().await.map_err(|e| {
                 AppError::DatabaseError(format!("Failed to commit postgres transaction: {}", e))
             }),
-            DatabaseTransaction::Sqlite(tx) => tx.commit().await.map_err(|e| {
+            TransactionKind::Sqlite(tx) => tx.commit().await.map_err(|e| {
                 AppError::DatabaseError(format!("Failed to commit sqlite transaction: {}", e))
             }),
         }
@@ -100,11 +243,11 @@ impl DatabaseTransaction {
 
     /// Rollback the transaction
     pub async fn rollback(self) -> AppResult<()> {
-        match self {
-            DatabaseTransaction::Postgres(tx) => tx.rollback().await.map_err(|e| {
+        match self.tx {
+            TransactionKind::Postgres(tx) => tx.rollback().await.map_err(|e| {
                 AppError::DatabaseError(format!("Failed to rollback postgres transaction: {}", e))
             }),
-            DatabaseTransaction::Sqlite(tx) => tx.rollback().await.map_err(|e| {
+            TransactionKind::Sqlite(tx) => tx.rollback().await.map_err(|e| {
                 AppError::DatabaseError(format!("Failed to rollback sqlite transaction: {}", e))
             }),
         }
@@ -112,9 +255,9 @@ impl DatabaseTransaction {
 
     /// Get mutable reference to the underlying transaction for PostgreSQL
     pub fn as_postgres_mut(&mut self) -> AppResult<&mut Transaction<'static, Postgres>> {
-        match self {
-            DatabaseTransaction::Postgres(tx) => Ok(tx),
-            DatabaseTransaction::Sqlite(_) => Err(AppError::DatabaseError(
+        match &mut self.tx {
+            TransactionKind::Postgres(tx) => Ok(tx),
+            TransactionKind::Sqlite(_) => Err(AppError::DatabaseError(
                 "Transaction is not PostgreSQL".to_string(),
             )),
         }
@@ -122,9 +265,9 @@ impl DatabaseTransaction {
 
     /// Get mutable reference to the underlying transaction for SQLite
     pub fn as_sqlite_mut(&mut self) -> AppResult<&mut Transaction<'static, Sqlite>> {
-        match self {
-            DatabaseTransaction::Sqlite(tx) => Ok(tx),
-            DatabaseTransaction::Postgres(_) => Err(AppError::DatabaseError(
+        match &mut self.tx {
+            TransactionKind::Sqlite(tx) => Ok(tx),
+            TransactionKind::Postgres(_) => Err(AppError::DatabaseError(
                 "Transaction is not SQLite".to_string(),
             )),
         }
@@ -137,6 +280,13 @@ impl DatabaseTransaction {
 pub trait DatabaseInterface: Send + Sync {
     /// Allow downcasting to concrete database types
     fn as_any(&self) -> &dyn std::any::Any;
+    /// Connection pool statistics: (idle connections, pool size)
+    fn pool_stats(&self) -> (u32, u32);
+
+    /// Apply per-operation statement timeouts; backends without server-side timeouts ignore this
+    fn set_statement_timeouts(&self, _timeouts: StatementTimeouts) {}
+    /// Health check to verify database connectivity
+    async fn health_check(&self) -> AppResult<()>;
     // Transaction management
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction>;
 
@@ -148,6 +298,23 @@ pub trait DatabaseInterface: Send + Sync {
     async fn delete_object(&self, id: ObjectId) -> AppResult<bool>;
     async fn object_exists(&self, id: ObjectId) -> AppResult<bool>;
 
+    // Object history - previous values of objects whose type keeps history
+    /// `update_object` that first appends the value being replaced to the object's
+    /// history, in the same transaction
+    async fn update_object_with_history(
+        &self,
+        id: ObjectId,
+        data: Vec<u8>,
+        changed_by: Option<ObjectId>,
+    ) -> AppResult<()>;
+    /// Replaced values of `id`, newest first
+    async fn get_object_history(&self, id: ObjectId, limit: u32) -> AppResult<Vec<ObjectVersion>>;
+    async fn get_object_version(
+        &self,
+        id: ObjectId,
+        version: u64,
+    ) -> AppResult<Option<ObjectVersion>>;
+
     // Association operations - Generic association storage
     async fn get_associations(&self, query: AssocQuery) -> AppResult<AssocQueryResult>;
     async fn create_association(&self, assoc: Association) -> AppResult<()>;
@@ -157,13 +324,73 @@ pub trait DatabaseInterface: Send + Sync {
         atype: AssociationType,
         id2: ObjectId,
     ) -> AppResult<bool>;
+    /// Delete every `atype` association from `id1` in one statement and zero its count,
+    /// returning the id2 of each deleted edge
+    async fn delete_associations_of_type(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<Vec<ObjectId>>;
     async fn association_exists(
         &self,
         id1: ObjectId,
         atype: AssociationType,
         id2: ObjectId,
     ) -> AppResult<bool>;
+    /// The single (id1, atype, id2) edge with its time and data, if present
+    async fn get_association(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+    ) -> AppResult<Option<Association>>;
+    /// Set an existing association's time, leaving its count and data as they are
+    async fn touch_association(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+        time: Timestamp,
+    ) -> AppResult<bool>;
     async fn count_associations(&self, id1: ObjectId, atype: AssociationType) -> AppResult<u64>;
+    /// Counts for many `(id1, atype)` pairs in one query; pairs without a count row are omitted
+    async fn count_associations_many(
+        &self,
+        pairs: Vec<(ObjectId, AssociationType)>,
+    ) -> AppResult<HashMap<(ObjectId, AssociationType), u64>>;
+    /// Edges of `atype` from `id1` counted per subtype, edges without one under `None`.
+    /// Counted from the associations themselves, so it scans the edge list.
+    async fn count_associations_by_subtype(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<HashMap<Option<String>, u64>>;
+    /// Associations of any of `atypes` from `id1`, newest first within each type and
+    /// capped at `limit_per_type` edges per type, in a single query.
+    async fn get_associations_multi_type(
+        &self,
+        id1: ObjectId,
+        atypes: Vec<AssociationType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<Vec<Association>>;
+    /// Associations created strictly after `since_time`, oldest first.
+    /// Served by the (id1, atype, time_created) index.
+    async fn get_associations_since(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        since_time: Timestamp,
+        limit: u32,
+    ) -> AppResult<Vec<Association>>;
+    /// Every association stored with `id1` as its source, of any type
+    async fn get_associations_from_object(&self, id1: ObjectId) -> AppResult<Vec<Association>>;
+    /// Every association from `id1` to `id2`, of any type, newest first.
+    /// Served by the (id1, id2) index.
+    async fn get_associations_between(
+        &self,
+        id1: ObjectId,
+        id2: ObjectId,
+    ) -> AppResult<Vec<Association>>;
 
     // Index operations - Generic association counting
     async fn update_association_count(
@@ -173,6 +400,79 @@ pub trait DatabaseInterface: Send + Sync {
         delta: i64,
     ) -> AppResult<()>;
     async fn get_association_count(&self, id: ObjectId, atype: AssociationType) -> AppResult<u64>;
+    /// Every atype with a non-zero count for `id`, ordered by atype
+    async fn get_association_counts_for_object(
+        &self,
+        id: ObjectId,
+    ) -> AppResult<Vec<(AssociationType, u64)>>;
+    /// Number of objects of `otype` on this shard, read from a counter maintained by
+    /// create_object/delete_object instead of scanning the objects table.
+    /// It drifts if rows are written outside this interface, so treat it as an estimate.
+    async fn get_object_type_count(&self, otype: ObjectType) -> AppResult<u64>;
+    /// Reset the stored count of `(id, atype)` to its actual number of edges, returning it
+    async fn recount_association(&self, id: ObjectId, atype: AssociationType) -> AppResult<u64>;
+    /// Compare every stored association count with its edges and find edges whose id1 object
+    /// is gone. Bucketed fan-out inverses are skipped since their id1 lives on another shard.
+    /// With `repair`, mismatched counts are recounted; orphans are only reported.
+    async fn audit_consistency(&self, repair: bool) -> AppResult<Vec<ConsistencyIssue>>;
+
+    // Object attributes - Free-form values kept beside the object, outside its Thrift payload.
+    // Only the (object_id, key) primary key is indexed; lookups by value need an index added
+    // explicitly. Deleting the object deletes its attributes.
+    async fn set_object_attribute(
+        &self,
+        id: ObjectId,
+        key: String,
+        value: Vec<u8>,
+    ) -> AppResult<()>;
+    async fn get_object_attribute(&self, id: ObjectId, key: String) -> AppResult<Option<Vec<u8>>>;
+    async fn get_object_attributes(&self, id: ObjectId) -> AppResult<HashMap<String, Vec<u8>>>;
+
+    // Unique keys - (otype, field, value) -> object id, guarded by a unique constraint.
+    // A key is stored on its object's shard, and deleting the object releases its keys.
+    async fn get_unique_key(
+        &self,
+        otype: ObjectType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<ObjectId>>;
+
+    // Idempotency keys for retried HTTP writes; expired records read as absent
+    async fn get_idempotency_record(&self, key: &str) -> AppResult<Option<IdempotencyRecord>>;
+    /// Store (or replace) a record, pruning expired ones
+    async fn put_idempotency_record(&self, record: IdempotencyRecord) -> AppResult<()>;
+    /// Atomically claim `key` with a pending record until `expires_at`, unless an unexpired
+    /// record already holds it. Returns None when the claim was taken, else the holding record.
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        expires_at: Timestamp,
+    ) -> AppResult<Option<IdempotencyRecord>>;
+    /// Drop the record for `key`, so the next request carrying it runs
+    async fn delete_idempotency_record(&self, key: &str) -> AppResult<()>;
+
+    // Association change log - numbered adds and removals per (id1, atype) for incremental sync
+    /// `record_association_changes_tx` in a transaction of its own
+    async fn record_association_changes(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        changes: Vec<(ObjectId, bool)>,
+    ) -> AppResult<u64> {
+        let mut tx = self.begin_transaction().await?;
+        let seq = self
+            .record_association_changes_tx(&mut tx, id1, atype, changes)
+            .await?;
+        tx.commit().await?;
+        Ok(seq)
+    }
+    /// Logged changes of `(id1, atype)` with a sequence number above `since_seq`
+    async fn get_association_changes(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        since_seq: u64,
+    ) -> AppResult<AssociationChangeLog>;
 
     // Transactional operations - Execute within existing transaction
     async fn create_object_tx(
@@ -182,6 +482,20 @@ pub trait DatabaseInterface: Send + Sync {
         otype: ObjectType,
         data: Vec<u8>,
     ) -> AppResult<()>;
+    /// Claim a unique key for `id` within a transaction; false if another object holds it.
+    /// A concurrent claim of the same key waits for this transaction to finish.
+    async fn claim_unique_key_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        otype: ObjectType,
+        field: String,
+        value: String,
+        id: ObjectId,
+    ) -> AppResult<bool>;
+    /// Delete an object, its attributes and its unique keys within a transaction;
+    /// false if it did not exist
+    async fn delete_object_tx(&self, tx: &mut DatabaseTransaction, id: ObjectId)
+        -> AppResult<bool>;
     async fn create_association_tx(
         &self,
         tx: &mut DatabaseTransaction,
@@ -194,6 +508,13 @@ pub trait DatabaseInterface: Send + Sync {
         atype: AssociationType,
         id2: ObjectId,
     ) -> AppResult<bool>;
+    /// `delete_associations_of_type` within a transaction
+    async fn delete_associations_of_type_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<Vec<ObjectId>>;
     async fn update_association_count_tx(
         &self,
         tx: &mut DatabaseTransaction,
@@ -201,10 +522,24 @@ pub trait DatabaseInterface: Send + Sync {
         atype: AssociationType,
         delta: i64,
     ) -> AppResult<()>;
+    /// Append `changes` (each an id2 and whether it was added) to the log of `(id1, atype)`
+    /// under consecutive sequence numbers, dropping entries more than
+    /// `ASSOC_CHANGE_LOG_RETENTION` behind the newest. Returns the newest sequence number.
+    async fn record_association_changes_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id1: ObjectId,
+        atype: AssociationType,
+        changes: Vec<(ObjectId, bool)>,
+    ) -> AppResult<u64>;
 
     /// Execute a raw SQL query and return results as a vector of hashmaps
     async fn execute_query(&self, query: String) -> AppResult<Vec<HashMap<String, String>>>;
 
+    /// Time of the latest write to objects or association counts, 0 when empty.
+    /// A replica's lag is its primary's watermark minus its own.
+    async fn write_high_watermark(&self) -> AppResult<Timestamp>;
+
     // Graph visualization methods
     /// Get all objects from this shard for graph visualization
     async fn get_all_objects_from_shard(&self) -> AppResult<Vec<Object>>;
@@ -212,28 +547,261 @@ pub trait DatabaseInterface: Send + Sync {
     async fn get_all_associations_from_shard(&self) -> AppResult<Vec<Association>>;
 }
 
+/// Server-side statement timeouts applied to reads, in milliseconds (0 disables the limit)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatementTimeouts {
+    /// Point reads and association range queries
+    pub read_timeout_ms: u64,
+    /// Full scans such as per-type object listings and shard dumps
+    pub scan_timeout_ms: u64,
+}
+
+impl Default for StatementTimeouts {
+    fn default() -> Self {
+        Self {
+            read_timeout_ms: 5_000,
+            scan_timeout_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StatementClass {
+    Read,
+    Scan,
+}
+
+/// SQLSTATE 57014 query_canceled, raised when statement_timeout fires
+const QUERY_CANCELED_SQLSTATE: &str = "57014";
+
+fn is_statement_timeout(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db_error) => db_error
+            .code()
+            .is_some_and(|code| code == QUERY_CANCELED_SQLSTATE),
+        _ => false,
+    }
+}
+
+/// SQLSTATEs Postgres raises when a transaction lost a conflict and can simply be run again:
+/// 40001 serialization_failure and 40P01 deadlock_detected.
+pub const RETRYABLE_SQLSTATES: [&str; 2] = ["40001", "40P01"];
+
+const ASSOCIATION_COUNT_UPSERT: &str =
+    "INSERT INTO association_counts (id, atype, count, updated_time) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (id, atype) DO UPDATE SET count = association_counts.count + $3, updated_time = $4";
+
+const OBJECT_TYPE_COUNT_UPSERT: &str =
+    "INSERT INTO object_type_counts (otype, count, updated_time) VALUES ($1, $2, $3)
+             ON CONFLICT (otype) DO UPDATE SET count = object_type_counts.count + $2, updated_time = $3";
+
+/// Rows per multi-row INSERT in `bulk_load`, keeping each statement well under
+/// Postgres' 65535 bind parameter limit
+const BULK_LOAD_BATCH_SIZE: usize = 1000;
+
+/// Whether a SQLSTATE marks a transaction that is safe to retry
+pub fn is_retryable_sqlstate(code: &str) -> bool {
+    RETRYABLE_SQLSTATES.contains(&code)
+}
+
+/// Whether a sqlx error is a deadlock or serialization failure reported by the database
+pub fn is_retryable_transaction_error(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db_error) => db_error
+            .code()
+            .is_some_and(|code| is_retryable_sqlstate(&code)),
+        _ => false,
+    }
+}
+
+/// Whether sqlx gave up waiting for a pooled connection (or the pool was closed), as
+/// opposed to the database rejecting a statement
+pub fn is_pool_unavailable(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed)
+}
+
+/// Map a sqlx error from a pool-backed call. Pool exhaustion is transient and worth retrying,
+/// so it surfaces as `ServiceUnavailable`; everything else stays a `DatabaseError`.
+fn pool_error(context: impl std::fmt::Display, error: sqlx::Error) -> AppError {
+    if is_pool_unavailable(&error) {
+        AppError::ServiceUnavailable(format!("{}: {}", context, error))
+    } else {
+        AppError::DatabaseError(format!("{}: {}", context, error))
+    }
+}
+
+fn object_version_from_row(row: &PgRow) -> ObjectVersion {
+    ObjectVersion {
+        id: row.get("id"),
+        version: row.get::<i64, _>("version") as u64,
+        data: row.get("data"),
+        updated_time: row.get("updated_time"),
+        changed_by: row.get("changed_by"),
+    }
+}
+
+/// Retry policy for transactions aborted by a deadlock or serialization failure
+#[derive(Debug, Clone)]
+pub struct TransactionRetryConfig {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for TransactionRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 10,
+            max_delay_ms: 500,
+        }
+    }
+}
+
+impl TransactionRetryConfig {
+    /// Exponential backoff for the given retry (1-based), with jitter in [delay/2, delay]
+    fn backoff(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(20));
+        let delay = exp.min(self.max_delay_ms).max(1);
+        Duration::from_millis(rand::rng().random_range(delay / 2..=delay))
+    }
+}
+
 /// PostgreSQL implementation of database interface
 pub struct PostgresDatabase {
     pool: PgPool,
+    retry_config: TransactionRetryConfig,
+    statement_timeouts: std::sync::RwLock<StatementTimeouts>,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 impl PostgresDatabase {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            retry_config: TransactionRetryConfig::default(),
+            statement_timeouts: std::sync::RwLock::new(StatementTimeouts::default()),
+            metrics: None,
+        }
     }
 
-    /// Health check to verify database connectivity
-    pub async fn health_check(&self) -> AppResult<()> {
-        sqlx::query("SELECT 1")
-            .execute(&self.pool)
-            .await
-            .map_err(|e| AppError::DatabaseError(format!("Database health check failed: {}", e)))?;
-        Ok(())
+    /// Open a pool and apply `timeouts` to its reads and scans. The pooled connections
+    /// keep the server's statement_timeout, so writes are not bounded by the read timeout.
+    pub async fn connect(
+        pool_options: PgPoolOptions,
+        connect_options: PgConnectOptions,
+        timeouts: StatementTimeouts,
+    ) -> Result<Self, sqlx::Error> {
+        let pool = pool_options.connect_with(connect_options).await?;
+
+        let database = Self::new(pool);
+        database.set_statement_timeouts(timeouts);
+        Ok(database)
     }
 
-    /// Get connection pool statistics
-    pub fn pool_stats(&self) -> (u32, u32) {
-        (self.pool.num_idle() as u32, self.pool.size())
+    pub fn with_retry_config(mut self, retry_config: TransactionRetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Report deadlock retries to the given collector
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Fetch rows with the statement timeout for `class` scoped to this query only: it is
+    /// set with `SET LOCAL` in a transaction of its own, so the pooled connection and the
+    /// writes that later run on it keep their own limit.
+    /// A fired timeout surfaces as `AppError::TimeoutError`.
+    async fn fetch_all_with_timeout<'q>(
+        &self,
+        class: StatementClass,
+        operation: &str,
+        query: sqlx::query::Query<'q, Postgres, PgArguments>,
+    ) -> AppResult<Vec<PgRow>> {
+        let timeouts = *self.statement_timeouts.read().unwrap();
+        let timeout_ms = match class {
+            StatementClass::Read => timeouts.read_timeout_ms,
+            StatementClass::Scan => timeouts.scan_timeout_ms,
+        };
+
+        let result = async {
+            let mut tx = self.pool.begin().await?;
+            // SET does not take bind parameters; timeout_ms is a plain integer
+            sqlx::query(&format!("SET LOCAL statement_timeout = {}", timeout_ms))
+                .execute(&mut *tx)
+                .await?;
+            let rows = query.fetch_all(&mut *tx).await?;
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(rows)
+        }
+        .await;
+
+        match result {
+            Ok(rows) => Ok(rows),
+            Err(e) if is_statement_timeout(&e) => {
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_timeout().await;
+                }
+                Err(AppError::TimeoutError(format!(
+                    "Failed to {}: exceeded statement timeout of {}ms",
+                    operation, timeout_ms
+                )))
+            }
+            Err(e) => Err(pool_error(format!("Failed to {}", operation), e)),
+        }
+    }
+
+    /// Run `op` inside its own transaction, retrying the whole transaction with jittered
+    /// backoff when Postgres aborts it with a deadlock or serialization failure.
+    /// `op` may run several times, so it must not have side effects outside the transaction.
+    async fn run_in_transaction<T, F>(&self, operation: &str, mut op: F) -> AppResult<T>
+    where
+        T: Send,
+        F: for<'c> FnMut(&'c mut PgConnection) -> BoxFuture<'c, Result<T, sqlx::Error>> + Send,
+    {
+        let mut attempt = 1;
+        loop {
+            let result = async {
+                let mut tx = self.pool.begin().await?;
+                let value = op(&mut tx).await?;
+                tx.commit().await?;
+                Ok(value)
+            }
+            .await;
+
+            match result {
+                Ok(value) => return Ok(value),
+                Err(e)
+                    if is_retryable_transaction_error(&e)
+                        && attempt < self.retry_config.max_attempts =>
+                {
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_deadlock().await;
+                    }
+                    let delay = self.retry_config.backoff(attempt);
+                    tracing::warn!(
+                        "{} hit a retryable transaction conflict (attempt {}), retrying in {:?}: {}",
+                        operation,
+                        attempt,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(pool_error(
+                        format!("Failed to {} after {} attempt(s)", operation, attempt),
+                        e,
+                    ))
+                }
+            }
+        }
     }
 
     /// Initialize TAO database tables with date partitioning and ID sharding
@@ -254,6 +822,45 @@ impl PostgresDatabase {
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to drop association counts table: {}", e))
             })?;
+        sqlx::query("DROP TABLE IF EXISTS object_type_counts CASCADE")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to drop object type counts table: {}", e))
+            })?;
+        sqlx::query("DROP TABLE IF EXISTS object_attributes CASCADE")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to drop object attributes table: {}", e))
+            })?;
+        sqlx::query("DROP TABLE IF EXISTS unique_keys CASCADE")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to drop unique keys table: {}", e))
+            })?;
+        sqlx::query("DROP TABLE IF EXISTS object_history CASCADE")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to drop object history table: {}", e))
+            })?;
+        sqlx::query("DROP TABLE IF EXISTS association_changes CASCADE")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to drop association changes table: {}", e))
+            })?;
+        sqlx::query("DROP TABLE IF EXISTS association_change_seqs CASCADE")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to drop association change sequences table: {}",
+                    e
+                ))
+            })?;
 
         // Create objects table partitioned by date (time_created)
         sqlx::query(
@@ -282,6 +889,7 @@ impl PostgresDatabase {
                 id2 BIGINT NOT NULL,
                 time_created BIGINT NOT NULL,
                 data BYTEA,
+                subtype VARCHAR(64),
                 PRIMARY KEY (id1, atype, id2, time_created)
             ) PARTITION BY RANGE (time_created)
         "#,
@@ -310,37 +918,164 @@ impl PostgresDatabase {
             AppError::DatabaseError(format!("Failed to create association counts table: {}", e))
         })?;
 
-        // Create monthly partitions for current and next 12 months
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as i64;
-        let current_month_start =
-            (current_time / (30 * 24 * 60 * 60 * 1000)) * (30 * 24 * 60 * 60 * 1000); // Rough monthly boundaries
+        // Create per-type object count table, backing count estimates without full scans
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS object_type_counts (
+                otype VARCHAR(64) PRIMARY KEY,
+                count BIGINT NOT NULL DEFAULT 0,
+                updated_time BIGINT NOT NULL
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create object type counts table: {}", e))
+        })?;
 
-        for i in 0..13 {
-            // Current month + 12 future months
-            let month_start = current_month_start + (i * 30 * 24 * 60 * 60 * 1000);
-            let month_end = month_start + (30 * 24 * 60 * 60 * 1000);
+        // Create object attributes table for values that live outside the typed payload
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS object_attributes (
+                object_id BIGINT NOT NULL,
+                key VARCHAR(128) NOT NULL,
+                value BYTEA NOT NULL,
+                updated_time BIGINT NOT NULL,
+                PRIMARY KEY (object_id, key)
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create object attributes table: {}", e))
+        })?;
 
-            // Objects partitions
-            sqlx::query(&format!(
-                "CREATE TABLE IF NOT EXISTS objects_m{} PARTITION OF objects FOR VALUES FROM ({}) TO ({})",
-                i, month_start, month_end
-            ))
-            .execute(&self.pool)
-            .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to create objects monthly partition {}: {}", i, e)))?;
+        // Create unique keys table; the primary key is what makes get-or-create race-safe
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS unique_keys (
+                otype VARCHAR(64) NOT NULL,
+                field VARCHAR(128) NOT NULL,
+                value VARCHAR(512) NOT NULL,
+                object_id BIGINT NOT NULL,
+                PRIMARY KEY (otype, field, value)
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create unique keys table: {}", e))
+        })?;
 
-            // Associations partitions
-            sqlx::query(&format!(
-                "CREATE TABLE IF NOT EXISTS associations_m{} PARTITION OF associations FOR VALUES FROM ({}) TO ({})",
-                i, month_start, month_end
-            ))
-            .execute(&self.pool)
-            .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to create associations monthly partition {}: {}", i, e)))?;
-        }
+        // Create object history table; only types that keep history write to it
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS object_history (
+                id BIGINT NOT NULL,
+                version BIGINT NOT NULL,
+                data BYTEA,
+                updated_time BIGINT NOT NULL,
+                changed_by BIGINT,
+                PRIMARY KEY (id, version)
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create object history table: {}", e))
+        })?;
+
+        // Create association change log tables; the sequence row serializes concurrent writers
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS association_change_seqs (
+                id1 BIGINT NOT NULL,
+                atype VARCHAR(64) NOT NULL,
+                seq BIGINT NOT NULL,
+                PRIMARY KEY (id1, atype)
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to create association change sequences table: {}",
+                e
+            ))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS association_changes (
+                id1 BIGINT NOT NULL,
+                atype VARCHAR(64) NOT NULL,
+                seq BIGINT NOT NULL,
+                id2 BIGINT NOT NULL,
+                added BOOLEAN NOT NULL,
+                changed_time BIGINT NOT NULL,
+                PRIMARY KEY (id1, atype, seq)
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create association changes table: {}", e))
+        })?;
+
+        // Create idempotency keys table for replaying retried HTTP writes
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS idempotency_keys (
+                key VARCHAR(512) PRIMARY KEY,
+                status INTEGER NOT NULL,
+                body BYTEA NOT NULL,
+                expires_at BIGINT NOT NULL
+            )
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create idempotency keys table: {}", e))
+        })?;
+
+        // Create monthly partitions for current and next 12 months
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let current_month_start =
+            (current_time / (30 * 24 * 60 * 60 * 1000)) * (30 * 24 * 60 * 60 * 1000); // Rough monthly boundaries
+
+        for i in 0..13 {
+            // Current month + 12 future months
+            let month_start = current_month_start + (i * 30 * 24 * 60 * 60 * 1000);
+            let month_end = month_start + (30 * 24 * 60 * 60 * 1000);
+
+            // Objects partitions
+            sqlx::query(&format!(
+                "CREATE TABLE IF NOT EXISTS objects_m{} PARTITION OF objects FOR VALUES FROM ({}) TO ({})",
+                i, month_start, month_end
+            ))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to create objects monthly partition {}: {}", i, e)))?;
+
+            // Associations partitions
+            sqlx::query(&format!(
+                "CREATE TABLE IF NOT EXISTS associations_m{} PARTITION OF associations FOR VALUES FROM ({}) TO ({})",
+                i, month_start, month_end
+            ))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to create associations monthly partition {}: {}", i, e)))?;
+        }
 
         // Create indexes for performance
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_objects_otype ON objects(otype)")
@@ -360,9 +1095,208 @@ impl PostgresDatabase {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to create reverse associations index: {}", e)))?;
 
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tao_assoc_id1_id2 ON associations(id1, id2)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to create associations pair index: {}", e))
+            })?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tao_assoc_id1_atype_subtype ON associations(id1, atype, subtype)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to create associations subtype index: {}", e))
+            })?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_unique_keys_object_id ON unique_keys(object_id)",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create unique keys index: {}", e))
+        })?;
+
         println!("✅ TAO database tables initialized with date partitioning (monthly)");
         Ok(())
     }
+
+    /// Load objects and associations with multi-row inserts, for dev/test seeding only.
+    ///
+    /// Rows go straight into the tables in one transaction, bypassing the decorator chain,
+    /// the WAL and the cache; association and object type counts are applied in bulk once
+    /// every row is in. Rows that already exist are skipped and not counted.
+    ///
+    /// Not safe alongside production writes: nothing invalidates caches, publishes change
+    /// events or records the load in the WAL, and counts are only correct if no other writer
+    /// touches the same rows while the load runs.
+    pub async fn bulk_load(
+        &self,
+        objects: Vec<Object>,
+        associations: Vec<Association>,
+    ) -> AppResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let bulk_load_error =
+            |e: sqlx::Error| AppError::DatabaseError(format!("Bulk load failed: {}", e));
+
+        let mut tx = self.pool.begin().await.map_err(bulk_load_error)?;
+
+        let mut object_counts: HashMap<ObjectType, i64> = HashMap::new();
+        for batch in objects.chunks(BULK_LOAD_BATCH_SIZE) {
+            let mut qb = QueryBuilder::<Postgres>::new(
+                "INSERT INTO objects (id, otype, time_created, time_updated, data, version) ",
+            );
+            qb.push_values(batch, |mut row, object| {
+                row.push_bind(object.id)
+                    .push_bind(&object.otype)
+                    .push_bind(object.created_time)
+                    .push_bind(object.updated_time)
+                    .push_bind(&object.data)
+                    .push_bind(object.version as i32);
+            });
+            qb.push(" ON CONFLICT DO NOTHING RETURNING otype");
+
+            let inserted = qb
+                .build()
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(bulk_load_error)?;
+            for row in inserted {
+                *object_counts.entry(row.get("otype")).or_default() += 1;
+            }
+        }
+
+        let mut association_counts: HashMap<(ObjectId, AssociationType), i64> = HashMap::new();
+        for batch in associations.chunks(BULK_LOAD_BATCH_SIZE) {
+            let mut qb = QueryBuilder::<Postgres>::new(
+                "INSERT INTO associations (id1, atype, id2, time_created, data, subtype) ",
+            );
+            qb.push_values(batch, |mut row, assoc| {
+                row.push_bind(assoc.id1)
+                    .push_bind(&assoc.atype)
+                    .push_bind(assoc.id2)
+                    .push_bind(assoc.time)
+                    .push_bind(&assoc.data)
+                    .push_bind(&assoc.subtype);
+            });
+            qb.push(" ON CONFLICT DO NOTHING RETURNING id1, atype");
+
+            let inserted = qb
+                .build()
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(bulk_load_error)?;
+            for row in inserted {
+                *association_counts
+                    .entry((row.get("id1"), row.get("atype")))
+                    .or_default() += 1;
+            }
+        }
+
+        let object_counts: Vec<_> = object_counts.into_iter().collect();
+        for batch in object_counts.chunks(BULK_LOAD_BATCH_SIZE) {
+            let mut qb = QueryBuilder::<Postgres>::new(
+                "INSERT INTO object_type_counts (otype, count, updated_time) ",
+            );
+            qb.push_values(batch, |mut row, (otype, count)| {
+                row.push_bind(otype).push_bind(*count).push_bind(now);
+            });
+            qb.push(
+                " ON CONFLICT (otype) DO UPDATE SET count = object_type_counts.count + EXCLUDED.count, updated_time = EXCLUDED.updated_time",
+            );
+            qb.build()
+                .execute(&mut *tx)
+                .await
+                .map_err(bulk_load_error)?;
+        }
+
+        let association_counts: Vec<_> = association_counts.into_iter().collect();
+        for batch in association_counts.chunks(BULK_LOAD_BATCH_SIZE) {
+            let mut qb = QueryBuilder::<Postgres>::new(
+                "INSERT INTO association_counts (id, atype, count, updated_time) ",
+            );
+            qb.push_values(batch, |mut row, ((id1, atype), count)| {
+                row.push_bind(*id1)
+                    .push_bind(atype)
+                    .push_bind(*count)
+                    .push_bind(now);
+            });
+            qb.push(
+                " ON CONFLICT (id, atype) DO UPDATE SET count = association_counts.count + EXCLUDED.count, updated_time = EXCLUDED.updated_time",
+            );
+            qb.build()
+                .execute(&mut *tx)
+                .await
+                .map_err(bulk_load_error)?;
+        }
+
+        tx.commit().await.map_err(bulk_load_error)
+    }
+
+    /// Insert a batch of associations for a graph import, leaving `association_counts`
+    /// alone. Call it once per batch, then `finalize_association_import` once to compute
+    /// every count in a single pass. Each batch is one statement; edges that already exist
+    /// are skipped. Returns the number of edges inserted.
+    ///
+    /// Like `bulk_load`, this bypasses the decorator chain, the WAL and the cache, and
+    /// counts read before finalizing miss the imported edges.
+    pub async fn import_associations(&self, batch: Vec<Association>) -> AppResult<u64> {
+        let mut id1s = Vec::with_capacity(batch.len());
+        let mut atypes = Vec::with_capacity(batch.len());
+        let mut id2s = Vec::with_capacity(batch.len());
+        let mut times = Vec::with_capacity(batch.len());
+        let mut data = Vec::with_capacity(batch.len());
+        let mut subtypes = Vec::with_capacity(batch.len());
+        for assoc in batch {
+            id1s.push(assoc.id1);
+            atypes.push(assoc.atype);
+            id2s.push(assoc.id2);
+            times.push(assoc.time);
+            data.push(assoc.data);
+            subtypes.push(assoc.subtype);
+        }
+
+        let result = sqlx::query(
+            "INSERT INTO associations (id1, atype, id2, time_created, data, subtype)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::VARCHAR[], $3::BIGINT[], $4::BIGINT[], $5::BYTEA[], $6::VARCHAR[])
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&id1s)
+        .bind(&atypes)
+        .bind(&id2s)
+        .bind(&times)
+        .bind(&data)
+        .bind(&subtypes)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| pool_error("Association import failed", e))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Recompute `association_counts` from the stored associations with one grouped
+    /// insert, after the last `import_associations` batch. Every (id1, atype) that has
+    /// edges gets its exact count. Returns the number of count rows written.
+    pub async fn finalize_association_import(&self) -> AppResult<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        let result = sqlx::query(
+            "INSERT INTO association_counts (id, atype, count, updated_time)
+             SELECT id1, atype, COUNT(*), $1 FROM associations GROUP BY id1, atype
+             ON CONFLICT (id, atype) DO UPDATE SET count = EXCLUDED.count, updated_time = EXCLUDED.updated_time",
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| pool_error("Failed to finalize association import", e))?;
+        Ok(result.rows_affected())
+    }
 }
 
 #[async_trait]
@@ -371,11 +1305,26 @@ impl DatabaseInterface for PostgresDatabase {
         self
     }
 
-    async fn execute_query(&self, query: String) -> AppResult<Vec<HashMap<String, String>>> {
-        let rows = sqlx::query(&query)
-            .fetch_all(&self.pool)
+    fn pool_stats(&self) -> (u32, u32) {
+        (self.pool.num_idle() as u32, self.pool.size())
+    }
+
+    fn set_statement_timeouts(&self, timeouts: StatementTimeouts) {
+        *self.statement_timeouts.write().unwrap() = timeouts;
+    }
+
+    async fn health_check(&self) -> AppResult<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
             .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to execute query: {}", e)))?;
+            .map_err(|e| pool_error("Database health check failed", e))?;
+        Ok(())
+    }
+
+    async fn execute_query(&self, query: String) -> AppResult<Vec<HashMap<String, String>>> {
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Read, "execute query", sqlx::query(&query))
+            .await?;
 
         let mut results = Vec::new();
         for row in rows {
@@ -404,21 +1353,22 @@ impl DatabaseInterface for PostgresDatabase {
     }
 
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
-        let tx =
-            self.pool.begin().await.map_err(|e| {
-                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
-            })?;
+        let tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| pool_error("Failed to begin transaction", e))?;
         Ok(DatabaseTransaction::new_postgres(tx))
     }
 
     async fn get_object(&self, id: ObjectId) -> AppResult<Option<Object>> {
         let row = sqlx::query(
-            "SELECT id, otype, time_created, time_updated, data FROM objects WHERE id = $1",
+            "SELECT id, otype, time_created, time_updated, data, version FROM objects WHERE id = $1",
         )
         .bind(id)
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to get object {}: {}", id, e)))?;
+        .map_err(|e| pool_error(format!("Failed to get object {}", id), e))?;
 
         if let Some(row) = row {
             Ok(Some(Object {
@@ -435,20 +1385,44 @@ impl DatabaseInterface for PostgresDatabase {
     }
 
     async fn get_objects(&self, query: ObjectQuery) -> AppResult<ObjectQueryResult> {
-        let sql =
-            "SELECT id, otype, time_created, time_updated, data FROM objects WHERE otype = $1"
-                .to_string();
-
-        let mut query_builder = sqlx::query(&sql).bind(&query.otype);
-
+        let mut query_builder = QueryBuilder::<Postgres>::new(
+            "SELECT id, otype, time_created, time_updated, data, version FROM objects WHERE TRUE",
+        );
+        if !query.ids.is_empty() {
+            query_builder
+                .push(" AND id = ANY(")
+                .push_bind(&query.ids)
+                .push(")");
+        }
         if let Some(ref otype) = query.otype {
-            query_builder = query_builder.bind(otype);
+            query_builder.push(" AND otype = ").push_bind(otype);
+        }
+        // Bounds on the partition key let the planner prune partitions outside the range
+        if let Some(created_after) = query.created_after {
+            query_builder
+                .push(" AND time_created >= ")
+                .push_bind(created_after);
+        }
+        if let Some(created_before) = query.created_before {
+            query_builder
+                .push(" AND time_created <= ")
+                .push_bind(created_before);
+        }
+        if query.created_after.is_some() || query.created_before.is_some() {
+            query_builder.push(" ORDER BY time_created, id");
+        } else {
+            query_builder.push(" ORDER BY id");
+        }
+        if let Some(limit) = query.limit {
+            query_builder.push(" LIMIT ").push_bind(limit as i64);
+        }
+        if let Some(offset) = query.offset {
+            query_builder.push(" OFFSET ").push_bind(offset as i64);
         }
 
-        let rows = query_builder
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to get objects: {}", e)))?;
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Scan, "get objects", query_builder.build())
+            .await?;
 
         let objects = rows
             .into_iter()
@@ -464,7 +1438,8 @@ impl DatabaseInterface for PostgresDatabase {
 
         Ok(ObjectQueryResult {
             objects,
-            next_cursor: None, // TODO: Implement pagination
+            // Object queries page by limit/offset, not by cursor
+            next_cursor: None,
         })
     }
 
@@ -474,18 +1449,32 @@ impl DatabaseInterface for PostgresDatabase {
             .unwrap()
             .as_millis() as i64;
 
-        sqlx::query(
-            "INSERT INTO objects (id, otype, time_created, time_updated, data) VALUES ($1, $2, $3, $4, $5)"
-        )
-        .bind(id)
-        .bind(&otype)
-        .bind(now)
-        .bind(now)
-        .bind(&data)
-        .execute(&self.pool)
+        // Insert the object and bump its type count atomically
+        self.run_in_transaction("create object", |conn| {
+            let otype = otype.clone();
+            let data = data.clone();
+            Box::pin(async move {
+                sqlx::query(
+                    "INSERT INTO objects (id, otype, time_created, time_updated, data) VALUES ($1, $2, $3, $4, $5)"
+                )
+                .bind(id)
+                .bind(&otype)
+                .bind(now)
+                .bind(now)
+                .bind(&data)
+                .execute(&mut *conn)
+                .await?;
+
+                sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+                    .bind(&otype)
+                    .bind(1i64)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+                Ok(())
+            })
+        })
         .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to create object with ID {}: {}", id, e)))?;
-        Ok(())
     }
 
     async fn update_object(&self, id: ObjectId, data: Vec<u8>) -> AppResult<()> {
@@ -502,7 +1491,7 @@ impl DatabaseInterface for PostgresDatabase {
         .bind(id)
         .execute(&self.pool)
         .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to update object {}: {}", id, e)))?;
+        .map_err(|e| pool_error(format!("Failed to update object {}", id), e))?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound(format!("Object {} not found", id)));
@@ -512,15 +1501,121 @@ impl DatabaseInterface for PostgresDatabase {
     }
 
     async fn delete_object(&self, id: ObjectId) -> AppResult<bool> {
-        let result = sqlx::query("DELETE FROM objects WHERE id = $1")
-            .bind(id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!("Failed to delete object {}: {}", id, e))
-            })?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
 
-        Ok(result.rows_affected() > 0)
+        self.run_in_transaction("delete object", |conn| {
+            Box::pin(async move {
+                let deleted = sqlx::query("DELETE FROM objects WHERE id = $1 RETURNING otype")
+                    .bind(id)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+
+                let Some(row) = deleted else {
+                    return Ok(false);
+                };
+                let otype: String = row.get("otype");
+
+                sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+                    .bind(&otype)
+                    .bind(-1i64)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+                sqlx::query("DELETE FROM object_attributes WHERE object_id = $1")
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await?;
+                sqlx::query("DELETE FROM unique_keys WHERE object_id = $1")
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await?;
+                Ok(true)
+            })
+        })
+        .await
+    }
+
+    async fn update_object_with_history(
+        &self,
+        id: ObjectId,
+        data: Vec<u8>,
+        changed_by: Option<ObjectId>,
+    ) -> AppResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        let updated = self
+            .run_in_transaction("update object with history", |conn| {
+                let data = data.clone();
+                Box::pin(async move {
+                    // Lock the row so a concurrent update waits and then records the
+                    // version this one leaves behind, not the same one again
+                    sqlx::query(
+                        "INSERT INTO object_history (id, version, data, updated_time, changed_by)
+                         SELECT id, version, data, time_updated, $2 FROM objects WHERE id = $1
+                         FOR UPDATE",
+                    )
+                    .bind(id)
+                    .bind(changed_by)
+                    .execute(&mut *conn)
+                    .await?;
+                    let result = sqlx::query(
+                        "UPDATE objects SET data = $1, time_updated = $2, version = version + 1 WHERE id = $3",
+                    )
+                    .bind(&data)
+                    .bind(now)
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await?;
+                    Ok(result.rows_affected() > 0)
+                })
+            })
+            .await?;
+
+        if !updated {
+            return Err(AppError::NotFound(format!("Object {} not found", id)));
+        }
+        Ok(())
+    }
+
+    async fn get_object_history(&self, id: ObjectId, limit: u32) -> AppResult<Vec<ObjectVersion>> {
+        let rows = sqlx::query(
+            "SELECT id, version, data, updated_time, changed_by FROM object_history
+             WHERE id = $1 ORDER BY version DESC LIMIT $2",
+        )
+        .bind(id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| pool_error(format!("Failed to get history of object {}", id), e))?;
+        Ok(rows.iter().map(object_version_from_row).collect())
+    }
+
+    async fn get_object_version(
+        &self,
+        id: ObjectId,
+        version: u64,
+    ) -> AppResult<Option<ObjectVersion>> {
+        let row = sqlx::query(
+            "SELECT id, version, data, updated_time, changed_by FROM object_history
+             WHERE id = $1 AND version = $2",
+        )
+        .bind(id)
+        .bind(version as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            pool_error(
+                format!("Failed to get version {} of object {}", version, id),
+                e,
+            )
+        })?;
+        Ok(row.as_ref().map(object_version_from_row))
     }
 
     async fn object_exists(&self, id: ObjectId) -> AppResult<bool> {
@@ -528,34 +1623,67 @@ impl DatabaseInterface for PostgresDatabase {
             .bind(id)
             .fetch_optional(&self.pool)
             .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!("Failed to check association existence: {}", e))
-            })?;
+            .map_err(|e| pool_error("Failed to check association existence", e))?;
 
         Ok(row.is_some())
     }
 
     async fn get_associations(&self, query: AssocQuery) -> AppResult<AssocQueryResult> {
-        let mut sql = "SELECT id1, atype, id2, time_created, data FROM associations WHERE id1 = $1 AND atype = $2".to_string();
+        let mut edge_filters = String::new();
         let mut param_index = 2;
 
         // Add id2_set clause if present
         if let Some(ref _id2_set) = query.id2_set {
             param_index += 1;
-            sql.push_str(&format!(" AND id2 = ANY(${})", param_index));
+            edge_filters.push_str(&format!(" AND id2 = ANY(${})", param_index));
+        }
+
+        if query.low_id2.is_some() {
+            param_index += 1;
+            edge_filters.push_str(&format!(" AND id2 >= ${}", param_index));
+        }
+
+        if query.high_id2.is_some() {
+            param_index += 1;
+            edge_filters.push_str(&format!(" AND id2 <= ${}", param_index));
         }
 
+        // Time filters apply to the latest row of each edge, after dedup
+        let mut time_filters = String::new();
         if query.low_time.is_some() {
             param_index += 1;
-            sql.push_str(&format!(" AND time_created >= ${}", param_index));
+            time_filters.push_str(&format!(" AND time_created >= ${}", param_index));
         }
 
         if query.high_time.is_some() {
             param_index += 1;
-            sql.push_str(&format!(" AND time_created <= ${}", param_index));
+            time_filters.push_str(&format!(" AND time_created <= ${}", param_index));
+        }
+
+        if query.after.is_some() {
+            time_filters.push_str(&format!(
+                " AND (time_created, id2) < (${}, ${})",
+                param_index + 1,
+                param_index + 2
+            ));
+            param_index += 2;
         }
 
-        sql.push_str(" ORDER BY time_created DESC");
+        let columns = "id1, atype, id2, time_created, data, subtype";
+        let mut sql = if query.dedup {
+            format!(
+                "SELECT {columns} FROM (SELECT DISTINCT ON (id2) {columns} FROM associations \
+                 WHERE id1 = $1 AND atype = $2{edge_filters} ORDER BY id2, time_created DESC) latest \
+                 WHERE TRUE{time_filters}"
+            )
+        } else {
+            format!(
+                "SELECT {columns} FROM associations WHERE id1 = $1 AND atype = $2{edge_filters}{time_filters}"
+            )
+        };
+
+        // id2 breaks ties between edges created in the same millisecond
+        sql.push_str(" ORDER BY time_created DESC, id2 DESC");
 
         if query.limit.is_some() {
             param_index += 1;
@@ -573,12 +1701,21 @@ impl DatabaseInterface for PostgresDatabase {
         if let Some(ref id2_set) = query.id2_set {
             query_builder = query_builder.bind(id2_set);
         }
+        if let Some(low_id2) = query.low_id2 {
+            query_builder = query_builder.bind(low_id2);
+        }
+        if let Some(high_id2) = query.high_id2 {
+            query_builder = query_builder.bind(high_id2);
+        }
         if let Some(low_time) = query.low_time {
             query_builder = query_builder.bind(low_time);
         }
         if let Some(high_time) = query.high_time {
             query_builder = query_builder.bind(high_time);
         }
+        if let Some((time, id2)) = query.after {
+            query_builder = query_builder.bind(time).bind(id2);
+        }
         if let Some(limit) = query.limit {
             query_builder = query_builder.bind(limit as i64);
         }
@@ -586,12 +1723,11 @@ impl DatabaseInterface for PostgresDatabase {
             query_builder = query_builder.bind(offset as i64);
         }
 
-        let rows = query_builder
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to get associations: {}", e)))?;
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Read, "get associations", query_builder)
+            .await?;
 
-        let associations = rows
+        let associations: Vec<Association> = rows
             .into_iter()
             .map(|row| Association {
                 id1: row.get("id1"),
@@ -599,34 +1735,250 @@ impl DatabaseInterface for PostgresDatabase {
                 id2: row.get("id2"),
                 time: row.get("time_created"),
                 data: row.get("data"),
+                subtype: row.get("subtype"),
             })
             .collect();
+        let next_cursor = Cursor::next_page(
+            query.offset,
+            query.limit,
+            associations.len(),
+            associations.last().map(|assoc| assoc.id2),
+            associations.last().map(|assoc| assoc.time),
+        );
+
+        let total_count = if query.include_total {
+            Some(self.get_association_count(query.id1, query.atype).await?)
+        } else {
+            None
+        };
 
         Ok(AssocQueryResult {
             associations,
-            next_cursor: None, // TODO: Implement pagination cursors
+            next_cursor,
+            total_count,
         })
     }
 
-    async fn create_association(&self, assoc: Association) -> AppResult<()> {
-        // Insert association
-        sqlx::query(
-            "INSERT INTO associations (id1, atype, id2, time_created, data) VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING"
+    async fn get_associations_multi_type(
+        &self,
+        id1: ObjectId,
+        atypes: Vec<AssociationType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<Vec<Association>> {
+        // Rank edges within each type so the per-type limit applies in the database
+        let query = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM (
+                SELECT id1, atype, id2, time_created, data, subtype,
+                       ROW_NUMBER() OVER (PARTITION BY atype ORDER BY time_created DESC) AS rank
+                FROM associations WHERE id1 = $1 AND atype = ANY($2)
+             ) ranked
+             WHERE $3::BIGINT IS NULL OR rank <= $3
+             ORDER BY atype, time_created DESC",
         )
-        .bind(assoc.id1)
-        .bind(&assoc.atype)
-        .bind(assoc.id2)
-        .bind(assoc.time)
-        .bind(&assoc.data)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to create association: {}", e)))?;
-
-        // Update association count
-        self.update_association_count(assoc.id1, assoc.atype, 1)
+        .bind(id1)
+        .bind(&atypes)
+        .bind(limit_per_type.map(|limit| limit as i64));
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Read, "get associations by type", query)
             .await?;
 
-        Ok(())
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_associations_since(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        since_time: Timestamp,
+        limit: u32,
+    ) -> AppResult<Vec<Association>> {
+        let query = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM associations WHERE id1 = $1 AND atype = $2 AND time_created > $3 ORDER BY time_created ASC LIMIT $4",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .bind(since_time)
+        .bind(limit as i64);
+        let rows = self
+            .fetch_all_with_timeout(
+                StatementClass::Read,
+                &format!("get associations since {}", since_time),
+                query,
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_associations_from_object(&self, id1: ObjectId) -> AppResult<Vec<Association>> {
+        let query = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM associations WHERE id1 = $1 ORDER BY atype, time_created DESC",
+        )
+        .bind(id1);
+        let rows = self
+            .fetch_all_with_timeout(
+                StatementClass::Read,
+                &format!("get associations from object {}", id1),
+                query,
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_associations_between(
+        &self,
+        id1: ObjectId,
+        id2: ObjectId,
+    ) -> AppResult<Vec<Association>> {
+        let query = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM associations WHERE id1 = $1 AND id2 = $2 ORDER BY time_created DESC, atype",
+        )
+        .bind(id1)
+        .bind(id2);
+        let rows = self
+            .fetch_all_with_timeout(
+                StatementClass::Read,
+                &format!("get associations between {} and {}", id1, id2),
+                query,
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_association_changes(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        since_seq: u64,
+    ) -> AppResult<AssociationChangeLog> {
+        let desc = format!("get association changes of {} ({})", id1, atype);
+        // Bounds first, so changes committed after this read can't be returned as well
+        let query = sqlx::query(
+            "SELECT COALESCE(MIN(seq), 0) AS oldest_seq, COALESCE(MAX(seq), 0) AS latest_seq
+             FROM association_changes WHERE id1 = $1 AND atype = $2",
+        )
+        .bind(id1)
+        .bind(&atype);
+        let bounds = self
+            .fetch_all_with_timeout(StatementClass::Read, &desc, query)
+            .await?;
+        let (oldest_seq, latest_seq) = bounds
+            .first()
+            .map(|row| {
+                (
+                    row.get::<i64, _>("oldest_seq"),
+                    row.get::<i64, _>("latest_seq"),
+                )
+            })
+            .unwrap_or_default();
+
+        let query = sqlx::query(
+            "SELECT seq, id2, added, changed_time FROM association_changes
+             WHERE id1 = $1 AND atype = $2 AND seq > $3 AND seq <= $4 ORDER BY seq",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .bind(since_seq as i64)
+        .bind(latest_seq);
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Read, &desc, query)
+            .await?;
+
+        Ok(AssociationChangeLog {
+            changes: rows
+                .into_iter()
+                .map(|row| AssociationChange {
+                    seq: row.get::<i64, _>("seq") as u64,
+                    id2: row.get("id2"),
+                    added: row.get("added"),
+                    changed_time: row.get("changed_time"),
+                })
+                .collect(),
+            latest_seq: latest_seq as u64,
+            oldest_seq: oldest_seq as u64,
+        })
+    }
+
+    async fn create_association(&self, assoc: Association) -> AppResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        // Insert the association and bump its count atomically
+        self.run_in_transaction("create association", |conn| {
+            let assoc = assoc.clone();
+            Box::pin(async move {
+                let result = sqlx::query(
+                    "INSERT INTO associations (id1, atype, id2, time_created, data, subtype) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING"
+                )
+                .bind(assoc.id1)
+                .bind(&assoc.atype)
+                .bind(assoc.id2)
+                .bind(assoc.time)
+                .bind(&assoc.data)
+                .bind(&assoc.subtype)
+                .execute(&mut *conn)
+                .await?;
+
+                // The edge already existed, so the count is unchanged
+                if result.rows_affected() == 0 {
+                    return Ok(());
+                }
+
+                sqlx::query(ASSOCIATION_COUNT_UPSERT)
+                    .bind(assoc.id1)
+                    .bind(&assoc.atype)
+                    .bind(1i64)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+                Ok(())
+            })
+        })
+        .await
     }
 
     async fn delete_association(
@@ -635,24 +1987,95 @@ impl DatabaseInterface for PostgresDatabase {
         atype: AssociationType,
         id2: ObjectId,
     ) -> AppResult<bool> {
-        let result =
-            sqlx::query("DELETE FROM associations WHERE id1 = $1 AND atype = $2 AND id2 = $3")
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        self.run_in_transaction("delete association", |conn| {
+            let atype = atype.clone();
+            Box::pin(async move {
+                let result = sqlx::query(
+                    "DELETE FROM associations WHERE id1 = $1 AND atype = $2 AND id2 = $3",
+                )
                 .bind(id1)
                 .bind(&atype)
                 .bind(id2)
-                .execute(&self.pool)
-                .await
-                .map_err(|e| {
-                    AppError::DatabaseError(format!("Failed to delete association: {}", e))
-                })?;
+                .execute(&mut *conn)
+                .await?;
+
+                if result.rows_affected() == 0 {
+                    return Ok(false);
+                }
+
+                sqlx::query(ASSOCIATION_COUNT_UPSERT)
+                    .bind(id1)
+                    .bind(&atype)
+                    .bind(-1i64)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+                Ok(true)
+            })
+        })
+        .await
+    }
 
-        if result.rows_affected() > 0 {
-            // Update association count
-            self.update_association_count(id1, atype, -1).await?;
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+    async fn delete_associations_of_type(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<Vec<ObjectId>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        self.run_in_transaction("delete associations of type", |conn| {
+            let atype = atype.clone();
+            Box::pin(async move {
+                let rows = sqlx::query(
+                    "DELETE FROM associations WHERE id1 = $1 AND atype = $2 RETURNING id2",
+                )
+                .bind(id1)
+                .bind(&atype)
+                .fetch_all(&mut *conn)
+                .await?;
+
+                sqlx::query(
+                    "UPDATE association_counts SET count = 0, updated_time = $3 WHERE id = $1 AND atype = $2",
+                )
+                .bind(id1)
+                .bind(&atype)
+                .bind(now)
+                .execute(&mut *conn)
+                .await?;
+                Ok(rows.into_iter().map(|row| row.get("id2")).collect())
+            })
+        })
+        .await
+    }
+
+    async fn touch_association(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+        time: Timestamp,
+    ) -> AppResult<bool> {
+        // time_created is the partition key, so Postgres moves the row if the month changes
+        let result = sqlx::query(
+            "UPDATE associations SET time_created = $4 WHERE id1 = $1 AND atype = $2 AND id2 = $3",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .bind(id2)
+        .bind(time)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| pool_error("Failed to touch association", e))?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     async fn association_exists(
@@ -668,18 +2091,92 @@ impl DatabaseInterface for PostgresDatabase {
                 .bind(id2)
                 .fetch_optional(&self.pool)
                 .await
-                .map_err(|e| {
-                    AppError::DatabaseError(format!("Failed to check association existence: {}", e))
-                })?;
+                .map_err(|e| pool_error("Failed to check association existence", e))?;
 
         Ok(row.is_some())
     }
 
+    async fn get_association(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+    ) -> AppResult<Option<Association>> {
+        let row = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM associations WHERE id1 = $1 AND atype = $2 AND id2 = $3 LIMIT 1",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .bind(id2)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| pool_error("Failed to get association", e))?;
+
+        Ok(row.map(|row| Association {
+            id1: row.get("id1"),
+            atype: row.get("atype"),
+            id2: row.get("id2"),
+            time: row.get("time_created"),
+            data: row.get("data"),
+            subtype: row.get("subtype"),
+        }))
+    }
+
     async fn count_associations(&self, id1: ObjectId, atype: AssociationType) -> AppResult<u64> {
         // Rely on the pre-calculated index table for performance
         self.get_association_count(id1, atype).await
     }
 
+    async fn count_associations_many(
+        &self,
+        pairs: Vec<(ObjectId, AssociationType)>,
+    ) -> AppResult<HashMap<(ObjectId, AssociationType), u64>> {
+        if pairs.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let (ids, atypes): (Vec<ObjectId>, Vec<AssociationType>) = pairs.into_iter().unzip();
+        let query = sqlx::query(
+            "SELECT id, atype, count FROM association_counts WHERE (id, atype) IN (SELECT * FROM UNNEST($1::BIGINT[], $2::VARCHAR[]))",
+        )
+        .bind(ids)
+        .bind(atypes);
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Read, "count associations", query)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.get("count");
+                ((row.get("id"), row.get("atype")), count as u64)
+            })
+            .collect())
+    }
+
+    async fn count_associations_by_subtype(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<HashMap<Option<String>, u64>> {
+        let query = sqlx::query(
+            "SELECT subtype, COUNT(*) AS count FROM associations WHERE id1 = $1 AND atype = $2 GROUP BY subtype",
+        )
+        .bind(id1)
+        .bind(&atype);
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Read, "count associations by subtype", query)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.get("count");
+                (row.get("subtype"), count as u64)
+            })
+            .collect())
+    }
+
     async fn update_association_count(
         &self,
         id: ObjectId,
@@ -691,37 +2188,321 @@ impl DatabaseInterface for PostgresDatabase {
             .unwrap()
             .as_millis() as i64;
 
-        sqlx::query(
-            "INSERT INTO association_counts (id, atype, count, updated_time) VALUES ($1, $2, $3, $4)
-             ON CONFLICT (id, atype) DO UPDATE SET count = association_counts.count + $3, updated_time = $4"
+        sqlx::query(ASSOCIATION_COUNT_UPSERT)
+            .bind(id)
+            .bind(&atype)
+            .bind(delta)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| pool_error("Failed to update association count", e))?;
+
+        Ok(())
+    }
+
+    async fn get_association_count(&self, id: ObjectId, atype: AssociationType) -> AppResult<u64> {
+        let row = sqlx::query("SELECT count FROM association_counts WHERE id = $1 AND atype = $2")
+            .bind(id)
+            .bind(&atype)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| pool_error("Failed to get association count", e))?;
+
+        if let Some(row) = row {
+            let count: i64 = row.get("count");
+            Ok(count as u64)
+        } else {
+            Ok(0)
+        }
+    }
+
+    async fn get_association_counts_for_object(
+        &self,
+        id: ObjectId,
+    ) -> AppResult<Vec<(AssociationType, u64)>> {
+        let rows = sqlx::query(
+            "SELECT atype, count FROM association_counts WHERE id = $1 AND count > 0 ORDER BY atype",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            pool_error("Failed to get association counts", e)
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.get("count");
+                (row.get("atype"), count as u64)
+            })
+            .collect())
+    }
+
+    async fn get_object_type_count(&self, otype: ObjectType) -> AppResult<u64> {
+        let row = sqlx::query("SELECT count FROM object_type_counts WHERE otype = $1")
+            .bind(&otype)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| pool_error("Failed to get object type count", e))?;
+
+        // Clamp in case deletes of rows created before the counter existed pushed it negative
+        Ok(row.map_or(0, |row| row.get::<i64, _>("count").max(0) as u64))
+    }
+
+    async fn recount_association(&self, id: ObjectId, atype: AssociationType) -> AppResult<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        let row = sqlx::query(
+            "INSERT INTO association_counts (id, atype, count, updated_time)
+             SELECT $1, $2, COUNT(*), $3 FROM associations WHERE id1 = $1 AND atype = $2
+             ON CONFLICT (id, atype) DO UPDATE SET count = excluded.count, updated_time = excluded.updated_time
+             RETURNING count",
         )
         .bind(id)
         .bind(&atype)
-        .bind(delta)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| pool_error("Failed to recount association", e))?;
+
+        let count: i64 = row.get("count");
+        Ok(count as u64)
+    }
+
+    async fn audit_consistency(&self, repair: bool) -> AppResult<Vec<ConsistencyIssue>> {
+        let query = sqlx::query(
+            "SELECT COALESCE(c.id, a.id1) AS id1, COALESCE(c.atype, a.atype) AS atype,
+                    COALESCE(c.count, 0) AS stored, COALESCE(a.actual, 0) AS actual
+             FROM association_counts c
+             FULL OUTER JOIN (
+                 SELECT id1, atype, COUNT(*) AS actual FROM associations GROUP BY id1, atype
+             ) a ON a.id1 = c.id AND a.atype = c.atype
+             WHERE COALESCE(c.count, 0) <> COALESCE(a.actual, 0)
+             ORDER BY 1, 2",
+        );
+        let mismatches = self
+            .fetch_all_with_timeout(StatementClass::Scan, "audit association counts", query)
+            .await?;
+
+        let query = sqlx::query(
+            "SELECT a.id1, a.atype, a.id2 FROM associations a
+             LEFT JOIN objects o ON o.id = a.id1
+             WHERE o.id IS NULL AND a.atype NOT LIKE '%#%'
+             ORDER BY a.id1, a.atype, a.id2",
+        );
+        let orphans = self
+            .fetch_all_with_timeout(StatementClass::Scan, "audit orphaned associations", query)
+            .await?;
+
+        let mut issues = Vec::with_capacity(mismatches.len() + orphans.len());
+        for row in mismatches {
+            let id1: ObjectId = row.get("id1");
+            let atype: AssociationType = row.get("atype");
+            if repair {
+                self.recount_association(id1, atype.clone()).await?;
+            }
+            issues.push(ConsistencyIssue::CountMismatch {
+                id1,
+                atype,
+                stored: row.get("stored"),
+                actual: row.get("actual"),
+                repaired: repair,
+            });
+        }
+        issues.extend(
+            orphans
+                .into_iter()
+                .map(|row| ConsistencyIssue::OrphanedAssociation {
+                    id1: row.get("id1"),
+                    atype: row.get("atype"),
+                    id2: row.get("id2"),
+                }),
+        );
+        Ok(issues)
+    }
+
+    async fn set_object_attribute(
+        &self,
+        id: ObjectId,
+        key: String,
+        value: Vec<u8>,
+    ) -> AppResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        sqlx::query(
+            "INSERT INTO object_attributes (object_id, key, value, updated_time) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (object_id, key) DO UPDATE SET value = $3, updated_time = $4",
+        )
+        .bind(id)
+        .bind(&key)
+        .bind(value)
         .bind(now)
         .execute(&self.pool)
         .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to update association count: {}", e)))?;
+        .map_err(|e| pool_error(format!("Failed to set attribute {}", key), e))?;
+        Ok(())
+    }
+
+    async fn get_object_attribute(&self, id: ObjectId, key: String) -> AppResult<Option<Vec<u8>>> {
+        let row =
+            sqlx::query("SELECT value FROM object_attributes WHERE object_id = $1 AND key = $2")
+                .bind(id)
+                .bind(&key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| pool_error(format!("Failed to get attribute {}", key), e))?;
+        Ok(row.map(|row| row.get("value")))
+    }
+
+    async fn get_object_attributes(&self, id: ObjectId) -> AppResult<HashMap<String, Vec<u8>>> {
+        let rows = sqlx::query("SELECT key, value FROM object_attributes WHERE object_id = $1")
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| pool_error("Failed to get attributes", e))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect())
+    }
+
+    async fn get_unique_key(
+        &self,
+        otype: ObjectType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<ObjectId>> {
+        let row = sqlx::query(
+            "SELECT object_id FROM unique_keys WHERE otype = $1 AND field = $2 AND value = $3",
+        )
+        .bind(&otype)
+        .bind(&field)
+        .bind(&value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| pool_error(format!("Failed to get unique key {}.{}", otype, field), e))?;
+        Ok(row.map(|row| row.get("object_id")))
+    }
+
+    async fn get_idempotency_record(&self, key: &str) -> AppResult<Option<IdempotencyRecord>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        let row = sqlx::query(
+            "SELECT key, status, body, expires_at FROM idempotency_keys WHERE key = $1 AND expires_at > $2",
+        )
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            pool_error(format!("Failed to get idempotency key {}", key), e)
+        })?;
+        Ok(row.map(|row| IdempotencyRecord {
+            key: row.get("key"),
+            status: row.get::<i32, _>("status") as u16,
+            body: row.get("body"),
+            expires_at: row.get("expires_at"),
+        }))
+    }
+
+    async fn put_idempotency_record(&self, record: IdempotencyRecord) -> AppResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        self.run_in_transaction("store idempotency key", |conn| {
+            let record = record.clone();
+            Box::pin(async move {
+                sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= $1")
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+                sqlx::query(
+                    "INSERT INTO idempotency_keys (key, status, body, expires_at) VALUES ($1, $2, $3, $4)
+                     ON CONFLICT (key) DO UPDATE SET status = $2, body = $3, expires_at = $4",
+                )
+                .bind(record.key)
+                .bind(record.status as i32)
+                .bind(record.body)
+                .bind(record.expires_at)
+                .execute(&mut *conn)
+                .await?;
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        expires_at: Timestamp,
+    ) -> AppResult<Option<IdempotencyRecord>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
 
-        Ok(())
+        self.run_in_transaction("claim idempotency key", |conn| {
+            let key = key.to_string();
+            Box::pin(async move {
+                loop {
+                    // Takes the key when it is free or its record has expired
+                    let claimed = sqlx::query(
+                        "INSERT INTO idempotency_keys (key, status, body, expires_at) VALUES ($1, $2, $3, $4)
+                         ON CONFLICT (key) DO UPDATE SET status = $2, body = $3, expires_at = $4
+                         WHERE idempotency_keys.expires_at <= $5",
+                    )
+                    .bind(&key)
+                    .bind(IdempotencyRecord::PENDING_STATUS as i32)
+                    .bind(Vec::<u8>::new())
+                    .bind(expires_at)
+                    .bind(now)
+                    .execute(&mut *conn)
+                    .await?;
+                    if claimed.rows_affected() > 0 {
+                        return Ok(None);
+                    }
+
+                    let row = sqlx::query(
+                        "SELECT key, status, body, expires_at FROM idempotency_keys WHERE key = $1",
+                    )
+                    .bind(&key)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+                    // A missing row was deleted since the insert; try the claim again
+                    if let Some(row) = row {
+                        return Ok(Some(IdempotencyRecord {
+                            key: row.get("key"),
+                            status: row.get::<i32, _>("status") as u16,
+                            body: row.get("body"),
+                            expires_at: row.get("expires_at"),
+                        }));
+                    }
+                }
+            })
+        })
+        .await
     }
 
-    async fn get_association_count(&self, id: ObjectId, atype: AssociationType) -> AppResult<u64> {
-        let row = sqlx::query("SELECT count FROM association_counts WHERE id = $1 AND atype = $2")
-            .bind(id)
-            .bind(&atype)
-            .fetch_optional(&self.pool)
+    async fn delete_idempotency_record(&self, key: &str) -> AppResult<()> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
             .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!("Failed to get association count: {}", e))
-            })?;
-
-        if let Some(row) = row {
-            let count: i64 = row.get("count");
-            Ok(count as u64)
-        } else {
-            Ok(0)
-        }
+            .map_err(|e| pool_error(format!("Failed to delete idempotency key {}", key), e))?;
+        Ok(())
     }
 
     // Transactional operations - Execute within existing transaction
@@ -732,6 +2513,7 @@ impl DatabaseInterface for PostgresDatabase {
         otype: ObjectType,
         data: Vec<u8>,
     ) -> AppResult<()> {
+        tx.check_shard(id)?;
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -750,32 +2532,143 @@ impl DatabaseInterface for PostgresDatabase {
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create object with ID {} in transaction: {}", id, e)))?;
 
+        sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+            .bind(&otype)
+            .bind(1i64)
+            .bind(now)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to update object type count in transaction: {}",
+                    e
+                ))
+            })?;
+
         Ok(())
     }
 
+    async fn claim_unique_key_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        otype: ObjectType,
+        field: String,
+        value: String,
+        id: ObjectId,
+    ) -> AppResult<bool> {
+        tx.check_shard(id)?;
+        let postgres_tx = tx.as_postgres_mut()?;
+
+        let result = sqlx::query(
+            "INSERT INTO unique_keys (otype, field, value, object_id) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (otype, field, value) DO NOTHING",
+        )
+        .bind(&otype)
+        .bind(&field)
+        .bind(&value)
+        .bind(id)
+        .execute(&mut **postgres_tx)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to claim unique key {}.{} in transaction: {}",
+                otype, field, e
+            ))
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_object_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id: ObjectId,
+    ) -> AppResult<bool> {
+        tx.check_shard(id)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let postgres_tx = tx.as_postgres_mut()?;
+
+        let deleted = sqlx::query("DELETE FROM objects WHERE id = $1 RETURNING otype")
+            .bind(id)
+            .fetch_optional(&mut **postgres_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to delete object {} in transaction: {}",
+                    id, e
+                ))
+            })?;
+        let Some(row) = deleted else {
+            return Ok(false);
+        };
+        let otype: String = row.get("otype");
+
+        sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+            .bind(&otype)
+            .bind(-1i64)
+            .bind(now)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to update object type count in transaction: {}",
+                    e
+                ))
+            })?;
+        sqlx::query("DELETE FROM object_attributes WHERE object_id = $1")
+            .bind(id)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to delete object attributes in transaction: {}",
+                    e
+                ))
+            })?;
+        sqlx::query("DELETE FROM unique_keys WHERE object_id = $1")
+            .bind(id)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to delete unique keys in transaction: {}",
+                    e
+                ))
+            })?;
+
+        Ok(true)
+    }
+
     async fn create_association_tx(
         &self,
         tx: &mut DatabaseTransaction,
         assoc: Association,
     ) -> AppResult<()> {
+        tx.check_shard(assoc.id1)?;
         let postgres_tx = tx.as_postgres_mut()?;
 
         // Insert association
-        sqlx::query(
-            "INSERT INTO associations (id1, atype, id2, time_created, data) VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING"
+        let result = sqlx::query(
+            "INSERT INTO associations (id1, atype, id2, time_created, data, subtype) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING"
         )
         .bind(assoc.id1)
         .bind(&assoc.atype)
         .bind(assoc.id2)
         .bind(assoc.time)
         .bind(&assoc.data)
+        .bind(&assoc.subtype)
         .execute(&mut **postgres_tx)
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create association in transaction: {}", e)))?;
 
-        // Update association count
-        self.update_association_count_tx(tx, assoc.id1, assoc.atype, 1)
-            .await?;
+        // Update association count, unless the edge already existed
+        if result.rows_affected() > 0 {
+            self.update_association_count_tx(tx, assoc.id1, assoc.atype, 1)
+                .await?;
+        }
 
         Ok(())
     }
@@ -787,6 +2680,7 @@ impl DatabaseInterface for PostgresDatabase {
         atype: AssociationType,
         id2: ObjectId,
     ) -> AppResult<bool> {
+        tx.check_shard(id1)?;
         let postgres_tx = tx.as_postgres_mut()?;
 
         let result =
@@ -812,43 +2706,156 @@ impl DatabaseInterface for PostgresDatabase {
         }
     }
 
-    async fn update_association_count_tx(
+    async fn delete_associations_of_type_tx(
         &self,
         tx: &mut DatabaseTransaction,
-        id: ObjectId,
+        id1: ObjectId,
         atype: AssociationType,
-        delta: i64,
-    ) -> AppResult<()> {
+    ) -> AppResult<Vec<ObjectId>> {
+        tx.check_shard(id1)?;
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as i64;
         let postgres_tx = tx.as_postgres_mut()?;
+        let map_err = |e: sqlx::Error| {
+            AppError::DatabaseError(format!(
+                "Failed to delete associations in transaction: {}",
+                e
+            ))
+        };
 
+        let rows =
+            sqlx::query("DELETE FROM associations WHERE id1 = $1 AND atype = $2 RETURNING id2")
+                .bind(id1)
+                .bind(&atype)
+                .fetch_all(&mut **postgres_tx)
+                .await
+                .map_err(map_err)?;
         sqlx::query(
-            "INSERT INTO association_counts (id, atype, count, updated_time) VALUES ($1, $2, $3, $4)
-             ON CONFLICT (id, atype) DO UPDATE SET count = association_counts.count + $3, updated_time = $4"
+            "UPDATE association_counts SET count = 0, updated_time = $3 WHERE id = $1 AND atype = $2",
         )
-        .bind(id)
+        .bind(id1)
         .bind(&atype)
-        .bind(delta)
         .bind(now)
         .execute(&mut **postgres_tx)
         .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to update association count in transaction: {}", e)))?;
+        .map_err(map_err)?;
+        Ok(rows.into_iter().map(|row| row.get("id2")).collect())
+    }
+
+    async fn update_association_count_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id: ObjectId,
+        atype: AssociationType,
+        delta: i64,
+    ) -> AppResult<()> {
+        tx.check_shard(id)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let postgres_tx = tx.as_postgres_mut()?;
+
+        sqlx::query(ASSOCIATION_COUNT_UPSERT)
+            .bind(id)
+            .bind(&atype)
+            .bind(delta)
+            .bind(now)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to update association count in transaction: {}",
+                    e
+                ))
+            })?;
 
         Ok(())
     }
 
-    async fn get_all_objects_from_shard(&self) -> AppResult<Vec<Object>> {
-        let rows = sqlx::query(
-            "SELECT id, otype, time_created, time_updated, data, version FROM objects ORDER BY id",
+    async fn record_association_changes_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id1: ObjectId,
+        atype: AssociationType,
+        changes: Vec<(ObjectId, bool)>,
+    ) -> AppResult<u64> {
+        tx.check_shard(id1)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let postgres_tx = tx.as_postgres_mut()?;
+        let map_err = |e: sqlx::Error| {
+            AppError::DatabaseError(format!(
+                "Failed to record association changes of {} ({}): {}",
+                id1, atype, e
+            ))
+        };
+
+        // Holds the sequence row until commit, so sequence numbers commit in order
+        let latest_seq: i64 = sqlx::query_scalar(
+            "INSERT INTO association_change_seqs (id1, atype, seq) VALUES ($1, $2, $3)
+             ON CONFLICT (id1, atype) DO UPDATE SET seq = association_change_seqs.seq + $3
+             RETURNING seq",
         )
-        .fetch_all(&self.pool)
+        .bind(id1)
+        .bind(&atype)
+        .bind(changes.len() as i64)
+        .fetch_one(&mut **postgres_tx)
         .await
-        .map_err(|e| {
-            AppError::DatabaseError(format!("Failed to get all objects from shard: {}", e))
-        })?;
+        .map_err(map_err)?;
+
+        let first_seq = latest_seq - changes.len() as i64 + 1;
+        for (offset, (id2, added)) in changes.into_iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO association_changes (id1, atype, seq, id2, added, changed_time)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(id1)
+            .bind(&atype)
+            .bind(first_seq + offset as i64)
+            .bind(id2)
+            .bind(added)
+            .bind(now)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(map_err)?;
+        }
+
+        sqlx::query("DELETE FROM association_changes WHERE id1 = $1 AND atype = $2 AND seq <= $3")
+            .bind(id1)
+            .bind(&atype)
+            .bind(latest_seq - ASSOC_CHANGE_LOG_RETENTION as i64)
+            .execute(&mut **postgres_tx)
+            .await
+            .map_err(map_err)?;
+
+        Ok(latest_seq as u64)
+    }
+
+    async fn write_high_watermark(&self) -> AppResult<Timestamp> {
+        let row = sqlx::query(
+            "SELECT GREATEST(
+                 (SELECT COALESCE(MAX(time_updated), 0) FROM objects),
+                 (SELECT COALESCE(MAX(updated_time), 0) FROM association_counts)
+             ) AS watermark",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| pool_error("Failed to read write high-watermark", e))?;
+        Ok(row.get("watermark"))
+    }
+
+    async fn get_all_objects_from_shard(&self) -> AppResult<Vec<Object>> {
+        let query = sqlx::query(
+            "SELECT id, otype, time_created, time_updated, data, version FROM objects ORDER BY id",
+        );
+        let rows = self
+            .fetch_all_with_timeout(StatementClass::Scan, "get all objects from shard", query)
+            .await?;
 
         let objects = rows
             .into_iter()
@@ -866,14 +2873,16 @@ impl DatabaseInterface for PostgresDatabase {
     }
 
     async fn get_all_associations_from_shard(&self) -> AppResult<Vec<Association>> {
-        let rows = sqlx::query(
-            "SELECT id1, atype, id2, time_created, data FROM associations ORDER BY id1, atype, id2",
-        )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| {
-            AppError::DatabaseError(format!("Failed to get all associations from shard: {}", e))
-        })?;
+        let query = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM associations ORDER BY id1, atype, id2",
+        );
+        let rows = self
+            .fetch_all_with_timeout(
+                StatementClass::Scan,
+                "get all associations from shard",
+                query,
+            )
+            .await?;
 
         let associations = rows
             .into_iter()
@@ -883,9 +2892,411 @@ impl DatabaseInterface for PostgresDatabase {
                 id2: row.get("id2"),
                 time: row.get("time_created"),
                 data: row.get("data"),
+                subtype: row.get("subtype"),
             })
             .collect();
 
         Ok(associations)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct FakeDbError(&'static str);
+
+    impl fmt::Display for FakeDbError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "sqlstate {}", self.0)
+        }
+    }
+
+    impl std::error::Error for FakeDbError {}
+
+    impl sqlx::error::DatabaseError for FakeDbError {
+        fn message(&self) -> &str {
+            "fake"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    fn db_error(code: &'static str) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(FakeDbError(code)))
+    }
+
+    #[test]
+    fn test_retryable_transaction_error_classification() {
+        assert!(is_retryable_transaction_error(&db_error("40P01")));
+        assert!(is_retryable_transaction_error(&db_error("40001")));
+        // unique_violation is a real failure, not a conflict
+        assert!(!is_retryable_transaction_error(&db_error("23505")));
+        assert!(!is_retryable_transaction_error(&sqlx::Error::RowNotFound));
+    }
+
+    #[test]
+    fn test_pool_exhaustion_is_service_unavailable() {
+        assert!(matches!(
+            pool_error("Failed to get object 1", sqlx::Error::PoolTimedOut),
+            AppError::ServiceUnavailable(_)
+        ));
+        assert!(matches!(
+            pool_error("Failed to get object 1", sqlx::Error::PoolClosed),
+            AppError::ServiceUnavailable(_)
+        ));
+        // Errors reported by the database itself are not about capacity
+        assert!(matches!(
+            pool_error("Failed to get object 1", db_error("23505")),
+            AppError::DatabaseError(_)
+        ));
+        assert!(matches!(
+            pool_error("Failed to get object 1", sqlx::Error::RowNotFound),
+            AppError::DatabaseError(_)
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_statement_timeout_fires_on_slow_read() {
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let metrics = Arc::new(MetricsCollector::new());
+        let database = PostgresDatabase::new(PgPool::connect(&url).await.unwrap())
+            .with_metrics(metrics.clone());
+        database.set_statement_timeouts(StatementTimeouts {
+            read_timeout_ms: 100,
+            scan_timeout_ms: 100,
+        });
+
+        let err = database
+            .execute_query("SELECT pg_sleep(2)".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::TimeoutError(_)), "got {:?}", err);
+        let snapshot = metrics.get_metrics_snapshot().await;
+        assert_eq!(snapshot.database_metrics.timeouts, 1);
+
+        // The timeout is scoped to the failed transaction, not left on the pooled connection
+        database.set_statement_timeouts(StatementTimeouts::default());
+        database
+            .execute_query("SELECT pg_sleep(0.2)".to_string())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_connect_bounds_reads_but_not_writes() {
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let database = PostgresDatabase::connect(
+            PgPoolOptions::new().max_connections(1),
+            url.parse().unwrap(),
+            StatementTimeouts {
+                read_timeout_ms: 100,
+                scan_timeout_ms: 1_000,
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = database
+            .execute_query("SELECT pg_sleep(2)".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::TimeoutError(_)), "got {:?}", err);
+
+        // Scans still get their own, longer limit
+        let scan = sqlx::query("SELECT pg_sleep(0.3)");
+        database
+            .fetch_all_with_timeout(StatementClass::Scan, "scan", scan)
+            .await
+            .unwrap();
+
+        // A write slower than the read timeout still completes on the same connection
+        sqlx::query("DO $$ BEGIN PERFORM pg_sleep(0.3); END $$")
+            .execute(&database.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_bulk_load_inserts_rows_and_counts() {
+        // Recreates the TAO tables
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let database = PostgresDatabase::new(PgPool::connect(&url).await.unwrap());
+        database.initialize().await.unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let ids: Vec<ObjectId> = (1..=10_000).collect();
+        let objects = ids
+            .iter()
+            .map(|&id| Object {
+                id,
+                otype: if id % 2 == 0 { "ent_user" } else { "ent_post" }.to_string(),
+                data: id.to_le_bytes().to_vec(),
+                created_time: now,
+                updated_time: now,
+                version: 1,
+            })
+            .collect();
+        // Every object follows the first one, and the first one follows everyone back
+        let associations = ids[1..]
+            .iter()
+            .flat_map(|&id| {
+                [(id, 1), (1, id)].map(|(id1, id2)| Association {
+                    id1,
+                    atype: "follows".to_string(),
+                    id2,
+                    time: now,
+                    data: None,
+                    subtype: None,
+                })
+            })
+            .collect();
+
+        database.bulk_load(objects, associations).await.unwrap();
+
+        let loaded = database
+            .get_objects(ObjectQuery {
+                ids: ids.clone(),
+                otype: None,
+                created_after: None,
+                created_before: None,
+                limit: None,
+                offset: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(loaded.objects.len(), 10_000);
+        assert_eq!(
+            database
+                .get_object_type_count("ent_user".to_string())
+                .await
+                .unwrap(),
+            5_000
+        );
+        assert_eq!(
+            database
+                .get_object_type_count("ent_post".to_string())
+                .await
+                .unwrap(),
+            5_000
+        );
+        assert_eq!(
+            database
+                .get_association_count(1, "follows".to_string())
+                .await
+                .unwrap(),
+            9_999
+        );
+        assert_eq!(
+            database
+                .get_association_count(42, "follows".to_string())
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            database
+                .count_associations(1, "follows".to_string())
+                .await
+                .unwrap(),
+            9_999
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_imported_associations_are_counted_on_finalize() {
+        // Recreates the TAO tables
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let database = PostgresDatabase::new(PgPool::connect(&url).await.unwrap());
+        database.initialize().await.unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let edge = |id1: ObjectId, atype: &str, id2: ObjectId| Association {
+            id1,
+            atype: atype.to_string(),
+            id2,
+            time: now,
+            data: Some(vec![1]),
+            subtype: None,
+        };
+        // Users 1..=100 follow user 1000, which likes every even one of them
+        let follows: Vec<_> = (1..=100).map(|id| edge(id, "follows", 1000)).collect();
+        let likes: Vec<_> = (2..=100)
+            .step_by(2)
+            .map(|id| edge(1000, "likes", id))
+            .collect();
+
+        assert_eq!(database.import_associations(follows).await.unwrap(), 100);
+        // Re-importing an edge skips it
+        let mut batch = likes;
+        batch.push(edge(1, "follows", 1000));
+        assert_eq!(database.import_associations(batch).await.unwrap(), 50);
+
+        assert_eq!(
+            database
+                .get_association_count(1000, "likes".to_string())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(database.finalize_association_import().await.unwrap(), 101);
+
+        assert_eq!(
+            database
+                .get_association_count(1000, "likes".to_string())
+                .await
+                .unwrap(),
+            50
+        );
+        for id in [1, 57, 100] {
+            assert_eq!(
+                database
+                    .get_association_count(id, "follows".to_string())
+                    .await
+                    .unwrap(),
+                1
+            );
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_concurrent_updates_each_record_their_own_version() {
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let database = Arc::new(PostgresDatabase::new(PgPool::connect(&url).await.unwrap()));
+        database.initialize().await.unwrap();
+
+        let id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as i64;
+        database
+            .create_object(id, "ent_post".to_string(), b"v0".to_vec())
+            .await
+            .unwrap();
+        let updates = (1..=8).map(|n| {
+            let database = database.clone();
+            tokio::spawn(async move {
+                let data = format!("v{}", n).into_bytes();
+                database.update_object_with_history(id, data, None).await
+            })
+        });
+        for update in futures::future::join_all(updates).await {
+            update.unwrap().unwrap();
+        }
+
+        let history = database.get_object_history(id, 100).await.unwrap();
+        let mut versions: Vec<u64> = history.iter().map(|v| v.version).collect();
+        versions.sort();
+        assert_eq!(versions, (1..=8).collect::<Vec<u64>>());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a live Postgres at TAO_TEST_POSTGRES_URL"]
+    async fn test_edge_added_twice_is_read_once_as_its_latest_row() {
+        let url = std::env::var("TAO_TEST_POSTGRES_URL").unwrap();
+        let database = PostgresDatabase::new(PgPool::connect(&url).await.unwrap());
+        database.initialize().await.unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let id1 = now;
+        for (time, data) in [(now - 1000, b"old"), (now, b"new")] {
+            database
+                .create_association(Association {
+                    id1,
+                    atype: "follows".to_string(),
+                    id2: 7,
+                    time,
+                    data: Some(data.to_vec()),
+                    subtype: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let query = AssocQuery {
+            id1,
+            atype: "follows".to_string(),
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: None,
+            offset: None,
+            after: None,
+            include_total: false,
+            dedup: true,
+        };
+        let deduped = database.get_associations(query.clone()).await.unwrap();
+        assert_eq!(deduped.associations.len(), 1);
+        assert_eq!(deduped.associations[0].time, now);
+        assert_eq!(deduped.associations[0].data, Some(b"new".to_vec()));
+
+        // The older row no longer matches a time range that only it falls in
+        let before = database
+            .get_associations(AssocQuery {
+                high_time: Some(now - 500),
+                ..query.clone()
+            })
+            .await
+            .unwrap();
+        assert!(before.associations.is_empty());
+
+        let raw = database
+            .get_associations(AssocQuery {
+                dedup: false,
+                ..query
+            })
+            .await
+            .unwrap();
+        assert_eq!(raw.associations.len(), 2);
+    }
+
+    #[test]
+    fn test_backoff_is_capped_and_jittered() {
+        let config = TransactionRetryConfig {
+            max_attempts: 10,
+            base_delay_ms: 10,
+            max_delay_ms: 100,
+        };
+        let first = config.backoff(1);
+        assert!(first >= Duration::from_millis(5) && first <= Duration::from_millis(10));
+        let late = config.backoff(9);
+        assert!(late >= Duration::from_millis(50) && late <= Duration::from_millis(100));
+    }
+}
@@ -1,2 +1,5 @@
+#[cfg(test)]
+mod conformance;
 pub mod database;
+pub mod dsn;
 pub mod sqlite_database;
@@ -1,12 +1,31 @@
 use async_trait::async_trait;
-use sqlx::{sqlite::Sqlite, sqlite::SqlitePool, Column, QueryBuilder, Row, ValueRef};
+use sqlx::{
+    sqlite::Sqlite, sqlite::SqlitePool, sqlite::SqliteRow, Column, QueryBuilder, Row, ValueRef,
+};
 use std::collections::HashMap;
 
 use crate::error::{AppError, AppResult};
 use crate::infrastructure::database::database::{
-    AssocQuery, AssocQueryResult, Association, AssociationType, DatabaseInterface,
-    DatabaseTransaction, Object, ObjectId, ObjectQuery, ObjectQueryResult, ObjectType,
+    AssocQuery, AssocQueryResult, Association, AssociationChange, AssociationChangeLog,
+    AssociationType, ConsistencyIssue, DatabaseInterface, DatabaseTransaction, IdempotencyRecord,
+    Object, ObjectId, ObjectQuery, ObjectQueryResult, ObjectType, ObjectVersion, Timestamp,
+    ASSOC_CHANGE_LOG_RETENTION,
 };
+use crate::infrastructure::tao_core::cursor::Cursor;
+
+const OBJECT_TYPE_COUNT_UPSERT: &str =
+    "INSERT INTO tao_object_type_counts (otype, count, updated_time) VALUES (?, ?, ?)
+             ON CONFLICT (otype) DO UPDATE SET count = count + excluded.count, updated_time = excluded.updated_time";
+
+fn object_version_from_row(row: &SqliteRow) -> ObjectVersion {
+    ObjectVersion {
+        id: row.get("id"),
+        version: row.get::<i64, _>("version") as u64,
+        data: row.get("data"),
+        updated_time: row.get("updated_time"),
+        changed_by: row.get("changed_by"),
+    }
+}
 
 /// SQLite implementation of database interface for in-memory testing
 pub struct SqliteDatabase {
@@ -24,6 +43,11 @@ impl SqliteDatabase {
         Ok(db)
     }
 
+    /// Close the connection pool; every later query fails
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
     /// Initialize TAO database tables for SQLite
     pub async fn initialize(&self) -> AppResult<()> {
         sqlx::query("DROP TABLE IF EXISTS tao_objects")
@@ -38,6 +62,34 @@ impl SqliteDatabase {
             .execute(&self.pool)
             .await
             .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_object_type_counts")
+            .execute(&self.pool)
+            .await
+            .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_object_attributes")
+            .execute(&self.pool)
+            .await
+            .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_idempotency_keys")
+            .execute(&self.pool)
+            .await
+            .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_unique_keys")
+            .execute(&self.pool)
+            .await
+            .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_object_history")
+            .execute(&self.pool)
+            .await
+            .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_association_changes")
+            .execute(&self.pool)
+            .await
+            .ok();
+        sqlx::query("DROP TABLE IF EXISTS tao_association_change_seqs")
+            .execute(&self.pool)
+            .await
+            .ok();
 
         sqlx::query(
             r#"
@@ -63,6 +115,7 @@ impl SqliteDatabase {
                 id2 INTEGER NOT NULL,
                 time_created INTEGER NOT NULL,
                 data BLOB,
+                subtype TEXT,
                 PRIMARY KEY (id1, atype, id2)
             )
             "#,
@@ -90,6 +143,127 @@ impl SqliteDatabase {
             AppError::DatabaseError(format!("Failed to create association counts table: {}", e))
         })?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_object_type_counts (
+                otype TEXT PRIMARY KEY,
+                count INTEGER NOT NULL DEFAULT 0,
+                updated_time INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create object type counts table: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_object_attributes (
+                object_id INTEGER NOT NULL,
+                key TEXT NOT NULL,
+                value BLOB NOT NULL,
+                updated_time INTEGER NOT NULL,
+                PRIMARY KEY (object_id, key)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create object attributes table: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_idempotency_keys (
+                key TEXT PRIMARY KEY,
+                status INTEGER NOT NULL,
+                body BLOB NOT NULL,
+                expires_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create idempotency keys table: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_unique_keys (
+                otype TEXT NOT NULL,
+                field TEXT NOT NULL,
+                value TEXT NOT NULL,
+                object_id INTEGER NOT NULL,
+                PRIMARY KEY (otype, field, value)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create unique keys table: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_object_history (
+                id INTEGER NOT NULL,
+                version INTEGER NOT NULL,
+                data BLOB,
+                updated_time INTEGER NOT NULL,
+                changed_by INTEGER,
+                PRIMARY KEY (id, version)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create object history table: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_association_change_seqs (
+                id1 INTEGER NOT NULL,
+                atype TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                PRIMARY KEY (id1, atype)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to create association change sequences table: {}",
+                e
+            ))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE tao_association_changes (
+                id1 INTEGER NOT NULL,
+                atype TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                id2 INTEGER NOT NULL,
+                added BOOLEAN NOT NULL,
+                changed_time INTEGER NOT NULL,
+                PRIMARY KEY (id1, atype, seq)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to create association changes table: {}", e))
+        })?;
+
         sqlx::query("CREATE INDEX idx_tao_objects_otype ON tao_objects(otype)")
             .execute(&self.pool)
             .await
@@ -102,6 +276,32 @@ impl SqliteDatabase {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to create associations index: {}", e)))?;
 
+        sqlx::query("CREATE INDEX idx_tao_assoc_id1_id2 ON tao_associations(id1, id2)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to create associations pair index: {}", e))
+            })?;
+
+        sqlx::query(
+            "CREATE INDEX idx_tao_assoc_id1_atype_subtype ON tao_associations(id1, atype, subtype)",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to create associations subtype index: {}",
+                e
+            ))
+        })?;
+
+        sqlx::query("CREATE INDEX idx_tao_unique_keys_object_id ON tao_unique_keys(object_id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to create unique keys index: {}", e))
+            })?;
+
         Ok(())
     }
 }
@@ -112,6 +312,18 @@ impl DatabaseInterface for SqliteDatabase {
         self
     }
 
+    fn pool_stats(&self) -> (u32, u32) {
+        (self.pool.num_idle() as u32, self.pool.size())
+    }
+
+    async fn health_check(&self) -> AppResult<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Database health check failed: {}", e)))?;
+        Ok(())
+    }
+
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
         let tx =
             self.pool.begin().await.map_err(|e| {
@@ -145,20 +357,47 @@ impl DatabaseInterface for SqliteDatabase {
 
     async fn get_objects(&self, query: ObjectQuery) -> AppResult<ObjectQueryResult> {
         let mut qb = QueryBuilder::<Sqlite>::new(
-            "SELECT id, otype, time_created, time_updated, data, version FROM tao_objects WHERE id IN ("
+            "SELECT id, otype, time_created, time_updated, data, version FROM tao_objects WHERE 1 = 1"
         );
-        let mut separated = qb.separated(",");
-        for id in query.ids {
-            separated.push_bind(id);
+        if !query.ids.is_empty() {
+            qb.push(" AND id IN (");
+            let mut separated = qb.separated(",");
+            for id in query.ids {
+                separated.push_bind(id);
+            }
+            qb.push(")");
         }
-        qb.push(")");
 
         if query.otype.is_some() {
             qb.push(" AND otype = ");
             qb.push_bind(query.otype);
         }
+        if let Some(created_after) = query.created_after {
+            qb.push(" AND time_created >= ");
+            qb.push_bind(created_after);
+        }
+        if let Some(created_before) = query.created_before {
+            qb.push(" AND time_created <= ");
+            qb.push_bind(created_before);
+        }
 
-        qb.push(" ORDER BY id");
+        if query.created_after.is_some() || query.created_before.is_some() {
+            qb.push(" ORDER BY time_created, id");
+        } else {
+            qb.push(" ORDER BY id");
+        }
+        if let Some(limit) = query.limit {
+            qb.push(" LIMIT ");
+            qb.push_bind(limit as i64);
+        }
+        if let Some(offset) = query.offset {
+            // SQLite only accepts OFFSET after a LIMIT
+            if query.limit.is_none() {
+                qb.push(" LIMIT -1");
+            }
+            qb.push(" OFFSET ");
+            qb.push_bind(offset as i64);
+        }
 
         let rows = qb
             .build()
@@ -186,17 +425,33 @@ impl DatabaseInterface for SqliteDatabase {
 
     async fn create_object(&self, id: ObjectId, otype: ObjectType, data: Vec<u8>) -> AppResult<()> {
         let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
         sqlx::query(
             "INSERT INTO tao_objects (id, otype, time_created, time_updated, data) VALUES (?, ?, ?, ?, ?)",
         )
         .bind(id)
-        .bind(otype)
+        .bind(&otype)
         .bind(now)
         .bind(now)
         .bind(data)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create object with ID {}: {}", id, e)))?;
+        sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+            .bind(&otype)
+            .bind(1i64)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to update object type count: {}", e))
+            })?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
         Ok(())
     }
 
@@ -219,14 +474,124 @@ impl DatabaseInterface for SqliteDatabase {
     }
 
     async fn delete_object(&self, id: ObjectId) -> AppResult<bool> {
-        let result = sqlx::query("DELETE FROM tao_objects WHERE id = ?")
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+        let deleted = sqlx::query("DELETE FROM tao_objects WHERE id = ? RETURNING otype")
             .bind(id)
-            .execute(&self.pool)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to delete object {}: {}", id, e))
             })?;
-        Ok(result.rows_affected() > 0)
+        let Some(row) = deleted else {
+            return Ok(false);
+        };
+        sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+            .bind(row.get::<String, _>("otype"))
+            .bind(-1i64)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to update object type count: {}", e))
+            })?;
+        sqlx::query("DELETE FROM tao_object_attributes WHERE object_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to delete object attributes: {}", e))
+            })?;
+        sqlx::query("DELETE FROM tao_unique_keys WHERE object_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to delete unique keys: {}", e)))?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+        Ok(true)
+    }
+
+    async fn update_object_with_history(
+        &self,
+        id: ObjectId,
+        data: Vec<u8>,
+        changed_by: Option<ObjectId>,
+    ) -> AppResult<()> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+        sqlx::query(
+            "INSERT INTO tao_object_history (id, version, data, updated_time, changed_by)
+             SELECT id, version, data, time_updated, ? FROM tao_objects WHERE id = ?",
+        )
+        .bind(changed_by)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to record history of object {}: {}", id, e))
+        })?;
+        let result = sqlx::query(
+            "UPDATE tao_objects SET data = ?, time_updated = ?, version = version + 1 WHERE id = ?",
+        )
+        .bind(data)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update object {}: {}", id, e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Object {} not found", id)));
+        }
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+        Ok(())
+    }
+
+    async fn get_object_history(&self, id: ObjectId, limit: u32) -> AppResult<Vec<ObjectVersion>> {
+        let rows = sqlx::query(
+            "SELECT id, version, data, updated_time, changed_by FROM tao_object_history
+             WHERE id = ? ORDER BY version DESC LIMIT ?",
+        )
+        .bind(id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to get history of object {}: {}", id, e))
+        })?;
+        Ok(rows.iter().map(object_version_from_row).collect())
+    }
+
+    async fn get_object_version(
+        &self,
+        id: ObjectId,
+        version: u64,
+    ) -> AppResult<Option<ObjectVersion>> {
+        let row = sqlx::query(
+            "SELECT id, version, data, updated_time, changed_by FROM tao_object_history
+             WHERE id = ? AND version = ?",
+        )
+        .bind(id)
+        .bind(version as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to get version {} of object {}: {}",
+                version, id, e
+            ))
+        })?;
+        Ok(row.as_ref().map(object_version_from_row))
     }
 
     async fn object_exists(&self, id: ObjectId) -> AppResult<bool> {
@@ -242,7 +607,7 @@ impl DatabaseInterface for SqliteDatabase {
 
     async fn get_associations(&self, query: AssocQuery) -> AppResult<AssocQueryResult> {
         let mut qb = QueryBuilder::<Sqlite>::new(
-            "SELECT id1, atype, id2, time_created, data FROM tao_associations WHERE id1 = ",
+            "SELECT id1, atype, id2, time_created, data, subtype FROM tao_associations WHERE id1 = ",
         );
         qb.push_bind(query.id1);
         qb.push(" AND atype = ");
@@ -256,6 +621,14 @@ impl DatabaseInterface for SqliteDatabase {
             }
             qb.push(")");
         }
+        if let Some(low_id2) = query.low_id2 {
+            qb.push(" AND id2 >= ");
+            qb.push_bind(low_id2);
+        }
+        if let Some(high_id2) = query.high_id2 {
+            qb.push(" AND id2 <= ");
+            qb.push_bind(high_id2);
+        }
         if let Some(low_time) = query.low_time {
             qb.push(" AND time_created >= ");
             qb.push_bind(low_time);
@@ -264,8 +637,16 @@ impl DatabaseInterface for SqliteDatabase {
             qb.push(" AND time_created <= ");
             qb.push_bind(high_time);
         }
+        if let Some((time, id2)) = query.after {
+            qb.push(" AND (time_created, id2) < (");
+            qb.push_bind(time);
+            qb.push(", ");
+            qb.push_bind(id2);
+            qb.push(")");
+        }
 
-        qb.push(" ORDER BY time_created DESC");
+        // id2 breaks ties between edges created in the same millisecond
+        qb.push(" ORDER BY time_created DESC, id2 DESC");
 
         if let Some(limit) = query.limit {
             qb.push(" LIMIT ");
@@ -281,7 +662,7 @@ impl DatabaseInterface for SqliteDatabase {
                 AppError::DatabaseError(format!("Failed to get associations: {}", e))
             })?;
 
-        let associations = rows
+        let associations: Vec<Association> = rows
             .into_iter()
             .map(|row| Association {
                 id1: row.get("id1"),
@@ -289,30 +670,49 @@ impl DatabaseInterface for SqliteDatabase {
                 id2: row.get("id2"),
                 time: row.get("time_created"),
                 data: row.get("data"),
+                subtype: row.get("subtype"),
             })
             .collect();
+        let next_cursor = Cursor::next_page(
+            query.offset,
+            query.limit,
+            associations.len(),
+            associations.last().map(|assoc| assoc.id2),
+            associations.last().map(|assoc| assoc.time),
+        );
+
+        let total_count = if query.include_total {
+            Some(self.get_association_count(query.id1, query.atype).await?)
+        } else {
+            None
+        };
 
         Ok(AssocQueryResult {
             associations,
-            next_cursor: None,
+            next_cursor,
+            total_count,
         })
     }
 
     async fn create_association(&self, assoc: Association) -> AppResult<()> {
-        sqlx::query(
-            "INSERT OR IGNORE INTO tao_associations (id1, atype, id2, time_created, data) VALUES (?, ?, ?, ?, ?)",
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO tao_associations (id1, atype, id2, time_created, data, subtype) VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(assoc.id1)
         .bind(assoc.atype.clone())
         .bind(assoc.id2)
         .bind(assoc.time)
         .bind(assoc.data)
+        .bind(assoc.subtype)
         .execute(&self.pool)
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create association: {}", e)))?;
 
-        self.update_association_count(assoc.id1, assoc.atype, 1)
-            .await?;
+        // The edge already existed, so the count is unchanged
+        if result.rows_affected() > 0 {
+            self.update_association_count(assoc.id1, assoc.atype, 1)
+                .await?;
+        }
         Ok(())
     }
 
@@ -341,29 +741,357 @@ impl DatabaseInterface for SqliteDatabase {
         }
     }
 
-    async fn association_exists(
+    async fn delete_associations_of_type(
         &self,
         id1: ObjectId,
         atype: AssociationType,
-        id2: ObjectId,
-    ) -> AppResult<bool> {
-        let row =
-            sqlx::query("SELECT 1 FROM tao_associations WHERE id1 = ? AND atype = ? AND id2 = ?")
+    ) -> AppResult<Vec<ObjectId>> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+        let rows =
+            sqlx::query("DELETE FROM tao_associations WHERE id1 = ? AND atype = ? RETURNING id2")
                 .bind(id1)
-                .bind(atype)
-                .bind(id2)
-                .fetch_optional(&self.pool)
+                .bind(&atype)
+                .fetch_all(&mut *tx)
                 .await
                 .map_err(|e| {
-                    AppError::DatabaseError(format!("Failed to check association existence: {}", e))
+                    AppError::DatabaseError(format!("Failed to delete associations: {}", e))
                 })?;
-        Ok(row.is_some())
-    }
-
-    async fn count_associations(&self, id1: ObjectId, atype: AssociationType) -> AppResult<u64> {
+        sqlx::query(
+            "UPDATE tao_association_counts SET count = 0, updated_time = ? WHERE id = ? AND atype = ?",
+        )
+        .bind(now)
+        .bind(id1)
+        .bind(&atype)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to reset association count: {}", e)))?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+        Ok(rows.into_iter().map(|row| row.get("id2")).collect())
+    }
+
+    async fn touch_association(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+        time: Timestamp,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            "UPDATE tao_associations SET time_created = ? WHERE id1 = ? AND atype = ? AND id2 = ?",
+        )
+        .bind(time)
+        .bind(id1)
+        .bind(atype)
+        .bind(id2)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to touch association: {}", e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn association_exists(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+    ) -> AppResult<bool> {
+        let row =
+            sqlx::query("SELECT 1 FROM tao_associations WHERE id1 = ? AND atype = ? AND id2 = ?")
+                .bind(id1)
+                .bind(atype)
+                .bind(id2)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to check association existence: {}", e))
+                })?;
+        Ok(row.is_some())
+    }
+
+    async fn get_association(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        id2: ObjectId,
+    ) -> AppResult<Option<Association>> {
+        let row = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM tao_associations WHERE id1 = ? AND atype = ? AND id2 = ? LIMIT 1",
+        )
+        .bind(id1)
+        .bind(atype)
+        .bind(id2)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to get association: {}", e)))?;
+        Ok(row.map(|row| Association {
+            id1: row.get("id1"),
+            atype: row.get("atype"),
+            id2: row.get("id2"),
+            time: row.get("time_created"),
+            data: row.get("data"),
+            subtype: row.get("subtype"),
+        }))
+    }
+
+    async fn count_associations(&self, id1: ObjectId, atype: AssociationType) -> AppResult<u64> {
         self.get_association_count(id1, atype).await
     }
 
+    async fn count_associations_many(
+        &self,
+        pairs: Vec<(ObjectId, AssociationType)>,
+    ) -> AppResult<HashMap<(ObjectId, AssociationType), u64>> {
+        if pairs.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT id, atype, count FROM tao_association_counts WHERE (id, atype) IN (VALUES ",
+        );
+        let mut separated = qb.separated(", ");
+        for (id, atype) in pairs {
+            separated.push("(");
+            separated.push_bind_unseparated(id);
+            separated.push_unseparated(", ");
+            separated.push_bind_unseparated(atype);
+            separated.push_unseparated(")");
+        }
+        qb.push(")");
+
+        let rows =
+            qb.build().fetch_all(&self.pool).await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to count associations: {}", e))
+            })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.get("count");
+                ((row.get("id"), row.get("atype")), count as u64)
+            })
+            .collect())
+    }
+
+    async fn count_associations_by_subtype(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<HashMap<Option<String>, u64>> {
+        let rows = sqlx::query(
+            "SELECT subtype, COUNT(*) AS count FROM tao_associations WHERE id1 = ? AND atype = ? GROUP BY subtype",
+        )
+        .bind(id1)
+        .bind(atype)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to count associations by subtype: {}", e))
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.get("count");
+                (row.get("subtype"), count as u64)
+            })
+            .collect())
+    }
+
+    async fn get_associations_multi_type(
+        &self,
+        id1: ObjectId,
+        atypes: Vec<AssociationType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<Vec<Association>> {
+        if atypes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Rank edges within each type so the per-type limit applies in the database
+        let placeholders = vec!["?"; atypes.len()].join(", ");
+        let sql = format!(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM (
+                SELECT id1, atype, id2, time_created, data, subtype,
+                       ROW_NUMBER() OVER (PARTITION BY atype ORDER BY time_created DESC) AS rank
+                FROM tao_associations WHERE id1 = ? AND atype IN ({})
+             ) ranked
+             WHERE ? IS NULL OR rank <= ?
+             ORDER BY atype, time_created DESC",
+            placeholders
+        );
+
+        let mut query = sqlx::query(&sql).bind(id1);
+        for atype in &atypes {
+            query = query.bind(atype);
+        }
+        let limit = limit_per_type.map(|limit| limit as i64);
+        let rows = query
+            .bind(limit)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to get associations by type: {}", e))
+            })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_associations_since(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        since_time: Timestamp,
+        limit: u32,
+    ) -> AppResult<Vec<Association>> {
+        let rows = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM tao_associations WHERE id1 = ? AND atype = ? AND time_created > ? ORDER BY time_created ASC LIMIT ?",
+        )
+        .bind(id1)
+        .bind(atype)
+        .bind(since_time)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to get associations since {}: {}", since_time, e))
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_associations_from_object(&self, id1: ObjectId) -> AppResult<Vec<Association>> {
+        let rows = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM tao_associations WHERE id1 = ? ORDER BY atype, time_created DESC",
+        )
+        .bind(id1)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to get associations from object {}: {}", id1, e))
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_associations_between(
+        &self,
+        id1: ObjectId,
+        id2: ObjectId,
+    ) -> AppResult<Vec<Association>> {
+        let rows = sqlx::query(
+            "SELECT id1, atype, id2, time_created, data, subtype FROM tao_associations WHERE id1 = ? AND id2 = ? ORDER BY time_created DESC, atype",
+        )
+        .bind(id1)
+        .bind(id2)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to get associations between {} and {}: {}",
+                id1, id2, e
+            ))
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Association {
+                id1: row.get("id1"),
+                atype: row.get("atype"),
+                id2: row.get("id2"),
+                time: row.get("time_created"),
+                data: row.get("data"),
+                subtype: row.get("subtype"),
+            })
+            .collect())
+    }
+
+    async fn get_association_changes(
+        &self,
+        id1: ObjectId,
+        atype: AssociationType,
+        since_seq: u64,
+    ) -> AppResult<AssociationChangeLog> {
+        let map_err = |e: sqlx::Error| {
+            AppError::DatabaseError(format!(
+                "Failed to get association changes of {} ({}): {}",
+                id1, atype, e
+            ))
+        };
+        // Bounds first, so changes committed after this read can't be returned as well
+        let (oldest_seq, latest_seq): (i64, i64) = sqlx::query_as(
+            "SELECT COALESCE(MIN(seq), 0), COALESCE(MAX(seq), 0) FROM tao_association_changes
+             WHERE id1 = ? AND atype = ?",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_err)?;
+
+        let rows = sqlx::query(
+            "SELECT seq, id2, added, changed_time FROM tao_association_changes
+             WHERE id1 = ? AND atype = ? AND seq > ? AND seq <= ? ORDER BY seq",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .bind(since_seq as i64)
+        .bind(latest_seq)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(AssociationChangeLog {
+            changes: rows
+                .into_iter()
+                .map(|row| AssociationChange {
+                    seq: row.get::<i64, _>("seq") as u64,
+                    id2: row.get("id2"),
+                    added: row.get("added"),
+                    changed_time: row.get("changed_time"),
+                })
+                .collect(),
+            latest_seq: latest_seq as u64,
+            oldest_seq: oldest_seq as u64,
+        })
+    }
+
     async fn update_association_count(
         &self,
         id: ObjectId,
@@ -399,6 +1127,292 @@ impl DatabaseInterface for SqliteDatabase {
         Ok(row.map_or(0, |r| r.get::<i64, _>("count") as u64)) // Cast to u64
     }
 
+    async fn get_association_counts_for_object(
+        &self,
+        id: ObjectId,
+    ) -> AppResult<Vec<(AssociationType, u64)>> {
+        let rows = sqlx::query(
+            "SELECT atype, count FROM tao_association_counts WHERE id = ? AND count > 0 ORDER BY atype",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to get association counts: {}", e))
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.get("count");
+                (row.get("atype"), count as u64)
+            })
+            .collect())
+    }
+
+    async fn get_object_type_count(&self, otype: ObjectType) -> AppResult<u64> {
+        let row = sqlx::query("SELECT count FROM tao_object_type_counts WHERE otype = ?")
+            .bind(otype)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to get object type count: {}", e))
+            })?;
+        Ok(row.map_or(0, |r| r.get::<i64, _>("count").max(0) as u64))
+    }
+
+    async fn recount_association(&self, id: ObjectId, atype: AssociationType) -> AppResult<u64> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let count: i64 = sqlx::query_scalar(
+            "INSERT INTO tao_association_counts (id, atype, count, updated_time)
+             SELECT ?1, ?2, COUNT(*), ?3 FROM tao_associations WHERE id1 = ?1 AND atype = ?2
+             ON CONFLICT (id, atype) DO UPDATE SET count = excluded.count, updated_time = excluded.updated_time
+             RETURNING count",
+        )
+        .bind(id)
+        .bind(&atype)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to recount association: {}", e)))?;
+        Ok(count as u64)
+    }
+
+    async fn audit_consistency(&self, repair: bool) -> AppResult<Vec<ConsistencyIssue>> {
+        // SQLite has no FULL OUTER JOIN before 3.39, so stored counts without edges are a
+        // second arm of the union.
+        let mismatches = sqlx::query(
+            "SELECT a.id1 AS id1, a.atype AS atype, COALESCE(c.count, 0) AS stored, a.actual AS actual
+             FROM (SELECT id1, atype, COUNT(*) AS actual FROM tao_associations GROUP BY id1, atype) a
+             LEFT JOIN tao_association_counts c ON c.id = a.id1 AND c.atype = a.atype
+             WHERE COALESCE(c.count, 0) <> a.actual
+             UNION ALL
+             SELECT c.id, c.atype, c.count, 0 FROM tao_association_counts c
+             WHERE c.count <> 0 AND NOT EXISTS (
+                 SELECT 1 FROM tao_associations a WHERE a.id1 = c.id AND a.atype = c.atype
+             )
+             ORDER BY 1, 2",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to audit association counts: {}", e)))?;
+
+        let orphans = sqlx::query(
+            "SELECT a.id1, a.atype, a.id2 FROM tao_associations a
+             LEFT JOIN tao_objects o ON o.id = a.id1
+             WHERE o.id IS NULL AND a.atype NOT LIKE '%#%'
+             ORDER BY a.id1, a.atype, a.id2",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to audit orphaned associations: {}", e))
+        })?;
+
+        let mut issues = Vec::with_capacity(mismatches.len() + orphans.len());
+        for row in mismatches {
+            let id1: ObjectId = row.get("id1");
+            let atype: AssociationType = row.get("atype");
+            if repair {
+                self.recount_association(id1, atype.clone()).await?;
+            }
+            issues.push(ConsistencyIssue::CountMismatch {
+                id1,
+                atype,
+                stored: row.get("stored"),
+                actual: row.get("actual"),
+                repaired: repair,
+            });
+        }
+        issues.extend(
+            orphans
+                .into_iter()
+                .map(|row| ConsistencyIssue::OrphanedAssociation {
+                    id1: row.get("id1"),
+                    atype: row.get("atype"),
+                    id2: row.get("id2"),
+                }),
+        );
+        Ok(issues)
+    }
+
+    async fn set_object_attribute(
+        &self,
+        id: ObjectId,
+        key: String,
+        value: Vec<u8>,
+    ) -> AppResult<()> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        sqlx::query(
+            "INSERT INTO tao_object_attributes (object_id, key, value, updated_time) VALUES (?, ?, ?, ?)
+             ON CONFLICT (object_id, key) DO UPDATE SET value = excluded.value, updated_time = excluded.updated_time",
+        )
+        .bind(id)
+        .bind(&key)
+        .bind(value)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to set attribute {}: {}", key, e)))?;
+        Ok(())
+    }
+
+    async fn get_object_attribute(&self, id: ObjectId, key: String) -> AppResult<Option<Vec<u8>>> {
+        let row =
+            sqlx::query("SELECT value FROM tao_object_attributes WHERE object_id = ? AND key = ?")
+                .bind(id)
+                .bind(&key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to get attribute {}: {}", key, e))
+                })?;
+        Ok(row.map(|row| row.get("value")))
+    }
+
+    async fn get_object_attributes(&self, id: ObjectId) -> AppResult<HashMap<String, Vec<u8>>> {
+        let rows = sqlx::query("SELECT key, value FROM tao_object_attributes WHERE object_id = ?")
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to get attributes: {}", e)))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect())
+    }
+
+    async fn get_unique_key(
+        &self,
+        otype: ObjectType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<ObjectId>> {
+        let row = sqlx::query(
+            "SELECT object_id FROM tao_unique_keys WHERE otype = ? AND field = ? AND value = ?",
+        )
+        .bind(&otype)
+        .bind(&field)
+        .bind(&value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to get unique key {}.{}: {}",
+                otype, field, e
+            ))
+        })?;
+        Ok(row.map(|row| row.get("object_id")))
+    }
+
+    async fn get_idempotency_record(&self, key: &str) -> AppResult<Option<IdempotencyRecord>> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let row = sqlx::query(
+            "SELECT key, status, body, expires_at FROM tao_idempotency_keys WHERE key = ? AND expires_at > ?",
+        )
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to get idempotency key {}: {}", key, e))
+        })?;
+        Ok(row.map(|row| IdempotencyRecord {
+            key: row.get("key"),
+            status: row.get::<i64, _>("status") as u16,
+            body: row.get("body"),
+            expires_at: row.get("expires_at"),
+        }))
+    }
+
+    async fn put_idempotency_record(&self, record: IdempotencyRecord) -> AppResult<()> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let store_error = |e: sqlx::Error| {
+            AppError::DatabaseError(format!(
+                "Failed to store idempotency key {}: {}",
+                record.key, e
+            ))
+        };
+
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+        sqlx::query("DELETE FROM tao_idempotency_keys WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        sqlx::query(
+            "INSERT INTO tao_idempotency_keys (key, status, body, expires_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT (key) DO UPDATE SET status = excluded.status, body = excluded.body, expires_at = excluded.expires_at",
+        )
+        .bind(&record.key)
+        .bind(record.status as i64)
+        .bind(&record.body)
+        .bind(record.expires_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(store_error)?;
+        tx.commit().await.map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        expires_at: Timestamp,
+    ) -> AppResult<Option<IdempotencyRecord>> {
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let claim_error = |e: sqlx::Error| {
+            AppError::DatabaseError(format!("Failed to claim idempotency key {}: {}", key, e))
+        };
+
+        let mut tx = self.pool.begin().await.map_err(claim_error)?;
+        // Takes the key when it is free or its record has expired
+        let claimed = sqlx::query(
+            "INSERT INTO tao_idempotency_keys (key, status, body, expires_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT (key) DO UPDATE SET status = excluded.status, body = excluded.body, expires_at = excluded.expires_at
+             WHERE tao_idempotency_keys.expires_at <= ?",
+        )
+        .bind(key)
+        .bind(IdempotencyRecord::PENDING_STATUS as i64)
+        .bind(Vec::<u8>::new())
+        .bind(expires_at)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(claim_error)?;
+
+        let holder = if claimed.rows_affected() > 0 {
+            None
+        } else {
+            let row = sqlx::query(
+                "SELECT key, status, body, expires_at FROM tao_idempotency_keys WHERE key = ?",
+            )
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(claim_error)?;
+            Some(IdempotencyRecord {
+                key: row.get("key"),
+                status: row.get::<i64, _>("status") as u16,
+                body: row.get("body"),
+                expires_at: row.get("expires_at"),
+            })
+        };
+        tx.commit().await.map_err(claim_error)?;
+        Ok(holder)
+    }
+
+    async fn delete_idempotency_record(&self, key: &str) -> AppResult<()> {
+        sqlx::query("DELETE FROM tao_idempotency_keys WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to delete idempotency key {}: {}", key, e))
+            })?;
+        Ok(())
+    }
+
     async fn create_object_tx(
         &self,
         tx: &mut DatabaseTransaction,
@@ -406,6 +1420,7 @@ impl DatabaseInterface for SqliteDatabase {
         otype: ObjectType,
         data: Vec<u8>,
     ) -> AppResult<()> {
+        tx.check_shard(id)?;
         let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
         let sqlite_tx = tx.as_sqlite_mut()?;
 
@@ -413,37 +1428,142 @@ impl DatabaseInterface for SqliteDatabase {
             "INSERT INTO tao_objects (id, otype, time_created, time_updated, data) VALUES (?, ?, ?, ?, ?)",
         )
         .bind(id)
-        .bind(otype)
+        .bind(&otype)
         .bind(now)
         .bind(now)
         .bind(data)
         .execute(&mut **sqlite_tx)
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create object with ID {} in transaction: {}", id, e)))?;
+        sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+            .bind(&otype)
+            .bind(1i64)
+            .bind(now)
+            .execute(&mut **sqlite_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to update object type count in transaction: {}",
+                    e
+                ))
+            })?;
         Ok(())
     }
 
+    async fn claim_unique_key_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        otype: ObjectType,
+        field: String,
+        value: String,
+        id: ObjectId,
+    ) -> AppResult<bool> {
+        tx.check_shard(id)?;
+        let sqlite_tx = tx.as_sqlite_mut()?;
+
+        let result = sqlx::query(
+            "INSERT INTO tao_unique_keys (otype, field, value, object_id) VALUES (?, ?, ?, ?)
+             ON CONFLICT (otype, field, value) DO NOTHING",
+        )
+        .bind(&otype)
+        .bind(&field)
+        .bind(&value)
+        .bind(id)
+        .execute(&mut **sqlite_tx)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to claim unique key {}.{} in transaction: {}",
+                otype, field, e
+            ))
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_object_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id: ObjectId,
+    ) -> AppResult<bool> {
+        tx.check_shard(id)?;
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let sqlite_tx = tx.as_sqlite_mut()?;
+
+        let deleted = sqlx::query("DELETE FROM tao_objects WHERE id = ? RETURNING otype")
+            .bind(id)
+            .fetch_optional(&mut **sqlite_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to delete object {} in transaction: {}",
+                    id, e
+                ))
+            })?;
+        let Some(row) = deleted else {
+            return Ok(false);
+        };
+        sqlx::query(OBJECT_TYPE_COUNT_UPSERT)
+            .bind(row.get::<String, _>("otype"))
+            .bind(-1i64)
+            .bind(now)
+            .execute(&mut **sqlite_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to update object type count in transaction: {}",
+                    e
+                ))
+            })?;
+        sqlx::query("DELETE FROM tao_object_attributes WHERE object_id = ?")
+            .bind(id)
+            .execute(&mut **sqlite_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to delete object attributes in transaction: {}",
+                    e
+                ))
+            })?;
+        sqlx::query("DELETE FROM tao_unique_keys WHERE object_id = ?")
+            .bind(id)
+            .execute(&mut **sqlite_tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to delete unique keys in transaction: {}",
+                    e
+                ))
+            })?;
+        Ok(true)
+    }
+
     async fn create_association_tx(
         &self,
         tx: &mut DatabaseTransaction,
         assoc: Association,
     ) -> AppResult<()> {
+        tx.check_shard(assoc.id1)?;
         let sqlite_tx = tx.as_sqlite_mut()?;
 
-        sqlx::query(
-            "INSERT OR IGNORE INTO tao_associations (id1, atype, id2, time_created, data) VALUES (?, ?, ?, ?, ?)",
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO tao_associations (id1, atype, id2, time_created, data, subtype) VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(assoc.id1)
         .bind(assoc.atype.clone())
         .bind(assoc.id2)
         .bind(assoc.time)
         .bind(assoc.data)
+        .bind(assoc.subtype)
         .execute(&mut **sqlite_tx)
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create association in transaction: {}", e)))?;
 
-        self.update_association_count_tx(tx, assoc.id1, assoc.atype, 1)
-            .await?;
+        // The edge already existed, so the count is unchanged
+        if result.rows_affected() > 0 {
+            self.update_association_count_tx(tx, assoc.id1, assoc.atype, 1)
+                .await?;
+        }
         Ok(())
     }
 
@@ -454,6 +1574,7 @@ impl DatabaseInterface for SqliteDatabase {
         atype: AssociationType,
         id2: ObjectId,
     ) -> AppResult<bool> {
+        tx.check_shard(id1)?;
         let sqlite_tx = tx.as_sqlite_mut()?;
 
         let result =
@@ -478,6 +1599,40 @@ impl DatabaseInterface for SqliteDatabase {
         }
     }
 
+    async fn delete_associations_of_type_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id1: ObjectId,
+        atype: AssociationType,
+    ) -> AppResult<Vec<ObjectId>> {
+        tx.check_shard(id1)?;
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let sqlite_tx = tx.as_sqlite_mut()?;
+
+        let rows =
+            sqlx::query("DELETE FROM tao_associations WHERE id1 = ? AND atype = ? RETURNING id2")
+                .bind(id1)
+                .bind(&atype)
+                .fetch_all(&mut **sqlite_tx)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!(
+                        "Failed to delete associations in transaction: {}",
+                        e
+                    ))
+                })?;
+        sqlx::query(
+            "UPDATE tao_association_counts SET count = 0, updated_time = ? WHERE id = ? AND atype = ?",
+        )
+        .bind(now)
+        .bind(id1)
+        .bind(&atype)
+        .execute(&mut **sqlite_tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to reset association count: {}", e)))?;
+        Ok(rows.into_iter().map(|row| row.get("id2")).collect())
+    }
+
     async fn update_association_count_tx(
         &self,
         tx: &mut DatabaseTransaction,
@@ -485,6 +1640,7 @@ impl DatabaseInterface for SqliteDatabase {
         atype: AssociationType,
         delta: i64,
     ) -> AppResult<()> {
+        tx.check_shard(id)?;
         let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
         let sqlite_tx = tx.as_sqlite_mut()?;
 
@@ -503,6 +1659,63 @@ impl DatabaseInterface for SqliteDatabase {
         Ok(())
     }
 
+    async fn record_association_changes_tx(
+        &self,
+        tx: &mut DatabaseTransaction,
+        id1: ObjectId,
+        atype: AssociationType,
+        changes: Vec<(ObjectId, bool)>,
+    ) -> AppResult<u64> {
+        tx.check_shard(id1)?;
+        let now = crate::infrastructure::tao_core::tao_core::current_time_millis();
+        let sqlite_tx = tx.as_sqlite_mut()?;
+        let map_err = |e: sqlx::Error| {
+            AppError::DatabaseError(format!(
+                "Failed to record association changes of {} ({}): {}",
+                id1, atype, e
+            ))
+        };
+
+        let latest_seq: i64 = sqlx::query_scalar(
+            "INSERT INTO tao_association_change_seqs (id1, atype, seq) VALUES (?, ?, ?)
+             ON CONFLICT (id1, atype) DO UPDATE SET seq = seq + excluded.seq
+             RETURNING seq",
+        )
+        .bind(id1)
+        .bind(&atype)
+        .bind(changes.len() as i64)
+        .fetch_one(&mut **sqlite_tx)
+        .await
+        .map_err(map_err)?;
+
+        let first_seq = latest_seq - changes.len() as i64 + 1;
+        for (offset, (id2, added)) in changes.into_iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO tao_association_changes (id1, atype, seq, id2, added, changed_time)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id1)
+            .bind(&atype)
+            .bind(first_seq + offset as i64)
+            .bind(id2)
+            .bind(added)
+            .bind(now)
+            .execute(&mut **sqlite_tx)
+            .await
+            .map_err(map_err)?;
+        }
+
+        sqlx::query("DELETE FROM tao_association_changes WHERE id1 = ? AND atype = ? AND seq <= ?")
+            .bind(id1)
+            .bind(&atype)
+            .bind(latest_seq - ASSOC_CHANGE_LOG_RETENTION as i64)
+            .execute(&mut **sqlite_tx)
+            .await
+            .map_err(map_err)?;
+
+        Ok(latest_seq as u64)
+    }
+
     async fn execute_query(&self, query: String) -> AppResult<Vec<HashMap<String, String>>> {
         let rows = sqlx::query(&query)
             .fetch_all(&self.pool)
@@ -534,6 +1747,18 @@ impl DatabaseInterface for SqliteDatabase {
         Ok(results)
     }
 
+    async fn write_high_watermark(&self) -> AppResult<Timestamp> {
+        sqlx::query_scalar(
+            "SELECT MAX(
+                 (SELECT COALESCE(MAX(time_updated), 0) FROM tao_objects),
+                 (SELECT COALESCE(MAX(updated_time), 0) FROM tao_association_counts)
+             )",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to read write high-watermark: {}", e)))
+    }
+
     async fn get_all_objects_from_shard(&self) -> AppResult<Vec<Object>> {
         let rows = sqlx::query(
             "SELECT id, otype, time_created, time_updated, data, version FROM tao_objects ORDER BY id"
@@ -559,7 +1784,7 @@ impl DatabaseInterface for SqliteDatabase {
 
     async fn get_all_associations_from_shard(&self) -> AppResult<Vec<Association>> {
         let rows = sqlx::query(
-            "SELECT id1, atype, id2, time_created, data FROM tao_associations ORDER BY id1, atype, id2"
+            "SELECT id1, atype, id2, time_created, data, subtype FROM tao_associations ORDER BY id1, atype, id2"
         )
         .fetch_all(&self.pool)
         .await
@@ -573,9 +1798,109 @@ impl DatabaseInterface for SqliteDatabase {
                 id2: row.get("id2"),
                 time: row.get("time_created"),
                 data: row.get("data"),
+                subtype: row.get("subtype"),
             })
             .collect();
 
         Ok(associations)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_object_type_count_matches_count_star() {
+        let db = SqliteDatabase::new_in_memory().await.unwrap();
+        for id in 1..=5 {
+            db.create_object(id, "ent_user".to_string(), vec![])
+                .await
+                .unwrap();
+        }
+        for id in 6..=8 {
+            db.create_object(id, "ent_post".to_string(), vec![])
+                .await
+                .unwrap();
+        }
+        assert!(db.delete_object(2).await.unwrap());
+        assert!(!db.delete_object(2).await.unwrap());
+
+        for otype in ["ent_user", "ent_post", "ent_page"] {
+            let actual: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM tao_objects WHERE otype = ?")
+                    .bind(otype)
+                    .fetch_one(&db.pool)
+                    .await
+                    .unwrap();
+            let maintained = db.get_object_type_count(otype.to_string()).await.unwrap();
+            assert_eq!(maintained, actual as u64, "count mismatch for {}", otype);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_consistency_reports_count_mismatch_and_orphan() {
+        let db = SqliteDatabase::new_in_memory().await.unwrap();
+        for id in 1..=3 {
+            db.create_object(id, "ent_user".to_string(), vec![])
+                .await
+                .unwrap();
+        }
+        for (id1, id2) in [(1, 2), (1, 3), (99, 1)] {
+            db.create_association(Association {
+                id1,
+                atype: "friends".to_string(),
+                id2,
+                time: 1,
+                data: None,
+                subtype: None,
+            })
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "UPDATE tao_association_counts SET count = 5 WHERE id = 1 AND atype = 'friends'",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let issues = db.audit_consistency(false).await.unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                ConsistencyIssue::CountMismatch {
+                    id1: 1,
+                    atype: "friends".to_string(),
+                    stored: 5,
+                    actual: 2,
+                    repaired: false,
+                },
+                ConsistencyIssue::OrphanedAssociation {
+                    id1: 99,
+                    atype: "friends".to_string(),
+                    id2: 1,
+                },
+            ]
+        );
+
+        let issues = db.audit_consistency(true).await.unwrap();
+        assert!(matches!(
+            issues[0],
+            ConsistencyIssue::CountMismatch { repaired: true, .. }
+        ));
+        assert_eq!(
+            db.get_association_count(1, "friends".to_string())
+                .await
+                .unwrap(),
+            2
+        );
+        let issues = db.audit_consistency(false).await.unwrap();
+        assert_eq!(
+            issues.len(),
+            1,
+            "only the orphan should remain: {:?}",
+            issues
+        );
+    }
+}
@@ -1,61 +1,142 @@
 // TAO ID Generator - Snowflake-like IDs with embedded shard information
 // Based on Meta's TAO ID scheme: 64-bit IDs with shard routing
 
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::task_local;
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::shard_topology::ShardId;
+use crate::infrastructure::tao_core::tao_core::TaoId;
+
+const MAX_SHARDS: usize = 1024;
+const SEQUENCE_BITS: u32 = 12;
+const SEQUENCE_MASK: u64 = (1 << SEQUENCE_BITS) - 1;
+const TIMESTAMP_MASK: u64 = 0x3FFFFFFFFFF;
+
+/// How far in the future a supplied id's timestamp may be, allowing for clock skew
+/// between the client that generated it and this process
+const MAX_PROVIDED_ID_SKEW_MS: u64 = 60_000;
+
+/// Default id epoch: the Unix epoch, which every id issued before epochs were
+/// configurable was generated against
+pub const DEFAULT_ID_EPOCH_MS: u64 = 0;
+
+/// Process-wide generators per shard, one set per epoch in use
+static SHARD_GENERATORS: OnceLock<Mutex<HashMap<u64, &'static [TaoIdGenerator]>>> = OnceLock::new();
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
 
 /// TAO ID Generator following Meta's pattern
 /// 64-bit ID format: [timestamp:42][shard_id:10][sequence:12]
 /// This allows for 1024 shards and 4096 IDs per millisecond per shard
+///
+/// The timestamp counts milliseconds since the generator's epoch (Unix epoch by
+/// default). 42 bits last about 139 years from the epoch, so a later epoch such as a
+/// project start date keeps ids smaller for longer. The epoch is not encoded in the id:
+/// changing it on an existing deployment makes old ids decode to the wrong time and
+/// lets new ids collide with or sort before old ones, so it must never change once ids
+/// have been issued.
+///
+/// The last issued (timestamp, sequence) pair is kept in a single atomic and advanced
+/// with compare-and-swap, so ids from one generator are unique and strictly increasing
+/// even when called concurrently. If the 4096 sequence numbers of a millisecond run
+/// out, or the wall clock steps backwards, the generator borrows the next millisecond
+/// instead of reusing a timestamp.
 #[derive(Debug)]
 pub struct TaoIdGenerator {
     shard_id: u16,
-    sequence: AtomicU64,
-    last_timestamp: AtomicU64,
+    /// Unix time in milliseconds that timestamp 0 corresponds to
+    epoch_ms: u64,
+    /// Last issued `(timestamp << SEQUENCE_BITS) | sequence`
+    last_issued: AtomicU64,
 }
 
 impl TaoIdGenerator {
     /// Create new ID generator for given shard
+    /// Separate generators for the same shard do not coordinate; use `for_shard`
+    /// to share the process-wide one.
     pub fn new(shard_id: u16) -> Self {
+        Self::with_epoch(shard_id, DEFAULT_ID_EPOCH_MS)
+    }
+
+    /// Create a generator whose timestamps count from `epoch_ms` (Unix milliseconds)
+    pub fn with_epoch(shard_id: u16, epoch_ms: u64) -> Self {
         assert!(shard_id < 1024, "Shard ID must be less than 1024");
 
         Self {
             shard_id,
-            sequence: AtomicU64::new(0),
-            last_timestamp: AtomicU64::new(0),
+            epoch_ms,
+            last_issued: AtomicU64::new(0),
         }
     }
 
+    /// Process-wide generator for `shard_id`, shared by every caller in this process
+    pub fn for_shard(shard_id: u16) -> &'static TaoIdGenerator {
+        Self::for_shard_with_epoch(shard_id, DEFAULT_ID_EPOCH_MS)
+    }
+
+    /// Process-wide generator for `shard_id` counting from `epoch_ms`
+    pub fn for_shard_with_epoch(shard_id: u16, epoch_ms: u64) -> &'static TaoIdGenerator {
+        assert!(shard_id < 1024, "Shard ID must be less than 1024");
+
+        let mut by_epoch = SHARD_GENERATORS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap();
+        let generators = by_epoch.entry(epoch_ms).or_insert_with(|| {
+            (0..MAX_SHARDS as u16)
+                .map(|shard_id| TaoIdGenerator::with_epoch(shard_id, epoch_ms))
+                .collect::<Vec<_>>()
+                .leak()
+        });
+        &generators[shard_id as usize]
+    }
+
     /// Generate next unique ID with embedded shard information
+    /// If the clock reads earlier than the epoch the ids borrow timestamps past the last
+    /// one issued; `SnowflakeIdGenerator` refuses to generate in that case.
     pub fn next_id(&self) -> i64 {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-
-        let last_ts = self.last_timestamp.load(Ordering::Relaxed);
-
-        let sequence = if now == last_ts {
-            // Same millisecond - increment sequence
-            let seq = self.sequence.fetch_add(1, Ordering::Relaxed);
-            if seq >= 4096 {
-                // Sequence overflow - wait for next millisecond
-                std::thread::sleep(std::time::Duration::from_millis(1));
-                self.sequence.store(0, Ordering::Relaxed);
-                return self.next_id();
+        let now = unix_millis().saturating_sub(self.epoch_ms) & TIMESTAMP_MASK;
+
+        let mut last = self.last_issued.load(Ordering::Relaxed);
+        let issued = loop {
+            let last_ts = last >> SEQUENCE_BITS;
+            let next = if now > last_ts {
+                // New millisecond - reset sequence
+                now << SEQUENCE_BITS
+            } else {
+                // Same millisecond (or the clock went backwards) - next sequence,
+                // rolling into the following millisecond on overflow
+                last + 1
+            };
+
+            match self.last_issued.compare_exchange_weak(
+                last,
+                next,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break next,
+                Err(current) => last = current,
             }
-            seq
-        } else {
-            // New millisecond - reset sequence
-            self.last_timestamp.store(now, Ordering::Relaxed);
-            self.sequence.store(1, Ordering::Relaxed);
-            0
         };
 
+        let timestamp = issued >> SEQUENCE_BITS;
+        let sequence = issued & SEQUENCE_MASK;
+
         // Construct 64-bit ID: [timestamp:42][shard_id:10][sequence:12]
-        let id = ((now & 0x3FFFFFFFFFF) << 22) |    // 42 bits timestamp
+        let id = ((timestamp & TIMESTAMP_MASK) << 22) |    // 42 bits timestamp
                  ((self.shard_id as u64) << 12) |   // 10 bits shard_id
-                 (sequence & 0xFFF); // 12 bits sequence
+                 sequence; // 12 bits sequence
 
         id as i64
     }
@@ -65,11 +146,16 @@ impl TaoIdGenerator {
         ((id as u64) >> 12 & 0x3FF) as u16
     }
 
-    /// Extract timestamp from TAO ID
+    /// Extract timestamp from TAO ID, in milliseconds since the generator's epoch
     pub fn extract_timestamp(id: i64) -> u64 {
         (id as u64) >> 22
     }
 
+    /// Unix time in milliseconds at which `id` was generated, under this generator's epoch
+    pub fn timestamp_ms(&self, id: i64) -> u64 {
+        self.epoch_ms + Self::extract_timestamp(id)
+    }
+
     /// Extract sequence from TAO ID
     pub fn extract_sequence(id: i64) -> u16 {
         ((id as u64) & 0xFFF) as u16
@@ -79,6 +165,160 @@ impl TaoIdGenerator {
     pub fn shard_id(&self) -> u16 {
         self.shard_id
     }
+
+    pub fn epoch_ms(&self) -> u64 {
+        self.epoch_ms
+    }
+}
+
+/// Strategy for assigning ids to new objects.
+/// Whatever the strategy, the shard must be recoverable from the id alone,
+/// since every read is routed by `shard_of`.
+pub trait IdGenerator: Send + Sync + std::fmt::Debug {
+    /// Generate an id for a new object. With an owner the id is colocated on the
+    /// owner's shard, otherwise it is placed on `default_shard` (chosen by the router).
+    fn next_id(&self, owner: Option<TaoId>, default_shard: ShardId) -> AppResult<TaoId>;
+
+    /// Shard that owns `id`
+    fn shard_of(&self, id: TaoId) -> ShardId;
+
+    /// Unix time in milliseconds at which `id` was generated
+    fn timestamp_of(&self, id: TaoId) -> u64;
+}
+
+/// Selects which `IdGenerator` TAO uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdStrategy {
+    /// Snowflake-style ids generated by TAO
+    #[default]
+    Snowflake,
+    /// Client-supplied ids (e.g. for imports), falling back to Snowflake when none is given
+    Provided,
+}
+
+impl IdStrategy {
+    pub fn build(self) -> std::sync::Arc<dyn IdGenerator> {
+        match self {
+            IdStrategy::Snowflake => std::sync::Arc::new(SnowflakeIdGenerator::default()),
+            IdStrategy::Provided => std::sync::Arc::new(ProvidedIdGenerator::default()),
+        }
+    }
+
+    /// Build with ids counting from `epoch_ms` (Unix milliseconds).
+    /// Errors if the epoch is still in the future.
+    pub fn build_with_epoch(self, epoch_ms: u64) -> AppResult<std::sync::Arc<dyn IdGenerator>> {
+        if epoch_ms > unix_millis() {
+            return Err(AppError::ConfigurationError(format!(
+                "Id epoch {} is in the future",
+                epoch_ms
+            )));
+        }
+        let snowflake = SnowflakeIdGenerator::with_epoch(epoch_ms);
+        Ok(match self {
+            IdStrategy::Snowflake => std::sync::Arc::new(snowflake),
+            IdStrategy::Provided => std::sync::Arc::new(ProvidedIdGenerator {
+                fallback: snowflake,
+            }),
+        })
+    }
+}
+
+/// The default strategy: [timestamp:42][shard_id:10][sequence:12] ids from the
+/// process-wide `TaoIdGenerator` of each shard, counting from the configured epoch
+#[derive(Debug, Default)]
+pub struct SnowflakeIdGenerator {
+    epoch_ms: u64,
+}
+
+impl SnowflakeIdGenerator {
+    pub fn with_epoch(epoch_ms: u64) -> Self {
+        Self { epoch_ms }
+    }
+}
+
+impl IdGenerator for SnowflakeIdGenerator {
+    fn next_id(&self, owner: Option<TaoId>, default_shard: ShardId) -> AppResult<TaoId> {
+        let now = unix_millis();
+        if now < self.epoch_ms {
+            return Err(AppError::IdGenerationError(format!(
+                "Clock reads {} which is before the id epoch {}",
+                now, self.epoch_ms
+            )));
+        }
+        let shard_id = owner.map_or(default_shard, TaoIdGenerator::extract_shard_id);
+        Ok(TaoIdGenerator::for_shard_with_epoch(shard_id, self.epoch_ms).next_id())
+    }
+
+    /// The shard bits sit below the timestamp, so the epoch does not affect them
+    fn shard_of(&self, id: TaoId) -> ShardId {
+        TaoIdGenerator::extract_shard_id(id)
+    }
+
+    fn timestamp_of(&self, id: TaoId) -> u64 {
+        self.epoch_ms + TaoIdGenerator::extract_timestamp(id)
+    }
+}
+
+task_local! {
+    static PROVIDED_ID: TaoId;
+}
+
+/// Run `f` so that the next id requested inside it is `id` instead of a generated one.
+/// Only honoured by `ProvidedIdGenerator`.
+pub async fn with_provided_id<F>(id: TaoId, f: F) -> F::Output
+where
+    F: Future,
+{
+    PROVIDED_ID.scope(id, f).await
+}
+
+/// Accepts client-supplied ids, set with `with_provided_id`.
+/// The shard is derived from the id's shard bits, and an id owned by someone must
+/// land on the owner's shard. Ids whose timestamp decodes to the future under the
+/// configured epoch were generated against an earlier epoch and are rejected.
+/// Without a supplied id it behaves like Snowflake.
+#[derive(Debug, Default)]
+pub struct ProvidedIdGenerator {
+    fallback: SnowflakeIdGenerator,
+}
+
+impl IdGenerator for ProvidedIdGenerator {
+    fn next_id(&self, owner: Option<TaoId>, default_shard: ShardId) -> AppResult<TaoId> {
+        let Ok(id) = PROVIDED_ID.try_with(|id| *id) else {
+            return self.fallback.next_id(owner, default_shard);
+        };
+
+        if id <= 0 {
+            return Err(AppError::Validation(format!(
+                "Provided id {} must be positive",
+                id
+            )));
+        }
+        if self.timestamp_of(id) > unix_millis() + MAX_PROVIDED_ID_SKEW_MS {
+            return Err(AppError::Validation(format!(
+                "Provided id {} was not generated against id epoch {}",
+                id, self.fallback.epoch_ms
+            )));
+        }
+        if let Some(owner) = owner {
+            let (id_shard, owner_shard) = (self.shard_of(id), self.shard_of(owner));
+            if id_shard != owner_shard {
+                return Err(AppError::Validation(format!(
+                    "Provided id {} is on shard {} but owner {} is on shard {}",
+                    id, id_shard, owner, owner_shard
+                )));
+            }
+        }
+        Ok(id)
+    }
+
+    fn shard_of(&self, id: TaoId) -> ShardId {
+        self.fallback.shard_of(id)
+    }
+
+    fn timestamp_of(&self, id: TaoId) -> u64 {
+        self.fallback.timestamp_of(id)
+    }
 }
 
 #[cfg(test)]
@@ -121,4 +361,112 @@ mod tests {
         assert_eq!(TaoIdGenerator::extract_shard_id(id), 500);
         assert_eq!(generator.shard_id(), 500);
     }
+
+    #[test]
+    fn test_snowflake_strategy_shard_of() {
+        let generator = SnowflakeIdGenerator::default();
+
+        let id = generator.next_id(None, 42).unwrap();
+        assert_eq!(generator.shard_of(id), 42);
+
+        // Owned ids are colocated with the owner regardless of the default shard
+        let owned = generator.next_id(Some(id), 7).unwrap();
+        assert_eq!(generator.shard_of(owned), 42);
+    }
+
+    #[tokio::test]
+    async fn test_custom_epoch_round_trips_timestamp() {
+        // 2024-01-01T00:00:00Z
+        let epoch_ms = 1_704_067_200_000;
+        let generator = IdStrategy::Snowflake.build_with_epoch(epoch_ms).unwrap();
+
+        let before = unix_millis();
+        let id = generator.next_id(None, 17).unwrap();
+        let after = unix_millis();
+
+        assert_eq!(generator.shard_of(id), 17);
+        let timestamp = generator.timestamp_of(id);
+        assert!(before <= timestamp && timestamp <= after + 1);
+        // The raw timestamp bits count from the custom epoch, so the id is smaller
+        assert!(id < TaoIdGenerator::new(17).next_id());
+
+        // An epoch that has not started yet cannot be configured
+        let future = IdStrategy::Snowflake.build_with_epoch(unix_millis() + 60_000);
+        assert!(matches!(future, Err(AppError::ConfigurationError(_))));
+
+        // Ids generated against the Unix epoch decode to the future and are rejected
+        let provided = IdStrategy::Provided.build_with_epoch(epoch_ms).unwrap();
+        let unix_epoch_id = TaoIdGenerator::new(17).next_id();
+        let result = with_provided_id(unix_epoch_id, async { provided.next_id(None, 0) }).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let accepted = with_provided_id(id, async { provided.next_id(None, 0) }).await;
+        assert_eq!(accepted.unwrap(), id);
+    }
+
+    #[tokio::test]
+    async fn test_provided_strategy_shard_of() {
+        let generator = ProvidedIdGenerator::default();
+        let supplied = TaoIdGenerator::new(9).next_id();
+
+        let id = with_provided_id(supplied, async { generator.next_id(None, 0) })
+            .await
+            .unwrap();
+        assert_eq!(id, supplied);
+        assert_eq!(generator.shard_of(id), 9);
+
+        // Without a supplied id it generates one on the default shard
+        let generated = generator.next_id(None, 3).unwrap();
+        assert_eq!(generator.shard_of(generated), 3);
+
+        // A supplied id must be colocated with its owner
+        let owner = TaoIdGenerator::new(4).next_id();
+        let result = with_provided_id(supplied, async { generator.next_id(Some(owner), 0) }).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_sequence_overflow_borrows_next_millisecond() {
+        let generator = TaoIdGenerator::new(1);
+
+        // Far more than 4096 ids in a tight loop must still never repeat or go backwards
+        let mut previous = generator.next_id();
+        for _ in 0..20_000 {
+            let id = generator.next_id();
+            assert!(id > previous);
+            assert_eq!(TaoIdGenerator::extract_shard_id(id), 1);
+            previous = id;
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_concurrent_ids_unique_and_monotonic_per_shard() {
+        const SHARDS: u16 = 4;
+        const TASKS: usize = 32;
+        const IDS_PER_TASK: usize = 100_000;
+
+        let handles: Vec<_> = (0..TASKS)
+            .map(|task| {
+                tokio::spawn(async move {
+                    let shard_id = 1000 + (task as u16 % SHARDS);
+                    let generator = TaoIdGenerator::for_shard(shard_id);
+                    (0..IDS_PER_TASK)
+                        .map(|_| generator.next_id())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut all_ids = Vec::with_capacity(TASKS * IDS_PER_TASK);
+        for handle in handles {
+            let ids = handle.await.unwrap();
+            // Every caller observes strictly increasing ids from its shard
+            assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+            all_ids.extend(ids);
+        }
+
+        let total = all_ids.len();
+        all_ids.sort_unstable();
+        all_ids.dedup();
+        assert_eq!(all_ids.len(), total, "duplicate ids generated");
+    }
 }
@@ -0,0 +1,241 @@
+// CORS Configuration - Builds the server's CorsLayer from an explicit allow-list
+// Permissive CORS is only used when dev mode is switched on explicitly
+
+use crate::error::{AppError, AppResult};
+use axum::http::{HeaderName, HeaderValue, Method, Uri};
+use serde::{Deserialize, Serialize};
+use tower_http::cors::CorsLayer;
+
+/// The `cors` section of the server configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Allow any origin, method and header. Local development only.
+    pub dev_mode: bool,
+    /// Origins allowed to call the API, e.g. `https://app.example.com`
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    /// Let allowed origins send cookies and auth headers
+    pub allow_credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            dev_mode: false,
+            allowed_origins: Vec::new(),
+            allowed_methods: ["GET", "POST", "PUT", "DELETE"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            allowed_headers: ["authorization", "content-type"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            allow_credentials: false,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Read `CORS_DEV_MODE`, `CORS_ALLOWED_ORIGINS`, `CORS_ALLOWED_METHODS`,
+    /// `CORS_ALLOWED_HEADERS` and `CORS_ALLOW_CREDENTIALS` (lists are comma-separated)
+    pub fn from_env() -> AppResult<Self> {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Like `from_env`, reading variables through `lookup`; unset variables keep their defaults
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> AppResult<Self> {
+        let flag = |key: &str| -> AppResult<Option<bool>> {
+            lookup(key)
+                .map(|value| {
+                    value.trim().parse::<bool>().map_err(|_| {
+                        AppError::ConfigurationError(format!(
+                            "{} must be true or false, got '{}'",
+                            key, value
+                        ))
+                    })
+                })
+                .transpose()
+        };
+        let list = |key: &str| {
+            lookup(key).map(|value| {
+                value
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let mut config = Self::default();
+        if let Some(dev_mode) = flag("CORS_DEV_MODE")? {
+            config.dev_mode = dev_mode;
+        }
+        if let Some(origins) = list("CORS_ALLOWED_ORIGINS") {
+            config.allowed_origins = origins;
+        }
+        if let Some(methods) = list("CORS_ALLOWED_METHODS") {
+            config.allowed_methods = methods;
+        }
+        if let Some(headers) = list("CORS_ALLOWED_HEADERS") {
+            config.allowed_headers = headers;
+        }
+        if let Some(allow_credentials) = flag("CORS_ALLOW_CREDENTIALS")? {
+            config.allow_credentials = allow_credentials;
+        }
+        Ok(config)
+    }
+
+    /// Build the layer, rejecting malformed origins, methods or headers so a typo
+    /// fails at startup instead of silently blocking (or allowing) callers
+    pub fn build_layer(&self) -> AppResult<CorsLayer> {
+        if self.dev_mode {
+            return Ok(CorsLayer::permissive());
+        }
+
+        let origins = self
+            .allowed_origins
+            .iter()
+            .map(|origin| parse_origin(origin))
+            .collect::<AppResult<Vec<_>>>()?;
+        let methods = self
+            .allowed_methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes()).map_err(|_| {
+                    AppError::ConfigurationError(format!("Invalid CORS method '{}'", method))
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        let headers = self
+            .allowed_headers
+            .iter()
+            .map(|header| {
+                HeaderName::from_bytes(header.as_bytes()).map_err(|_| {
+                    AppError::ConfigurationError(format!("Invalid CORS header '{}'", header))
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .allow_credentials(self.allow_credentials))
+    }
+}
+
+/// An origin is `scheme://host[:port]` with no path, query or trailing slash,
+/// which is exactly what browsers send in the `Origin` header
+fn parse_origin(origin: &str) -> AppResult<HeaderValue> {
+    let invalid = || AppError::ConfigurationError(format!("Invalid CORS origin '{}'", origin));
+
+    let uri: Uri = origin.parse().map_err(|_| invalid())?;
+    let scheme_ok = matches!(uri.scheme_str(), Some("http") | Some("https"));
+    // The parser reports a bare authority as path "/", so a literal trailing slash is checked below
+    let bare = uri.path_and_query().is_none_or(|path| path.as_str() == "/");
+    if !scheme_ok || uri.host().is_none() || !bare || origin.ends_with('/') {
+        return Err(invalid());
+    }
+    HeaderValue::from_str(origin).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_cors_config_parsing() {
+        let vars: HashMap<&str, &str> = [
+            (
+                "CORS_ALLOWED_ORIGINS",
+                "https://app.example.com, http://localhost:5173",
+            ),
+            ("CORS_ALLOWED_METHODS", "get,post"),
+            ("CORS_ALLOW_CREDENTIALS", "true"),
+        ]
+        .into_iter()
+        .collect();
+        let config = CorsConfig::from_vars(|key| vars.get(key).map(|v| v.to_string())).unwrap();
+
+        assert!(!config.dev_mode);
+        assert_eq!(
+            config.allowed_origins,
+            vec!["https://app.example.com", "http://localhost:5173"]
+        );
+        assert_eq!(config.allowed_methods, vec!["get", "post"]);
+        assert_eq!(
+            config.allowed_headers,
+            CorsConfig::default().allowed_headers
+        );
+        assert!(config.allow_credentials);
+        assert!(config.build_layer().is_ok());
+
+        for origin in [
+            "app.example.com",
+            "https://app.example.com/",
+            "ftp://x.com",
+            "https://x.com/a",
+        ] {
+            let config = CorsConfig {
+                allowed_origins: vec![origin.to_string()],
+                ..CorsConfig::default()
+            };
+            assert!(
+                config.build_layer().is_err(),
+                "{} should be rejected",
+                origin
+            );
+        }
+        assert!(
+            CorsConfig::from_vars(|key| (key == "CORS_DEV_MODE").then(|| "yes".to_string()))
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_origin_gets_no_cors_headers() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            ..CorsConfig::default()
+        };
+        let app = Router::new()
+            .route("/api/health", get(|| async { "ok" }))
+            .layer(config.build_layer().unwrap());
+
+        let request = |origin: &str| {
+            Request::builder()
+                .uri("/api/health")
+                .header("origin", origin)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let allowed = app
+            .clone()
+            .oneshot(request("https://app.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            allowed
+                .headers()
+                .get("access-control-allow-origin")
+                .unwrap(),
+            "https://app.example.com"
+        );
+
+        let rejected = app
+            .oneshot(request("https://evil.example.com"))
+            .await
+            .unwrap();
+        assert!(rejected
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
+}
@@ -0,0 +1,192 @@
+// Idempotency Middleware - Replays the original response when a client retries a write
+// Responses are stored per `Idempotency-Key` in the shard databases and expire after a TTL
+// A key is claimed before its request runs, so concurrent retries never run the write twice
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::database::database::{DatabaseInterface, IdempotencyRecord};
+use crate::infrastructure::query_router::TaoQueryRouter;
+use crate::infrastructure::tao_core::tao_core::current_time_millis;
+use crate::infrastructure::viewer::viewer::ViewerContext;
+
+/// Request header carrying the client's idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Response header set to "true" on a replayed response
+pub const IDEMPOTENT_REPLAY_HEADER: &str = "x-idempotent-replayed";
+
+const MAX_KEY_LEN: usize = 255;
+/// Larger responses are passed through without being stored
+const MAX_STORED_BODY: usize = 1024 * 1024;
+/// How long a claimed key turns retries away if its request never finishes, e.g.
+/// because the server died while running it
+const CLAIM_TTL: Duration = Duration::from_secs(60);
+
+/// Where idempotency records live and how long they are kept
+#[derive(Clone)]
+pub struct IdempotencyStore {
+    query_router: Arc<TaoQueryRouter>,
+    ttl: Duration,
+}
+
+impl IdempotencyStore {
+    pub fn new(query_router: Arc<TaoQueryRouter>, ttl: Duration) -> Self {
+        Self { query_router, ttl }
+    }
+
+    /// Keys are spread over shards by a stable hash. Adding or removing a shard can
+    /// only make a key be forgotten early, never replay the wrong response.
+    async fn database_for(&self, key: &str) -> AppResult<Arc<dyn DatabaseInterface>> {
+        let mut shards = self.query_router.get_all_shards().await;
+        if shards.is_empty() {
+            return Err(AppError::ShardError("No shards available".to_string()));
+        }
+        shards.sort();
+        let hash = key.bytes().fold(0u64, |hash, byte| {
+            hash.wrapping_mul(31).wrapping_add(byte as u64)
+        });
+        let shard_id = shards[(hash % shards.len() as u64) as usize];
+        self.query_router.get_database_for_shard(shard_id).await
+    }
+
+    /// Claim `key` for a request about to run. None when the claim was taken; otherwise
+    /// the record holding the key, pending while its request is still running.
+    pub async fn claim(&self, key: &str) -> AppResult<Option<IdempotencyRecord>> {
+        let expires_at = current_time_millis() + CLAIM_TTL.min(self.ttl).as_millis() as i64;
+        self.database_for(key)
+            .await?
+            .claim_idempotency_key(key, expires_at)
+            .await
+    }
+
+    /// Give up a claim without storing a response, so a retry runs the request again
+    pub async fn release(&self, key: &str) -> AppResult<()> {
+        self.database_for(key)
+            .await?
+            .delete_idempotency_record(key)
+            .await
+    }
+
+    pub async fn put(&self, key: String, status: StatusCode, body: Vec<u8>) -> AppResult<()> {
+        let database = self.database_for(&key).await?;
+        database
+            .put_idempotency_record(IdempotencyRecord {
+                key,
+                status: status.as_u16(),
+                body,
+                expires_at: current_time_millis() + self.ttl.as_millis() as i64,
+            })
+            .await
+    }
+}
+
+/// Middleware for JSON write endpoints, layered inside `viewer_context_middleware`. A request carrying `Idempotency-Key` runs once;
+/// retries with the same key (same viewer, method and path) get the stored response back,
+/// or 409 while the first request is still running.
+/// 5xx responses are not stored, so a retry after a server failure runs again.
+pub async fn idempotency_middleware(
+    State(store): State<IdempotencyStore>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key,
+        _ => {
+            return AppError::Validation(format!(
+                "{} must be 1-{} visible ASCII characters",
+                IDEMPOTENCY_KEY_HEADER, MAX_KEY_LEN
+            ))
+            .into_response()
+        }
+    };
+    // Scope keys to the viewer so two clients picking the same key never see each other's results
+    let viewer = request
+        .extensions()
+        .get::<Arc<ViewerContext>>()
+        .and_then(|vc| vc.user_id)
+        .map_or_else(|| "anonymous".to_string(), |id| id.to_string());
+    let scoped_key = format!(
+        "{} {} {} {}",
+        request.method(),
+        request.uri().path(),
+        viewer,
+        key
+    );
+
+    match store.claim(&scoped_key).await {
+        Ok(None) => {}
+        Ok(Some(record)) if record.is_pending() => {
+            return AppError::Conflict(format!(
+                "A request with this {} is still in progress",
+                IDEMPOTENCY_KEY_HEADER
+            ))
+            .into_response()
+        }
+        Ok(Some(record)) => return replayed_response(record),
+        // Running the write without a working store could duplicate it
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next.run(request).await;
+    if response.status().is_server_error() {
+        release(&store, &scoped_key).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(
+                "Failed to buffer response for idempotency key {}: {}",
+                scoped_key, e
+            );
+            release(&store, &scoped_key).await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if body.len() > MAX_STORED_BODY {
+        warn!(
+            "Response for idempotency key {} too large to store",
+            scoped_key
+        );
+        release(&store, &scoped_key).await;
+    } else if let Err(e) = store
+        .put(scoped_key.clone(), parts.status, body.to_vec())
+        .await
+    {
+        warn!("Failed to store idempotency key {}: {}", scoped_key, e);
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Release a claim; on failure the claim lapses after `CLAIM_TTL`
+async fn release(store: &IdempotencyStore, scoped_key: &str) {
+    if let Err(e) = store.release(scoped_key).await {
+        warn!("Failed to release idempotency key {}: {}", scoped_key, e);
+    }
+}
+
+fn replayed_response(record: IdempotencyRecord) -> Response {
+    let status = StatusCode::from_u16(record.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, record.body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(IDEMPOTENT_REPLAY_HEADER, HeaderValue::from_static("true"));
+    response
+}
@@ -1,8 +1,22 @@
 // ViewerContext Middleware - Meta's authentic pattern implementation
 // Separates infrastructure concerns from business logic
 
+pub mod cors;
+pub mod idempotency_middleware;
+pub mod query_plan_middleware;
+pub mod rate_limit_middleware;
+pub mod read_consistency_middleware;
+pub mod request_deadline_middleware;
+pub mod stale_read_middleware;
 pub mod viewer_context_middleware;
 pub mod viewer_context_extractor;
 
+pub use cors::CorsConfig;
+pub use idempotency_middleware::*;
+pub use query_plan_middleware::*;
+pub use rate_limit_middleware::*;
+pub use read_consistency_middleware::*;
+pub use request_deadline_middleware::*;
+pub use stale_read_middleware::*;
 pub use viewer_context_middleware::*;
-pub use viewer_context_extractor::*;
\ No newline at end of file
+pub use viewer_context_extractor::*;
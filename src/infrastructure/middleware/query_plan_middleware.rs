@@ -0,0 +1,38 @@
+// Query Plan Middleware - Returns the per-shard query plan of a request to admins
+// Debugging aid for slow scatter-gather reads, opted into per request with a header
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use std::sync::Arc;
+
+use crate::infrastructure::monitoring::query_plan::explain;
+use crate::infrastructure::viewer::viewer::ViewerContext;
+
+/// Request header asking for the query plan, honoured for admins only
+pub const EXPLAIN_HEADER: &str = "x-tao-explain";
+/// Response header carrying the JSON `QueryPlan` of an explained request
+pub const QUERY_PLAN_HEADER: &str = "x-tao-query-plan";
+
+/// Middleware that captures the query plan of requests sent with `x-tao-explain: true`.
+/// Must run inside `viewer_context_middleware`, which tells it who the viewer is.
+pub async fn query_plan_middleware(request: Request, next: Next) -> Response {
+    let requested = request
+        .headers()
+        .get(EXPLAIN_HEADER)
+        .is_some_and(|value| value == "true");
+    let admin = request
+        .extensions()
+        .get::<Arc<ViewerContext>>()
+        .is_some_and(|vc| vc.is_admin());
+    if !(requested && admin) {
+        return next.run(request).await;
+    }
+
+    let (mut response, plan) = explain(next.run(request)).await;
+    if let Some(value) = serde_json::to_string(&plan)
+        .ok()
+        .and_then(|json| HeaderValue::from_str(&json).ok())
+    {
+        response.headers_mut().insert(QUERY_PLAN_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,394 @@
+// Rate Limit Middleware - Token-bucket limits per client, applied per route group
+// Clients are the authenticated user, or the caller's ip for anonymous requests
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::tao_core::request_context::{with_request_context, RequestContext};
+use crate::infrastructure::viewer::viewer::{Capability, ViewerContext};
+
+/// Header listing the client and the proxies a request passed through
+pub const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+/// Response header with the rate-limit tokens a request was charged
+pub const COST_UNITS_HEADER: &str = "x-tao-cost-units";
+
+/// Above this many tracked clients, buckets that have refilled completely are dropped
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// The `rate_limit` section of the server configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Tokens a client can spend in a burst
+    pub capacity: u32,
+    /// Tokens given back to each client per second
+    pub refill_per_second: f64,
+    /// Peers whose `X-Forwarded-For` header is believed, e.g. the load balancer
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 100,
+            refill_per_second: 10.0,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Read `RATE_LIMIT_CAPACITY`, `RATE_LIMIT_REFILL_PER_SECOND` and
+    /// `RATE_LIMIT_TRUSTED_PROXIES` (comma-separated ips)
+    pub fn from_env() -> AppResult<Self> {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Like `from_env`, reading variables through `lookup`; unset variables keep their defaults
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> AppResult<Self> {
+        let invalid = |key: &str, value: &str, expected: &str| {
+            AppError::ConfigurationError(format!("{} must be {}, got '{}'", key, expected, value))
+        };
+
+        let mut config = Self::default();
+        if let Some(value) = lookup("RATE_LIMIT_CAPACITY") {
+            config.capacity = value
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|capacity| *capacity > 0)
+                .ok_or_else(|| invalid("RATE_LIMIT_CAPACITY", &value, "a positive integer"))?;
+        }
+        if let Some(value) = lookup("RATE_LIMIT_REFILL_PER_SECOND") {
+            config.refill_per_second = value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|rate| rate.is_finite() && *rate > 0.0)
+                .ok_or_else(|| {
+                    invalid("RATE_LIMIT_REFILL_PER_SECOND", &value, "a positive number")
+                })?;
+        }
+        if let Some(value) = lookup("RATE_LIMIT_TRUSTED_PROXIES") {
+            config.trusted_proxies = value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| {
+                    item.parse::<IpAddr>().map_err(|_| {
+                        invalid("RATE_LIMIT_TRUSTED_PROXIES", item, "a list of ip addresses")
+                    })
+                })
+                .collect::<AppResult<Vec<_>>>()?;
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token buckets keyed by client. Every client starts with a full bucket of `capacity`
+/// tokens; a request spends its route's cost and tokens come back at `refill_per_second`.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Spend `cost` tokens from `client`'s bucket. When it holds too few, nothing is spent
+    /// and the error is how long until it will hold enough. A cost above the capacity
+    /// is charged as a full bucket.
+    pub fn check(&self, client: &str, cost: u32) -> Result<(), Duration> {
+        let cost = (cost as f64).min(self.config.capacity as f64);
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = self.refilled_bucket(&mut buckets, client);
+
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (cost - bucket.tokens) / self.config.refill_per_second,
+            ))
+        }
+    }
+
+    /// Spend `units` tokens from `client`'s bucket without turning anything away, for
+    /// costs only known once a request has run. The bucket bottoms out at empty.
+    pub fn charge(&self, client: &str, units: u64) {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = self.refilled_bucket(&mut buckets, client);
+        bucket.tokens = (bucket.tokens - units as f64).max(0.0);
+    }
+
+    /// Tokens left in `client`'s bucket
+    pub fn remaining(&self, client: &str) -> f64 {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        self.refilled_bucket(&mut buckets, client).tokens
+    }
+
+    /// `client`'s bucket topped up for the time since it was last used
+    fn refilled_bucket<'a>(
+        &self,
+        buckets: &'a mut HashMap<String, Bucket>,
+        client: &str,
+    ) -> &'a mut Bucket {
+        let capacity = self.config.capacity as f64;
+        let now = Instant::now();
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            let refill_per_second = self.config.refill_per_second;
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+                bucket.tokens + elapsed * refill_per_second < capacity
+            });
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.config.refill_per_second).min(capacity);
+        bucket.refilled_at = now;
+        bucket
+    }
+
+    /// The ip a request came from. `X-Forwarded-For` is only believed when the peer is a
+    /// trusted proxy, and then read right to left up to the first untrusted hop, since
+    /// anything left of that was written by the client itself.
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer?;
+        if !self.config.trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
+
+        let hops: Vec<&str> = headers
+            .get_all(FORWARDED_FOR_HEADER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        // A hop that doesn't parse wasn't written by a trusted proxy, so nothing left of
+        // it can be believed either
+        let mut client = peer;
+        for hop in hops.iter().rev() {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(ip) => client = ip,
+                Err(_) => break,
+            }
+            if !self.config.trusted_proxies.contains(&client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+/// State of one route group's rate-limit layer: the shared limiter and what a call costs
+#[derive(Debug, Clone)]
+pub struct RouteRateLimit {
+    limiter: Arc<RateLimiter>,
+    cost: u32,
+}
+
+impl RouteRateLimit {
+    pub fn new(limiter: Arc<RateLimiter>, cost: u32) -> Self {
+        Self { limiter, cost }
+    }
+}
+
+/// Middleware for a route group, layered inside `viewer_context_middleware`. Authenticated
+/// users are limited by user id and everyone else by client ip; viewers holding
+/// `BypassRateLimit` are never limited. Over the limit, the response is
+/// `429 Too Many Requests` with `Retry-After` in whole seconds.
+///
+/// The route's cost is checked up front. The request then runs metered, and whatever
+/// its TAO calls cost beyond that is charged afterwards, so a scatter-gather drains the
+/// bucket faster than a point read on the same route. The units charged are returned
+/// in `X-Tao-Cost-Units`.
+pub async fn rate_limit_middleware(
+    State(limit): State<RouteRateLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let viewer = request.extensions().get::<Arc<ViewerContext>>();
+    if viewer.is_some_and(|vc| vc.has_capability(&Capability::BypassRateLimit)) {
+        return next.run(request).await;
+    }
+
+    let client = match viewer.and_then(|vc| vc.user_id) {
+        Some(user_id) => format!("user:{}", user_id),
+        None => {
+            let peer = request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip());
+            match limit.limiter.client_ip(peer, request.headers()) {
+                Some(ip) => format!("ip:{}", ip),
+                None => "ip:unknown".to_string(),
+            }
+        }
+    };
+
+    match limit.limiter.check(&client, limit.cost) {
+        Ok(()) => {
+            let context = RequestContext::metered();
+            let mut response = with_request_context(context.clone(), next.run(request)).await;
+            let units = context.units_consumed();
+            let extra = units.saturating_sub(limit.cost as u64);
+            if extra > 0 {
+                limit.limiter.charge(&client, extra);
+            }
+            response.headers_mut().insert(
+                COST_UNITS_HEADER,
+                HeaderValue::from(units.max(limit.cost as u64)),
+            );
+            response
+        }
+        Err(retry_after) => {
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let mut response = AppError::TooManyRequests(format!(
+                "Rate limit exceeded, retry in {} seconds",
+                seconds
+            ))
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(capacity: u32, trusted_proxies: &[&str]) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            capacity,
+            refill_per_second: 1.0,
+            trusted_proxies: trusted_proxies
+                .iter()
+                .map(|ip| ip.parse().unwrap())
+                .collect(),
+        })
+    }
+
+    fn ip(ip: &str) -> Option<IpAddr> {
+        Some(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn test_bucket_spends_costs_and_reports_wait() {
+        let limiter = limiter(10, &[]);
+        assert!(limiter.check("a", 6).is_ok());
+        assert!(limiter.check("a", 4).is_ok());
+
+        let wait = limiter.check("a", 3).unwrap_err();
+        assert!(wait > Duration::from_millis(2_900) && wait <= Duration::from_secs(3));
+        // Other clients have their own bucket
+        assert!(limiter.check("b", 10).is_ok());
+    }
+
+    #[test]
+    fn test_charge_spends_past_what_check_would_allow() {
+        let limiter = limiter(10, &[]);
+        assert!(limiter.check("a", 1).is_ok());
+        limiter.charge("a", 30);
+        assert!(limiter.remaining("a") < 0.1);
+        assert!(limiter.check("a", 1).is_err());
+    }
+
+    #[test]
+    fn test_forwarded_for_is_only_trusted_from_proxies() {
+        let limiter = limiter(10, &["10.0.0.1", "10.0.0.2"]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            FORWARDED_FOR_HEADER,
+            HeaderValue::from_static("1.1.1.1, 2.2.2.2, 10.0.0.2"),
+        );
+
+        // A client talking to us directly can't pick its own ip
+        assert_eq!(limiter.client_ip(ip("3.3.3.3"), &headers), ip("3.3.3.3"));
+        // Behind the proxies the first untrusted hop from the right is the client
+        assert_eq!(limiter.client_ip(ip("10.0.0.1"), &headers), ip("2.2.2.2"));
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &HeaderMap::new()),
+            ip("10.0.0.1")
+        );
+        assert_eq!(limiter.client_ip(None, &headers), None);
+    }
+
+    #[test]
+    fn test_forwarded_for_ignores_what_the_client_wrote_left_of_the_proxy() {
+        let limiter = limiter(10, &["10.0.0.1"]);
+        let forwarded = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(FORWARDED_FOR_HEADER, HeaderValue::from_static(value));
+            headers
+        };
+
+        // The proxy appends the real ip after whatever the client sent
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &forwarded("9.9.9.9, junk, 2.2.2.2")),
+            ip("2.2.2.2")
+        );
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &forwarded("junk, 2.2.2.2")),
+            ip("2.2.2.2")
+        );
+        // Garbage right of the proxy stops the walk at the last hop that could be read
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &forwarded("9.9.9.9, junk")),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_config_from_vars() {
+        let vars = |key: &str| match key {
+            "RATE_LIMIT_CAPACITY" => Some("20".to_string()),
+            "RATE_LIMIT_TRUSTED_PROXIES" => Some("10.0.0.1, ::1".to_string()),
+            _ => None,
+        };
+        let config = RateLimitConfig::from_vars(vars).unwrap();
+        assert_eq!(config.capacity, 20);
+        assert_eq!(config.refill_per_second, 10.0);
+        assert_eq!(
+            config.trusted_proxies,
+            vec![ip("10.0.0.1").unwrap(), ip("::1").unwrap()]
+        );
+
+        let bad = RateLimitConfig::from_vars(|key| {
+            (key == "RATE_LIMIT_REFILL_PER_SECOND").then(|| "0".to_string())
+        });
+        assert!(matches!(bad, Err(AppError::ConfigurationError(_))));
+    }
+}
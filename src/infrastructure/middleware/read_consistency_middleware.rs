@@ -0,0 +1,30 @@
+// Read Consistency Middleware - Lets HTTP clients ask for reads that bypass the cache
+// A request with `Cache-Control: no-cache` (or `Pragma: no-cache`) is served with fresh reads
+
+use axum::{
+    extract::Request,
+    http::header::{CACHE_CONTROL, PRAGMA},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::infrastructure::cache::read_consistency::{with_read_consistency, ReadConsistency};
+
+/// Middleware that runs `no-cache` requests with `ReadConsistency::Fresh`
+pub async fn read_consistency_middleware(request: Request, next: Next) -> Response {
+    let no_cache = [CACHE_CONTROL, PRAGMA].iter().any(|name| {
+        request
+            .headers()
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|directive| directive.trim().eq_ignore_ascii_case("no-cache"))
+    });
+    let consistency = if no_cache {
+        ReadConsistency::Fresh
+    } else {
+        ReadConsistency::Cached
+    };
+    with_read_consistency(consistency, next.run(request)).await
+}
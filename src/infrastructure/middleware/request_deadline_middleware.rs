@@ -0,0 +1,62 @@
+// Request Deadline Middleware - Bounds the TAO reads of each request and keeps its writes
+// running when the client disconnects
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+use tracing::error;
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::tao_core::request_context::{with_request_context, RequestContext};
+
+/// How long the TAO reads of a request may run unless `REQUEST_TIMEOUT_MS` says otherwise
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read `REQUEST_TIMEOUT_MS`, falling back to `DEFAULT_REQUEST_TIMEOUT` when unset
+pub fn request_timeout_from_env() -> AppResult<Duration> {
+    match std::env::var("REQUEST_TIMEOUT_MS") {
+        Ok(value) => value
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+            .ok_or_else(|| {
+                AppError::ConfigurationError(format!(
+                    "REQUEST_TIMEOUT_MS must be a positive integer, got '{}'",
+                    value
+                ))
+            }),
+        Err(_) => Ok(DEFAULT_REQUEST_TIMEOUT),
+    }
+}
+
+/// Middleware running each request in a `RequestContext` whose deadline is `timeout` from
+/// now; `DeadlineDecorator` enforces it on TAO reads.
+///
+/// Axum drops the request future when the client disconnects. Reads can stop anywhere, but
+/// a write dropped between two of its statements would be left half applied, so requests
+/// with unsafe methods run on a task of their own that finishes whether or not anyone is
+/// still waiting. Task-local scopes don't carry over to that task, so this layer has to
+/// sit outside every layer that sets one.
+pub async fn request_deadline_middleware(
+    State(timeout): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let context = RequestContext::with_timeout(timeout);
+    if request.method().is_safe() {
+        return with_request_context(context, next.run(request)).await;
+    }
+
+    match tokio::spawn(with_request_context(context, next.run(request))).await {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Request handler task failed: {}", e);
+            AppError::Internal("Request handler failed".to_string()).into_response()
+        }
+    }
+}
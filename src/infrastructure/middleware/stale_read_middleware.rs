@@ -0,0 +1,22 @@
+// Stale Read Middleware - Surfaces degraded (stale cache) reads to HTTP clients
+// Wraps each request in a stale-read scope and tags the response when any read was stale
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+
+use crate::infrastructure::cache::stale_read::track_stale_reads;
+
+/// Response header set to "true" when part of the response came from expired cache
+pub const STALE_RESPONSE_HEADER: &str = "x-tao-stale";
+
+/// Middleware that marks responses built from stale cache entries
+pub async fn stale_read_middleware(request: Request, next: Next) -> Response {
+    let (mut response, stale) = track_stale_reads(next.run(request)).await;
+
+    if stale {
+        response
+            .headers_mut()
+            .insert(STALE_RESPONSE_HEADER, HeaderValue::from_static("true"));
+    }
+
+    response
+}
@@ -18,7 +18,7 @@ use axum::{
 /// - **Clean API**: No need for explicit .clone_arc() calls in most cases
 ///
 /// ## Usage:
-/// ```rust
+/// ```rust,ignore
 /// async fn handler(vc: Vc, Json(data): Json<RequestData>) -> impl IntoResponse {
 ///     // Access fields directly - feels like a reference
 ///     println!("User: {:?}", vc.user_id);
@@ -39,6 +39,11 @@ impl Vc {
         Self(vc)
     }
     
+    /// Get a reference to the ViewerContext
+    pub fn get(&self) -> &ViewerContext {
+        &self.0
+    }
+    
     /// Get the inner Arc<ViewerContext> (rarely needed)
     pub fn arc(self) -> Arc<ViewerContext> {
         self.0
@@ -101,75 +106,36 @@ where
 mod tests {
     use super::*;
     use crate::infrastructure::{
-        tao_core::tao_core::TaoOperations,
+        association_registry::AssociationRegistry,
+        query_router::{QueryRouterConfig, TaoQueryRouter},
+        tao_core::{tao::Tao, tao_core::{TaoCore, TaoOperations}},
         viewer::viewer::ViewerContext,
     };
     use std::sync::Arc;
 
-    // Mock TaoOperations for testing
-    struct MockTao;
-    
-    #[async_trait::async_trait]
-    impl TaoOperations for MockTao {
-        async fn get_object(&self, _id: i64) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
-            Ok(None)
-        }
-        
-        async fn create_object(&self, _data: Vec<u8>) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
-            Ok(1)
-        }
-        
-        // ... other required methods would be implemented for a real test
-        async fn update_object(&self, _id: i64, _data: Vec<u8>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-            Ok(())
-        }
-        
-        async fn delete_object(&self, _id: i64) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-            Ok(true)
-        }
-        
-        async fn assoc_add(&self, _assoc: crate::infrastructure::tao_core::tao_core::TaoAssociation) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-            Ok(())
-        }
-        
-        async fn assoc_get(&self, _query: crate::infrastructure::tao_core::tao_core::TaoAssocQuery) -> Result<Vec<crate::infrastructure::tao_core::tao_core::TaoAssociation>, Box<dyn std::error::Error + Send + Sync>> {
-            Ok(vec![])
-        }
-        
-        async fn assoc_delete(&self, _query: crate::infrastructure::tao_core::tao_core::TaoAssocQuery) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
-            Ok(0)
-        }
-        
-        async fn assoc_count(&self, _query: crate::infrastructure::tao_core::tao_core::TaoAssocQuery) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
-            Ok(0)
-        }
-        
-        async fn create_entity<T>(&self, _builder_state: T) -> Result<T, Box<dyn std::error::Error + Send + Sync>>
-        where
-            T: Send + Sync,
-        {
-            todo!("Mock implementation")
-        }
+    async fn test_tao() -> Arc<dyn TaoOperations> {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let tao_core = Arc::new(TaoCore::new(query_router, Arc::new(AssociationRegistry::new())));
+        Arc::new(Tao::minimal(tao_core))
     }
 
-    #[test]
-    fn test_vc_deref() {
-        let mock_tao: Arc<dyn TaoOperations> = Arc::new(MockTao);
+    #[tokio::test]
+    async fn test_vc_deref() {
         let viewer_context = Arc::new(ViewerContext::system(
             "test-request".to_string(),
-            mock_tao,
+            test_tao().await,
         ));
         let vc = Vc(viewer_context.clone());
         
         // Test that we can access ViewerContext fields directly
-        assert_eq!(vc.request_id, "test-request");
+        assert_eq!(vc.request_metadata.request_id, "test-request");
         
         // Test that get() returns a reference
         let vc_ref = vc.get();
-        assert_eq!(vc_ref.request_id, "test-request");
+        assert_eq!(vc_ref.request_metadata.request_id, "test-request");
         
         // Test that arc() returns the Arc
         let vc_arc = vc.arc();
-        assert_eq!(vc_arc.request_id, "test-request");
+        assert_eq!(vc_arc.request_metadata.request_id, "test-request");
     }
-}
\ No newline at end of file
+}
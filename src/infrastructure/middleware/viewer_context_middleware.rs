@@ -8,10 +8,13 @@ use axum::{
     response::Response,
 };
 use std::sync::Arc;
+use tokio::task_local;
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::{
     infrastructure::{
+        query_router::with_session,
         tao_core::tao_core::TaoOperations,
         viewer::viewer::ViewerContext,
     },
@@ -26,6 +29,16 @@ pub struct AuthInfo {
     pub is_authenticated: bool,
 }
 
+task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the request being handled, for responses built away from the viewer context
+/// (e.g. error bodies); `None` outside `viewer_context_middleware`
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
 /// Trait for application state that contains TAO operations
 pub trait HasTaoOperations {
     fn get_tao(&self) -> &Arc<dyn TaoOperations>;
@@ -47,11 +60,31 @@ where
     // Create appropriate ViewerContext based on authentication
     let viewer_context = create_viewer_context(auth_info, app_state.get_tao().clone())?;
     
+    // Everything logged while handling the request carries its request id
+    let span = tracing::info_span!(
+        "request",
+        request_id = %viewer_context.request_metadata.request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let request_id = viewer_context.request_metadata.request_id.clone();
+    // A viewer's requests form one session, so it reads back what it just wrote
+    let session = viewer_context.auth_info.session_id.clone().or_else(|| {
+        viewer_context
+            .user_id
+            .map(|user_id| format!("user:{}", user_id))
+    });
+
     // Inject ViewerContext into request extensions for handlers
     request.extensions_mut().insert(viewer_context);
     
     // Continue to next handler
-    Ok(next.run(request).await)
+    let response = REQUEST_ID.scope(request_id, next.run(request).instrument(span));
+    Ok(match session {
+        Some(session) => with_session(session, response).await,
+        None => response.await,
+    })
 }
 
 /// Extract authentication information from request headers
@@ -3,9 +3,11 @@ pub mod association_registry; // Manages association type mappings
 pub mod global_tao;
 pub mod id_generator; // ID generation system
 pub mod query_router; // Query routing
+pub mod shard_migrator; // Moves objects between shards
 pub mod shard_topology; // Shard management
 
 pub mod cache;
+pub mod change_feed;
 pub mod database;
 pub mod middleware;
 pub mod monitoring;
@@ -17,10 +19,12 @@ pub mod viewer;
 // Re-export core infrastructure components
 pub use database::database::{
     AssocQueryResult, DatabaseInterface, DatabaseTransaction, ObjectQueryResult, PostgresDatabase,
+    StatementTimeouts,
 };
-pub use id_generator::TaoIdGenerator;
+pub use id_generator::{IdGenerator, IdStrategy, TaoIdGenerator};
 pub use tao_core::tao_core::{
-    create_tao_association, current_time_millis, AssocType, TaoAssocQuery, TaoAssociation, TaoId,
+    create_tao_association, current_time_millis, AssocType, TaoAssocQuery, TaoAssocQueryResult,
+    TaoAssociation, TaoId,
     TaoObject, TaoObjectQuery, TaoOperations, TaoTime, TaoType,
 };
 pub use viewer::viewer::ViewerContext;
@@ -32,7 +36,7 @@ pub use cache::cache_layer::{
     initialize_cache_default, CacheConfig, CacheEntry, TaoMultiTierCache,
 };
 pub use monitoring::monitoring::{
-    initialize_metrics_default, initialize_monitoring, MetricsCollector,
+    init_logging, initialize_metrics_default, initialize_monitoring, LogFormat, MetricsCollector,
 };
 
 // Re-export new traits
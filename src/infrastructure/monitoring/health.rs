@@ -0,0 +1,224 @@
+// Health Probes - Per-component checks behind the detailed health report
+// Each probe exercises one subsystem; `MetricsCollector::perform_health_check` runs and times them
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::infrastructure::cache::cache_layer::TaoMultiTierCache;
+use crate::infrastructure::monitoring::monitoring::ServiceStatus;
+use crate::infrastructure::query_router::TaoQueryRouter;
+use crate::infrastructure::storage::write_ahead_log::TaoWriteAheadLog;
+use crate::infrastructure::tao_core::tao_core::{current_time_millis, TaoObject};
+
+/// Component names the probes report under, matching the `HealthStatus` fields
+pub const DATABASE_COMPONENT: &str = "database";
+pub const CACHE_COMPONENT: &str = "cache";
+pub const QUERY_ROUTER_COMPONENT: &str = "query_router";
+pub const WAL_COMPONENT: &str = "wal";
+
+/// Id the cache probe writes its scratch object under; never a real object id
+const CACHE_PROBE_ID: i64 = -1;
+
+/// Result of one probe: the component's status and what the probe found
+#[derive(Debug, Clone)]
+pub struct ProbeOutcome {
+    pub status: ServiceStatus,
+    pub details: String,
+}
+
+impl ProbeOutcome {
+    pub fn healthy(details: impl Into<String>) -> Self {
+        Self {
+            status: ServiceStatus::Healthy,
+            details: details.into(),
+        }
+    }
+
+    pub fn degraded(details: impl Into<String>) -> Self {
+        Self {
+            status: ServiceStatus::Degraded,
+            details: details.into(),
+        }
+    }
+
+    pub fn unhealthy(details: impl Into<String>) -> Self {
+        Self {
+            status: ServiceStatus::Unhealthy,
+            details: details.into(),
+        }
+    }
+}
+
+/// One subsystem checked by `MetricsCollector::perform_health_check`
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    /// Name the component is reported under
+    fn component(&self) -> &'static str;
+
+    /// Whether this component being unhealthy makes the whole service unhealthy,
+    /// rather than only degraded
+    fn critical(&self) -> bool;
+
+    /// Exercise the component once
+    async fn check(&self) -> ProbeOutcome;
+}
+
+/// Runs `SELECT 1` against every shard's primary database. Critical.
+pub struct DatabaseProbe {
+    query_router: Arc<TaoQueryRouter>,
+}
+
+impl DatabaseProbe {
+    pub fn new(query_router: Arc<TaoQueryRouter>) -> Self {
+        Self { query_router }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for DatabaseProbe {
+    fn component(&self) -> &'static str {
+        DATABASE_COMPONENT
+    }
+
+    fn critical(&self) -> bool {
+        true
+    }
+
+    async fn check(&self) -> ProbeOutcome {
+        let mut shards = self.query_router.get_all_shards().await;
+        shards.sort_unstable();
+        let mut failures = Vec::new();
+        for &shard_id in &shards {
+            let result = match self.query_router.get_database_for_shard(shard_id).await {
+                Ok(database) => database.health_check().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                failures.push(format!("shard {}: {}", shard_id, e));
+            }
+        }
+
+        if failures.is_empty() {
+            ProbeOutcome::healthy(format!("{} shard database(s) reachable", shards.len()))
+        } else {
+            ProbeOutcome::unhealthy(failures.join("; "))
+        }
+    }
+}
+
+/// Compares the shards the router considers healthy with every registered one. Critical.
+pub struct QueryRouterProbe {
+    query_router: Arc<TaoQueryRouter>,
+}
+
+impl QueryRouterProbe {
+    pub fn new(query_router: Arc<TaoQueryRouter>) -> Self {
+        Self { query_router }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for QueryRouterProbe {
+    fn component(&self) -> &'static str {
+        QUERY_ROUTER_COMPONENT
+    }
+
+    fn critical(&self) -> bool {
+        true
+    }
+
+    async fn check(&self) -> ProbeOutcome {
+        let total = self.query_router.get_all_shards().await.len();
+        let healthy = self
+            .query_router
+            .shard_manager
+            .get_healthy_shards()
+            .await
+            .len();
+        let details = format!("{} of {} shards healthy", healthy, total);
+        if healthy == 0 {
+            ProbeOutcome::unhealthy(details)
+        } else if healthy < total {
+            ProbeOutcome::degraded(details)
+        } else {
+            ProbeOutcome::healthy(details)
+        }
+    }
+}
+
+/// Puts a scratch object in the cache and reads it back. Not critical: reads fall
+/// back to the database while the cache is down.
+pub struct CacheProbe {
+    cache: Arc<TaoMultiTierCache>,
+}
+
+impl CacheProbe {
+    pub fn new(cache: Arc<TaoMultiTierCache>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for CacheProbe {
+    fn component(&self) -> &'static str {
+        CACHE_COMPONENT
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+
+    async fn check(&self) -> ProbeOutcome {
+        let now = current_time_millis();
+        let probe = TaoObject {
+            id: CACHE_PROBE_ID,
+            otype: "health_probe".to_string(),
+            data: now.to_le_bytes().to_vec(),
+            created_time: now,
+            updated_time: now,
+            version: 1,
+        };
+        if let Err(e) = self.cache.put_object(CACHE_PROBE_ID, &probe).await {
+            return ProbeOutcome::unhealthy(format!("put failed: {}", e));
+        }
+        let read = self.cache.get_object(CACHE_PROBE_ID).await;
+        let _ = self.cache.invalidate_object(CACHE_PROBE_ID).await;
+        match read {
+            Ok(Some(object)) if object.data == probe.data => {
+                ProbeOutcome::healthy("put and get round-tripped")
+            }
+            Ok(_) => ProbeOutcome::degraded("get did not return the object just put"),
+            Err(e) => ProbeOutcome::unhealthy(format!("get failed: {}", e)),
+        }
+    }
+}
+
+/// Writes and reads back a scratch file in the WAL directory. Not critical: the WAL
+/// logs after the database write, so a failing WAL loses replay, not the write.
+pub struct WalProbe {
+    wal: Arc<TaoWriteAheadLog>,
+}
+
+impl WalProbe {
+    pub fn new(wal: Arc<TaoWriteAheadLog>) -> Self {
+        Self { wal }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for WalProbe {
+    fn component(&self) -> &'static str {
+        WAL_COMPONENT
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+
+    async fn check(&self) -> ProbeOutcome {
+        match self.wal.probe_storage() {
+            Ok(()) => ProbeOutcome::healthy("storage write-read probe succeeded"),
+            Err(e) => ProbeOutcome::unhealthy(e.to_string()),
+        }
+    }
+}
@@ -1 +1,3 @@
+pub mod health;
 pub mod monitoring;
+pub mod query_plan;
@@ -1,23 +1,90 @@
 // Production-grade Monitoring and Observability
 // Implements comprehensive metrics, tracing, and health monitoring
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::monitoring::health::{
+    HealthProbe, CACHE_COMPONENT, DATABASE_COMPONENT, QUERY_ROUTER_COMPONENT, WAL_COMPONENT,
+};
+use crate::infrastructure::shard_topology::ShardId;
 use crate::infrastructure::tao_core::tao_core::TaoId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{RwLock, RwLockWriteGuard};
+use tracing::Subscriber;
 use tracing::{info, instrument};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+/// Number of independent accumulators request samples are spread across
+const REQUEST_ACCUMULATOR_SHARDS: usize = 16;
+/// Distinct `op` label values exported; further operations are summed into `op="other"`
+const MAX_EXPORTED_OPERATIONS: usize = 128;
+/// Upper bounds (exclusive) of the latency histogram buckets, with their labels;
+/// the last bucket is open-ended
+const HISTOGRAM_BUCKETS: [(f64, &str); 5] = [
+    (10.0, "0-10ms"),
+    (50.0, "10-50ms"),
+    (100.0, "50-100ms"),
+    (500.0, "100-500ms"),
+    (f64::INFINITY, "500ms+"),
+];
+
+/// Which slow queries get a full record kept. Every slow query is still counted.
+#[derive(Debug, Clone, Copy)]
+pub struct SamplingConfig {
+    /// Queries slower than this count as slow
+    pub slow_query_threshold_ms: f64,
+    /// Fraction of slow queries kept as `SlowQueryRecord`s, from 0.0 (none) to 1.0 (all)
+    pub trace_sample_rate: f64,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            slow_query_threshold_ms: 100.0,
+            trace_sample_rate: 1.0,
+        }
+    }
+}
+
+/// Whether to keep this call when keeping roughly `rate` of them, spread evenly rather
+/// than in runs. `counter` counts the calls made so far.
+pub(crate) fn sample_evenly(counter: &AtomicU64, rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    // splitmix64 finalizer over the call counter
+    let mut x = counter
+        .fetch_add(1, Ordering::Relaxed)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    (x as f64) < rate * u64::MAX as f64
+}
 
 /// Comprehensive metrics collector
 #[derive(Debug)]
 pub struct MetricsCollector {
-    /// Request metrics
+    /// Request metrics (aggregate, updated on flush)
     request_metrics: Arc<RwLock<RequestMetrics>>,
-    /// Database metrics
+    /// Hot request counters and per-shard pending samples
+    request_accumulator: RequestAccumulator,
+    /// Database lookups routed to each shard
+    shard_requests: Mutex<HashMap<ShardId, u64>>,
+    /// Lag of each shard's read replicas at the last check, in replica order
+    replica_lag_ms: Mutex<HashMap<ShardId, Vec<Option<i64>>>>,
+    /// Database metrics (averages, histograms and samples; counters live in `database_counters`)
     database_metrics: Arc<RwLock<DatabaseMetrics>>,
+    /// Database counters, bumped without a lock so no count is ever dropped
+    database_counters: DatabaseCounters,
     /// Cache metrics
     cache_metrics: Arc<RwLock<CacheMetrics>>,
     /// System metrics
@@ -26,6 +93,13 @@ pub struct MetricsCollector {
     business_metrics: Arc<RwLock<BusinessMetrics>>,
     /// Health status
     health_status: Arc<RwLock<HealthStatus>>,
+    /// Probes run and failed so far, per component
+    health_checks: Mutex<HashMap<&'static str, (u64, u64)>>,
+    sampling: SamplingConfig,
+    /// Slow queries seen so far; hashed to make the sample decision
+    sample_counter: AtomicU64,
+    /// Samples thrown away because their lock was busy, rather than waiting on it
+    dropped_samples: AtomicU64,
 }
 
 /// Request-level metrics
@@ -52,6 +126,132 @@ pub struct EndpointMetrics {
     pub last_called: Option<SystemTime>,
 }
 
+/// Lock-free hot path for `record_request`.
+/// Counters are atomics; per-endpoint samples land in one of several small
+/// accumulators (picked round-robin, skipping busy ones) and are folded into
+/// `RequestMetrics` on flush. Samples are aggregated as they are recorded, so an
+/// accumulator's size depends on the number of endpoints, not on how long it has
+/// been since the last flush.
+#[derive(Debug)]
+struct RequestAccumulator {
+    total_requests: AtomicU64,
+    successful_requests: AtomicU64,
+    failed_requests: AtomicU64,
+    next_shard: AtomicUsize,
+    shards: Vec<Mutex<PendingRequestSamples>>,
+}
+
+#[derive(Debug, Default)]
+struct PendingRequestSamples {
+    endpoints: HashMap<String, PendingEndpointSamples>,
+    durations: PendingHistogram,
+}
+
+/// Latency samples folded into `HISTOGRAM_BUCKETS` counts plus count, sum, min and max
+#[derive(Debug, Default)]
+struct PendingHistogram {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    buckets: [u64; HISTOGRAM_BUCKETS.len()],
+}
+
+impl PendingHistogram {
+    fn record(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.sum += value;
+        self.buckets[histogram_bucket(value)] += 1;
+    }
+}
+
+/// Index into `HISTOGRAM_BUCKETS` of the bucket `value` falls in
+fn histogram_bucket(value: f64) -> usize {
+    HISTOGRAM_BUCKETS
+        .iter()
+        .position(|&(upper, _)| value < upper)
+        .unwrap_or(HISTOGRAM_BUCKETS.len() - 1)
+}
+
+#[derive(Debug, Default)]
+struct PendingEndpointSamples {
+    success_count: u64,
+    error_count: u64,
+    total_time_ms: f64,
+    last_called: Option<SystemTime>,
+}
+
+impl RequestAccumulator {
+    fn new() -> Self {
+        Self {
+            total_requests: AtomicU64::new(0),
+            successful_requests: AtomicU64::new(0),
+            failed_requests: AtomicU64::new(0),
+            next_shard: AtomicUsize::new(0),
+            shards: (0..REQUEST_ACCUMULATOR_SHARDS)
+                .map(|_| Mutex::new(PendingRequestSamples::default()))
+                .collect(),
+        }
+    }
+
+    /// Count a request and keep its sample; false when every accumulator was busy and
+    /// the sample was dropped, though the request is still counted
+    fn record(&self, endpoint: &str, duration_ms: f64, success: bool) -> bool {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        if success {
+            self.successful_requests.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed_requests.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let first = self.next_shard.fetch_add(1, Ordering::Relaxed);
+        let pending = (0..self.shards.len()).find_map(|offset| {
+            match self.shards[(first + offset) % self.shards.len()].try_lock() {
+                Ok(pending) => Some(pending),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            }
+        });
+        let Some(mut pending) = pending else {
+            return false;
+        };
+
+        pending.durations.record(duration_ms);
+        let endpoint_samples = match pending.endpoints.get_mut(endpoint) {
+            Some(samples) => samples,
+            None => pending.endpoints.entry(endpoint.to_string()).or_default(),
+        };
+        if success {
+            endpoint_samples.success_count += 1;
+        } else {
+            endpoint_samples.error_count += 1;
+        }
+        endpoint_samples.total_time_ms += duration_ms;
+        endpoint_samples.last_called = Some(SystemTime::now());
+        true
+    }
+
+    /// Take everything recorded since the last drain
+    fn drain(&self) -> Vec<PendingRequestSamples> {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut pending = shard
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                std::mem::take(&mut *pending)
+            })
+            .collect()
+    }
+}
+
 /// Database performance metrics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DatabaseMetrics {
@@ -61,9 +261,43 @@ pub struct DatabaseMetrics {
     pub query_times: HistogramMetrics,
     pub connection_pool_stats: ConnectionPoolStats,
     pub queries_by_type: HashMap<String, QueryTypeMetrics>,
+    /// Sampled records of recent slow queries
     pub slow_queries: Vec<SlowQueryRecord>,
+    /// Every slow query, sampled or not
+    pub slow_queries_total: u64,
     pub deadlocks: u64,
     pub timeouts: u64,
+    /// Calls that waited for a concurrency-limit permit
+    pub concurrency_queued: u64,
+    /// Calls shed because no concurrency-limit permit freed up in time
+    pub concurrency_rejected: u64,
+}
+
+/// The `DatabaseMetrics` counters, as atomics so recording them never needs the lock
+#[derive(Debug, Default)]
+struct DatabaseCounters {
+    total_queries: AtomicU64,
+    successful_queries: AtomicU64,
+    failed_queries: AtomicU64,
+    slow_queries_total: AtomicU64,
+    deadlocks: AtomicU64,
+    timeouts: AtomicU64,
+    concurrency_queued: AtomicU64,
+    concurrency_rejected: AtomicU64,
+}
+
+impl DatabaseCounters {
+    /// Copy the current counts into a snapshot's `metrics`
+    fn fill(&self, metrics: &mut DatabaseMetrics) {
+        metrics.total_queries = self.total_queries.load(Ordering::Relaxed);
+        metrics.successful_queries = self.successful_queries.load(Ordering::Relaxed);
+        metrics.failed_queries = self.failed_queries.load(Ordering::Relaxed);
+        metrics.slow_queries_total = self.slow_queries_total.load(Ordering::Relaxed);
+        metrics.deadlocks = self.deadlocks.load(Ordering::Relaxed);
+        metrics.timeouts = self.timeouts.load(Ordering::Relaxed);
+        metrics.concurrency_queued = self.concurrency_queued.load(Ordering::Relaxed);
+        metrics.concurrency_rejected = self.concurrency_rejected.load(Ordering::Relaxed);
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -154,7 +388,7 @@ pub struct HealthStatus {
     pub services: HashMap<String, ComponentHealth>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ServiceStatus {
     Healthy,
     Degraded,
@@ -195,48 +429,121 @@ impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             request_metrics: Arc::new(RwLock::new(RequestMetrics::default())),
+            request_accumulator: RequestAccumulator::new(),
+            shard_requests: Mutex::new(HashMap::new()),
+            replica_lag_ms: Mutex::new(HashMap::new()),
             database_metrics: Arc::new(RwLock::new(DatabaseMetrics::default())),
+            database_counters: DatabaseCounters::default(),
             cache_metrics: Arc::new(RwLock::new(CacheMetrics::default())),
             system_metrics: Arc::new(RwLock::new(SystemMetrics::default())),
             business_metrics: Arc::new(RwLock::new(BusinessMetrics::default())),
             health_status: Arc::new(RwLock::new(HealthStatus::default())),
+            health_checks: Mutex::new(HashMap::new()),
+            sampling: SamplingConfig::default(),
+            sample_counter: AtomicU64::new(0),
+            dropped_samples: AtomicU64::new(0),
+        }
+    }
+
+    /// Use `sampling` to decide which slow queries are recorded
+    pub fn with_sampling(mut self, sampling: SamplingConfig) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Keep roughly `trace_sample_rate` of calls, spread evenly rather than in runs
+    fn sample(&self) -> bool {
+        sample_evenly(&self.sample_counter, self.sampling.trace_sample_rate)
+    }
+
+    /// Samples dropped so far because recording them would have had to wait on a lock
+    pub fn dropped_samples(&self) -> u64 {
+        self.dropped_samples.load(Ordering::Relaxed)
+    }
+
+    /// Write access to `lock` if it is free right now. A busy lock counts a dropped
+    /// sample instead: recording sits on the data path and must never wait on a
+    /// snapshot or a stalled monitoring task.
+    fn try_write<'a, T>(&self, lock: &'a RwLock<T>) -> Option<RwLockWriteGuard<'a, T>> {
+        let guard = lock.try_write().ok();
+        if guard.is_none() {
+            self.dropped_samples.fetch_add(1, Ordering::Relaxed);
         }
+        guard
     }
 
     /// Record a request completion
+    /// Never waits on a lock - samples are folded in by `flush_request_metrics`
     #[instrument(skip(self))]
     pub async fn record_request(&self, endpoint: &str, duration: Duration, success: bool) {
+        let duration_ms = duration.as_millis() as f64;
+        if !self
+            .request_accumulator
+            .record(endpoint, duration_ms, success)
+        {
+            self.dropped_samples.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Fold pending request samples into the aggregate request metrics.
+    /// Runs periodically from the monitoring task and before every snapshot.
+    pub async fn flush_request_metrics(&self) {
+        let drained = self.request_accumulator.drain();
         let mut metrics = self.request_metrics.write().await;
 
-        metrics.total_requests += 1;
-        if success {
-            metrics.successful_requests += 1;
-        } else {
-            metrics.failed_requests += 1;
-        }
+        for pending in drained {
+            self.merge_histogram(&mut metrics.response_times, &pending.durations);
 
-        let duration_ms = duration.as_millis() as f64;
-        self.update_histogram(&mut metrics.response_times, duration_ms);
+            for (endpoint, samples) in pending.endpoints {
+                let endpoint_metrics = metrics.requests_per_endpoint.entry(endpoint).or_default();
 
-        let endpoint_metrics = metrics
-            .requests_per_endpoint
-            .entry(endpoint.to_string())
-            .or_insert_with(EndpointMetrics::default);
+                let previous_calls = endpoint_metrics.total_calls;
+                let new_calls = samples.success_count + samples.error_count;
+                endpoint_metrics.total_calls += new_calls;
+                endpoint_metrics.success_count += samples.success_count;
+                endpoint_metrics.error_count += samples.error_count;
 
-        endpoint_metrics.total_calls += 1;
-        if success {
-            endpoint_metrics.success_count += 1;
-        } else {
-            endpoint_metrics.error_count += 1;
+                // Update running average (simplified)
+                endpoint_metrics.avg_response_time_ms = (endpoint_metrics.avg_response_time_ms
+                    * previous_calls as f64
+                    + samples.total_time_ms)
+                    / endpoint_metrics.total_calls as f64;
+
+                if samples.last_called > endpoint_metrics.last_called {
+                    endpoint_metrics.last_called = samples.last_called;
+                }
+            }
         }
 
-        // Update running average (simplified)
-        endpoint_metrics.avg_response_time_ms = (endpoint_metrics.avg_response_time_ms
-            * (endpoint_metrics.total_calls - 1) as f64
-            + duration_ms)
-            / endpoint_metrics.total_calls as f64;
+        metrics.total_requests = self
+            .request_accumulator
+            .total_requests
+            .load(Ordering::Relaxed);
+        metrics.successful_requests = self
+            .request_accumulator
+            .successful_requests
+            .load(Ordering::Relaxed);
+        metrics.failed_requests = self
+            .request_accumulator
+            .failed_requests
+            .load(Ordering::Relaxed);
+    }
+
+    /// Record a request routed to `shard_id`'s database
+    pub fn record_shard_request(&self, shard_id: ShardId) {
+        let mut shard_requests = self
+            .shard_requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *shard_requests.entry(shard_id).or_insert(0) += 1;
+    }
 
-        endpoint_metrics.last_called = Some(SystemTime::now());
+    /// Record the lag of each of `shard_id`'s replicas; `None` marks a failed check
+    pub fn record_replica_lag(&self, shard_id: ShardId, lags_ms: Vec<Option<i64>>) {
+        self.replica_lag_ms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(shard_id, lags_ms);
     }
 
     /// Record a database query
@@ -249,16 +556,22 @@ impl MetricsCollector {
         success: bool,
         rows_affected: u64,
     ) {
-        let mut metrics = self.database_metrics.write().await;
-
-        metrics.total_queries += 1;
+        let counters = &self.database_counters;
+        counters.total_queries.fetch_add(1, Ordering::Relaxed);
         if success {
-            metrics.successful_queries += 1;
+            counters.successful_queries.fetch_add(1, Ordering::Relaxed);
         } else {
-            metrics.failed_queries += 1;
+            counters.failed_queries.fetch_add(1, Ordering::Relaxed);
         }
-
         let duration_ms = duration.as_millis() as f64;
+        let slow = duration_ms > self.sampling.slow_query_threshold_ms;
+        if slow {
+            counters.slow_queries_total.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let Some(mut metrics) = self.try_write(&self.database_metrics) else {
+            return;
+        };
         self.update_histogram(&mut metrics.query_times, duration_ms);
 
         // Track by query type
@@ -273,8 +586,10 @@ impl MetricsCollector {
         query_metrics.max_time_ms = query_metrics.max_time_ms.max(duration_ms);
         query_metrics.rows_affected += rows_affected;
 
-        // Record slow queries (> 100ms)
-        if duration_ms > 100.0 {
+        if slow {
+            if !self.sample() {
+                return;
+            }
             if metrics.slow_queries.len() >= 100 {
                 metrics.slow_queries.remove(0); // Keep only last 100
             }
@@ -289,10 +604,40 @@ impl MetricsCollector {
         }
     }
 
+    /// Record a transaction retried after a deadlock or serialization failure
+    pub async fn record_deadlock(&self) {
+        self.database_counters
+            .deadlocks
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a query cancelled by its statement timeout
+    pub async fn record_timeout(&self) {
+        self.database_counters
+            .timeouts
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a call that had to wait for a concurrency-limit permit
+    pub async fn record_concurrency_queued(&self) {
+        self.database_counters
+            .concurrency_queued
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a call rejected by the concurrency limiter
+    pub async fn record_concurrency_rejected(&self) {
+        self.database_counters
+            .concurrency_rejected
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Record cache operation
     #[instrument(skip(self))]
     pub async fn record_cache_operation(&self, hit: bool, lookup_time: Duration) {
-        let mut metrics = self.cache_metrics.write().await;
+        let Some(mut metrics) = self.try_write(&self.cache_metrics) else {
+            return;
+        };
 
         // Update average lookup time
         let total_lookups =
@@ -313,7 +658,9 @@ impl MetricsCollector {
     /// Record business metric
     #[instrument(skip(self))]
     pub async fn record_business_event(&self, event: &str) {
-        let mut metrics = self.business_metrics.write().await;
+        let Some(mut metrics) = self.try_write(&self.business_metrics) else {
+            return;
+        };
 
         match event {
             "UserRegistered" => metrics.new_user_registrations += 1,
@@ -342,26 +689,56 @@ impl MetricsCollector {
         metrics.uptime_seconds = self.get_uptime().await;
     }
 
-    /// Perform comprehensive health check
-    #[instrument(skip(self))]
-    pub async fn perform_health_check(&self) -> HealthStatus {
-        let mut health = HealthStatus::default();
-        health.last_health_check = Some(SystemTime::now());
-
-        // Check database health
-        health.database_status = self.check_database_health().await;
-
-        // Check cache health
-        health.cache_status = self.check_cache_health().await;
-
-        // Check query router health
-        health.query_router_status = self.check_query_router_health().await;
-
-        // Check WAL health
-        health.wal_status = self.check_wal_health().await;
+    /// Run every probe and record the result as the current health status.
+    /// The service is unhealthy when a critical component is, and degraded when any
+    /// other component is not healthy. Components without a probe stay `Unknown`.
+    #[instrument(skip(self, probes))]
+    pub async fn perform_health_check(&self, probes: &[Arc<dyn HealthProbe>]) -> HealthStatus {
+        let mut health = HealthStatus {
+            last_health_check: Some(SystemTime::now()),
+            health_check_failures: self.health_status.read().await.health_check_failures,
+            overall_status: ServiceStatus::Healthy,
+            ..HealthStatus::default()
+        };
 
-        // Determine overall status
-        health.overall_status = self.determine_overall_status(&health);
+        for probe in probes {
+            let started = Instant::now();
+            let outcome = probe.check().await;
+            let response_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+            let error_rate = self
+                .record_health_check(probe.component(), outcome.status != ServiceStatus::Healthy);
+
+            match outcome.status {
+                ServiceStatus::Healthy => {}
+                ServiceStatus::Unhealthy if probe.critical() => {
+                    health.overall_status = ServiceStatus::Unhealthy
+                }
+                _ if health.overall_status == ServiceStatus::Healthy => {
+                    health.overall_status = ServiceStatus::Degraded
+                }
+                _ => {}
+            }
+            match probe.component() {
+                DATABASE_COMPONENT => health.database_status = outcome.status.clone(),
+                CACHE_COMPONENT => health.cache_status = outcome.status.clone(),
+                QUERY_ROUTER_COMPONENT => health.query_router_status = outcome.status.clone(),
+                WAL_COMPONENT => health.wal_status = outcome.status.clone(),
+                _ => {}
+            }
+            health.services.insert(
+                probe.component().to_string(),
+                ComponentHealth {
+                    status: outcome.status,
+                    last_check: SystemTime::now(),
+                    response_time_ms,
+                    error_rate,
+                    details: outcome.details,
+                },
+            );
+        }
+        if health.overall_status != ServiceStatus::Healthy {
+            health.health_check_failures += 1;
+        }
 
         // Update stored health status
         {
@@ -372,15 +749,45 @@ impl MetricsCollector {
         health
     }
 
+    /// Count one probe of `component`, returning its failed fraction of probes so far
+    fn record_health_check(&self, component: &'static str, failed: bool) -> f64 {
+        let mut checks = self
+            .health_checks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (total, failures) = checks.entry(component).or_default();
+        *total += 1;
+        *failures += failed as u64;
+        *failures as f64 / *total as f64
+    }
+
     /// Get comprehensive metrics snapshot
     pub async fn get_metrics_snapshot(&self) -> MetricsSnapshot {
+        self.flush_request_metrics().await;
+        let shard_requests = self
+            .shard_requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let replica_lag_ms = self
+            .replica_lag_ms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+
+        let mut database_metrics = self.database_metrics.read().await.clone();
+        self.database_counters.fill(&mut database_metrics);
+
         MetricsSnapshot {
             request_metrics: self.request_metrics.read().await.clone(),
-            database_metrics: self.database_metrics.read().await.clone(),
+            shard_requests,
+            replica_lag_ms,
+            database_metrics,
             cache_metrics: self.cache_metrics.read().await.clone(),
             system_metrics: self.system_metrics.read().await.clone(),
             business_metrics: self.business_metrics.read().await.clone(),
             health_status: self.health_status.read().await.clone(),
+            dropped_samples: self.dropped_samples(),
             snapshot_time: SystemTime::now(),
         }
     }
@@ -409,6 +816,87 @@ impl MetricsCollector {
             snapshot.request_metrics.response_times.count
         ));
 
+        // Per-operation series. Operation names come from a fixed set (the TAO methods),
+        // but anything unexpected is folded into "other" to keep label cardinality bounded.
+        let mut operations: Vec<(&str, u64, f64)> = Vec::new();
+        let mut other = (0u64, 0.0f64);
+        let mut endpoints: Vec<_> = snapshot
+            .request_metrics
+            .requests_per_endpoint
+            .iter()
+            .collect();
+        endpoints.sort_by(|a, b| a.0.cmp(b.0));
+        for (op, endpoint) in endpoints {
+            let seconds = endpoint.avg_response_time_ms * endpoint.total_calls as f64 / 1000.0;
+            if is_label_safe(op) && operations.len() < MAX_EXPORTED_OPERATIONS {
+                operations.push((op, endpoint.total_calls, seconds));
+            } else {
+                other.0 += endpoint.total_calls;
+                other.1 += seconds;
+            }
+        }
+        if other.0 > 0 {
+            operations.push(("other", other.0, other.1));
+        }
+
+        output.push_str(
+            "# HELP tao_operation_total Total number of TAO operations\n\
+             # TYPE tao_operation_total counter\n",
+        );
+        for (op, calls, _) in &operations {
+            output.push_str(&format!("tao_operation_total{{op=\"{}\"}} {}\n", op, calls));
+        }
+        output.push('\n');
+
+        output.push_str(
+            "# HELP tao_operation_duration_seconds TAO operation duration in seconds\n\
+             # TYPE tao_operation_duration_seconds summary\n",
+        );
+        for (op, calls, seconds) in &operations {
+            output.push_str(&format!(
+                "tao_operation_duration_seconds_sum{{op=\"{}\"}} {}\n\
+                 tao_operation_duration_seconds_count{{op=\"{}\"}} {}\n",
+                op, seconds, op, calls
+            ));
+        }
+        output.push('\n');
+
+        // Per-shard series; shard ids are numeric, so there is one series per shard
+        let mut shards: Vec<_> = snapshot.shard_requests.iter().collect();
+        shards.sort();
+        output.push_str(
+            "# HELP tao_shard_requests_total Total number of requests routed to each shard\n\
+             # TYPE tao_shard_requests_total counter\n",
+        );
+        for (shard_id, requests) in shards {
+            output.push_str(&format!(
+                "tao_shard_requests_total{{shard=\"{}\"}} {}\n",
+                shard_id, requests
+            ));
+        }
+        output.push('\n');
+
+        // Replicas whose last check failed have no current lag and are left out
+        let mut replica_lags: Vec<_> = snapshot.replica_lag_ms.iter().collect();
+        replica_lags.sort();
+        output.push_str(
+            "# HELP tao_replica_lag_seconds How far each read replica is behind its primary\n\
+             # TYPE tao_replica_lag_seconds gauge\n",
+        );
+        for (shard_id, lags) in replica_lags {
+            for (replica, lag_ms) in lags.iter().enumerate() {
+                if let Some(lag_ms) = lag_ms {
+                    output.push_str(&format!(
+                        "tao_replica_lag_seconds{{shard=\"{}\",replica=\"{}\"}} {}\n",
+                        shard_id,
+                        replica,
+                        *lag_ms as f64 / 1000.0
+                    ));
+                }
+            }
+        }
+        output.push('\n');
+
         // Database metrics
         output.push_str(&format!(
             "# HELP tao_database_queries_total Total number of database queries\n\
@@ -433,6 +921,13 @@ impl MetricsCollector {
             snapshot.business_metrics.active_users
         ));
 
+        output.push_str(&format!(
+            "# HELP tao_metrics_dropped_samples_total Metric samples dropped because their lock was busy\n\
+             # TYPE tao_metrics_dropped_samples_total counter\n\
+             tao_metrics_dropped_samples_total {}\n\n",
+            snapshot.dropped_samples
+        ));
+
         output
     }
 
@@ -449,20 +944,31 @@ impl MetricsCollector {
             histogram.max = histogram.max.max(value);
         }
 
-        // Update buckets (simplified)
-        let bucket = if value < 10.0 {
-            "0-10ms"
-        } else if value < 50.0 {
-            "10-50ms"
-        } else if value < 100.0 {
-            "50-100ms"
-        } else if value < 500.0 {
-            "100-500ms"
+        let bucket = HISTOGRAM_BUCKETS[histogram_bucket(value)].1;
+        *histogram.buckets.entry(bucket.to_string()).or_insert(0) += 1;
+    }
+
+    /// Fold samples aggregated by a `PendingHistogram` into `histogram`
+    fn merge_histogram(&self, histogram: &mut HistogramMetrics, pending: &PendingHistogram) {
+        if pending.count == 0 {
+            return;
+        }
+
+        if histogram.count == 0 {
+            histogram.min = pending.min;
+            histogram.max = pending.max;
         } else {
-            "500ms+"
-        };
+            histogram.min = histogram.min.min(pending.min);
+            histogram.max = histogram.max.max(pending.max);
+        }
+        histogram.count += pending.count;
+        histogram.sum += pending.sum;
 
-        *histogram.buckets.entry(bucket.to_string()).or_insert(0) += 1;
+        for (&(_, label), &count) in HISTOGRAM_BUCKETS.iter().zip(&pending.buckets) {
+            if count > 0 {
+                *histogram.buckets.entry(label.to_string()).or_insert(0) += count;
+            }
+        }
     }
 
     // System metric collection helpers (would use real system APIs in production)
@@ -484,61 +990,108 @@ impl MetricsCollector {
     async fn get_uptime(&self) -> u64 {
         0
     }
+}
 
-    // Health check helpers
-    async fn check_database_health(&self) -> ServiceStatus {
-        ServiceStatus::Healthy
-    }
-    async fn check_cache_health(&self) -> ServiceStatus {
-        ServiceStatus::Healthy
-    }
-    async fn check_query_router_health(&self) -> ServiceStatus {
-        ServiceStatus::Healthy
-    }
-    async fn check_wal_health(&self) -> ServiceStatus {
-        ServiceStatus::Healthy
-    }
-
-    fn determine_overall_status(&self, health: &HealthStatus) -> ServiceStatus {
-        // Simple logic: if any critical component is unhealthy, overall is unhealthy
-        if matches!(health.database_status, ServiceStatus::Unhealthy)
-            || matches!(health.query_router_status, ServiceStatus::Unhealthy)
-        {
-            ServiceStatus::Unhealthy
-        } else if matches!(health.database_status, ServiceStatus::Degraded)
-            || matches!(health.cache_status, ServiceStatus::Degraded)
-            || matches!(health.query_router_status, ServiceStatus::Degraded)
-        {
-            ServiceStatus::Degraded
-        } else {
-            ServiceStatus::Healthy
-        }
-    }
+/// Operation names are exported as label values verbatim, so only plain identifiers qualify
+fn is_label_safe(op: &str) -> bool {
+    !op.is_empty()
+        && op
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsSnapshot {
     pub request_metrics: RequestMetrics,
+    pub shard_requests: HashMap<ShardId, u64>,
+    pub replica_lag_ms: HashMap<ShardId, Vec<Option<i64>>>,
     pub database_metrics: DatabaseMetrics,
     pub cache_metrics: CacheMetrics,
     pub system_metrics: SystemMetrics,
     pub business_metrics: BusinessMetrics,
     pub health_status: HealthStatus,
+    /// Samples dropped rather than waiting on a busy metrics lock
+    pub dropped_samples: u64,
     pub snapshot_time: SystemTime,
 }
 
-/// Initialize comprehensive monitoring
-pub fn initialize_monitoring() -> AppResult<Arc<MetricsCollector>> {
-    // Initialize tracing subscriber
+/// Log line format, selected with the `LOG_FORMAT` environment variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines, for local development
+    #[default]
+    Pretty,
+    /// One JSON object per line, for log aggregators
+    Json,
+}
+
+impl LogFormat {
+    /// Read `LOG_FORMAT` (`json` or `pretty`); unset means `pretty`
+    pub fn from_env() -> AppResult<Self> {
+        Self::parse(std::env::var("LOG_FORMAT").ok().as_deref())
+    }
+
+    fn parse(value: Option<&str>) -> AppResult<Self> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("pretty") => Ok(LogFormat::Pretty),
+            Some("json") => Ok(LogFormat::Json),
+            Some(other) => Err(AppError::ConfigurationError(format!(
+                "LOG_FORMAT must be json or pretty, got '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Formatting layer for `format`, writing to `writer`.
+/// JSON lines put the event's fields at the top level and the `#[instrument]` fields of the
+/// current span and its parents (object ids, atypes, the request span's `request_id`)
+/// under `span` and `spans`, as structured keys rather than text.
+pub fn log_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync + 'static>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+/// Install the global tracing subscriber, honouring `RUST_LOG` and `LOG_FORMAT`
+pub fn init_logging() -> AppResult<()> {
+    let format = LogFormat::from_env()?;
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+        .with(log_layer(format, std::io::stdout))
+        .try_init()
+        .map_err(|e| AppError::ConfigurationError(format!("Failed to initialize logging: {}", e)))
+}
+
+/// Initialize comprehensive monitoring
+pub fn initialize_monitoring() -> AppResult<Arc<MetricsCollector>> {
+    init_logging()?;
 
     let metrics_collector = Arc::new(MetricsCollector::new());
 
+    // Fold batched request samples into the aggregate view
+    let flush_collector = Arc::clone(&metrics_collector);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            flush_collector.flush_request_metrics().await;
+        }
+    });
+
     // Start background metrics collection
     let collector_clone = Arc::clone(&metrics_collector);
     tokio::spawn(async move {
@@ -546,7 +1099,6 @@ pub fn initialize_monitoring() -> AppResult<Arc<MetricsCollector>> {
         loop {
             interval.tick().await;
             collector_clone.update_system_metrics().await;
-            collector_clone.perform_health_check().await;
         }
     });
 
@@ -558,3 +1110,281 @@ pub fn initialize_monitoring() -> AppResult<Arc<MetricsCollector>> {
 pub async fn initialize_metrics_default() -> AppResult<Arc<MetricsCollector>> {
     initialize_monitoring()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_metrics_flushed_into_snapshot() {
+        let collector = MetricsCollector::new();
+        collector
+            .record_request("obj_get", Duration::from_millis(4), true)
+            .await;
+        collector
+            .record_request("obj_get", Duration::from_millis(8), false)
+            .await;
+        collector
+            .record_request("assoc_add", Duration::from_millis(20), true)
+            .await;
+
+        let snapshot = collector.get_metrics_snapshot().await;
+        let request_metrics = snapshot.request_metrics;
+        assert_eq!(request_metrics.total_requests, 3);
+        assert_eq!(request_metrics.successful_requests, 2);
+        assert_eq!(request_metrics.failed_requests, 1);
+        assert_eq!(request_metrics.response_times.count, 3);
+        assert_eq!(request_metrics.response_times.sum, 32.0);
+        assert_eq!(request_metrics.response_times.min, 4.0);
+        assert_eq!(request_metrics.response_times.max, 20.0);
+        assert_eq!(request_metrics.response_times.buckets["0-10ms"], 2);
+        assert_eq!(request_metrics.response_times.buckets["10-50ms"], 1);
+
+        let obj_get = &request_metrics.requests_per_endpoint["obj_get"];
+        assert_eq!(obj_get.total_calls, 2);
+        assert_eq!(obj_get.error_count, 1);
+        assert_eq!(obj_get.avg_response_time_ms, 6.0);
+    }
+
+    #[tokio::test]
+    async fn test_zero_sample_rate_counts_slow_queries_without_keeping_them() {
+        let sampling = SamplingConfig {
+            slow_query_threshold_ms: 10.0,
+            trace_sample_rate: 0.0,
+        };
+        let metrics = MetricsCollector::new().with_sampling(sampling);
+        for _ in 0..5 {
+            metrics
+                .record_database_query("select", "SELECT 1", Duration::from_millis(50), true, 1)
+                .await;
+        }
+        metrics
+            .record_database_query("select", "SELECT 1", Duration::from_millis(5), true, 1)
+            .await;
+
+        let database = metrics.get_metrics_snapshot().await.database_metrics;
+        assert_eq!(database.total_queries, 6);
+        assert_eq!(database.slow_queries_total, 5);
+        assert!(database.slow_queries.is_empty());
+
+        let metrics = MetricsCollector::new().with_sampling(SamplingConfig {
+            trace_sample_rate: 0.5,
+            ..sampling
+        });
+        let kept = (0..1000).filter(|_| metrics.sample()).count();
+        assert!((400..600).contains(&kept), "kept {} of 1000", kept);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_record_request_does_not_wait_on_aggregate_lock() {
+        let collector = Arc::new(MetricsCollector::new());
+
+        // Hold the aggregate lock for the whole run - recording must still make progress
+        let guard = collector.request_metrics.write().await;
+
+        let tasks: Vec<_> = (0..32)
+            .map(|task| {
+                let collector = Arc::clone(&collector);
+                tokio::spawn(async move {
+                    let endpoint = format!("op_{}", task % 4);
+                    for _ in 0..1_000 {
+                        collector
+                            .record_request(&endpoint, Duration::from_micros(50), true)
+                            .await;
+                    }
+                })
+            })
+            .collect();
+
+        let all_recorded =
+            tokio::time::timeout(Duration::from_secs(5), futures::future::join_all(tasks)).await;
+        assert!(
+            all_recorded.is_ok(),
+            "record_request blocked on the aggregate lock"
+        );
+        drop(guard);
+
+        let snapshot = collector.get_metrics_snapshot().await;
+        assert_eq!(snapshot.request_metrics.total_requests, 32_000);
+        let per_endpoint: u64 = snapshot
+            .request_metrics
+            .requests_per_endpoint
+            .values()
+            .map(|endpoint| endpoint.total_calls)
+            .sum();
+        assert_eq!(per_endpoint, 32_000);
+    }
+
+    #[tokio::test]
+    async fn test_database_counters_are_kept_while_the_metrics_lock_is_busy() {
+        let collector = MetricsCollector::new();
+        let busy = collector.database_metrics.write().await;
+        collector.record_deadlock().await;
+        collector.record_timeout().await;
+        collector.record_concurrency_queued().await;
+        collector.record_concurrency_rejected().await;
+        collector
+            .record_database_query("select", "SELECT 1", Duration::from_millis(500), false, 0)
+            .await;
+        drop(busy);
+
+        let database = collector.get_metrics_snapshot().await.database_metrics;
+        assert_eq!((database.deadlocks, database.timeouts), (1, 1));
+        assert_eq!(
+            (database.concurrency_queued, database.concurrency_rejected),
+            (1, 1)
+        );
+        assert_eq!((database.total_queries, database.failed_queries), (1, 1));
+        assert_eq!(database.slow_queries_total, 1);
+        // Only the query's timing sample needed the lock
+        assert_eq!(database.query_times.count, 0);
+        assert_eq!(collector.dropped_samples(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tao_operations_drop_samples_instead_of_waiting_on_metrics_locks() {
+        use crate::infrastructure::association_registry::AssociationRegistry;
+        use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+        use crate::infrastructure::id_generator::TaoIdGenerator;
+        use crate::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
+        use crate::infrastructure::shard_topology::{ShardHealth, ShardInfo};
+        use crate::infrastructure::tao_core::tao_core::{
+            current_time_millis, TaoCore, TaoOperations,
+        };
+        use crate::infrastructure::tao_core::tao_decorators::{BaseTao, MetricsDecorator};
+
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        query_router.add_shard(shard_info, database).await.unwrap();
+        let tao_core = Arc::new(TaoCore::new(
+            query_router,
+            Arc::new(AssociationRegistry::new()),
+        ));
+        let collector = Arc::new(MetricsCollector::new());
+        let tao = MetricsDecorator::new(Arc::new(BaseTao::new(tao_core)), collector.clone());
+        let ids = TaoIdGenerator::new(0);
+
+        // A stalled snapshot: every lock recording could need is held
+        let business = collector.business_metrics.write().await;
+        let accumulators: Vec<_> = collector
+            .request_accumulator
+            .shards
+            .iter()
+            .map(|shard| shard.lock().unwrap())
+            .collect();
+
+        let id = ids.next_id();
+        let completed = tokio::time::timeout(Duration::from_secs(5), async {
+            tao.create_object(id, "user".to_string(), vec![1]).await?;
+            tao.obj_get(id).await
+        })
+        .await
+        .expect("TAO operations blocked on the metrics locks");
+        assert!(completed.unwrap().is_some());
+        // create_object's request and business event, and obj_get's request
+        assert_eq!(collector.dropped_samples(), 3);
+
+        drop(accumulators);
+        drop(business);
+        tao.obj_get(id).await.unwrap();
+        let snapshot = collector.get_metrics_snapshot().await;
+        assert_eq!(snapshot.dropped_samples, 3);
+        assert_eq!(snapshot.request_metrics.total_requests, 3);
+        assert_eq!(
+            snapshot.request_metrics.requests_per_endpoint["obj_get"].total_calls,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_export_has_labeled_series() {
+        let collector = MetricsCollector::new();
+        for ms in [4, 8] {
+            collector
+                .record_request("obj_get", Duration::from_millis(ms), true)
+                .await;
+        }
+        collector
+            .record_request("assoc_get", Duration::from_millis(20), false)
+            .await;
+        collector
+            .record_request("GET /api/{id}", Duration::from_millis(1), true)
+            .await;
+        for shard_id in [3, 3, 0] {
+            collector.record_shard_request(shard_id);
+        }
+
+        let export = collector.export_prometheus_metrics().await;
+        let series: HashMap<&str, f64> = export
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (name, value) = line.rsplit_once(' ').unwrap();
+                (name, value.parse().unwrap())
+            })
+            .collect();
+
+        assert_eq!(series["tao_operation_total{op=\"obj_get\"}"], 2.0);
+        assert_eq!(series["tao_operation_total{op=\"assoc_get\"}"], 1.0);
+        assert_eq!(series["tao_operation_total{op=\"other\"}"], 1.0);
+        assert_eq!(
+            series["tao_operation_duration_seconds_sum{op=\"obj_get\"}"],
+            0.012
+        );
+        assert_eq!(
+            series["tao_operation_duration_seconds_count{op=\"assoc_get\"}"],
+            1.0
+        );
+        assert_eq!(series["tao_shard_requests_total{shard=\"3\"}"], 2.0);
+        assert_eq!(series["tao_shard_requests_total{shard=\"0\"}"], 1.0);
+        assert_eq!(series["tao_requests_total"], 4.0);
+    }
+
+    #[test]
+    fn test_json_log_format_emits_structured_lines() {
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Buffer {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        assert_eq!(LogFormat::parse(Some("JSON")).unwrap(), LogFormat::Json);
+        assert_eq!(LogFormat::parse(None).unwrap(), LogFormat::Pretty);
+        assert!(LogFormat::parse(Some("xml")).is_err());
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber =
+            tracing_subscriber::registry().with(log_layer(LogFormat::Json, move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let request = tracing::info_span!("request", request_id = "req-1");
+            let _request = request.enter();
+            let operation = tracing::info_span!("obj_get", object_id = 42, atype = "friends");
+            let _operation = operation.enter();
+            info!(shard = 3, "Cache miss");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["message"], "Cache miss");
+        assert_eq!(line["shard"], 3);
+        assert_eq!(line["span"]["object_id"], 42);
+        assert_eq!(line["span"]["atype"], "friends");
+        assert_eq!(line["spans"][0]["request_id"], "req-1");
+    }
+}
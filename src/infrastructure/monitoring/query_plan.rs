@@ -0,0 +1,84 @@
+// Query Plan Capture - Opt-in per-shard breakdown of scatter-gather TAO operations
+// Enabled for one scope with `explain`; outside of one, recording a shard span is a no-op
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::task_local;
+
+use crate::infrastructure::shard_topology::ShardId;
+
+/// One shard's part of an operation: the query sent to it and how long it took
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardSpan {
+    pub operation: String,
+    pub shard_id: ShardId,
+    pub query: String,
+    pub duration_ms: f64,
+}
+
+/// Every shard span recorded while explaining a scope, in the order they finished
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryPlan {
+    pub spans: Vec<ShardSpan>,
+    pub total_ms: f64,
+}
+
+/// Collects the shard spans of one `explain` scope
+#[derive(Debug, Default)]
+pub struct PerformanceProfiler {
+    spans: Mutex<Vec<ShardSpan>>,
+}
+
+impl PerformanceProfiler {
+    fn record(&self, span: ShardSpan) {
+        if let Ok(mut spans) = self.spans.lock() {
+            spans.push(span);
+        }
+    }
+
+    fn into_plan(self, started: Instant) -> QueryPlan {
+        QueryPlan {
+            spans: self.spans.into_inner().unwrap_or_default(),
+            total_ms: started.elapsed().as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+task_local! {
+    static PROFILER: Arc<PerformanceProfiler>;
+}
+
+/// Run `f` with query plan capture enabled.
+/// Returns the output of `f` and the shard spans of every operation inside it.
+pub async fn explain<F>(f: F) -> (F::Output, QueryPlan)
+where
+    F: Future,
+{
+    let profiler = Arc::new(PerformanceProfiler::default());
+    let started = Instant::now();
+    let output = PROFILER.scope(profiler.clone(), f).await;
+    let plan = Arc::try_unwrap(profiler)
+        .map(|profiler| profiler.into_plan(started))
+        .unwrap_or_default();
+    (output, plan)
+}
+
+/// Record that `operation` sent `query` to `shard_id`, starting at `started`. `query` is
+/// only built inside an `explain` scope, so describing it costs nothing otherwise.
+pub fn record_shard_span(
+    operation: &str,
+    shard_id: ShardId,
+    started: Instant,
+    query: impl FnOnce() -> String,
+) {
+    let _ = PROFILER.try_with(|profiler| {
+        profiler.record(ShardSpan {
+            operation: operation.to_string(),
+            shard_id,
+            query: query(),
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        })
+    });
+}
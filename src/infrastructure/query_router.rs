@@ -1,16 +1,32 @@
 use rand;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task_local;
+use tracing::warn;
 
 use crate::error::{AppError, AppResult};
-use crate::infrastructure::id_generator::TaoIdGenerator;
+use crate::infrastructure::cache::data_source::{record_data_source, DataSource};
+use crate::infrastructure::cache::read_consistency::{current_read_consistency, ReadConsistency};
+use crate::infrastructure::database::database::{DatabaseTransaction, StatementTimeouts};
+use crate::infrastructure::database::dsn::redact_dsn;
+use crate::infrastructure::id_generator::{IdGenerator, SnowflakeIdGenerator};
+use crate::infrastructure::monitoring::monitoring::MetricsCollector;
 use crate::infrastructure::shard_topology::{
     ConsistentHashingShardManager, ShardHealth, ShardId, ShardInfo, ShardManager, ShardTopology,
 };
+use crate::infrastructure::tao_core::request_context::{
+    charge_units, POINT_READ_UNITS, SCATTER_UNITS_PER_SHARD,
+};
 use crate::infrastructure::tao_core::tao_core::TaoId;
 
+/// Healthy shards whose load factors are this close are considered balanced
+pub const REBALANCE_TOLERANCE: f64 = 0.1;
+
 /// Information about a specific shard (no operations, just metadata)
 #[derive(Debug, Clone)]
 pub struct TaoShardInfo {
@@ -31,6 +47,61 @@ impl TaoShardInfo {
     }
 }
 
+task_local! {
+    static MAX_STALENESS_MS: u64;
+}
+
+/// Run `f` with replica reads inside it limited to replicas at most `max_staleness_ms`
+/// behind their primary. Reads with no replica that fresh go to the primary.
+pub async fn with_max_staleness<F>(max_staleness_ms: u64, f: F) -> F::Output
+where
+    F: Future,
+{
+    MAX_STALENESS_MS.scope(max_staleness_ms, f).await
+}
+
+/// Staleness budget requested by the enclosing scope, if any
+pub fn current_max_staleness() -> Option<u64> {
+    MAX_STALENESS_MS.try_with(|budget| *budget).ok()
+}
+
+task_local! {
+    static SESSION: String;
+}
+
+/// Run `f` as part of `session`, e.g. one user's requests. For a while after the session
+/// writes an object, its reads of that object skip the replicas; see
+/// `QueryRouterConfig::read_your_writes_window_ms`.
+pub async fn with_session<F>(session: String, f: F) -> F::Output
+where
+    F: Future,
+{
+    SESSION.scope(session, f).await
+}
+
+/// Session of the enclosing scope, if any
+pub fn current_session() -> Option<String> {
+    SESSION.try_with(|session| session.clone()).ok()
+}
+
+/// Above this many tracked sessions, ones whose window has closed are dropped
+const MAX_TRACKED_SESSIONS: usize = 10_000;
+
+/// Objects a session wrote since its window last closed
+#[derive(Debug)]
+struct SessionWrites {
+    last_write_at: Instant,
+    objects: HashSet<TaoId>,
+}
+
+/// A read replica of one shard
+struct ShardReplica {
+    database: Arc<dyn crate::infrastructure::DatabaseInterface>,
+    /// Lag behind the primary at the last check; `None` until measured (or when the
+    /// check failed), and such a replica is never read from
+    lag_ms: Option<i64>,
+}
+
 /// The main TAO Query Router - Provides database instances for operations
 /// This determines which shard to route requests to and provides the database connection
 pub struct TaoQueryRouter {
@@ -39,8 +110,16 @@ pub struct TaoQueryRouter {
     /// Database instances for each shard (initialized at startup)
     shard_databases:
         Arc<RwLock<HashMap<ShardId, Arc<dyn crate::infrastructure::DatabaseInterface>>>>,
+    /// Read replicas of each shard, kept in sync by the database itself
+    shard_replicas: Arc<RwLock<HashMap<ShardId, Vec<ShardReplica>>>>,
     /// Router configuration
     config: QueryRouterConfig,
+    /// Strategy for assigning ids; also decides which shard an id routes to
+    id_generator: Arc<dyn IdGenerator>,
+    /// Receives a count for every database handed out, per shard
+    metrics: Option<Arc<MetricsCollector>>,
+    /// Recent writes of each session, whose reads of those objects go to the primary
+    session_writes: Mutex<HashMap<String, SessionWrites>>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +128,16 @@ pub struct QueryRouterConfig {
     pub health_check_interval_ms: u64,
     pub max_retry_attempts: u32,
     pub enable_read_from_replicas: bool,
+    /// Furthest a replica may lag its primary and still serve reads, unless a read
+    /// sets its own budget with `with_max_staleness`
+    pub max_replica_staleness_ms: u64,
+    /// Statement timeout for point reads and association queries on each shard
+    pub read_statement_timeout_ms: u64,
+    /// Statement timeout for full scans such as get_all_objects_of_type
+    pub scan_statement_timeout_ms: u64,
+    /// For this long after a session's last write, its reads of the objects it wrote
+    /// go to the primary, so it reads its own writes; 0 turns this off
+    pub read_your_writes_window_ms: u64,
 }
 
 impl Default for QueryRouterConfig {
@@ -58,12 +147,24 @@ impl Default for QueryRouterConfig {
             health_check_interval_ms: 30_000, // 30 seconds
             max_retry_attempts: 3,
             enable_read_from_replicas: true,
+            max_replica_staleness_ms: 5_000,
+            read_statement_timeout_ms: 5_000,
+            scan_statement_timeout_ms: 30_000,
+            read_your_writes_window_ms: 1_000,
         }
     }
 }
 
 impl TaoQueryRouter {
     pub async fn new(config: QueryRouterConfig) -> Self {
+        Self::with_id_generator(config, Arc::new(SnowflakeIdGenerator::default())).await
+    }
+
+    /// Create a router that assigns ids with the given strategy
+    pub async fn with_id_generator(
+        config: QueryRouterConfig,
+        id_generator: Arc<dyn IdGenerator>,
+    ) -> Self {
         let topology = Arc::new(RwLock::new(ShardTopology::new(config.replication_factor)));
         let shard_manager = Arc::new(ConsistentHashingShardManager::new(topology));
         let shard_databases = Arc::new(RwLock::new(HashMap::new()));
@@ -71,7 +172,25 @@ impl TaoQueryRouter {
         Self {
             shard_manager,
             shard_databases,
+            shard_replicas: Arc::new(RwLock::new(HashMap::new())),
             config,
+            id_generator,
+            metrics: None,
+            session_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count the requests routed to each shard into `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Statement timeouts applied to every shard and replica database
+    pub fn statement_timeouts(&self) -> StatementTimeouts {
+        StatementTimeouts {
+            read_timeout_ms: self.config.read_statement_timeout_ms,
+            scan_timeout_ms: self.config.scan_statement_timeout_ms,
         }
     }
 
@@ -83,6 +202,8 @@ impl TaoQueryRouter {
     ) -> AppResult<()> {
         let shard_id = shard_info.shard_id;
 
+        database.set_statement_timeouts(self.statement_timeouts());
+
         // Add to topology
         {
             self.shard_manager.add_shard(shard_info).await;
@@ -101,6 +222,116 @@ impl TaoQueryRouter {
         Ok(())
     }
 
+    /// Add a read replica of an existing shard. It serves no reads until
+    /// `refresh_replica_lag` has measured it.
+    pub async fn add_replica(
+        &self,
+        shard_id: ShardId,
+        database: Arc<dyn crate::infrastructure::DatabaseInterface>,
+    ) -> AppResult<()> {
+        if !self.shard_databases.read().await.contains_key(&shard_id) {
+            return Err(AppError::ShardError(format!(
+                "Shard {} does not exist",
+                shard_id
+            )));
+        }
+        database.set_statement_timeouts(self.statement_timeouts());
+        self.shard_replicas
+            .write()
+            .await
+            .entry(shard_id)
+            .or_default()
+            .push(ShardReplica {
+                database,
+                lag_ms: None,
+            });
+        Ok(())
+    }
+
+    /// Measure every replica's lag as its primary's write high-watermark minus its own
+    pub async fn refresh_replica_lag(&self) {
+        let targets: Vec<_> = {
+            let replicas = self.shard_replicas.read().await;
+            replicas
+                .iter()
+                .map(|(shard_id, replicas)| {
+                    let databases: Vec<_> = replicas
+                        .iter()
+                        .map(|replica| replica.database.clone())
+                        .collect();
+                    (*shard_id, databases)
+                })
+                .collect()
+        };
+
+        for (shard_id, databases) in targets {
+            let primary = match self.shard_databases.read().await.get(&shard_id).cloned() {
+                Some(primary) => primary.write_high_watermark().await,
+                None => continue,
+            };
+            let mut lags = Vec::with_capacity(databases.len());
+            for (index, database) in databases.iter().enumerate() {
+                let watermark = database.write_high_watermark().await;
+                let lag = match (&primary, &watermark) {
+                    (Ok(primary), Ok(replica)) => Some((primary - replica).max(0)),
+                    (Err(e), _) | (_, Err(e)) => {
+                        warn!(
+                            "Lag check failed for replica {} of shard {}: {}",
+                            index, shard_id, e
+                        );
+                        None
+                    }
+                };
+                lags.push(lag);
+            }
+
+            if let Some(metrics) = &self.metrics {
+                metrics.record_replica_lag(shard_id, lags.clone());
+            }
+            if let Some(replicas) = self.shard_replicas.write().await.get_mut(&shard_id) {
+                for (replica, lag) in replicas.iter_mut().zip(lags) {
+                    replica.lag_ms = lag;
+                }
+            }
+        }
+    }
+
+    /// Re-measure replica lag every `health_check_interval_ms`
+    pub fn spawn_replica_lag_monitor(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let router = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(
+                router.config.health_check_interval_ms,
+            ));
+            loop {
+                interval.tick().await;
+                router.refresh_replica_lag().await;
+            }
+        })
+    }
+
+    /// Mark a shard's primary healthy, or `Degraded` to take it into maintenance. A shard
+    /// in maintenance serves reads from its replicas and rejects writes.
+    pub async fn set_shard_health(&self, shard_id: ShardId, health: ShardHealth) -> AppResult<()> {
+        if !self.shard_databases.read().await.contains_key(&shard_id) {
+            return Err(AppError::ShardError(format!(
+                "Shard {} does not exist",
+                shard_id
+            )));
+        }
+        self.shard_manager
+            .update_shard_health(shard_id, health)
+            .await;
+        Ok(())
+    }
+
+    async fn in_maintenance(&self, shard_id: ShardId) -> bool {
+        self.shard_manager
+            .get_shard_info(shard_id)
+            .await
+            .is_some_and(|info| info.health == ShardHealth::Degraded)
+    }
+
     /// =========================================================================
     /// ROUTING METHODS - Pure routing logic, provides database instances
     /// =========================================================================
@@ -110,9 +341,24 @@ impl TaoQueryRouter {
         self.shard_manager.get_shard_for_owner(owner_id).await
     }
 
-    /// Determine which shard contains an object based on object ID
-    pub async fn get_shard_for_object(&self, object_id: i64) -> ShardId {
-        self.shard_manager.get_shard_for_object(object_id).await
+    /// Determine which shard contains an object based on object ID. Read straight from
+    /// the id's shard bits, so it takes no lock, allocates nothing and costs the same
+    /// however many shards there are.
+    pub fn get_shard_for_object(&self, object_id: i64) -> ShardId {
+        self.id_generator.shard_of(object_id)
+    }
+
+    /// Begin a transaction on `database`, the database of `shard_id`. Its `*_tx` writes
+    /// fail for any id this router places on another shard.
+    pub async fn begin_shard_transaction(
+        &self,
+        shard_id: ShardId,
+        database: &Arc<dyn crate::infrastructure::DatabaseInterface>,
+    ) -> AppResult<DatabaseTransaction> {
+        Ok(database
+            .begin_transaction()
+            .await?
+            .bind_to_shard(shard_id, self.id_generator.clone()))
     }
 
     /// Get database instance for a shard - This is the key method TAO uses
@@ -120,48 +366,102 @@ impl TaoQueryRouter {
         &self,
         shard_id: ShardId,
     ) -> AppResult<Arc<dyn crate::infrastructure::DatabaseInterface>> {
-        let databases = self.shard_databases.read().await;
-        databases.get(&shard_id).cloned().ok_or_else(|| {
-            AppError::ShardError(format!("Database for shard {} not available", shard_id))
-        })
+        self.database_for_shard(shard_id, POINT_READ_UNITS).await
+    }
+
+    /// Get database instance for one shard of a scatter-gather query, which costs the
+    /// request `SCATTER_UNITS_PER_SHARD` rather than a point read
+    pub async fn get_database_for_scan(
+        &self,
+        shard_id: ShardId,
+    ) -> AppResult<Arc<dyn crate::infrastructure::DatabaseInterface>> {
+        self.database_for_shard(shard_id, SCATTER_UNITS_PER_SHARD)
+            .await
+    }
+
+    async fn database_for_shard(
+        &self,
+        shard_id: ShardId,
+        units: u64,
+    ) -> AppResult<Arc<dyn crate::infrastructure::DatabaseInterface>> {
+        let database = self
+            .shard_databases
+            .read()
+            .await
+            .get(&shard_id)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::ShardError(format!("Database for shard {} not available", shard_id))
+            })?;
+        if self.in_maintenance(shard_id).await {
+            return Err(AppError::ServiceUnavailable(format!(
+                "Shard {} is in maintenance",
+                shard_id
+            )));
+        }
+        self.record_request(shard_id, units);
+        Ok(database)
+    }
+
+    /// Count a request to `shard_id` and charge its cost to the calling request
+    fn record_request(&self, shard_id: ShardId, units: u64) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_shard_request(shard_id);
+        }
+        charge_units(units);
     }
 
     /// Generate a new TAO ID with proper shard placement
     /// If owner_id is provided, colocate with the owner; otherwise assign random shard
     pub async fn generate_tao_id(&self, owner_id: Option<TaoId>) -> AppResult<TaoId> {
-        if let Some(owner_id) = owner_id {
-            // Extract shard from owner_id for colocation
-            let owner_shard_id = TaoIdGenerator::extract_shard_id(owner_id);
-            let id_generator = TaoIdGenerator::new(owner_shard_id);
-            Ok(id_generator.next_id())
-        } else {
-            // No owner - assign random shard
-            let available_shards = self.shard_manager.get_healthy_shards().await;
-            if available_shards.is_empty() {
-                return Err(AppError::ShardError(
-                    "No healthy shards available".to_string(),
-                ));
-            }
-
-            // Pick a random shard
-            use rand::Rng;
-            let mut rng = rand::rng();
-            let mut random_shard_id;
-            let mut generated_id;
-            loop {
-                let random_index = rng.random_range(0..available_shards.len());
-                random_shard_id = available_shards[random_index];
-                let id_generator = TaoIdGenerator::new(random_shard_id);
-                generated_id = id_generator.next_id();
-                // Verify that the generated ID's embedded shard ID matches the chosen random shard ID
-                if TaoIdGenerator::extract_shard_id(generated_id) == random_shard_id {
-                    break;
-                } else {
-                    println!("Generated ID's embedded shard ID does not match the chosen random shard ID. Retrying ID generation.");
+        let default_shard = match owner_id {
+            Some(owner_id) => self.id_generator.shard_of(owner_id),
+            None => {
+                // No owner - assign random shard
+                let available_shards = self.shard_manager.get_healthy_shards().await;
+                if available_shards.is_empty() {
+                    return Err(AppError::ShardError(
+                        "No healthy shards available".to_string(),
+                    ));
                 }
+
+                use rand::Rng;
+                let random_index = rand::rng().random_range(0..available_shards.len());
+                available_shards[random_index]
             }
-            Ok(generated_id)
+        };
+
+        let generated_id = self.id_generator.next_id(owner_id, default_shard)?;
+
+        // Ids can come from clients, so make sure they route somewhere real
+        let shard_id = self.id_generator.shard_of(generated_id);
+        if !self.shard_databases.read().await.contains_key(&shard_id) {
+            return Err(AppError::ShardError(format!(
+                "Id {} routes to shard {}, which does not exist",
+                generated_id, shard_id
+            )));
+        }
+        Ok(generated_id)
+    }
+
+    /// Generate a new TAO ID placed on a specific shard, e.g. when relocating an object
+    pub async fn generate_tao_id_on_shard(&self, shard_id: ShardId) -> AppResult<TaoId> {
+        if !self.shard_databases.read().await.contains_key(&shard_id) {
+            return Err(AppError::ShardError(format!(
+                "Shard {} does not exist",
+                shard_id
+            )));
+        }
+
+        let generated_id = self.id_generator.next_id(None, shard_id)?;
+        // A client-supplied id carries its own shard bits and may not honour the request
+        if self.id_generator.shard_of(generated_id) != shard_id {
+            return Err(AppError::ShardError(format!(
+                "Id {} does not route to shard {}",
+                generated_id, shard_id
+            )));
         }
+        Ok(generated_id)
     }
 
     /// Get database instance for an object (convenience method)
@@ -169,11 +469,132 @@ impl TaoQueryRouter {
         &self,
         object_id: i64,
     ) -> AppResult<Arc<dyn crate::infrastructure::DatabaseInterface>> {
-        let shard_id = self.get_shard_for_object(object_id).await;
+        let shard_id = self.get_shard_for_object(object_id);
         // println!("Shard for object id {} is {}", object_id, shard_id);
         self.get_database_for_shard(shard_id).await
     }
 
+    /// Database to write an object through: its primary. Stamps the calling session so
+    /// that its reads of the object come from the primary for the next while.
+    pub async fn get_write_database_for_object(
+        &self,
+        object_id: i64,
+    ) -> AppResult<Arc<dyn crate::infrastructure::DatabaseInterface>> {
+        self.record_write(object_id);
+        self.get_database_for_object(object_id).await
+    }
+
+    /// Note that the calling session wrote `object_id`; a no-op outside a session
+    pub fn record_write(&self, object_id: TaoId) {
+        let Some(session) = current_session() else {
+            return;
+        };
+        if self.config.read_your_writes_window_ms == 0 {
+            return;
+        }
+        let window = Duration::from_millis(self.config.read_your_writes_window_ms);
+        let now = Instant::now();
+
+        let mut sessions = self
+            .session_writes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if sessions.len() >= MAX_TRACKED_SESSIONS && !sessions.contains_key(&session) {
+            sessions.retain(|_, writes| now.duration_since(writes.last_write_at) < window);
+        }
+        let writes = sessions.entry(session).or_insert_with(|| SessionWrites {
+            last_write_at: now,
+            objects: HashSet::new(),
+        });
+        if now.duration_since(writes.last_write_at) >= window {
+            writes.objects.clear();
+        }
+        writes.last_write_at = now;
+        writes.objects.insert(object_id);
+    }
+
+    /// Whether the calling session wrote `object_id` within the read-your-writes window
+    fn wrote_recently(&self, object_id: TaoId) -> bool {
+        let Some(session) = current_session() else {
+            return false;
+        };
+        let window = Duration::from_millis(self.config.read_your_writes_window_ms);
+        self.session_writes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&session)
+            .is_some_and(|writes| {
+                writes.last_write_at.elapsed() < window && writes.objects.contains(&object_id)
+            })
+    }
+
+    /// Database to serve a read of an object from: the least lagged replica within the
+    /// staleness budget, or the primary when there is none. Fresh reads, reads of an
+    /// object the session just wrote and routers with replica reads disabled always use
+    /// the primary.
+    ///
+    /// A shard in maintenance serves every read from its least lagged measured replica,
+    /// however stale, and is `ServiceUnavailable` without one so that the cache can
+    /// answer with stale entries where it is allowed to.
+    pub async fn get_read_database_for_object(
+        &self,
+        object_id: i64,
+    ) -> AppResult<Arc<dyn crate::infrastructure::DatabaseInterface>> {
+        let shard_id = self.get_shard_for_object(object_id);
+        if self.in_maintenance(shard_id).await {
+            let replica = self
+                .shard_replicas
+                .read()
+                .await
+                .get(&shard_id)
+                .and_then(|replicas| {
+                    replicas
+                        .iter()
+                        .filter_map(|replica| Some((replica.lag_ms?, &replica.database)))
+                        .min_by_key(|(lag, _)| *lag)
+                        .map(|(_, database)| database.clone())
+                });
+            return match replica {
+                Some(replica) => {
+                    self.record_request(shard_id, POINT_READ_UNITS);
+                    record_data_source(DataSource::Replica);
+                    Ok(replica)
+                }
+                None => Err(AppError::ServiceUnavailable(format!(
+                    "Shard {} is in maintenance and has no measured replica",
+                    shard_id
+                ))),
+            };
+        }
+        if self.config.enable_read_from_replicas
+            && current_read_consistency() == ReadConsistency::Cached
+            && !self.wrote_recently(object_id)
+        {
+            let budget = current_max_staleness().unwrap_or(self.config.max_replica_staleness_ms);
+            let replica = self
+                .shard_replicas
+                .read()
+                .await
+                .get(&shard_id)
+                .and_then(|replicas| {
+                    replicas
+                        .iter()
+                        .filter_map(|replica| Some((replica.lag_ms?, &replica.database)))
+                        .filter(|(lag, _)| *lag as u64 <= budget)
+                        .min_by_key(|(lag, _)| *lag)
+                        .map(|(_, database)| database.clone())
+                });
+            if let Some(replica) = replica {
+                self.record_request(shard_id, POINT_READ_UNITS);
+                record_data_source(DataSource::Replica);
+                return Ok(replica);
+            }
+        }
+        let database = self.get_database_for_shard(shard_id).await?;
+        record_data_source(DataSource::Primary);
+        Ok(database)
+    }
+
     /// Get database instance for an owner (convenience method)
     pub async fn get_database_for_owner(
         &self,
@@ -189,6 +610,96 @@ impl TaoQueryRouter {
         databases.keys().copied().collect()
     }
 
+    /// Topology info and live pool statistics for every registered shard, ordered by shard id
+    pub async fn shard_report(&self) -> Vec<ShardReport> {
+        let databases: Vec<_> = {
+            let databases = self.shard_databases.read().await;
+            databases
+                .iter()
+                .map(|(shard_id, database)| (*shard_id, database.clone()))
+                .collect()
+        };
+
+        let mut reports = Vec::with_capacity(databases.len());
+        for (shard_id, database) in databases {
+            let Some(mut info) = self.shard_manager.get_shard_info(shard_id).await else {
+                continue;
+            };
+            info.connection_string = redact_dsn(&info.connection_string);
+            let (idle_connections, pool_size) = database.pool_stats();
+            let replica_lag_ms = self
+                .shard_replicas
+                .read()
+                .await
+                .get(&shard_id)
+                .map(|replicas| replicas.iter().map(|replica| replica.lag_ms).collect())
+                .unwrap_or_default();
+            reports.push(ShardReport {
+                info,
+                idle_connections,
+                pool_size,
+                replica_lag_ms,
+            });
+        }
+
+        reports.sort_by_key(|report| report.info.shard_id);
+        reports
+    }
+
+    /// Suggest id ranges to move from hot to cold shards until every healthy shard's
+    /// load factor is within `REBALANCE_TOLERANCE` of the others. Nothing is moved: the
+    /// ranges are for an operator to review and then relocate with `ShardMigrator`.
+    ///
+    /// A shard's load is assumed to be spread evenly over its objects and their outgoing
+    /// associations. Shards above the mean load are read in full to find their objects,
+    /// which costs as much as a graph export of those shards.
+    pub async fn rebalance_recommendations(&self) -> AppResult<Vec<RebalanceMove>> {
+        let mut shard_ids = self.get_all_shards().await;
+        shard_ids.sort_unstable();
+        let mut loads = Vec::with_capacity(shard_ids.len());
+        for shard_id in shard_ids {
+            match self.shard_manager.get_shard_info(shard_id).await {
+                Some(info) if info.health == ShardHealth::Healthy => {
+                    loads.push((shard_id, info.load_factor))
+                }
+                _ => {}
+            }
+        }
+        if loads.len() < 2 {
+            return Ok(Vec::new());
+        }
+
+        let mean = loads.iter().map(|(_, load)| load).sum::<f64>() / loads.len() as f64;
+        let mut shards = Vec::with_capacity(loads.len());
+        for (shard_id, load_factor) in loads {
+            let mut objects = Vec::new();
+            if load_factor > mean {
+                let database = self.get_database_for_shard(shard_id).await?;
+                let mut associations: HashMap<TaoId, u64> = HashMap::new();
+                for assoc in database.get_all_associations_from_shard().await? {
+                    *associations.entry(assoc.id1).or_default() += 1;
+                }
+                objects = database
+                    .get_all_objects_from_shard()
+                    .await?
+                    .into_iter()
+                    .map(|object| {
+                        let count = associations.get(&object.id).copied().unwrap_or(0);
+                        (object.id, count)
+                    })
+                    .collect();
+                objects.sort_unstable();
+            }
+            shards.push(ShardLoad {
+                shard_id,
+                load_factor,
+                objects,
+            });
+        }
+
+        Ok(plan_rebalance(shards, REBALANCE_TOLERANCE))
+    }
+
     /// =========================================================================
     /// EXECUTION METHODS - Executes operations on their respective shards
     /// =========================================================================
@@ -218,6 +729,105 @@ impl TaoQueryRouter {
     }
 }
 
+/// Operational snapshot of a single shard
+#[derive(Debug, Clone, Serialize)]
+pub struct ShardReport {
+    #[serde(flatten)]
+    pub info: ShardInfo,
+    pub idle_connections: u32,
+    pub pool_size: u32,
+    /// Lag of each read replica at the last check, `None` if not yet measured
+    pub replica_lag_ms: Vec<Option<i64>>,
+}
+
+/// One suggested move: the objects of `from_shard` with ids in `first_id..=last_id`,
+/// with their outgoing associations, should go to `to_shard`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RebalanceMove {
+    pub from_shard: ShardId,
+    pub to_shard: ShardId,
+    pub first_id: TaoId,
+    pub last_id: TaoId,
+    pub objects: u64,
+    pub associations: u64,
+    /// Load factor expected to move with the range
+    pub load: f64,
+}
+
+/// Input to `plan_rebalance`. `objects` are `(id, outgoing association count)` in id
+/// order, and only filled in for shards that may give load away.
+struct ShardLoad {
+    shard_id: ShardId,
+    load_factor: f64,
+    objects: Vec<(TaoId, u64)>,
+}
+
+/// Repeatedly move the lowest remaining ids of the hottest shard to the coldest one,
+/// each time taking about as much load as brings either of them to the mean
+fn plan_rebalance(mut shards: Vec<ShardLoad>, tolerance: f64) -> Vec<RebalanceMove> {
+    let mut moves = Vec::new();
+    if shards.len() < 2 {
+        return moves;
+    }
+    let mean = shards.iter().map(|shard| shard.load_factor).sum::<f64>() / shards.len() as f64;
+    // Load carried by one object or association, as measured before anything moves
+    let unit_load: Vec<f64> = shards
+        .iter()
+        .map(|shard| {
+            let units: u64 = shard.objects.iter().map(|(_, assocs)| 1 + assocs).sum();
+            if units == 0 {
+                0.0
+            } else {
+                shard.load_factor / units as f64
+            }
+        })
+        .collect();
+    let mut next_object = vec![0; shards.len()];
+
+    loop {
+        let by_load =
+            |a: &usize, b: &usize| shards[*a].load_factor.total_cmp(&shards[*b].load_factor);
+        let Some(coldest) = (0..shards.len()).min_by(by_load) else {
+            break;
+        };
+        let Some(hottest) = (0..shards.len())
+            .filter(|i| next_object[*i] < shards[*i].objects.len() && unit_load[*i] > 0.0)
+            .max_by(by_load)
+        else {
+            break;
+        };
+        let (hot_load, cold_load) = (shards[hottest].load_factor, shards[coldest].load_factor);
+        let wanted = (hot_load - mean).min(mean - cold_load);
+        if hot_load - cold_load <= tolerance || wanted <= 0.0 {
+            break;
+        }
+
+        let start = next_object[hottest];
+        let mut end = start;
+        let (mut associations, mut load) = (0, 0.0);
+        while end < shards[hottest].objects.len() && load < wanted {
+            let (_, assocs) = shards[hottest].objects[end];
+            load += (1 + assocs) as f64 * unit_load[hottest];
+            associations += assocs;
+            end += 1;
+        }
+        next_object[hottest] = end;
+
+        moves.push(RebalanceMove {
+            from_shard: shards[hottest].shard_id,
+            to_shard: shards[coldest].shard_id,
+            first_id: shards[hottest].objects[start].0,
+            last_id: shards[hottest].objects[end - 1].0,
+            objects: (end - start) as u64,
+            associations,
+            load,
+        });
+        shards[hottest].load_factor -= load;
+        shards[coldest].load_factor += load;
+    }
+    moves
+}
+
 #[derive(Debug, Serialize)]
 pub struct QueryRouterStats {
     pub active_connections: usize,
@@ -231,3 +841,354 @@ impl std::fmt::Debug for TaoQueryRouter {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::cache::read_consistency::with_read_consistency;
+    use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+    use crate::infrastructure::id_generator::{with_provided_id, IdStrategy, TaoIdGenerator};
+    use crate::infrastructure::tao_core::tao_core::current_time_millis;
+    use crate::infrastructure::DatabaseInterface;
+
+    async fn router_with_shard(strategy: IdStrategy, shard_id: ShardId) -> TaoQueryRouter {
+        let router =
+            TaoQueryRouter::with_id_generator(QueryRouterConfig::default(), strategy.build()).await;
+        let shard_info = ShardInfo {
+            shard_id,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        router.add_shard(shard_info, database).await.unwrap();
+        router
+    }
+
+    #[tokio::test]
+    async fn test_generated_ids_route_to_their_shard() {
+        for strategy in [IdStrategy::Snowflake, IdStrategy::Provided] {
+            let router = router_with_shard(strategy, 3).await;
+
+            let id = router.generate_tao_id(None).await.unwrap();
+            assert_eq!(router.get_shard_for_object(id), 3);
+
+            let owned = router.generate_tao_id(Some(id)).await.unwrap();
+            assert_eq!(router.get_shard_for_object(owned), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provided_id_must_route_to_existing_shard() {
+        let router = router_with_shard(IdStrategy::Provided, 3).await;
+
+        let routable = TaoIdGenerator::new(3).next_id();
+        let id = with_provided_id(routable, router.generate_tao_id(None))
+            .await
+            .unwrap();
+        assert_eq!(id, routable);
+        assert_eq!(router.get_shard_for_object(id), 3);
+
+        let unroutable = TaoIdGenerator::new(5).next_id();
+        let result = with_provided_id(unroutable, router.generate_tao_id(None)).await;
+        assert!(matches!(result, Err(AppError::ShardError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_shard_transaction_rejects_writes_for_other_shards() {
+        use crate::infrastructure::database::database::Association;
+
+        let router = router_with_shard(IdStrategy::Snowflake, 3).await;
+        let database = router.get_database_for_shard(3).await.unwrap();
+        let local = TaoIdGenerator::new(3).next_id();
+        let foreign = TaoIdGenerator::new(4).next_id();
+        let is_cross_shard = |result: AppResult<_>| {
+            matches!(result, Err(AppError::Internal(message))
+                if message.starts_with("cross-shard write in single-shard transaction"))
+        };
+
+        let mut tx = router.begin_shard_transaction(3, &database).await.unwrap();
+        database
+            .create_object_tx(&mut tx, local, "user".to_string(), vec![])
+            .await
+            .unwrap();
+        assert!(is_cross_shard(
+            database
+                .create_object_tx(&mut tx, foreign, "user".to_string(), vec![])
+                .await
+        ));
+        let edge = Association {
+            id1: foreign,
+            atype: "follows".to_string(),
+            id2: local,
+            time: 0,
+            data: None,
+            subtype: None,
+        };
+        assert!(is_cross_shard(
+            database.create_association_tx(&mut tx, edge).await
+        ));
+        tx.commit().await.unwrap();
+
+        // Only the local write went through
+        assert!(database.get_object(local).await.unwrap().is_some());
+        assert!(database.get_object(foreign).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_routing_is_constant_time_and_skips_the_shard_map_lock() {
+        let router = router_with_shard(IdStrategy::Snowflake, 3).await;
+        // One id for every possible shard, though only shard 3 is registered
+        let ids: Vec<TaoId> = (0..1024)
+            .map(|shard| TaoIdGenerator::new(shard).next_id())
+            .collect();
+
+        // Held throughout: routing must not wait on the shard map
+        let _rewriting = router.shard_databases.write().await;
+        let rounds = 100;
+        let started = Instant::now();
+        for _ in 0..rounds {
+            for (shard, id) in ids.iter().enumerate() {
+                assert_eq!(router.get_shard_for_object(*id), shard as ShardId);
+            }
+        }
+        let per_lookup = started.elapsed() / (rounds * ids.len()) as u32;
+        assert!(
+            per_lookup < Duration::from_micros(1),
+            "{:?} per lookup",
+            per_lookup
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shard_report_lists_every_shard() {
+        let router = router_with_shard(IdStrategy::Snowflake, 0).await;
+        let shard_info = ShardInfo {
+            shard_id: 1,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "eu-west".to_string(),
+            health: ShardHealth::Degraded,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.5,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        router.add_shard(shard_info, database).await.unwrap();
+
+        let report = router.shard_report().await;
+        assert_eq!(report.len(), 2);
+
+        assert_eq!(report[0].info.shard_id, 0);
+        assert_eq!(report[0].info.region, "local");
+        assert_eq!(report[0].info.health, ShardHealth::Healthy);
+
+        assert_eq!(report[1].info.shard_id, 1);
+        assert_eq!(report[1].info.region, "eu-west");
+        assert_eq!(report[1].info.health, ShardHealth::Degraded);
+        assert!(report[1].pool_size >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_replica_excluded_beyond_staleness_budget() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let router = TaoQueryRouter::new(QueryRouterConfig::default())
+            .await
+            .with_metrics(metrics.clone());
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let primary = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        router.add_shard(shard_info, primary.clone()).await.unwrap();
+
+        // The replica still has the object's previous version, written 10s ago. It is
+        // written first so the lag can't come out a millisecond short of 10s.
+        let id = TaoIdGenerator::new(0).next_id();
+        let replica = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        replica
+            .create_object(id, "ent_user".to_string(), vec![1])
+            .await
+            .unwrap();
+        primary
+            .create_object(id, "ent_user".to_string(), vec![2])
+            .await
+            .unwrap();
+        replica
+            .execute_query("UPDATE tao_objects SET time_updated = time_updated - 10000".to_string())
+            .await
+            .unwrap();
+        router.add_replica(0, replica).await.unwrap();
+
+        let read = |budget_ms| {
+            with_max_staleness(budget_ms, async {
+                let database = router.get_read_database_for_object(id).await.unwrap();
+                database.get_object(id).await.unwrap().unwrap().data
+            })
+        };
+        // Unmeasured replicas serve nothing
+        assert_eq!(read(60_000).await, vec![2]);
+
+        router.refresh_replica_lag().await;
+        let lag = router.shard_report().await[0].replica_lag_ms[0].unwrap();
+        assert!((10_000..60_000).contains(&lag), "lag {}", lag);
+        assert_eq!(read(1_000).await, vec![2]);
+        assert_eq!(read(60_000).await, vec![1]);
+        let fresh = with_read_consistency(ReadConsistency::Fresh, read(60_000)).await;
+        assert_eq!(fresh, vec![2]);
+
+        let exported = metrics.export_prometheus_metrics().await;
+        assert!(exported.contains("tao_replica_lag_seconds{shard=\"0\",replica=\"0\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_shard_in_maintenance_reads_from_replica_and_rejects_writes() {
+        let router = router_with_shard(IdStrategy::Snowflake, 0).await;
+        let id = TaoIdGenerator::new(0).next_id();
+        let replica = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        replica
+            .create_object(id, "ent_user".to_string(), vec![1])
+            .await
+            .unwrap();
+        router.add_replica(0, replica).await.unwrap();
+
+        router
+            .set_shard_health(0, ShardHealth::Degraded)
+            .await
+            .unwrap();
+        // Unmeasured replicas serve nothing, even in maintenance
+        let result = router.get_read_database_for_object(id).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+
+        router.refresh_replica_lag().await;
+        let fresh = with_read_consistency(
+            ReadConsistency::Fresh,
+            router.get_read_database_for_object(id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(fresh.get_object(id).await.unwrap().unwrap().data, vec![1]);
+
+        let write = router.get_database_for_object(id).await;
+        assert!(matches!(write, Err(AppError::ServiceUnavailable(_))));
+
+        router
+            .set_shard_health(0, ShardHealth::Healthy)
+            .await
+            .unwrap();
+        let primary = router.get_database_for_object(id).await.unwrap();
+        assert!(primary.get_object(id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_reads_its_writes_from_primary_within_window() {
+        let router = TaoQueryRouter::new(QueryRouterConfig {
+            read_your_writes_window_ms: 50,
+            ..QueryRouterConfig::default()
+        })
+        .await;
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let primary = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        router.add_shard(shard_info, primary).await.unwrap();
+
+        // The replica holds older versions of the objects and is measured as current
+        let ids = TaoIdGenerator::new(0);
+        let (id, other_id) = (ids.next_id(), ids.next_id());
+        let replica = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        replica
+            .create_object(id, "ent_user".to_string(), vec![1])
+            .await
+            .unwrap();
+        replica
+            .create_object(other_id, "ent_user".to_string(), vec![3])
+            .await
+            .unwrap();
+        router.add_replica(0, replica).await.unwrap();
+        router.refresh_replica_lag().await;
+
+        let read = |session: &'static str, id: TaoId| {
+            let router = &router;
+            with_session(session.to_string(), async move {
+                let database = router.get_read_database_for_object(id).await.unwrap();
+                database
+                    .get_object(id)
+                    .await
+                    .unwrap()
+                    .map(|object| object.data)
+            })
+        };
+
+        with_session("alice".to_string(), async {
+            let database = router.get_write_database_for_object(id).await.unwrap();
+            database
+                .create_object(id, "ent_user".to_string(), vec![2])
+                .await
+                .unwrap();
+        })
+        .await;
+
+        // Right after the write, only the writer's reads of that object skip the replica
+        assert_eq!(read("alice", id).await, Some(vec![2]));
+        assert_eq!(read("bob", id).await, Some(vec![1]));
+        assert_eq!(read("alice", other_id).await, Some(vec![3]));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(read("alice", id).await, Some(vec![1]));
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_moves_from_hottest_to_coldest_shard() {
+        let router = TaoQueryRouter::new(QueryRouterConfig::default()).await;
+        for (shard_id, load_factor) in [(0, 0.5), (1, 0.9), (2, 0.1)] {
+            let shard_info = ShardInfo {
+                shard_id,
+                connection_string: "sqlite::memory:".to_string(),
+                region: "local".to_string(),
+                health: ShardHealth::Healthy,
+                replicas: vec![],
+                last_health_check: current_time_millis(),
+                load_factor,
+            };
+            let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+            router.add_shard(shard_info, database).await.unwrap();
+        }
+
+        let hot = router.get_database_for_shard(1).await.unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..10 {
+            let id = router.generate_tao_id_on_shard(1).await.unwrap();
+            hot.create_object(id, "ent_user".to_string(), vec![1])
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        ids.sort_unstable();
+
+        let moves = router.rebalance_recommendations().await.unwrap();
+        assert_eq!(moves.len(), 1, "{:?}", moves);
+        let plan = &moves[0];
+        assert_eq!((plan.from_shard, plan.to_shard), (1, 2));
+        // 0.4 of shard 1's 0.9 is about four of its ten objects, oldest first
+        assert_eq!(plan.first_id, ids[0]);
+        assert!((4..=5).contains(&plan.objects), "{:?}", plan);
+        assert_eq!(plan.last_id, ids[plan.objects as usize - 1]);
+        assert!((plan.load - 0.4).abs() < 0.1);
+        // Nothing was moved
+        assert_eq!(hot.get_all_objects_from_shard().await.unwrap().len(), 10);
+    }
+}
@@ -0,0 +1,257 @@
+// Shard Migrator - Moves an object and its outgoing associations to another shard
+// Used when rebalancing or decommissioning shards
+
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::database::database::Association;
+use crate::infrastructure::query_router::TaoQueryRouter;
+use crate::infrastructure::shard_topology::ShardId;
+use crate::infrastructure::tao_core::tao_core::{AssocType, TaoId, TaoType};
+
+/// Outcome of migrating (or planning to migrate) one object
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub old_id: TaoId,
+    /// Id the object lives under on the target shard; `None` for a dry run
+    pub new_id: Option<TaoId>,
+    pub otype: TaoType,
+    pub from_shard: ShardId,
+    pub to_shard: ShardId,
+    /// Outgoing associations (id1 == object) copied along with the object
+    pub associations: Vec<(AssocType, TaoId)>,
+    pub dry_run: bool,
+}
+
+/// Copies an object row, its attributes and its id1-local associations onto another shard,
+/// verifies the copy, then deletes the source.
+///
+/// Constraints:
+/// - Both id strategies route by the shard bits embedded in the id, so an object can
+///   only change shard by changing id. The object is re-created under a fresh id generated
+///   on the target shard; with `IdStrategy::Provided` this means the client-supplied id
+///   does not survive the move.
+/// - Only edges stored with the object as `id1` move. Edges on other objects that point
+///   at the old id (inverse edges included) are not rewritten; callers that keep them must
+///   re-point them to `new_id`.
+/// - The object's `time_created`/`version` restart on the target, as `create_object_tx` does.
+/// - This works directly against shard databases, below the TAO decorators, so cached
+///   entries for the old id are left to expire.
+pub struct ShardMigrator {
+    query_router: Arc<TaoQueryRouter>,
+    dry_run: bool,
+}
+
+impl ShardMigrator {
+    pub fn new(query_router: Arc<TaoQueryRouter>) -> Self {
+        Self {
+            query_router,
+            dry_run: false,
+        }
+    }
+
+    /// Only report what would move, without writing to either shard
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub async fn migrate_object(&self, id: TaoId, to_shard: ShardId) -> AppResult<MigrationReport> {
+        let from_shard = self.query_router.get_shard_for_object(id);
+        if from_shard == to_shard {
+            return Err(AppError::Validation(format!(
+                "Object {} is already on shard {}",
+                id, to_shard
+            )));
+        }
+        let source = self.query_router.get_database_for_shard(from_shard).await?;
+        let target = self.query_router.get_database_for_shard(to_shard).await?;
+
+        let object = source
+            .get_object(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Object {} not found", id)))?;
+        let associations = source.get_associations_from_object(id).await?;
+        let attributes = source.get_object_attributes(id).await?;
+
+        let mut report = MigrationReport {
+            old_id: id,
+            new_id: None,
+            otype: object.otype.clone(),
+            from_shard,
+            to_shard,
+            associations: associations
+                .iter()
+                .map(|assoc| (assoc.atype.clone(), assoc.id2))
+                .collect(),
+            dry_run: self.dry_run,
+        };
+        if self.dry_run {
+            return Ok(report);
+        }
+
+        let new_id = self.query_router.generate_tao_id_on_shard(to_shard).await?;
+
+        // Copy the object and its edges in one target transaction
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(to_shard, &target)
+            .await?;
+        let copied = async {
+            target
+                .create_object_tx(&mut tx, new_id, object.otype.clone(), object.data.clone())
+                .await?;
+            for assoc in &associations {
+                target
+                    .create_association_tx(
+                        &mut tx,
+                        Association {
+                            id1: new_id,
+                            ..assoc.clone()
+                        },
+                    )
+                    .await?;
+            }
+            AppResult::Ok(())
+        }
+        .await;
+        match copied {
+            Ok(()) => tx.commit().await?,
+            Err(e) => {
+                tx.rollback().await?;
+                return Err(e);
+            }
+        }
+        // Attributes have no transactional write path; a failure here leaves the source intact
+        for (key, value) in &attributes {
+            target
+                .set_object_attribute(new_id, key.clone(), value.clone())
+                .await?;
+        }
+
+        // Verify before touching the source, so a bad copy never loses data
+        let copy = target.get_object(new_id).await?;
+        let copied_associations = target.get_associations_from_object(new_id).await?;
+        if copy.map(|copy| copy.data) != Some(object.data)
+            || copied_associations.len() != associations.len()
+            || target.get_object_attributes(new_id).await? != attributes
+        {
+            return Err(AppError::ShardError(format!(
+                "Copy of object {} on shard {} as {} failed verification; source left in place",
+                id, to_shard, new_id
+            )));
+        }
+
+        for assoc in &associations {
+            source
+                .delete_association(assoc.id1, assoc.atype.clone(), assoc.id2)
+                .await?;
+        }
+        source.delete_object(id).await?;
+
+        info!(
+            "Migrated object {} from shard {} to shard {} as {} with {} associations",
+            id,
+            from_shard,
+            to_shard,
+            new_id,
+            associations.len()
+        );
+        report.new_id = Some(new_id);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+    use crate::infrastructure::id_generator::TaoIdGenerator;
+    use crate::infrastructure::query_router::QueryRouterConfig;
+    use crate::infrastructure::shard_topology::{ShardHealth, ShardInfo};
+    use crate::infrastructure::tao_core::tao_core::current_time_millis;
+
+    async fn two_shard_router() -> Arc<TaoQueryRouter> {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        for shard_id in [0, 1] {
+            let shard_info = ShardInfo {
+                shard_id,
+                connection_string: "sqlite::memory:".to_string(),
+                region: "local".to_string(),
+                health: ShardHealth::Healthy,
+                replicas: vec![],
+                last_health_check: current_time_millis(),
+                load_factor: 0.0,
+            };
+            let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+            query_router.add_shard(shard_info, database).await.unwrap();
+        }
+        query_router
+    }
+
+    #[tokio::test]
+    async fn test_migrate_object_with_edges() {
+        let router = two_shard_router().await;
+        let source = router.get_database_for_shard(0).await.unwrap();
+        let id = TaoIdGenerator::new(0).next_id();
+        source
+            .create_object(id, "ent_user".to_string(), vec![1, 2, 3])
+            .await
+            .unwrap();
+        for (id2, time) in [(101, 1), (102, 2), (103, 3)] {
+            source
+                .create_association(Association {
+                    id1: id,
+                    atype: "friends".to_string(),
+                    id2,
+                    time,
+                    data: None,
+                    subtype: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let plan = ShardMigrator::new(router.clone())
+            .dry_run(true)
+            .migrate_object(id, 1)
+            .await
+            .unwrap();
+        assert_eq!(plan.associations.len(), 3);
+        assert_eq!(plan.new_id, None);
+        assert!(source.object_exists(id).await.unwrap());
+
+        let report = ShardMigrator::new(router.clone())
+            .migrate_object(id, 1)
+            .await
+            .unwrap();
+        let new_id = report.new_id.unwrap();
+
+        assert_eq!(router.get_shard_for_object(new_id), 1);
+        let target = router.get_database_for_object(new_id).await.unwrap();
+        assert_eq!(
+            target.get_object(new_id).await.unwrap().unwrap().data,
+            vec![1, 2, 3]
+        );
+        let moved = target.get_associations_from_object(new_id).await.unwrap();
+        let mut id2s: Vec<TaoId> = moved.iter().map(|assoc| assoc.id2).collect();
+        id2s.sort();
+        assert_eq!(id2s, vec![101, 102, 103]);
+        assert_eq!(
+            target
+                .count_associations(new_id, "friends".to_string())
+                .await
+                .unwrap(),
+            3
+        );
+
+        assert!(!source.object_exists(id).await.unwrap());
+        assert!(source
+            .get_associations_from_object(id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
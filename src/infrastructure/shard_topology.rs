@@ -305,6 +305,7 @@ pub trait ShardManager {
     async fn add_shard(&self, shard_info: ShardInfo);
     async fn remove_shard(&self, shard_id: ShardId);
     async fn get_healthy_shards(&self) -> Vec<ShardId>;
+    async fn update_shard_health(&self, shard_id: ShardId, health: ShardHealth);
 }
 
 /// Implementation of ShardManager using consistent hashing
@@ -351,6 +352,11 @@ impl ShardManager for ConsistentHashingShardManager {
         let topology = self.topology.read().await;
         topology.get_healthy_shards()
     }
+
+    async fn update_shard_health(&self, shard_id: ShardId, health: ShardHealth) {
+        let mut topology = self.topology.write().await;
+        topology.update_shard_health(shard_id, health);
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,62 @@
+// Durability Policies - per-type WAL logging rules declared by the schemas
+// Consulted by the WAL decorator before it logs a write
+
+use std::collections::HashSet;
+
+use crate::framework::schema::ent_schema::{Durability, SchemaRegistry};
+
+/// Otypes and atypes whose writes are `Durability::BestEffort`. Any other type is durable.
+#[derive(Debug, Clone, Default)]
+pub struct DurabilityPolicies {
+    best_effort_otypes: HashSet<String>,
+    best_effort_atypes: HashSet<String>,
+}
+
+impl DurabilityPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policies of every registered schema and edge that is best effort
+    pub fn from_schema_registry(schemas: &SchemaRegistry) -> Self {
+        let mut policies = Self::new();
+        for entity_type in schemas.get_entity_types() {
+            policies.set_object(entity_type.as_str(), schemas.get_durability(entity_type));
+            for edge in schemas.get_edges(entity_type).into_iter().flatten() {
+                policies.set_association(edge.name.clone(), edge.durability);
+            }
+        }
+        policies
+    }
+
+    pub fn set_object(&mut self, otype: impl Into<String>, durability: Durability) {
+        Self::set(&mut self.best_effort_otypes, otype.into(), durability);
+    }
+
+    pub fn set_association(&mut self, atype: impl Into<String>, durability: Durability) {
+        Self::set(&mut self.best_effort_atypes, atype.into(), durability);
+    }
+
+    pub fn object_durability(&self, otype: &str) -> Durability {
+        Self::get(&self.best_effort_otypes, otype)
+    }
+
+    pub fn association_durability(&self, atype: &str) -> Durability {
+        Self::get(&self.best_effort_atypes, atype)
+    }
+
+    fn set(types: &mut HashSet<String>, name: String, durability: Durability) {
+        match durability {
+            Durability::BestEffort => types.insert(name),
+            Durability::Durable => types.remove(&name),
+        };
+    }
+
+    fn get(types: &HashSet<String>, name: &str) -> Durability {
+        if types.contains(name) {
+            Durability::BestEffort
+        } else {
+            Durability::Durable
+        }
+    }
+}
@@ -1,2 +1,3 @@
+pub mod durability;
 pub mod wal_storage;
 pub mod write_ahead_log;
@@ -1,25 +1,117 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
 use super::write_ahead_log::{PendingTransaction, TransactionStatus, TxnId};
 use crate::error::{AppError, AppResult};
 
+/// Default size at which the active log segment is sealed and a new one started
+pub const DEFAULT_SEGMENT_MAX_BYTES: u64 = 64 * 1024 * 1024; // 64 MB
+
 /// File-based storage for the Write-Ahead Log
-/// Provides durable persistence for transaction logs
+/// Provides durable persistence for transaction logs.
+///
+/// The log is split into numbered segment files (`wal-00000000.log`, ...). Writes
+/// always go to the newest segment, which is rolled once it reaches the configured
+/// size. `compact` deletes sealed segments that only reference finished transactions.
 #[derive(Debug)]
 pub struct WalStorage {
     /// Directory where WAL files are stored
     storage_dir: PathBuf,
-    /// Transaction log file
-    log_file: Arc<Mutex<BufWriter<File>>>,
+    /// Size at which the active segment is rolled
+    segment_max_bytes: u64,
+    /// Active (newest) log segment
+    log_file: Arc<Mutex<ActiveSegment>>,
     /// Index file for quick transaction lookups
     index_file: Arc<Mutex<BufWriter<File>>>,
+    /// Segment -> transactions referenced by it, and the latest state of every transaction
+    segment_index: std::sync::Mutex<SegmentIndex>,
+    /// Set when data has been written since the last fsync
+    dirty: AtomicBool,
+    /// Number of fsyncs performed, for observability and tests
+    syncs_performed: AtomicU64,
+}
+
+#[derive(Debug)]
+struct ActiveSegment {
+    segment_id: u64,
+    writer: BufWriter<File>,
+    size_bytes: u64,
+}
+
+/// In-memory view of which transactions live in which segment
+#[derive(Debug, Default)]
+struct SegmentIndex {
+    /// Every transaction with at least one entry in the segment
+    segments: BTreeMap<u64, HashSet<TxnId>>,
+    /// Latest index entry per transaction (its segment is where the transaction was logged)
+    transactions: HashMap<TxnId, IndexEntry>,
+}
+
+impl SegmentIndex {
+    fn record(&mut self, segment_id: u64, entry: &IndexEntry, is_new_transaction: bool) {
+        self.segments
+            .entry(segment_id)
+            .or_default()
+            .insert(entry.txn_id);
+
+        if is_new_transaction {
+            self.transactions.insert(entry.txn_id, entry.clone());
+        } else if let Some(existing) = self.transactions.get_mut(&entry.txn_id) {
+            // Keep pointing at the segment the transaction was logged in
+            existing.status = entry.status;
+            existing.file_offset = entry.file_offset;
+            existing.timestamp = entry.timestamp;
+        }
+    }
+
+    fn is_finished(&self, txn_id: &TxnId) -> bool {
+        self.transactions
+            .get(txn_id)
+            .is_none_or(|entry| entry.status.is_terminal())
+    }
+
+    /// Sealed segments that can be deleted without changing what recovery sees.
+    /// A segment qualifies when every transaction it references is finished, and the
+    /// segments those transactions were logged in are being deleted too - otherwise we
+    /// would drop a commit record while keeping the original transaction around.
+    fn removable_segments(&self, active_segment: u64) -> HashSet<u64> {
+        let mut removable: HashSet<u64> = self
+            .segments
+            .iter()
+            .filter(|(segment_id, txns)| {
+                **segment_id != active_segment && txns.iter().all(|id| self.is_finished(id))
+            })
+            .map(|(segment_id, _)| *segment_id)
+            .collect();
+
+        loop {
+            let blocked: Vec<u64> = removable
+                .iter()
+                .copied()
+                .filter(|segment_id| {
+                    self.segments[segment_id].iter().any(|txn_id| {
+                        self.transactions
+                            .get(txn_id)
+                            .is_some_and(|entry| !removable.contains(&entry.segment))
+                    })
+                })
+                .collect();
+
+            if blocked.is_empty() {
+                return removable;
+            }
+            for segment_id in blocked {
+                removable.remove(&segment_id);
+            }
+        }
+    }
 }
 
 /// Entry in the WAL log file
@@ -44,14 +136,44 @@ enum WalEntryType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct IndexEntry {
     txn_id: TxnId,
+    #[serde(default)]
+    segment: u64,
     file_offset: u64,
     status: TransactionStatus,
     timestamp: i64,
 }
 
+/// Result of a compaction pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompactionResult {
+    pub segments_removed: Vec<u64>,
+    pub transactions_removed: Vec<TxnId>,
+}
+
+fn segment_file_name(segment_id: u64) -> String {
+    format!("wal-{:08}.log", segment_id)
+}
+
+fn parse_segment_id(file_name: &str) -> Option<u64> {
+    file_name
+        .strip_prefix("wal-")?
+        .strip_suffix(".log")?
+        .parse()
+        .ok()
+}
+
+fn open_append(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
 impl WalStorage {
     /// Create a new WAL storage instance
     pub fn new(storage_dir: &str) -> AppResult<Self> {
+        Self::with_segment_size(storage_dir, DEFAULT_SEGMENT_MAX_BYTES)
+    }
+
+    /// Create a new WAL storage instance that rolls segments at `segment_max_bytes`
+    pub fn with_segment_size(storage_dir: &str, segment_max_bytes: u64) -> AppResult<Self> {
         let storage_path = PathBuf::from(storage_dir);
 
         // Create storage directory if it doesn't exist
@@ -59,122 +181,150 @@ impl WalStorage {
             AppError::StorageError(format!("Failed to create WAL storage directory: {}", e))
         })?;
 
-        let log_path = storage_path.join("wal.log");
+        // Logs written before segmentation become the first segment
+        let legacy_log_path = storage_path.join("wal.log");
+        if legacy_log_path.exists() && Self::list_segment_ids(&storage_path)?.is_empty() {
+            std::fs::rename(&legacy_log_path, storage_path.join(segment_file_name(0))).map_err(
+                |e| AppError::StorageError(format!("Failed to migrate legacy WAL log: {}", e)),
+            )?;
+            info!("Migrated legacy wal.log to segment 0");
+        }
+
+        let active_segment_id = Self::list_segment_ids(&storage_path)?
+            .last()
+            .copied()
+            .unwrap_or(0);
+        let log_path = storage_path.join(segment_file_name(active_segment_id));
         let index_path = storage_path.join("wal.index");
 
         // Open or create log file
-        let log_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)
+        let log_file = open_append(&log_path)
             .map_err(|e| AppError::StorageError(format!("Failed to open WAL log file: {}", e)))?;
+        let size_bytes = log_file
+            .metadata()
+            .map_err(|e| AppError::StorageError(format!("Failed to stat WAL log file: {}", e)))?
+            .len();
 
         // Open or create index file
-        let index_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&index_path)
+        let index_file = open_append(&index_path)
             .map_err(|e| AppError::StorageError(format!("Failed to open WAL index file: {}", e)))?;
 
         let storage = Self {
             storage_dir: storage_path,
-            log_file: Arc::new(Mutex::new(BufWriter::new(log_file))),
+            segment_max_bytes,
+            log_file: Arc::new(Mutex::new(ActiveSegment {
+                segment_id: active_segment_id,
+                writer: BufWriter::new(log_file),
+                size_bytes,
+            })),
             index_file: Arc::new(Mutex::new(BufWriter::new(index_file))),
+            segment_index: std::sync::Mutex::new(SegmentIndex::default()),
+            dirty: AtomicBool::new(false),
+            syncs_performed: AtomicU64::new(0),
         };
 
-        info!("WAL storage initialized at: {}", storage_dir);
+        info!(
+            "WAL storage initialized at: {} (active segment {})",
+            storage_dir, active_segment_id
+        );
         Ok(storage)
     }
 
-    /// Load all pending transactions from storage
-    pub fn load_transactions(&self) -> AppResult<HashMap<TxnId, PendingTransaction>> {
-        let index_path = self.storage_dir.join("wal.index");
-        let log_path = self.storage_dir.join("wal.log");
-
-        if !index_path.exists() || !log_path.exists() {
-            debug!("WAL files don't exist, starting with empty transaction set");
-            return Ok(HashMap::new());
-        }
-
-        // Load index to get transaction metadata
-        let mut index_entries = Vec::new();
-        let index_file = File::open(&index_path).map_err(|e| {
-            AppError::StorageError(format!("Failed to open index file for reading: {}", e))
+    /// Sorted ids of all segment files in `dir`
+    fn list_segment_ids(dir: &Path) -> AppResult<Vec<u64>> {
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            AppError::StorageError(format!("Failed to list WAL storage directory: {}", e))
         })?;
 
-        let reader = BufReader::new(index_file);
-        for line in reader.lines() {
-            let line = line
-                .map_err(|e| AppError::StorageError(format!("Failed to read index line: {}", e)))?;
+        let mut ids: Vec<u64> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| parse_segment_id(&entry.file_name().to_string_lossy()))
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
 
-            if line.trim().is_empty() {
-                continue;
-            }
+    fn lock_segment_index(&self) -> std::sync::MutexGuard<'_, SegmentIndex> {
+        self.segment_index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Load all pending transactions from storage
+    /// Reads every segment in order and rebuilds the segment index.
+    pub fn load_transactions(&self) -> AppResult<HashMap<TxnId, PendingTransaction>> {
+        let mut segment_index = SegmentIndex::default();
+        let mut transactions: HashMap<TxnId, PendingTransaction> = HashMap::new();
 
-            let entry: IndexEntry = serde_json::from_str(&line).map_err(|e| {
-                AppError::DeserializationError(format!("Failed to deserialize index entry: {}", e))
+        for segment_id in Self::list_segment_ids(&self.storage_dir)? {
+            let log_path = self.storage_dir.join(segment_file_name(segment_id));
+            let log_file = File::open(&log_path).map_err(|e| {
+                AppError::StorageError(format!("Failed to open log file for reading: {}", e))
             })?;
 
-            index_entries.push(entry);
-        }
+            let mut file_offset = 0u64;
+            let reader = BufReader::new(log_file);
+            for line in reader.lines() {
+                let line = line.map_err(|e| {
+                    AppError::StorageError(format!("Failed to read log line: {}", e))
+                })?;
+                let entry_offset = file_offset;
+                file_offset += line.len() as u64 + 1;
 
-        // Group index entries by transaction ID and get the latest status
-        let mut latest_status: HashMap<TxnId, (TransactionStatus, u64)> = HashMap::new();
-        for entry in index_entries {
-            match latest_status.get(&entry.txn_id) {
-                Some((_, existing_offset)) if *existing_offset > entry.file_offset => {
-                    // Skip older entries
+                if line.trim().is_empty() {
                     continue;
                 }
-                _ => {
-                    latest_status.insert(entry.txn_id, (entry.status, entry.file_offset));
-                }
-            }
-        }
-
-        // Load transactions that are still pending or need processing
-        let mut transactions = HashMap::new();
-        let log_file = File::open(&log_path).map_err(|e| {
-            AppError::StorageError(format!("Failed to open log file for reading: {}", e))
-        })?;
-
-        let reader = BufReader::new(log_file);
-        for line in reader.lines() {
-            let line = line
-                .map_err(|e| AppError::StorageError(format!("Failed to read log line: {}", e)))?;
-
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            let entry: WalLogEntry = serde_json::from_str(&line).map_err(|e| {
-                AppError::DeserializationError(format!("Failed to deserialize log entry: {}", e))
-            })?;
 
-            // Only load transactions that are still active
-            if let Some((status, _)) = latest_status.get(&entry.txn_id) {
-                match status {
-                    TransactionStatus::Committed => continue, // Skip committed
-                    _ => {
-                        // Load the transaction data
-                        if let WalEntryType::Transaction = entry.entry_type {
-                            let mut txn: PendingTransaction = serde_json::from_slice(&entry.data)
-                                .map_err(|e| {
+                let entry: WalLogEntry = serde_json::from_str(&line).map_err(|e| {
+                    AppError::DeserializationError(format!(
+                        "Failed to deserialize log entry: {}",
+                        e
+                    ))
+                })?;
+
+                match entry.entry_type {
+                    WalEntryType::Transaction => {
+                        let txn: PendingTransaction =
+                            serde_json::from_slice(&entry.data).map_err(|e| {
                                 AppError::DeserializationError(format!(
                                     "Failed to deserialize transaction: {}",
                                     e
                                 ))
                             })?;
 
-                            // Update with latest status
-                            txn.status = *status;
-                            transactions.insert(entry.txn_id, txn);
+                        let index_entry = IndexEntry {
+                            txn_id: entry.txn_id,
+                            segment: segment_id,
+                            file_offset: entry_offset,
+                            status: txn.status,
+                            timestamp: entry.timestamp,
+                        };
+                        segment_index.record(segment_id, &index_entry, true);
+                        transactions.insert(entry.txn_id, txn);
+                    }
+                    WalEntryType::StatusUpdate(status) => {
+                        // Status updates for compacted-away transactions are ignored
+                        if let Some(txn) = transactions.get_mut(&entry.txn_id) {
+                            txn.status = status;
+                            let index_entry = IndexEntry {
+                                txn_id: entry.txn_id,
+                                segment: segment_id,
+                                file_offset: entry_offset,
+                                status,
+                                timestamp: entry.timestamp,
+                            };
+                            segment_index.record(segment_id, &index_entry, false);
                         }
                     }
                 }
             }
         }
 
+        *self.lock_segment_index() = segment_index;
+
+        // Only load transactions that are still active
+        transactions.retain(|_, txn| txn.status != TransactionStatus::Committed);
+
         info!(
             "Loaded {} pending transactions from WAL storage",
             transactions.len()
@@ -182,6 +332,78 @@ impl WalStorage {
         Ok(transactions)
     }
 
+    /// Append one log line to the active segment, rolling to a new segment first if
+    /// it would exceed the size limit. Returns (segment, offset) of the written line.
+    async fn append_log_line(&self, log_line: &str) -> AppResult<(u64, u64)> {
+        let mut active = self.log_file.lock().await;
+        let line_len = log_line.len() as u64 + 1;
+
+        if active.size_bytes > 0 && active.size_bytes + line_len > self.segment_max_bytes {
+            self.roll_segment(&mut active)?;
+        }
+
+        let offset = active.size_bytes;
+        writeln!(active.writer, "{}", log_line)
+            .map_err(|e| AppError::StorageError(format!("Failed to write to log file: {}", e)))?;
+
+        active
+            .writer
+            .flush()
+            .map_err(|e| AppError::StorageError(format!("Failed to flush log file: {}", e)))?;
+        active.size_bytes += line_len;
+
+        Ok((active.segment_id, offset))
+    }
+
+    /// Seal the active segment (fsynced, so sealed segments are always durable) and open the next one
+    fn roll_segment(&self, active: &mut ActiveSegment) -> AppResult<()> {
+        active
+            .writer
+            .flush()
+            .map_err(|e| AppError::StorageError(format!("Failed to flush log file: {}", e)))?;
+        active
+            .writer
+            .get_ref()
+            .sync_data()
+            .map_err(|e| AppError::StorageError(format!("Failed to fsync log file: {}", e)))?;
+
+        let next_segment_id = active.segment_id + 1;
+        let next_path = self.storage_dir.join(segment_file_name(next_segment_id));
+        let next_file = open_append(&next_path).map_err(|e| {
+            AppError::StorageError(format!("Failed to open new WAL segment: {}", e))
+        })?;
+
+        info!(
+            "Rolled WAL segment {} -> {}",
+            active.segment_id, next_segment_id
+        );
+        *active = ActiveSegment {
+            segment_id: next_segment_id,
+            writer: BufWriter::new(next_file),
+            size_bytes: 0,
+        };
+        Ok(())
+    }
+
+    async fn append_index_entry(&self, index_entry: &IndexEntry) -> AppResult<()> {
+        let index_line = serde_json::to_string(index_entry).map_err(|e| {
+            AppError::SerializationError(format!("Failed to serialize index entry: {}", e))
+        })?;
+
+        {
+            let mut index_file = self.index_file.lock().await;
+            writeln!(index_file, "{}", index_line).map_err(|e| {
+                AppError::StorageError(format!("Failed to write to index file: {}", e))
+            })?;
+
+            index_file.flush().map_err(|e| {
+                AppError::StorageError(format!("Failed to flush index file: {}", e))
+            })?;
+        }
+        self.dirty.store(true, Ordering::Release);
+        Ok(())
+    }
+
     /// Append a new transaction to the WAL
     pub async fn append_transaction(&self, txn: &PendingTransaction) -> AppResult<()> {
         let current_time = crate::infrastructure::tao_core::tao_core::current_time_millis();
@@ -204,47 +426,24 @@ impl WalStorage {
             AppError::SerializationError(format!("Failed to serialize log entry: {}", e))
         })?;
 
-        let file_offset = {
-            let mut log_file = self.log_file.lock().await;
-            let offset = log_file.seek(SeekFrom::End(0)).map_err(|e| {
-                AppError::StorageError(format!("Failed to seek to end of log file: {}", e))
-            })?;
-
-            writeln!(log_file, "{}", log_line).map_err(|e| {
-                AppError::StorageError(format!("Failed to write to log file: {}", e))
-            })?;
-
-            log_file
-                .flush()
-                .map_err(|e| AppError::StorageError(format!("Failed to flush log file: {}", e)))?;
-
-            offset
-        };
+        let (segment, file_offset) = self.append_log_line(&log_line).await?;
 
         // Write to index file
         let index_entry = IndexEntry {
             txn_id: txn.txn_id,
+            segment,
             file_offset,
             status: txn.status,
             timestamp: current_time,
         };
+        self.lock_segment_index()
+            .record(segment, &index_entry, true);
+        self.append_index_entry(&index_entry).await?;
 
-        let index_line = serde_json::to_string(&index_entry).map_err(|e| {
-            AppError::SerializationError(format!("Failed to serialize index entry: {}", e))
-        })?;
-
-        {
-            let mut index_file = self.index_file.lock().await;
-            writeln!(index_file, "{}", index_line).map_err(|e| {
-                AppError::StorageError(format!("Failed to write to index file: {}", e))
-            })?;
-
-            index_file.flush().map_err(|e| {
-                AppError::StorageError(format!("Failed to flush index file: {}", e))
-            })?;
-        }
-
-        debug!("Appended transaction {} to WAL storage", txn.txn_id);
+        debug!(
+            "Appended transaction {} to WAL segment {}",
+            txn.txn_id, segment
+        );
         Ok(())
     }
 
@@ -269,45 +468,19 @@ impl WalStorage {
             AppError::SerializationError(format!("Failed to serialize status update: {}", e))
         })?;
 
-        let file_offset = {
-            let mut log_file = self.log_file.lock().await;
-            let offset = log_file.seek(SeekFrom::End(0)).map_err(|e| {
-                AppError::StorageError(format!("Failed to seek to end of log file: {}", e))
-            })?;
-
-            writeln!(log_file, "{}", log_line).map_err(|e| {
-                AppError::StorageError(format!("Failed to write status update to log file: {}", e))
-            })?;
-
-            log_file
-                .flush()
-                .map_err(|e| AppError::StorageError(format!("Failed to flush log file: {}", e)))?;
-
-            offset
-        };
+        let (segment, file_offset) = self.append_log_line(&log_line).await?;
 
         // Update index
         let index_entry = IndexEntry {
             txn_id,
+            segment,
             file_offset,
             status,
             timestamp: current_time,
         };
-
-        let index_line = serde_json::to_string(&index_entry).map_err(|e| {
-            AppError::SerializationError(format!("Failed to serialize index entry: {}", e))
-        })?;
-
-        {
-            let mut index_file = self.index_file.lock().await;
-            writeln!(index_file, "{}", index_line).map_err(|e| {
-                AppError::StorageError(format!("Failed to write to index file: {}", e))
-            })?;
-
-            index_file.flush().map_err(|e| {
-                AppError::StorageError(format!("Failed to flush index file: {}", e))
-            })?;
-        }
+        self.lock_segment_index()
+            .record(segment, &index_entry, false);
+        self.append_index_entry(&index_entry).await?;
 
         debug!("Updated transaction {} status to {:?}", txn_id, status);
         Ok(())
@@ -321,32 +494,186 @@ impl WalStorage {
         self.update_transaction_status(txn.txn_id, txn.status).await
     }
 
-    /// Compact the WAL files by removing committed transactions
-    /// This is a maintenance operation that should be run periodically
-    pub async fn compact(&self) -> AppResult<()> {
-        warn!("WAL compaction not yet implemented - this is a TODO for production systems");
-        // TODO: Implement compaction logic
-        // 1. Read all transactions
-        // 2. Filter out committed/expired ones
-        // 3. Write remaining transactions to new files
-        // 4. Atomically replace old files with new ones
+    /// Fsync the log and index files so everything written so far survives a crash.
+    /// `flush()` alone only hands data to the OS page cache.
+    pub async fn sync(&self) -> AppResult<()> {
+        // Clear first so writes racing with this sync mark the storage dirty again
+        self.dirty.store(false, Ordering::Release);
+
+        {
+            let mut active = self.log_file.lock().await;
+            active
+                .writer
+                .flush()
+                .map_err(|e| AppError::StorageError(format!("Failed to flush log file: {}", e)))?;
+            active
+                .writer
+                .get_ref()
+                .sync_data()
+                .map_err(|e| AppError::StorageError(format!("Failed to fsync log file: {}", e)))?;
+        }
+
+        {
+            let mut index_file = self.index_file.lock().await;
+            index_file.flush().map_err(|e| {
+                AppError::StorageError(format!("Failed to flush index file: {}", e))
+            })?;
+            index_file.get_ref().sync_data().map_err(|e| {
+                AppError::StorageError(format!("Failed to fsync index file: {}", e))
+            })?;
+        }
+
+        self.syncs_performed.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
 
+    /// Write a scratch file next to the segments, read it back and remove it. Fails when
+    /// the WAL directory can no longer be written, e.g. because the disk is full.
+    pub fn probe(&self) -> AppResult<()> {
+        let path = self.storage_dir.join("health.probe");
+        let written = uuid::Uuid::new_v4().to_string();
+        std::fs::write(&path, &written).map_err(|e| {
+            AppError::StorageError(format!("Failed to write WAL probe file: {}", e))
+        })?;
+        let read = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::StorageError(format!("Failed to read WAL probe file: {}", e)))?;
+        let _ = std::fs::remove_file(&path);
+        if read != written {
+            return Err(AppError::StorageError(
+                "WAL probe file read back different contents".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fsync only if something was written since the last sync
+    pub async fn sync_if_dirty(&self) -> AppResult<bool> {
+        if !self.dirty.load(Ordering::Acquire) {
+            return Ok(false);
+        }
+        self.sync().await?;
+        Ok(true)
+    }
+
+    /// Compact the WAL by deleting sealed segments whose transactions have all finished
+    /// (committed, aborted or compensated). The index file is rewritten to cover only
+    /// the transactions that remain. The active segment is never removed.
+    pub async fn compact(&self) -> AppResult<CompactionResult> {
+        // Hold the writer locks so no entries land while segments are being dropped
+        let active = self.log_file.lock().await;
+        let mut index_file = self.index_file.lock().await;
+
+        let (result, remaining_entries) = {
+            let segment_index = self.lock_segment_index();
+            let removable = segment_index.removable_segments(active.segment_id);
+
+            let mut segments_removed: Vec<u64> = removable.iter().copied().collect();
+            segments_removed.sort_unstable();
+            let transactions_removed: Vec<TxnId> = segment_index
+                .transactions
+                .values()
+                .filter(|entry| removable.contains(&entry.segment))
+                .map(|entry| entry.txn_id)
+                .collect();
+            let remaining_entries: Vec<IndexEntry> = segment_index
+                .transactions
+                .values()
+                .filter(|entry| !removable.contains(&entry.segment))
+                .cloned()
+                .collect();
+
+            (
+                CompactionResult {
+                    segments_removed,
+                    transactions_removed,
+                },
+                remaining_entries,
+            )
+        };
+
+        if result.segments_removed.is_empty() {
+            debug!("WAL compaction found no removable segments");
+            return Ok(result);
+        }
+
+        for segment_id in &result.segments_removed {
+            let path = self.storage_dir.join(segment_file_name(*segment_id));
+            std::fs::remove_file(&path).map_err(|e| {
+                AppError::StorageError(format!(
+                    "Failed to remove WAL segment {}: {}",
+                    segment_id, e
+                ))
+            })?;
+        }
+
+        {
+            let mut segment_index = self.lock_segment_index();
+            for segment_id in &result.segments_removed {
+                segment_index.segments.remove(segment_id);
+            }
+            for txn_id in &result.transactions_removed {
+                segment_index.transactions.remove(txn_id);
+            }
+        }
+
+        // Rewrite the index with only the surviving transactions, then swap it in
+        let index_path = self.storage_dir.join("wal.index");
+        let tmp_path = self.storage_dir.join("wal.index.tmp");
+        {
+            let mut tmp_file = BufWriter::new(File::create(&tmp_path).map_err(|e| {
+                AppError::StorageError(format!("Failed to create WAL index file: {}", e))
+            })?);
+            for entry in &remaining_entries {
+                let line = serde_json::to_string(entry).map_err(|e| {
+                    AppError::SerializationError(format!("Failed to serialize index entry: {}", e))
+                })?;
+                writeln!(tmp_file, "{}", line).map_err(|e| {
+                    AppError::StorageError(format!("Failed to write to index file: {}", e))
+                })?;
+            }
+            tmp_file
+                .into_inner()
+                .map_err(|e| AppError::StorageError(format!("Failed to flush index file: {}", e)))?
+                .sync_all()
+                .map_err(|e| {
+                    AppError::StorageError(format!("Failed to fsync index file: {}", e))
+                })?;
+        }
+        std::fs::rename(&tmp_path, &index_path).map_err(|e| {
+            AppError::StorageError(format!("Failed to replace WAL index file: {}", e))
+        })?;
+        *index_file = BufWriter::new(open_append(&index_path).map_err(|e| {
+            AppError::StorageError(format!("Failed to open WAL index file: {}", e))
+        })?);
+
+        info!(
+            "WAL compaction removed {} segments and {} finished transactions",
+            result.segments_removed.len(),
+            result.transactions_removed.len()
+        );
+        drop(active);
+        Ok(result)
+    }
+
+    /// Ids of the segment files currently on disk
+    pub fn segment_ids(&self) -> AppResult<Vec<u64>> {
+        Self::list_segment_ids(&self.storage_dir)
+    }
+
     /// Get storage statistics
     pub fn get_storage_stats(&self) -> AppResult<WalStorageStats> {
-        let log_path = self.storage_dir.join("wal.log");
         let index_path = self.storage_dir.join("wal.index");
+        let segment_ids = self.segment_ids()?;
 
-        let log_size = if log_path.exists() {
-            std::fs::metadata(&log_path)
+        let mut log_size = 0;
+        for segment_id in &segment_ids {
+            let path = self.storage_dir.join(segment_file_name(*segment_id));
+            log_size += std::fs::metadata(&path)
                 .map_err(|e| {
                     AppError::StorageError(format!("Failed to get log file metadata: {}", e))
                 })?
-                .len()
-        } else {
-            0
-        };
+                .len();
+        }
 
         let index_size = if index_path.exists() {
             std::fs::metadata(&index_path)
@@ -363,6 +690,8 @@ impl WalStorage {
             index_file_size_bytes: index_size,
             total_size_bytes: log_size + index_size,
             storage_dir: self.storage_dir.to_string_lossy().to_string(),
+            syncs_performed: self.syncs_performed.load(Ordering::Relaxed),
+            segment_count: segment_ids.len(),
         })
     }
 }
@@ -374,6 +703,8 @@ pub struct WalStorageStats {
     pub index_file_size_bytes: u64,
     pub total_size_bytes: u64,
     pub storage_dir: String,
+    pub syncs_performed: u64,
+    pub segment_count: usize,
 }
 
 #[cfg(test)]
@@ -408,8 +739,9 @@ mod tests {
                 id1: 123,
                 atype: "test".to_string(),
                 id2: 456,
-                time: crate::infrastructure::tao_core::current_time_millis(),
+                time: crate::infrastructure::tao_core::tao_core::current_time_millis(),
                 data: None,
+                subtype: None,
             },
         }];
 
@@ -486,4 +818,47 @@ mod tests {
         let loaded_txns = storage.load_transactions().unwrap();
         assert_eq!(loaded_txns.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_pending_transaction_pins_its_segment() {
+        let dir = tempdir().unwrap();
+        let storage_dir = dir.path().to_str().unwrap();
+        let storage = WalStorage::with_segment_size(storage_dir, 256).unwrap();
+
+        let new_txn = || {
+            PendingTransaction::new(vec![TaoOperation::InsertObject {
+                object_id: 1,
+                object_type: "test_object".to_string(),
+                data: vec![1, 2, 3],
+            }])
+        };
+
+        // Segment 0 holds a transaction that never finishes
+        let pinned = new_txn();
+        storage.append_transaction(&pinned).await.unwrap();
+
+        for _ in 0..6 {
+            let txn = new_txn();
+            storage.append_transaction(&txn).await.unwrap();
+            storage
+                .update_transaction_status(txn.txn_id, TransactionStatus::Committed)
+                .await
+                .unwrap();
+        }
+        assert!(storage.segment_ids().unwrap().len() > 2);
+
+        let result = storage.compact().await.unwrap();
+        assert!(!result.segments_removed.contains(&0));
+        assert!(!result.transactions_removed.contains(&pinned.txn_id));
+        assert!(storage.segment_ids().unwrap().contains(&0));
+
+        // Once it finishes, the whole history can be reclaimed except the active segment
+        storage
+            .update_transaction_status(pinned.txn_id, TransactionStatus::Aborted)
+            .await
+            .unwrap();
+        storage.compact().await.unwrap();
+        assert_eq!(storage.segment_ids().unwrap().len(), 1);
+        assert!(storage.load_transactions().unwrap().is_empty());
+    }
 }
@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::{Duration, SystemTime};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, RwLock, Semaphore};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::infrastructure::storage::wal_storage::WalStorage;
+use crate::infrastructure::storage::wal_storage::{
+    CompactionResult, WalStorage, WalStorageStats, DEFAULT_SEGMENT_MAX_BYTES,
+};
 use crate::infrastructure::tao_core::tao_core::current_time_millis;
 
 /// Unique transaction identifier
@@ -30,6 +32,12 @@ pub enum TaoOperation {
         atype: String,
         id2: i64,
     },
+    TouchAssociation {
+        id1: i64,
+        atype: String,
+        id2: i64,
+        time: i64,
+    },
     UpdateObject {
         object_id: i64,
         data: Vec<u8>,
@@ -37,6 +45,26 @@ pub enum TaoOperation {
     DeleteObject {
         object_id: i64,
     },
+    SetAttribute {
+        object_id: i64,
+        key: String,
+        value: Vec<u8>,
+    },
+    DeleteAllAssociations {
+        id1: i64,
+        atype: String,
+    },
+    MoveAssociation {
+        id1: i64,
+        atype: String,
+        old_id2: i64,
+        new_id2: i64,
+    },
+    DeleteAssociationsBetween {
+        id1: i64,
+        id2: i64,
+        atypes: Option<Vec<String>>,
+    },
 }
 
 // Re-export the TaoAssociation for WAL to use
@@ -48,8 +76,13 @@ impl TaoOperation {
             TaoOperation::InsertObject { .. } => "insert_object",
             TaoOperation::InsertAssociation { .. } => "insert_association",
             TaoOperation::DeleteAssociation { .. } => "delete_association",
+            TaoOperation::TouchAssociation { .. } => "touch_association",
             TaoOperation::UpdateObject { .. } => "update_object",
             TaoOperation::DeleteObject { .. } => "delete_object",
+            TaoOperation::SetAttribute { .. } => "set_attribute",
+            TaoOperation::DeleteAllAssociations { .. } => "delete_all_associations",
+            TaoOperation::MoveAssociation { .. } => "move_association",
+            TaoOperation::DeleteAssociationsBetween { .. } => "delete_associations_between",
         }
     }
 }
@@ -71,6 +104,18 @@ pub enum TransactionStatus {
     Compensated,
 }
 
+impl TransactionStatus {
+    /// Whether the transaction will never be executed again
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Committed
+                | TransactionStatus::Aborted
+                | TransactionStatus::Compensated
+        )
+    }
+}
+
 /// A pending transaction in the WAL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingTransaction {
@@ -115,6 +160,36 @@ impl PendingTransaction {
     }
 }
 
+/// When the WAL fsyncs its files, trading durability for throughput
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalDurability {
+    /// Fsync before every log or status write returns.
+    /// Nothing acknowledged is lost on a crash, at the cost of one disk sync per write.
+    #[default]
+    FsyncEachWrite,
+    /// Fsync from a background task at most once per interval.
+    /// A crash can lose up to one interval of acknowledged writes; syncs are amortized across them.
+    FsyncInterval(Duration),
+    /// Never fsync; data is flushed to the OS page cache only.
+    /// Survives a process crash but not a machine crash or power loss. Fastest.
+    NoFsync,
+}
+
+/// What `log_operations` does when the write queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalOverflowPolicy {
+    /// Fail straight away with `ServiceUnavailable`
+    Reject,
+    /// Wait up to this long for a slot, then fail with `ServiceUnavailable`
+    Block(Duration),
+}
+
+impl Default for WalOverflowPolicy {
+    fn default() -> Self {
+        WalOverflowPolicy::Block(Duration::from_secs(1))
+    }
+}
+
 /// Configuration for the WAL system
 #[derive(Debug, Clone)]
 pub struct WalConfig {
@@ -130,6 +205,14 @@ pub struct WalConfig {
     pub cleanup_interval_ms: u64,
     /// Batch size for WAL operations
     pub batch_size: usize,
+    /// Fsync policy for the log files
+    pub durability: WalDurability,
+    /// Size at which the active log segment is rolled over
+    pub segment_max_bytes: u64,
+    /// Writes that may be appending or waiting on fsync at once; beyond this, callers
+    /// get backpressure per `overflow_policy` instead of piling up in memory
+    pub write_queue_capacity: usize,
+    pub overflow_policy: WalOverflowPolicy,
 }
 
 impl Default for WalConfig {
@@ -141,6 +224,10 @@ impl Default for WalConfig {
             max_retry_delay_ms: 30_000,  // 30 seconds
             cleanup_interval_ms: 60_000, // 1 minute
             batch_size: 100,
+            durability: WalDurability::default(),
+            segment_max_bytes: DEFAULT_SEGMENT_MAX_BYTES,
+            write_queue_capacity: 1024,
+            overflow_policy: WalOverflowPolicy::default(),
         }
     }
 }
@@ -156,7 +243,9 @@ pub struct TaoWriteAheadLog {
     /// WAL configuration
     config: WalConfig,
     /// Persistent storage for the WAL
-    storage: WalStorage,
+    storage: Arc<WalStorage>,
+    /// One permit per slot in the bounded write queue
+    write_queue: Semaphore,
     /// Statistics
     stats: Arc<RwLock<WalStats>>,
 }
@@ -169,16 +258,39 @@ pub struct WalStats {
     pub retries_executed: u64,
     pub pending_transactions: u64,
     pub avg_commit_time_ms: f64,
+    /// Writes turned away because the write queue was full
+    pub writes_rejected: u64,
+}
+
+/// Point-in-time view of work the WAL still owes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WalStatus {
+    /// Logged transactions that have not finished executing
+    pub pending: usize,
+    /// Failed transactions waiting in the retry queue
+    pub retry_queued: usize,
+    /// Failed transactions that ran out of retries and need an operator
+    pub dead_lettered: usize,
+    /// Writes currently holding a slot in the bounded write queue
+    pub write_queue_depth: usize,
 }
 
 impl TaoWriteAheadLog {
     pub async fn new(config: WalConfig, storage_dir: &str) -> AppResult<Self> {
-        let storage = WalStorage::new(storage_dir)?;
+        let storage = Arc::new(WalStorage::with_segment_size(
+            storage_dir,
+            config.segment_max_bytes,
+        )?);
         let pending_transactions = storage.load_transactions()?;
 
+        if let WalDurability::FsyncInterval(interval) = config.durability {
+            Self::start_fsync_worker(Arc::downgrade(&storage), interval);
+        }
+
         let wal = Self {
             pending_transactions: Arc::new(RwLock::new(pending_transactions)),
             retry_queue: Arc::new(Mutex::new(VecDeque::new())),
+            write_queue: Semaphore::new(config.write_queue_capacity),
             config,
             storage,
             stats: Arc::new(RwLock::new(WalStats::default())),
@@ -191,9 +303,35 @@ impl TaoWriteAheadLog {
         Ok(wal)
     }
 
+    /// Periodically fsync the storage for `WalDurability::FsyncInterval`.
+    /// Holds only a weak reference so the task ends when the WAL is dropped.
+    fn start_fsync_worker(storage: Weak<WalStorage>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let Some(storage) = storage.upgrade() else {
+                    break;
+                };
+                if let Err(e) = storage.sync_if_dirty().await {
+                    error!("Background WAL fsync failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Fsync now if the durability policy requires it for every write
+    async fn sync_for_policy(&self) -> AppResult<()> {
+        if self.config.durability == WalDurability::FsyncEachWrite {
+            self.storage.sync().await?;
+        }
+        Ok(())
+    }
+
     /// Start the background cleanup worker
     pub async fn start_cleanup_worker(&self) {
         let pending_transactions = Arc::clone(&self.pending_transactions);
+        let storage = Arc::clone(&self.storage);
         let cleanup_interval = self.config.cleanup_interval_ms;
         let max_age = self.config.max_transaction_age_ms;
 
@@ -229,6 +367,18 @@ impl TaoWriteAheadLog {
                         }
                     }
                 }
+
+                // Reclaim log segments that only hold finished transactions
+                match storage.compact().await {
+                    Ok(result) if !result.transactions_removed.is_empty() => {
+                        let mut pending = pending_transactions.write().await;
+                        for txn_id in &result.transactions_removed {
+                            pending.remove(txn_id);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("WAL compaction failed: {}", e),
+                }
             }
         });
     }
@@ -240,6 +390,9 @@ impl TaoWriteAheadLog {
             return Err(AppError::Validation("No operations provided".to_string()));
         }
 
+        // Held until the write is durable, so slow fsyncs push back on callers
+        let _slot = self.acquire_write_slot().await?;
+
         // Create transaction and log ALL operations to WAL atomically
         let txn = PendingTransaction::new(operations.clone());
         let txn_id = txn.txn_id;
@@ -252,6 +405,7 @@ impl TaoWriteAheadLog {
 
         // Write to persistent storage first
         self.storage.append_transaction(&txn).await?;
+        self.sync_for_policy().await?;
 
         // Then, update in-memory state
         {
@@ -274,12 +428,48 @@ impl TaoWriteAheadLog {
         Ok(txn_id)
     }
 
+    /// Take a slot in the write queue, applying the overflow policy when it is full
+    async fn acquire_write_slot(&self) -> AppResult<tokio::sync::SemaphorePermit<'_>> {
+        if let Ok(slot) = self.write_queue.try_acquire() {
+            return Ok(slot);
+        }
+
+        let slot = match self.config.overflow_policy {
+            WalOverflowPolicy::Reject => None,
+            WalOverflowPolicy::Block(timeout) => {
+                tokio::time::timeout(timeout, self.write_queue.acquire())
+                    .await
+                    .ok()
+                    .and_then(Result::ok)
+            }
+        };
+        match slot {
+            Some(slot) => Ok(slot),
+            None => {
+                self.stats.write().await.writes_rejected += 1;
+                warn!(
+                    "Rejected WAL write: write queue full ({} slots)",
+                    self.config.write_queue_capacity
+                );
+                Err(AppError::ServiceUnavailable(
+                    "WAL write queue is full".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Writes currently holding a slot in the write queue
+    pub fn write_queue_depth(&self) -> usize {
+        self.config.write_queue_capacity - self.write_queue.available_permits()
+    }
+
     /// Mark a transaction as committed
     pub async fn mark_transaction_committed(&self, txn_id: uuid::Uuid) -> AppResult<()> {
         // Update persistent storage first
         self.storage
             .update_transaction_status(txn_id, TransactionStatus::Committed)
             .await?;
+        self.sync_for_policy().await?;
 
         let mut pending = self.pending_transactions.write().await;
         let mut stats = self.stats.write().await;
@@ -308,6 +498,7 @@ impl TaoWriteAheadLog {
         self.storage
             .update_transaction_status(txn_id, TransactionStatus::Failed)
             .await?;
+        self.sync_for_policy().await?;
 
         let mut pending = self.pending_transactions.write().await;
         let mut stats = self.stats.write().await;
@@ -441,6 +632,53 @@ impl TaoWriteAheadLog {
         *self.stats.read().await
     }
 
+    /// Get storage statistics, including how many fsyncs have been performed
+    pub fn get_storage_stats(&self) -> AppResult<WalStorageStats> {
+        self.storage.get_storage_stats()
+    }
+
+    /// Check the log directory still takes writes, without logging a transaction
+    pub fn probe_storage(&self) -> AppResult<()> {
+        self.storage.probe()
+    }
+
+    /// Delete log segments that only hold finished transactions and forget those
+    /// transactions in memory. Safe to run while the WAL is in use.
+    pub async fn compact(&self) -> AppResult<CompactionResult> {
+        let result = self.storage.compact().await?;
+
+        if !result.transactions_removed.is_empty() {
+            let mut pending = self.pending_transactions.write().await;
+            for txn_id in &result.transactions_removed {
+                pending.remove(txn_id);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Count pending, retry-queued and dead-lettered transactions
+    pub async fn status(&self) -> WalStatus {
+        let pending = self.pending_transactions.read().await;
+        let retry_queue = self.retry_queue.lock().await;
+
+        let mut status = WalStatus {
+            retry_queued: retry_queue.len(),
+            write_queue_depth: self.write_queue_depth(),
+            ..WalStatus::default()
+        };
+        for txn in pending.values() {
+            match txn.status {
+                TransactionStatus::Pending | TransactionStatus::Executing => status.pending += 1,
+                TransactionStatus::Failed if !retry_queue.contains(&txn.txn_id) => {
+                    status.dead_lettered += 1
+                }
+                _ => {}
+            }
+        }
+        status
+    }
+
     /// Get pending transaction count
     pub async fn get_pending_transaction_count(&self) -> usize {
         self.pending_transactions.read().await.len()
@@ -478,6 +716,7 @@ mod tests {
                 id2: 456,
                 time: current_time_millis(),
                 data: None,
+                subtype: None,
             },
         }];
 
@@ -535,4 +774,191 @@ mod tests {
         assert_eq!(txn.operations[0].operation_type(), "insert_object");
         assert_eq!(txn.status, TransactionStatus::Pending);
     }
+
+    fn sample_operations() -> Vec<TaoOperation> {
+        vec![TaoOperation::InsertObject {
+            object_id: 1,
+            object_type: "durable_object".to_string(),
+            data: vec![1, 2, 3],
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_fsync_each_write() {
+        let dir = tempdir().unwrap();
+        let config = WalConfig {
+            durability: WalDurability::FsyncEachWrite,
+            ..WalConfig::default()
+        };
+        let wal = TaoWriteAheadLog::new(config, dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let txn_id = wal.log_operations(sample_operations()).await.unwrap();
+        assert_eq!(wal.get_storage_stats().unwrap().syncs_performed, 1);
+
+        wal.mark_transaction_committed(txn_id).await.unwrap();
+        assert_eq!(wal.get_storage_stats().unwrap().syncs_performed, 2);
+
+        let txn_id = wal.log_operations(sample_operations()).await.unwrap();
+        wal.mark_transaction_failed(txn_id, "boom".to_string())
+            .await
+            .unwrap();
+        assert_eq!(wal.get_storage_stats().unwrap().syncs_performed, 4);
+    }
+
+    #[tokio::test]
+    async fn test_no_fsync() {
+        let dir = tempdir().unwrap();
+        let config = WalConfig {
+            durability: WalDurability::NoFsync,
+            ..WalConfig::default()
+        };
+        let wal = TaoWriteAheadLog::new(config, dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let txn_id = wal.log_operations(sample_operations()).await.unwrap();
+        wal.mark_transaction_committed(txn_id).await.unwrap();
+        assert_eq!(wal.get_storage_stats().unwrap().syncs_performed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fsync_interval_syncs_in_background() {
+        let dir = tempdir().unwrap();
+        let config = WalConfig {
+            durability: WalDurability::FsyncInterval(Duration::from_millis(10)),
+            ..WalConfig::default()
+        };
+        let wal = TaoWriteAheadLog::new(config, dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        // Writes return without syncing inline; the worker picks them up
+        for _ in 0..5 {
+            wal.log_operations(sample_operations()).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let syncs = wal.get_storage_stats().unwrap().syncs_performed;
+        assert!(syncs >= 1, "background worker should have synced");
+
+        // Nothing new was written, so idle ticks don't sync again
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(wal.get_storage_stats().unwrap().syncs_performed, syncs);
+    }
+
+    #[tokio::test]
+    async fn test_segment_rotation_and_compaction() {
+        let dir = tempdir().unwrap();
+        let storage_dir = dir.path().to_str().unwrap();
+        let config = WalConfig {
+            durability: WalDurability::NoFsync,
+            segment_max_bytes: 512,
+            ..WalConfig::default()
+        };
+        let wal = TaoWriteAheadLog::new(config.clone(), storage_dir)
+            .await
+            .unwrap();
+
+        let mut committed = Vec::new();
+        for _ in 0..20 {
+            let txn_id = wal.log_operations(sample_operations()).await.unwrap();
+            wal.mark_transaction_committed(txn_id).await.unwrap();
+            committed.push(txn_id);
+        }
+        let pending_txn = wal.log_operations(sample_operations()).await.unwrap();
+
+        let before = wal.get_storage_stats().unwrap();
+        assert!(before.segment_count > 3, "expected several segments");
+
+        let result = wal.compact().await.unwrap();
+        assert!(!result.segments_removed.is_empty());
+
+        let after = wal.get_storage_stats().unwrap();
+        assert!(after.segment_count < before.segment_count);
+        assert!(after.log_file_size_bytes < before.log_file_size_bytes);
+        for segment_id in &result.segments_removed {
+            let path = dir.path().join(format!("wal-{:08}.log", segment_id));
+            assert!(!path.exists(), "segment {} should be deleted", segment_id);
+        }
+        for txn_id in &result.transactions_removed {
+            assert!(committed.contains(txn_id));
+            assert!(wal.get_transaction(*txn_id).await.is_none());
+        }
+        assert_eq!(
+            wal.get_transaction_status(pending_txn).await,
+            Some(TransactionStatus::Pending)
+        );
+        drop(wal);
+
+        // Recovery reads the remaining segments in order
+        let reloaded = TaoWriteAheadLog::new(config, storage_dir).await.unwrap();
+        assert_eq!(reloaded.get_pending_transaction_count().await, 1);
+        assert_eq!(
+            reloaded.get_transaction_status(pending_txn).await,
+            Some(TransactionStatus::Pending)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_write_queue_rejects_with_reject_policy() {
+        let dir = tempdir().unwrap();
+        let config = WalConfig {
+            durability: WalDurability::NoFsync,
+            write_queue_capacity: 2,
+            overflow_policy: WalOverflowPolicy::Reject,
+            ..WalConfig::default()
+        };
+        let wal = TaoWriteAheadLog::new(config, dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        // Stand in for two writes stuck behind a slow fsync
+        let in_flight = wal.write_queue.acquire_many(2).await.unwrap();
+        assert_eq!(wal.status().await.write_queue_depth, 2);
+
+        let result = wal.log_operations(sample_operations()).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+        assert_eq!(wal.get_stats().await.writes_rejected, 1);
+        assert_eq!(wal.get_pending_transaction_count().await, 0);
+
+        drop(in_flight);
+        wal.log_operations(sample_operations()).await.unwrap();
+        assert_eq!(wal.write_queue_depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_write_queue_blocks_until_drained_with_block_policy() {
+        let dir = tempdir().unwrap();
+        let config = WalConfig {
+            durability: WalDurability::NoFsync,
+            write_queue_capacity: 1,
+            overflow_policy: WalOverflowPolicy::Block(Duration::from_millis(50)),
+            ..WalConfig::default()
+        };
+        let wal = Arc::new(
+            TaoWriteAheadLog::new(config, dir.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        );
+
+        // Nothing drains the queue within the timeout
+        let in_flight = wal.write_queue.acquire().await.unwrap();
+        let result = wal.log_operations(sample_operations()).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+
+        // The queue drains while the caller is waiting
+        let blocked = tokio::spawn({
+            let wal = wal.clone();
+            async move { wal.log_operations(sample_operations()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!blocked.is_finished());
+        drop(in_flight);
+
+        blocked.await.unwrap().unwrap();
+        assert_eq!(wal.get_pending_transaction_count().await, 1);
+        assert_eq!(wal.get_stats().await.writes_rejected, 1);
+    }
 }
@@ -1,3 +1,6 @@
+pub mod cursor;
+pub mod request_context;
 pub mod tao;
 pub mod tao_core;
 pub mod tao_decorators;
+pub mod tao_stack;
@@ -0,0 +1,164 @@
+// Request Context - Request-scoped limits for every TAO call made while serving a request
+// Set once per request (e.g. by the HTTP layer) and enforced by `DeadlineDecorator`
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task_local;
+use tokio::time::Instant;
+
+/// Cost units of reading one object or one page of edges from a shard
+pub const POINT_READ_UNITS: u64 = 1;
+/// Edges returned per extra cost unit of an association read
+pub const EDGES_PER_UNIT: u64 = 100;
+/// Cost units of scanning one shard in a scatter-gather query
+pub const SCATTER_UNITS_PER_SHARD: u64 = 10;
+
+/// Cost units charged so far to a request, shared by every task serving it
+#[derive(Debug, Clone, Default)]
+pub struct CostMeter(Arc<AtomicU64>);
+
+impl CostMeter {
+    pub fn units(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn add(&self, units: u64) {
+        self.0.fetch_add(units, Ordering::Relaxed);
+    }
+}
+
+impl PartialEq for CostMeter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CostMeter {}
+
+/// Limits applying to the TAO calls of one request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestContext {
+    /// Reads still running at this instant are abandoned with `AppError::TimeoutError`
+    pub deadline: Option<Instant>,
+    /// Where `charge_units` adds the cost of each call; unmetered when `None`
+    pub meter: Option<CostMeter>,
+}
+
+impl RequestContext {
+    /// A context whose deadline is `timeout` from now
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            deadline: Some(Instant::now() + timeout),
+            meter: None,
+        }
+    }
+
+    /// A context counting the cost units of its calls, without a deadline
+    pub fn metered() -> Self {
+        Self {
+            deadline: None,
+            meter: Some(CostMeter::default()),
+        }
+    }
+
+    /// Cost units charged so far; zero when unmetered
+    pub fn units_consumed(&self) -> u64 {
+        self.meter.as_ref().map_or(0, CostMeter::units)
+    }
+
+    /// Time left until the deadline, zero once it has passed; `None` without a deadline
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+/// Run `f` with `context` applying to every TAO call inside it. Nested scopes can only
+/// shorten the deadline of the enclosing one, never extend it, and keep charging the
+/// enclosing meter unless they bring their own.
+pub async fn with_request_context<F>(context: RequestContext, f: F) -> F::Output
+where
+    F: Future,
+{
+    let outer = current_request_context();
+    let deadline = match (outer.deadline, context.deadline) {
+        (Some(outer), Some(inner)) => Some(outer.min(inner)),
+        (outer, inner) => outer.or(inner),
+    };
+    let meter = context.meter.or(outer.meter);
+    REQUEST_CONTEXT
+        .scope(RequestContext { deadline, meter }, f)
+        .await
+}
+
+/// Context of the enclosing scope; no deadline or meter outside of one
+pub fn current_request_context() -> RequestContext {
+    REQUEST_CONTEXT
+        .try_with(|context| context.clone())
+        .unwrap_or_default()
+}
+
+/// Add `units` to the meter of the enclosing scope, if it has one
+pub fn charge_units(units: u64) {
+    let _ = REQUEST_CONTEXT.try_with(|context| {
+        if let Some(meter) = &context.meter {
+            meter.add(units);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_nested_scopes_keep_the_earliest_deadline() {
+        assert_eq!(current_request_context().deadline, None);
+
+        let outer = RequestContext::with_timeout(Duration::from_secs(1));
+        let (short, long) = with_request_context(outer.clone(), async {
+            let short = with_request_context(
+                RequestContext::with_timeout(Duration::from_millis(10)),
+                async { current_request_context().remaining().unwrap() },
+            )
+            .await;
+            let long = with_request_context(
+                RequestContext::with_timeout(Duration::from_secs(60)),
+                async { current_request_context().deadline },
+            )
+            .await;
+            (short, long)
+        })
+        .await;
+
+        assert!(short <= Duration::from_millis(10));
+        assert_eq!(long, outer.deadline);
+    }
+
+    #[tokio::test]
+    async fn test_nested_scopes_charge_the_enclosing_meter() {
+        charge_units(5);
+
+        let context = RequestContext::metered();
+        with_request_context(context.clone(), async {
+            charge_units(POINT_READ_UNITS);
+            with_request_context(
+                RequestContext::with_timeout(Duration::from_secs(1)),
+                async { charge_units(SCATTER_UNITS_PER_SHARD) },
+            )
+            .await;
+        })
+        .await;
+
+        assert_eq!(
+            context.units_consumed(),
+            POINT_READ_UNITS + SCATTER_UNITS_PER_SHARD
+        );
+    }
+}
@@ -10,16 +10,20 @@ use std::time::Duration;
 
 use crate::infrastructure::{
     cache::cache_layer::TaoMultiTierCache,
+    change_feed::feed::ChangeFeed,
     database::database::DatabaseTransaction,
     monitoring::monitoring::MetricsCollector,
     storage::write_ahead_log::TaoWriteAheadLog,
     tao_core::tao_core::{
-        AssocType, TaoAssocQuery, TaoAssociation, TaoCore, TaoId, TaoObject, TaoOperations, TaoType,
+        AssocDeleteAllReport, AssocMoveReport, AssocType, PurgeReport, TaoAssocQuery,
+        TaoAssocQueryResult, TaoAssociation, TaoCore, TaoId, TaoObject, TaoObjectVersion,
+        TaoOperations, TaoTime, TaoType, TaoWriteBatch,
     },
     tao_core::tao_decorators::{
-        BaseTao, CacheDecorator, CircuitBreakerDecorator, MetricsDecorator, TaoDecorator,
-        WalDecorator,
+        BaseTao, ChangeFeedDecorator, ConcurrencyLimitConfig, ConcurrencyLimitDecorator,
+        DeadlineDecorator, MaintenanceDecorator, MaintenanceMode, TaoDecorator, WalDecorator,
     },
+    tao_core::tao_stack::TaoStackBuilder,
 };
 
 // Re-export core types for convenience
@@ -34,6 +38,8 @@ pub use crate::infrastructure::tao_core::tao_core::{
 pub struct Tao {
     /// Fully decorated TAO implementation chain
     decorated_tao: Arc<dyn TaoDecorator>,
+    /// WAL layer of the chain, kept for replay and status inspection
+    wal_decorator: Option<Arc<WalDecorator>>,
 }
 
 impl Tao {
@@ -47,31 +53,88 @@ impl Tao {
         enable_circuit_breaker: bool,
     ) -> Self {
         // Build the decorator chain: CircuitBreaker -> Metrics -> WAL -> Cache -> BaseTao -> TaoCore
-        let base_tao = Arc::new(BaseTao::new(tao_core));
+        // Degraded read mode moves the cache outside the circuit breaker, so it sees
+        // `ServiceUnavailable` and can fall back to stale entries:
+        // Cache -> CircuitBreaker -> Metrics -> WAL -> BaseTao -> TaoCore
+        let serve_stale = cache.config().serve_stale_on_unavailable;
+        let mut stack = TaoStackBuilder::new(tao_core);
+        if !serve_stale {
+            stack = stack.with_cache(cache.clone(), enable_caching);
+        }
+        stack = stack.with_wal(wal).with_metrics(metrics);
+        if enable_circuit_breaker {
+            stack = stack.with_circuit_breaker(
+                5,                       // failure threshold
+                Duration::from_secs(30), // recovery timeout
+            );
+        }
+        if serve_stale {
+            stack = stack.with_cache(cache, enable_caching);
+        }
 
-        let cache_decorator = Arc::new(CacheDecorator::new(base_tao, cache, enable_caching));
+        let (decorated_tao, wal_decorator) = stack
+            .build_with_wal()
+            .expect("Tao::new adds its layers in a valid order");
+        Self {
+            decorated_tao,
+            wal_decorator,
+        }
+    }
 
-        let wal_decorator = Arc::new(WalDecorator::new(cache_decorator, wal));
+    /// Publish change events for every successful write made through this instance.
+    /// Wraps the outside of the chain, so events are only emitted once the full
+    /// chain (WAL, circuit breaker, ...) has accepted the write.
+    pub fn with_change_feed(self, feed: Arc<dyn ChangeFeed>) -> Self {
+        Self {
+            decorated_tao: Arc::new(ChangeFeedDecorator::new(self.decorated_tao, feed)),
+            wal_decorator: self.wal_decorator,
+        }
+    }
 
-        let metrics_decorator = Arc::new(MetricsDecorator::new(wal_decorator, metrics));
+    /// Cap in-flight operations with separate read and write limits. Wraps the outside of
+    /// the chain, so shed calls are turned away before they reach the cache, WAL or database.
+    pub fn with_concurrency_limit(
+        self,
+        config: ConcurrencyLimitConfig,
+        metrics: Arc<MetricsCollector>,
+    ) -> Self {
+        let limiter =
+            ConcurrencyLimitDecorator::new(self.decorated_tao, config).with_metrics(metrics);
+        Self {
+            decorated_tao: Arc::new(limiter),
+            wal_decorator: self.wal_decorator,
+        }
+    }
 
-        let circuit_breaker_decorator = Arc::new(CircuitBreakerDecorator::new(
-            metrics_decorator,
-            5,                       // failure threshold
-            Duration::from_secs(30), // recovery timeout
-            enable_circuit_breaker,
-        ));
+    /// Reject writes while `mode` is enabled. Wraps the outside of the chain, so frozen
+    /// writes never reach the WAL or database.
+    pub fn with_maintenance_mode(self, mode: Arc<MaintenanceMode>) -> Self {
+        Self {
+            decorated_tao: Arc::new(MaintenanceDecorator::new(self.decorated_tao, mode)),
+            wal_decorator: self.wal_decorator,
+        }
+    }
 
+    /// Enforce the deadline each request sets in its `RequestContext`. Wraps the outside
+    /// of the chain, so time spent queued in inner layers counts against the deadline.
+    pub fn with_deadline(self) -> Self {
         Self {
-            decorated_tao: circuit_breaker_decorator,
+            decorated_tao: Arc::new(DeadlineDecorator::new(self.decorated_tao)),
+            wal_decorator: self.wal_decorator,
         }
     }
 
+    /// The WAL layer of the chain, if this instance was built with one
+    pub fn wal_decorator(&self) -> Option<Arc<WalDecorator>> {
+        self.wal_decorator.clone()
+    }
+
     /// Create a minimal TAO instance with only basic functionality
     pub fn minimal(tao_core: Arc<TaoCore>) -> Self {
         let base_tao = Arc::new(BaseTao::new(tao_core));
         Self {
             decorated_tao: base_tao,
+            wal_decorator: None,
         }
     }
 }
@@ -121,10 +184,88 @@ impl TaoOperations for Tao {
         self.decorated_tao.obj_delete_by_type(id, otype).await
     }
 
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        self.decorated_tao.purge_object(id).await
+    }
+
+    async fn obj_get_by_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoObject>> {
+        self.decorated_tao
+            .obj_get_by_unique(otype, field, value)
+            .await
+    }
+
+    async fn generate_id_for_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<TaoId> {
+        self.decorated_tao
+            .generate_id_for_unique(otype, field, value)
+            .await
+    }
+
+    async fn obj_create_unique(
+        &self,
+        id: TaoId,
+        otype: TaoType,
+        data: Vec<u8>,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoId>> {
+        self.decorated_tao
+            .obj_create_unique(id, otype, data, field, value)
+            .await
+    }
+
+    async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+        self.decorated_tao.write_batch(batch).await
+    }
+
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+        self.decorated_tao.get_object_history(id, limit).await
+    }
+
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+        self.decorated_tao.obj_rollback(id, to_version).await
+    }
+
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        self.decorated_tao.set_attribute(id, key, value).await
+    }
+
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+        self.decorated_tao.get_attribute(id, key).await
+    }
+
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+        self.decorated_tao.get_attributes(id).await
+    }
+
     async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
         self.decorated_tao.assoc_get(query).await
     }
 
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+        self.decorated_tao.assoc_get_page(query).await
+    }
+
+    async fn assoc_get_multi_type(
+        &self,
+        id1: TaoId,
+        atypes: Vec<AssocType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+        self.decorated_tao
+            .assoc_get_multi_type(id1, atypes, limit_per_type)
+            .await
+    }
+
     async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
         self.decorated_tao.assoc_add(assoc).await
     }
@@ -133,10 +274,72 @@ impl TaoOperations for Tao {
         self.decorated_tao.assoc_delete(id1, atype, id2).await
     }
 
+    async fn assoc_delete_all(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<AssocDeleteAllReport> {
+        self.decorated_tao.assoc_delete_all(id1, atype).await
+    }
+
+    async fn assoc_touch(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+        new_time: Option<TaoTime>,
+    ) -> AppResult<bool> {
+        self.decorated_tao
+            .assoc_touch(id1, atype, id2, new_time)
+            .await
+    }
+
+    async fn assoc_move(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    ) -> AppResult<AssocMoveReport> {
+        self.decorated_tao
+            .assoc_move(id1, atype, old_id2, new_id2)
+            .await
+    }
+
+    async fn assoc_delete_between(
+        &self,
+        id1: TaoId,
+        id2: TaoId,
+        atypes: Option<Vec<AssocType>>,
+    ) -> AppResult<HashMap<AssocType, u64>> {
+        self.decorated_tao
+            .assoc_delete_between(id1, id2, atypes)
+            .await
+    }
+
     async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
         self.decorated_tao.assoc_count(id1, atype).await
     }
 
+    async fn assoc_count_many(
+        &self,
+        pairs: Vec<(TaoId, AssocType)>,
+    ) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+        self.decorated_tao.assoc_count_many(pairs).await
+    }
+
+    async fn assoc_count_by_subtype(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<HashMap<Option<String>, u64>> {
+        self.decorated_tao.assoc_count_by_subtype(id1, atype).await
+    }
+
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+        self.decorated_tao.list_assoc_types(id).await
+    }
+
     async fn assoc_range(
         &self,
         id1: TaoId,
@@ -162,10 +365,46 @@ impl TaoOperations for Tao {
             .await
     }
 
+    async fn assoc_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_time: TaoTime,
+        limit: u32,
+    ) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+        self.decorated_tao
+            .assoc_since(id1, atype, since_time, limit)
+            .await
+    }
+
+    async fn assoc_delta_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_seq: u64,
+    ) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+        self.decorated_tao
+            .assoc_delta_since(id1, atype, since_seq)
+            .await
+    }
+
     async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
         self.decorated_tao.assoc_exists(id1, atype, id2).await
     }
 
+    async fn assoc_get_one(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+    ) -> AppResult<Option<TaoAssociation>> {
+        self.decorated_tao.assoc_get_one(id1, atype, id2).await
+    }
+
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+        self.decorated_tao.edges_between(id1, id2).await
+    }
+
     async fn get_by_id_and_type(
         &self,
         ids: Vec<TaoId>,
@@ -183,6 +422,17 @@ impl TaoOperations for Tao {
         self.decorated_tao.get_neighbors(id, atype, limit).await
     }
 
+    async fn get_neighbors_with_edges(
+        &self,
+        id: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+        self.decorated_tao
+            .get_neighbors_with_edges(id, atype, limit)
+            .await
+    }
+
     async fn get_neighbor_ids(
         &self,
         id: TaoId,
@@ -192,6 +442,21 @@ impl TaoOperations for Tao {
         self.decorated_tao.get_neighbor_ids(id, atype, limit).await
     }
 
+    async fn get_in_neighbor_ids(
+        &self,
+        id2: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoId>> {
+        self.decorated_tao
+            .get_in_neighbor_ids(id2, atype, limit)
+            .await
+    }
+
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+        self.decorated_tao.estimate_count_of_type(otype).await
+    }
+
     async fn get_all_objects_of_type(
         &self,
         otype: TaoType,
@@ -202,6 +467,18 @@ impl TaoOperations for Tao {
             .await
     }
 
+    async fn get_objects_in_range(
+        &self,
+        otype: TaoType,
+        start: TaoTime,
+        end: TaoTime,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoObject>> {
+        self.decorated_tao
+            .get_objects_in_range(otype, start, end, limit)
+            .await
+    }
+
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
         self.decorated_tao.begin_transaction().await
     }
@@ -255,10 +532,84 @@ impl<T: TaoOperations + ?Sized> TaoOperations for Arc<T> {
         (**self).obj_delete_by_type(id, otype).await
     }
 
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        (**self).purge_object(id).await
+    }
+
+    async fn obj_get_by_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoObject>> {
+        (**self).obj_get_by_unique(otype, field, value).await
+    }
+
+    async fn generate_id_for_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<TaoId> {
+        (**self).generate_id_for_unique(otype, field, value).await
+    }
+
+    async fn obj_create_unique(
+        &self,
+        id: TaoId,
+        otype: TaoType,
+        data: Vec<u8>,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoId>> {
+        (**self)
+            .obj_create_unique(id, otype, data, field, value)
+            .await
+    }
+
+    async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+        (**self).write_batch(batch).await
+    }
+
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+        (**self).get_object_history(id, limit).await
+    }
+
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+        (**self).obj_rollback(id, to_version).await
+    }
+
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        (**self).set_attribute(id, key, value).await
+    }
+
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+        (**self).get_attribute(id, key).await
+    }
+
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+        (**self).get_attributes(id).await
+    }
+
     async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
         (**self).assoc_get(query).await
     }
 
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+        (**self).assoc_get_page(query).await
+    }
+
+    async fn assoc_get_multi_type(
+        &self,
+        id1: TaoId,
+        atypes: Vec<AssocType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+        (**self)
+            .assoc_get_multi_type(id1, atypes, limit_per_type)
+            .await
+    }
+
     async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
         (**self).assoc_add(assoc).await
     }
@@ -267,10 +618,66 @@ impl<T: TaoOperations + ?Sized> TaoOperations for Arc<T> {
         (**self).assoc_delete(id1, atype, id2).await
     }
 
+    async fn assoc_delete_all(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<AssocDeleteAllReport> {
+        (**self).assoc_delete_all(id1, atype).await
+    }
+
+    async fn assoc_touch(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+        new_time: Option<TaoTime>,
+    ) -> AppResult<bool> {
+        (**self).assoc_touch(id1, atype, id2, new_time).await
+    }
+
+    async fn assoc_move(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    ) -> AppResult<AssocMoveReport> {
+        (**self).assoc_move(id1, atype, old_id2, new_id2).await
+    }
+
+    async fn assoc_delete_between(
+        &self,
+        id1: TaoId,
+        id2: TaoId,
+        atypes: Option<Vec<AssocType>>,
+    ) -> AppResult<HashMap<AssocType, u64>> {
+        (**self).assoc_delete_between(id1, id2, atypes).await
+    }
+
     async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
         (**self).assoc_count(id1, atype).await
     }
 
+    async fn assoc_count_many(
+        &self,
+        pairs: Vec<(TaoId, AssocType)>,
+    ) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+        (**self).assoc_count_many(pairs).await
+    }
+
+    async fn assoc_count_by_subtype(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<HashMap<Option<String>, u64>> {
+        (**self).assoc_count_by_subtype(id1, atype).await
+    }
+
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+        (**self).list_assoc_types(id).await
+    }
+
     async fn assoc_range(
         &self,
         id1: TaoId,
@@ -294,10 +701,42 @@ impl<T: TaoOperations + ?Sized> TaoOperations for Arc<T> {
             .await
     }
 
+    async fn assoc_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_time: TaoTime,
+        limit: u32,
+    ) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+        (**self).assoc_since(id1, atype, since_time, limit).await
+    }
+
+    async fn assoc_delta_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_seq: u64,
+    ) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+        (**self).assoc_delta_since(id1, atype, since_seq).await
+    }
+
     async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
         (**self).assoc_exists(id1, atype, id2).await
     }
 
+    async fn assoc_get_one(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+    ) -> AppResult<Option<TaoAssociation>> {
+        (**self).assoc_get_one(id1, atype, id2).await
+    }
+
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+        (**self).edges_between(id1, id2).await
+    }
+
     async fn get_by_id_and_type(
         &self,
         ids: Vec<TaoId>,
@@ -315,6 +754,15 @@ impl<T: TaoOperations + ?Sized> TaoOperations for Arc<T> {
         (**self).get_neighbors(id, atype, limit).await
     }
 
+    async fn get_neighbors_with_edges(
+        &self,
+        id: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+        (**self).get_neighbors_with_edges(id, atype, limit).await
+    }
+
     async fn get_neighbor_ids(
         &self,
         id: TaoId,
@@ -324,6 +772,19 @@ impl<T: TaoOperations + ?Sized> TaoOperations for Arc<T> {
         (**self).get_neighbor_ids(id, atype, limit).await
     }
 
+    async fn get_in_neighbor_ids(
+        &self,
+        id2: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoId>> {
+        (**self).get_in_neighbor_ids(id2, atype, limit).await
+    }
+
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+        (**self).estimate_count_of_type(otype).await
+    }
+
     async fn get_all_objects_of_type(
         &self,
         otype: TaoType,
@@ -332,6 +793,18 @@ impl<T: TaoOperations + ?Sized> TaoOperations for Arc<T> {
         (**self).get_all_objects_of_type(otype, limit).await
     }
 
+    async fn get_objects_in_range(
+        &self,
+        otype: TaoType,
+        start: TaoTime,
+        end: TaoTime,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoObject>> {
+        (**self)
+            .get_objects_in_range(otype, start, end, limit)
+            .await
+    }
+
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
         (**self).begin_transaction().await
     }
@@ -4,21 +4,36 @@
 
 use crate::error::{AppError, AppResult};
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
-use tracing::info;
+use std::time::Instant;
+use tracing::{debug, error, info};
 
 use crate::framework::builder::ent_builder::EntBuilder;
+use crate::framework::builder::ent_transaction::EntTransaction;
 use crate::framework::builder::has_tao::HasTao;
+use crate::framework::context::get_viewer_context;
+use crate::framework::ent_hooks;
+use crate::framework::entity::edge_payload::EdgePayload;
 use crate::framework::entity::ent_trait::Entity;
+use crate::framework::schema::ent_schema::validate_payload;
 use crate::infrastructure::association_registry::AssociationRegistry;
+use crate::infrastructure::cache::read_consistency::{with_read_consistency, ReadConsistency};
 use crate::infrastructure::database::database::{
     AssocQuery, Association, DatabaseInterface, DatabaseTransaction, Object, ObjectQuery,
-    PostgresDatabase,
+    ObjectVersion, PostgresDatabase,
 };
+use crate::infrastructure::database::dsn::{pg_connect_options, redact_dsn, SecretSource};
+use crate::infrastructure::id_generator::{IdStrategy, DEFAULT_ID_EPOCH_MS};
+use crate::infrastructure::monitoring::query_plan::record_shard_span;
 use crate::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
 use crate::infrastructure::shard_topology::{ShardHealth, ShardId, ShardInfo};
+use crate::infrastructure::tao_core::cursor::Cursor;
+use crate::infrastructure::tao_core::request_context::{charge_units, EDGES_PER_UNIT};
 use sqlx::postgres::PgPoolOptions;
 
 /// Current time in milliseconds since Unix epoch
@@ -45,7 +60,10 @@ pub type AssocType = String;
 #[derive(Debug, Clone)]
 pub struct DatabaseShardConfig {
     pub shard_id: u16,
+    /// DSN of the shard's database; keep the password out of it with `password_source`
     pub connection_string: String,
+    /// Where to read the password from at connect time, overriding any in the DSN
+    pub password_source: Option<SecretSource>,
     pub region: String,
     pub max_connections: u32,
     pub min_connections: u32,
@@ -57,6 +75,13 @@ pub struct DatabaseShardConfig {
 pub struct TaoConfig {
     pub database_shards: Vec<DatabaseShardConfig>,
     pub query_router_config: QueryRouterConfig,
+    /// How ids are assigned to new objects
+    pub id_strategy: IdStrategy,
+    /// Unix time in milliseconds that id timestamps count from. Must never change once
+    /// ids have been issued, see `TaoIdGenerator`.
+    pub id_epoch_ms: u64,
+    /// Page sizes of neighbor lists and association ranges
+    pub list_limits: ListLimits,
 }
 
 impl Default for TaoConfig {
@@ -70,6 +95,9 @@ impl TaoConfig {
         Self {
             database_shards: Vec::new(),
             query_router_config: QueryRouterConfig::default(),
+            id_strategy: IdStrategy::default(),
+            id_epoch_ms: DEFAULT_ID_EPOCH_MS,
+            list_limits: ListLimits::default(),
         }
     }
 
@@ -78,6 +106,61 @@ impl TaoConfig {
     }
 }
 
+/// How many items a list read returns: `default` when the caller gives no limit, and
+/// never more than `max` whatever it asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListLimits {
+    pub default: u32,
+    pub max: u32,
+}
+
+impl Default for ListLimits {
+    fn default() -> Self {
+        Self {
+            default: 100,
+            max: 1000,
+        }
+    }
+}
+
+impl ListLimits {
+    /// Limits from `LIST_DEFAULT_LIMIT` and `LIST_MAX_LIMIT`, each falling back to its
+    /// default when unset
+    pub fn from_env() -> AppResult<Self> {
+        let defaults = Self::default();
+        let var = |key: &str, fallback: u32| match std::env::var(key) {
+            Ok(value) => value
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| {
+                    AppError::ConfigurationError(format!(
+                        "{} must be a positive integer, got '{}'",
+                        key, value
+                    ))
+                }),
+            Err(_) => Ok(fallback),
+        };
+        let limits = Self {
+            default: var("LIST_DEFAULT_LIMIT", defaults.default)?,
+            max: var("LIST_MAX_LIMIT", defaults.max)?,
+        };
+        if limits.default > limits.max {
+            return Err(AppError::ConfigurationError(format!(
+                "LIST_DEFAULT_LIMIT ({}) must not exceed LIST_MAX_LIMIT ({})",
+                limits.default, limits.max
+            )));
+        }
+        Ok(limits)
+    }
+
+    /// The limit to use for a `requested` one, always at least 1
+    pub fn apply(&self, requested: Option<u32>) -> u32 {
+        requested.unwrap_or(self.default).min(self.max).max(1)
+    }
+}
+
 /// TAO Association representing edge relationships between entities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaoAssociation {
@@ -86,6 +169,17 @@ pub struct TaoAssociation {
     pub id2: TaoId,
     pub time: TaoTime,
     pub data: Option<Vec<u8>>,
+    /// Dimension within `atype`, e.g. `love` for a `reaction`. Queries on the atype
+    /// see every subtype; `assoc_count_by_subtype` breaks the count down.
+    #[serde(default)]
+    pub subtype: Option<String>,
+}
+
+impl TaoAssociation {
+    pub fn with_subtype(mut self, subtype: impl Into<String>) -> Self {
+        self.subtype = Some(subtype.into());
+        self
+    }
 }
 
 /// TAO Object representing an entity
@@ -99,6 +193,17 @@ pub struct TaoObject {
     pub version: u64,
 }
 
+/// A previous value of an object whose type keeps history: `data` as of `version`,
+/// last written at `updated_time`, and the user whose update replaced it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaoObjectVersion {
+    pub id: TaoId,
+    pub version: u64,
+    pub data: Vec<u8>,
+    pub updated_time: TaoTime,
+    pub changed_by: Option<TaoId>,
+}
+
 /// Conversion functions between TAO types and database types
 impl From<Object> for TaoObject {
     fn from(obj: Object) -> Self {
@@ -126,6 +231,18 @@ impl From<TaoObject> for Object {
     }
 }
 
+impl From<ObjectVersion> for TaoObjectVersion {
+    fn from(version: ObjectVersion) -> Self {
+        TaoObjectVersion {
+            id: version.id,
+            version: version.version,
+            data: version.data,
+            updated_time: version.updated_time,
+            changed_by: version.changed_by,
+        }
+    }
+}
+
 impl From<Association> for TaoAssociation {
     fn from(assoc: Association) -> Self {
         TaoAssociation {
@@ -134,6 +251,7 @@ impl From<Association> for TaoAssociation {
             id2: assoc.id2,
             time: assoc.time,
             data: assoc.data,
+            subtype: assoc.subtype,
         }
     }
 }
@@ -146,6 +264,7 @@ impl From<TaoAssociation> for Association {
             id2: tao_assoc.id2,
             time: tao_assoc.time,
             data: tao_assoc.data,
+            subtype: tao_assoc.subtype,
         }
     }
 }
@@ -156,17 +275,98 @@ pub struct TaoAssocQuery {
     pub id1: TaoId,
     pub atype: AssocType,
     pub id2_set: Option<Vec<TaoId>>,
+    /// Inclusive id2 bounds, for scanning edges to a range of targets (e.g. one shard's ids)
+    pub low_id2: Option<TaoId>,
+    pub high_id2: Option<TaoId>,
     pub high_time: Option<TaoTime>,
     pub low_time: Option<TaoTime>,
     pub limit: Option<u32>,
+    /// Edges to skip. Unstable when edges are added between page fetches; prefer `after`.
     pub offset: Option<u64>,
+    /// Only edges after this `(time, id2)`, newest first with id2 breaking ties. Taken
+    /// from the previous page's `next_cursor`, it neither repeats nor skips edges.
+    pub after: Option<(TaoTime, TaoId)>,
+    /// Also return the total association count (cheap: read from the count table)
+    pub include_total: bool,
 }
 
+/// A page of associations, optionally with the total across all pages
+#[derive(Debug, Clone)]
+pub struct TaoAssocQueryResult {
+    pub associations: Vec<TaoAssociation>,
+    /// Set when the page was full, i.e. there may be more edges after it
+    pub next_cursor: Option<Cursor>,
+    pub total_count: Option<u64>,
+}
+
+/// Outcome of `purge_object`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    pub object_deleted: bool,
+    /// Edges stored with the object as id1, removed in the same transaction as the object
+    pub associations_removed: Vec<(AssocType, TaoId)>,
+    /// Inverse edges `(id1, atype, id2)` pointing back at the object that have been removed
+    pub inverse_removed: Vec<(TaoId, AssocType, TaoId)>,
+    /// Inverse edges still to remove. They can live on other shards, so the WAL decorator
+    /// enqueues them for retry; without one they are only reported.
+    pub inverse_deferred: Vec<(TaoId, AssocType, TaoId)>,
+}
+
+/// Outcome of `assoc_delete_all`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssocDeleteAllReport {
+    /// id2 of each deleted `id1 -atype->` edge, removed and logged in one transaction
+    pub removed: Vec<TaoId>,
+    /// Inverse edges `(id1, atype, id2)` pointing back at id1 that have been removed
+    pub inverse_removed: Vec<(TaoId, AssocType, TaoId)>,
+}
+
+/// Outcome of `assoc_move`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssocMoveReport {
+    /// False if `id1 -atype-> old_id2` did not exist, in which case nothing changed
+    pub moved: bool,
+    /// Type of the inverse edge moved from old_id2's list to new_id2's, if there was one
+    pub inverse_atype: Option<AssocType>,
+}
+
+/// Objects and edges created together by `write_batch`
+#[derive(Debug, Clone, Default)]
+pub struct TaoWriteBatch {
+    /// `(id, otype, data)` of each object to create
+    pub objects: Vec<(TaoId, TaoType, Vec<u8>)>,
+    pub associations: Vec<TaoAssociation>,
+}
+
+impl TaoWriteBatch {
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty() && self.associations.is_empty()
+    }
+}
+
+/// Reads `prefetch` loads into cache ahead of use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefetchRequest {
+    Object(TaoId),
+    /// The newest `limit` `atype` edges of `id1`, read with `assoc_get`, and the objects
+    /// they reach
+    Neighbors {
+        id1: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    },
+}
+
+/// Reads `prefetch` keeps in flight at once
+pub const PREFETCH_CONCURRENCY: usize = 16;
+
 /// TAO object query parameters
 #[derive(Debug, Clone)]
 pub struct TaoObjectQuery {
     pub ids: Vec<TaoId>,
     pub otype: Option<TaoType>,
+    pub created_after: Option<TaoTime>,
+    pub created_before: Option<TaoTime>,
     pub limit: Option<u32>,
     pub offset: Option<u64>,
 }
@@ -178,10 +378,15 @@ impl From<TaoAssocQuery> for AssocQuery {
             id1: tao_query.id1,
             atype: tao_query.atype,
             id2_set: tao_query.id2_set,
+            low_id2: tao_query.low_id2,
+            high_id2: tao_query.high_id2,
             high_time: tao_query.high_time,
             low_time: tao_query.low_time,
             limit: tao_query.limit,
             offset: tao_query.offset,
+            after: tao_query.after,
+            include_total: tao_query.include_total,
+            dedup: true,
         }
     }
 }
@@ -191,6 +396,8 @@ impl From<TaoObjectQuery> for ObjectQuery {
         ObjectQuery {
             ids: tao_query.ids,
             otype: tao_query.otype,
+            created_after: tao_query.created_after,
+            created_before: tao_query.created_before,
             limit: tao_query.limit,
             offset: tao_query.offset,
         }
@@ -212,27 +419,77 @@ pub trait TaoOperations: Send + Sync + std::fmt::Debug {
         B::BuilderState: Send + Sync,
     {
         let id = self.generate_id(owner_id).await?;
-        let entity = B::build(state, id).map_err(|e| AppError::Validation(e.to_string()))?;
+        let mut entity = B::build(state, id).map_err(AppError::ValidationFailed)?;
+        ent_hooks::run_before_create(&mut entity).await?;
 
-        let validation_errors = entity.validate()?;
-        if !validation_errors.is_empty() {
-            return Err(AppError::Validation(format!(
-                "Validation failed: {}",
-                validation_errors.join(", ")
-            )));
-        }
+        entity.validate()?.into_result()?;
 
         let data = entity.serialize_to_bytes()?;
         let otype = <B as EntBuilder>::entity_type().to_string();
 
         self.create_object(id, otype, data).await?;
+        ent_hooks::run_after_create(&entity).await;
 
         Ok(entity)
     }
 
+    /// Load the `E` whose unique key `unique_field` equals `value`, or create it from
+    /// `build_state` if there is none. The flag is true when this call created it.
+    /// Concurrent calls for the same key are settled by the key's unique constraint:
+    /// exactly one creates, the others get its entity back.
+    async fn get_or_create_entity<E: EntBuilder + Send>(
+        &self,
+        unique_field: &str,
+        value: &str,
+        build_state: E::BuilderState,
+    ) -> AppResult<(E, bool)>
+    where
+        Self: Sized,
+        E::BuilderState: Send + Sync,
+    {
+        let otype = <E as EntBuilder>::entity_type().to_string();
+        let (field, value) = (unique_field.to_string(), value.to_string());
+
+        if let Some(existing) = self
+            .obj_get_by_unique(otype.clone(), field.clone(), value.clone())
+            .await?
+        {
+            return Ok((E::from_stored_object(&existing)?, false));
+        }
+
+        let id = self
+            .generate_id_for_unique(otype.clone(), field.clone(), value.clone())
+            .await?;
+        let mut entity = E::build(build_state, id).map_err(AppError::ValidationFailed)?;
+        ent_hooks::run_before_create(&mut entity).await?;
+
+        entity.validate()?.into_result()?;
+
+        let data = entity.serialize_to_bytes()?;
+        match self
+            .obj_create_unique(id, otype, data, field, value)
+            .await?
+        {
+            None => {
+                ent_hooks::run_after_create(&entity).await;
+                Ok((entity, true))
+            }
+            Some(winner) => {
+                let existing = self.obj_get_fresh(winner).await?.ok_or_else(|| {
+                    AppError::NotFound(format!("Object {} holding unique key not found", winner))
+                })?;
+                Ok((E::from_stored_object(&existing)?, false))
+            }
+        }
+    }
+
     async fn generate_id(&self, owner_id: Option<TaoId>) -> AppResult<TaoId>;
     async fn create_object(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<()>;
     async fn obj_get(&self, id: TaoId) -> AppResult<Option<TaoObject>>;
+    /// `obj_get` that bypasses the cache and replicas, repopulating the cache from the primary
+    async fn obj_get_fresh(&self, id: TaoId) -> AppResult<Option<TaoObject>> {
+        with_read_consistency(ReadConsistency::Fresh, self.obj_get(id)).await
+    }
     async fn obj_update(&self, id: TaoId, data: Vec<u8>) -> AppResult<()>;
     async fn obj_delete(&self, id: TaoId) -> AppResult<bool>;
     async fn obj_exists(&self, id: TaoId) -> AppResult<bool>;
@@ -240,12 +497,136 @@ pub trait TaoOperations: Send + Sync + std::fmt::Debug {
     async fn obj_update_by_type(&self, id: TaoId, otype: TaoType, data: Vec<u8>)
         -> AppResult<bool>;
     async fn obj_delete_by_type(&self, id: TaoId, otype: TaoType) -> AppResult<bool>;
+    /// Delete an object together with every edge stored on it, in one transaction on
+    /// its shard. The inverses of those edges sit on the other endpoints' shards and are
+    /// returned in `inverse_deferred` rather than deleted here.
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport>;
+    /// Values `obj_update` overwrote, newest first. Only recorded for entity types whose
+    /// schema sets `keep_history`; other objects have none.
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>>;
+    /// Restore the data `id` had at `to_version` from its history. The restore is itself
+    /// an update, so it gets a new version and the current value goes into the history.
+    /// Returns the restored data.
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>>;
+
+    // Unique key operations - a secondary index from (otype, field, value) to one object.
+    // Each key is hashed to a shard and its object is created there, so claiming the key
+    // and writing the object happen in one transaction.
+    /// The object holding the unique key `field = value` of `otype`, if any
+    async fn obj_get_by_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoObject>>;
+    /// An id on the shard that owns the unique key, for use with `obj_create_unique`
+    async fn generate_id_for_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<TaoId>;
+    /// Create the object and claim its unique key in one transaction. If another object
+    /// already holds the key nothing is written, and that object's id is returned.
+    async fn obj_create_unique(
+        &self,
+        id: TaoId,
+        otype: TaoType,
+        data: Vec<u8>,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoId>>;
+    /// Create every object and edge of `batch` in one transaction, all or nothing. All
+    /// object ids and edge id1s must live on one shard, and no edge may have a bucketed
+    /// inverse; otherwise nothing is written.
+    async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()>;
+
+    // Attribute operations - free-form values stored beside an object, outside its typed
+    // Thrift payload, so experimental data needs no schema change and leaves the wire
+    // format alone. Attributes are not indexed beyond (id, key) and are deleted with the object.
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()>;
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>>;
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>>;
 
     // Association operations
     async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>>;
+    /// `assoc_get` that bypasses the cache and repopulates it from the store
+    async fn assoc_get_fresh(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
+        with_read_consistency(ReadConsistency::Fresh, self.assoc_get(query)).await
+    }
+    /// Like `assoc_get`, but also returns the total count when `query.include_total` is set
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult>;
+    /// Edges of several types from `id1` in one round-trip, newest first within each type.
+    /// Every requested type has an entry, empty if it has no edges.
+    async fn assoc_get_multi_type(
+        &self,
+        id1: TaoId,
+        atypes: Vec<AssocType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>>;
+    /// Adds the edge. A symmetric type (see `EdgeDefinition::symmetric`) is written in
+    /// both directions, each on its id1's shard.
     async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()>;
+    /// Deletes the edge, and for a symmetric type the other direction too. True if
+    /// either direction existed.
     async fn assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool>;
+    /// Delete every `atype` edge from `id1` in one statement and reset its count to 0,
+    /// logging the removals in the same transaction. Registered inverse edges are removed
+    /// too, one delete per edge on the other endpoint's shard.
+    async fn assoc_delete_all(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<AssocDeleteAllReport>;
+    /// Move an existing edge to `new_time` (default: now) in id1's time-ordered list,
+    /// e.g. to bump it to the top of recents. Unlike delete + add, the count and the
+    /// edge data are left untouched. Returns false if the edge does not exist.
+    async fn assoc_touch(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+        new_time: Option<TaoTime>,
+    ) -> AppResult<bool>;
+    /// Re-point the edge `id1 -atype-> old_id2` at `new_id2`, keeping its time, data and
+    /// subtype, in one transaction on id1's shard. The count only drops if `new_id2` was
+    /// already linked. The inverse moves from `old_id2` to `new_id2` too, if there is one,
+    /// on those shards once the forward edge has committed; if that fails the error says
+    /// the inverse still points from `old_id2`.
+    async fn assoc_move(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    ) -> AppResult<AssocMoveReport>;
+    /// Delete every edge between `id1` and `id2` in both directions, e.g. when one blocks
+    /// the other, or only those of `atypes` and their registered inverses. Each direction
+    /// is deleted in one transaction on its id1's shard, counts included. Returns how
+    /// many edges were removed per type; types with none are left out.
+    async fn assoc_delete_between(
+        &self,
+        id1: TaoId,
+        id2: TaoId,
+        atypes: Option<Vec<AssocType>>,
+    ) -> AppResult<HashMap<AssocType, u64>>;
     async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64>;
+    /// Counts for many `(id1, atype)` pairs, one query per shard. Pairs with no
+    /// associations map to 0, so every requested pair is present in the result.
+    async fn assoc_count_many(
+        &self,
+        pairs: Vec<(TaoId, AssocType)>,
+    ) -> AppResult<HashMap<(TaoId, AssocType), u64>>;
+    /// Edges of `atype` from `id1` counted per subtype, plain edges under `None`.
+    /// The counts add up to `assoc_count`, which keeps aggregating across subtypes.
+    async fn assoc_count_by_subtype(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<HashMap<Option<String>, u64>>;
+    /// Every association type stored with `id` as id1, with its count, ordered by type.
+    /// Read from the count table, so it costs one indexed lookup however many edges exist.
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>>;
     async fn assoc_range(
         &self,
         id1: TaoId,
@@ -261,7 +642,71 @@ pub trait TaoOperations: Send + Sync + std::fmt::Debug {
         low_time: i64,
         limit: Option<u32>,
     ) -> AppResult<Vec<TaoAssociation>>;
+    /// Edges created strictly after `since_time`, oldest first, together with the
+    /// newest timestamp seen. Pass that timestamp back as `since_time` on the next
+    /// poll to receive only edges added in between.
+    async fn assoc_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_time: TaoTime,
+        limit: u32,
+    ) -> AppResult<(Vec<TaoAssociation>, TaoTime)>;
+    /// The id2s added to and removed from `(id1, atype)` since version `since_seq`, and the
+    /// current version to pass back on the next poll. An id2 changed more than once is
+    /// reported by its last change. Version 0 returns every current id2 as added: a full
+    /// resync. A `since_seq` older than the retained changes fails with
+    /// `AppError::ResyncRequired`, after which the client starts over from 0.
+    async fn assoc_delta_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_seq: u64,
+    ) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)>;
+    /// For a symmetric type, true if the edge exists in either direction
     async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool>;
+    /// The single (id1, atype, id2) edge with its time and data, or `None` if absent.
+    /// One point lookup instead of `assoc_exists` followed by a filtered `assoc_get`.
+    async fn assoc_get_one(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+    ) -> AppResult<Option<TaoAssociation>>;
+    /// Add the `P::ATYPE` edge from `id1` to `id2` with `payload` as its data
+    async fn assoc_add_typed<P: EdgePayload>(
+        &self,
+        id1: TaoId,
+        id2: TaoId,
+        payload: P,
+    ) -> AppResult<()>
+    where
+        Self: Sized,
+    {
+        let data = payload.to_data();
+        self.assoc_add(create_tao_association(
+            id1,
+            P::ATYPE.to_string(),
+            id2,
+            Some(data),
+        ))
+        .await
+    }
+    /// The payload of the `P::ATYPE` edge from `id1` to `id2`, or `None` if absent.
+    /// An edge without data decodes as an empty object.
+    async fn assoc_get_typed<P: EdgePayload>(&self, id1: TaoId, id2: TaoId) -> AppResult<Option<P>>
+    where
+        Self: Sized,
+    {
+        match self.assoc_get_one(id1, P::ATYPE.to_string(), id2).await? {
+            Some(assoc) => P::from_data(assoc.data.as_deref().unwrap_or(b"{}")).map(Some),
+            None => Ok(None),
+        }
+    }
+    /// Every edge from `id1` to `id2`, of any type, newest first: how the two are directly
+    /// connected. Only edges stored on id1's shard are found, so bucketed fan-out inverses
+    /// are not; ask with the ids swapped for the edges in the other direction.
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>>;
 
     // Batch and utility operations
     async fn get_by_id_and_type(
@@ -269,24 +714,184 @@ pub trait TaoOperations: Send + Sync + std::fmt::Debug {
         ids: Vec<TaoId>,
         otype: TaoType,
     ) -> AppResult<Vec<TaoObject>>;
+    /// Typed batch read: the objects among `ids` whose type is `E`'s, deserialized, in
+    /// request order. Missing ids and objects of other types are skipped.
+    async fn get_entities_by_ids<E: Entity>(&self, ids: Vec<TaoId>) -> AppResult<Vec<E>>
+    where
+        Self: Sized,
+    {
+        let objects = self
+            .get_by_id_and_type(ids.clone(), E::entity_type().to_string())
+            .await?;
+        let mut by_id: HashMap<TaoId, TaoObject> = objects
+            .into_iter()
+            .filter(|obj| obj.otype == E::entity_type())
+            .map(|obj| (obj.id, obj))
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| by_id.remove(&id))
+            .map(|obj| E::from_stored_object(&obj))
+            .collect()
+    }
+    /// Typed traversal: the `atype` neighbors of `id` whose type is `E`'s, deserialized, in
+    /// association order. `atype` may be an inverse edge. Neighbors of other types are
+    /// dropped after `limit` is applied, so fewer than `limit` entities can come back.
+    async fn get_neighbors_as<E: Entity>(
+        &self,
+        id: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<E>>
+    where
+        Self: Sized,
+    {
+        let neighbor_ids = self.get_neighbor_ids(id, atype, limit).await?;
+        self.get_entities_by_ids(neighbor_ids).await
+    }
     async fn get_neighbors(
         &self,
         id: TaoId,
         atype: AssocType,
         limit: Option<u32>,
     ) -> AppResult<Vec<TaoObject>>;
+    /// Like `get_neighbors`, but pairs each neighbor with the association that reached it
+    /// (its `time` and `data` included), in association order. Whether that edge is a
+    /// forward or inverse one follows from its `atype` in the association registry.
+    async fn get_neighbors_with_edges(
+        &self,
+        id: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<(TaoObject, TaoAssociation)>>;
     async fn get_neighbor_ids(
         &self,
         id1: TaoId,
         atype: AssocType,
         limit: Option<u32>,
     ) -> AppResult<Vec<TaoId>>;
+    /// Ids with an `atype` edge to `id2`, newest first, read from the inverse edges.
+    /// For high fan-out types this gathers the inverse from every bucket.
+    async fn get_in_neighbor_ids(
+        &self,
+        id2: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoId>>;
+    /// Edges of every type in `atypes` from `id` merged into one feed, newest first and
+    /// at most `limit` long, e.g. a user's posts, comments and likes. Each type is read
+    /// with up to `limit` edges in one multi-type query, then a k-way merge holding one
+    /// head per type takes the newest until the feed is full. Edges keep their `atype`.
+    async fn activity_feed(
+        &self,
+        id: TaoId,
+        atypes: Vec<AssocType>,
+        limit: u32,
+    ) -> AppResult<Vec<TaoAssociation>> {
+        let mut grouped: Vec<(AssocType, Vec<TaoAssociation>)> = self
+            .assoc_get_multi_type(id, atypes, Some(limit))
+            .await?
+            .into_iter()
+            .collect();
+        // Type order only breaks ties between edges of the same time and id2
+        grouped.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut lists: Vec<_> = grouped
+            .into_iter()
+            .map(|(_, mut edges)| {
+                edges.sort_by_key(|edge| std::cmp::Reverse((edge.time, edge.id2)));
+                edges.into_iter()
+            })
+            .collect();
+
+        let mut heads: Vec<Option<TaoAssociation>> = lists.iter_mut().map(Iterator::next).collect();
+        let mut newest: BinaryHeap<(TaoTime, TaoId, usize)> = heads
+            .iter()
+            .enumerate()
+            .filter_map(|(list, head)| head.as_ref().map(|edge| (edge.time, edge.id2, list)))
+            .collect();
+        let mut feed = Vec::with_capacity(limit as usize);
+        while feed.len() < limit as usize {
+            let Some((_, _, list)) = newest.pop() else {
+                break;
+            };
+            let next = lists[list].next();
+            if let Some(next) = &next {
+                newest.push((next.time, next.id2, list));
+            }
+            feed.extend(std::mem::replace(&mut heads[list], next));
+        }
+        Ok(feed)
+    }
+    /// Warm the cache for reads known to be coming, e.g. everything a feed render needs.
+    /// Edge lists are read first and then every requested or reached object, each step
+    /// with up to `PREFETCH_CONCURRENCY` reads in flight. The reads go through `self`, so
+    /// any cache layer in the stack keeps the results. Failures are only logged: the
+    /// real read will hit them again.
+    async fn prefetch(&self, requests: Vec<PrefetchRequest>) {
+        let mut object_ids = Vec::new();
+        let mut edge_queries = Vec::new();
+        for request in requests {
+            match request {
+                PrefetchRequest::Object(id) => object_ids.push(id),
+                PrefetchRequest::Neighbors { id1, atype, limit } => {
+                    edge_queries.push((id1, atype, limit))
+                }
+            }
+        }
+
+        let edge_lists: Vec<Vec<TaoAssociation>> = stream::iter(edge_queries)
+            .map(|(id1, atype, limit)| async move {
+                let query = TaoAssocQuery {
+                    id1,
+                    atype: atype.clone(),
+                    id2_set: None,
+                    low_id2: None,
+                    high_id2: None,
+                    high_time: None,
+                    low_time: None,
+                    limit,
+                    offset: None,
+                    after: None,
+                    include_total: false,
+                };
+                self.assoc_get(query)
+                    .await
+                    .map_err(|e| debug!("prefetch: edges {} -> {} failed: {}", id1, atype, e))
+                    .unwrap_or_default()
+            })
+            .buffer_unordered(PREFETCH_CONCURRENCY)
+            .collect()
+            .await;
+        object_ids.extend(edge_lists.into_iter().flatten().map(|assoc| assoc.id2));
+        object_ids.sort_unstable();
+        object_ids.dedup();
+
+        stream::iter(object_ids)
+            .for_each_concurrent(PREFETCH_CONCURRENCY, |id| async move {
+                if let Err(e) = self.obj_get(id).await {
+                    debug!("prefetch: object {} failed: {}", id, e);
+                }
+            })
+            .await;
+    }
+    /// Estimated number of objects of `otype`, summed across shards from each shard's
+    /// maintained per-type counter. Cheap enough to call before `get_all_objects_of_type`
+    /// to decide whether to paginate, but not an exact count.
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64>;
     /// Get all objects of a specific type across all shards.
     async fn get_all_objects_of_type(
         &self,
         otype: TaoType,
         limit: Option<u32>,
     ) -> AppResult<Vec<TaoObject>>;
+    /// Objects of `otype` created within `[start, end]` (inclusive) across all shards,
+    /// oldest first. The bounds are on the partition key, so out-of-range partitions are skipped.
+    async fn get_objects_in_range(
+        &self,
+        otype: TaoType,
+        start: TaoTime,
+        end: TaoTime,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoObject>>;
 
     // Transaction support
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction>;
@@ -306,6 +911,9 @@ pub trait TaoEntityBuilder: TaoOperations {
     ) -> AppResult<E>
     where
         E::BuilderState: Send + Sync + HasTao;
+
+    /// Start an `EntTransaction` that creates entities and edges as one atomic write
+    fn transaction(&self) -> EntTransaction;
 }
 
 // Implementation for Arc<dyn TaoOperations>
@@ -319,26 +927,58 @@ impl TaoEntityBuilder for Arc<dyn TaoOperations> {
         E::BuilderState: Send + Sync + HasTao,
     {
         state.set_tao(Arc::clone(self));
-        let id = self.generate_id(None).await?;
-        let entity = E::build(state, id).map_err(AppError::Validation)?;
+        // An owned entity is placed on its creator's shard, so it and both owner edges
+        // are written in one transaction there
+        let owner = E::owner_edge().zip(state.get_viewer_id());
+        let id = self
+            .generate_id(owner.map(|(_, viewer_id)| viewer_id))
+            .await?;
+        let mut entity = E::build(state, id).map_err(AppError::ValidationFailed)?;
+        ent_hooks::run_before_create(&mut entity).await?;
 
         // Validate entity
-        let validation_errors = entity.validate()?;
-        if !validation_errors.is_empty() {
-            return Err(AppError::Validation(format!(
-                "Validation failed: {}",
-                validation_errors.join(", ")
-            )));
-        }
+        entity.validate()?.into_result()?;
 
         // Serialize and store
         let data = entity.serialize_to_bytes()?;
         let otype = <E as EntBuilder>::entity_type().to_string();
+        let mut batch = TaoWriteBatch {
+            objects: vec![(id, otype, data)],
+            associations: Vec::new(),
+        };
 
-        self.create_object(id, otype, data).await?;
+        // Record authorship for schemas that declare an owner edge
+        if let Some((owner_edge, viewer_id)) = owner {
+            let time = current_time_millis();
+            batch.associations.push(TaoAssociation {
+                id1: id,
+                atype: owner_edge.atype.to_string(),
+                id2: viewer_id,
+                time,
+                data: None,
+                subtype: None,
+            });
+            if let Some(inverse) = owner_edge.inverse {
+                batch.associations.push(TaoAssociation {
+                    id1: viewer_id,
+                    atype: inverse.to_string(),
+                    id2: id,
+                    time,
+                    data: None,
+                    subtype: None,
+                });
+            }
+        }
+        self.write_batch(batch).await?;
+
+        ent_hooks::run_after_create(&entity).await;
 
         Ok(entity)
     }
+
+    fn transaction(&self) -> EntTransaction {
+        EntTransaction::new(Arc::clone(self))
+    }
 }
 
 /// TaoCore - Core TAO implementation following Meta's architecture
@@ -349,6 +989,10 @@ pub struct TaoCore {
     query_router: Arc<TaoQueryRouter>,
     /// Association registry for inverse type lookups
     association_registry: Arc<AssociationRegistry>,
+    /// Otypes whose overwritten values are kept by `obj_update`
+    history_otypes: HashSet<TaoType>,
+    /// Applied to every neighbor list and association range
+    list_limits: ListLimits,
 }
 
 impl TaoCore {
@@ -359,39 +1003,70 @@ impl TaoCore {
         Self {
             query_router,
             association_registry,
+            history_otypes: HashSet::new(),
+            list_limits: ListLimits::default(),
         }
     }
 
+    /// Use `limits` for neighbor lists and association ranges instead of the defaults
+    pub fn with_list_limits(mut self, limits: ListLimits) -> Self {
+        self.list_limits = limits;
+        self
+    }
+
+    /// Keep the history of objects of these types, as declared by schemas' `keep_history`
+    pub fn with_object_history<I, S>(mut self, otypes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<TaoType>,
+    {
+        self.history_otypes = otypes.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Initialize TaoCore with configuration
     pub async fn from_config(
         mut config: TaoConfig,
         association_registry: Arc<AssociationRegistry>,
     ) -> AppResult<Self> {
-        let query_router = Arc::new(TaoQueryRouter::new(config.query_router_config).await);
+        let query_router = Arc::new(
+            TaoQueryRouter::with_id_generator(
+                config.query_router_config,
+                config.id_strategy.build_with_epoch(config.id_epoch_ms)?,
+            )
+            .await,
+        );
 
         // Initialize database shards from config
         for shard_config in config.database_shards.drain(..) {
             info!(
                 "Initializing shard {} at {}",
-                shard_config.shard_id, shard_config.connection_string
+                shard_config.shard_id,
+                redact_dsn(&shard_config.connection_string)
             );
 
-            let pool = PgPoolOptions::new()
+            let connect_options = pg_connect_options(
+                &shard_config.connection_string,
+                shard_config.password_source.as_ref(),
+            )?;
+            let pool_options = PgPoolOptions::new()
                 .max_connections(shard_config.max_connections)
                 .min_connections(shard_config.min_connections)
                 .acquire_timeout(std::time::Duration::from_secs(
                     shard_config.acquire_timeout_secs,
+                ));
+            let database = PostgresDatabase::connect(
+                pool_options,
+                connect_options,
+                query_router.statement_timeouts(),
+            )
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to connect to database for shard {}: {}",
+                    shard_config.shard_id, e
                 ))
-                .connect(&shard_config.connection_string)
-                .await
-                .map_err(|e| {
-                    AppError::DatabaseError(format!(
-                        "Failed to connect to database for shard {}: {}",
-                        shard_config.shard_id, e
-                    ))
-                })?;
-
-            let database = PostgresDatabase::new(pool);
+            })?;
             database.initialize().await?;
 
             let db_interface: Arc<dyn DatabaseInterface> = Arc::new(database);
@@ -412,10 +1087,215 @@ impl TaoCore {
 
         info!("✅ All {} shards configured", config.database_shards.len());
 
-        Ok(Self::new(query_router, association_registry))
+        Ok(Self::new(query_router, association_registry).with_list_limits(config.list_limits))
+    }
+
+    /// Add `assoc` on its id1's shard and log the addition in the same transaction
+    async fn create_association_logged(&self, assoc: Association) -> AppResult<()> {
+        let database = self
+            .query_router
+            .get_write_database_for_object(assoc.id1)
+            .await?;
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(self.query_router.get_shard_for_object(assoc.id1), &database)
+            .await?;
+        let (id1, atype, id2) = (assoc.id1, assoc.atype.clone(), assoc.id2);
+        let created = async {
+            database.create_association_tx(&mut tx, assoc).await?;
+            database
+                .record_association_changes_tx(&mut tx, id1, atype, vec![(id2, true)])
+                .await?;
+            AppResult::Ok(())
+        }
+        .await;
+        match created {
+            Ok(()) => tx.commit().await?,
+            Err(e) => {
+                tx.rollback().await?;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete `id1 -atype-> id2` on id1's shard and log the removal in the same
+    /// transaction; false if the edge did not exist
+    async fn delete_association_logged(
+        &self,
+        id1: TaoId,
+        atype: &str,
+        id2: TaoId,
+    ) -> AppResult<bool> {
+        let database = self.query_router.get_write_database_for_object(id1).await?;
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(self.query_router.get_shard_for_object(id1), &database)
+            .await?;
+        let deleted = async {
+            let deleted = database
+                .delete_association_tx(&mut tx, id1, atype.to_string(), id2)
+                .await?;
+            if deleted {
+                database
+                    .record_association_changes_tx(
+                        &mut tx,
+                        id1,
+                        atype.to_string(),
+                        vec![(id2, false)],
+                    )
+                    .await?;
+            }
+            AppResult::Ok(deleted)
+        }
+        .await;
+        match deleted {
+            Ok(deleted) => {
+                tx.commit().await?;
+                Ok(deleted)
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Where the bucketed inverse of the high fan-out edge `id1 -atype-> id2` is stored,
+    /// or `None` if `atype` is not high fan-out
+    async fn fanout_inverse(
+        &self,
+        id1: TaoId,
+        atype: &str,
+        id2: TaoId,
+    ) -> AppResult<Option<(AssocType, Arc<dyn DatabaseInterface>)>> {
+        let Some(buckets) = self.association_registry.get_fanout_buckets(atype).await else {
+            return Ok(None);
+        };
+        let Some(inverse) = self
+            .association_registry
+            .get_inverse_association_type(atype)
+            .await
+        else {
+            return Ok(None);
+        };
+        let bucket = id1.rem_euclid(buckets as i64) as u32;
+        let database = self.fanout_bucket_database(id2, bucket).await?;
+        Ok(Some((fanout_bucket_atype(&inverse, bucket), database)))
+    }
+
+    /// Move the inverse of `edge`, which `assoc_move` re-pointed from `old_id2` to
+    /// `new_id2`, to the new endpoint. Returns the inverse's type if it was moved.
+    async fn move_inverse(
+        &self,
+        id1: TaoId,
+        atype: &str,
+        edge: &Association,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    ) -> AppResult<Option<AssocType>> {
+        if let Some((bucket_atype, old_bucket)) = self.fanout_inverse(id1, atype, old_id2).await? {
+            old_bucket
+                .delete_association(old_id2, bucket_atype.clone(), id1)
+                .await?;
+            if let Some((_, new_bucket)) = self.fanout_inverse(id1, atype, new_id2).await? {
+                new_bucket
+                    .create_association(Association {
+                        id1: new_id2,
+                        atype: bucket_atype.clone(),
+                        id2: id1,
+                        time: edge.time,
+                        data: None,
+                        subtype: edge.subtype.clone(),
+                    })
+                    .await?;
+            }
+            return Ok(Some(bucket_atype));
+        }
+
+        let Some(inverse) = self
+            .association_registry
+            .get_inverse_association_type(atype)
+            .await
+        else {
+            return Ok(None);
+        };
+        let old_database = self
+            .query_router
+            .get_write_database_for_object(old_id2)
+            .await?;
+        let Some(inverse_edge) = old_database
+            .get_association(old_id2, inverse.clone(), id1)
+            .await?
+        else {
+            return Ok(None);
+        };
+        // Each side is deleted or added in one transaction with its change log entry
+        self.delete_association_logged(old_id2, &inverse, id1)
+            .await?;
+        let new_database = self
+            .query_router
+            .get_write_database_for_object(new_id2)
+            .await?;
+        if new_database
+            .get_association(new_id2, inverse.clone(), id1)
+            .await?
+            .is_none()
+        {
+            self.create_association_logged(Association {
+                id1: new_id2,
+                ..inverse_edge
+            })
+            .await?;
+        }
+        Ok(Some(inverse))
+    }
+
+    async fn fanout_bucket_database(
+        &self,
+        id: TaoId,
+        bucket: u32,
+    ) -> AppResult<Arc<dyn DatabaseInterface>> {
+        let shard_id = self.fanout_bucket_shard(id, bucket).await?;
+        self.query_router.get_database_for_shard(shard_id).await
+    }
+
+    /// The buckets of `id`'s inverse edges go round-robin over the shards in id order,
+    /// starting at `id`'s own shard
+    async fn fanout_bucket_shard(&self, id: TaoId, bucket: u32) -> AppResult<ShardId> {
+        let mut shards = self.query_router.get_all_shards().await;
+        if shards.is_empty() {
+            return Err(AppError::ShardError("No shards available".to_string()));
+        }
+        shards.sort();
+        let home = self.query_router.get_shard_for_object(id);
+        let start = shards.iter().position(|shard| *shard == home).unwrap_or(0);
+        Ok(shards[(start + bucket as usize) % shards.len()])
+    }
+
+    /// Unique keys are hashed over the shards in id order
+    async fn unique_key_shard(&self, otype: &str, field: &str, value: &str) -> AppResult<ShardId> {
+        let mut shards = self.query_router.get_all_shards().await;
+        if shards.is_empty() {
+            return Err(AppError::ShardError("No shards available".to_string()));
+        }
+        shards.sort();
+        let mut hasher = DefaultHasher::new();
+        (otype, field, value).hash(&mut hasher);
+        Ok(shards[(hasher.finish() % shards.len() as u64) as usize])
     }
 }
 
+/// Association type under which one bucket of a high fan-out inverse is stored
+fn fanout_bucket_atype(inverse: &str, bucket: u32) -> AssocType {
+    format!("{}#{}", inverse, bucket)
+}
+
+/// Charge a page of `edges` associations on top of the shard read that fetched it
+fn charge_edge_page(edges: usize) {
+    charge_units(edges as u64 / EDGES_PER_UNIT);
+}
+
 #[async_trait]
 impl TaoOperations for TaoCore {
     async fn generate_id(&self, owner_id: Option<TaoId>) -> AppResult<TaoId> {
@@ -423,12 +1303,12 @@ impl TaoOperations for TaoCore {
     }
 
     async fn create_object(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<()> {
-        let database = self.query_router.get_database_for_object(id).await?;
+        let database = self.query_router.get_write_database_for_object(id).await?;
         database.create_object(id, otype, data).await
     }
 
     async fn obj_get(&self, id: TaoId) -> AppResult<Option<TaoObject>> {
-        let database = self.query_router.get_database_for_object(id).await?;
+        let database = self.query_router.get_read_database_for_object(id).await?;
         let result = database.get_object(id).await?;
 
         if let Some(obj) = result {
@@ -446,14 +1326,27 @@ impl TaoOperations for TaoCore {
     }
 
     async fn obj_update(&self, id: TaoId, data: Vec<u8>) -> AppResult<()> {
-        let database = self.query_router.get_database_for_object(id).await?;
-        database.update_object(id, data).await?; // Data is already in raw bytes (Thrift)
+        let database = self.query_router.get_write_database_for_object(id).await?;
+        // Only look the type up when some type keeps history
+        let keep_history = !self.history_otypes.is_empty()
+            && database
+                .get_object(id)
+                .await?
+                .is_some_and(|object| self.history_otypes.contains(&object.otype));
+        if keep_history {
+            let changed_by = get_viewer_context().ok().and_then(|vc| vc.user_id);
+            database
+                .update_object_with_history(id, data, changed_by)
+                .await?;
+        } else {
+            database.update_object(id, data).await?; // Data is already in raw bytes (Thrift)
+        }
         info!("obj_update: Object {} updated", id);
         Ok(())
     }
 
     async fn obj_delete(&self, id: TaoId) -> AppResult<bool> {
-        let database = self.query_router.get_database_for_object(id).await?;
+        let database = self.query_router.get_write_database_for_object(id).await?;
         let deleted = database.delete_object(id).await?;
         if deleted {
             info!("obj_delete: Deleted object {}", id);
@@ -464,7 +1357,7 @@ impl TaoOperations for TaoCore {
     }
 
     async fn obj_exists(&self, id: TaoId) -> AppResult<bool> {
-        let database = self.query_router.get_database_for_object(id).await?;
+        let database = self.query_router.get_read_database_for_object(id).await?;
         database.object_exists(id).await
     }
 
@@ -494,34 +1387,444 @@ impl TaoOperations for TaoCore {
         self.obj_delete(id).await
     }
 
-    async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
-        let database = self.query_router.get_database_for_object(assoc.id1).await?;
-        let db_assoc: Association = assoc.clone().into(); // Convert TaoAssociation to Association
-        database.create_association(db_assoc).await?;
-        info!(
-            "assoc_add: Created association {}->{} ({})",
-            assoc.id1, assoc.id2, assoc.atype
-        );
-        Ok(())
+    async fn obj_get_by_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoObject>> {
+        let shard_id = self.unique_key_shard(&otype, &field, &value).await?;
+        let database = self.query_router.get_database_for_shard(shard_id).await?;
+        match database.get_unique_key(otype, field, value).await? {
+            Some(id) => self.obj_get(id).await,
+            None => Ok(None),
+        }
     }
 
-    async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
-        let database = self.query_router.get_database_for_object(query.id1).await?;
-        let db_query: AssocQuery = query.into();
-        let result = database.get_associations(db_query).await?;
-        // Convert database associations back to TAO associations
-        Ok(result
-            .associations
-            .into_iter()
-            .map(|assoc| assoc.into())
-            .collect())
+    async fn generate_id_for_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<TaoId> {
+        let shard_id = self.unique_key_shard(&otype, &field, &value).await?;
+        self.query_router.generate_tao_id_on_shard(shard_id).await
     }
 
-    async fn assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
-        let database = self.query_router.get_database_for_object(id1).await?;
-        let deleted = database.delete_association(id1, atype.clone(), id2).await?;
-        if deleted {
-            // Cache removed - handled by decorators now
+    async fn obj_create_unique(
+        &self,
+        id: TaoId,
+        otype: TaoType,
+        data: Vec<u8>,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoId>> {
+        let shard_id = self.unique_key_shard(&otype, &field, &value).await?;
+        if self.query_router.get_shard_for_object(id) != shard_id {
+            return Err(AppError::Validation(format!(
+                "Object {} is not on shard {} of unique key {}.{}",
+                id, shard_id, otype, field
+            )));
+        }
+        let database = self.query_router.get_database_for_shard(shard_id).await?;
+        self.query_router.record_write(id);
+
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(shard_id, &database)
+            .await?;
+        let claimed = async {
+            let claimed = database
+                .claim_unique_key_tx(&mut tx, otype.clone(), field.clone(), value.clone(), id)
+                .await?;
+            if claimed {
+                database
+                    .create_object_tx(&mut tx, id, otype.clone(), data)
+                    .await?;
+            }
+            AppResult::Ok(claimed)
+        }
+        .await;
+        match claimed {
+            Ok(true) => {
+                tx.commit().await?;
+                info!(
+                    "obj_create_unique: Created object {} for {}.{}",
+                    id, otype, field
+                );
+                Ok(None)
+            }
+            Ok(false) => {
+                tx.rollback().await?;
+                let holder = database.get_unique_key(otype, field, value).await?;
+                Ok(Some(holder.ok_or_else(|| {
+                    AppError::Internal("Unique key claimed but not readable".to_string())
+                })?))
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_batch(&self, mut batch: TaoWriteBatch) -> AppResult<()> {
+        // Symmetric edges go in both directions, so both endpoints must be on the shard
+        let mut reverses = Vec::new();
+        for assoc in &batch.associations {
+            if assoc.id1 != assoc.id2 && self.association_registry.is_symmetric(&assoc.atype).await
+            {
+                reverses.push(reversed(assoc));
+            }
+        }
+        batch.associations.extend(reverses);
+
+        let Some(&anchor) = batch
+            .objects
+            .iter()
+            .map(|(id, _, _)| id)
+            .chain(batch.associations.iter().map(|assoc| &assoc.id1))
+            .next()
+        else {
+            return Ok(());
+        };
+
+        // Everything is checked before the transaction opens
+        let shard_id = self.query_router.get_shard_for_object(anchor);
+        for id in batch
+            .objects
+            .iter()
+            .map(|(id, _, _)| *id)
+            .chain(batch.associations.iter().map(|assoc| assoc.id1))
+        {
+            let other = self.query_router.get_shard_for_object(id);
+            if other != shard_id {
+                return Err(AppError::Validation(format!(
+                    "Batch spans shards {} and {}: object {} is not co-located with {}",
+                    shard_id, other, id, anchor
+                )));
+            }
+            self.query_router.record_write(id);
+        }
+        for assoc in &batch.associations {
+            if self
+                .association_registry
+                .get_fanout_buckets(&assoc.atype)
+                .await
+                .is_some()
+            {
+                return Err(AppError::Validation(format!(
+                    "{} has a bucketed inverse on other shards and cannot be batched",
+                    assoc.atype
+                )));
+            }
+            if let Some(fields) = self
+                .association_registry
+                .get_payload_schema(&assoc.atype)
+                .await
+            {
+                let errors = validate_payload(&fields, assoc.data.as_deref());
+                if !errors.is_empty() {
+                    return Err(AppError::Validation(format!(
+                        "Invalid {} payload: {}",
+                        assoc.atype,
+                        errors.join(", ")
+                    )));
+                }
+            }
+        }
+        let database = self.query_router.get_database_for_shard(shard_id).await?;
+
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(shard_id, &database)
+            .await?;
+        let written = async {
+            for (id, otype, data) in &batch.objects {
+                database
+                    .create_object_tx(&mut tx, *id, otype.clone(), data.clone())
+                    .await?;
+            }
+            let mut changes: HashMap<(TaoId, AssocType), Vec<(TaoId, bool)>> = HashMap::new();
+            for assoc in &batch.associations {
+                database
+                    .create_association_tx(&mut tx, assoc.clone().into())
+                    .await?;
+                changes
+                    .entry((assoc.id1, assoc.atype.clone()))
+                    .or_default()
+                    .push((assoc.id2, true));
+            }
+            // Logged in the same transaction, so a sync never sees edges it isn't told about
+            for ((id1, atype), added) in changes {
+                database
+                    .record_association_changes_tx(&mut tx, id1, atype, added)
+                    .await?;
+            }
+            AppResult::Ok(())
+        }
+        .await;
+        match written {
+            Ok(()) => {
+                tx.commit().await?;
+                info!(
+                    "write_batch: Created {} objects and {} associations on shard {}",
+                    batch.objects.len(),
+                    batch.associations.len(),
+                    shard_id
+                );
+                Ok(())
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+        let database = self.query_router.get_read_database_for_object(id).await?;
+        let history = database.get_object_history(id, limit).await?;
+        Ok(history.into_iter().map(Into::into).collect())
+    }
+
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+        let database = self.query_router.get_write_database_for_object(id).await?;
+        let object = database
+            .get_object(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Object {} not found", id)))?;
+        if object.version == to_version {
+            return Ok(object.data);
+        }
+        let version = database
+            .get_object_version(id, to_version)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Object {} has no version {}", id, to_version))
+            })?;
+        self.obj_update(id, version.data.clone()).await?;
+        info!(
+            "obj_rollback: Object {} restored to version {}",
+            id, to_version
+        );
+        Ok(version.data)
+    }
+
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        let database = self.query_router.get_write_database_for_object(id).await?;
+        let associations = database.get_associations_from_object(id).await?;
+
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(self.query_router.get_shard_for_object(id), &database)
+            .await?;
+        let purged = async {
+            let mut removed = Vec::with_capacity(associations.len());
+            for assoc in &associations {
+                if database
+                    .delete_association_tx(&mut tx, id, assoc.atype.clone(), assoc.id2)
+                    .await?
+                {
+                    removed.push((assoc.atype.clone(), assoc.id2));
+                }
+            }
+            let mut changes: HashMap<AssocType, Vec<(TaoId, bool)>> = HashMap::new();
+            for (atype, id2) in &removed {
+                changes
+                    .entry(atype.clone())
+                    .or_default()
+                    .push((*id2, false));
+            }
+            for (atype, removed) in changes {
+                database
+                    .record_association_changes_tx(&mut tx, id, atype, removed)
+                    .await?;
+            }
+            let object_deleted = database.delete_object_tx(&mut tx, id).await?;
+            AppResult::Ok((object_deleted, removed))
+        }
+        .await;
+        let (object_deleted, associations_removed) = match purged {
+            Ok(purged) => {
+                tx.commit().await?;
+                purged
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                return Err(e);
+            }
+        };
+
+        // Inbound edges are only found through the inverses of the outbound ones:
+        // nothing indexes associations by id2
+        let mut inverse_removed = Vec::new();
+        let mut inverse_deferred = Vec::new();
+        for (atype, id2) in &associations_removed {
+            if let Some((bucket_atype, bucket_database)) =
+                self.fanout_inverse(id, atype, *id2).await?
+            {
+                // Bucketed inverses are not routed by their id1, so only this layer can find them
+                bucket_database
+                    .delete_association(*id2, bucket_atype.clone(), id)
+                    .await?;
+                inverse_removed.push((*id2, bucket_atype, id));
+            } else if let Some(inverse) = self
+                .association_registry
+                .get_inverse_association_type(atype)
+                .await
+            {
+                inverse_deferred.push((*id2, inverse, id));
+            }
+        }
+
+        info!(
+            "purge_object: Deleted object {} with {} associations, {} inverse edges deferred",
+            id,
+            associations_removed.len(),
+            inverse_deferred.len()
+        );
+        Ok(PurgeReport {
+            object_deleted,
+            associations_removed,
+            inverse_removed,
+            inverse_deferred,
+        })
+    }
+
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        let database = self.query_router.get_write_database_for_object(id).await?;
+        if !database.object_exists(id).await? {
+            return Err(AppError::NotFound(format!("Object {} not found", id)));
+        }
+        database.set_object_attribute(id, key, value).await
+    }
+
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+        let database = self.query_router.get_database_for_object(id).await?;
+        database.get_object_attribute(id, key).await
+    }
+
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+        let database = self.query_router.get_database_for_object(id).await?;
+        database.get_object_attributes(id).await
+    }
+
+    async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
+        if let Some(fields) = self
+            .association_registry
+            .get_payload_schema(&assoc.atype)
+            .await
+        {
+            let errors = validate_payload(&fields, assoc.data.as_deref());
+            if !errors.is_empty() {
+                return Err(AppError::Validation(format!(
+                    "Invalid {} payload: {}",
+                    assoc.atype,
+                    errors.join(", ")
+                )));
+            }
+        }
+        let db_assoc: Association = assoc.clone().into(); // Convert TaoAssociation to Association
+        self.create_association_logged(db_assoc).await?;
+        if assoc.id1 != assoc.id2 && self.association_registry.is_symmetric(&assoc.atype).await {
+            // Stored with id2 like any edge from it, so id2's count goes up too
+            self.create_association_logged(reversed(&assoc).into())
+                .await?;
+        }
+        if let Some((bucket_atype, bucket_database)) = self
+            .fanout_inverse(assoc.id1, &assoc.atype, assoc.id2)
+            .await?
+        {
+            bucket_database
+                .create_association(Association {
+                    id1: assoc.id2,
+                    atype: bucket_atype,
+                    id2: assoc.id1,
+                    time: assoc.time,
+                    data: None,
+                    subtype: assoc.subtype.clone(),
+                })
+                .await?;
+        }
+        info!(
+            "assoc_add: Created association {}->{} ({})",
+            assoc.id1, assoc.id2, assoc.atype
+        );
+        Ok(())
+    }
+
+    async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
+        let database = self.query_router.get_read_database_for_object(query.id1).await?;
+        let db_query: AssocQuery = query.into();
+        let result = database.get_associations(db_query).await?;
+        charge_edge_page(result.associations.len());
+        // Convert database associations back to TAO associations
+        Ok(result
+            .associations
+            .into_iter()
+            .map(|assoc| assoc.into())
+            .collect())
+    }
+
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+        let database = self.query_router.get_read_database_for_object(query.id1).await?;
+        let result = database.get_associations(query.into()).await?;
+        charge_edge_page(result.associations.len());
+        Ok(TaoAssocQueryResult {
+            associations: result
+                .associations
+                .into_iter()
+                .map(|assoc| assoc.into())
+                .collect(),
+            next_cursor: result.next_cursor,
+            total_count: result.total_count,
+        })
+    }
+
+    async fn assoc_get_multi_type(
+        &self,
+        id1: TaoId,
+        atypes: Vec<AssocType>,
+        limit_per_type: Option<u32>,
+    ) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+        let mut grouped: HashMap<AssocType, Vec<TaoAssociation>> = atypes
+            .iter()
+            .map(|atype| (atype.clone(), Vec::new()))
+            .collect();
+        if atypes.is_empty() {
+            return Ok(grouped);
+        }
+
+        let database = self.query_router.get_database_for_object(id1).await?;
+        let atypes: Vec<AssocType> = grouped.keys().cloned().collect();
+        for assoc in database
+            .get_associations_multi_type(id1, atypes, limit_per_type)
+            .await?
+        {
+            let assoc: TaoAssociation = assoc.into();
+            grouped.entry(assoc.atype.clone()).or_default().push(assoc);
+        }
+        Ok(grouped)
+    }
+
+    async fn assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+        let mut deleted = self.delete_association_logged(id1, &atype, id2).await?;
+        if id1 != id2
+            && self.association_registry.is_symmetric(&atype).await
+            && self.delete_association_logged(id2, &atype, id1).await?
+        {
+            deleted = true;
+        }
+        if deleted {
+            if let Some((bucket_atype, bucket_database)) =
+                self.fanout_inverse(id1, &atype, id2).await?
+            {
+                bucket_database
+                    .delete_association(id2, bucket_atype, id1)
+                    .await?;
+            }
+            // Cache removed - handled by decorators now
             info!(
                 "assoc_delete: Deleted association {}->{} ({})",
                 id1, id2, atype
@@ -535,11 +1838,334 @@ impl TaoOperations for TaoCore {
         Ok(deleted)
     }
 
+    async fn assoc_delete_all(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<AssocDeleteAllReport> {
+        let database = self.query_router.get_write_database_for_object(id1).await?;
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(self.query_router.get_shard_for_object(id1), &database)
+            .await?;
+        let deleted = async {
+            let removed = database
+                .delete_associations_of_type_tx(&mut tx, id1, atype.clone())
+                .await?;
+            if !removed.is_empty() {
+                database
+                    .record_association_changes_tx(
+                        &mut tx,
+                        id1,
+                        atype.clone(),
+                        removed.iter().map(|id2| (*id2, false)).collect(),
+                    )
+                    .await?;
+            }
+            AppResult::Ok(removed)
+        }
+        .await;
+        let removed = match deleted {
+            Ok(removed) => {
+                tx.commit().await?;
+                removed
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                return Err(e);
+            }
+        };
+
+        // Each inverse lives with its id2, so these cannot share the transaction above
+        let inverse = self
+            .association_registry
+            .get_inverse_association_type(&atype)
+            .await;
+        let mut inverse_removed = Vec::new();
+        for id2 in &removed {
+            if let Some((bucket_atype, bucket_database)) =
+                self.fanout_inverse(id1, &atype, *id2).await?
+            {
+                if bucket_database
+                    .delete_association(*id2, bucket_atype.clone(), id1)
+                    .await?
+                {
+                    inverse_removed.push((*id2, bucket_atype, id1));
+                }
+            } else if let Some(inverse) = &inverse {
+                if self.delete_association_logged(*id2, inverse, id1).await? {
+                    inverse_removed.push((*id2, inverse.clone(), id1));
+                }
+            }
+        }
+
+        info!(
+            "assoc_delete_all: Deleted {} associations {} ({})",
+            removed.len(),
+            id1,
+            atype
+        );
+        Ok(AssocDeleteAllReport {
+            removed,
+            inverse_removed,
+        })
+    }
+
+    async fn assoc_touch(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+        new_time: Option<TaoTime>,
+    ) -> AppResult<bool> {
+        let time = new_time.unwrap_or_else(current_time_millis);
+        let database = self.query_router.get_write_database_for_object(id1).await?;
+        database.touch_association(id1, atype, id2, time).await
+    }
+
+    async fn assoc_move(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    ) -> AppResult<AssocMoveReport> {
+        let database = self.query_router.get_write_database_for_object(id1).await?;
+        let Some(edge) = database
+            .get_association(id1, atype.clone(), old_id2)
+            .await?
+        else {
+            return Ok(AssocMoveReport::default());
+        };
+        if old_id2 == new_id2 {
+            return Ok(AssocMoveReport {
+                moved: true,
+                inverse_atype: None,
+            });
+        }
+        let already_linked = database
+            .get_association(id1, atype.clone(), new_id2)
+            .await?
+            .is_some();
+
+        let mut changes = vec![(old_id2, false)];
+        if !already_linked {
+            changes.push((new_id2, true));
+        }
+        let mut tx = self
+            .query_router
+            .begin_shard_transaction(self.query_router.get_shard_for_object(id1), &database)
+            .await?;
+        let moved = async {
+            database
+                .delete_association_tx(&mut tx, id1, atype.clone(), old_id2)
+                .await?;
+            if !already_linked {
+                database
+                    .create_association_tx(
+                        &mut tx,
+                        Association {
+                            id2: new_id2,
+                            ..edge.clone()
+                        },
+                    )
+                    .await?;
+            }
+            database
+                .record_association_changes_tx(&mut tx, id1, atype.clone(), changes)
+                .await?;
+            AppResult::Ok(())
+        }
+        .await;
+        match moved {
+            Ok(()) => tx.commit().await?,
+            Err(e) => {
+                tx.rollback().await?;
+                return Err(e);
+            }
+        }
+        self.query_router.record_write(id1);
+
+        // The inverse lives with the other endpoint, so it moves between shards after the
+        // forward edge has committed
+        let inverse_atype = match self
+            .move_inverse(id1, &atype, &edge, old_id2, new_id2)
+            .await
+        {
+            Ok(inverse_atype) => inverse_atype,
+            Err(e) => {
+                error!(
+                    "assoc_move: Moved {}->{} ({}) to {} but not its inverse, which needs \
+                     repair: {}",
+                    id1, old_id2, atype, new_id2, e
+                );
+                return Err(AppError::TransactionError(format!(
+                    "Moved {} -{}-> {} to {}, but its inverse still points from {}: {}",
+                    id1, atype, old_id2, new_id2, old_id2, e
+                )));
+            }
+        };
+
+        info!(
+            "assoc_move: Moved association {}->{} ({}) to {}",
+            id1, old_id2, atype, new_id2
+        );
+        Ok(AssocMoveReport {
+            moved: true,
+            inverse_atype,
+        })
+    }
+
+    async fn assoc_delete_between(
+        &self,
+        id1: TaoId,
+        id2: TaoId,
+        atypes: Option<Vec<AssocType>>,
+    ) -> AppResult<HashMap<AssocType, u64>> {
+        // Asked-for types take their inverses along, so no half of an edge is left behind
+        let atypes = match atypes {
+            Some(atypes) => {
+                let mut with_inverses = atypes.clone();
+                for atype in &atypes {
+                    if let Some(inverse) = self
+                        .association_registry
+                        .get_inverse_association_type(atype)
+                        .await
+                    {
+                        with_inverses.push(inverse);
+                    }
+                }
+                with_inverses.sort();
+                with_inverses.dedup();
+                Some(with_inverses)
+            }
+            None => None,
+        };
+
+        let mut removed: HashMap<AssocType, u64> = HashMap::new();
+        let directions = if id1 == id2 {
+            vec![(id1, id2)]
+        } else {
+            vec![(id1, id2), (id2, id1)]
+        };
+        for (from, to) in directions {
+            let database = self
+                .query_router
+                .get_write_database_for_object(from)
+                .await?;
+            let from_atypes = match &atypes {
+                Some(atypes) => atypes.clone(),
+                // Fan-out bucket inverses are deleted with their forward edges below
+                None => database
+                    .get_association_counts_for_object(from)
+                    .await?
+                    .into_iter()
+                    .map(|(atype, _)| atype)
+                    .filter(|atype| !atype.contains('#'))
+                    .collect(),
+            };
+
+            let mut deleted = Vec::new();
+            let mut tx = self
+                .query_router
+                .begin_shard_transaction(self.query_router.get_shard_for_object(from), &database)
+                .await?;
+            let result = async {
+                for atype in &from_atypes {
+                    if database
+                        .delete_association_tx(&mut tx, from, atype.clone(), to)
+                        .await?
+                    {
+                        database
+                            .record_association_changes_tx(
+                                &mut tx,
+                                from,
+                                atype.clone(),
+                                vec![(to, false)],
+                            )
+                            .await?;
+                        deleted.push(atype.clone());
+                    }
+                }
+                AppResult::Ok(())
+            }
+            .await;
+            match result {
+                Ok(()) => tx.commit().await?,
+                Err(e) => {
+                    tx.rollback().await?;
+                    return Err(e);
+                }
+            }
+            if deleted.is_empty() {
+                continue;
+            }
+            self.query_router.record_write(from);
+
+            for atype in deleted {
+                if let Some((bucket_atype, bucket_database)) =
+                    self.fanout_inverse(from, &atype, to).await?
+                {
+                    bucket_database
+                        .delete_association(to, bucket_atype, from)
+                        .await?;
+                }
+                *removed.entry(atype).or_default() += 1;
+            }
+        }
+
+        info!(
+            "assoc_delete_between: Deleted {} associations between {} and {}",
+            removed.values().sum::<u64>(),
+            id1,
+            id2
+        );
+        Ok(removed)
+    }
+
     async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
-        let database = self.query_router.get_database_for_object(id1).await?;
+        let database = self.query_router.get_read_database_for_object(id1).await?;
         database.count_associations(id1, atype).await
     }
 
+    async fn assoc_count_many(
+        &self,
+        pairs: Vec<(TaoId, AssocType)>,
+    ) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+        let mut counts = HashMap::with_capacity(pairs.len());
+        let mut shard_groups: HashMap<ShardId, Vec<(TaoId, AssocType)>> = HashMap::new();
+        for (id1, atype) in pairs {
+            let shard_id = self.query_router.get_shard_for_object(id1);
+            counts.insert((id1, atype.clone()), 0);
+            shard_groups.entry(shard_id).or_default().push((id1, atype));
+        }
+
+        for (shard_id, shard_pairs) in shard_groups {
+            let database = self.query_router.get_database_for_shard(shard_id).await?;
+            let started = Instant::now();
+            let pairs = shard_pairs.len();
+            counts.extend(database.count_associations_many(shard_pairs).await?);
+            record_shard_span("assoc_count_many", shard_id, started, || {
+                format!("count_associations_many pairs={}", pairs)
+            });
+        }
+        Ok(counts)
+    }
+
+    async fn assoc_count_by_subtype(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+    ) -> AppResult<HashMap<Option<String>, u64>> {
+        let database = self.query_router.get_read_database_for_object(id1).await?;
+        database.count_associations_by_subtype(id1, atype).await
+    }
+
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+        let database = self.query_router.get_database_for_object(id).await?;
+        database.get_association_counts_for_object(id).await
+    }
+
     async fn assoc_range(
         &self,
         id1: TaoId,
@@ -551,13 +2177,19 @@ impl TaoOperations for TaoCore {
             id1,
             atype,
             id2_set: None,
+            low_id2: None,
+            high_id2: None,
             high_time: None,
             low_time: None,
-            limit: Some(limit),
+            limit: Some(self.list_limits.apply(Some(limit))),
             offset: Some(offset),
+            after: None,
+            include_total: false,
+            dedup: true,
         };
-        let database = self.query_router.get_database_for_object(id1).await?;
+        let database = self.query_router.get_read_database_for_object(id1).await?;
         let result = database.get_associations(query).await?;
+        charge_edge_page(result.associations.len());
         // Convert database associations back to TAO associations
         Ok(result
             .associations
@@ -578,13 +2210,19 @@ impl TaoOperations for TaoCore {
             id1,
             atype,
             id2_set: None,
+            low_id2: None,
+            high_id2: None,
             high_time: Some(high_time),
             low_time: Some(low_time),
             limit,
             offset: None,
+            after: None,
+            include_total: false,
+            dedup: true,
         };
-        let database = self.query_router.get_database_for_object(id1).await?;
+        let database = self.query_router.get_read_database_for_object(id1).await?;
         let result = database.get_associations(query).await?;
+        charge_edge_page(result.associations.len());
         // Convert database associations back to TAO associations
         Ok(result
             .associations
@@ -593,9 +2231,116 @@ impl TaoOperations for TaoCore {
             .collect())
     }
 
-    async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+    async fn assoc_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_time: TaoTime,
+        limit: u32,
+    ) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+        let database = self.query_router.get_database_for_object(id1).await?;
+        let associations: Vec<TaoAssociation> = database
+            .get_associations_since(id1, atype, since_time, limit)
+            .await?
+            .into_iter()
+            .map(|assoc| assoc.into())
+            .collect();
+
+        // Results are ascending, so the cursor is the last edge's time
+        let cursor = associations.last().map_or(since_time, |assoc| assoc.time);
+        Ok((associations, cursor))
+    }
+
+    async fn assoc_delta_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_seq: u64,
+    ) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+        // Not a replica: one lagging behind would hand out versions older than the client's
         let database = self.query_router.get_database_for_object(id1).await?;
-        database.association_exists(id1, atype, id2).await
+        let log = database
+            .get_association_changes(id1, atype.clone(), since_seq)
+            .await?;
+
+        if since_seq == 0 {
+            // Edges changed after the log was read come again on the next poll, and
+            // applying a change twice leaves the client's list the same
+            let edges = database
+                .get_associations(AssocQuery {
+                    id1,
+                    atype,
+                    id2_set: None,
+                    low_id2: None,
+                    high_id2: None,
+                    high_time: None,
+                    low_time: None,
+                    limit: None,
+                    offset: None,
+                    after: None,
+                    include_total: false,
+                    dedup: true,
+                })
+                .await?;
+            let added = edges.associations.iter().map(|assoc| assoc.id2).collect();
+            return Ok((added, Vec::new(), log.latest_seq));
+        }
+        if since_seq > log.latest_seq || since_seq + 1 < log.oldest_seq {
+            return Err(AppError::ResyncRequired(format!(
+                "Changes of {} ({}) since version {} are no longer retained",
+                id1, atype, since_seq
+            )));
+        }
+
+        let mut last_changes = HashMap::new();
+        for change in &log.changes {
+            last_changes.insert(change.id2, change);
+        }
+        let mut last_changes: Vec<_> = last_changes.into_values().collect();
+        last_changes.sort_by_key(|change| change.seq);
+        let (added, removed): (Vec<_>, Vec<_>) =
+            last_changes.into_iter().partition(|change| change.added);
+        Ok((
+            added.iter().map(|change| change.id2).collect(),
+            removed.iter().map(|change| change.id2).collect(),
+            log.latest_seq,
+        ))
+    }
+
+    async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+        let database = self.query_router.get_read_database_for_object(id1).await?;
+        if database.association_exists(id1, atype.clone(), id2).await? {
+            return Ok(true);
+        }
+        // A symmetric edge whose two writes were interrupted halfway still counts
+        if id1 != id2 && self.association_registry.is_symmetric(&atype).await {
+            let reverse_database = self.query_router.get_read_database_for_object(id2).await?;
+            return reverse_database.association_exists(id2, atype, id1).await;
+        }
+        Ok(false)
+    }
+
+    async fn assoc_get_one(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+    ) -> AppResult<Option<TaoAssociation>> {
+        let database = self.query_router.get_read_database_for_object(id1).await?;
+        Ok(database
+            .get_association(id1, atype, id2)
+            .await?
+            .map(|assoc| assoc.into()))
+    }
+
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+        let database = self.query_router.get_read_database_for_object(id1).await?;
+        Ok(database
+            .get_associations_between(id1, id2)
+            .await?
+            .into_iter()
+            .map(|assoc| assoc.into())
+            .collect())
     }
 
     async fn get_by_id_and_type(
@@ -607,7 +2352,7 @@ impl TaoOperations for TaoCore {
         let mut shard_groups: HashMap<ShardId, Vec<TaoId>> = HashMap::new();
 
         for id in ids {
-            let shard_id = self.query_router.get_shard_for_object(id).await;
+            let shard_id = self.query_router.get_shard_for_object(id);
             shard_groups.entry(shard_id).or_default().push(id);
         }
 
@@ -615,7 +2360,10 @@ impl TaoOperations for TaoCore {
             let database = self.query_router.get_database_for_shard(shard_id).await?;
             let query = ObjectQuery {
                 ids: shard_ids,
-                otype: Some(otype.clone()),
+                // An empty type matches any type (used by the neighbor lookups)
+                otype: (!otype.is_empty()).then(|| otype.clone()),
+                created_after: None,
+                created_before: None,
                 limit: None,
                 offset: None,
             };
@@ -646,26 +2394,142 @@ impl TaoOperations for TaoCore {
         self.get_by_id_and_type(neighbor_ids, "".to_string()).await
     }
 
+    async fn get_neighbors_with_edges(
+        &self,
+        id: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+        let limit = Some(self.list_limits.apply(limit));
+        let associations = self
+            .assoc_get(TaoAssocQuery {
+                id1: id,
+                atype,
+                id2_set: None,
+                low_id2: None,
+                high_id2: None,
+                high_time: None,
+                low_time: None,
+                limit,
+                offset: None,
+                after: None,
+                include_total: false,
+            })
+            .await?;
+        if associations.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let neighbor_ids = associations.iter().map(|assoc| assoc.id2).collect();
+        let mut objects: HashMap<TaoId, TaoObject> = self
+            .get_by_id_and_type(neighbor_ids, "".to_string())
+            .await?
+            .into_iter()
+            .map(|object| (object.id, object))
+            .collect();
+        // Edges whose target no longer exists are dropped, as in `get_neighbors`
+        Ok(associations
+            .into_iter()
+            .filter_map(|assoc| objects.remove(&assoc.id2).map(|object| (object, assoc)))
+            .collect())
+    }
+
     async fn get_neighbor_ids(
         &self,
         id1: TaoId,
         atype: AssocType,
         limit: Option<u32>,
     ) -> AppResult<Vec<TaoId>> {
-        let database = self.query_router.get_database_for_object(id1).await?;
+        let limit = Some(self.list_limits.apply(limit));
+        let database = self.query_router.get_read_database_for_object(id1).await?;
         let query = AssocQuery {
             id1,
             atype,
             id2_set: None,
+            low_id2: None,
+            high_id2: None,
             high_time: None,
             low_time: None,
             limit,
             offset: None,
+            after: None,
+            include_total: false,
+            dedup: true,
         };
         let result = database.get_associations(query).await?;
         Ok(result.associations.into_iter().map(|a| a.id2).collect())
     }
 
+    async fn get_in_neighbor_ids(
+        &self,
+        id2: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoId>> {
+        let Some(inverse) = self
+            .association_registry
+            .get_inverse_association_type(&atype)
+            .await
+        else {
+            return Err(AppError::Validation(format!(
+                "Association type {} has no inverse to read incoming edges from",
+                atype
+            )));
+        };
+        let Some(buckets) = self.association_registry.get_fanout_buckets(&atype).await else {
+            return self.get_neighbor_ids(id2, inverse, limit).await;
+        };
+        let limit = self.list_limits.apply(limit);
+
+        let mut edges = Vec::new();
+        for bucket in 0..buckets {
+            let shard_id = self.fanout_bucket_shard(id2, bucket).await?;
+            let database = self.query_router.get_database_for_shard(shard_id).await?;
+            let started = Instant::now();
+            let query = AssocQuery {
+                id1: id2,
+                atype: fanout_bucket_atype(&inverse, bucket),
+                id2_set: None,
+                low_id2: None,
+                high_id2: None,
+                high_time: None,
+                low_time: None,
+                limit: Some(limit),
+                offset: None,
+                after: None,
+                include_total: false,
+                dedup: true,
+            };
+            let bucket_atype = query.atype.clone();
+            edges.extend(database.get_associations(query).await?.associations);
+            record_shard_span("get_in_neighbor_ids", shard_id, started, || {
+                format!(
+                    "get_associations id1={} atype={} limit={}",
+                    id2, bucket_atype, limit
+                )
+            });
+        }
+        // Each bucket comes back newest first with up to `limit` edges, so this is exact
+        edges.sort_by_key(|assoc| std::cmp::Reverse(assoc.time));
+        edges.truncate(limit as usize);
+        Ok(edges.into_iter().map(|assoc| assoc.id2).collect())
+    }
+
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+        let mut total = 0;
+        let all_shard_ids = self.query_router.shard_manager.get_healthy_shards().await;
+
+        for shard_id in all_shard_ids {
+            let db = self.query_router.get_database_for_scan(shard_id).await?;
+            let started = Instant::now();
+            total += db.get_object_type_count(otype.clone()).await?;
+            record_shard_span("estimate_count_of_type", shard_id, started, || {
+                format!("get_object_type_count otype={}", otype)
+            });
+        }
+        Ok(total)
+    }
+
     async fn get_all_objects_of_type(
         &self,
         otype: TaoType,
@@ -675,14 +2539,20 @@ impl TaoOperations for TaoCore {
         let all_shard_ids = self.query_router.shard_manager.get_healthy_shards().await;
 
         for shard_id in all_shard_ids {
-            let db = self.query_router.get_database_for_shard(shard_id).await?;
+            let db = self.query_router.get_database_for_scan(shard_id).await?;
             let query = ObjectQuery {
                 ids: vec![],
                 otype: Some(otype.clone()),
+                created_after: None,
+                created_before: None,
                 limit,
                 offset: None,
             };
+            let started = Instant::now();
             let result = db.get_objects(query).await?;
+            record_shard_span("get_all_objects_of_type", shard_id, started, || {
+                format!("get_objects otype={} limit={:?}", otype, limit)
+            });
             // Convert database objects back to TAO objects
             all_objects.extend(result.objects.into_iter().map(|obj| TaoObject {
                 id: obj.id,
@@ -696,6 +2566,52 @@ impl TaoOperations for TaoCore {
         Ok(all_objects)
     }
 
+    async fn get_objects_in_range(
+        &self,
+        otype: TaoType,
+        start: TaoTime,
+        end: TaoTime,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoObject>> {
+        let mut objects = Vec::new();
+        let all_shard_ids = self.query_router.shard_manager.get_healthy_shards().await;
+
+        for shard_id in all_shard_ids {
+            let db = self.query_router.get_database_for_scan(shard_id).await?;
+            let query = ObjectQuery {
+                ids: vec![],
+                otype: Some(otype.clone()),
+                created_after: Some(start),
+                created_before: Some(end),
+                limit,
+                offset: None,
+            };
+            let started = Instant::now();
+            let result = db.get_objects(query).await?;
+            record_shard_span("get_objects_in_range", shard_id, started, || {
+                format!(
+                    "get_objects otype={} created {}..{} limit={:?}",
+                    otype, start, end, limit
+                )
+            });
+            objects.extend(result.objects.into_iter().map(|obj| TaoObject {
+                id: obj.id,
+                otype: obj.otype,
+                data: obj.data,
+                created_time: obj.created_time,
+                updated_time: obj.updated_time,
+                version: obj.version,
+            }));
+        }
+
+        // Each shard returned up to `limit`; merge and keep the oldest overall
+        objects.sort_by_key(|obj| (obj.created_time, obj.id));
+        if let Some(limit) = limit {
+            objects.truncate(limit as usize);
+        }
+        Ok(objects)
+    }
+
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
         Err(AppError::Internal(
             "Distributed transactions not supported".to_string(),
@@ -708,6 +2624,18 @@ impl TaoOperations for TaoCore {
     }
 }
 
+/// The other direction of a symmetric edge
+fn reversed(assoc: &TaoAssociation) -> TaoAssociation {
+    TaoAssociation {
+        id1: assoc.id2,
+        atype: assoc.atype.clone(),
+        id2: assoc.id1,
+        time: assoc.time,
+        data: assoc.data.clone(),
+        subtype: assoc.subtype.clone(),
+    }
+}
+
 /// Create a TAO association
 pub fn create_tao_association(
     id1: TaoId,
@@ -721,5 +2649,1539 @@ pub fn create_tao_association(
         id2,
         time: current_time_millis(),
         data,
+        subtype: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::database::ASSOC_CHANGE_LOG_RETENTION;
+    use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+    use crate::infrastructure::id_generator::TaoIdGenerator;
+
+    async fn sqlite_tao_core() -> TaoCore {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        query_router.add_shard(shard_info, database).await.unwrap();
+
+        TaoCore::new(query_router, Arc::new(AssociationRegistry::new()))
+    }
+
+    fn like(id1: TaoId, id2: TaoId, time: TaoTime) -> TaoAssociation {
+        TaoAssociation {
+            id1,
+            atype: "liked_by".to_string(),
+            id2,
+            time,
+            data: None,
+            subtype: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assoc_since_returns_only_new_edges() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let post = ids.next_id();
+        let (alice, bob, carol) = (ids.next_id(), ids.next_id(), ids.next_id());
+
+        tao.assoc_add(like(post, alice, 1_000)).await.unwrap();
+        tao.assoc_add(like(post, bob, 2_000)).await.unwrap();
+
+        // First poll sees everything, oldest first
+        let (edges, cursor) = tao
+            .assoc_since(post, "liked_by".to_string(), 0, 10)
+            .await
+            .unwrap();
+        let seen: Vec<TaoId> = edges.iter().map(|a| a.id2).collect();
+        assert_eq!(seen, vec![alice, bob]);
+        assert_eq!(cursor, 2_000);
+
+        tao.assoc_add(like(post, carol, 3_000)).await.unwrap();
+
+        // Second poll from the returned cursor only sees the new like
+        let (edges, cursor) = tao
+            .assoc_since(post, "liked_by".to_string(), cursor, 10)
+            .await
+            .unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].id2, carol);
+        assert_eq!(cursor, 3_000);
+
+        // Nothing new: the cursor stays put
+        let (edges, cursor) = tao
+            .assoc_since(post, "liked_by".to_string(), cursor, 10)
+            .await
+            .unwrap();
+        assert!(edges.is_empty());
+        assert_eq!(cursor, 3_000);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_get_bounds_id2_range() {
+        let tao = sqlite_tao_core().await;
+        let post = TaoIdGenerator::new(0).next_id();
+        for id2 in 100..110 {
+            tao.assoc_add(like(post, id2, id2)).await.unwrap();
+        }
+
+        let edges = tao
+            .assoc_get(TaoAssocQuery {
+                id1: post,
+                atype: "liked_by".to_string(),
+                id2_set: None,
+                low_id2: Some(103),
+                high_id2: Some(106),
+                high_time: None,
+                low_time: None,
+                limit: None,
+                offset: None,
+                after: None,
+                include_total: false,
+            })
+            .await
+            .unwrap();
+        let mut id2s: Vec<TaoId> = edges.iter().map(|assoc| assoc.id2).collect();
+        id2s.sort();
+        assert_eq!(id2s, vec![103, 104, 105, 106]);
+    }
+
+    #[tokio::test]
+    async fn test_get_entities_by_ids_returns_only_requested_type() {
+        use crate::domains::post::EntPost;
+        use crate::domains::user::EntUser;
+
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let (alice, post, bob) = (ids.next_id(), ids.next_id(), ids.next_id());
+        for (id, name) in [(alice, "alice"), (bob, "bob")] {
+            let user = EntUser {
+                id,
+                username: name.to_string(),
+                email: format!("{}@example.com", name),
+                created_time: 1,
+                full_name: None,
+                bio: None,
+                profile_picture_url: None,
+                last_active_time: None,
+                is_verified: false,
+                location: None,
+                privacy_settings: None,
+            };
+            tao.create_object(
+                id,
+                "ent_user".to_string(),
+                user.serialize_to_bytes().unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        tao.create_object(post, "ent_post".to_string(), vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let users: Vec<EntUser> = tao
+            .get_entities_by_ids(vec![bob, post, alice, ids.next_id()])
+            .await
+            .unwrap();
+        let names: Vec<&str> = users.iter().map(|user| user.username.as_str()).collect();
+        assert_eq!(names, vec!["bob", "alice"]);
+        assert_eq!(users[0].id, bob);
+
+        let posts: Vec<EntPost> = tao.get_entities_by_ids(vec![alice, bob]).await.unwrap();
+        assert!(posts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gen_nullable_tells_missing_ids_from_wrong_types() {
+        use crate::domains::user::EntUser;
+        use crate::infrastructure::tao_core::tao::Tao;
+        use crate::infrastructure::viewer::viewer::ViewerContext;
+
+        let tao: Arc<dyn TaoOperations> = Arc::new(Tao::minimal(Arc::new(sqlite_tao_core().await)));
+        let vc = Arc::new(ViewerContext::system(
+            "test-request".to_string(),
+            Arc::clone(&tao),
+        ));
+        let ids = TaoIdGenerator::new(0);
+        let (post, missing) = (ids.next_id(), ids.next_id());
+        tao.create_object(post, "ent_post".to_string(), vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let result = EntUser::gen_nullable(Arc::clone(&vc), Some(missing)).await;
+        assert!(matches!(result, Ok(None)));
+
+        let result = EntUser::gen_nullable(vc, Some(post)).await;
+        match result {
+            Err(AppError::TypeMismatch { expected, actual }) => {
+                assert_eq!(expected, "ent_user");
+                assert_eq!(actual, "ent_post");
+            }
+            other => panic!("expected a type mismatch, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_stored_object_error_names_its_id_and_type() {
+        use crate::domains::post::EntPost;
+
+        let tao = sqlite_tao_core().await;
+        let post = TaoIdGenerator::new(0).next_id();
+        tao.create_object(post, "ent_post".to_string(), vec![0xff; 4])
+            .await
+            .unwrap();
+
+        let err = tao
+            .get_entities_by_ids::<EntPost>(vec![post])
+            .await
+            .unwrap_err();
+        match &err {
+            AppError::Serialization { otype, id, .. } => {
+                assert_eq!(otype, "ent_post");
+                assert_eq!(*id, post);
+            }
+            other => panic!("expected a serialization error, got {:?}", other),
+        }
+        let message = err.to_string();
+        assert!(message.contains("ent_post"));
+        assert!(message.contains(&post.to_string()));
+        assert_eq!(err.code(), "corrupt_object");
+    }
+
+    #[tokio::test]
+    async fn test_get_neighbors_as_keeps_only_requested_type() {
+        use crate::domains::comment::EntComment;
+        use crate::domains::post::EntPost;
+
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let (user, first_post, comment, second_post) =
+            (ids.next_id(), ids.next_id(), ids.next_id(), ids.next_id());
+        for (post, content) in [(first_post, "first"), (second_post, "second")] {
+            let post_entity = EntPost::new(
+                post,
+                user,
+                content.to_string(),
+                None,
+                1,
+                None,
+                "text".to_string(),
+                None,
+                0,
+                0,
+                0,
+                None,
+                None,
+            );
+            tao.create_object(
+                post,
+                "ent_post".to_string(),
+                post_entity.serialize_to_bytes().unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+        let comment_entity = EntComment::new(comment, user, first_post, "nice".to_string(), 1);
+        tao.create_object(
+            comment,
+            "ent_comment".to_string(),
+            comment_entity.serialize_to_bytes().unwrap(),
+        )
+        .await
+        .unwrap();
+        for (time, target) in [(1, first_post), (2, comment), (3, second_post)] {
+            tao.assoc_add(TaoAssociation {
+                id1: user,
+                atype: "likes".to_string(),
+                id2: target,
+                time,
+                data: None,
+                subtype: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let posts: Vec<EntPost> = tao
+            .get_neighbors_as(user, "likes".to_string(), None)
+            .await
+            .unwrap();
+        let contents: Vec<&str> = posts.iter().map(|post| post.content.as_str()).collect();
+        assert_eq!(contents, vec!["second", "first"]);
+
+        let comments: Vec<EntComment> = tao
+            .get_neighbors_as(user, "likes".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].id, comment);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_get_page_includes_total() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let post = ids.next_id();
+        for time in 1..=5 {
+            tao.assoc_add(like(post, ids.next_id(), time))
+                .await
+                .unwrap();
+        }
+
+        let query = TaoAssocQuery {
+            id1: post,
+            atype: "liked_by".to_string(),
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: Some(2),
+            offset: None,
+            after: None,
+            include_total: true,
+        };
+        let page = tao.assoc_get_page(query.clone()).await.unwrap();
+        assert_eq!(page.associations.len(), 2);
+        assert_eq!(
+            page.total_count,
+            Some(tao.assoc_count(post, "liked_by".to_string()).await.unwrap())
+        );
+        assert_eq!(page.total_count, Some(5));
+
+        // The total is only looked up when asked for
+        let page = tao
+            .assoc_get_page(TaoAssocQuery {
+                include_total: false,
+                ..query
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.associations.len(), 2);
+        assert_eq!(page.total_count, None);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_get_multi_type_groups_and_limits_per_type() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let user = ids.next_id();
+        let edge = |atype: &str, time: TaoTime| TaoAssociation {
+            id1: user,
+            atype: atype.to_string(),
+            id2: ids.next_id(),
+            time,
+            data: None,
+            subtype: None,
+        };
+        for time in 1..=3 {
+            tao.assoc_add(edge("friend", time)).await.unwrap();
+        }
+        tao.assoc_add(edge("follows", 10)).await.unwrap();
+        // Not requested, so not returned
+        tao.assoc_add(edge("blocks", 20)).await.unwrap();
+
+        let atypes = vec![
+            "friend".to_string(),
+            "follows".to_string(),
+            "follow_request".to_string(),
+        ];
+        let grouped = tao
+            .assoc_get_multi_type(user, atypes.clone(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(grouped.len(), 3);
+        let friend_times: Vec<TaoTime> = grouped["friend"].iter().map(|a| a.time).collect();
+        assert_eq!(friend_times, vec![3, 2]);
+        assert_eq!(grouped["follows"].len(), 1);
+        assert!(grouped["follow_request"].is_empty());
+
+        let unlimited = tao.assoc_get_multi_type(user, atypes, None).await.unwrap();
+        assert_eq!(unlimited["friend"].len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_create_as_viewer_records_owner_edge() {
+        use crate::domains::post::EntPost;
+        use crate::infrastructure::tao_core::tao::Tao;
+        use crate::infrastructure::viewer::viewer::ViewerContext;
+
+        let tao: Arc<dyn TaoOperations> = Arc::new(Tao::minimal(Arc::new(sqlite_tao_core().await)));
+        let author_id = TaoIdGenerator::new(0).next_id();
+        let vc = ViewerContext::authenticated_user(
+            author_id,
+            "author".to_string(),
+            "test-request".to_string(),
+            Arc::clone(&tao),
+        );
+
+        let post = EntPost::create(vc)
+            .author_id(author_id)
+            .content("hello".to_string())
+            .savex()
+            .await
+            .unwrap();
+
+        assert!(tao
+            .assoc_exists(post.id, "author".to_string(), author_id)
+            .await
+            .unwrap());
+        assert!(tao
+            .assoc_exists(author_id, "posts".to_string(), post.id)
+            .await
+            .unwrap());
+        // Placed with its author, so the post and both edges share one transaction
+        assert_eq!(
+            TaoIdGenerator::extract_shard_id(post.id),
+            TaoIdGenerator::extract_shard_id(author_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assoc_touch_reorders_without_changing_count() {
+        let tao = sqlite_tao_core().await;
+        let post = TaoIdGenerator::new(0).next_id();
+        for (user, time) in [(1, 100), (2, 200), (3, 300)] {
+            tao.assoc_add(like(post, user, time)).await.unwrap();
+        }
+        let newest_first = |assocs: Vec<TaoAssociation>| -> Vec<TaoId> {
+            assocs.into_iter().map(|assoc| assoc.id2).collect()
+        };
+        let query = TaoAssocQuery {
+            id1: post,
+            atype: "liked_by".to_string(),
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: None,
+            offset: None,
+            after: None,
+            include_total: false,
+        };
+        assert_eq!(
+            newest_first(tao.assoc_get(query.clone()).await.unwrap()),
+            vec![3, 2, 1]
+        );
+
+        assert!(tao
+            .assoc_touch(post, "liked_by".to_string(), 1, None)
+            .await
+            .unwrap());
+        assert_eq!(
+            newest_first(tao.assoc_get(query).await.unwrap()),
+            vec![1, 3, 2]
+        );
+        assert_eq!(
+            tao.assoc_count(post, "liked_by".to_string()).await.unwrap(),
+            3
+        );
+
+        assert!(!tao
+            .assoc_touch(post, "liked_by".to_string(), 99, Some(400))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_assoc_delete_all_clears_edges_count_and_inverses() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let post = ids.next_id();
+        let fans: Vec<TaoId> = (0..3).map(|_| ids.next_id()).collect();
+        for (time, fan) in fans.iter().enumerate() {
+            tao.assoc_add(like(post, *fan, time as TaoTime))
+                .await
+                .unwrap();
+            tao.assoc_add(TaoAssociation {
+                id1: *fan,
+                atype: "likes".to_string(),
+                id2: post,
+                time: time as TaoTime,
+                data: None,
+                subtype: None,
+            })
+            .await
+            .unwrap();
+        }
+        tao.assoc_add(TaoAssociation {
+            id1: post,
+            atype: "tagged".to_string(),
+            id2: fans[0],
+            time: 0,
+            data: None,
+            subtype: None,
+        })
+        .await
+        .unwrap();
+
+        let (_, _, version) = tao
+            .assoc_delta_since(post, "liked_by".to_string(), 0)
+            .await
+            .unwrap();
+        let deleted = tao
+            .assoc_delete_all(post, "liked_by".to_string())
+            .await
+            .unwrap();
+        let mut removed = deleted.removed.clone();
+        removed.sort();
+        assert_eq!(removed, fans);
+        let mut inverse_removed = deleted.inverse_removed.clone();
+        inverse_removed.sort();
+        assert_eq!(
+            inverse_removed,
+            fans.iter()
+                .map(|fan| (*fan, "likes".to_string(), post))
+                .collect::<Vec<_>>()
+        );
+        // The removals are logged with the delete
+        let (added, removed, _) = tao
+            .assoc_delta_since(post, "liked_by".to_string(), version)
+            .await
+            .unwrap();
+        assert!(added.is_empty());
+        assert_eq!(removed.len(), 3);
+        assert_eq!(
+            tao.assoc_count(post, "liked_by".to_string()).await.unwrap(),
+            0
+        );
+        assert!(tao
+            .get_neighbor_ids(post, "liked_by".to_string(), None)
+            .await
+            .unwrap()
+            .is_empty());
+        for fan in &fans {
+            assert!(!tao
+                .assoc_exists(*fan, "likes".to_string(), post)
+                .await
+                .unwrap());
+        }
+        // Other types on the same object are untouched
+        assert_eq!(
+            tao.assoc_count(post, "tagged".to_string()).await.unwrap(),
+            1
+        );
+
+        // Nothing left to delete
+        let deleted = tao
+            .assoc_delete_all(post, "liked_by".to_string())
+            .await
+            .unwrap();
+        assert_eq!(deleted, AssocDeleteAllReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_assoc_move_repoints_edge_and_its_inverse() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let (post, old_fan, new_fan, other_fan) =
+            (ids.next_id(), ids.next_id(), ids.next_id(), ids.next_id());
+        tao.assoc_add(TaoAssociation {
+            data: Some(b"via share".to_vec()),
+            ..like(post, old_fan, 7)
+        })
+        .await
+        .unwrap();
+        tao.assoc_add(TaoAssociation {
+            id1: old_fan,
+            atype: "likes".to_string(),
+            id2: post,
+            time: 7,
+            data: None,
+            subtype: None,
+        })
+        .await
+        .unwrap();
+
+        let report = tao
+            .assoc_move(post, "liked_by".to_string(), old_fan, new_fan)
+            .await
+            .unwrap();
+        assert!(report.moved);
+        assert_eq!(report.inverse_atype, Some("likes".to_string()));
+        let moved = tao
+            .assoc_get_one(post, "liked_by".to_string(), new_fan)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(moved.time, 7);
+        assert_eq!(moved.data, Some(b"via share".to_vec()));
+        assert!(!tao
+            .assoc_exists(post, "liked_by".to_string(), old_fan)
+            .await
+            .unwrap());
+        assert_eq!(
+            tao.assoc_count(post, "liked_by".to_string()).await.unwrap(),
+            1
+        );
+        // The old target loses the inbound edge and the new one gains it
+        assert!(!tao
+            .assoc_exists(old_fan, "likes".to_string(), post)
+            .await
+            .unwrap());
+        assert!(tao
+            .assoc_exists(new_fan, "likes".to_string(), post)
+            .await
+            .unwrap());
+        // Both sides of the inverse move are in the change log
+        let (added, _, _) = tao
+            .assoc_delta_since(new_fan, "likes".to_string(), 0)
+            .await
+            .unwrap();
+        assert_eq!(added, vec![post]);
+        let (_, _, old_fan_version) = tao
+            .assoc_delta_since(old_fan, "likes".to_string(), 0)
+            .await
+            .unwrap();
+        assert_eq!(old_fan_version, 2);
+
+        // Moving onto a target that is already linked merges the two
+        tao.assoc_add(like(post, other_fan, 8)).await.unwrap();
+        assert!(
+            tao.assoc_move(post, "liked_by".to_string(), new_fan, other_fan)
+                .await
+                .unwrap()
+                .moved
+        );
+        assert_eq!(
+            tao.assoc_count(post, "liked_by".to_string()).await.unwrap(),
+            1
+        );
+
+        assert!(
+            !tao.assoc_move(post, "liked_by".to_string(), old_fan, new_fan)
+                .await
+                .unwrap()
+                .moved
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_neighbors_with_edges_returns_edge_metadata() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let post = ids.next_id();
+        let (alice, bob) = (ids.next_id(), ids.next_id());
+        for user in [alice, bob] {
+            tao.create_object(user, "ent_user".to_string(), vec![user as u8])
+                .await
+                .unwrap();
+        }
+        tao.assoc_add(TaoAssociation {
+            data: Some(b"thumbs_up".to_vec()),
+            ..like(post, alice, 1_000)
+        })
+        .await
+        .unwrap();
+        tao.assoc_add(like(post, bob, 2_000)).await.unwrap();
+
+        let neighbors = tao
+            .get_neighbors_with_edges(post, "liked_by".to_string(), None)
+            .await
+            .unwrap();
+        let edges: Vec<(TaoId, TaoId, TaoTime, Option<Vec<u8>>)> = neighbors
+            .into_iter()
+            .map(|(object, assoc)| (object.id, assoc.id2, assoc.time, assoc.data))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (bob, bob, 2_000, None),
+                (alice, alice, 1_000, Some(b"thumbs_up".to_vec())),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attributes_round_trip_and_die_with_object() {
+        let tao = sqlite_tao_core().await;
+        let id = TaoIdGenerator::new(0).next_id();
+        let missing = tao
+            .set_attribute(id, "beta_badge".to_string(), b"gold".to_vec())
+            .await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+
+        tao.create_object(id, "ent_user".to_string(), vec![1, 2, 3])
+            .await
+            .unwrap();
+        tao.set_attribute(id, "beta_badge".to_string(), b"gold".to_vec())
+            .await
+            .unwrap();
+        tao.set_attribute(id, "theme".to_string(), b"dark".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            tao.get_attribute(id, "beta_badge".to_string())
+                .await
+                .unwrap(),
+            Some(b"gold".to_vec())
+        );
+
+        // Overwriting replaces the value and leaves the typed payload untouched
+        tao.set_attribute(id, "beta_badge".to_string(), b"silver".to_vec())
+            .await
+            .unwrap();
+        let attributes = tao.get_attributes(id).await.unwrap();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes["beta_badge"], b"silver".to_vec());
+        assert_eq!(tao.obj_get(id).await.unwrap().unwrap().data, vec![1, 2, 3]);
+
+        assert!(tao.obj_delete(id).await.unwrap());
+        assert!(tao.get_attributes(id).await.unwrap().is_empty());
+        assert_eq!(
+            tao.get_attribute(id, "theme".to_string()).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_assoc_types_reports_each_type_with_count() {
+        let tao = sqlite_tao_core().await;
+        let post = TaoIdGenerator::new(0).next_id();
+        for user in [1, 2, 3] {
+            tao.assoc_add(like(post, user, 1_000)).await.unwrap();
+        }
+        for (atype, id2) in [("tagged", 10), ("tagged", 11), ("shared_by", 1)] {
+            tao.assoc_add(TaoAssociation {
+                atype: atype.to_string(),
+                ..like(post, id2, 1_000)
+            })
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            tao.list_assoc_types(post).await.unwrap(),
+            vec![
+                ("liked_by".to_string(), 3),
+                ("shared_by".to_string(), 1),
+                ("tagged".to_string(), 2),
+            ]
+        );
+
+        // A type whose last edge is gone is no longer listed
+        assert!(tao
+            .assoc_delete(post, "shared_by".to_string(), 1)
+            .await
+            .unwrap());
+        let atypes: Vec<AssocType> = tao
+            .list_assoc_types(post)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(atype, _)| atype)
+            .collect();
+        assert_eq!(atypes, vec!["liked_by", "tagged"]);
+    }
+
+    #[tokio::test]
+    async fn test_reaction_subtypes_are_counted_separately_and_together() {
+        let tao = sqlite_tao_core().await;
+        let post = TaoIdGenerator::new(0).next_id();
+        let reaction = |user: TaoId| TaoAssociation {
+            atype: "reaction".to_string(),
+            ..like(post, user, 1_000 + user)
+        };
+        for (user, subtype) in [
+            (1, "like"),
+            (2, "love"),
+            (3, "like"),
+            (4, "haha"),
+            (5, "love"),
+        ] {
+            tao.assoc_add(reaction(user).with_subtype(subtype))
+                .await
+                .unwrap();
+        }
+        tao.assoc_add(reaction(6)).await.unwrap();
+
+        let counts = tao
+            .assoc_count_by_subtype(post, "reaction".to_string())
+            .await
+            .unwrap();
+        let expected: HashMap<Option<String>, u64> = [
+            (Some("like".to_string()), 2),
+            (Some("love".to_string()), 2),
+            (Some("haha".to_string()), 1),
+            (None, 1),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(counts, expected);
+
+        // Plain-atype reads see every subtype, and return it with each edge
+        assert_eq!(
+            tao.assoc_count(post, "reaction".to_string()).await.unwrap(),
+            6
+        );
+        let love = tao
+            .assoc_get_one(post, "reaction".to_string(), 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(love.subtype.as_deref(), Some("love"));
+    }
+
+    #[tokio::test]
+    async fn test_explained_scan_has_one_span_per_shard() {
+        use crate::infrastructure::monitoring::query_plan::explain;
+
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        for shard_id in [0, 1] {
+            let shard_info = ShardInfo {
+                shard_id,
+                connection_string: "sqlite::memory:".to_string(),
+                region: "local".to_string(),
+                health: ShardHealth::Healthy,
+                replicas: vec![],
+                last_health_check: current_time_millis(),
+                load_factor: 0.0,
+            };
+            let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+            query_router.add_shard(shard_info, database).await.unwrap();
+        }
+        let tao = TaoCore::new(query_router, Arc::new(AssociationRegistry::new()));
+
+        let (objects, plan) =
+            explain(tao.get_all_objects_of_type("ent_user".to_string(), None)).await;
+        assert!(objects.unwrap().is_empty());
+        let mut shards: Vec<ShardId> = plan.spans.iter().map(|span| span.shard_id).collect();
+        shards.sort();
+        assert_eq!(shards, vec![0, 1]);
+        for span in &plan.spans {
+            assert_eq!(span.operation, "get_all_objects_of_type");
+            assert!(span.query.contains("ent_user"));
+            assert!(span.duration_ms > 0.0);
+            assert!(span.duration_ms <= plan.total_ms);
+        }
+
+        // Without explain nothing is captured, and nothing breaks
+        tao.get_all_objects_of_type("ent_user".to_string(), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_high_fanout_inverse_is_bucketed_across_shards() {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        for shard_id in [0, 1, 2] {
+            let shard_info = ShardInfo {
+                shard_id,
+                connection_string: "sqlite::memory:".to_string(),
+                region: "local".to_string(),
+                health: ShardHealth::Healthy,
+                replicas: vec![],
+                last_health_check: current_time_millis(),
+                load_factor: 0.0,
+            };
+            let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+            query_router.add_shard(shard_info, database).await.unwrap();
+        }
+        let registry = Arc::new(AssociationRegistry::new());
+        registry
+            .register_high_fanout("follows".to_string(), 4)
+            .await;
+        let tao = TaoCore::new(query_router.clone(), registry);
+
+        let celebrity = TaoIdGenerator::new(0).next_id();
+        // Consecutive ids, so each of the 4 buckets gets 5 followers
+        let followers: Vec<TaoId> = (1..=20).map(|i| celebrity + i).collect();
+        for (time, follower) in followers.iter().enumerate() {
+            tao.assoc_add(TaoAssociation {
+                id1: *follower,
+                atype: "follows".to_string(),
+                id2: celebrity,
+                time: time as TaoTime,
+                data: None,
+                subtype: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        // Buckets 0..4 go round-robin from the celebrity's shard: 0, 1, 2, 0
+        for (bucket, shard_id) in [(0, 0), (1, 1), (2, 2), (3, 0)] {
+            let database = query_router.get_database_for_shard(shard_id).await.unwrap();
+            let count = database
+                .count_associations(celebrity, format!("followers#{}", bucket))
+                .await
+                .unwrap();
+            assert_eq!(count, 5, "bucket {}", bucket);
+        }
+        assert_eq!(
+            tao.assoc_count(celebrity, "followers".to_string())
+                .await
+                .unwrap(),
+            0
+        );
+
+        let mut all = tao
+            .get_in_neighbor_ids(celebrity, "follows".to_string(), None)
+            .await
+            .unwrap();
+        all.sort();
+        assert_eq!(all, followers);
+        let newest = tao
+            .get_in_neighbor_ids(celebrity, "follows".to_string(), Some(3))
+            .await
+            .unwrap();
+        assert_eq!(newest, vec![followers[19], followers[18], followers[17]]);
+
+        assert!(tao
+            .assoc_delete(followers[0], "follows".to_string(), celebrity)
+            .await
+            .unwrap());
+        let remaining = tao
+            .get_in_neighbor_ids(celebrity, "follows".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 19);
+        assert!(!remaining.contains(&followers[0]));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_get_or_create_entity_creates_once_under_concurrency() {
+        use crate::domains::user::{EntUser, EntUserBuilderState};
+
+        let tao = Arc::new(sqlite_tao_core().await);
+        let new_user = |name: &str| {
+            EntUserBuilderState::default()
+                .username(name.to_string())
+                .email("ext-42@example.com".to_string())
+        };
+
+        let start = Arc::new(tokio::sync::Barrier::new(2));
+        let calls: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|name| {
+                let (tao, start, state) = (tao.clone(), start.clone(), new_user(name));
+                tokio::spawn(async move {
+                    start.wait().await;
+                    tao.get_or_create_entity::<EntUser>("email", "ext-42@example.com", state)
+                        .await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for call in calls {
+            results.push(call.await.unwrap().unwrap());
+        }
+        let created: Vec<bool> = results.iter().map(|(_, created)| *created).collect();
+        assert_eq!(created.iter().filter(|c| **c).count(), 1);
+        assert_eq!(results[0].0.id, results[1].0.id);
+        assert_eq!(results[0].0.username, results[1].0.username);
+        assert_eq!(
+            tao.estimate_count_of_type("ent_user".to_string())
+                .await
+                .unwrap(),
+            1
+        );
+        let user_id = results[0].0.id;
+
+        // A claim that lost the race writes nothing and names the holder
+        let otype = "ent_user".to_string();
+        let (field, value) = ("email".to_string(), "ext-42@example.com".to_string());
+        let loser = tao
+            .generate_id_for_unique(otype.clone(), field.clone(), value.clone())
+            .await
+            .unwrap();
+        let holder = tao
+            .obj_create_unique(loser, otype, vec![], field, value)
+            .await
+            .unwrap();
+        assert_eq!(holder, Some(user_id));
+        assert!(!tao.obj_exists(loser).await.unwrap());
+
+        // Deleting the user releases the key
+        assert!(tao.obj_delete(user_id).await.unwrap());
+        let (replacement, created) = tao
+            .get_or_create_entity::<EntUser>("email", "ext-42@example.com", new_user("third"))
+            .await
+            .unwrap();
+        assert!(created);
+        assert_ne!(replacement.id, user_id);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_add_validates_membership_role_payload() {
+        use crate::domains::user::EntUserGroupsPayload;
+        use crate::framework::entity::edge_payload::EdgePayload;
+        use crate::framework::schema::ent_schema::EntSchema;
+        use crate::schemas::UserSchema;
+
+        let core = sqlite_tao_core().await;
+        let groups = UserSchema::edges()
+            .into_iter()
+            .find(|edge| edge.name == "groups")
+            .unwrap();
+        core.association_registry
+            .register_payload_schema("groups".to_string(), groups.payload)
+            .await;
+        let generator = TaoIdGenerator::new(0);
+        let (user, group) = (generator.next_id(), generator.next_id());
+
+        let valid = EntUserGroupsPayload::new("moderator".to_string());
+        core.assoc_add(create_tao_association(
+            user,
+            "groups".to_string(),
+            group,
+            Some(valid.to_data()),
+        ))
+        .await
+        .unwrap();
+        let stored = core
+            .assoc_range(user, "groups".to_string(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            EntUserGroupsPayload::from_data(stored[0].data.as_deref().unwrap()).unwrap(),
+            valid
+        );
+
+        let other_group = generator.next_id();
+        for data in [Some(br#"{"role":"owner"}"#.to_vec()), None] {
+            let result = core
+                .assoc_add(create_tao_association(
+                    user,
+                    "groups".to_string(),
+                    other_group,
+                    data,
+                ))
+                .await;
+            assert!(
+                matches!(result, Err(AppError::Validation(_))),
+                "{:?}",
+                result
+            );
+        }
+        assert_eq!(core.assoc_count(user, "groups".to_string()).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_typed_membership_payload_round_trips_through_assoc_add_typed() {
+        use crate::domains::user::EntUserGroupsPayload;
+        use crate::framework::schema::ent_schema::EntSchema;
+        use crate::schemas::UserSchema;
+
+        let core = sqlite_tao_core().await;
+        let groups = UserSchema::edges()
+            .into_iter()
+            .find(|edge| edge.name == "groups")
+            .unwrap();
+        core.association_registry
+            .register_payload_schema("groups".to_string(), groups.payload)
+            .await;
+        let generator = TaoIdGenerator::new(0);
+        let (user, group) = (generator.next_id(), generator.next_id());
+
+        let missing = EntUserGroupsPayload::builder().build().unwrap_err();
+        assert_eq!(
+            missing.by_field().get("role"),
+            Some(&vec!["role is required"])
+        );
+
+        let membership = EntUserGroupsPayload::builder()
+            .role("admin".to_string())
+            .build()
+            .unwrap();
+        core.assoc_add_typed(user, group, membership.clone())
+            .await
+            .unwrap();
+
+        let stored = core
+            .assoc_get_typed::<EntUserGroupsPayload>(user, group)
+            .await
+            .unwrap();
+        assert_eq!(stored, Some(membership));
+        let absent = core
+            .assoc_get_typed::<EntUserGroupsPayload>(group, user)
+            .await
+            .unwrap();
+        assert_eq!(absent, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_objects_in_range_returns_only_in_range_objects() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let mut seeded = Vec::new();
+        for (otype, time_created) in [
+            ("ent_post", 1_000),
+            ("ent_post", 2_000),
+            ("ent_post", 3_000),
+            ("ent_post", 4_000),
+            ("ent_user", 2_500),
+        ] {
+            let id = ids.next_id();
+            tao.create_object(id, otype.to_string(), vec![]).await.unwrap();
+            tao.execute_query(format!(
+                "UPDATE tao_objects SET time_created = {} WHERE id = {}",
+                time_created, id
+            ))
+            .await
+            .unwrap();
+            seeded.push(id);
+        }
+
+        let in_range: Vec<TaoId> = tao
+            .get_objects_in_range("ent_post".to_string(), 2_000, 3_000, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|obj| obj.id)
+            .collect();
+        assert_eq!(in_range, vec![seeded[1], seeded[2]]);
+
+        let oldest = tao
+            .get_objects_in_range("ent_post".to_string(), 0, 10_000, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(oldest.len(), 1);
+        assert_eq!(oldest[0].created_time, 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_get_one_returns_edge_with_data() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let (post, viewer, other) = (ids.next_id(), ids.next_id(), ids.next_id());
+        tao.assoc_add(TaoAssociation {
+            id1: post,
+            atype: "liked_by".to_string(),
+            id2: viewer,
+            time: 42,
+            data: Some(vec![7, 8]),
+            subtype: None,
+        })
+        .await
+        .unwrap();
+
+        let edge = tao
+            .assoc_get_one(post, "liked_by".to_string(), viewer)
+            .await
+            .unwrap()
+            .expect("edge should exist");
+        assert_eq!((edge.id1, edge.id2, edge.time), (post, viewer, 42));
+        assert_eq!(edge.data, Some(vec![7, 8]));
+
+        assert!(tao
+            .assoc_get_one(post, "liked_by".to_string(), other)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(tao
+            .assoc_get_one(post, "likes".to_string(), viewer)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_object_history_records_updates_and_rolls_back() {
+        use crate::framework::context::with_viewer_context;
+        use crate::infrastructure::viewer::viewer::ViewerContext;
+
+        let tao = Arc::new(sqlite_tao_core().await.with_object_history(["ent_page"]));
+        let ids = TaoIdGenerator::new(0);
+        let (page, user) = (ids.next_id(), ids.next_id());
+        tao.create_object(page, "ent_page".to_string(), b"v1".to_vec())
+            .await
+            .unwrap();
+        tao.create_object(user, "ent_user".to_string(), b"v1".to_vec())
+            .await
+            .unwrap();
+
+        let editor = Arc::new(ViewerContext::authenticated_user(
+            7,
+            "editor".to_string(),
+            "req-1".to_string(),
+            tao.clone(),
+        ));
+        with_viewer_context(editor, async {
+            tao.obj_update(page, b"v2".to_vec()).await.unwrap();
+            tao.obj_update(page, b"v3".to_vec()).await.unwrap();
+            tao.obj_update(user, b"v2".to_vec()).await.unwrap();
+        })
+        .await;
+
+        let history = tao.get_object_history(page, 10).await.unwrap();
+        let versions: Vec<_> = history
+            .iter()
+            .map(|entry| (entry.version, entry.data.clone(), entry.changed_by))
+            .collect();
+        assert_eq!(
+            versions,
+            vec![(2, b"v2".to_vec(), Some(7)), (1, b"v1".to_vec(), Some(7))]
+        );
+        assert_eq!(tao.get_object_history(page, 1).await.unwrap().len(), 1);
+        // Types without `keep_history` record nothing
+        assert!(tao.get_object_history(user, 10).await.unwrap().is_empty());
+
+        assert_eq!(tao.obj_rollback(page, 1).await.unwrap(), b"v1".to_vec());
+        let restored = tao.obj_get(page).await.unwrap().unwrap();
+        assert_eq!((restored.data, restored.version), (b"v1".to_vec(), 4));
+        // The rollback is an update of its own, so v3 is kept too
+        let history = tao.get_object_history(page, 10).await.unwrap();
+        assert_eq!(history[0].version, 3);
+        assert_eq!(history[0].data, b"v3".to_vec());
+        assert_eq!(history[0].changed_by, None);
+
+        assert!(matches!(
+            tao.obj_rollback(page, 9).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_symmetric_edges_are_added_and_removed_from_either_side() {
+        let tao = sqlite_tao_core().await;
+        tao.association_registry
+            .register_symmetric("friends".to_string())
+            .await;
+        let ids = TaoIdGenerator::new(0);
+        let (alice, bob) = (ids.next_id(), ids.next_id());
+        let friends = || "friends".to_string();
+
+        tao.assoc_add(create_tao_association(alice, friends(), bob, None))
+            .await
+            .unwrap();
+        assert!(tao.assoc_exists(bob, friends(), alice).await.unwrap());
+        assert_eq!(tao.assoc_count(alice, friends()).await.unwrap(), 1);
+        assert_eq!(tao.assoc_count(bob, friends()).await.unwrap(), 1);
+        // The reverse edge is logged on its own shard like any other
+        let delta = tao.assoc_delta_since(bob, friends(), 0).await.unwrap();
+        assert_eq!(delta, (vec![alice], vec![], 1));
+
+        // Adding an existing edge again leaves both counts alone
+        tao.assoc_add(create_tao_association(bob, friends(), alice, None))
+            .await
+            .unwrap();
+        assert_eq!(tao.assoc_count(alice, friends()).await.unwrap(), 1);
+        assert_eq!(tao.assoc_count(bob, friends()).await.unwrap(), 1);
+
+        assert!(tao.assoc_delete(bob, friends(), alice).await.unwrap());
+        assert!(!tao.assoc_exists(alice, friends(), bob).await.unwrap());
+        assert!(!tao.assoc_exists(bob, friends(), alice).await.unwrap());
+        assert_eq!(tao.assoc_count(alice, friends()).await.unwrap(), 0);
+        assert_eq!(tao.assoc_count(bob, friends()).await.unwrap(), 0);
+        let (_, removed, _) = tao.assoc_delta_since(alice, friends(), 1).await.unwrap();
+        assert_eq!(removed, vec![bob]);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_delta_since_reports_exactly_the_changes_made() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let alice = ids.next_id();
+        let [bob, carol, dave, erin, frank] = [(); 5].map(|_| ids.next_id());
+        let friends = || "friends".to_string();
+        let add = |id2| tao.assoc_add(create_tao_association(alice, friends(), id2, None));
+
+        for id2 in [bob, carol, dave] {
+            add(id2).await.unwrap();
+        }
+        // Version 0 is a full sync of the current list
+        let (mut added, removed, seq) = tao.assoc_delta_since(alice, friends(), 0).await.unwrap();
+        added.sort();
+        assert_eq!((added, removed, seq), (vec![bob, carol, dave], vec![], 3));
+
+        add(erin).await.unwrap();
+        assert!(tao.assoc_delete(alice, friends(), carol).await.unwrap());
+        add(frank).await.unwrap();
+        assert!(tao.assoc_delete(alice, friends(), frank).await.unwrap());
+        // Deleting a missing edge changes nothing
+        assert!(!tao.assoc_delete(alice, friends(), carol).await.unwrap());
+
+        let delta = tao.assoc_delta_since(alice, friends(), seq).await.unwrap();
+        assert_eq!(delta, (vec![erin], vec![carol, frank], 7));
+        let delta = tao.assoc_delta_since(alice, friends(), 7).await.unwrap();
+        assert_eq!(delta, (vec![], vec![], 7));
+
+        // Once the log has moved past a client's version it has to start over
+        let database = tao
+            .query_router
+            .get_database_for_object(alice)
+            .await
+            .unwrap();
+        database
+            .record_association_changes(
+                alice,
+                friends(),
+                vec![(bob, true); ASSOC_CHANGE_LOG_RETENTION as usize + 1],
+            )
+            .await
+            .unwrap();
+        let result = tao.assoc_delta_since(alice, friends(), 7).await;
+        assert!(matches!(result, Err(AppError::ResyncRequired(_))));
+        let latest = 8 + ASSOC_CHANGE_LOG_RETENTION;
+        let delta = tao.assoc_delta_since(alice, friends(), 8).await.unwrap();
+        assert_eq!(delta, (vec![bob], vec![], latest));
+    }
+
+    #[tokio::test]
+    async fn test_edges_between_returns_every_type_connecting_two_objects() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let (alice, bob, carol) = (ids.next_id(), ids.next_id(), ids.next_id());
+        let edge = |id1, atype: &str, id2, time| TaoAssociation {
+            id1,
+            atype: atype.to_string(),
+            id2,
+            time,
+            data: Some(atype.as_bytes().to_vec()),
+            subtype: None,
+        };
+        tao.assoc_add(edge(alice, "friends", bob, 1_000))
+            .await
+            .unwrap();
+        tao.assoc_add(edge(alice, "following", bob, 2_000))
+            .await
+            .unwrap();
+        tao.assoc_add(edge(alice, "following", carol, 3_000))
+            .await
+            .unwrap();
+        tao.assoc_add(edge(bob, "following", alice, 4_000))
+            .await
+            .unwrap();
+
+        let summary = |edges: Vec<TaoAssociation>| {
+            edges
+                .into_iter()
+                .map(|assoc| (assoc.atype, assoc.time, assoc.data))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(tao.edges_between(alice, bob).await.unwrap()),
+            vec![
+                ("following".to_string(), 2_000, Some(b"following".to_vec())),
+                ("friends".to_string(), 1_000, Some(b"friends".to_vec()))
+            ]
+        );
+        assert_eq!(
+            summary(tao.edges_between(bob, alice).await.unwrap()),
+            vec![("following".to_string(), 4_000, Some(b"following".to_vec()))]
+        );
+        assert!(tao.edges_between(bob, carol).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_write_batch_is_all_or_nothing() {
+        use std::task::Poll;
+
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+
+        // Drop the batch after ever more polls, as a client disconnect would, until it
+        // gets to finish; every attempt must leave all of it or none of it behind
+        let mut cancelled = 0;
+        for polls in 1.. {
+            let (post, tags) = (ids.next_id(), [ids.next_id(), ids.next_id()]);
+            let batch = TaoWriteBatch {
+                objects: vec![(post, "ent_post".to_string(), vec![1])],
+                associations: tags
+                    .iter()
+                    .map(|tag| create_tao_association(post, "tagged".to_string(), *tag, None))
+                    .collect(),
+            };
+            let mut write = Box::pin(tao.write_batch(batch));
+            let mut finished = None;
+            for _ in 0..polls {
+                if let Poll::Ready(result) = futures::poll!(&mut write) {
+                    finished = Some(result);
+                    break;
+                }
+                // Lets the statement in flight complete before the next poll
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+            drop(write);
+
+            let written = tao.obj_exists(post).await.unwrap();
+            assert_eq!(
+                tao.assoc_count(post, "tagged".to_string()).await.unwrap(),
+                if written { 2 } else { 0 }
+            );
+            for tag in tags {
+                let exists = tao.assoc_exists(post, "tagged".to_string(), tag).await;
+                assert_eq!(exists.unwrap(), written);
+            }
+            match finished {
+                Some(result) => {
+                    result.unwrap();
+                    break;
+                }
+                None => cancelled += 1,
+            }
+        }
+        assert!(cancelled > 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_limits_default_missing_limits_and_clamp_large_ones() {
+        let tao = sqlite_tao_core()
+            .await
+            .with_list_limits(ListLimits { default: 3, max: 5 });
+        let ids = TaoIdGenerator::new(0);
+        let post = ids.next_id();
+        for time in 0..8 {
+            tao.assoc_add(like(post, ids.next_id(), time))
+                .await
+                .unwrap();
+        }
+        let neighbor_count = |limit| {
+            let tao = &tao;
+            async move {
+                tao.get_neighbor_ids(post, "liked_by".to_string(), limit)
+                    .await
+                    .unwrap()
+                    .len()
+            }
+        };
+
+        // Default applied
+        assert_eq!(neighbor_count(None).await, 3);
+        // Clamped to max
+        assert_eq!(neighbor_count(Some(50)).await, 5);
+        assert_eq!(
+            tao.assoc_range(post, "liked_by".to_string(), 0, 50)
+                .await
+                .unwrap()
+                .len(),
+            5
+        );
+        // Within bounds
+        assert_eq!(neighbor_count(Some(4)).await, 4);
+        assert_eq!(
+            tao.assoc_range(post, "liked_by".to_string(), 6, 4)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assoc_delete_between_removes_every_edge_in_both_directions() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let (alice, bob, carol) = (ids.next_id(), ids.next_id(), ids.next_id());
+        let edge = |id1, atype: &str, id2| TaoAssociation {
+            id1,
+            atype: atype.to_string(),
+            id2,
+            time: 0,
+            data: None,
+            subtype: None,
+        };
+        for assoc in [
+            edge(alice, "follows", bob),
+            edge(bob, "followers", alice),
+            edge(alice, "friends", bob),
+            edge(bob, "friends", alice),
+            edge(alice, "tagged", bob),
+            edge(alice, "follows", carol),
+        ] {
+            tao.assoc_add(assoc).await.unwrap();
+        }
+
+        // Only the asked-for type, with its inverse
+        let removed = tao
+            .assoc_delete_between(alice, bob, Some(vec!["follows".to_string()]))
+            .await
+            .unwrap();
+        assert_eq!(
+            removed,
+            HashMap::from([("follows".to_string(), 1), ("followers".to_string(), 1)])
+        );
+        assert!(tao
+            .assoc_exists(alice, "friends".to_string(), bob)
+            .await
+            .unwrap());
+
+        // Everything else
+        let removed = tao.assoc_delete_between(bob, alice, None).await.unwrap();
+        assert_eq!(
+            removed,
+            HashMap::from([("friends".to_string(), 2), ("tagged".to_string(), 1)])
+        );
+        for (id, atype, count) in [
+            (alice, "friends", 0),
+            (bob, "friends", 0),
+            (alice, "tagged", 0),
+            (bob, "followers", 0),
+            (alice, "follows", 1),
+        ] {
+            assert_eq!(tao.assoc_count(id, atype.to_string()).await.unwrap(), count);
+        }
+
+        // Nothing left between them
+        assert!(tao
+            .assoc_delete_between(alice, bob, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_activity_feed_merges_types_newest_first() {
+        let tao = sqlite_tao_core().await;
+        let ids = TaoIdGenerator::new(0);
+        let user = ids.next_id();
+        for (atype, times) in [
+            ("authored", vec![10, 40, 70]),
+            ("commented", vec![20, 50]),
+            ("likes", vec![30, 60, 80, 90]),
+        ] {
+            for time in times {
+                tao.assoc_add(TaoAssociation {
+                    id1: user,
+                    atype: atype.to_string(),
+                    id2: ids.next_id(),
+                    time,
+                    data: None,
+                    subtype: None,
+                })
+                .await
+                .unwrap();
+            }
+        }
+        // Not asked for
+        tao.assoc_add(like(user, ids.next_id(), 100)).await.unwrap();
+
+        let atypes = ["authored", "commented", "likes"]
+            .map(String::from)
+            .to_vec();
+        let feed = tao.activity_feed(user, atypes.clone(), 6).await.unwrap();
+        let merged: Vec<(&str, TaoTime)> = feed
+            .iter()
+            .map(|edge| (edge.atype.as_str(), edge.time))
+            .collect();
+        assert_eq!(
+            merged,
+            vec![
+                ("likes", 90),
+                ("likes", 80),
+                ("authored", 70),
+                ("likes", 60),
+                ("commented", 50),
+                ("authored", 40),
+            ]
+        );
+
+        // A limit past the end returns every edge once
+        let everything = tao.activity_feed(user, atypes, 50).await.unwrap();
+        assert_eq!(everything.len(), 9);
+        assert_eq!(everything.last().unwrap().time, 10);
     }
 }
@@ -2,9 +2,11 @@
 // Allows composing different features around the core TAO functionality
 
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
@@ -52,10 +54,58 @@ macro_rules! impl_tao_operations_delegate {
                 self.$field.obj_delete_by_type(id, otype).await
             }
 
+            async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+                self.$field.purge_object(id).await
+            }
+
+            async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+                self.$field.get_object_history(id, limit).await
+            }
+
+            async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+                self.$field.obj_rollback(id, to_version).await
+            }
+
+            async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+                self.$field.obj_get_by_unique(otype, field, value).await
+            }
+
+            async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+                self.$field.generate_id_for_unique(otype, field, value).await
+            }
+
+            async fn obj_create_unique(&self, id: TaoId, otype: TaoType, data: Vec<u8>, field: String, value: String) -> AppResult<Option<TaoId>> {
+                self.$field.obj_create_unique(id, otype, data, field, value).await
+            }
+
+            async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+                self.$field.write_batch(batch).await
+            }
+
+            async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+                self.$field.set_attribute(id, key, value).await
+            }
+
+            async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+                self.$field.get_attribute(id, key).await
+            }
+
+            async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+                self.$field.get_attributes(id).await
+            }
+
             async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
                 self.$field.assoc_get(query).await
             }
 
+            async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+                self.$field.assoc_get_page(query).await
+            }
+
+            async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+                self.$field.assoc_get_multi_type(id1, atypes, limit_per_type).await
+            }
+
             async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
                 self.$field.assoc_add(assoc).await
             }
@@ -64,10 +114,38 @@ macro_rules! impl_tao_operations_delegate {
                 self.$field.assoc_delete(id1, atype, id2).await
             }
 
+            async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+                self.$field.assoc_delete_all(id1, atype).await
+            }
+
+            async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+                self.$field.assoc_touch(id1, atype, id2, new_time).await
+            }
+
+            async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+                self.$field.assoc_move(id1, atype, old_id2, new_id2).await
+            }
+
+            async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+                self.$field.assoc_delete_between(id1, id2, atypes).await
+            }
+
             async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
                 self.$field.assoc_count(id1, atype).await
             }
 
+            async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+                self.$field.assoc_count_many(pairs).await
+            }
+
+            async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+                self.$field.assoc_count_by_subtype(id1, atype).await
+            }
+
+            async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+                self.$field.list_assoc_types(id).await
+            }
+
             async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
                 self.$field.assoc_range(id1, atype, offset, limit).await
             }
@@ -76,10 +154,26 @@ macro_rules! impl_tao_operations_delegate {
                 self.$field.assoc_time_range(id1, atype, high_time, low_time, limit).await
             }
 
+            async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+                self.$field.assoc_since(id1, atype, since_time, limit).await
+            }
+
+            async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+                self.$field.assoc_delta_since(id1, atype, since_seq).await
+            }
+
             async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
                 self.$field.assoc_exists(id1, atype, id2).await
             }
 
+            async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+                self.$field.assoc_get_one(id1, atype, id2).await
+            }
+
+            async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+                self.$field.edges_between(id1, id2).await
+            }
+
             async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
                 self.$field.get_by_id_and_type(ids, otype).await
             }
@@ -88,14 +182,30 @@ macro_rules! impl_tao_operations_delegate {
                 self.$field.get_neighbors(id, atype, limit).await
             }
 
+            async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+                self.$field.get_neighbors_with_edges(id, atype, limit).await
+            }
+
             async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
                 self.$field.get_neighbor_ids(id, atype, limit).await
             }
 
+            async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+                self.$field.get_in_neighbor_ids(id2, atype, limit).await
+            }
+
+            async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+                self.$field.estimate_count_of_type(otype).await
+            }
+
             async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
                 self.$field.get_all_objects_of_type(otype, limit).await
             }
 
+            async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                self.$field.get_objects_in_range(otype, start, end, limit).await
+            }
+
             async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
                 self.$field.begin_transaction().await
             }
@@ -133,14 +243,42 @@ macro_rules! impl_tao_operations_with_custom_writes {
                 self.$field.obj_exists_by_type(id, otype).await
             }
 
+            async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+                self.$field.obj_get_by_unique(otype, field, value).await
+            }
+
+            async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+                self.$field.generate_id_for_unique(otype, field, value).await
+            }
+
             async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
                 self.$field.assoc_get(query).await
             }
 
+            async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+                self.$field.assoc_get_page(query).await
+            }
+
+            async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+                self.$field.assoc_get_multi_type(id1, atypes, limit_per_type).await
+            }
+
             async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
                 self.$field.assoc_count(id1, atype).await
             }
 
+            async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+                self.$field.assoc_count_many(pairs).await
+            }
+
+            async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+                self.$field.assoc_count_by_subtype(id1, atype).await
+            }
+
+            async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+                self.$field.list_assoc_types(id).await
+            }
+
             async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
                 self.$field.assoc_range(id1, atype, offset, limit).await
             }
@@ -149,10 +287,26 @@ macro_rules! impl_tao_operations_with_custom_writes {
                 self.$field.assoc_time_range(id1, atype, high_time, low_time, limit).await
             }
 
+            async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+                self.$field.assoc_since(id1, atype, since_time, limit).await
+            }
+
+            async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+                self.$field.assoc_delta_since(id1, atype, since_seq).await
+            }
+
             async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
                 self.$field.assoc_exists(id1, atype, id2).await
             }
 
+            async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+                self.$field.assoc_get_one(id1, atype, id2).await
+            }
+
+            async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+                self.$field.edges_between(id1, id2).await
+            }
+
             async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
                 self.$field.get_by_id_and_type(ids, otype).await
             }
@@ -161,14 +315,30 @@ macro_rules! impl_tao_operations_with_custom_writes {
                 self.$field.get_neighbors(id, atype, limit).await
             }
 
+            async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+                self.$field.get_neighbors_with_edges(id, atype, limit).await
+            }
+
             async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
                 self.$field.get_neighbor_ids(id, atype, limit).await
             }
 
+            async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+                self.$field.get_in_neighbor_ids(id2, atype, limit).await
+            }
+
+            async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+                self.$field.estimate_count_of_type(otype).await
+            }
+
             async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
                 self.$field.get_all_objects_of_type(otype, limit).await
             }
 
+            async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                self.$field.get_objects_in_range(otype, start, end, limit).await
+            }
+
             async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
                 self.$field.begin_transaction().await
             }
@@ -254,6 +424,81 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+                let start = Instant::now();
+                let result = self.$field.purge_object(id).await;
+                self.record_operation("purge_object", start, result.is_ok()).await;
+                result
+            }
+
+            async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+                let start = Instant::now();
+                let result = self.$field.get_object_history(id, limit).await;
+                self.record_operation("get_object_history", start, result.is_ok()).await;
+                result
+            }
+
+            async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+                let start = Instant::now();
+                let result = self.$field.obj_rollback(id, to_version).await;
+                self.record_operation("obj_rollback", start, result.is_ok()).await;
+                result
+            }
+
+            async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+                let start = Instant::now();
+                let result = self.$field.obj_get_by_unique(otype, field, value).await;
+                self.record_operation("obj_get_by_unique", start, result.is_ok()).await;
+                result
+            }
+
+            async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+                let start = Instant::now();
+                let result = self.$field.generate_id_for_unique(otype, field, value).await;
+                self.record_operation("generate_id_for_unique", start, result.is_ok()).await;
+                result
+            }
+
+            async fn obj_create_unique(&self, id: TaoId, otype: TaoType, data: Vec<u8>, field: String, value: String) -> AppResult<Option<TaoId>> {
+                let start = Instant::now();
+                let result = self.$field.obj_create_unique(id, otype, data, field, value).await;
+                self.record_operation("obj_create_unique", start, result.is_ok()).await;
+                if let Ok(None) = result { self.record_business_event("create_object").await; }
+                result
+            }
+
+            async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+                let start = Instant::now();
+                let objects = batch.objects.len();
+                let result = self.$field.write_batch(batch).await;
+                self.record_operation("write_batch", start, result.is_ok()).await;
+                if result.is_ok() {
+                    for _ in 0..objects { self.record_business_event("create_object").await; }
+                }
+                result
+            }
+
+            async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+                let start = Instant::now();
+                let result = self.$field.set_attribute(id, key, value).await;
+                self.record_operation("set_attribute", start, result.is_ok()).await;
+                result
+            }
+
+            async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+                let start = Instant::now();
+                let result = self.$field.get_attribute(id, key).await;
+                self.record_operation("get_attribute", start, result.is_ok()).await;
+                result
+            }
+
+            async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+                let start = Instant::now();
+                let result = self.$field.get_attributes(id).await;
+                self.record_operation("get_attributes", start, result.is_ok()).await;
+                result
+            }
+
             async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
                 let start = Instant::now();
                 let result = self.$field.assoc_get(query).await;
@@ -261,6 +506,20 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+                let start = Instant::now();
+                let result = self.$field.assoc_get_page(query).await;
+                self.record_operation("assoc_get_page", start, result.is_ok()).await;
+                result
+            }
+
+            async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+                let start = Instant::now();
+                let result = self.$field.assoc_get_multi_type(id1, atypes, limit_per_type).await;
+                self.record_operation("assoc_get_multi_type", start, result.is_ok()).await;
+                result
+            }
+
             async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
                 let start = Instant::now();
                 let result = self.$field.assoc_add(assoc).await;
@@ -276,6 +535,34 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+                let start = Instant::now();
+                let result = self.$field.assoc_delete_all(id1, atype).await;
+                self.record_operation("assoc_delete_all", start, result.is_ok()).await;
+                result
+            }
+
+            async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+                let start = Instant::now();
+                let result = self.$field.assoc_touch(id1, atype, id2, new_time).await;
+                self.record_operation("assoc_touch", start, result.is_ok()).await;
+                result
+            }
+
+            async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+                let start = Instant::now();
+                let result = self.$field.assoc_move(id1, atype, old_id2, new_id2).await;
+                self.record_operation("assoc_move", start, result.is_ok()).await;
+                result
+            }
+
+            async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+                let start = Instant::now();
+                let result = self.$field.assoc_delete_between(id1, id2, atypes).await;
+                self.record_operation("assoc_delete_between", start, result.is_ok()).await;
+                result
+            }
+
             async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
                 let start = Instant::now();
                 let result = self.$field.assoc_count(id1, atype).await;
@@ -283,6 +570,27 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+                let start = Instant::now();
+                let result = self.$field.assoc_count_many(pairs).await;
+                self.record_operation("assoc_count_many", start, result.is_ok()).await;
+                result
+            }
+
+            async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+                let start = Instant::now();
+                let result = self.$field.assoc_count_by_subtype(id1, atype).await;
+                self.record_operation("assoc_count_by_subtype", start, result.is_ok()).await;
+                result
+            }
+
+            async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+                let start = Instant::now();
+                let result = self.$field.list_assoc_types(id).await;
+                self.record_operation("list_assoc_types", start, result.is_ok()).await;
+                result
+            }
+
             async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
                 let start = Instant::now();
                 let result = self.$field.assoc_range(id1, atype, offset, limit).await;
@@ -297,6 +605,20 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+                let start = Instant::now();
+                let result = self.$field.assoc_since(id1, atype, since_time, limit).await;
+                self.record_operation("assoc_since", start, result.is_ok()).await;
+                result
+            }
+
+            async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+                let start = Instant::now();
+                let result = self.$field.assoc_delta_since(id1, atype, since_seq).await;
+                self.record_operation("assoc_delta_since", start, result.is_ok()).await;
+                result
+            }
+
             async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
                 let start = Instant::now();
                 let result = self.$field.assoc_exists(id1, atype, id2).await;
@@ -304,6 +626,20 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+                let start = Instant::now();
+                let result = self.$field.assoc_get_one(id1, atype, id2).await;
+                self.record_operation("assoc_get_one", start, result.is_ok()).await;
+                result
+            }
+
+            async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+                let start = Instant::now();
+                let result = self.$field.edges_between(id1, id2).await;
+                self.record_operation("edges_between", start, result.is_ok()).await;
+                result
+            }
+
             async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
                 let start = Instant::now();
                 let result = self.$field.get_by_id_and_type(ids, otype).await;
@@ -318,6 +654,13 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+                let start = Instant::now();
+                let result = self.$field.get_neighbors_with_edges(id, atype, limit).await;
+                self.record_operation("get_neighbors_with_edges", start, result.is_ok()).await;
+                result
+            }
+
             async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
                 let start = Instant::now();
                 let result = self.$field.get_neighbor_ids(id, atype, limit).await;
@@ -325,6 +668,20 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+                let start = Instant::now();
+                let result = self.$field.get_in_neighbor_ids(id2, atype, limit).await;
+                self.record_operation("get_in_neighbor_ids", start, result.is_ok()).await;
+                result
+            }
+
+            async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+                let start = Instant::now();
+                let result = self.$field.estimate_count_of_type(otype).await;
+                self.record_operation("estimate_count_of_type", start, result.is_ok()).await;
+                result
+            }
+
             async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
                 let start = Instant::now();
                 let result = self.$field.get_all_objects_of_type(otype, limit).await;
@@ -332,6 +689,13 @@ macro_rules! impl_tao_operations_with_metrics {
                 result
             }
 
+            async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                let started = Instant::now();
+                let result = self.$field.get_objects_in_range(otype, start, end, limit).await;
+                self.record_operation("get_objects_in_range", started, result.is_ok()).await;
+                result
+            }
+
             async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
                 let start = Instant::now();
                 let result = self.$field.begin_transaction().await;
@@ -390,10 +754,58 @@ macro_rules! impl_tao_operations_with_circuit_breaker {
                 self.execute_with_breaker(self.$field.obj_delete_by_type(id, otype)).await
             }
 
+            async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+                self.execute_with_breaker(self.$field.purge_object(id)).await
+            }
+
+            async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+                self.execute_with_breaker(self.$field.get_object_history(id, limit)).await
+            }
+
+            async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+                self.execute_with_breaker(self.$field.obj_rollback(id, to_version)).await
+            }
+
+            async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+                self.execute_with_breaker(self.$field.obj_get_by_unique(otype, field, value)).await
+            }
+
+            async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+                self.execute_with_breaker(self.$field.generate_id_for_unique(otype, field, value)).await
+            }
+
+            async fn obj_create_unique(&self, id: TaoId, otype: TaoType, data: Vec<u8>, field: String, value: String) -> AppResult<Option<TaoId>> {
+                self.execute_with_breaker(self.$field.obj_create_unique(id, otype, data, field, value)).await
+            }
+
+            async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+                self.execute_with_breaker(self.$field.write_batch(batch)).await
+            }
+
+            async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+                self.execute_with_breaker(self.$field.set_attribute(id, key, value)).await
+            }
+
+            async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+                self.execute_with_breaker(self.$field.get_attribute(id, key)).await
+            }
+
+            async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+                self.execute_with_breaker(self.$field.get_attributes(id)).await
+            }
+
             async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
                 self.execute_with_breaker(self.$field.assoc_get(query)).await
             }
 
+            async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+                self.execute_with_breaker(self.$field.assoc_get_page(query)).await
+            }
+
+            async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+                self.execute_with_breaker(self.$field.assoc_get_multi_type(id1, atypes, limit_per_type)).await
+            }
+
             async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
                 self.execute_with_breaker(self.$field.assoc_add(assoc)).await
             }
@@ -402,10 +814,38 @@ macro_rules! impl_tao_operations_with_circuit_breaker {
                 self.execute_with_breaker(self.$field.assoc_delete(id1, atype, id2)).await
             }
 
+            async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+                self.execute_with_breaker(self.$field.assoc_delete_all(id1, atype)).await
+            }
+
+            async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+                self.execute_with_breaker(self.$field.assoc_touch(id1, atype, id2, new_time)).await
+            }
+
+            async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+                self.execute_with_breaker(self.$field.assoc_move(id1, atype, old_id2, new_id2)).await
+            }
+
+            async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+                self.execute_with_breaker(self.$field.assoc_delete_between(id1, id2, atypes)).await
+            }
+
             async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
                 self.execute_with_breaker(self.$field.assoc_count(id1, atype)).await
             }
 
+            async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+                self.execute_with_breaker(self.$field.assoc_count_many(pairs)).await
+            }
+
+            async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+                self.execute_with_breaker(self.$field.assoc_count_by_subtype(id1, atype)).await
+            }
+
+            async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+                self.execute_with_breaker(self.$field.list_assoc_types(id)).await
+            }
+
             async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
                 self.execute_with_breaker(self.$field.assoc_range(id1, atype, offset, limit)).await
             }
@@ -414,10 +854,26 @@ macro_rules! impl_tao_operations_with_circuit_breaker {
                 self.execute_with_breaker(self.$field.assoc_time_range(id1, atype, high_time, low_time, limit)).await
             }
 
+            async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+                self.execute_with_breaker(self.$field.assoc_since(id1, atype, since_time, limit)).await
+            }
+
+            async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+                self.execute_with_breaker(self.$field.assoc_delta_since(id1, atype, since_seq)).await
+            }
+
             async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
                 self.execute_with_breaker(self.$field.assoc_exists(id1, atype, id2)).await
             }
 
+            async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+                self.execute_with_breaker(self.$field.assoc_get_one(id1, atype, id2)).await
+            }
+
+            async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+                self.execute_with_breaker(self.$field.edges_between(id1, id2)).await
+            }
+
             async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
                 self.execute_with_breaker(self.$field.get_by_id_and_type(ids, otype)).await
             }
@@ -426,14 +882,30 @@ macro_rules! impl_tao_operations_with_circuit_breaker {
                 self.execute_with_breaker(self.$field.get_neighbors(id, atype, limit)).await
             }
 
+            async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+                self.execute_with_breaker(self.$field.get_neighbors_with_edges(id, atype, limit)).await
+            }
+
             async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
                 self.execute_with_breaker(self.$field.get_neighbor_ids(id, atype, limit)).await
             }
 
+            async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+                self.execute_with_breaker(self.$field.get_in_neighbor_ids(id2, atype, limit)).await
+            }
+
+            async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+                self.execute_with_breaker(self.$field.estimate_count_of_type(otype)).await
+            }
+
             async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
                 self.execute_with_breaker(self.$field.get_all_objects_of_type(otype, limit)).await
             }
 
+            async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                self.execute_with_breaker(self.$field.get_objects_in_range(otype, start, end, limit)).await
+            }
+
             async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
                 self.execute_with_breaker(self.$field.begin_transaction()).await
             }
@@ -445,20 +917,296 @@ macro_rules! impl_tao_operations_with_circuit_breaker {
     };
 }
 
-use crate::error::{AppError, AppResult};
-use crate::infrastructure::cache::cache_layer::TaoMultiTierCache;
-use crate::infrastructure::database::database::DatabaseTransaction;
-use crate::infrastructure::monitoring::monitoring::MetricsCollector;
-use crate::infrastructure::tao_core::tao_core::{
-    AssocType, TaoAssocQuery, TaoAssociation, TaoId, TaoObject, TaoOperations, TaoType,
-};
-use crate::infrastructure::storage::write_ahead_log::{TaoOperation, TaoWriteAheadLog};
+/// Implements `TaoOperations` for a decorator that treats reads and writes differently:
+/// every call is made on `$field`, the next layer down, and the resulting future handed to
+/// the decorator's own `execute_read` or `execute_write`. Those two are the decorator's
+/// hooks, each with the signature
+/// `async fn execute_read<F, T>(&self, operation: F) -> AppResult<T> where F: Future<Output = AppResult<T>>`.
+/// Exported so decorators written outside the crate can forward every operation, e.g.
+/// `tao_database::impl_tao_operations_read_write_split!(AuditDecorator, inner);`
+#[macro_export]
+macro_rules! impl_tao_operations_read_write_split {
+    ($decorator:ty, $field:ident) => {
+        // Scoped imports, so the impl resolves the same inside and outside the crate
+        const _: () = {
+            use ::std::collections::HashMap;
+            use $crate::error::AppResult;
+            use $crate::infrastructure::database::database::DatabaseTransaction;
+            use $crate::infrastructure::tao_core::tao_core::{
+                AssocDeleteAllReport, AssocMoveReport, AssocType, PurgeReport, TaoAssocQuery,
+                TaoAssocQueryResult, TaoAssociation, TaoId, TaoObject, TaoObjectVersion,
+                TaoOperations, TaoTime, TaoType, TaoWriteBatch,
+            };
+            use $crate::infrastructure::tao_core::tao_decorators::__async_trait as async_trait;
 
-/// Base TAO decorator trait - all decorators implement this
-#[async_trait]
+            #[async_trait]
+            impl TaoOperations for $decorator {
+                async fn generate_id(&self, owner_id: Option<TaoId>) -> AppResult<TaoId> {
+                    self.execute_read(self.$field.generate_id(owner_id)).await
+                }
+
+                async fn create_object(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<()> {
+                    self.execute_write(self.$field.create_object(id, otype, data)).await
+                }
+
+                async fn obj_get(&self, id: TaoId) -> AppResult<Option<TaoObject>> {
+                    self.execute_read(self.$field.obj_get(id)).await
+                }
+
+                async fn obj_update(&self, id: TaoId, data: Vec<u8>) -> AppResult<()> {
+                    self.execute_write(self.$field.obj_update(id, data)).await
+                }
+
+                async fn obj_delete(&self, id: TaoId) -> AppResult<bool> {
+                    self.execute_write(self.$field.obj_delete(id)).await
+                }
+
+                async fn obj_exists(&self, id: TaoId) -> AppResult<bool> {
+                    self.execute_read(self.$field.obj_exists(id)).await
+                }
+
+                async fn obj_exists_by_type(&self, id: TaoId, otype: TaoType) -> AppResult<bool> {
+                    self.execute_read(self.$field.obj_exists_by_type(id, otype)).await
+                }
+
+                async fn obj_update_by_type(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<bool> {
+                    self.execute_write(self.$field.obj_update_by_type(id, otype, data)).await
+                }
+
+                async fn obj_delete_by_type(&self, id: TaoId, otype: TaoType) -> AppResult<bool> {
+                    self.execute_write(self.$field.obj_delete_by_type(id, otype)).await
+                }
+
+                async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+                    self.execute_write(self.$field.purge_object(id)).await
+                }
+
+                async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+                    self.execute_read(self.$field.get_object_history(id, limit)).await
+                }
+
+                async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+                    self.execute_write(self.$field.obj_rollback(id, to_version)).await
+                }
+
+                async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+                    self.execute_read(self.$field.obj_get_by_unique(otype, field, value)).await
+                }
+
+                async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+                    self.execute_read(self.$field.generate_id_for_unique(otype, field, value)).await
+                }
+
+                async fn obj_create_unique(&self, id: TaoId, otype: TaoType, data: Vec<u8>, field: String, value: String) -> AppResult<Option<TaoId>> {
+                    self.execute_write(self.$field.obj_create_unique(id, otype, data, field, value)).await
+                }
+
+                async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+                    self.execute_write(self.$field.write_batch(batch)).await
+                }
+
+                async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+                    self.execute_write(self.$field.set_attribute(id, key, value)).await
+                }
+
+                async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+                    self.execute_read(self.$field.get_attribute(id, key)).await
+                }
+
+                async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+                    self.execute_read(self.$field.get_attributes(id)).await
+                }
+
+                async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
+                    self.execute_read(self.$field.assoc_get(query)).await
+                }
+
+                async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+                    self.execute_read(self.$field.assoc_get_page(query)).await
+                }
+
+                async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+                    self.execute_read(self.$field.assoc_get_multi_type(id1, atypes, limit_per_type)).await
+                }
+
+                async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
+                    self.execute_write(self.$field.assoc_add(assoc)).await
+                }
+
+                async fn assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+                    self.execute_write(self.$field.assoc_delete(id1, atype, id2)).await
+                }
+
+                async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+                    self.execute_write(self.$field.assoc_delete_all(id1, atype)).await
+                }
+
+                async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+                    self.execute_write(self.$field.assoc_touch(id1, atype, id2, new_time)).await
+                }
+
+                async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+                    self.execute_write(self.$field.assoc_move(id1, atype, old_id2, new_id2)).await
+                }
+
+                async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+                    self.execute_write(self.$field.assoc_delete_between(id1, id2, atypes)).await
+                }
+
+                async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
+                    self.execute_read(self.$field.assoc_count(id1, atype)).await
+                }
+
+                async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+                    self.execute_read(self.$field.assoc_count_many(pairs)).await
+                }
+
+                async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+                    self.execute_read(self.$field.assoc_count_by_subtype(id1, atype)).await
+                }
+
+                async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+                    self.execute_read(self.$field.list_assoc_types(id)).await
+                }
+
+                async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
+                    self.execute_read(self.$field.assoc_range(id1, atype, offset, limit)).await
+                }
+
+                async fn assoc_time_range(&self, id1: TaoId, atype: AssocType, high_time: i64, low_time: i64, limit: Option<u32>) -> AppResult<Vec<TaoAssociation>> {
+                    self.execute_read(self.$field.assoc_time_range(id1, atype, high_time, low_time, limit)).await
+                }
+
+                async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+                    self.execute_read(self.$field.assoc_since(id1, atype, since_time, limit)).await
+                }
+
+                async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+                    self.execute_read(self.$field.assoc_delta_since(id1, atype, since_seq)).await
+                }
+
+                async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+                    self.execute_read(self.$field.assoc_exists(id1, atype, id2)).await
+                }
+
+                async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+                    self.execute_read(self.$field.assoc_get_one(id1, atype, id2)).await
+                }
+
+                async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+                    self.execute_read(self.$field.edges_between(id1, id2)).await
+                }
+
+                async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
+                    self.execute_read(self.$field.get_by_id_and_type(ids, otype)).await
+                }
+
+                async fn get_neighbors(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                    self.execute_read(self.$field.get_neighbors(id, atype, limit)).await
+                }
+
+                async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+                    self.execute_read(self.$field.get_neighbors_with_edges(id, atype, limit)).await
+                }
+
+                async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+                    self.execute_read(self.$field.get_neighbor_ids(id, atype, limit)).await
+                }
+
+                async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+                    self.execute_read(self.$field.get_in_neighbor_ids(id2, atype, limit)).await
+                }
+
+                async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+                    self.execute_read(self.$field.estimate_count_of_type(otype)).await
+                }
+
+                async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                    self.execute_read(self.$field.get_all_objects_of_type(otype, limit)).await
+                }
+
+                async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+                    self.execute_read(self.$field.get_objects_in_range(otype, start, end, limit)).await
+                }
+
+                async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
+                    self.execute_write(self.$field.begin_transaction()).await
+                }
+
+                async fn execute_query(&self, query: String) -> AppResult<Vec<HashMap<String, String>>> {
+                    self.execute_write(self.$field.execute_query(query)).await
+                }
+            }
+        };
+    };
+}
+
+use crate::error::{AppError, AppResult};
+use crate::framework::schema::ent_schema::{CachePolicy, Durability};
+use crate::infrastructure::cache::access_tracker::AccessTracker;
+use crate::infrastructure::cache::cache_layer::TaoMultiTierCache;
+use crate::infrastructure::cache::cache_policy::CachePolicies;
+use crate::infrastructure::cache::data_source::{record_data_source, DataSource};
+use crate::infrastructure::cache::invalidation_bus::{
+    spawn_invalidation_listener, CacheInvalidation, InvalidationBus, InvalidationMessage,
+};
+use crate::infrastructure::cache::read_consistency::{current_read_consistency, ReadConsistency};
+use crate::infrastructure::cache::stale_read::mark_stale_read;
+use crate::infrastructure::change_feed::feed::{ChangeEvent, ChangeFeed};
+use crate::infrastructure::database::database::DatabaseTransaction;
+use crate::infrastructure::monitoring::monitoring::MetricsCollector;
+use crate::infrastructure::tao_core::request_context::current_request_context;
+use crate::infrastructure::tao_core::tao_core::{
+    current_time_millis, AssocDeleteAllReport, AssocMoveReport, AssocType, PurgeReport,
+    TaoAssocQuery, TaoAssocQueryResult, TaoAssociation, TaoId, TaoObject, TaoObjectVersion,
+    TaoOperations, TaoTime, TaoType, TaoWriteBatch,
+};
+use crate::infrastructure::storage::durability::DurabilityPolicies;
+use crate::infrastructure::storage::write_ahead_log::{TaoOperation, TaoWriteAheadLog, WalStatus};
+use serde::Serialize;
+
+#[doc(hidden)]
+pub use async_trait::async_trait as __async_trait;
+
+/// Base TAO decorator trait - all decorators implement this
+///
+/// A decorator is one layer of the chain: it holds the next layer down (`inner`) and
+/// implements `TaoOperations` around it. Decorators from outside the crate are added
+/// with `TaoStackBuilder::with_decorator`. Every decorator has to:
+///
+/// - Forward every required `TaoOperations` method to `inner`, with the same arguments
+///   unless changing them is its purpose. A method answered without `inner` skips every
+///   layer below, including the WAL and cache invalidation. Provided methods such as
+///   `activity_feed` are built on the required ones and need no override.
+/// - Return `inner`'s errors unchanged, except ones it deliberately translates. The
+///   cache only serves stale reads when it sees the breaker's `ServiceUnavailable`.
+/// - Stay cheap and never block the runtime: every call in the process goes through it.
+/// - Report itself through `decorator_name` and `inner_decorator`, so `decorator_chain`
+///   can list the whole stack.
+///
+/// `impl_tao_operations_read_write_split!` writes the forwarding for you: it implements
+/// every `TaoOperations` method by passing the call on `inner` through the decorator's
+/// `execute_read` or `execute_write` hook.
+#[async_trait]
 pub trait TaoDecorator: TaoOperations + Send + Sync + std::fmt::Debug {
     /// Get the name of this decorator for logging
     fn decorator_name(&self) -> &'static str;
+
+    /// The next layer down the chain; `None` for the base
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        None
+    }
+}
+
+/// Decorator names from the outermost layer down to the base
+pub fn decorator_chain(top: &dyn TaoDecorator) -> Vec<&'static str> {
+    let mut names = vec![top.decorator_name()];
+    let mut current = top.inner_decorator();
+    while let Some(layer) = current {
+        names.push(layer.decorator_name());
+        current = layer.inner_decorator();
+    }
+    names
 }
 
 /// Base TAO wrapper around TaoCore - the foundation for all decorators
@@ -483,16 +1231,57 @@ impl TaoDecorator for BaseTao {
     }
 }
 
-/// WAL Decorator - Adds Write-Ahead Log functionality for durability and retry
+/// Outcome of one pass over the WAL retry queue
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WalReplaySummary {
+    /// Transactions found in the retry queue
+    pub pending_found: usize,
+    /// Transactions that were re-executed
+    pub retried: usize,
+    pub committed: usize,
+    pub failed: usize,
+    /// Failed retries that exhausted their attempts and were not requeued
+    pub dead_lettered: usize,
+}
+
+/// WAL Decorator - Adds Write-Ahead Log functionality for durability and retry.
+/// Writes of `Durability::BestEffort` types go to the database without a WAL entry, so
+/// one lost to a crash between the database write and the acknowledgement is never
+/// replayed. `obj_update`, `obj_delete`, `obj_rollback` and `set_attribute` do not know
+/// the otype and are always logged.
 #[derive(Debug)]
 pub struct WalDecorator {
     inner: Arc<dyn TaoDecorator>,
     wal: Arc<TaoWriteAheadLog>,
+    durability: Arc<DurabilityPolicies>,
 }
 
 impl WalDecorator {
     pub fn new(inner: Arc<dyn TaoDecorator>, wal: Arc<TaoWriteAheadLog>) -> Self {
-        Self { inner, wal }
+        Self {
+            inner,
+            wal,
+            durability: Arc::new(DurabilityPolicies::new()),
+        }
+    }
+
+    /// The write-ahead log this decorator writes to
+    pub fn wal(&self) -> &Arc<TaoWriteAheadLog> {
+        &self.wal
+    }
+
+    /// Skip the WAL for the best-effort types in `durability`
+    pub fn with_durability(mut self, durability: Arc<DurabilityPolicies>) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    fn is_best_effort_object(&self, otype: &str) -> bool {
+        self.durability.object_durability(otype) == Durability::BestEffort
+    }
+
+    fn is_best_effort_association(&self, atype: &str) -> bool {
+        self.durability.association_durability(atype) == Durability::BestEffort
     }
 
     /// Execute operations with WAL logging and retry on failure
@@ -523,12 +1312,45 @@ impl WalDecorator {
                 TaoOperation::DeleteAssociation { id1, atype, id2 } => {
                     self.inner.assoc_delete(id1, atype, id2).await.map(|_| ())
                 }
+                TaoOperation::DeleteAllAssociations { id1, atype } => {
+                    self.inner.assoc_delete_all(id1, atype).await.map(|_| ())
+                }
+                TaoOperation::MoveAssociation {
+                    id1,
+                    atype,
+                    old_id2,
+                    new_id2,
+                } => self
+                    .inner
+                    .assoc_move(id1, atype, old_id2, new_id2)
+                    .await
+                    .map(|_| ()),
+                TaoOperation::DeleteAssociationsBetween { id1, id2, atypes } => self
+                    .inner
+                    .assoc_delete_between(id1, id2, atypes)
+                    .await
+                    .map(|_| ()),
+                TaoOperation::TouchAssociation {
+                    id1,
+                    atype,
+                    id2,
+                    time,
+                } => self
+                    .inner
+                    .assoc_touch(id1, atype, id2, Some(time))
+                    .await
+                    .map(|_| ()),
                 TaoOperation::UpdateObject { object_id, data } => {
                     self.inner.obj_update(object_id, data).await
                 }
                 TaoOperation::DeleteObject { object_id } => {
                     self.inner.obj_delete(object_id).await.map(|_| ())
                 }
+                TaoOperation::SetAttribute {
+                    object_id,
+                    key,
+                    value,
+                } => self.inner.set_attribute(object_id, key, value).await,
             };
 
             if let Err(e) = result {
@@ -553,12 +1375,21 @@ impl WalDecorator {
         }
     }
 
+    /// Current pending, retry and dead-letter counts of the underlying WAL
+    pub async fn status(&self) -> WalStatus {
+        self.wal.status().await
+    }
+
     /// Process pending transactions from WAL
-    pub async fn process_pending_transactions(&self) -> AppResult<()> {
+    pub async fn process_pending_transactions(&self) -> AppResult<WalReplaySummary> {
         let retry_txns = self.wal.get_pending_retries().await;
+        let mut summary = WalReplaySummary {
+            pending_found: retry_txns.len(),
+            ..WalReplaySummary::default()
+        };
 
         if retry_txns.is_empty() {
-            return Ok(());
+            return Ok(summary);
         }
 
         info!(
@@ -574,6 +1405,7 @@ impl WalDecorator {
                 // Increment retry count
                 let retry_count = self.wal.increment_retry_count(txn_id).await?;
                 info!("Retrying transaction {} (attempt {})", txn_id, retry_count);
+                summary.retried += 1;
 
                 // Execute operations individually via inner decorator chain
                 let mut success = true;
@@ -595,12 +1427,45 @@ impl WalDecorator {
                         TaoOperation::DeleteAssociation { id1, atype, id2 } => {
                             self.inner.assoc_delete(id1, atype, id2).await.map(|_| ())
                         }
+                        TaoOperation::DeleteAllAssociations { id1, atype } => {
+                            self.inner.assoc_delete_all(id1, atype).await.map(|_| ())
+                        }
+                        TaoOperation::MoveAssociation {
+                            id1,
+                            atype,
+                            old_id2,
+                            new_id2,
+                        } => self
+                            .inner
+                            .assoc_move(id1, atype, old_id2, new_id2)
+                            .await
+                            .map(|_| ()),
+                        TaoOperation::DeleteAssociationsBetween { id1, id2, atypes } => self
+                            .inner
+                            .assoc_delete_between(id1, id2, atypes)
+                            .await
+                            .map(|_| ()),
+                        TaoOperation::TouchAssociation {
+                            id1,
+                            atype,
+                            id2,
+                            time,
+                        } => self
+                            .inner
+                            .assoc_touch(id1, atype, id2, Some(time))
+                            .await
+                            .map(|_| ()),
                         TaoOperation::UpdateObject { object_id, data } => {
                             self.inner.obj_update(object_id, data).await
                         }
                         TaoOperation::DeleteObject { object_id } => {
                             self.inner.obj_delete(object_id).await.map(|_| ())
                         }
+                        TaoOperation::SetAttribute {
+                            object_id,
+                            key,
+                            value,
+                        } => self.inner.set_attribute(object_id, key, value).await,
                     };
 
                     if let Err(e) = result {
@@ -612,23 +1477,32 @@ impl WalDecorator {
 
                 if success {
                     self.wal.mark_transaction_committed(txn_id).await?;
+                    summary.committed += 1;
                     info!("Retry of transaction {} succeeded", txn_id);
                 } else {
                     self.wal
                         .mark_transaction_failed(txn_id, error_msg.clone())
                         .await?;
+                    summary.failed += 1;
+                    // Not requeued means the transaction is out of retries
+                    if !self.wal.get_pending_retries().await.contains(&txn_id) {
+                        summary.dead_lettered += 1;
+                    }
                     error!("Retry of transaction {} failed: {}", txn_id, error_msg);
                 }
             }
         }
 
-        Ok(())
+        Ok(summary)
     }
 }
 
 impl WalDecorator {
     async fn wal_create_object(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<()> {
         self.inner.create_object(id, otype.clone(), data.clone()).await?;
+        if self.is_best_effort_object(&otype) {
+            return Ok(());
+        }
         let operation = TaoOperation::InsertObject { object_id: id, object_type: otype, data };
         let txn_id = self.wal.log_operations(vec![operation]).await?;
         self.wal.mark_transaction_committed(txn_id).await?;
@@ -658,6 +1532,9 @@ impl WalDecorator {
 
     async fn wal_assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
         self.inner.assoc_add(assoc.clone()).await?;
+        if self.is_best_effort_association(&assoc.atype) {
+            return Ok(());
+        }
         let operation = TaoOperation::InsertAssociation { assoc };
         let txn_id = self.wal.log_operations(vec![operation]).await?;
         self.wal.mark_transaction_committed(txn_id).await?;
@@ -667,7 +1544,7 @@ impl WalDecorator {
 
     async fn wal_assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
         let result = self.inner.assoc_delete(id1, atype.clone(), id2).await?;
-        if result {
+        if result && !self.is_best_effort_association(&atype) {
             let operation = TaoOperation::DeleteAssociation { id1, atype, id2 };
             let txn_id = self.wal.log_operations(vec![operation]).await?;
             self.wal.mark_transaction_committed(txn_id).await?;
@@ -675,6 +1552,96 @@ impl WalDecorator {
         }
         Ok(result)
     }
+
+    async fn wal_set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        self.inner.set_attribute(id, key.clone(), value.clone()).await?;
+        let operation = TaoOperation::SetAttribute { object_id: id, key, value };
+        let txn_id = self.wal.log_operations(vec![operation]).await?;
+        self.wal.mark_transaction_committed(txn_id).await?;
+        debug!("Logged set_attribute operation {} to WAL as transaction {}", id, txn_id);
+        Ok(())
+    }
+
+    async fn wal_assoc_touch(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        id2: TaoId,
+        new_time: Option<TaoTime>,
+    ) -> AppResult<bool> {
+        // Resolve the time up front so a replay writes the same timestamp
+        let time = new_time.unwrap_or_else(current_time_millis);
+        let result = self.inner.assoc_touch(id1, atype.clone(), id2, Some(time)).await?;
+        if result && !self.is_best_effort_association(&atype) {
+            let operation = TaoOperation::TouchAssociation { id1, atype, id2, time };
+            let txn_id = self.wal.log_operations(vec![operation]).await?;
+            self.wal.mark_transaction_committed(txn_id).await?;
+            debug!("Logged assoc_touch operation to WAL as transaction {}", txn_id);
+        }
+        Ok(result)
+    }
+
+    async fn wal_assoc_move(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        old_id2: TaoId,
+        new_id2: TaoId,
+    ) -> AppResult<AssocMoveReport> {
+        let result = self.inner.assoc_move(id1, atype.clone(), old_id2, new_id2).await?;
+        if result.moved && !self.is_best_effort_association(&atype) {
+            let operation = TaoOperation::MoveAssociation { id1, atype, old_id2, new_id2 };
+            let txn_id = self.wal.log_operations(vec![operation]).await?;
+            self.wal.mark_transaction_committed(txn_id).await?;
+            debug!("Logged assoc_move operation to WAL as transaction {}", txn_id);
+        }
+        Ok(result)
+    }
+
+    /// Purge locally, then run the inverse edge deletions as one WAL transaction. If any
+    /// of them fails the transaction stays queued for `process_pending_transactions`.
+    async fn wal_purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        let mut report = self.inner.purge_object(id).await?;
+
+        let mut operations: Vec<TaoOperation> = report
+            .associations_removed
+            .iter()
+            .map(|(atype, id2)| TaoOperation::DeleteAssociation {
+                id1: id,
+                atype: atype.clone(),
+                id2: *id2,
+            })
+            .collect();
+        if report.object_deleted {
+            operations.push(TaoOperation::DeleteObject { object_id: id });
+        }
+        if !operations.is_empty() {
+            let txn_id = self.wal.log_operations(operations).await?;
+            self.wal.mark_transaction_committed(txn_id).await?;
+            debug!("Logged purge_object operation {} to WAL as transaction {}", id, txn_id);
+        }
+
+        if report.inverse_deferred.is_empty() {
+            return Ok(report);
+        }
+        let inverse_deletes = report
+            .inverse_deferred
+            .iter()
+            .map(|(id1, atype, id2)| TaoOperation::DeleteAssociation {
+                id1: *id1,
+                atype: atype.clone(),
+                id2: *id2,
+            })
+            .collect();
+        match self.execute_transaction_with_wal(inverse_deletes).await {
+            Ok(_) => report.inverse_removed.append(&mut report.inverse_deferred),
+            Err(e) => warn!(
+                "Inverse edges of purged object {} left queued in the WAL: {}",
+                id, e
+            ),
+        }
+        Ok(report)
+    }
 }
 
 #[async_trait]
@@ -708,8 +1675,9 @@ impl TaoOperations for WalDecorator {
     }
 
     async fn obj_update_by_type(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<bool> {
+        let best_effort = self.is_best_effort_object(&otype);
         let result = self.inner.obj_update_by_type(id, otype, data.clone()).await?;
-        if result {
+        if result && !best_effort {
             let operation = TaoOperation::UpdateObject { object_id: id, data };
             let txn_id = self.wal.log_operations(vec![operation]).await?;
             self.wal.mark_transaction_committed(txn_id).await?;
@@ -719,8 +1687,9 @@ impl TaoOperations for WalDecorator {
     }
 
     async fn obj_delete_by_type(&self, id: TaoId, otype: TaoType) -> AppResult<bool> {
+        let best_effort = self.is_best_effort_object(&otype);
         let result = self.inner.obj_delete_by_type(id, otype).await?;
-        if result {
+        if result && !best_effort {
             let operation = TaoOperation::DeleteObject { object_id: id };
             let txn_id = self.wal.log_operations(vec![operation]).await?;
             self.wal.mark_transaction_committed(txn_id).await?;
@@ -729,10 +1698,92 @@ impl TaoOperations for WalDecorator {
         Ok(result)
     }
 
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        self.wal_purge_object(id).await
+    }
+
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+        self.inner.get_object_history(id, limit).await
+    }
+
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+        let data = self.inner.obj_rollback(id, to_version).await?;
+        // Replayed as the plain update it amounts to
+        let operation = TaoOperation::UpdateObject { object_id: id, data: data.clone() };
+        let txn_id = self.wal.log_operations(vec![operation]).await?;
+        self.wal.mark_transaction_committed(txn_id).await?;
+        debug!("Logged obj_rollback operation {} to WAL as transaction {}", id, txn_id);
+        Ok(data)
+    }
+
+    async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+        self.inner.obj_get_by_unique(otype, field, value).await
+    }
+
+    async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+        self.inner.generate_id_for_unique(otype, field, value).await
+    }
+
+    async fn obj_create_unique(&self, id: TaoId, otype: TaoType, data: Vec<u8>, field: String, value: String) -> AppResult<Option<TaoId>> {
+        let holder = self.inner.obj_create_unique(id, otype.clone(), data.clone(), field, value).await?;
+        if holder.is_none() && !self.is_best_effort_object(&otype) {
+            let operation = TaoOperation::InsertObject { object_id: id, object_type: otype, data };
+            let txn_id = self.wal.log_operations(vec![operation]).await?;
+            self.wal.mark_transaction_committed(txn_id).await?;
+            debug!("Logged obj_create_unique operation {} to WAL as transaction {}", id, txn_id);
+        }
+        Ok(holder)
+    }
+
+    async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+        self.inner.write_batch(batch.clone()).await?;
+        // Best-effort writes are left out of the WAL, as on the single-op paths
+        let mut operations: Vec<TaoOperation> = batch
+            .objects
+            .into_iter()
+            .filter(|(_, object_type, _)| !self.is_best_effort_object(object_type))
+            .map(|(object_id, object_type, data)| TaoOperation::InsertObject { object_id, object_type, data })
+            .collect();
+        operations.extend(
+            batch
+                .associations
+                .into_iter()
+                .filter(|assoc| !self.is_best_effort_association(&assoc.atype))
+                .map(|assoc| TaoOperation::InsertAssociation { assoc }),
+        );
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let txn_id = self.wal.log_operations(operations).await?;
+        self.wal.mark_transaction_committed(txn_id).await?;
+        debug!("Logged write_batch operations to WAL as transaction {}", txn_id);
+        Ok(())
+    }
+
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        self.wal_set_attribute(id, key, value).await
+    }
+
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+        self.inner.get_attribute(id, key).await
+    }
+
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+        self.inner.get_attributes(id).await
+    }
+
     async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
         self.inner.assoc_get(query).await
     }
 
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+        self.inner.assoc_get_page(query).await
+    }
+
+    async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+        self.inner.assoc_get_multi_type(id1, atypes, limit_per_type).await
+    }
+
     async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
         self.wal_assoc_add(assoc).await
     }
@@ -741,10 +1792,52 @@ impl TaoOperations for WalDecorator {
         self.wal_assoc_delete(id1, atype, id2).await
     }
 
+    async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+        let deleted = self.inner.assoc_delete_all(id1, atype.clone()).await?;
+        if !deleted.removed.is_empty() && !self.is_best_effort_association(&atype) {
+            let operation = TaoOperation::DeleteAllAssociations { id1, atype };
+            let txn_id = self.wal.log_operations(vec![operation]).await?;
+            self.wal.mark_transaction_committed(txn_id).await?;
+            debug!("Logged assoc_delete_all operation to WAL as transaction {}", txn_id);
+        }
+        Ok(deleted)
+    }
+
+    async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+        self.wal_assoc_touch(id1, atype, id2, new_time).await
+    }
+
+    async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+        self.wal_assoc_move(id1, atype, old_id2, new_id2).await
+    }
+
+    async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+        let removed = self.inner.assoc_delete_between(id1, id2, atypes.clone()).await?;
+        if removed.keys().any(|atype| !self.is_best_effort_association(atype)) {
+            let operation = TaoOperation::DeleteAssociationsBetween { id1, id2, atypes };
+            let txn_id = self.wal.log_operations(vec![operation]).await?;
+            self.wal.mark_transaction_committed(txn_id).await?;
+            debug!("Logged assoc_delete_between operation to WAL as transaction {}", txn_id);
+        }
+        Ok(removed)
+    }
+
     async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
         self.inner.assoc_count(id1, atype).await
     }
 
+    async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+        self.inner.assoc_count_many(pairs).await
+    }
+
+    async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+        self.inner.assoc_count_by_subtype(id1, atype).await
+    }
+
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+        self.inner.list_assoc_types(id).await
+    }
+
     async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
         self.inner.assoc_range(id1, atype, offset, limit).await
     }
@@ -753,10 +1846,26 @@ impl TaoOperations for WalDecorator {
         self.inner.assoc_time_range(id1, atype, high_time, low_time, limit).await
     }
 
+    async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+        self.inner.assoc_since(id1, atype, since_time, limit).await
+    }
+
+    async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+        self.inner.assoc_delta_since(id1, atype, since_seq).await
+    }
+
     async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
         self.inner.assoc_exists(id1, atype, id2).await
     }
 
+    async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+        self.inner.assoc_get_one(id1, atype, id2).await
+    }
+
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+        self.inner.edges_between(id1, id2).await
+    }
+
     async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
         self.inner.get_by_id_and_type(ids, otype).await
     }
@@ -765,14 +1874,30 @@ impl TaoOperations for WalDecorator {
         self.inner.get_neighbors(id, atype, limit).await
     }
 
+    async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+        self.inner.get_neighbors_with_edges(id, atype, limit).await
+    }
+
     async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
         self.inner.get_neighbor_ids(id, atype, limit).await
     }
 
+    async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+        self.inner.get_in_neighbor_ids(id2, atype, limit).await
+    }
+
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+        self.inner.estimate_count_of_type(otype).await
+    }
+
     async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
         self.inner.get_all_objects_of_type(otype, limit).await
     }
 
+    async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+        self.inner.get_objects_in_range(otype, start, end, limit).await
+    }
+
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
         self.inner.begin_transaction().await
     }
@@ -787,6 +1912,10 @@ impl TaoDecorator for WalDecorator {
     fn decorator_name(&self) -> &'static str {
         "WalDecorator"
     }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
 }
 
 /// Metrics Decorator - Adds comprehensive monitoring and metrics collection
@@ -820,6 +1949,10 @@ impl TaoDecorator for MetricsDecorator {
     fn decorator_name(&self) -> &'static str {
         "MetricsDecorator"
     }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
 }
 
 /// Cache Decorator - Adds caching functionality for read operations
@@ -828,6 +1961,12 @@ pub struct CacheDecorator {
     inner: Arc<dyn TaoDecorator>,
     cache: Arc<TaoMultiTierCache>,
     enable_caching: bool,
+    /// Bus to announce invalidations on, and this node's id on it
+    invalidation_bus: Option<(Arc<dyn InvalidationBus>, String)>,
+    /// Per-otype rules for whether and how long `obj_get` results are cached
+    cache_policies: Arc<CachePolicies>,
+    /// Samples `obj_get` calls to find the most read objects
+    access_tracker: Option<Arc<AccessTracker>>,
 }
 
 impl CacheDecorator {
@@ -840,8 +1979,72 @@ impl CacheDecorator {
             inner,
             cache,
             enable_caching,
+            invalidation_bus: None,
+            cache_policies: Arc::new(CachePolicies::new()),
+            access_tracker: None,
+        }
+    }
+
+    /// Cache objects according to their type's policy instead of always using the
+    /// cache's TTLs
+    pub fn with_cache_policies(mut self, policies: Arc<CachePolicies>) -> Self {
+        self.cache_policies = policies;
+        self
+    }
+
+    /// Record object reads in `tracker`, whether or not the cache answers them
+    pub fn with_access_tracker(mut self, tracker: Arc<AccessTracker>) -> Self {
+        self.access_tracker = Some(tracker);
+        self
+    }
+
+    /// Announce this node's invalidations on `bus` and evict L1 entries other nodes
+    /// announce there. `node_id` must be unique per node; it keeps a node from acting
+    /// on its own messages. Must be called inside a Tokio runtime.
+    pub fn with_invalidation_bus(
+        mut self,
+        bus: Arc<dyn InvalidationBus>,
+        node_id: impl Into<String>,
+    ) -> Self {
+        let node_id = node_id.into();
+        spawn_invalidation_listener(self.cache.clone(), bus.as_ref(), node_id.clone());
+        self.invalidation_bus = Some((bus, node_id));
+        self
+    }
+
+    async fn invalidate_object(&self, id: TaoId) {
+        let _ = self.cache.invalidate_object(id).await;
+        self.announce(CacheInvalidation::Object(id)).await;
+    }
+
+    async fn invalidate_associations(&self, id1: TaoId, atype: &str) {
+        let _ = self.cache.invalidate_associations(id1, atype).await;
+        self.announce(CacheInvalidation::Associations {
+            id1,
+            atype: atype.to_string(),
+        })
+        .await;
+    }
+
+    async fn announce(&self, invalidation: CacheInvalidation) {
+        if let Some((bus, node_id)) = &self.invalidation_bus {
+            let message = InvalidationMessage {
+                origin: node_id.clone(),
+                invalidation,
+            };
+            if let Err(e) = bus.publish(message).await {
+                warn!("Failed to broadcast cache invalidation: {}", e);
+            }
         }
     }
+
+    /// Whether a failed read should fall back to stale cache.
+    /// Only `ServiceUnavailable` (e.g. an open circuit breaker) qualifies - other errors surface as-is.
+    fn should_serve_stale(&self, error: &AppError) -> bool {
+        self.enable_caching
+            && self.cache.config().serve_stale_on_unavailable
+            && matches!(error, AppError::ServiceUnavailable(_))
+    }
 }
 
 #[async_trait]
@@ -855,7 +2058,7 @@ impl TaoOperations for CacheDecorator {
 
         // Invalidate cache on successful creation
         if result.is_ok() && self.enable_caching {
-            let _ = self.cache.invalidate_object(id).await;
+            self.invalidate_object(id).await;
         }
 
         result
@@ -863,22 +2066,52 @@ impl TaoOperations for CacheDecorator {
 
     #[instrument(skip(self), fields(object_id = %id))]
     async fn obj_get(&self, id: TaoId) -> AppResult<Option<TaoObject>> {
+        if let Some(tracker) = &self.access_tracker {
+            tracker.record(id);
+        }
         if !self.enable_caching {
             return self.inner.obj_get(id).await;
         }
 
-        // Try cache first
-        if let Ok(Some(cached)) = self.cache.get_object(id).await {
-            debug!("Cache hit for object {}", id);
-            return Ok(Some(cached));
+        // Try cache first, unless the caller asked for a fresh read
+        let fresh = current_read_consistency() == ReadConsistency::Fresh;
+        if !fresh {
+            if let Ok(Some(cached)) = self.cache.get_object(id).await {
+                debug!("Cache hit for object {}", id);
+                record_data_source(DataSource::Cache);
+                return Ok(Some(cached));
+            }
         }
 
         // Cache miss, fetch from inner
-        let result = self.inner.obj_get(id).await?;
+        let result = match self.inner.obj_get(id).await {
+            Ok(result) => result,
+            Err(e) if !fresh && self.should_serve_stale(&e) => {
+                if let Ok(Some(stale)) = self.cache.get_stale_object(id).await {
+                    warn!("Serving stale cached object {} ({})", id, e);
+                    mark_stale_read();
+                    record_data_source(DataSource::Cache);
+                    return Ok(Some(stale));
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
 
-        // Populate cache if object found
+        // Populate cache if object found; a fresh read that finds nothing drops any cached copy
         if let Some(ref obj) = result {
-            let _ = self.cache.put_object(id, obj).await;
+            match self.cache_policies.policy_for(&obj.otype) {
+                CachePolicy::Default => {
+                    let _ = self.cache.put_object(id, obj).await;
+                }
+                CachePolicy::Ttl(ms) => {
+                    let ttl = Duration::from_millis(ms);
+                    let _ = self.cache.put_object_with_ttl(id, obj, ttl).await;
+                }
+                CachePolicy::Never => {}
+            }
+        } else if fresh {
+            self.invalidate_object(id).await;
         }
 
         Ok(result)
@@ -889,7 +2122,7 @@ impl TaoOperations for CacheDecorator {
 
         // Invalidate cache on successful update
         if result.is_ok() && self.enable_caching {
-            let _ = self.cache.invalidate_object(id).await;
+            self.invalidate_object(id).await;
         }
 
         result
@@ -901,7 +2134,7 @@ impl TaoOperations for CacheDecorator {
         // Invalidate cache on successful deletion
         if let Ok(true) = result {
             if self.enable_caching {
-                let _ = self.cache.invalidate_object(id).await;
+                self.invalidate_object(id).await;
             }
         }
 
@@ -909,23 +2142,52 @@ impl TaoOperations for CacheDecorator {
     }
 
     async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
-        if !self.enable_caching || query.id2_set.is_some() {
+        if !self.enable_caching
+            || query.id2_set.is_some()
+            || query.low_id2.is_some()
+            || query.high_id2.is_some()
+            || query.after.is_some()
+        {
             // Skip cache for complex queries
             return self.inner.assoc_get(query).await;
         }
 
-        // Try cache for simple queries
-        if let Ok(Some(cached_assocs)) = self.cache.get_associations(query.id1, &query.atype).await
-        {
-            debug!(
-                "Cache hit for associations {} -> {}",
-                query.id1, query.atype
-            );
-            return Ok(cached_assocs);
+        // Try cache for simple queries, unless the caller asked for a fresh read
+        let fresh = current_read_consistency() == ReadConsistency::Fresh;
+        if !fresh {
+            if let Ok(Some(cached_assocs)) =
+                self.cache.get_associations(query.id1, &query.atype).await
+            {
+                debug!(
+                    "Cache hit for associations {} -> {}",
+                    query.id1, query.atype
+                );
+                record_data_source(DataSource::Cache);
+                return Ok(cached_assocs);
+            }
         }
 
         // Cache miss, fetch from inner
-        let associations = self.inner.assoc_get(query.clone()).await?;
+        let associations = match self.inner.assoc_get(query.clone()).await {
+            Ok(associations) => associations,
+            Err(e) if !fresh && self.should_serve_stale(&e) => {
+                if let Ok(Some(stale)) = self
+                    .cache
+                    .get_stale_associations(query.id1, &query.atype)
+                    .await
+                {
+                    warn!(
+                        "Serving stale cached associations {} -> {} ({})",
+                        query.id1, query.atype, e
+                    );
+                    mark_stale_read();
+                    record_data_source(DataSource::Cache);
+                    return Ok(stale);
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
 
         // Populate cache
         let _ = self
@@ -936,26 +2198,116 @@ impl TaoOperations for CacheDecorator {
         Ok(associations)
     }
 
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+        // Pages are not cached: the cached association lists have no total to go with them
+        self.inner.assoc_get_page(query).await
+    }
+
+    async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+        // Not cached: the per-type lists are served by assoc_get's cache entries only
+        self.inner.assoc_get_multi_type(id1, atypes, limit_per_type).await
+    }
+
     async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
         let result = self.inner.assoc_add(assoc.clone()).await;
 
-        // Invalidate cache for both objects
+        // Invalidate cache for both objects. A symmetric edge is also in id2's list, and
+        // the cache can't tell which types are symmetric, so id2's list always goes.
         if result.is_ok() && self.enable_caching {
-            let _ = self.cache.invalidate_object(assoc.id1).await;
-            let _ = self.cache.invalidate_object(assoc.id2).await;
+            self.invalidate_associations(assoc.id1, &assoc.atype).await;
+            self.invalidate_associations(assoc.id2, &assoc.atype).await;
+            self.invalidate_object(assoc.id1).await;
+            self.invalidate_object(assoc.id2).await;
         }
 
         result
     }
 
     async fn assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
-        let result = self.inner.assoc_delete(id1, atype, id2).await;
+        let result = self.inner.assoc_delete(id1, atype.clone(), id2).await;
 
         // Invalidate cache for both objects on successful deletion
         if let Ok(true) = result {
             if self.enable_caching {
-                let _ = self.cache.invalidate_object(id1).await;
-                let _ = self.cache.invalidate_object(id2).await;
+                self.invalidate_associations(id1, &atype).await;
+                self.invalidate_associations(id2, &atype).await;
+                self.invalidate_object(id1).await;
+                self.invalidate_object(id2).await;
+            }
+        }
+
+        result
+    }
+
+    async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+        let result = self.inner.assoc_delete_all(id1, atype.clone()).await;
+
+        // Every id2 lost its inverse edge and with it a count, so drop those like id1's
+        if let Ok(deleted) = &result {
+            if !deleted.removed.is_empty() && self.enable_caching {
+                self.invalidate_associations(id1, &atype).await;
+                self.invalidate_object(id1).await;
+                for (inverse_id1, inverse, _) in &deleted.inverse_removed {
+                    self.invalidate_associations(*inverse_id1, inverse).await;
+                }
+                for id2 in &deleted.removed {
+                    self.invalidate_object(*id2).await;
+                }
+            }
+        }
+        result
+    }
+
+    async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+        let result = self.inner.assoc_touch(id1, atype.clone(), id2, new_time).await;
+
+        // The edge moved within id1's time-ordered list, so drop both ends like assoc_add
+        if let Ok(true) = result {
+            if self.enable_caching {
+                self.invalidate_associations(id1, &atype).await;
+                self.invalidate_object(id1).await;
+                self.invalidate_object(id2).await;
+            }
+        }
+
+        result
+    }
+
+    async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+        let result = self.inner.assoc_move(id1, atype.clone(), old_id2, new_id2).await;
+
+        // Both targets' lists change, so drop them like assoc_delete and assoc_add, and
+        // with them the inverse lists the inverse edge moved between
+        if let Ok(report) = &result {
+            if report.moved && self.enable_caching {
+                self.invalidate_associations(id1, &atype).await;
+                self.invalidate_associations(old_id2, &atype).await;
+                self.invalidate_associations(new_id2, &atype).await;
+                if let Some(inverse) = &report.inverse_atype {
+                    self.invalidate_associations(old_id2, inverse).await;
+                    self.invalidate_associations(new_id2, inverse).await;
+                }
+                self.invalidate_object(id1).await;
+                self.invalidate_object(old_id2).await;
+                self.invalidate_object(new_id2).await;
+            }
+        }
+
+        result
+    }
+
+    async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+        let result = self.inner.assoc_delete_between(id1, id2, atypes).await;
+
+        // Either end may have lost edges of any removed type
+        if let Ok(removed) = &result {
+            if !removed.is_empty() && self.enable_caching {
+                for atype in removed.keys() {
+                    self.invalidate_associations(id1, atype).await;
+                    self.invalidate_associations(id2, atype).await;
+                }
+                self.invalidate_object(id1).await;
+                self.invalidate_object(id2).await;
             }
         }
 
@@ -980,7 +2332,7 @@ impl TaoOperations for CacheDecorator {
         let result = self.inner.obj_update_by_type(id, otype, data).await;
         if let Ok(true) = result {
             if self.enable_caching {
-                let _ = self.cache.invalidate_object(id).await;
+                self.invalidate_object(id).await;
             }
         }
         result
@@ -990,16 +2342,164 @@ impl TaoOperations for CacheDecorator {
         let result = self.inner.obj_delete_by_type(id, otype).await;
         if let Ok(true) = result {
             if self.enable_caching {
-                let _ = self.cache.invalidate_object(id).await;
+                self.invalidate_object(id).await;
+            }
+        }
+        result
+    }
+
+    async fn obj_get_by_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoObject>> {
+        self.inner.obj_get_by_unique(otype, field, value).await
+    }
+
+    async fn generate_id_for_unique(
+        &self,
+        otype: TaoType,
+        field: String,
+        value: String,
+    ) -> AppResult<TaoId> {
+        self.inner.generate_id_for_unique(otype, field, value).await
+    }
+
+    async fn obj_create_unique(
+        &self,
+        id: TaoId,
+        otype: TaoType,
+        data: Vec<u8>,
+        field: String,
+        value: String,
+    ) -> AppResult<Option<TaoId>> {
+        let result = self
+            .inner
+            .obj_create_unique(id, otype, data, field, value)
+            .await;
+        if let Ok(None) = result {
+            if self.enable_caching {
+                self.invalidate_object(id).await;
+            }
+        }
+        result
+    }
+
+    async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+        self.inner.write_batch(batch.clone()).await?;
+        if self.enable_caching {
+            let mut objects: HashSet<TaoId> = batch.objects.iter().map(|(id, _, _)| *id).collect();
+            let mut lists: HashSet<(TaoId, &str)> = HashSet::new();
+            for assoc in &batch.associations {
+                objects.insert(assoc.id1);
+                objects.insert(assoc.id2);
+                lists.insert((assoc.id1, assoc.atype.as_str()));
+                lists.insert((assoc.id2, assoc.atype.as_str()));
+            }
+            for (id1, atype) in lists {
+                self.invalidate_associations(id1, atype).await;
             }
+            for id in objects {
+                self.invalidate_object(id).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+        self.inner.get_object_history(id, limit).await
+    }
+
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+        let result = self.inner.obj_rollback(id, to_version).await;
+
+        // Invalidate cache on successful rollback
+        if result.is_ok() && self.enable_caching {
+            self.invalidate_object(id).await;
         }
+
         result
     }
 
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        let report = self.inner.purge_object(id).await?;
+        if self.enable_caching {
+            if report.object_deleted {
+                self.invalidate_object(id).await;
+            }
+            let mut lists: HashSet<(TaoId, &str)> = report
+                .associations_removed
+                .iter()
+                .map(|(atype, _)| (id, atype.as_str()))
+                .collect();
+            for (id1, atype, _) in report.inverse_removed.iter().chain(&report.inverse_deferred) {
+                lists.insert((*id1, atype.as_str()));
+            }
+            for (id1, atype) in lists {
+                self.invalidate_associations(id1, atype).await;
+            }
+        }
+        Ok(report)
+    }
+
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        self.inner.set_attribute(id, key, value).await
+    }
+
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+        self.inner.get_attribute(id, key).await
+    }
+
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+        self.inner.get_attributes(id).await
+    }
+
     async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
         self.inner.assoc_count(id1, atype).await
     }
 
+    async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+        if !self.enable_caching {
+            return self.inner.assoc_count_many(pairs).await;
+        }
+
+        // Serve what the cache has, then fetch every miss in one batch
+        let mut counts = HashMap::new();
+        let mut misses = Vec::new();
+        for (id1, atype) in pairs {
+            match self.cache.get_assoc_count(id1, &atype).await {
+                Ok(Some(count)) => {
+                    record_data_source(DataSource::Cache);
+                    counts.insert((id1, atype), count);
+                }
+                _ => misses.push((id1, atype)),
+            }
+        }
+        if misses.is_empty() {
+            return Ok(counts);
+        }
+
+        let fetched = self.inner.assoc_count_many(misses).await?;
+        let mut by_object: HashMap<TaoId, HashMap<AssocType, u64>> = HashMap::new();
+        for ((id1, atype), count) in &fetched {
+            by_object.entry(*id1).or_default().insert(atype.clone(), *count);
+        }
+        for (id1, object_counts) in by_object {
+            let _ = self.cache.put_assoc_counts(id1, &object_counts).await;
+        }
+        counts.extend(fetched);
+        Ok(counts)
+    }
+
+    async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+        self.inner.assoc_count_by_subtype(id1, atype).await
+    }
+
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+        self.inner.list_assoc_types(id).await
+    }
+
     async fn assoc_range(
         &self,
         id1: TaoId,
@@ -1023,10 +2523,49 @@ impl TaoOperations for CacheDecorator {
             .await
     }
 
+    async fn assoc_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_time: TaoTime,
+        limit: u32,
+    ) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+        // Incremental reads are never cached: each poll must see the latest edges
+        self.inner.assoc_since(id1, atype, since_time, limit).await
+    }
+
+    async fn assoc_delta_since(
+        &self,
+        id1: TaoId,
+        atype: AssocType,
+        since_seq: u64,
+    ) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+        // Deltas are never cached either: a stale sequence number would hide changes
+        self.inner.assoc_delta_since(id1, atype, since_seq).await
+    }
+
     async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
         self.inner.assoc_exists(id1, atype, id2).await
     }
 
+    async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+        // Served from a cached (id1, atype) list when it holds the edge; the list may be
+        // truncated, so a miss still goes to storage
+        if self.enable_caching && current_read_consistency() != ReadConsistency::Fresh {
+            if let Ok(Some(cached_assocs)) = self.cache.get_associations(id1, &atype).await {
+                if let Some(assoc) = cached_assocs.into_iter().find(|assoc| assoc.id2 == id2) {
+                    record_data_source(DataSource::Cache);
+                    return Ok(Some(assoc));
+                }
+            }
+        }
+        self.inner.assoc_get_one(id1, atype, id2).await
+    }
+
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+        self.inner.edges_between(id1, id2).await
+    }
+
     async fn get_by_id_and_type(
         &self,
         ids: Vec<TaoId>,
@@ -1044,6 +2583,15 @@ impl TaoOperations for CacheDecorator {
         self.inner.get_neighbors(id, atype, limit).await
     }
 
+    async fn get_neighbors_with_edges(
+        &self,
+        id: TaoId,
+        atype: AssocType,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+        self.inner.get_neighbors_with_edges(id, atype, limit).await
+    }
+
     async fn get_neighbor_ids(
         &self,
         id: TaoId,
@@ -1053,6 +2601,10 @@ impl TaoOperations for CacheDecorator {
         self.inner.get_neighbor_ids(id, atype, limit).await
     }
 
+    async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+        self.inner.get_in_neighbor_ids(id2, atype, limit).await
+    }
+
     async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
         self.inner.begin_transaction().await
     }
@@ -1061,6 +2613,10 @@ impl TaoOperations for CacheDecorator {
         self.inner.execute_query(query).await
     }
 
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+        self.inner.estimate_count_of_type(otype).await
+    }
+
     async fn get_all_objects_of_type(
         &self,
         otype: TaoType,
@@ -1068,6 +2624,16 @@ impl TaoOperations for CacheDecorator {
     ) -> AppResult<Vec<TaoObject>> {
         self.inner.get_all_objects_of_type(otype, limit).await
     }
+
+    async fn get_objects_in_range(
+        &self,
+        otype: TaoType,
+        start: TaoTime,
+        end: TaoTime,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<TaoObject>> {
+        self.inner.get_objects_in_range(otype, start, end, limit).await
+    }
 }
 
 #[async_trait]
@@ -1075,6 +2641,10 @@ impl TaoDecorator for CacheDecorator {
     fn decorator_name(&self) -> &'static str {
         "CacheDecorator"
     }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
 }
 
 /// Circuit Breaker Decorator - Adds fault tolerance
@@ -1119,6 +2689,560 @@ impl TaoDecorator for CircuitBreakerDecorator {
     fn decorator_name(&self) -> &'static str {
         "CircuitBreakerDecorator"
     }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
+}
+
+/// Limits for `ConcurrencyLimitDecorator`
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimitConfig {
+    pub max_concurrent_reads: usize,
+    pub max_concurrent_writes: usize,
+    /// How long a call waits for a permit once the limit is reached; zero rejects straight away
+    pub queue_timeout: Duration,
+}
+
+impl Default for ConcurrencyLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_reads: 256,
+            max_concurrent_writes: 64,
+            queue_timeout: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Concurrency Limit Decorator - Caps in-flight operations so a load spike queues briefly
+/// and is then shed with `ServiceUnavailable`, instead of exhausting database connections.
+/// A permit is held for the duration of one call; for `begin_transaction` that covers
+/// opening the transaction, not its lifetime.
+#[derive(Debug)]
+pub struct ConcurrencyLimitDecorator {
+    inner: Arc<dyn TaoDecorator>,
+    read_permits: Semaphore,
+    write_permits: Semaphore,
+    queue_timeout: Duration,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl ConcurrencyLimitDecorator {
+    pub fn new(inner: Arc<dyn TaoDecorator>, config: ConcurrencyLimitConfig) -> Self {
+        Self {
+            inner,
+            read_permits: Semaphore::new(config.max_concurrent_reads),
+            write_permits: Semaphore::new(config.max_concurrent_writes),
+            queue_timeout: config.queue_timeout,
+            metrics: None,
+        }
+    }
+
+    /// Count queued and rejected calls in the given collector
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Permits currently free, as (reads, writes)
+    pub fn available_permits(&self) -> (usize, usize) {
+        (
+            self.read_permits.available_permits(),
+            self.write_permits.available_permits(),
+        )
+    }
+
+    async fn execute_read<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        self.execute_with_limit(&self.read_permits, "read", operation).await
+    }
+
+    async fn execute_write<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        self.execute_with_limit(&self.write_permits, "write", operation).await
+    }
+
+    async fn execute_with_limit<F, T>(
+        &self,
+        permits: &Semaphore,
+        kind: &str,
+        operation: F,
+    ) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        let _permit = match permits.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let queued = if self.queue_timeout.is_zero() {
+                    None
+                } else {
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_concurrency_queued().await;
+                    }
+                    tokio::time::timeout(self.queue_timeout, permits.acquire())
+                        .await
+                        .ok()
+                        .and_then(Result::ok)
+                };
+                match queued {
+                    Some(permit) => permit,
+                    None => {
+                        if let Some(metrics) = &self.metrics {
+                            metrics.record_concurrency_rejected().await;
+                        }
+                        debug!("Rejected TAO {}: concurrency limit reached", kind);
+                        return Err(AppError::ServiceUnavailable(format!(
+                            "Too many concurrent TAO {}s, try again later",
+                            kind
+                        )));
+                    }
+                }
+            }
+        };
+        operation.await
+    }
+}
+
+// Use macro for ConcurrencyLimitDecorator - wraps every operation in a read or write permit
+impl_tao_operations_read_write_split!(ConcurrencyLimitDecorator, inner);
+
+#[async_trait]
+impl TaoDecorator for ConcurrencyLimitDecorator {
+    fn decorator_name(&self) -> &'static str {
+        "ConcurrencyLimitDecorator"
+    }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
+}
+
+/// Switch that freezes writes while keeping reads live, e.g. during migrations.
+/// Shared between the `MaintenanceDecorator` and whatever toggles it.
+#[derive(Debug, Default)]
+pub struct MaintenanceMode {
+    enabled: AtomicBool,
+}
+
+impl MaintenanceMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+    }
+}
+
+/// Maintenance Decorator - Rejects every write with `ServiceUnavailable` while maintenance
+/// mode is on, leaving reads untouched. Uses the same read/write split as the concurrency
+/// limit, so raw queries and transactions count as writes.
+#[derive(Debug)]
+pub struct MaintenanceDecorator {
+    inner: Arc<dyn TaoDecorator>,
+    mode: Arc<MaintenanceMode>,
+}
+
+impl MaintenanceDecorator {
+    pub fn new(inner: Arc<dyn TaoDecorator>, mode: Arc<MaintenanceMode>) -> Self {
+        Self { inner, mode }
+    }
+
+    async fn execute_read<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        operation.await
+    }
+
+    async fn execute_write<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        if self.mode.is_enabled() {
+            return Err(AppError::ServiceUnavailable(
+                "TAO is in maintenance mode, writes are disabled".to_string(),
+            ));
+        }
+        operation.await
+    }
+}
+
+impl_tao_operations_read_write_split!(MaintenanceDecorator, inner);
+
+#[async_trait]
+impl TaoDecorator for MaintenanceDecorator {
+    fn decorator_name(&self) -> &'static str {
+        "MaintenanceDecorator"
+    }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
+}
+
+/// Deadline Decorator - Enforces the deadline of the calling task's `RequestContext`.
+/// A read still running at the deadline is dropped, which releases its connection, and
+/// fails with `TimeoutError`. A write is rejected if the deadline has already passed, but
+/// once started it runs to completion: most writes are several statements outside one
+/// transaction, and cutting one off halfway would leave it partially applied.
+#[derive(Debug)]
+pub struct DeadlineDecorator {
+    inner: Arc<dyn TaoDecorator>,
+}
+
+impl DeadlineDecorator {
+    pub fn new(inner: Arc<dyn TaoDecorator>) -> Self {
+        Self { inner }
+    }
+
+    async fn execute_read<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        match current_request_context().remaining() {
+            Some(remaining) => tokio::time::timeout(remaining, operation)
+                .await
+                .map_err(|_| {
+                    AppError::TimeoutError("TAO read exceeded the request deadline".to_string())
+                })?,
+            None => operation.await,
+        }
+    }
+
+    async fn execute_write<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: std::future::Future<Output = AppResult<T>>,
+    {
+        if current_request_context().remaining() == Some(Duration::ZERO) {
+            return Err(AppError::TimeoutError(
+                "Request deadline passed before the TAO write started".to_string(),
+            ));
+        }
+        operation.await
+    }
+}
+
+impl_tao_operations_read_write_split!(DeadlineDecorator, inner);
+
+#[async_trait]
+impl TaoDecorator for DeadlineDecorator {
+    fn decorator_name(&self) -> &'static str {
+        "DeadlineDecorator"
+    }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
+}
+
+/// Change Feed Decorator - Publishes change events after successful writes
+#[derive(Debug)]
+pub struct ChangeFeedDecorator {
+    inner: Arc<dyn TaoDecorator>,
+    feed: Arc<dyn ChangeFeed>,
+}
+
+impl ChangeFeedDecorator {
+    pub fn new(inner: Arc<dyn TaoDecorator>, feed: Arc<dyn ChangeFeed>) -> Self {
+        Self { inner, feed }
+    }
+
+    /// The write already succeeded, so a feed failure is logged rather than returned
+    async fn publish(&self, event: ChangeEvent) {
+        if let Err(e) = self.feed.publish(event).await {
+            warn!("Failed to publish change event: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl TaoOperations for ChangeFeedDecorator {
+    async fn generate_id(&self, owner_id: Option<TaoId>) -> AppResult<TaoId> {
+        self.inner.generate_id(owner_id).await
+    }
+
+    async fn create_object(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<()> {
+        self.inner.create_object(id, otype.clone(), data).await?;
+        self.publish(ChangeEvent::ObjectCreated { id, otype }).await;
+        Ok(())
+    }
+
+    async fn obj_get(&self, id: TaoId) -> AppResult<Option<TaoObject>> {
+        self.inner.obj_get(id).await
+    }
+
+    async fn obj_update(&self, id: TaoId, data: Vec<u8>) -> AppResult<()> {
+        self.inner.obj_update(id, data).await?;
+        self.publish(ChangeEvent::ObjectUpdated { id }).await;
+        Ok(())
+    }
+
+    async fn obj_delete(&self, id: TaoId) -> AppResult<bool> {
+        let deleted = self.inner.obj_delete(id).await?;
+        if deleted {
+            self.publish(ChangeEvent::ObjectDeleted { id }).await;
+        }
+        Ok(deleted)
+    }
+
+    async fn obj_exists(&self, id: TaoId) -> AppResult<bool> {
+        self.inner.obj_exists(id).await
+    }
+
+    async fn obj_exists_by_type(&self, id: TaoId, otype: TaoType) -> AppResult<bool> {
+        self.inner.obj_exists_by_type(id, otype).await
+    }
+
+    async fn obj_update_by_type(&self, id: TaoId, otype: TaoType, data: Vec<u8>) -> AppResult<bool> {
+        let updated = self.inner.obj_update_by_type(id, otype, data).await?;
+        if updated {
+            self.publish(ChangeEvent::ObjectUpdated { id }).await;
+        }
+        Ok(updated)
+    }
+
+    async fn obj_delete_by_type(&self, id: TaoId, otype: TaoType) -> AppResult<bool> {
+        let deleted = self.inner.obj_delete_by_type(id, otype).await?;
+        if deleted {
+            self.publish(ChangeEvent::ObjectDeleted { id }).await;
+        }
+        Ok(deleted)
+    }
+
+    async fn obj_get_by_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<Option<TaoObject>> {
+        self.inner.obj_get_by_unique(otype, field, value).await
+    }
+
+    async fn generate_id_for_unique(&self, otype: TaoType, field: String, value: String) -> AppResult<TaoId> {
+        self.inner.generate_id_for_unique(otype, field, value).await
+    }
+
+    async fn obj_create_unique(&self, id: TaoId, otype: TaoType, data: Vec<u8>, field: String, value: String) -> AppResult<Option<TaoId>> {
+        let holder = self.inner.obj_create_unique(id, otype.clone(), data, field, value).await?;
+        if holder.is_none() {
+            self.publish(ChangeEvent::ObjectCreated { id, otype }).await;
+        }
+        Ok(holder)
+    }
+
+    async fn write_batch(&self, batch: TaoWriteBatch) -> AppResult<()> {
+        self.inner.write_batch(batch.clone()).await?;
+        for (id, otype, _) in batch.objects {
+            self.publish(ChangeEvent::ObjectCreated { id, otype }).await;
+        }
+        for assoc in batch.associations {
+            self.publish(ChangeEvent::AssocAdded { id1: assoc.id1, atype: assoc.atype, id2: assoc.id2, time: assoc.time }).await;
+        }
+        Ok(())
+    }
+
+    async fn get_object_history(&self, id: TaoId, limit: u32) -> AppResult<Vec<TaoObjectVersion>> {
+        self.inner.get_object_history(id, limit).await
+    }
+
+    async fn obj_rollback(&self, id: TaoId, to_version: u64) -> AppResult<Vec<u8>> {
+        let data = self.inner.obj_rollback(id, to_version).await?;
+        self.publish(ChangeEvent::ObjectUpdated { id }).await;
+        Ok(data)
+    }
+
+    async fn purge_object(&self, id: TaoId) -> AppResult<PurgeReport> {
+        let report = self.inner.purge_object(id).await?;
+        for (atype, id2) in &report.associations_removed {
+            self.publish(ChangeEvent::AssocDeleted {
+                id1: id,
+                atype: atype.clone(),
+                id2: *id2,
+            })
+            .await;
+        }
+        for (id1, atype, id2) in &report.inverse_removed {
+            self.publish(ChangeEvent::AssocDeleted {
+                id1: *id1,
+                atype: atype.clone(),
+                id2: *id2,
+            })
+            .await;
+        }
+        if report.object_deleted {
+            self.publish(ChangeEvent::ObjectDeleted { id }).await;
+        }
+        Ok(report)
+    }
+
+    async fn set_attribute(&self, id: TaoId, key: String, value: Vec<u8>) -> AppResult<()> {
+        self.inner.set_attribute(id, key, value).await
+    }
+
+    async fn get_attribute(&self, id: TaoId, key: String) -> AppResult<Option<Vec<u8>>> {
+        self.inner.get_attribute(id, key).await
+    }
+
+    async fn get_attributes(&self, id: TaoId) -> AppResult<HashMap<String, Vec<u8>>> {
+        self.inner.get_attributes(id).await
+    }
+
+    async fn assoc_get(&self, query: TaoAssocQuery) -> AppResult<Vec<TaoAssociation>> {
+        self.inner.assoc_get(query).await
+    }
+
+    async fn assoc_get_page(&self, query: TaoAssocQuery) -> AppResult<TaoAssocQueryResult> {
+        self.inner.assoc_get_page(query).await
+    }
+
+    async fn assoc_get_multi_type(&self, id1: TaoId, atypes: Vec<AssocType>, limit_per_type: Option<u32>) -> AppResult<HashMap<AssocType, Vec<TaoAssociation>>> {
+        self.inner.assoc_get_multi_type(id1, atypes, limit_per_type).await
+    }
+
+    async fn assoc_add(&self, assoc: TaoAssociation) -> AppResult<()> {
+        let event = ChangeEvent::AssocAdded { id1: assoc.id1, atype: assoc.atype.clone(), id2: assoc.id2, time: assoc.time };
+        self.inner.assoc_add(assoc).await?;
+        self.publish(event).await;
+        Ok(())
+    }
+
+    async fn assoc_delete(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+        let deleted = self.inner.assoc_delete(id1, atype.clone(), id2).await?;
+        if deleted {
+            self.publish(ChangeEvent::AssocDeleted { id1, atype, id2 }).await;
+        }
+        Ok(deleted)
+    }
+
+    async fn assoc_delete_all(&self, id1: TaoId, atype: AssocType) -> AppResult<AssocDeleteAllReport> {
+        let deleted = self.inner.assoc_delete_all(id1, atype.clone()).await?;
+        if !deleted.removed.is_empty() {
+            let count = deleted.removed.len() as u64;
+            self.publish(ChangeEvent::AssocsCleared { id1, atype, count }).await;
+        }
+        Ok(deleted)
+    }
+
+    async fn assoc_touch(&self, id1: TaoId, atype: AssocType, id2: TaoId, new_time: Option<TaoTime>) -> AppResult<bool> {
+        // Not published: the edge already exists, only its position in recents changes
+        self.inner.assoc_touch(id1, atype, id2, new_time).await
+    }
+
+    async fn assoc_move(&self, id1: TaoId, atype: AssocType, old_id2: TaoId, new_id2: TaoId) -> AppResult<AssocMoveReport> {
+        let moved = self.inner.assoc_move(id1, atype.clone(), old_id2, new_id2).await?;
+        if moved.moved && old_id2 != new_id2 {
+            self.publish(ChangeEvent::AssocMoved { id1, atype, old_id2, new_id2 }).await;
+        }
+        Ok(moved)
+    }
+
+    async fn assoc_delete_between(&self, id1: TaoId, id2: TaoId, atypes: Option<Vec<AssocType>>) -> AppResult<HashMap<AssocType, u64>> {
+        let removed = self.inner.assoc_delete_between(id1, id2, atypes).await?;
+        if !removed.is_empty() {
+            self.publish(ChangeEvent::AssocsDeletedBetween { id1, id2, removed: removed.clone() }).await;
+        }
+        Ok(removed)
+    }
+
+    async fn assoc_count(&self, id1: TaoId, atype: AssocType) -> AppResult<u64> {
+        self.inner.assoc_count(id1, atype).await
+    }
+
+    async fn assoc_count_many(&self, pairs: Vec<(TaoId, AssocType)>) -> AppResult<HashMap<(TaoId, AssocType), u64>> {
+        self.inner.assoc_count_many(pairs).await
+    }
+
+    async fn assoc_count_by_subtype(&self, id1: TaoId, atype: AssocType) -> AppResult<HashMap<Option<String>, u64>> {
+        self.inner.assoc_count_by_subtype(id1, atype).await
+    }
+
+    async fn list_assoc_types(&self, id: TaoId) -> AppResult<Vec<(AssocType, u64)>> {
+        self.inner.list_assoc_types(id).await
+    }
+
+    async fn assoc_range(&self, id1: TaoId, atype: AssocType, offset: u64, limit: u32) -> AppResult<Vec<TaoAssociation>> {
+        self.inner.assoc_range(id1, atype, offset, limit).await
+    }
+
+    async fn assoc_time_range(&self, id1: TaoId, atype: AssocType, high_time: i64, low_time: i64, limit: Option<u32>) -> AppResult<Vec<TaoAssociation>> {
+        self.inner.assoc_time_range(id1, atype, high_time, low_time, limit).await
+    }
+
+    async fn assoc_since(&self, id1: TaoId, atype: AssocType, since_time: TaoTime, limit: u32) -> AppResult<(Vec<TaoAssociation>, TaoTime)> {
+        self.inner.assoc_since(id1, atype, since_time, limit).await
+    }
+
+    async fn assoc_delta_since(&self, id1: TaoId, atype: AssocType, since_seq: u64) -> AppResult<(Vec<TaoId>, Vec<TaoId>, u64)> {
+        self.inner.assoc_delta_since(id1, atype, since_seq).await
+    }
+
+    async fn assoc_exists(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<bool> {
+        self.inner.assoc_exists(id1, atype, id2).await
+    }
+
+    async fn assoc_get_one(&self, id1: TaoId, atype: AssocType, id2: TaoId) -> AppResult<Option<TaoAssociation>> {
+        self.inner.assoc_get_one(id1, atype, id2).await
+    }
+
+    async fn edges_between(&self, id1: TaoId, id2: TaoId) -> AppResult<Vec<TaoAssociation>> {
+        self.inner.edges_between(id1, id2).await
+    }
+
+    async fn get_by_id_and_type(&self, ids: Vec<TaoId>, otype: TaoType) -> AppResult<Vec<TaoObject>> {
+        self.inner.get_by_id_and_type(ids, otype).await
+    }
+
+    async fn get_neighbors(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+        self.inner.get_neighbors(id, atype, limit).await
+    }
+
+    async fn get_neighbors_with_edges(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<(TaoObject, TaoAssociation)>> {
+        self.inner.get_neighbors_with_edges(id, atype, limit).await
+    }
+
+    async fn get_neighbor_ids(&self, id: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+        self.inner.get_neighbor_ids(id, atype, limit).await
+    }
+
+    async fn get_in_neighbor_ids(&self, id2: TaoId, atype: AssocType, limit: Option<u32>) -> AppResult<Vec<TaoId>> {
+        self.inner.get_in_neighbor_ids(id2, atype, limit).await
+    }
+
+    async fn estimate_count_of_type(&self, otype: TaoType) -> AppResult<u64> {
+        self.inner.estimate_count_of_type(otype).await
+    }
+
+    async fn get_all_objects_of_type(&self, otype: TaoType, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+        self.inner.get_all_objects_of_type(otype, limit).await
+    }
+
+    async fn get_objects_in_range(&self, otype: TaoType, start: TaoTime, end: TaoTime, limit: Option<u32>) -> AppResult<Vec<TaoObject>> {
+        self.inner.get_objects_in_range(otype, start, end, limit).await
+    }
+
+    async fn begin_transaction(&self) -> AppResult<DatabaseTransaction> {
+        self.inner.begin_transaction().await
+    }
+
+    async fn execute_query(&self, query: String) -> AppResult<Vec<HashMap<String, String>>> {
+        self.inner.execute_query(query).await
+    }
+}
+
+#[async_trait]
+impl TaoDecorator for ChangeFeedDecorator {
+    fn decorator_name(&self) -> &'static str {
+        "ChangeFeedDecorator"
+    }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
 }
 
 /// Circuit breaker implementation for fault tolerance
@@ -1203,3 +3327,664 @@ impl CircuitBreaker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::association_registry::AssociationRegistry;
+    use crate::infrastructure::cache::cache_layer::CacheConfig;
+    use crate::infrastructure::cache::stale_read::track_stale_reads;
+    use crate::infrastructure::database::sqlite_database::SqliteDatabase;
+    use crate::infrastructure::id_generator::TaoIdGenerator;
+    use crate::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
+    use crate::infrastructure::shard_topology::{ShardHealth, ShardInfo};
+    use crate::infrastructure::tao_core::tao_core::{
+        create_tao_association, current_time_millis, TaoCore,
+    };
+
+    async fn sqlite_base_tao() -> Arc<dyn TaoDecorator> {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let shard_info = ShardInfo {
+            shard_id: 0,
+            connection_string: "sqlite::memory:".to_string(),
+            region: "local".to_string(),
+            health: ShardHealth::Healthy,
+            replicas: vec![],
+            last_health_check: current_time_millis(),
+            load_factor: 0.0,
+        };
+        let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+        query_router.add_shard(shard_info, database).await.unwrap();
+
+        let tao_core = Arc::new(TaoCore::new(
+            query_router,
+            Arc::new(AssociationRegistry::new()),
+        ));
+        Arc::new(BaseTao::new(tao_core))
+    }
+
+    #[tokio::test]
+    async fn test_serves_stale_cache_when_breaker_open() {
+        let base_tao = sqlite_base_tao().await;
+        let breaker = Arc::new(CircuitBreakerDecorator::new(
+            base_tao,
+            1,
+            Duration::from_secs(60),
+            true,
+        ));
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig {
+            l1_default_ttl: Duration::from_millis(1),
+            serve_stale_on_unavailable: true,
+            ..CacheConfig::default()
+        }));
+        let tao = CacheDecorator::new(breaker, cache, true);
+
+        let id = TaoIdGenerator::new(0).next_id();
+        tao.create_object(id, "user".to_string(), vec![1, 2, 3]).await.unwrap();
+
+        // Populate the cache, then let the entry expire
+        assert!(tao.obj_get(id).await.unwrap().is_some());
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        // A read routed to a shard with no database trips the breaker
+        let unroutable_id = TaoIdGenerator::new(7).next_id();
+        assert!(tao.obj_get(unroutable_id).await.is_err());
+
+        let (result, stale) = track_stale_reads(tao.obj_get(id)).await;
+        let object = result.unwrap().expect("stale object should be served");
+        assert_eq!(object.data, vec![1, 2, 3]);
+        assert!(stale);
+
+        // Without anything cached the breaker error still surfaces
+        let uncached_id = TaoIdGenerator::new(0).next_id();
+        let (result, _) = track_stale_reads(tao.obj_get(uncached_id)).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stale_mode_is_opt_in() {
+        let base_tao = sqlite_base_tao().await;
+        let breaker = Arc::new(CircuitBreakerDecorator::new(
+            base_tao,
+            1,
+            Duration::from_secs(60),
+            true,
+        ));
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig {
+            l1_default_ttl: Duration::from_millis(1),
+            ..CacheConfig::default()
+        }));
+        let tao = CacheDecorator::new(breaker, cache, true);
+
+        let id = TaoIdGenerator::new(0).next_id();
+        tao.create_object(id, "user".to_string(), vec![1]).await.unwrap();
+        assert!(tao.obj_get(id).await.unwrap().is_some());
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let unroutable_id = TaoIdGenerator::new(7).next_id();
+        assert!(tao.obj_get(unroutable_id).await.is_err());
+
+        let (result, stale) = track_stale_reads(tao.obj_get(id)).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+        assert!(!stale);
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_publishes_writes_in_order() {
+        use crate::infrastructure::change_feed::feed::BroadcastChangeFeed;
+        use futures::StreamExt;
+
+        let feed = Arc::new(BroadcastChangeFeed::default());
+        let events = feed.stream();
+        let tao = ChangeFeedDecorator::new(sqlite_base_tao().await, feed.clone());
+
+        let ids = TaoIdGenerator::new(0);
+        let (user, post) = (ids.next_id(), ids.next_id());
+        tao.create_object(user, "user".to_string(), vec![1]).await.unwrap();
+        tao.obj_update(user, vec![2]).await.unwrap();
+        let assoc = TaoAssociation {
+            id1: user,
+            atype: "authored".to_string(),
+            id2: post,
+            time: 1_000,
+            data: None,
+            subtype: None,
+        };
+        tao.assoc_add(assoc).await.unwrap();
+        assert!(tao.assoc_delete(user, "authored".to_string(), post).await.unwrap());
+        assert!(tao.obj_delete(user).await.unwrap());
+
+        // Writes that don't change anything publish nothing
+        assert!(!tao.obj_delete(user).await.unwrap());
+        // Reads publish nothing either
+        tao.obj_get(user).await.unwrap();
+
+        let received: Vec<ChangeEvent> = events.take(5).collect().await;
+        assert_eq!(
+            received,
+            vec![
+                ChangeEvent::ObjectCreated { id: user, otype: "user".to_string() },
+                ChangeEvent::ObjectUpdated { id: user },
+                ChangeEvent::AssocAdded { id1: user, atype: "authored".to_string(), id2: post, time: 1_000 },
+                ChangeEvent::AssocDeleted { id1: user, atype: "authored".to_string(), id2: post },
+                ChangeEvent::ObjectDeleted { id: user },
+            ]
+        );
+
+        // Nothing else is queued
+        let mut receiver = feed.subscribe();
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_queues_then_rejects() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let limiter = ConcurrencyLimitDecorator::new(
+            sqlite_base_tao().await,
+            ConcurrencyLimitConfig {
+                max_concurrent_reads: 2,
+                max_concurrent_writes: 1,
+                queue_timeout: Duration::from_millis(50),
+            },
+        )
+        .with_metrics(metrics.clone());
+        let ids = TaoIdGenerator::new(0);
+
+        // Saturate the write pool: the next write waits out the queue timeout, then is shed
+        let held = limiter.write_permits.acquire().await.unwrap();
+        let result = limiter
+            .create_object(ids.next_id(), "user".to_string(), vec![1])
+            .await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+
+        // Reads draw on their own pool and are unaffected
+        assert!(limiter.obj_get(ids.next_id()).await.unwrap().is_none());
+        assert_eq!(limiter.available_permits(), (2, 0));
+
+        // A write queued behind a permit that frees up in time goes through
+        let id = ids.next_id();
+        let (created, _) = tokio::join!(
+            limiter.create_object(id, "user".to_string(), vec![1]),
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                drop(held);
+            }
+        );
+        created.unwrap();
+        assert!(limiter.obj_exists(id).await.unwrap());
+        assert_eq!(limiter.available_permits(), (2, 1));
+
+        let snapshot = metrics.get_metrics_snapshot().await;
+        assert_eq!(snapshot.database_metrics.concurrency_queued, 2);
+        assert_eq!(snapshot.database_metrics.concurrency_rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_read_past_request_deadline_times_out_promptly() {
+        use crate::infrastructure::tao_core::request_context::{
+            with_request_context, RequestContext,
+        };
+
+        // Reads queue behind a held permit for far longer than the deadline allows
+        let limiter = Arc::new(ConcurrencyLimitDecorator::new(
+            sqlite_base_tao().await,
+            ConcurrencyLimitConfig {
+                max_concurrent_reads: 1,
+                max_concurrent_writes: 1,
+                queue_timeout: Duration::from_secs(30),
+            },
+        ));
+        let tao = DeadlineDecorator::new(limiter.clone());
+        let id = TaoIdGenerator::new(0).next_id();
+        let _held = limiter.read_permits.acquire().await.unwrap();
+
+        let started = Instant::now();
+        let context = RequestContext::with_timeout(Duration::from_millis(50));
+        let result = with_request_context(context.clone(), tao.obj_get(id)).await;
+        assert!(
+            matches!(result, Err(AppError::TimeoutError(_))),
+            "{:?}",
+            result
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Writes are only turned away once the deadline has passed
+        let late =
+            with_request_context(context, tao.create_object(id, "user".to_string(), vec![1])).await;
+        assert!(matches!(late, Err(AppError::TimeoutError(_))));
+        let fresh = RequestContext::with_timeout(Duration::from_secs(5));
+        with_request_context(fresh, tao.create_object(id, "user".to_string(), vec![1]))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_assoc_count_many_matches_individual_counts() {
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let tao = CacheDecorator::new(sqlite_base_tao().await, cache, true);
+        let ids = TaoIdGenerator::new(0);
+        let (post_a, post_b, post_c) = (ids.next_id(), ids.next_id(), ids.next_id());
+        let like = |id1: TaoId, id2: TaoId| TaoAssociation {
+            id1,
+            atype: "liked_by".to_string(),
+            id2,
+            time: 1_000,
+            data: None,
+            subtype: None,
+        };
+        for (post, user) in [(post_a, 1), (post_a, 2), (post_a, 3), (post_b, 1)] {
+            tao.assoc_add(like(post, user)).await.unwrap();
+        }
+
+        let pairs: Vec<(TaoId, AssocType)> = [post_a, post_b, post_c]
+            .into_iter()
+            .map(|post| (post, "liked_by".to_string()))
+            .chain([(post_a, "commented_by".to_string())])
+            .collect();
+        let counts = tao.assoc_count_many(pairs.clone()).await.unwrap();
+        assert_eq!(counts.len(), pairs.len());
+        for (id1, atype) in &pairs {
+            let single = tao.assoc_count(*id1, atype.clone()).await.unwrap();
+            assert_eq!(counts[&(*id1, atype.clone())], single);
+        }
+        assert_eq!(counts[&(post_a, "liked_by".to_string())], 3);
+        assert_eq!(counts[&(post_c, "liked_by".to_string())], 0);
+        assert_eq!(counts[&(post_a, "commented_by".to_string())], 0);
+
+        // Cached counts are dropped when an edge on the object changes
+        tao.assoc_add(like(post_b, 2)).await.unwrap();
+        let counts = tao.assoc_count_many(pairs).await.unwrap();
+        assert_eq!(counts[&(post_b, "liked_by".to_string())], 2);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_delete_all_drops_cached_inverse_lists_and_counts() {
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let tao = CacheDecorator::new(sqlite_base_tao().await, cache, true);
+        let ids = TaoIdGenerator::new(0);
+        let (post, fan) = (ids.next_id(), ids.next_id());
+        let liked_by = create_tao_association(post, "liked_by".to_string(), fan, None);
+        tao.assoc_add(liked_by).await.unwrap();
+        tao.assoc_add(create_tao_association(fan, "likes".to_string(), post, None))
+            .await
+            .unwrap();
+        let likes = TaoAssocQuery {
+            id1: fan,
+            atype: "likes".to_string(),
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: None,
+            offset: None,
+            after: None,
+            include_total: false,
+        };
+        let fan_likes = (fan, "likes".to_string());
+        // Warm the fan's cached list and counts
+        assert_eq!(tao.assoc_get(likes.clone()).await.unwrap().len(), 1);
+        let counts = tao.assoc_count_many(vec![fan_likes.clone()]).await.unwrap();
+        assert_eq!(counts[&fan_likes], 1);
+
+        tao.assoc_delete_all(post, "liked_by".to_string())
+            .await
+            .unwrap();
+        assert!(tao.assoc_get(likes).await.unwrap().is_empty());
+        let counts = tao.assoc_count_many(vec![fan_likes.clone()]).await.unwrap();
+        assert_eq!(counts[&fan_likes], 0);
+    }
+
+    #[tokio::test]
+    async fn test_assoc_move_drops_cached_inverse_lists_of_both_targets() {
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let tao = CacheDecorator::new(sqlite_base_tao().await, cache, true);
+        let ids = TaoIdGenerator::new(0);
+        let (post, old_fan, new_fan) = (ids.next_id(), ids.next_id(), ids.next_id());
+        let liked_by = create_tao_association(post, "liked_by".to_string(), old_fan, None);
+        tao.assoc_add(liked_by).await.unwrap();
+        let likes = create_tao_association(old_fan, "likes".to_string(), post, None);
+        tao.assoc_add(likes).await.unwrap();
+        let old_likes = (old_fan, "likes".to_string());
+        let new_likes = (new_fan, "likes".to_string());
+        // Warm both fans' cached counts
+        let pairs = vec![old_likes.clone(), new_likes.clone()];
+        let counts = tao.assoc_count_many(pairs.clone()).await.unwrap();
+        assert_eq!((counts[&old_likes], counts[&new_likes]), (1, 0));
+
+        tao.assoc_move(post, "liked_by".to_string(), old_fan, new_fan)
+            .await
+            .unwrap();
+        let counts = tao.assoc_count_many(pairs).await.unwrap();
+        assert_eq!((counts[&old_likes], counts[&new_likes]), (0, 1));
+        let new_fan_likes = tao
+            .assoc_range(new_fan, "likes".to_string(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(new_fan_likes.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_access_tracker_reports_repeatedly_read_objects_as_hot() {
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let tracker = Arc::new(AccessTracker::new(1.0));
+        let tao = CacheDecorator::new(sqlite_base_tao().await, cache, true)
+            .with_access_tracker(tracker.clone());
+        let ids = TaoIdGenerator::new(0);
+        let objects: Vec<TaoId> = (0..5).map(|_| ids.next_id()).collect();
+        for id in &objects {
+            tao.create_object(*id, "ent_user".to_string(), vec![1])
+                .await
+                .unwrap();
+        }
+
+        // Two hot objects, read from the cache after the first miss, and three cold ones
+        for _ in 0..50 {
+            tao.obj_get(objects[0]).await.unwrap();
+            tao.obj_get(objects[1]).await.unwrap();
+        }
+        for id in &objects[2..] {
+            tao.obj_get(*id).await.unwrap();
+        }
+
+        let hot = tracker.hot_objects(2);
+        let hot_ids: HashSet<TaoId> = hot.iter().map(|object| object.id).collect();
+        assert_eq!(hot_ids, HashSet::from([objects[0], objects[1]]));
+        assert!(hot.iter().all(|object| object.sampled_reads >= 50));
+        assert_eq!(tracker.hot_objects(10).len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_read_ignores_poisoned_cache() {
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let tao = CacheDecorator::new(sqlite_base_tao().await, cache.clone(), true);
+        let id = TaoIdGenerator::new(0).next_id();
+        tao.create_object(id, "user".to_string(), vec![1]).await.unwrap();
+        let mut poisoned = tao.obj_get(id).await.unwrap().unwrap();
+        poisoned.data = vec![0xBA, 0xD0];
+        cache.put_object(id, &poisoned).await.unwrap();
+
+        assert_eq!(tao.obj_get(id).await.unwrap().unwrap().data, vec![0xBA, 0xD0]);
+        assert_eq!(tao.obj_get_fresh(id).await.unwrap().unwrap().data, vec![1]);
+        // The fresh read repopulated the cache
+        assert_eq!(tao.obj_get(id).await.unwrap().unwrap().data, vec![1]);
+
+        let edge = TaoAssociation {
+            id1: id,
+            atype: "friends".to_string(),
+            id2: 42,
+            time: 1_000,
+            data: None,
+            subtype: None,
+        };
+        tao.assoc_add(edge.clone()).await.unwrap();
+        cache
+            .put_associations(id, "friends", &[TaoAssociation { id2: 666, ..edge }])
+            .await
+            .unwrap();
+        let query = TaoAssocQuery {
+            id1: id,
+            atype: "friends".to_string(),
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: None,
+            offset: None,
+            after: None,
+            include_total: false,
+        };
+        let fresh = tao.assoc_get_fresh(query).await.unwrap();
+        assert_eq!(fresh.iter().map(|a| a.id2).collect::<Vec<_>>(), vec![42]);
+    }
+
+    #[tokio::test]
+    async fn test_never_cached_otype_always_reads_inner_store() {
+        let schemas = crate::schemas::create_schema_registry();
+        let policies = CachePolicies::from_schema_registry(&schemas);
+        assert_eq!(policies.policy_for("ent_event"), CachePolicy::Never);
+        assert_eq!(policies.policy_for("ent_user"), CachePolicy::Default);
+
+        let base = sqlite_base_tao().await;
+        let cache = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let tao = CacheDecorator::new(base.clone(), cache.clone(), true)
+            .with_cache_policies(Arc::new(policies));
+        let ids = TaoIdGenerator::new(0);
+        let (user, event) = (ids.next_id(), ids.next_id());
+        tao.create_object(user, "ent_user".to_string(), vec![1]).await.unwrap();
+        tao.create_object(event, "ent_event".to_string(), vec![1]).await.unwrap();
+        tao.obj_get(user).await.unwrap();
+        tao.obj_get(event).await.unwrap();
+
+        // Change both underneath the cache
+        base.obj_update(user, vec![2]).await.unwrap();
+        base.obj_update(event, vec![2]).await.unwrap();
+
+        assert_eq!(tao.obj_get(user).await.unwrap().unwrap().data, vec![1]);
+        assert_eq!(tao.obj_get(event).await.unwrap().unwrap().data, vec![2]);
+        assert!(cache.get_object(user).await.unwrap().is_some());
+        assert!(cache.get_object(event).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_warms_cache_for_following_reads() {
+        use crate::infrastructure::monitoring::monitoring::MetricsCollector;
+        use crate::infrastructure::tao_core::tao_core::PrefetchRequest;
+
+        // Metrics under the cache count the reads that get past it
+        let metrics = Arc::new(MetricsCollector::new());
+        let inner = Arc::new(MetricsDecorator::new(
+            sqlite_base_tao().await,
+            metrics.clone(),
+        ));
+        let tao = CacheDecorator::new(
+            inner,
+            Arc::new(TaoMultiTierCache::new(CacheConfig::default())),
+            true,
+        );
+        let inner_reads = || async {
+            metrics
+                .get_metrics_snapshot()
+                .await
+                .request_metrics
+                .total_requests
+        };
+
+        let ids = TaoIdGenerator::new(0);
+        let (author, post_author) = (ids.next_id(), ids.next_id());
+        let friends: Vec<TaoId> = (0..3).map(|_| ids.next_id()).collect();
+        for &id in friends.iter().chain([&author, &post_author]) {
+            tao.create_object(id, "ent_user".to_string(), vec![1])
+                .await
+                .unwrap();
+        }
+        for &friend in &friends {
+            tao.assoc_add(create_tao_association(
+                author,
+                "friends".to_string(),
+                friend,
+                None,
+            ))
+            .await
+            .unwrap();
+        }
+
+        tao.prefetch(vec![
+            PrefetchRequest::Neighbors {
+                id1: author,
+                atype: "friends".to_string(),
+                limit: None,
+            },
+            PrefetchRequest::Object(post_author),
+        ])
+        .await;
+        let after_prefetch = inner_reads().await;
+
+        let query = TaoAssocQuery {
+            id1: author,
+            atype: "friends".to_string(),
+            id2_set: None,
+            low_id2: None,
+            high_id2: None,
+            high_time: None,
+            low_time: None,
+            limit: None,
+            offset: None,
+            after: None,
+            include_total: false,
+        };
+        let edges = tao.assoc_get(query).await.unwrap();
+        assert_eq!(edges.len(), 3);
+        for id in edges.iter().map(|edge| edge.id2).chain([post_author]) {
+            assert!(tao.obj_get(id).await.unwrap().is_some());
+        }
+        assert_eq!(inner_reads().await, after_prefetch);
+    }
+
+    #[tokio::test]
+    async fn test_write_on_one_node_evicts_l1_on_another() {
+        use crate::infrastructure::cache::invalidation_bus::InProcessInvalidationBus;
+
+        let base = sqlite_base_tao().await;
+        let bus: Arc<dyn InvalidationBus> = Arc::new(InProcessInvalidationBus::new());
+        let cache_b = Arc::new(TaoMultiTierCache::new(CacheConfig::default()));
+        let node_a = CacheDecorator::new(
+            base.clone(),
+            Arc::new(TaoMultiTierCache::new(CacheConfig::default())),
+            true,
+        )
+        .with_invalidation_bus(bus.clone(), "node-a");
+        let node_b = CacheDecorator::new(base, cache_b.clone(), true)
+            .with_invalidation_bus(bus, "node-b");
+
+        let id = TaoIdGenerator::new(0).next_id();
+        node_a.create_object(id, "user".to_string(), vec![1]).await.unwrap();
+        // Node B caches the object in its L1
+        assert_eq!(node_b.obj_get(id).await.unwrap().unwrap().data, vec![1]);
+        assert!(cache_b.get_object(id).await.unwrap().is_some());
+
+        node_a.obj_update(id, vec![2]).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while cache_b.get_object(id).await.unwrap().is_some() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("node B never saw node A's invalidation");
+        assert_eq!(node_b.obj_get(id).await.unwrap().unwrap().data, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_purge_object_removes_inbound_and_outbound_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = Arc::new(
+            TaoWriteAheadLog::new(Default::default(), dir.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        );
+        let base = sqlite_base_tao().await;
+        let tao = WalDecorator::new(base.clone(), wal);
+
+        let ids = TaoIdGenerator::new(0);
+        let (post, other_post, alice, bob) =
+            (ids.next_id(), ids.next_id(), ids.next_id(), ids.next_id());
+        for id in [post, other_post, alice, bob] {
+            tao.create_object(id, "ent".to_string(), vec![]).await.unwrap();
+        }
+        let edges = [
+            (post, "liked_by", alice),
+            (post, "liked_by", bob),
+            (alice, "likes", post),
+            (bob, "likes", post),
+            (alice, "likes", other_post),
+            (other_post, "liked_by", alice),
+        ];
+        for (id1, atype, id2) in edges {
+            tao.assoc_add(create_tao_association(id1, atype.to_string(), id2, None))
+                .await
+                .unwrap();
+        }
+
+        let report = tao.purge_object(post).await.unwrap();
+        assert!(report.object_deleted);
+        assert_eq!(report.associations_removed.len(), 2);
+        assert_eq!(report.inverse_removed.len(), 2);
+        assert!(report.inverse_deferred.is_empty());
+
+        assert!(!tao.obj_exists(post).await.unwrap());
+        assert_eq!(
+            tao.assoc_count(post, "liked_by".to_string()).await.unwrap(),
+            0
+        );
+        assert!(!tao
+            .assoc_exists(bob, "likes".to_string(), post)
+            .await
+            .unwrap());
+        // Edges not involving the purged object survive
+        assert!(tao
+            .assoc_exists(alice, "likes".to_string(), other_post)
+            .await
+            .unwrap());
+        assert_eq!(tao.assoc_count(alice, "likes".to_string()).await.unwrap(), 1);
+
+        // Below the WAL the inverse edges are only reported
+        let report = base.purge_object(other_post).await.unwrap();
+        assert!(report.object_deleted);
+        assert!(report.inverse_removed.is_empty());
+        assert_eq!(
+            report.inverse_deferred,
+            vec![(alice, "likes".to_string(), other_post)]
+        );
+        assert!(base
+            .assoc_exists(alice, "likes".to_string(), other_post)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_best_effort_writes_skip_the_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = Arc::new(
+            TaoWriteAheadLog::new(Default::default(), dir.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        );
+        let mut durability = DurabilityPolicies::new();
+        durability.set_object("presence", Durability::BestEffort);
+        durability.set_association("viewed", Durability::BestEffort);
+        let tao = WalDecorator::new(sqlite_base_tao().await, wal.clone())
+            .with_durability(Arc::new(durability));
+
+        let ids = TaoIdGenerator::new(0);
+        let (presence, user, post) = (ids.next_id(), ids.next_id(), ids.next_id());
+        tao.create_object(presence, "presence".to_string(), vec![]).await.unwrap();
+        tao.assoc_add(create_tao_association(user, "viewed".to_string(), post, None))
+            .await
+            .unwrap();
+        assert_eq!(wal.get_stats().await.total_transactions, 0);
+        // The best-effort writes still reach the database
+        assert!(tao.obj_exists(presence).await.unwrap());
+        assert!(tao
+            .assoc_exists(user, "viewed".to_string(), post)
+            .await
+            .unwrap());
+
+        tao.create_object(user, "user".to_string(), vec![]).await.unwrap();
+        tao.assoc_add(create_tao_association(user, "likes".to_string(), post, None))
+            .await
+            .unwrap();
+        assert_eq!(wal.get_stats().await.total_transactions, 2);
+
+        // A batch of only best-effort writes is not logged either
+        let other_presence = ids.next_id();
+        let viewed = create_tao_association(user, "viewed".to_string(), presence, None);
+        let batch = TaoWriteBatch {
+            objects: vec![(other_presence, "presence".to_string(), vec![])],
+            associations: vec![viewed],
+        };
+        tao.write_batch(batch).await.unwrap();
+        assert_eq!(wal.get_stats().await.total_transactions, 2);
+        assert!(tao.obj_exists(other_presence).await.unwrap());
+    }
+}
@@ -0,0 +1,480 @@
+// TAO Stack Builder - assembles the decorator chain and rejects orderings that break it
+// Layers are added innermost first: each `with_*` call wraps everything added before it
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::{
+    cache::cache_layer::TaoMultiTierCache,
+    change_feed::feed::ChangeFeed,
+    monitoring::monitoring::MetricsCollector,
+    storage::write_ahead_log::TaoWriteAheadLog,
+    tao_core::tao_core::TaoOperations,
+    tao_core::tao_decorators::{
+        BaseTao, CacheDecorator, ChangeFeedDecorator, CircuitBreakerDecorator,
+        ConcurrencyLimitConfig, ConcurrencyLimitDecorator, DeadlineDecorator, MaintenanceDecorator,
+        MaintenanceMode, MetricsDecorator, TaoDecorator, WalDecorator,
+    },
+};
+
+/// Kinds of layer a stack can hold, used to check their relative order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    Wal,
+    Metrics,
+    Cache,
+    CircuitBreaker,
+    ConcurrencyLimit,
+    Maintenance,
+    ChangeFeed,
+    Deadline,
+    /// Added with `with_decorator`; any number are allowed, and no ordering rule applies
+    Custom,
+}
+
+/// Wraps the chain built so far in a user-provided decorator (see `with_decorator`)
+pub type DecoratorFactory = Box<dyn FnOnce(Arc<dyn TaoDecorator>) -> Arc<dyn TaoDecorator> + Send>;
+
+/// A built chain and its WAL layer, if it has one (see `build_with_wal`)
+pub type StackWithWal = (Arc<dyn TaoDecorator>, Option<Arc<WalDecorator>>);
+
+/// `(outer, inner, reason)`: when both are present, `outer` has to wrap `inner`
+const ORDERING_RULES: &[(LayerKind, LayerKind, &str)] = &[
+    (
+        LayerKind::CircuitBreaker,
+        LayerKind::Wal,
+        "a WAL above the circuit breaker logs writes the breaker then rejects, and replays them later",
+    ),
+    (
+        LayerKind::Maintenance,
+        LayerKind::Wal,
+        "writes frozen by maintenance mode must never reach the WAL",
+    ),
+    (
+        LayerKind::ConcurrencyLimit,
+        LayerKind::Cache,
+        "shed calls must be turned away before they reach the cache",
+    ),
+    (
+        LayerKind::ConcurrencyLimit,
+        LayerKind::Wal,
+        "shed calls must be turned away before they reach the WAL",
+    ),
+    (
+        LayerKind::ChangeFeed,
+        LayerKind::Wal,
+        "change events must only be published once the WAL has accepted the write",
+    ),
+    (
+        LayerKind::ChangeFeed,
+        LayerKind::CircuitBreaker,
+        "change events must only be published once the circuit breaker has let the write through",
+    ),
+    (
+        LayerKind::Deadline,
+        LayerKind::ConcurrencyLimit,
+        "time spent queued for a permit must count against the request deadline",
+    ),
+    (
+        LayerKind::Maintenance,
+        LayerKind::CircuitBreaker,
+        "writes refused by maintenance mode would count as failures and trip the breaker, \
+         blocking reads",
+    ),
+    (
+        LayerKind::ConcurrencyLimit,
+        LayerKind::CircuitBreaker,
+        "shed calls would count as failures and trip the breaker, blocking every call",
+    ),
+    (
+        LayerKind::Deadline,
+        LayerKind::CircuitBreaker,
+        "calls past their deadline would count as failures and trip the breaker",
+    ),
+];
+
+enum Layer {
+    Wal(Arc<TaoWriteAheadLog>),
+    Metrics(Arc<MetricsCollector>),
+    Cache {
+        cache: Arc<TaoMultiTierCache>,
+        enable_caching: bool,
+    },
+    CircuitBreaker {
+        failure_threshold: u32,
+        recovery_timeout: Duration,
+    },
+    ConcurrencyLimit {
+        config: ConcurrencyLimitConfig,
+        metrics: Option<Arc<MetricsCollector>>,
+    },
+    Maintenance(Arc<MaintenanceMode>),
+    ChangeFeed(Arc<dyn ChangeFeed>),
+    Deadline,
+    Custom(DecoratorFactory),
+}
+
+impl std::fmt::Debug for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.kind())
+    }
+}
+
+impl Layer {
+    fn kind(&self) -> LayerKind {
+        match self {
+            Layer::Wal(_) => LayerKind::Wal,
+            Layer::Metrics(_) => LayerKind::Metrics,
+            Layer::Cache { .. } => LayerKind::Cache,
+            Layer::CircuitBreaker { .. } => LayerKind::CircuitBreaker,
+            Layer::ConcurrencyLimit { .. } => LayerKind::ConcurrencyLimit,
+            Layer::Maintenance(_) => LayerKind::Maintenance,
+            Layer::ChangeFeed(_) => LayerKind::ChangeFeed,
+            Layer::Deadline => LayerKind::Deadline,
+            Layer::Custom(_) => LayerKind::Custom,
+        }
+    }
+
+    fn wrap(self, inner: Arc<dyn TaoDecorator>) -> Arc<dyn TaoDecorator> {
+        match self {
+            Layer::Wal(wal) => Arc::new(WalDecorator::new(inner, wal)),
+            Layer::Metrics(metrics) => Arc::new(MetricsDecorator::new(inner, metrics)),
+            Layer::Cache {
+                cache,
+                enable_caching,
+            } => Arc::new(CacheDecorator::new(inner, cache, enable_caching)),
+            Layer::CircuitBreaker {
+                failure_threshold,
+                recovery_timeout,
+            } => Arc::new(CircuitBreakerDecorator::new(
+                inner,
+                failure_threshold,
+                recovery_timeout,
+                true,
+            )),
+            Layer::ConcurrencyLimit { config, metrics } => {
+                let limiter = ConcurrencyLimitDecorator::new(inner, config);
+                match metrics {
+                    Some(metrics) => Arc::new(limiter.with_metrics(metrics)),
+                    None => Arc::new(limiter),
+                }
+            }
+            Layer::Maintenance(mode) => Arc::new(MaintenanceDecorator::new(inner, mode)),
+            Layer::ChangeFeed(feed) => Arc::new(ChangeFeedDecorator::new(inner, feed)),
+            Layer::Deadline => Arc::new(DeadlineDecorator::new(inner)),
+            Layer::Custom(factory) => factory(inner),
+        }
+    }
+}
+
+/// Builds a decorator chain over a core, checking the layer order before composing it
+#[derive(Debug)]
+pub struct TaoStackBuilder {
+    core: Arc<dyn TaoOperations>,
+    layers: Vec<Layer>,
+}
+
+impl TaoStackBuilder {
+    pub fn new(core: Arc<dyn TaoOperations>) -> Self {
+        Self {
+            core,
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn with_wal(mut self, wal: Arc<TaoWriteAheadLog>) -> Self {
+        self.layers.push(Layer::Wal(wal));
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.layers.push(Layer::Metrics(metrics));
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Arc<TaoMultiTierCache>, enable_caching: bool) -> Self {
+        self.layers.push(Layer::Cache {
+            cache,
+            enable_caching,
+        });
+        self
+    }
+
+    pub fn with_circuit_breaker(
+        mut self,
+        failure_threshold: u32,
+        recovery_timeout: Duration,
+    ) -> Self {
+        self.layers.push(Layer::CircuitBreaker {
+            failure_threshold,
+            recovery_timeout,
+        });
+        self
+    }
+
+    pub fn with_concurrency_limit(
+        mut self,
+        config: ConcurrencyLimitConfig,
+        metrics: Option<Arc<MetricsCollector>>,
+    ) -> Self {
+        self.layers
+            .push(Layer::ConcurrencyLimit { config, metrics });
+        self
+    }
+
+    pub fn with_maintenance_mode(mut self, mode: Arc<MaintenanceMode>) -> Self {
+        self.layers.push(Layer::Maintenance(mode));
+        self
+    }
+
+    pub fn with_change_feed(mut self, feed: Arc<dyn ChangeFeed>) -> Self {
+        self.layers.push(Layer::ChangeFeed(feed));
+        self
+    }
+
+    /// Enforce the `RequestContext` deadline of each call
+    pub fn with_deadline(mut self) -> Self {
+        self.layers.push(Layer::Deadline);
+        self
+    }
+
+    /// Wrap everything added so far in a decorator of your own, such as tenant isolation
+    /// or auditing, e.g. `with_decorator(|inner| Arc::new(AuditDecorator::new(inner)))`.
+    /// The factory runs once, in `build`. See `TaoDecorator` for what the decorator has
+    /// to uphold. Custom layers may be added any number of times and are exempt from the
+    /// ordering rules, so their position is entirely up to the caller.
+    pub fn with_decorator<F>(mut self, factory: F) -> Self
+    where
+        F: FnOnce(Arc<dyn TaoDecorator>) -> Arc<dyn TaoDecorator> + Send + 'static,
+    {
+        self.layers.push(Layer::Custom(Box::new(factory)));
+        self
+    }
+
+    /// Check the layer order without building anything
+    pub fn validate(&self) -> AppResult<()> {
+        let position = |kind: LayerKind| self.layers.iter().position(|layer| layer.kind() == kind);
+
+        for (index, layer) in self.layers.iter().enumerate() {
+            if layer.kind() != LayerKind::Custom && position(layer.kind()) != Some(index) {
+                return Err(AppError::ConfigurationError(format!(
+                    "{:?} layer added more than once",
+                    layer.kind()
+                )));
+            }
+        }
+
+        for (outer, inner, reason) in ORDERING_RULES {
+            if let (Some(outer_at), Some(inner_at)) = (position(*outer), position(*inner)) {
+                if outer_at < inner_at {
+                    return Err(AppError::ConfigurationError(format!(
+                        "{:?} must wrap {:?}: {}",
+                        outer, inner, reason
+                    )));
+                }
+            }
+        }
+
+        // Stale reads are served by the cache catching the breaker's `ServiceUnavailable`
+        let serves_stale = self.layers.iter().any(|layer| {
+            matches!(layer, Layer::Cache { cache, .. } if cache.config().serve_stale_on_unavailable)
+        });
+        if serves_stale {
+            if let (Some(cache_at), Some(breaker_at)) = (
+                position(LayerKind::Cache),
+                position(LayerKind::CircuitBreaker),
+            ) {
+                if cache_at < breaker_at {
+                    return Err(AppError::ConfigurationError(
+                        "Cache must wrap CircuitBreaker when serve_stale_on_unavailable is set, \
+                         otherwise it never sees the breaker trip"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the order, then compose the chain. The result is a `TaoOperations`;
+    /// `decorator_chain` lists its layers.
+    pub fn build(self) -> AppResult<Arc<dyn TaoDecorator>> {
+        Ok(self.build_with_wal()?.0)
+    }
+
+    /// Like `build`, also handing back the WAL layer if the stack has one, for replay
+    /// and status inspection
+    pub fn build_with_wal(self) -> AppResult<StackWithWal> {
+        self.validate()?;
+
+        let mut chain: Arc<dyn TaoDecorator> = Arc::new(BaseTao::new(self.core));
+        let mut wal_decorator = None;
+        for layer in self.layers {
+            chain = match layer {
+                Layer::Wal(wal) => {
+                    let wal_layer = Arc::new(WalDecorator::new(chain, wal));
+                    wal_decorator = Some(wal_layer.clone());
+                    wal_layer
+                }
+                layer => layer.wrap(chain),
+            };
+        }
+        Ok((chain, wal_decorator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::association_registry::AssociationRegistry;
+    use crate::infrastructure::cache::cache_layer::CacheConfig;
+    use crate::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
+    use crate::infrastructure::tao_core::tao_core::TaoCore;
+    use crate::infrastructure::tao_core::tao_decorators::decorator_chain;
+
+    async fn builder() -> TaoStackBuilder {
+        let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+        let core = TaoCore::new(query_router, Arc::new(AssociationRegistry::new()));
+        TaoStackBuilder::new(Arc::new(core))
+    }
+
+    async fn wal(dir: &tempfile::TempDir) -> Arc<TaoWriteAheadLog> {
+        Arc::new(
+            TaoWriteAheadLog::new(Default::default(), dir.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        )
+    }
+
+    fn cache(serve_stale_on_unavailable: bool) -> Arc<TaoMultiTierCache> {
+        Arc::new(TaoMultiTierCache::new(CacheConfig {
+            serve_stale_on_unavailable,
+            ..CacheConfig::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_layers_wrap_in_call_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let metrics = Arc::new(MetricsCollector::new());
+
+        let chain = builder()
+            .await
+            .with_wal(wal(&dir).await)
+            .with_metrics(metrics.clone())
+            .with_cache(cache(false), true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            decorator_chain(chain.as_ref()),
+            vec![
+                "CacheDecorator",
+                "MetricsDecorator",
+                "WalDecorator",
+                "BaseTao"
+            ]
+        );
+
+        // The stale-read layout from `Tao::new` is accepted too
+        let (chain, wal_decorator) = builder()
+            .await
+            .with_wal(wal(&dir).await)
+            .with_metrics(metrics)
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .with_cache(cache(true), true)
+            .with_maintenance_mode(Arc::new(MaintenanceMode::new()))
+            .build_with_wal()
+            .unwrap();
+        assert!(wal_decorator.is_some());
+        assert_eq!(
+            decorator_chain(chain.as_ref()),
+            vec![
+                "MaintenanceDecorator",
+                "CacheDecorator",
+                "CircuitBreakerDecorator",
+                "MetricsDecorator",
+                "WalDecorator",
+                "BaseTao"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_orders_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let wal_above_breaker = builder()
+            .await
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .with_wal(wal(&dir).await)
+            .build();
+        assert!(matches!(
+            wal_above_breaker,
+            Err(AppError::ConfigurationError(_))
+        ));
+
+        let stale_cache_below_breaker = builder()
+            .await
+            .with_cache(cache(true), true)
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .validate();
+        assert!(matches!(
+            stale_cache_below_breaker,
+            Err(AppError::ConfigurationError(_))
+        ));
+
+        // Rejections from these layers must never reach the breaker as failures
+        let breaker_wraps_maintenance = builder()
+            .await
+            .with_maintenance_mode(Arc::new(MaintenanceMode::new()))
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .validate();
+        assert!(matches!(
+            breaker_wraps_maintenance,
+            Err(AppError::ConfigurationError(_))
+        ));
+        let breaker_wraps_limit = builder()
+            .await
+            .with_concurrency_limit(ConcurrencyLimitConfig::default(), None)
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .validate();
+        assert!(matches!(
+            breaker_wraps_limit,
+            Err(AppError::ConfigurationError(_))
+        ));
+        let breaker_wraps_deadline = builder()
+            .await
+            .with_deadline()
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .validate();
+        assert!(matches!(
+            breaker_wraps_deadline,
+            Err(AppError::ConfigurationError(_))
+        ));
+
+        let duplicate = builder()
+            .await
+            .with_cache(cache(false), true)
+            .with_cache(cache(false), true)
+            .validate();
+        assert!(matches!(duplicate, Err(AppError::ConfigurationError(_))));
+
+        // Without stale reads the cache may sit on either side of the breaker
+        assert!(builder()
+            .await
+            .with_cache(cache(false), true)
+            .with_circuit_breaker(5, Duration::from_secs(30))
+            .validate()
+            .is_ok());
+
+        // Custom layers are not subject to the duplicate check
+        assert!(builder()
+            .await
+            .with_decorator(|inner| inner)
+            .with_decorator(|inner| inner)
+            .validate()
+            .is_ok());
+    }
+}
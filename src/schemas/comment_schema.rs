@@ -25,7 +25,7 @@ impl EntSchema for CommentSchema {
 
     fn edges() -> Vec<EdgeDefinition> {
         vec![
-            EdgeDefinition::from("author", EntityType::EntUser, "comments"),
+            EdgeDefinition::from("author", EntityType::EntUser, "comments").owner_edge(),
             EdgeDefinition::from("post", EntityType::EntPost, "comments"),
         ]
     }
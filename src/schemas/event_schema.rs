@@ -2,7 +2,7 @@
 
 use crate::framework::schema::ent_schema::EntityType;
 use crate::framework::schema::ent_schema::{
-    EdgeDefinition, EntSchema, FieldDefault, FieldDefinition, FieldType,
+    CachePolicy, EdgeDefinition, EntSchema, FieldDefault, FieldDefinition, FieldType,
 };
 
 /// Event entity schema
@@ -29,4 +29,9 @@ impl EntSchema for EventSchema {
             EdgeDefinition::from("related_posts", EntityType::EntPost, "related_events"),
         ]
     }
+
+    // Events are short-lived and read around the time they change
+    fn cache_policy() -> CachePolicy {
+        CachePolicy::Never
+    }
 }
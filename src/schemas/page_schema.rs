@@ -2,7 +2,7 @@
 
 use crate::framework::schema::ent_schema::EntityType;
 use crate::framework::schema::ent_schema::{
-    EdgeDefinition, EntSchema, FieldDefault, FieldDefinition, FieldType,
+    CachePolicy, EdgeDefinition, EntSchema, FieldDefault, FieldDefinition, FieldType,
 };
 
 /// Page entity schema
@@ -28,4 +28,14 @@ impl EntSchema for PageSchema {
             EdgeDefinition::from("posts", EntityType::EntPost, "appears_on_pages"),
         ]
     }
+
+    // Pages are read constantly and rarely edited
+    fn cache_policy() -> CachePolicy {
+        CachePolicy::Ttl(60 * 60 * 1000)
+    }
+
+    // Page edits are audited and can be rolled back
+    fn keep_history() -> bool {
+        true
+    }
 }
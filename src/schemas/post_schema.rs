@@ -3,7 +3,7 @@
 
 use crate::framework::schema::ent_schema::{
     AnnotationDefinition, EdgeDefinition, EntSchema, EntityType, FieldDefault, FieldDefinition,
-    FieldType, FieldValidator, IndexDefinition,
+    FieldType, IndexDefinition,
 };
 
 /// Post entity schema demonstrating various edge types and constraints
@@ -20,8 +20,8 @@ impl EntSchema for PostSchema {
             FieldDefinition::new("author_id", FieldType::Int64),
             // Post content
             FieldDefinition::new("content", FieldType::String)
-                .validate(FieldValidator::MinLength(1))
-                .validate(FieldValidator::MaxLength(10000)),
+                .min_len(1)
+                .max_len(10000),
             // Optional media
             FieldDefinition::new("media_url", FieldType::String).optional(),
             // Timestamps
@@ -36,10 +36,15 @@ impl EntSchema for PostSchema {
                 .optional()
                 .default_value(FieldDefault::String("public".to_string())),
             // Engagement metrics
-            FieldDefinition::new("like_count", FieldType::Int).default_value(FieldDefault::Int(0)),
+            FieldDefinition::new("like_count", FieldType::Int)
+                .default_value(FieldDefault::Int(0))
+                .range(0..=i32::MAX),
             FieldDefinition::new("comment_count", FieldType::Int)
-                .default_value(FieldDefault::Int(0)),
-            FieldDefinition::new("share_count", FieldType::Int).default_value(FieldDefault::Int(0)),
+                .default_value(FieldDefault::Int(0))
+                .range(0..=i32::MAX),
+            FieldDefinition::new("share_count", FieldType::Int)
+                .default_value(FieldDefault::Int(0))
+                .range(0..=i32::MAX),
             // SEO and discovery
             FieldDefinition::new("tags", FieldType::JSON).optional(),
             FieldDefinition::new("mentions", FieldType::JSON).optional(),
@@ -49,7 +54,9 @@ impl EntSchema for PostSchema {
     fn edges() -> Vec<EdgeDefinition> {
         vec![
             // Author relationship (many-to-one, unidirectional from post perspective)
-            EdgeDefinition::from("author", EntityType::EntUser, "posts").required(),
+            EdgeDefinition::from("author", EntityType::EntUser, "posts")
+                .required()
+                .owner_edge(),
             // Comments on this post (one-to-many)
             EdgeDefinition::to("comments", EntityType::EntComment),
             // Users who liked this post (many-to-many, bidirectional)
@@ -48,10 +48,8 @@ impl EntSchema for UserSchema {
 
     fn edges() -> Vec<EdgeDefinition> {
         vec![
-            // Bidirectional friendship edge (symmetric)
-            EdgeDefinition::to("friends", EntityType::EntUser)
-                .bidirectional()
-                .inverse("friends"), // Same name for symmetric relationship
+            // Friendship: adding or removing it from either side covers both
+            EdgeDefinition::to("friends", EntityType::EntUser).symmetric(),
             // Following relationship (asymmetric)
             EdgeDefinition::to("following", EntityType::EntUser)
                 .bidirectional()
@@ -64,10 +62,18 @@ impl EntSchema for UserSchema {
             EdgeDefinition::to("liked_posts", EntityType::EntPost)
                 .bidirectional()
                 .inverse("liked_by"),
-            // Groups the user is a member of
+            // Groups the user is a member of, with the member's role in each
             EdgeDefinition::to("groups", EntityType::EntGroup)
                 .bidirectional()
-                .inverse("members"),
+                .inverse("members")
+                .payload(vec![FieldDefinition::new(
+                    "role",
+                    FieldType::Enum(vec![
+                        "member".to_string(),
+                        "moderator".to_string(),
+                        "admin".to_string(),
+                    ]),
+                )]),
             // Pages the user follows
             EdgeDefinition::to("followed_pages", EntityType::EntPage)
                 .bidirectional()
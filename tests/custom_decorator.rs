@@ -0,0 +1,123 @@
+// Custom Decorator - a layer written outside the crate, plugged into the stack through
+// the public API only
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tao_database::infrastructure::association_registry::AssociationRegistry;
+use tao_database::infrastructure::database::sqlite_database::SqliteDatabase;
+use tao_database::infrastructure::id_generator::TaoIdGenerator;
+use tao_database::infrastructure::monitoring::monitoring::MetricsCollector;
+use tao_database::infrastructure::query_router::{QueryRouterConfig, TaoQueryRouter};
+use tao_database::infrastructure::shard_topology::{ShardHealth, ShardInfo};
+use tao_database::infrastructure::tao_core::tao_core::{
+    create_tao_association, current_time_millis, TaoCore, TaoOperations,
+};
+use tao_database::infrastructure::tao_core::tao_decorators::{decorator_chain, TaoDecorator};
+use tao_database::infrastructure::tao_core::tao_stack::TaoStackBuilder;
+use tao_database::AppResult;
+
+/// Counts the reads and writes passing through it
+#[derive(Debug)]
+struct CountingDecorator {
+    inner: Arc<dyn TaoDecorator>,
+    reads: Arc<AtomicUsize>,
+    writes: Arc<AtomicUsize>,
+}
+
+impl CountingDecorator {
+    async fn execute_read<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: Future<Output = AppResult<T>>,
+    {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        operation.await
+    }
+
+    async fn execute_write<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: Future<Output = AppResult<T>>,
+    {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        operation.await
+    }
+}
+
+tao_database::impl_tao_operations_read_write_split!(CountingDecorator, inner);
+
+impl TaoDecorator for CountingDecorator {
+    fn decorator_name(&self) -> &'static str {
+        "CountingDecorator"
+    }
+
+    fn inner_decorator(&self) -> Option<&Arc<dyn TaoDecorator>> {
+        Some(&self.inner)
+    }
+}
+
+async fn sqlite_tao_core() -> Arc<TaoCore> {
+    let query_router = Arc::new(TaoQueryRouter::new(QueryRouterConfig::default()).await);
+    let shard_info = ShardInfo {
+        shard_id: 0,
+        connection_string: "sqlite::memory:".to_string(),
+        region: "local".to_string(),
+        health: ShardHealth::Healthy,
+        replicas: vec![],
+        last_health_check: current_time_millis(),
+        load_factor: 0.0,
+    };
+    let database = Arc::new(SqliteDatabase::new_in_memory().await.unwrap());
+    query_router.add_shard(shard_info, database).await.unwrap();
+    Arc::new(TaoCore::new(
+        query_router,
+        Arc::new(AssociationRegistry::new()),
+    ))
+}
+
+#[tokio::test]
+async fn test_custom_decorator_sees_every_operation_in_the_chain() {
+    let (reads, writes) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let (counted_reads, counted_writes) = (reads.clone(), writes.clone());
+    let tao = TaoStackBuilder::new(sqlite_tao_core().await)
+        .with_decorator(move |inner| {
+            Arc::new(CountingDecorator {
+                inner,
+                reads: counted_reads,
+                writes: counted_writes,
+            })
+        })
+        .with_metrics(Arc::new(MetricsCollector::new()))
+        .build()
+        .unwrap();
+    assert_eq!(
+        decorator_chain(tao.as_ref()),
+        vec!["MetricsDecorator", "CountingDecorator", "BaseTao"]
+    );
+
+    let ids = TaoIdGenerator::new(0);
+    let (alice, bob) = (ids.next_id(), ids.next_id());
+    tao.create_object(alice, "user".to_string(), vec![])
+        .await
+        .unwrap();
+    tao.assoc_add(create_tao_association(
+        alice,
+        "friend".to_string(),
+        bob,
+        None,
+    ))
+    .await
+    .unwrap();
+    assert!(tao.obj_get(alice).await.unwrap().is_some());
+    assert_eq!(
+        tao.assoc_count(alice, "friend".to_string()).await.unwrap(),
+        1
+    );
+    // Provided methods reach the layer through the required ones they are built on
+    tao.activity_feed(alice, vec!["friend".to_string()], 10)
+        .await
+        .unwrap();
+
+    assert_eq!(writes.load(Ordering::Relaxed), 2);
+    assert_eq!(reads.load(Ordering::Relaxed), 3);
+}